@@ -0,0 +1,181 @@
+use image::{GenericImage, RgbaImage};
+
+/// Packs the images of a directory (recursively) into one texture,
+/// writing `<name>.png` and `<name>.json` with the rects of each image
+#[derive(Clone, Debug, PartialEq)]
+pub struct AtlasConfig {
+    pub(crate) name: String,
+    pub(crate) dir: String,
+    pub(crate) max_size: u32,
+    pub(crate) padding: u32,
+}
+
+impl AtlasConfig {
+    /// `name` is the output path without extension, `dir` is relative to the input directory
+    pub fn new(name: &str, dir: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            dir: dir.trim_matches('/').to_string(),
+            max_size: 4096,
+            padding: 1,
+        }
+    }
+
+    pub fn with_max_size(mut self, size: u32) -> Self {
+        self.max_size = size;
+        self
+    }
+
+    /// Empty pixels between images to avoid bleeding when they are filtered
+    pub fn with_padding(mut self, padding: u32) -> Self {
+        self.padding = padding;
+        self
+    }
+}
+
+/// Size of the atlas and position of each rect
+type Packed = ((u32, u32), Vec<(u32, u32)>);
+
+/// Places the rects in rows sorted by height, trying every power of two width
+/// and keeping the one with the smallest area
+pub(crate) fn pack_rects(
+    sizes: &[(u32, u32)],
+    max_size: u32,
+    padding: u32,
+) -> Result<Packed, String> {
+    let mut order = (0..sizes.len()).collect::<Vec<_>>();
+    order.sort_by_key(|idx| std::cmp::Reverse((sizes[*idx].1, sizes[*idx].0)));
+
+    let widest = sizes.iter().map(|(w, _)| *w).max().unwrap_or(1);
+    let mut width = widest.max(1).next_power_of_two();
+    let mut best: Option<Packed> = None;
+    while width <= max_size {
+        if let Some((height, positions)) = pack_rows(sizes, &order, width, padding) {
+            let height = height.max(1).next_power_of_two();
+            let area = |(w, h): (u32, u32)| w as u64 * h as u64;
+            let smaller = best
+                .as_ref()
+                .is_none_or(|(size, _)| area((width, height)) < area(*size));
+            if height <= max_size && smaller {
+                best = Some(((width, height), positions));
+            }
+        }
+
+        width *= 2;
+    }
+
+    best.ok_or_else(|| format!("The images don't fit in a {max_size}x{max_size} atlas"))
+}
+
+fn pack_rows(
+    sizes: &[(u32, u32)],
+    order: &[usize],
+    width: u32,
+    padding: u32,
+) -> Option<(u32, Vec<(u32, u32)>)> {
+    let mut positions = vec![(0, 0); sizes.len()];
+    let (mut x, mut y, mut row_height) = (0, 0, 0);
+    for idx in order {
+        let (w, h) = sizes[*idx];
+        if w > width {
+            return None;
+        }
+
+        if x + w > width {
+            x = 0;
+            y += row_height + padding;
+            row_height = 0;
+        }
+
+        positions[*idx] = (x, y);
+        x += w + padding;
+        row_height = row_height.max(h);
+    }
+
+    Some((y + row_height, positions))
+}
+
+/// Builds the atlas image and its json description
+pub(crate) fn build_atlas(
+    config: &AtlasConfig,
+    images: &[(String, Vec<u8>)],
+) -> Result<(Vec<u8>, String), String> {
+    let decoded = images
+        .iter()
+        .map(|(path, data)| {
+            image::load_from_memory(data)
+                .map(|img| img.to_rgba8())
+                .map_err(|e| format!("Cannot decode '{path}': {e}"))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let sizes = decoded
+        .iter()
+        .map(|img| img.dimensions())
+        .collect::<Vec<_>>();
+    let ((width, height), positions) = pack_rects(&sizes, config.max_size, config.padding)?;
+
+    let mut atlas = RgbaImage::new(width, height);
+    let mut frames = vec![];
+    for ((img, (path, _)), (x, y)) in decoded.iter().zip(images).zip(&positions) {
+        atlas
+            .copy_from(img, *x, *y)
+            .map_err(|e| format!("Cannot copy '{path}' to the atlas: {e}"))?;
+
+        // names are relative to the atlas directory
+        let name = path
+            .strip_prefix(&config.dir)
+            .unwrap_or(path)
+            .trim_start_matches('/');
+        frames.push(format!(
+            "    \"{}\": {{ \"x\": {x}, \"y\": {y}, \"w\": {}, \"h\": {} }}",
+            name.replace('"', "\\\""),
+            img.width(),
+            img.height()
+        ));
+    }
+
+    let mut png = vec![];
+    atlas
+        .write_to(&mut std::io::Cursor::new(&mut png), image::ImageFormat::Png)
+        .map_err(|e| format!("Cannot encode atlas '{}': {e}", config.name))?;
+
+    let image_name = format!("{}.png", config.name);
+    let image_name = image_name.rsplit('/').next().unwrap_or(&image_name);
+    let json = format!(
+        "{{\n  \"image\": \"{image_name}\",\n  \"width\": {width},\n  \"height\": {height},\n  \"frames\": {{\n{}\n  }}\n}}\n",
+        frames.join(",\n")
+    );
+
+    Ok((png, json))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pack_rects() {
+        let sizes = [(32, 32), (64, 16), (16, 64), (32, 32)];
+        let ((w, h), positions) = pack_rects(&sizes, 1024, 0).unwrap();
+        assert_eq!((w, h), (64, 128));
+        // the tallest goes first
+        assert_eq!(positions[2], (0, 0));
+
+        let overlap = |a: usize, b: usize| {
+            let (ax, ay) = positions[a];
+            let (bx, by) = positions[b];
+            ax < bx + sizes[b].0
+                && bx < ax + sizes[a].0
+                && ay < by + sizes[b].1
+                && by < ay + sizes[a].1
+        };
+        for a in 0..sizes.len() {
+            for b in a + 1..sizes.len() {
+                assert!(!overlap(a, b), "{a} overlaps {b}");
+            }
+        }
+
+        assert!(pack_rects(&[(100, 100)], 64, 0).is_err());
+    }
+}
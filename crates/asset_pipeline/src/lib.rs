@@ -0,0 +1,435 @@
+//! Processes the assets ahead of time, packing atlases, converting files with
+//! `AssetProcessor` and writing an `AssetPack` and a manifest the runtime can mount.
+//! Patch packs with only the files changed since a previous manifest can be written too.
+//! It can be used from a `build.rs` or with the `asset_pipeline` binary.
+
+mod atlas;
+mod processor;
+
+pub use atlas::AtlasConfig;
+pub use processor::{with_extension, AssetProcessor, CommandProcessor};
+
+use assets::{AssetManifest, AssetPackWriter};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+const IMAGE_EXTENSIONS: [&str; 3] = ["png", "jpg", "jpeg"];
+
+/// Result of `AssetPipeline::run`, paths are relative to the input and output directories
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PipelineReport {
+    /// Files processed or copied on this run
+    pub processed: Vec<String>,
+    /// Files skipped because the output is newer than the input
+    pub skipped: Vec<String>,
+    /// Every file of the output directory generated by the pipeline
+    pub outputs: Vec<String>,
+    /// Files added to the patch pack because they changed since the previous manifest
+    pub patched: Vec<String>,
+}
+
+pub struct AssetPipeline {
+    input: PathBuf,
+    output: PathBuf,
+    mount_path: String,
+    processors: Vec<(String, Box<dyn AssetProcessor>)>,
+    atlases: Vec<AtlasConfig>,
+    pack: Option<String>,
+    manifest: Option<String>,
+    patch: Option<(String, PathBuf)>,
+    compression: u8,
+    incremental: bool,
+}
+
+impl AssetPipeline {
+    pub fn new(input: impl AsRef<Path>, output: impl AsRef<Path>) -> Self {
+        let output = output.as_ref().to_path_buf();
+        let mount_path = normalize(&output.to_string_lossy());
+        Self {
+            input: input.as_ref().to_path_buf(),
+            output,
+            mount_path,
+            processors: vec![],
+            atlases: vec![],
+            pack: None,
+            manifest: None,
+            patch: None,
+            compression: 6,
+            incremental: true,
+        }
+    }
+
+    /// Processes the files with the extension, files without a processor are copied
+    pub fn with_processor<P>(mut self, ext: &str, processor: P) -> Self
+    where
+        P: AssetProcessor + 'static,
+    {
+        let ext = ext.trim_start_matches('.').to_lowercase();
+        self.processors.retain(|(e, _)| *e != ext);
+        self.processors.push((ext, Box::new(processor)));
+        self
+    }
+
+    /// Packs the images inside of the atlas directory instead of processing them one by one
+    pub fn with_atlas(mut self, atlas: AtlasConfig) -> Self {
+        self.atlases.push(atlas);
+        self
+    }
+
+    /// Writes an `AssetPack` with all the outputs in the output directory
+    pub fn with_pack(mut self, name: &str) -> Self {
+        self.pack = Some(name.to_string());
+        self
+    }
+
+    /// Writes an `AssetManifest` with the size and checksum of the outputs in the output
+    /// directory, it can be used with `set_assets_integrity` and `set_assets_manifest`
+    pub fn with_manifest(mut self, name: &str) -> Self {
+        self.manifest = Some(name.to_string());
+        self
+    }
+
+    /// Writes an `AssetPack` with only the outputs changed since the previous manifest,
+    /// mounted after the previous pack it updates it without downloading everything again
+    pub fn with_patch(mut self, name: &str, previous_manifest: impl AsRef<Path>) -> Self {
+        self.patch = Some((name.to_string(), previous_manifest.as_ref().to_path_buf()));
+        self
+    }
+
+    /// Path used to load the files at runtime, by default the output directory
+    pub fn with_mount_path(mut self, path: &str) -> Self {
+        self.mount_path = normalize(path);
+        self
+    }
+
+    /// Compression level of the pack from 0 (none) to 10
+    pub fn with_compression_level(mut self, level: u8) -> Self {
+        self.compression = level;
+        self
+    }
+
+    /// Skips files whose output is newer than the input (default true)
+    pub fn with_incremental(mut self, incremental: bool) -> Self {
+        self.incremental = incremental;
+        self
+    }
+
+    /// Tells cargo to run the build script again when the input changes
+    pub fn rerun_if_changed(&self) {
+        println!("cargo:rerun-if-changed={}", self.input.display());
+    }
+
+    pub fn run(&self) -> Result<PipelineReport, String> {
+        let mut files = vec![];
+        collect_files(&self.input, "", &mut files)?;
+        files.sort();
+
+        let mut report = PipelineReport::default();
+        let (atlas_files, files): (Vec<_>, Vec<_>) = files
+            .into_iter()
+            .partition(|file| self.atlas_for(file).is_some());
+
+        for atlas in &self.atlases {
+            self.run_atlas(atlas, &atlas_files, &mut report)?;
+        }
+
+        for file in files {
+            let processor = self.processor_for(&file);
+            let out = processor.map_or_else(|| file.clone(), |p| p.output_path(&file));
+            report.outputs.push(out.clone());
+
+            let input = self.input.join(&file);
+            let output = self.output.join(&out);
+            if self.is_updated(&[&input], &output) {
+                report.skipped.push(file);
+                continue;
+            }
+
+            let data = std::fs::read(&input)
+                .map_err(|e| format!("Cannot read file '{}': {e}", input.display()))?;
+            let data = match processor {
+                Some(processor) => processor.process(&file, data)?,
+                None => data,
+            };
+
+            write_file(&output, &data)?;
+            log::info!("Asset processed '{file}' -> '{out}'");
+            report.processed.push(file);
+        }
+
+        report.outputs.sort();
+        self.write_manifest(&report)?;
+        self.write_pack(&report)?;
+        self.write_patch(&mut report)?;
+        Ok(report)
+    }
+
+    fn processor_for(&self, file: &str) -> Option<&dyn AssetProcessor> {
+        let ext = extension(file)?;
+        self.processors
+            .iter()
+            .find(|(e, _)| *e == ext)
+            .map(|(_, p)| p.as_ref())
+    }
+
+    fn atlas_for(&self, file: &str) -> Option<&AtlasConfig> {
+        let is_image = extension(file).is_some_and(|ext| IMAGE_EXTENSIONS.contains(&ext.as_str()));
+        if !is_image {
+            return None;
+        }
+
+        self.atlases
+            .iter()
+            .find(|atlas| file.starts_with(&format!("{}/", atlas.dir)))
+    }
+
+    fn run_atlas(
+        &self,
+        atlas: &AtlasConfig,
+        files: &[String],
+        report: &mut PipelineReport,
+    ) -> Result<(), String> {
+        let files = files
+            .iter()
+            .filter(|file| self.atlas_for(file) == Some(atlas))
+            .collect::<Vec<_>>();
+
+        let image = format!("{}.png", atlas.name);
+        let json = format!("{}.json", atlas.name);
+        report.outputs.push(image.clone());
+        report.outputs.push(json.clone());
+
+        let inputs = files
+            .iter()
+            .map(|file| self.input.join(file))
+            .collect::<Vec<_>>();
+        let inputs = inputs.iter().map(|p| p.as_path()).collect::<Vec<_>>();
+        if self.is_updated(&inputs, &self.output.join(&json)) {
+            report.skipped.extend(files.into_iter().cloned());
+            return Ok(());
+        }
+
+        let images = files
+            .iter()
+            .map(|file| {
+                let path = self.input.join(file);
+                std::fs::read(&path)
+                    .map(|data| (file.to_string(), data))
+                    .map_err(|e| format!("Cannot read file '{}': {e}", path.display()))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let (png, desc) = atlas::build_atlas(atlas, &images)?;
+        write_file(&self.output.join(&image), &png)?;
+        write_file(&self.output.join(&json), desc.as_bytes())?;
+        log::info!("Atlas '{}' packed with {} images", atlas.name, images.len());
+        report.processed.extend(files.into_iter().cloned());
+        Ok(())
+    }
+
+    /// True if the output exists and it's newer than all the inputs
+    fn is_updated(&self, inputs: &[&Path], output: &Path) -> bool {
+        if !self.incremental {
+            return false;
+        }
+
+        let Some(out_time) = modified(output) else {
+            return false;
+        };
+
+        inputs
+            .iter()
+            .all(|input| modified(input).is_some_and(|time| time <= out_time))
+    }
+
+    fn mounted(&self, file: &str) -> String {
+        if self.mount_path.is_empty() {
+            file.to_string()
+        } else {
+            format!("{}/{file}", self.mount_path)
+        }
+    }
+
+    fn build_manifest(&self, report: &PipelineReport) -> Result<AssetManifest, String> {
+        let mut manifest = AssetManifest::new();
+        for file in &report.outputs {
+            manifest.add(&self.mounted(file), &read_file(&self.output.join(file))?);
+        }
+
+        Ok(manifest)
+    }
+
+    fn write_manifest(&self, report: &PipelineReport) -> Result<(), String> {
+        let Some(name) = &self.manifest else {
+            return Ok(());
+        };
+
+        let manifest = self.build_manifest(report)?;
+        write_file(&self.output.join(name), manifest.to_text().as_bytes())
+    }
+
+    fn write_pack(&self, report: &PipelineReport) -> Result<(), String> {
+        let Some(name) = &self.pack else {
+            return Ok(());
+        };
+
+        self.write_pack_with(name, &report.outputs)
+    }
+
+    fn write_patch(&self, report: &mut PipelineReport) -> Result<(), String> {
+        let Some((name, previous)) = &self.patch else {
+            return Ok(());
+        };
+
+        let previous = std::fs::read_to_string(previous)
+            .map_err(|e| format!("Cannot read manifest '{}': {e}", previous.display()))
+            .and_then(|text| AssetManifest::parse(&text))?;
+        let manifest = self.build_manifest(report)?;
+        let changed = manifest.changed_since(&previous);
+
+        report.patched = report
+            .outputs
+            .iter()
+            .filter(|file| changed.contains(&self.mounted(file)))
+            .cloned()
+            .collect();
+
+        let removed = manifest.removed_since(&previous);
+        if !removed.is_empty() {
+            log::warn!("Files removed since the previous manifest: {removed:?}");
+        }
+
+        log::info!("Patch '{name}' with {} changed files", report.patched.len());
+        self.write_pack_with(name, &report.patched)
+    }
+
+    fn write_pack_with(&self, name: &str, files: &[String]) -> Result<(), String> {
+        let mut writer = AssetPackWriter::new().with_compression_level(self.compression);
+        for file in files {
+            writer.add(&self.mounted(file), read_file(&self.output.join(file))?);
+        }
+
+        write_file(&self.output.join(name), &writer.write()?)
+    }
+}
+
+fn collect_files(dir: &Path, prefix: &str, files: &mut Vec<String>) -> Result<(), String> {
+    let entries = std::fs::read_dir(dir)
+        .map_err(|e| format!("Cannot read directory '{}': {e}", dir.display()))?;
+
+    for entry in entries {
+        let entry = entry.map_err(|e| e.to_string())?;
+        let name = format!("{prefix}{}", entry.file_name().to_string_lossy());
+        let path = entry.path();
+        if path.is_dir() {
+            collect_files(&path, &format!("{name}/"), files)?;
+        } else {
+            files.push(name);
+        }
+    }
+
+    Ok(())
+}
+
+fn read_file(path: &Path) -> Result<Vec<u8>, String> {
+    std::fs::read(path).map_err(|e| format!("Cannot read file '{}': {e}", path.display()))
+}
+
+fn write_file(path: &Path, data: &[u8]) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Cannot create directory '{}': {e}", parent.display()))?;
+    }
+
+    std::fs::write(path, data).map_err(|e| format!("Cannot write file '{}': {e}", path.display()))
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+fn extension(file: &str) -> Option<String> {
+    let name = file.rsplit('/').next()?;
+    name.rsplit_once('.').map(|(_, ext)| ext.to_lowercase())
+}
+
+fn normalize(path: &str) -> String {
+    path.replace('\\', "/").trim_end_matches('/').to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_run() {
+        let dir = std::env::temp_dir().join(format!("asset_pipeline_test_{}", std::process::id()));
+        let input = dir.join("input");
+        let output = dir.join("output");
+        write_file(&input.join("data/level.txt"), b"level").unwrap();
+        write_file(&input.join("sfx/jump.wav"), b"wav").unwrap();
+
+        let pipeline = AssetPipeline::new(&input, &output)
+            .with_processor("wav", |_: &str, data: Vec<u8>| {
+                Ok([data, b"!".to_vec()].concat())
+            })
+            .with_manifest("assets.manifest")
+            .with_pack("assets.pack")
+            .with_mount_path("assets");
+
+        let report = pipeline.run().unwrap();
+        assert_eq!(report.processed, ["data/level.txt", "sfx/jump.wav"]);
+        assert_eq!(std::fs::read(output.join("sfx/jump.wav")).unwrap(), b"wav!");
+
+        let manifest = std::fs::read_to_string(output.join("assets.manifest")).unwrap();
+        let manifest = AssetManifest::parse(&manifest).unwrap();
+        assert_eq!(
+            manifest.paths(),
+            ["assets/data/level.txt", "assets/sfx/jump.wav"]
+        );
+        assert!(manifest.verify("assets/sfx/jump.wav", b"wav!").is_ok());
+
+        let pack = std::fs::read(output.join("assets.pack")).unwrap();
+        let pack = assets::AssetPack::from_bytes(pack).unwrap();
+        assert_eq!(pack.read("assets/sfx/jump.wav").unwrap(), b"wav!");
+
+        // nothing changed so everything is skipped
+        let report = pipeline.run().unwrap();
+        assert!(report.processed.is_empty());
+        assert_eq!(report.skipped.len(), 2);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_patch() {
+        let dir = std::env::temp_dir().join(format!("asset_pipeline_patch_{}", std::process::id()));
+        let input = dir.join("input");
+        let output = dir.join("output");
+        write_file(&input.join("a.txt"), b"a").unwrap();
+        write_file(&input.join("b.txt"), b"b").unwrap();
+
+        let mut previous = AssetManifest::new();
+        previous.add("a.txt", b"a");
+        previous.add("b.txt", b"old");
+        write_file(
+            &dir.join("previous.manifest"),
+            previous.to_text().as_bytes(),
+        )
+        .unwrap();
+
+        let report = AssetPipeline::new(&input, &output)
+            .with_mount_path("")
+            .with_patch("patch.pack", dir.join("previous.manifest"))
+            .run()
+            .unwrap();
+        assert_eq!(report.patched, ["b.txt"]);
+
+        let pack = std::fs::read(output.join("patch.pack")).unwrap();
+        let pack = assets::AssetPack::from_bytes(pack).unwrap();
+        assert_eq!(pack.len(), 1);
+        assert_eq!(pack.read("b.txt").unwrap(), b"b");
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,63 @@
+use asset_pipeline::{AssetPipeline, AtlasConfig, CommandProcessor};
+
+const USAGE: &str = "Usage: asset_pipeline <input> <output> [options]
+  --pack <name>          writes an asset pack with the outputs
+  --manifest <name>      writes a manifest with the outputs
+  --patch <name>=<file>  writes a pack with the outputs changed since the manifest file
+  --mount <path>         path used to load the files at runtime
+  --atlas <name>=<dir>   packs the images of the directory in an atlas
+  --ktx2                 compresses png and jpeg textures to ktx2 (needs toktx)
+  --ogg                  transcodes wav, mp3 and flac files to ogg (needs ffmpeg)
+  --force                processes every file even if it's updated";
+
+fn main() -> Result<(), String> {
+    let mut args = std::env::args().skip(1);
+    let (Some(input), Some(output)) = (args.next(), args.next()) else {
+        return Err(USAGE.to_string());
+    };
+
+    let mut pipeline = AssetPipeline::new(&input, &output);
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .ok_or_else(|| format!("Missing value for '{arg}'"))
+        };
+        pipeline = match arg.as_str() {
+            "--pack" => pipeline.with_pack(&value()?),
+            "--manifest" => pipeline.with_manifest(&value()?),
+            "--mount" => pipeline.with_mount_path(&value()?),
+            "--patch" => {
+                let value = value()?;
+                let (name, previous) = value
+                    .split_once('=')
+                    .ok_or_else(|| format!("Invalid patch '{value}'\n{USAGE}"))?;
+                pipeline.with_patch(name, previous)
+            }
+            "--atlas" => {
+                let value = value()?;
+                let (name, dir) = value
+                    .split_once('=')
+                    .ok_or_else(|| format!("Invalid atlas '{value}'\n{USAGE}"))?;
+                pipeline.with_atlas(AtlasConfig::new(name, dir))
+            }
+            "--ktx2" => ["png", "jpg", "jpeg"].iter().fold(pipeline, |p, ext| {
+                p.with_processor(ext, CommandProcessor::ktx2())
+            }),
+            "--ogg" => ["wav", "mp3", "flac"].iter().fold(pipeline, |p, ext| {
+                p.with_processor(ext, CommandProcessor::ogg())
+            }),
+            "--force" => pipeline.with_incremental(false),
+            _ => return Err(format!("Unknown option '{arg}'\n{USAGE}")),
+        };
+    }
+
+    let report = pipeline.run()?;
+    println!(
+        "Processed {} files, skipped {}, {} outputs in '{output}'",
+        report.processed.len(),
+        report.skipped.len(),
+        report.outputs.len()
+    );
+
+    Ok(())
+}
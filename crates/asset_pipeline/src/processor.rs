@@ -0,0 +1,154 @@
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Transforms a file before it's written to the output directory
+pub trait AssetProcessor {
+    /// Path of the processed file, by default the same as the input
+    fn output_path(&self, path: &str) -> String {
+        path.to_string()
+    }
+
+    fn process(&self, path: &str, data: Vec<u8>) -> Result<Vec<u8>, String>;
+}
+
+impl<F> AssetProcessor for F
+where
+    F: Fn(&str, Vec<u8>) -> Result<Vec<u8>, String>,
+{
+    fn process(&self, path: &str, data: Vec<u8>) -> Result<Vec<u8>, String> {
+        self(path, data)
+    }
+}
+
+/// Replaces the extension of the path, used by processors that change the format
+pub fn with_extension(path: &str, ext: &str) -> String {
+    let name_start = path.rfind('/').map_or(0, |idx| idx + 1);
+    match path[name_start..].rfind('.') {
+        Some(idx) => format!("{}.{ext}", &path[..name_start + idx]),
+        None => format!("{path}.{ext}"),
+    }
+}
+
+/// Runs an external tool to process the file, `{input}` and `{output}` in the
+/// arguments are replaced by temporary files. Used for the formats that need
+/// encoders, like KTX2 textures (`toktx`, `basisu`) or audio (`ffmpeg`)
+pub struct CommandProcessor {
+    program: String,
+    args: Vec<String>,
+    ext: Option<String>,
+}
+
+impl CommandProcessor {
+    pub fn new(program: &str, args: &[&str]) -> Self {
+        Self {
+            program: program.to_string(),
+            args: args.iter().map(|arg| arg.to_string()).collect(),
+            ext: None,
+        }
+    }
+
+    /// Extension of the output file
+    pub fn with_extension(mut self, ext: &str) -> Self {
+        self.ext = Some(ext.trim_start_matches('.').to_string());
+        self
+    }
+
+    /// Compresses textures to KTX2 using `toktx` from the KTX-Software tools
+    pub fn ktx2() -> Self {
+        Self::new(
+            "toktx",
+            &["--t2", "--encode", "uastc", "{output}", "{input}"],
+        )
+        .with_extension("ktx2")
+    }
+
+    /// Transcodes audio to ogg vorbis using `ffmpeg`
+    pub fn ogg() -> Self {
+        Self::new(
+            "ffmpeg",
+            &[
+                "-y",
+                "-loglevel",
+                "error",
+                "-i",
+                "{input}",
+                "-c:a",
+                "libvorbis",
+                "{output}",
+            ],
+        )
+        .with_extension("ogg")
+    }
+}
+
+impl AssetProcessor for CommandProcessor {
+    fn output_path(&self, path: &str) -> String {
+        match &self.ext {
+            Some(ext) => with_extension(path, ext),
+            None => path.to_string(),
+        }
+    }
+
+    fn process(&self, path: &str, data: Vec<u8>) -> Result<Vec<u8>, String> {
+        let input = TempFile::new(path)?;
+        let output = TempFile::new(&self.output_path(path))?;
+        std::fs::write(&input.0, data).map_err(|e| e.to_string())?;
+
+        let args = self.args.iter().map(|arg| {
+            arg.replace("{input}", &input.0.to_string_lossy())
+                .replace("{output}", &output.0.to_string_lossy())
+        });
+
+        let out = Command::new(&self.program)
+            .args(args)
+            .output()
+            .map_err(|e| format!("Cannot run '{}': {e}", self.program))?;
+
+        if !out.status.success() {
+            return Err(format!(
+                "'{}' failed processing '{path}': {}",
+                self.program,
+                String::from_utf8_lossy(&out.stderr).trim()
+            ));
+        }
+
+        std::fs::read(&output.0).map_err(|e| format!("Cannot read '{}' output: {e}", self.program))
+    }
+}
+
+/// File in the temporary directory removed on drop
+struct TempFile(PathBuf);
+
+impl TempFile {
+    fn new(path: &str) -> Result<Self, String> {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        static COUNT: AtomicUsize = AtomicUsize::new(0);
+
+        // keep the extension, the tools use it to know the format
+        let name = Path::new(path)
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .ok_or_else(|| format!("Invalid file path '{path}'"))?;
+        let id = COUNT.fetch_add(1, Ordering::Relaxed);
+        let file = format!("asset_pipeline_{}_{id}_{name}", std::process::id());
+        Ok(Self(std::env::temp_dir().join(file)))
+    }
+}
+
+impl Drop for TempFile {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_with_extension() {
+        assert_eq!(with_extension("sfx/jump.wav", "ogg"), "sfx/jump.ogg");
+        assert_eq!(with_extension("a.b/file", "ktx2"), "a.b/file.ktx2");
+        assert_eq!(with_extension("img.tar.png", "ktx2"), "img.tar.ktx2");
+    }
+}
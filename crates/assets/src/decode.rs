@@ -0,0 +1,33 @@
+use futures::channel::oneshot;
+
+/// Handle to an asset being decoded outside the main loop
+pub struct DecodeTask<T> {
+    rx: oneshot::Receiver<Result<T, String>>,
+    done: bool,
+}
+
+impl<T> DecodeTask<T> {
+    pub(crate) fn new(rx: oneshot::Receiver<Result<T, String>>) -> Self {
+        Self { rx, done: false }
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.done
+    }
+
+    /// Returns the result once the decoding is finished, it can be taken only once
+    pub fn try_take(&mut self) -> Option<Result<T, String>> {
+        if self.done {
+            return None;
+        }
+
+        let res = match self.rx.try_recv() {
+            Ok(Some(res)) => Some(res),
+            Ok(None) => None,
+            Err(_) => Some(Err("The channel was dropped.".to_string())),
+        };
+
+        self.done = res.is_some();
+        res
+    }
+}
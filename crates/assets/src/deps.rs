@@ -0,0 +1,113 @@
+use rustc_hash::{FxHashMap, FxHashSet};
+
+/// Path of a dependency requested by a file, relative to the directory of the file.
+/// Paths starting with `/` are relative to the assets root, and `scheme://` paths are kept.
+pub(crate) fn resolve_path(parent: &str, dep: &str) -> String {
+    if dep.contains("://") {
+        return dep.to_string();
+    }
+
+    let (scheme, parent) = match parent.split_once("://") {
+        Some((scheme, path)) => (format!("{scheme}://"), path),
+        None => (String::new(), parent),
+    };
+
+    let mut segments = vec![];
+    let base = match dep.strip_prefix('/') {
+        Some(_) => "",
+        None => parent.rsplit_once('/').map_or("", |(dir, _)| dir),
+    };
+
+    for segment in base.split('/').chain(dep.split('/')) {
+        match segment {
+            "" | "." => {}
+            ".." => {
+                segments.pop();
+            }
+            _ => segments.push(segment),
+        }
+    }
+
+    format!("{scheme}{}", segments.join("/"))
+}
+
+/// Returns the files that form a cycle (starting and ending with the same file) if any
+pub(crate) fn find_cycle<'a>(deps: &FxHashMap<&'a str, &'a [String]>) -> Option<Vec<&'a str>> {
+    let mut done = FxHashSet::default();
+    let mut stack = vec![];
+
+    let mut roots = deps.keys().copied().collect::<Vec<_>>();
+    roots.sort();
+    roots
+        .into_iter()
+        .find_map(|root| visit(root, deps, &mut done, &mut stack))
+}
+
+fn visit<'a>(
+    path: &'a str,
+    deps: &FxHashMap<&'a str, &'a [String]>,
+    done: &mut FxHashSet<&'a str>,
+    stack: &mut Vec<&'a str>,
+) -> Option<Vec<&'a str>> {
+    if let Some(pos) = stack.iter().position(|p| *p == path) {
+        let mut cycle = stack[pos..].to_vec();
+        cycle.push(path);
+        return Some(cycle);
+    }
+
+    if done.contains(path) {
+        return None;
+    }
+
+    stack.push(path);
+    let children = deps.get(path).copied().unwrap_or_default();
+    for child in children {
+        if let Some(cycle) = visit(child, deps, done, stack) {
+            return Some(cycle);
+        }
+    }
+    stack.pop();
+
+    done.insert(path);
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_path() {
+        assert_eq!(
+            resolve_path("sprites/hero.atlas", "hero.png"),
+            "sprites/hero.png"
+        );
+        assert_eq!(
+            resolve_path("sprites/hero.atlas", "../shared/./fx.png"),
+            "shared/fx.png"
+        );
+        assert_eq!(
+            resolve_path("sprites/hero.atlas", "/ui/font.ttf"),
+            "ui/font.ttf"
+        );
+        assert_eq!(resolve_path("hero.atlas", "hero.png"), "hero.png");
+        assert_eq!(resolve_path("pak://a/b.atlas", "c.png"), "pak://a/c.png");
+        assert_eq!(resolve_path("a/b.atlas", "pak://c.png"), "pak://c.png");
+    }
+
+    #[test]
+    fn test_find_cycle() {
+        let a = vec!["b".to_string()];
+        let b = vec!["c".to_string()];
+        let c = vec![];
+        let mut deps = FxHashMap::default();
+        deps.insert("a", a.as_slice());
+        deps.insert("b", b.as_slice());
+        deps.insert("c", c.as_slice());
+        assert_eq!(find_cycle(&deps), None);
+
+        let c = vec!["a".to_string()];
+        deps.insert("c", c.as_slice());
+        assert_eq!(find_cycle(&deps), Some(vec!["a", "b", "c", "a"]));
+    }
+}
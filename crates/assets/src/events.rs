@@ -1,7 +1,64 @@
+use crate::policy::LoadPolicy;
+use crate::AssetId;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
 #[derive(Clone, Debug)]
 pub(crate) struct AssetLoad {
     pub(crate) id: String,
     pub(crate) state: AssetState,
+    pub(crate) progress: Arc<ProgressCounter>,
+    pub(crate) refs: usize,
+    pub(crate) last_used: u64,
+    pub(crate) policy: LoadPolicy,
+    /// Retries done since the last successful load
+    pub(crate) attempts: u32,
+}
+
+/// Bytes loaded of a file, `total` is `None` if the size is not known yet
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct LoadProgress {
+    pub loaded: u64,
+    pub total: Option<u64>,
+}
+
+impl LoadProgress {
+    /// Returns the loaded fraction from 0.0 to 1.0 if the size is known
+    pub fn ratio(&self) -> Option<f32> {
+        self.total.map(|total| {
+            if total == 0 {
+                1.0
+            } else {
+                (self.loaded as f64 / total as f64).min(1.0) as f32
+            }
+        })
+    }
+}
+
+/// Shared with the loading task to report the bytes read
+#[derive(Debug, Default)]
+pub(crate) struct ProgressCounter {
+    loaded: AtomicU64,
+    // 0 means unknown
+    total: AtomicU64,
+}
+
+impl ProgressCounter {
+    pub fn set_loaded(&self, loaded: u64) {
+        self.loaded.store(loaded, Ordering::Relaxed);
+    }
+
+    pub fn set_total(&self, total: u64) {
+        self.total.store(total, Ordering::Relaxed);
+    }
+
+    pub fn progress(&self) -> LoadProgress {
+        let total = self.total.load(Ordering::Relaxed);
+        LoadProgress {
+            loaded: self.loaded.load(Ordering::Relaxed),
+            total: (total > 0).then_some(total),
+        }
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -10,3 +67,61 @@ pub(crate) enum AssetState {
     Loaded(Vec<u8>),
     Err(String),
 }
+
+/// State of a file, see `load_state`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LoadState {
+    /// Loading or waiting to retry after an error
+    Loading,
+    Loaded,
+    /// All the retries failed, it can be loaded again with `reload_asset`
+    Failed(String),
+}
+
+impl AssetState {
+    pub(crate) fn load_state(&self) -> LoadState {
+        match self {
+            AssetState::Loading => LoadState::Loading,
+            AssetState::Loaded(_) => LoadState::Loaded,
+            AssetState::Err(err) => LoadState::Failed(err.clone()),
+        }
+    }
+}
+
+/// Changes in the state of the files, see `asset_events`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AssetEvent {
+    /// The data is ready to be parsed or decoded (also after a reload)
+    Loaded(AssetId),
+    Failed(AssetId, String),
+    /// The file was unloaded and the id is no longer valid
+    Removed(AssetId),
+}
+
+/// Emitted when a watched file changed on disk and the new data is ready to be parsed
+/// Files that were already removed (e.g. parsed without `keep`) are loaded again with a new
+/// id, it's valid during the frame the event is sent, use `load_asset` to keep a reference
+#[cfg(all(feature = "watch", not(target_arch = "wasm32")))]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AssetReloadedEvent {
+    pub id: AssetId,
+    pub path: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_progress_ratio() {
+        let counter = ProgressCounter::default();
+        counter.set_loaded(50);
+        assert_eq!(counter.progress().ratio(), None);
+
+        counter.set_total(200);
+        assert_eq!(counter.progress().ratio(), Some(0.25));
+
+        counter.set_loaded(200);
+        assert_eq!(counter.progress().ratio(), Some(1.0));
+    }
+}
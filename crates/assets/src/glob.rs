@@ -0,0 +1,153 @@
+/// Returns true if the path contains wildcards (`*`, `**` or `?`)
+pub(crate) fn is_glob(path: &str) -> bool {
+    path.contains(['*', '?'])
+}
+
+/// Pattern that matches all the files inside of the directory (recursively)
+pub(crate) fn dir_pattern(dir: &str) -> String {
+    format!("{}/**", dir.trim_end_matches('/'))
+}
+
+/// Checks the path against the pattern, `*` and `?` don't match `/` but `**` matches any
+/// number of directories. A leading `./` is ignored on both.
+pub(crate) fn glob_match(pattern: &str, path: &str) -> bool {
+    let pattern = segments(pattern);
+    let path = segments(path);
+    match_segments(&pattern, &path)
+}
+
+/// Returns the paths that match the pattern, sorted and without duplicates
+pub(crate) fn filter<'a>(pattern: &str, paths: impl Iterator<Item = &'a str>) -> Vec<String> {
+    let mut matches = paths
+        .filter(|path| glob_match(pattern, path))
+        .map(|path| path.to_string())
+        .collect::<Vec<_>>();
+    matches.sort();
+    matches.dedup();
+    matches
+}
+
+/// Lists the files on disk that match the pattern
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) fn walk(pattern: &str) -> Result<Vec<String>, String> {
+    // start from the directories before the first wildcard
+    let base = pattern
+        .split('/')
+        .take_while(|segment| !is_glob(segment))
+        .collect::<Vec<_>>()
+        .join("/");
+
+    let mut files = vec![];
+    let base_path = if base.is_empty() { "." } else { base.as_str() };
+    if std::path::Path::new(base_path).is_dir() {
+        collect_files(base_path, &mut files)?;
+    }
+
+    Ok(filter(pattern, files.iter().map(|f| f.as_str())))
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn collect_files(dir: &str, files: &mut Vec<String>) -> Result<(), String> {
+    let entries =
+        std::fs::read_dir(dir).map_err(|e| format!("Cannot read directory '{dir}': {e}"))?;
+    for entry in entries {
+        let entry = entry.map_err(|e| e.to_string())?;
+        let name = entry.file_name().to_string_lossy().to_string();
+        let path = format!("{}/{name}", dir.trim_end_matches('/'));
+        if entry.path().is_dir() {
+            collect_files(&path, files)?;
+        } else {
+            files.push(path);
+        }
+    }
+
+    Ok(())
+}
+
+/// Parses a manifest with one path per line, empty lines and lines starting with `#` are ignored
+pub(crate) fn parse_manifest(text: &str) -> Vec<String> {
+    text.lines()
+        .map(|line| line.trim())
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        // integrity manifests have the size and checksum after a tab
+        .map(|line| line.split('\t').next().unwrap_or(line).to_string())
+        .collect()
+}
+
+fn segments(path: &str) -> Vec<&str> {
+    path.trim_start_matches("./")
+        .split('/')
+        .filter(|s| !s.is_empty() && *s != ".")
+        .collect()
+}
+
+fn match_segments(pattern: &[&str], path: &[&str]) -> bool {
+    match pattern.split_first() {
+        None => path.is_empty(),
+        Some((&"**", rest)) => (0..=path.len()).any(|i| match_segments(rest, &path[i..])),
+        Some((segment, rest)) => path.split_first().is_some_and(|(name, path_rest)| {
+            match_name(segment, name) && match_segments(rest, path_rest)
+        }),
+    }
+}
+
+fn match_name(pattern: &str, name: &str) -> bool {
+    let (p, n) = (pattern.as_bytes(), name.as_bytes());
+    let (mut pi, mut ni) = (0, 0);
+    let mut star = None;
+    while ni < n.len() {
+        if pi < p.len() && (p[pi] == b'?' || p[pi] == n[ni]) {
+            pi += 1;
+            ni += 1;
+        } else if pi < p.len() && p[pi] == b'*' {
+            star = Some((pi, ni));
+            pi += 1;
+        } else if let Some((star_pi, star_ni)) = star {
+            // let the last star consume one more char
+            pi = star_pi + 1;
+            ni = star_ni + 1;
+            star = Some((star_pi, star_ni + 1));
+        } else {
+            return false;
+        }
+    }
+
+    p[pi..].iter().all(|c| *c == b'*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_glob_match() {
+        assert!(glob_match("sprites/*.png", "sprites/hero.png"));
+        assert!(glob_match("./sprites/*.png", "sprites/hero.png"));
+        assert!(glob_match("sprites/h?ro.png", "./sprites/hero.png"));
+        assert!(!glob_match("sprites/*.png", "sprites/hero.jpg"));
+        assert!(!glob_match("sprites/*.png", "sprites/enemies/orc.png"));
+        assert!(glob_match("sprites/**/*.png", "sprites/enemies/orc.png"));
+        assert!(glob_match("sprites/**/*.png", "sprites/hero.png"));
+        assert!(glob_match("*", "file"));
+        assert!(glob_match("a*b*c", "aXXbYYc"));
+        assert!(!glob_match("a*b*c", "aXXbYY"));
+    }
+
+    #[test]
+    fn test_dir_pattern() {
+        let paths = ["levels/1.json", "levels/extra/2.json", "sprites/hero.png"];
+        let matches = filter(&dir_pattern("levels/"), paths.into_iter());
+        assert_eq!(matches, vec!["levels/1.json", "levels/extra/2.json"]);
+    }
+
+    #[test]
+    fn test_parse_manifest() {
+        let manifest = "# generated\nlevels/1.json\n\n  sprites/hero.png \n";
+        assert_eq!(
+            parse_manifest(manifest),
+            vec!["levels/1.json", "sprites/hero.png"]
+        );
+        let manifest = "levels/1.json\t12\t00000000000000ff";
+        assert_eq!(parse_manifest(manifest), vec!["levels/1.json"]);
+    }
+}
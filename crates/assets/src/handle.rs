@@ -0,0 +1,57 @@
+use crate::AssetId;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::marker::PhantomData;
+
+/// Typed reference to an asset added with `AssetList::load_typed`.
+/// The type is the one returned by the parser, so `AssetMap::get_typed`
+/// can't fail because of a type mismatch.
+pub struct Handle<T> {
+    id: AssetId,
+    _t: PhantomData<fn() -> T>,
+}
+
+impl<T> Handle<T> {
+    pub(crate) fn new(id: AssetId) -> Self {
+        Self {
+            id,
+            _t: PhantomData,
+        }
+    }
+
+    /// Id of the loaded file
+    pub fn id(&self) -> AssetId {
+        self.id
+    }
+}
+
+impl<T> Copy for Handle<T> {}
+
+impl<T> Clone for Handle<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> PartialEq for Handle<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl<T> Eq for Handle<T> {}
+
+impl<T> Hash for Handle<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.id.hash(state);
+    }
+}
+
+impl<T> fmt::Debug for Handle<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Handle")
+            .field("id", &self.id)
+            .field("type", &std::any::type_name::<T>())
+            .finish()
+    }
+}
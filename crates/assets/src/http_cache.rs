@@ -0,0 +1,159 @@
+use crate::events::ProgressCounter;
+use rustc_hash::FxHasher;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+
+/// Data and etag of a response, `None` if the server returns 'Not Modified'
+pub(crate) type UrlResponse = Option<(Vec<u8>, Option<String>)>;
+
+/// Stores the files loaded from urls on disk, keyed by url and validated with the `ETag`
+/// sent by the server, so the next runs can load them without waiting for the network
+/// or while offline. See `set_http_cache`
+#[derive(Clone, Debug, PartialEq)]
+pub struct HttpCache {
+    dir: PathBuf,
+    revalidate: bool,
+}
+
+impl HttpCache {
+    pub fn new(dir: impl AsRef<Path>) -> Self {
+        Self {
+            dir: dir.as_ref().to_path_buf(),
+            revalidate: true,
+        }
+    }
+
+    /// Cache inside of the user data directory of the app (see `utils::helpers::user_data_path`)
+    pub fn for_app(app_name: &str) -> Result<Self, String> {
+        let dir = utils::helpers::user_data_path(app_name)
+            .ok_or_else(|| "Cannot find the user data directory".to_string())?;
+        Ok(Self::new(dir.join("http_cache")))
+    }
+
+    /// Asks the server if the cached file changed before using it (default `true`).
+    /// If `false` the cached files are used directly and the urls are only requested once.
+    /// The cached file is always used if the request fails.
+    pub fn with_revalidate(mut self, revalidate: bool) -> Self {
+        self.revalidate = revalidate;
+        self
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Removes all the cached files
+    pub fn clear(&self) -> Result<(), String> {
+        if !self.dir.exists() {
+            return Ok(());
+        }
+
+        std::fs::remove_dir_all(&self.dir)
+            .map_err(|e| format!("Cannot clear '{}': {e}", self.dir.display()))
+    }
+
+    fn entry_path(&self, url: &str) -> PathBuf {
+        let mut hasher = FxHasher::default();
+        url.hash(&mut hasher);
+        self.dir.join(format!("{:016x}", hasher.finish()))
+    }
+
+    /// Returns the data and the etag, the url is stored with them to avoid collisions
+    fn read(&self, url: &str) -> Option<(Vec<u8>, Option<String>)> {
+        let data = std::fs::read(self.entry_path(url)).ok()?;
+        let (header, data) = data.split_at(data.iter().position(|b| *b == b'\n')? + 1);
+        let header = std::str::from_utf8(header).ok()?.trim_end();
+        let (cached_url, etag) = header.split_once(' ').unwrap_or((header, ""));
+        if cached_url != url {
+            return None;
+        }
+
+        let etag = (!etag.is_empty()).then(|| etag.to_string());
+        Some((data.to_vec(), etag))
+    }
+
+    fn write(&self, url: &str, data: &[u8], etag: Option<&str>) -> Result<(), String> {
+        std::fs::create_dir_all(&self.dir)
+            .map_err(|e| format!("Cannot create '{}': {e}", self.dir.display()))?;
+
+        // urls don't have spaces or line breaks, etags can have spaces but not line breaks
+        let etag = etag.filter(|etag| !etag.contains('\n')).unwrap_or("");
+        let mut entry = format!("{url} {etag}\n").into_bytes();
+        entry.extend_from_slice(data);
+
+        // write to a temp file first to not leave half files if the app is closed
+        let path = self.entry_path(url);
+        let tmp = path.with_extension("tmp");
+        std::fs::write(&tmp, entry).map_err(|e| e.to_string())?;
+        std::fs::rename(&tmp, &path).map_err(|e| e.to_string())
+    }
+}
+
+/// Loads the url using the cache if it's valid, storing the response on it
+pub(crate) fn load_cached_url(
+    cache: &HttpCache,
+    url: &str,
+    progress: &ProgressCounter,
+    load: impl FnOnce(Option<&str>) -> Result<UrlResponse, String>,
+) -> Result<Vec<u8>, String> {
+    let cached = cache.read(url);
+    let etag = cached.as_ref().and_then(|(_, etag)| etag.as_deref());
+    let use_cached = |data: Vec<u8>| {
+        let len = data.len() as u64;
+        progress.set_total(len);
+        progress.set_loaded(len);
+        Ok(data)
+    };
+
+    if !cache.revalidate {
+        if let Some((data, _)) = cached {
+            return use_cached(data);
+        }
+    }
+
+    match load(etag) {
+        // the server says that the file didn't change
+        Ok(None) => match cached {
+            Some((data, _)) => use_cached(data),
+            None => Err(format!("Invalid 'Not Modified' response for '{url}'")),
+        },
+        Ok(Some((data, etag))) => {
+            if let Err(e) = cache.write(url, &data, etag.as_deref()) {
+                log::warn!("Cannot cache '{url}': {e}");
+            }
+
+            Ok(data)
+        }
+        Err(e) => match cached {
+            Some((data, _)) => {
+                log::warn!("Using the cached '{url}' because the request failed: {e}");
+                use_cached(data)
+            }
+            None => Err(e),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_write() {
+        let dir = std::env::temp_dir().join(format!("assets_http_cache_{}", std::process::id()));
+        let cache = HttpCache::new(&dir);
+        let url = "https://example.com/hero.png";
+        assert!(cache.read(url).is_none());
+
+        cache.write(url, b"data\nmore", Some("\"abc 1\"")).unwrap();
+        let (data, etag) = cache.read(url).unwrap();
+        assert_eq!(data, b"data\nmore");
+        assert_eq!(etag.as_deref(), Some("\"abc 1\""));
+
+        cache.write(url, b"new", None).unwrap();
+        assert_eq!(cache.read(url), Some((b"new".to_vec(), None)));
+
+        cache.clear().unwrap();
+        assert!(cache.read(url).is_none());
+    }
+}
@@ -0,0 +1,199 @@
+use rustc_hash::FxHashMap;
+
+/// Size and checksum of a file listed in an `AssetManifest`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ManifestEntry {
+    pub size: u64,
+    pub checksum: u64,
+}
+
+impl ManifestEntry {
+    pub fn new(data: &[u8]) -> Self {
+        Self {
+            size: data.len() as u64,
+            checksum: checksum(data),
+        }
+    }
+}
+
+/// Files with their size and checksum, generated at build time to verify the
+/// files once they are loaded and to know which ones changed between builds.
+/// The text format is one `path\tsize\tchecksum` per line, so it can be used
+/// with `set_assets_manifest` too.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AssetManifest {
+    entries: FxHashMap<String, ManifestEntry>,
+}
+
+impl AssetManifest {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parses the text format, empty lines and lines starting with `#` are ignored
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut manifest = Self::new();
+        let lines = text
+            .lines()
+            .map(|line| line.trim())
+            .filter(|line| !line.is_empty() && !line.starts_with('#'));
+
+        for line in lines {
+            let mut parts = line.split('\t');
+            let (Some(path), Some(size), Some(checksum), None) =
+                (parts.next(), parts.next(), parts.next(), parts.next())
+            else {
+                return Err(format!("Invalid manifest line '{line}'"));
+            };
+
+            let size = size
+                .parse()
+                .map_err(|e| format!("Invalid size for '{path}': {e}"))?;
+            let checksum = u64::from_str_radix(checksum, 16)
+                .map_err(|e| format!("Invalid checksum for '{path}': {e}"))?;
+            manifest.insert(path, ManifestEntry { size, checksum });
+        }
+
+        Ok(manifest)
+    }
+
+    /// Adds the file computing its size and checksum
+    pub fn add(&mut self, path: &str, data: &[u8]) {
+        self.insert(path, ManifestEntry::new(data));
+    }
+
+    pub fn insert(&mut self, path: &str, entry: ManifestEntry) {
+        self.entries.insert(normalize_path(path).to_string(), entry);
+    }
+
+    pub fn get(&self, path: &str) -> Option<&ManifestEntry> {
+        self.entries.get(normalize_path(path))
+    }
+
+    pub fn contains(&self, path: &str) -> bool {
+        self.entries.contains_key(normalize_path(path))
+    }
+
+    /// Returns the paths of the files sorted
+    pub fn paths(&self) -> Vec<&str> {
+        let mut paths = self.entries.keys().map(|p| p.as_str()).collect::<Vec<_>>();
+        paths.sort();
+        paths
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the manifest in the text format, sorted by path
+    pub fn to_text(&self) -> String {
+        self.paths()
+            .into_iter()
+            .map(|path| {
+                let entry = &self.entries[path];
+                format!("{path}\t{}\t{:016x}", entry.size, entry.checksum)
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Checks the data against the entry of the file, files not listed are valid
+    pub fn verify(&self, path: &str, data: &[u8]) -> Result<(), String> {
+        let Some(entry) = self.get(path) else {
+            return Ok(());
+        };
+
+        if entry.size != data.len() as u64 {
+            return Err(format!(
+                "File '{path}' is corrupted: expected {} bytes, got {}",
+                entry.size,
+                data.len()
+            ));
+        }
+
+        if entry.checksum != checksum(data) {
+            return Err(format!("File '{path}' is corrupted: checksum mismatch"));
+        }
+
+        Ok(())
+    }
+
+    /// Files added or modified since the previous manifest, the ones a patch needs
+    pub fn changed_since(&self, previous: &AssetManifest) -> Vec<String> {
+        self.paths()
+            .into_iter()
+            .filter(|path| previous.get(path) != self.get(path))
+            .map(|path| path.to_string())
+            .collect()
+    }
+
+    /// Files listed in the previous manifest that are not listed anymore
+    pub fn removed_since(&self, previous: &AssetManifest) -> Vec<String> {
+        previous
+            .paths()
+            .into_iter()
+            .filter(|path| !self.contains(path))
+            .map(|path| path.to_string())
+            .collect()
+    }
+}
+
+/// 64 bits FNV-1a hash of the data, stable between builds and platforms
+pub fn checksum(data: &[u8]) -> u64 {
+    data.iter().fold(0xcbf29ce484222325, |hash, b| {
+        (hash ^ *b as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+// manifests store paths without the leading './' to match both forms
+fn normalize_path(path: &str) -> &str {
+    path.trim_start_matches("./")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_roundtrip() {
+        let mut manifest = AssetManifest::new();
+        manifest.add("./assets/b.txt", b"world");
+        manifest.add("assets/a.txt", b"hello");
+
+        let text = manifest.to_text();
+        assert!(text.starts_with("assets/a.txt\t5\t"));
+        assert_eq!(AssetManifest::parse(&text).unwrap(), manifest);
+        assert!(AssetManifest::parse("assets/a.txt").is_err());
+    }
+
+    #[test]
+    fn test_verify() {
+        let mut manifest = AssetManifest::new();
+        manifest.add("a.txt", b"hello");
+
+        assert!(manifest.verify("./a.txt", b"hello").is_ok());
+        assert!(manifest.verify("a.txt", b"hell").is_err());
+        assert!(manifest.verify("a.txt", b"hellO").is_err());
+        assert!(manifest.verify("unknown.txt", b"").is_ok());
+    }
+
+    #[test]
+    fn test_changes() {
+        let mut old = AssetManifest::new();
+        old.add("a.txt", b"a");
+        old.add("b.txt", b"b");
+        old.add("c.txt", b"c");
+
+        let mut new = AssetManifest::new();
+        new.add("a.txt", b"a");
+        new.add("b.txt", b"B");
+        new.add("d.txt", b"d");
+
+        assert_eq!(new.changed_since(&old), ["b.txt", "d.txt"]);
+        assert_eq!(new.removed_since(&old), ["c.txt"]);
+    }
+}
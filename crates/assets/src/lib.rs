@@ -1,11 +1,37 @@
+mod decode;
+mod deps;
 mod events;
+mod glob;
+mod handle;
+#[cfg(all(feature = "http", not(target_arch = "wasm32")))]
+mod http_cache;
+mod integrity;
 mod list;
 mod load_file;
 mod loader;
+mod pack;
+mod policy;
+mod sources;
+mod tracker;
 mod waker;
 
+#[cfg(all(feature = "watch", not(target_arch = "wasm32")))]
+mod watch;
+
+pub use crate::decode::DecodeTask;
+#[cfg(all(feature = "watch", not(target_arch = "wasm32")))]
+pub use crate::events::AssetReloadedEvent;
+pub use crate::events::{AssetEvent, LoadProgress, LoadState};
+pub use crate::handle::Handle;
+#[cfg(all(feature = "http", not(target_arch = "wasm32")))]
+pub use crate::http_cache::HttpCache;
+pub use crate::integrity::{checksum, AssetManifest, ManifestEntry};
 pub use crate::list::{AssetList, AssetMap};
 pub use crate::loader::AssetId;
+pub use crate::pack::{AssetPack, AssetPackWriter};
+pub use crate::policy::{LoadPolicy, LoadPriority};
+pub use crate::sources::{AssetSource, MemorySource};
+pub use crate::tracker::LoadingTracker;
 
 use crate::loader::ASSET_LOADER;
 
@@ -14,6 +40,36 @@ pub fn load_asset(file_path: &str) -> AssetId {
     ASSET_LOADER.borrow_mut().load(file_path)
 }
 
+/// Loads the file using `policy` instead of the default one to retry or time out
+#[inline]
+pub fn load_asset_with_policy(file_path: &str, policy: LoadPolicy) -> AssetId {
+    ASSET_LOADER
+        .borrow_mut()
+        .load_with_policy(file_path, policy)
+}
+
+/// Loads the file using the default policy with a different priority
+#[inline]
+pub fn load_asset_with_priority(file_path: &str, priority: LoadPriority) -> AssetId {
+    ASSET_LOADER
+        .borrow_mut()
+        .load_with_priority(file_path, priority)
+}
+
+/// Sets the policy used by the files loaded from now on, see `LoadPolicy`
+#[inline]
+pub fn set_load_policy(policy: LoadPolicy) {
+    ASSET_LOADER.borrow_mut().set_policy(policy);
+}
+
+/// Max number of files requested at the same time (`None` by default, no limit).
+/// The rest wait in a queue and start by priority, so critical files don't wait
+/// behind big ones on slow connections. `Critical` files ignore the limit.
+#[inline]
+pub fn set_max_concurrent_loads(max: Option<usize>) {
+    ASSET_LOADER.borrow_mut().set_max_concurrent(max);
+}
+
 /// Used by system to pull the assets futures
 #[inline]
 pub(crate) fn update_assets() {
@@ -30,6 +86,50 @@ pub fn is_loading(id: &AssetId) -> bool {
     ASSET_LOADER.borrow().is_loading(*id)
 }
 
+/// Returns `None` if the id is not valid
+#[inline]
+pub fn load_state(id: &AssetId) -> Option<LoadState> {
+    ASSET_LOADER.borrow().state(*id)
+}
+
+/// Requests again a file that failed (or cancels the backoff wait of a retry),
+/// the id is still valid and the file will be loading until it ends again
+#[inline]
+pub fn reload_asset(id: &AssetId) -> Result<(), String> {
+    ASSET_LOADER.borrow_mut().reload(*id)
+}
+
+/// Returns the bytes loaded of the file, useful to show download bars for remote files
+#[inline]
+pub fn load_progress(id: &AssetId) -> Option<LoadProgress> {
+    ASSET_LOADER.borrow().progress(*id)
+}
+
+/// Removes a reference to the file, the data is dropped (or cached if there is a budget)
+/// once no one is using it. Parsing or decoding with `keep: false` does the same,
+/// except for failed files which are kept to be reloaded until this is called.
+/// Returns `false` if the id is not valid.
+#[inline]
+pub fn unload_asset(id: &AssetId) -> bool {
+    let mut loader = ASSET_LOADER.borrow_mut();
+    let valid = loader.progress(*id).is_some();
+    let _ = loader.release(*id);
+    valid
+}
+
+/// Keeps the unused files in memory until their size exceeds `bytes`, then the least
+/// recently used are removed. `None` (default) removes them as soon as they are unused.
+#[inline]
+pub fn set_assets_budget(bytes: Option<usize>) {
+    ASSET_LOADER.borrow_mut().set_budget(bytes);
+}
+
+/// Bytes used by the data of the files loaded (and not removed yet)
+#[inline]
+pub fn loaded_assets_bytes() -> usize {
+    ASSET_LOADER.borrow().loaded_bytes()
+}
+
 #[inline]
 pub fn parse_asset<T, F>(id: &AssetId, parser: F, keep: bool) -> Result<Option<T>, String>
 where
@@ -38,6 +138,108 @@ where
     ASSET_LOADER.borrow_mut().parse(*id, parser, keep)
 }
 
+/// Decodes the asset outside the main loop (thread pool on native, spread across frames on web),
+/// useful for big images or audio files. Returns `None` while the file is still loading
+/// `Web`: Decoding is time-sliced, the decoder runs on the main thread one job per frame,
+/// so a single big file still blocks the frame that decodes it
+#[inline]
+pub fn decode_asset<T, F>(
+    id: &AssetId,
+    decoder: F,
+    keep: bool,
+) -> Result<Option<DecodeTask<T>>, String>
+where
+    T: Send + 'static,
+    F: FnOnce(&str, &[u8]) -> Result<T, String> + Send + 'static,
+{
+    ASSET_LOADER.borrow_mut().decode(*id, decoder, keep)
+}
+
+/// Returns the paths of the files that match the pattern (`sprites/*.png`, `levels/**/*.json`),
+/// a path without wildcards (`levels/`) returns all the files inside of the directory.
+/// The files are listed from disk, or from the manifest if one is set, plus the mounted packs.
+/// Web: it needs a manifest (see `set_assets_manifest`) or mounted packs
+pub fn glob_assets(pattern: &str) -> Result<Vec<String>, String> {
+    let pattern = if glob::is_glob(pattern) {
+        pattern.to_string()
+    } else {
+        glob::dir_pattern(pattern)
+    };
+
+    ASSET_LOADER.borrow().expand_glob(&pattern)
+}
+
+/// Sets the list of files used to expand glob patterns instead of reading the disk,
+/// one path per line (see the `assets_manifest` example to generate it). Manifests
+/// written by `AssetManifest` can be used too, only the paths are read
+#[inline]
+pub fn set_assets_manifest(manifest: Option<&str>) {
+    ASSET_LOADER
+        .borrow_mut()
+        .set_manifest(manifest.map(glob::parse_manifest));
+}
+
+/// Verifies the size and checksum of the files listed in the manifest once they are loaded,
+/// corrupted files fail and are requested again following the `LoadPolicy` retries
+#[inline]
+pub fn set_assets_integrity(manifest: Option<AssetManifest>) {
+    ASSET_LOADER.borrow_mut().set_integrity(manifest);
+}
+
+/// Returns the files loaded, failed or removed since the last frame,
+/// useful to react to them instead of checking `is_loaded` each frame
+#[inline]
+pub fn asset_events() -> Vec<AssetEvent> {
+    ASSET_LOADER.borrow().events().to_vec()
+}
+
+/// Returns the files reloaded during the current frame because they changed on disk
+#[cfg(all(feature = "watch", not(target_arch = "wasm32")))]
+#[inline]
+pub fn reloaded_assets() -> Vec<AssetReloadedEvent> {
+    ASSET_LOADER.borrow().watch.reloaded.clone()
+}
+
+/// Files inside of the pack will be loaded from it instead of the disk or network,
+/// if several packs have the same file the last one mounted is used
+#[inline]
+pub fn mount_pack(pack: AssetPack) {
+    ASSET_LOADER.borrow_mut().mount_pack(pack);
+}
+
+#[inline]
+pub fn unmount_packs() {
+    ASSET_LOADER.borrow_mut().unmount_packs();
+}
+
+/// Serves the file from memory when `path` is loaded, used by `embed_assets!` to
+/// ship the files inside of the binary. Mounted packs have priority over them.
+#[inline]
+pub fn embed_asset(path: &str, data: &'static [u8]) {
+    ASSET_LOADER.borrow_mut().embed(path, data);
+}
+
+/// Stores the files loaded from urls on disk to use them on the next runs, `None` disables it
+#[cfg(all(feature = "http", not(target_arch = "wasm32")))]
+#[inline]
+pub fn set_http_cache(cache: Option<HttpCache>) {
+    ASSET_LOADER.borrow_mut().set_http_cache(cache);
+}
+
+/// Loads the paths starting with `scheme://` (e.g. `pak://textures/hero.png`) from `source`
+#[inline]
+pub fn register_asset_source<T: AssetSource>(scheme: &str, source: T) {
+    ASSET_LOADER
+        .borrow_mut()
+        .register_source(scheme, std::sync::Arc::new(source));
+}
+
+/// Returns `false` if there was no source registered for the scheme
+#[inline]
+pub fn unregister_asset_source(scheme: &str) -> bool {
+    ASSET_LOADER.borrow_mut().unregister_source(scheme)
+}
+
 #[inline]
 pub fn clear_assets() {
     ASSET_LOADER.borrow_mut().clear();
@@ -1,24 +1,30 @@
-use crate::{is_loaded, is_loading, AssetId};
+use crate::deps;
+use crate::handle::Handle;
+use crate::{is_loaded, is_loading, AssetId, LoadState};
 use rustc_hash::FxHashMap;
 use std::any::{Any, TypeId};
+use std::collections::hash_map::Entry;
 use std::rc::Rc;
 
 #[derive(Default)]
 pub struct AssetMap {
     inner: FxHashMap<TypeId, FxHashMap<String, Rc<dyn Any>>>,
+    by_id: FxHashMap<AssetId, Rc<dyn Any>>,
     len: usize,
 }
 
 impl AssetMap {
-    pub(crate) fn insert<T: 'static>(&mut self, id: String, asset: T) {
+    pub(crate) fn insert<T: 'static>(&mut self, aid: AssetId, id: String, asset: T) {
         let type_id = TypeId::of::<T>();
+        let asset: Rc<dyn Any> = Rc::new(asset);
+        self.by_id.insert(aid, asset.clone());
 
-        self.inner
-            .entry(type_id)
-            .or_default()
-            .insert(id, Rc::new(asset));
+        let old = self.inner.entry(type_id).or_default().insert(id, asset);
 
-        self.len += 1;
+        // reloaded assets replace the old ones
+        if old.is_none() {
+            self.len += 1;
+        }
     }
 
     pub fn get<T: Clone + 'static>(&self, id: &str) -> Result<T, String> {
@@ -38,6 +44,15 @@ impl AssetMap {
             .map(|asset| (*asset).clone())
     }
 
+    /// Returns the asset referenced by the handle
+    pub fn get_typed<T: Clone + 'static>(&self, handle: &Handle<T>) -> Result<T, String> {
+        self.by_id
+            .get(&handle.id())
+            .and_then(|asset| asset.downcast_ref::<T>())
+            .cloned()
+            .ok_or_else(|| format!("Cannot find asset for handle {handle:?}"))
+    }
+
     pub fn len(&self) -> usize {
         self.len
     }
@@ -50,9 +65,30 @@ impl AssetMap {
 struct Data {
     id: AssetId,
     loaded: bool,
+    parser: Option<Box<ParserFn>>,
+    /// Files that must be parsed before this one, `None` until the file is loaded
+    deps: Option<Vec<String>>,
+    /// Parsing on the thread pool
+    task: Option<Box<TaskFn>>,
+}
+
+impl Data {
+    fn new(id: AssetId) -> Self {
+        Self {
+            id,
+            loaded: false,
+            parser: None,
+            deps: None,
+            task: None,
+        }
+    }
 }
 
 type ParserFn = dyn Fn(&AssetId, &str, &mut AssetMap) -> Result<(), String>;
+type DepsFn = dyn Fn(&AssetId) -> Result<Option<Vec<String>>, String>;
+type BackgroundFn = dyn Fn(&AssetId) -> Result<Option<Box<TaskFn>>, String>;
+// returns `None` until the task is done
+type TaskFn = dyn FnMut(&AssetId, &str, &mut AssetMap) -> Option<Result<(), String>>;
 
 pub struct AssetList {
     inner: FxHashMap<String, Data>,
@@ -60,16 +96,15 @@ pub struct AssetList {
 
     assets: AssetMap,
     parsers: FxHashMap<String, Box<ParserFn>>,
+    deps_parsers: FxHashMap<String, Box<DepsFn>>,
+    background_parsers: FxHashMap<String, Box<BackgroundFn>>,
 }
 
 impl AssetList {
     pub fn new(paths: &[&str]) -> Self {
         let inner = paths
             .iter()
-            .map(|path| {
-                let id = super::load_asset(path);
-                (path.to_string(), Data { id, loaded: false })
-            })
+            .map(|path| (path.to_string(), Data::new(super::load_asset(path))))
             .collect::<FxHashMap<String, Data>>();
         let count = inner.len();
         Self {
@@ -77,9 +112,25 @@ impl AssetList {
             total: count,
             assets: AssetMap::default(),
             parsers: FxHashMap::default(),
+            deps_parsers: FxHashMap::default(),
+            background_parsers: FxHashMap::default(),
         }
     }
 
+    /// Loads the files that match the pattern, or all the files of the directory, see `glob_assets`
+    pub fn from_glob(pattern: &str) -> Result<Self, String> {
+        let paths = super::glob_assets(pattern)?;
+        let paths = paths.iter().map(|p| p.as_str()).collect::<Vec<_>>();
+        Ok(Self::new(&paths))
+    }
+
+    /// Paths of the files in the list
+    pub fn paths(&self) -> impl Iterator<Item = &str> {
+        self.inner.keys().map(|p| p.as_str())
+    }
+
+    /// Progress of the files and their dependencies, the total grows when new dependencies
+    /// are found so it can go back a bit while the files are loading
     pub fn progress(&self) -> f32 {
         // let's do this just to avoid some float impression
         if self.is_loaded() {
@@ -91,20 +142,48 @@ impl AssetList {
     }
 
     pub fn load_len(&self) -> usize {
-        self.assets.len()
-            + self.inner.iter().fold(0, |count, (_, data)| {
-                if is_loaded(&data.id) {
-                    count + 1
-                } else {
-                    count
-                }
-            })
+        self.inner.iter().fold(0, |count, (path, data)| {
+            // files with dependencies or parsed in the background are loaded once they are parsed
+            let parsed_only = data.parser.is_none()
+                && extension(path).is_some_and(|ext| {
+                    self.deps_parsers.contains_key(ext) || self.background_parsers.contains_key(ext)
+                });
+            if data.loaded || (!parsed_only && is_loaded(&data.id)) {
+                count + 1
+            } else {
+                count
+            }
+        })
     }
 
     pub fn is_loaded(&self) -> bool {
         self.load_len() >= self.total
     }
 
+    /// Files that failed to load with their error
+    pub fn failed(&self) -> Vec<(&str, String)> {
+        self.inner
+            .iter()
+            .filter_map(|(path, data)| match super::load_state(&data.id) {
+                Some(LoadState::Failed(err)) => Some((path.as_str(), err)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Loads again the files that failed, returns how many of them
+    pub fn retry_failed(&mut self) -> Result<usize, String> {
+        let failed = self
+            .inner
+            .values()
+            .filter(|data| matches!(super::load_state(&data.id), Some(LoadState::Failed(_))))
+            .map(|data| data.id)
+            .collect::<Vec<_>>();
+
+        failed.iter().try_for_each(super::reload_asset)?;
+        Ok(failed.len())
+    }
+
     pub fn with_extension_parser<T, F>(mut self, ext: &str, parser: F) -> Self
     where
         F: Fn(&str, &[u8]) -> Result<T, String> + 'static + Clone,
@@ -115,7 +194,7 @@ impl AssetList {
             Box::new(move |aid: &AssetId, id: &str, map: &mut AssetMap| {
                 let parsed = super::parse_asset::<T, F>(aid, parser.clone(), false)?;
                 if let Some(parsed_asset) = parsed {
-                    map.insert(id.to_string(), parsed_asset);
+                    map.insert(*aid, id.to_string(), parsed_asset);
                 }
 
                 Ok(())
@@ -124,30 +203,222 @@ impl AssetList {
         self
     }
 
+    /// Parses the files with the extension on the thread pool (spread across frames on web),
+    /// the result is added to the list on the next calls to `parse`. Useful for heavy
+    /// parsers that don't need the main thread, otherwise use `with_extension_parser`
+    pub fn with_background_parser<T, F>(self, ext: &str, parser: F) -> Self
+    where
+        F: Fn(&str, &[u8]) -> Result<T, String> + Send + Sync + Clone + 'static,
+        T: Send + 'static,
+    {
+        self.with_background_decoder(ext, parser, |_, decoded| Ok(decoded))
+    }
+
+    /// Like `with_background_parser` but the decoded data is passed to `finish` on the
+    /// main thread, e.g. images decoded on the pool and uploaded to the GPU by `finish`
+    pub fn with_background_decoder<D, T, F, G>(mut self, ext: &str, decoder: F, finish: G) -> Self
+    where
+        F: Fn(&str, &[u8]) -> Result<D, String> + Send + Sync + Clone + 'static,
+        G: Fn(&str, D) -> Result<T, String> + Clone + 'static,
+        D: Send + 'static,
+        T: 'static,
+    {
+        self.background_parsers.insert(
+            ext.to_string(),
+            Box::new(move |aid: &AssetId| {
+                let task = super::decode_asset(aid, decoder.clone(), false)?;
+                let finish = finish.clone();
+                Ok(task.map(|mut task| {
+                    let task_fn: Box<TaskFn> =
+                        Box::new(move |aid: &AssetId, id: &str, map: &mut AssetMap| {
+                            let res = task.try_take()?.and_then(|decoded| finish(id, decoded));
+                            Some(res.map(|asset| map.insert(*aid, id.to_string(), asset)))
+                        });
+                    task_fn
+                }))
+            }),
+        );
+        self
+    }
+
+    /// Parses the files with the extension once the files they depend on are parsed
+    /// (e.g. an atlas that needs its image). `find_deps` returns the dependencies of the file,
+    /// relative to it or to the assets root if they start with `/`. They are added to
+    /// the list and parsed with their own parsers, then `parser` can get them from
+    /// the `AssetMap` using their resolved path. Cycles are returned as errors by `parse`.
+    pub fn with_dependencies_parser<T, D, F>(mut self, ext: &str, find_deps: D, parser: F) -> Self
+    where
+        D: Fn(&str, &[u8]) -> Result<Vec<String>, String> + 'static,
+        F: Fn(&str, &[u8], &AssetMap) -> Result<T, String> + 'static,
+        T: 'static,
+    {
+        self.deps_parsers.insert(
+            ext.to_string(),
+            Box::new(move |aid: &AssetId| {
+                // the data is kept to be parsed later
+                super::parse_asset(
+                    aid,
+                    |path, data| {
+                        let found = find_deps(path, data)?;
+                        Ok(found
+                            .iter()
+                            .map(|dep| deps::resolve_path(path, dep))
+                            .collect())
+                    },
+                    true,
+                )
+            }),
+        );
+
+        self.parsers.insert(
+            ext.to_string(),
+            Box::new(move |aid: &AssetId, id: &str, map: &mut AssetMap| {
+                let assets: &AssetMap = map;
+                let parsed =
+                    super::parse_asset(aid, |path, data| parser(path, data, assets), false)?;
+                if let Some(parsed_asset) = parsed {
+                    map.insert(*aid, id.to_string(), parsed_asset);
+                }
+
+                Ok(())
+            }),
+        );
+        self
+    }
+
+    /// Adds a file to the list parsed with `parser`, the handle returned can be used
+    /// with `AssetMap::get_typed` to get the asset with the parser's type
+    pub fn load_typed<T, F>(&mut self, path: &str, parser: F) -> Handle<T>
+    where
+        F: Fn(&str, &[u8]) -> Result<T, String> + 'static + Clone,
+        T: 'static,
+    {
+        let parser: Box<ParserFn> = Box::new(move |aid: &AssetId, id: &str, map: &mut AssetMap| {
+            let parsed = super::parse_asset::<T, F>(aid, parser.clone(), false)?;
+            if let Some(parsed_asset) = parsed {
+                map.insert(*aid, id.to_string(), parsed_asset);
+            }
+
+            Ok(())
+        });
+
+        let data = self.inner.entry(path.to_string()).or_insert_with(|| {
+            self.total += 1;
+            Data::new(super::load_asset(path))
+        });
+
+        data.parser = Some(parser);
+        Handle::new(data.id)
+    }
+
+    /// Checks if any file of the list was reloaded this frame, in that case
+    /// the next call to `parse` will parse them again and return the new result
+    #[cfg(all(feature = "watch", not(target_arch = "wasm32")))]
+    pub fn check_reloaded(&mut self) -> bool {
+        let reloaded = super::reloaded_assets();
+        let mut any = false;
+        self.inner.iter_mut().for_each(|(path, data)| {
+            if reloaded.iter().any(|evt| evt.path == *path) {
+                // parsed files released their reference, so it's taken again
+                // (the file may have been removed and loaded again with a new id)
+                if data.loaded {
+                    data.id = super::load_asset(path);
+                }
+                data.loaded = false;
+                data.deps = None;
+                any = true;
+            }
+        });
+
+        // the files that depend on the reloaded ones are parsed again too
+        loop {
+            let dependents = self
+                .inner
+                .iter()
+                .filter(|(_, data)| data.loaded && !self.deps_parsed(data))
+                .map(|(path, _)| path.clone())
+                .collect::<Vec<_>>();
+
+            if dependents.is_empty() {
+                break;
+            }
+
+            dependents.iter().for_each(|path| {
+                if let Some(data) = self.inner.get_mut(path) {
+                    data.loaded = false;
+                }
+            });
+        }
+
+        any
+    }
+
     pub fn parse<T, F>(&mut self, parser: F) -> Result<Option<T>, String>
     where
         F: FnOnce(&AssetMap) -> Result<T, String>,
     {
-        for (path, data) in &mut self.inner {
-            if data.loaded || is_loading(&data.id) {
-                continue;
+        self.resolve_dependencies()?;
+
+        // the files are parsed after their dependencies, so the parsers can use them
+        let mut waiting = vec![];
+        loop {
+            let ready = self
+                .inner
+                .iter()
+                .filter(|(path, data)| !data.loaded && !waiting.contains(*path))
+                .filter(|(_, data)| data.task.is_some() || !is_loading(&data.id))
+                .filter(|(_, data)| self.deps_parsed(data))
+                .map(|(path, _)| path.clone())
+                .collect::<Vec<_>>();
+
+            if ready.is_empty() {
+                break;
             }
 
-            let ext = path.split('.').last().and_then(|ext| self.parsers.get(ext));
-            match ext {
-                // use the parser provided to store the asset as the type needed
-                Some(parser) => (*parser)(&data.id, path, &mut self.assets)?,
+            for path in ready {
+                let Some(data) = self.inner.get_mut(&path) else {
+                    continue;
+                };
 
-                // parse as Vec<u8> if there is no parser added for this extension
-                _ => {
-                    let parsed = super::parse_asset(&data.id, parse_vec, false)?;
-                    if let Some(parsed_asset) = parsed {
-                        self.assets.insert(path.clone(), parsed_asset);
+                if data.task.is_none() && data.parser.is_none() {
+                    let background =
+                        extension(&path).and_then(|ext| self.background_parsers.get(ext));
+                    if let Some(start) = background {
+                        data.task = start(&data.id)?;
+                    }
+                }
+
+                if let Some(task) = &mut data.task {
+                    match task(&data.id, &path, &mut self.assets) {
+                        Some(res) => {
+                            data.task = None;
+                            res?;
+                            data.loaded = true;
+                        }
+                        None => waiting.push(path),
+                    }
+                    continue;
+                }
+
+                let ext = data
+                    .parser
+                    .as_ref()
+                    .or_else(|| extension(&path).and_then(|ext| self.parsers.get(ext)));
+                match ext {
+                    // use the parser provided to store the asset as the type needed
+                    Some(parser) => (*parser)(&data.id, &path, &mut self.assets)?,
+
+                    // parse as Vec<u8> if there is no parser added for this extension
+                    _ => {
+                        let parsed = super::parse_asset(&data.id, parse_vec, false)?;
+                        if let Some(parsed_asset) = parsed {
+                            self.assets.insert(data.id, path.clone(), parsed_asset);
+                        }
                     }
                 }
-            }
 
-            data.loaded = true;
+                data.loaded = true;
+            }
         }
 
         if !self.is_loaded() {
@@ -156,6 +427,81 @@ impl AssetList {
 
         parser(&self.assets).map(|res| Some(res))
     }
+
+    /// Returns true if the dependencies of the file are known and parsed
+    fn deps_parsed(&self, data: &Data) -> bool {
+        data.deps.as_ref().is_some_and(|deps| {
+            deps.iter()
+                .all(|dep| self.inner.get(dep).is_some_and(|d| d.loaded))
+        })
+    }
+
+    /// Gets the dependencies of the loaded files, adding to the list the new ones
+    fn resolve_dependencies(&mut self) -> Result<(), String> {
+        let mut found = vec![];
+        for (path, data) in &mut self.inner {
+            if data.deps.is_some() || is_loading(&data.id) {
+                continue;
+            }
+
+            let deps_parser = match &data.parser {
+                Some(_) => None,
+                None => extension(path).and_then(|ext| self.deps_parsers.get(ext)),
+            };
+
+            let deps = match deps_parser {
+                Some(deps_parser) => match deps_parser(&data.id)? {
+                    Some(deps) => deps,
+                    None => continue,
+                },
+                None => vec![],
+            };
+
+            found.extend(deps.iter().cloned());
+            data.deps = Some(deps);
+        }
+
+        if found.is_empty() {
+            return Ok(());
+        }
+
+        for path in found {
+            if let Entry::Vacant(entry) = self.inner.entry(path) {
+                log::info!("Loading dependency '{}'", entry.key());
+                let id = super::load_asset(entry.key());
+                entry.insert(Data::new(id));
+                self.total += 1;
+            }
+        }
+
+        let graph = self
+            .inner
+            .iter()
+            .filter_map(|(path, data)| Some((path.as_str(), data.deps.as_deref()?)))
+            .collect::<FxHashMap<_, _>>();
+
+        match deps::find_cycle(&graph) {
+            Some(cycle) => Err(format!("Assets dependency cycle: {}", cycle.join(" -> "))),
+            None => Ok(()),
+        }
+    }
+}
+
+// the files not parsed yet are still referenced by the list,
+// except the ones parsing in the background which were already released
+impl Drop for AssetList {
+    fn drop(&mut self) {
+        self.inner
+            .values()
+            .filter(|data| !data.loaded && data.task.is_none())
+            .for_each(|data| {
+                let _ = super::unload_asset(&data.id);
+            });
+    }
+}
+
+fn extension(path: &str) -> Option<&str> {
+    path.rsplit('.').next()
 }
 
 fn parse_vec(_id: &str, data: &[u8]) -> Result<Vec<u8>, String> {
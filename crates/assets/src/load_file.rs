@@ -1,23 +1,34 @@
+use crate::events::ProgressCounter;
 #[cfg(not(target_arch = "wasm32"))]
 use futures::channel::oneshot;
 #[cfg(not(target_arch = "wasm32"))]
 use rayon::{ThreadPool, ThreadPoolBuilder};
 use std::future::Future;
+use std::sync::Arc;
 
 #[cfg(target_arch = "wasm32")]
 use futures_util::future::{poll_fn, ready, TryFutureExt};
 #[cfg(target_arch = "wasm32")]
 use js_sys::Uint8Array;
 #[cfg(target_arch = "wasm32")]
+use parking_lot::Mutex;
+#[cfg(target_arch = "wasm32")]
+use std::collections::VecDeque;
+#[cfg(target_arch = "wasm32")]
 use std::task::{Context, Poll};
 #[cfg(target_arch = "wasm32")]
 use wasm_bindgen::{closure::Closure, JsCast, JsValue};
 #[cfg(target_arch = "wasm32")]
-use web_sys::{XmlHttpRequest, XmlHttpRequestResponseType};
+use web_sys::{ProgressEvent, XmlHttpRequest, XmlHttpRequestResponseType};
+
+#[cfg(all(feature = "http", not(target_arch = "wasm32")))]
+use crate::http_cache::{HttpCache, UrlResponse};
 
 #[cfg(not(target_arch = "wasm32"))]
 pub(crate) struct FileLoader {
     thread_pool: ThreadPool,
+    #[cfg(feature = "http")]
+    cache: Option<Arc<HttpCache>>,
 }
 
 #[cfg(not(target_arch = "wasm32"))]
@@ -26,16 +37,41 @@ impl FileLoader {
         let thread_pool = ThreadPoolBuilder::default()
             .build()
             .map_err(|e| e.to_string())?;
-        Ok(Self { thread_pool })
+        Ok(Self {
+            thread_pool,
+            #[cfg(feature = "http")]
+            cache: None,
+        })
     }
 
-    pub fn load_file(&self, path: &str) -> impl Future<Output = Result<Vec<u8>, String>> {
+    #[cfg(feature = "http")]
+    pub fn set_cache(&mut self, cache: Option<HttpCache>) {
+        self.cache = cache.map(Arc::new);
+    }
+
+    pub fn load_file(
+        &self,
+        path: &str,
+        progress: Arc<ProgressCounter>,
+    ) -> impl Future<Output = Result<Vec<u8>, String>> {
         let (tx, rx) = oneshot::channel();
 
         let path = path.to_owned();
+        #[cfg(feature = "http")]
+        let cache = self.cache.clone();
         self.thread_pool.spawn(move || {
-            let read_result = std::fs::read(&path);
-            let _ = tx.send(read_result.map_err(|e| e.to_string()));
+            let read_result = if is_url(&path) {
+                #[cfg(feature = "http")]
+                let read_result = load_url(&path, &progress, cache.as_deref());
+
+                #[cfg(not(feature = "http"))]
+                let read_result = load_url(&path, &progress);
+
+                read_result
+            } else {
+                read_file(&path, &progress)
+            };
+            let _ = tx.send(read_result);
         });
 
         async move {
@@ -43,21 +79,195 @@ impl FileLoader {
                 .unwrap_or_else(|_| Err("The channel was dropped.".to_string()))
         }
     }
+
+    /// Runs the job on the thread pool
+    pub fn spawn_decode<F>(&self, job: F)
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        self.thread_pool.spawn(job);
+    }
+
+    pub fn update(&mut self) {}
+}
+
+/// Returns true if the path must be requested over HTTP
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) fn is_url(path: &str) -> bool {
+    path.starts_with("http://") || path.starts_with("https://")
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn read_file(path: &str, progress: &ProgressCounter) -> Result<Vec<u8>, String> {
+    let data = read_bytes(path)?;
+    let len = data.len() as u64;
+    progress.set_total(len);
+    progress.set_loaded(len);
+    Ok(data)
+}
+
+#[cfg(not(any(target_arch = "wasm32", target_os = "android", target_os = "ios")))]
+fn read_bytes(path: &str) -> Result<Vec<u8>, String> {
+    std::fs::read(path).map_err(|e| e.to_string())
+}
+
+/// Relative paths are read from the `assets` folder of the apk
+#[cfg(target_os = "android")]
+fn read_bytes(path: &str) -> Result<Vec<u8>, String> {
+    use std::io::Read;
+
+    if std::path::Path::new(path).is_absolute() {
+        return std::fs::read(path).map_err(|e| e.to_string());
+    }
+
+    let app = corelib::app::android_app()
+        .ok_or_else(|| "The AndroidApp must be set with 'set_android_app'".to_string())?;
+    let c_path = std::ffi::CString::new(path).map_err(|e| e.to_string())?;
+    let mut asset = app
+        .asset_manager()
+        .open(&c_path)
+        .ok_or_else(|| format!("Cannot find '{path}' in the apk assets"))?;
+
+    let mut data = vec![];
+    asset.read_to_end(&mut data).map_err(|e| e.to_string())?;
+    Ok(data)
+}
+
+/// Relative paths are read from the app bundle, the working directory is not set to it
+#[cfg(target_os = "ios")]
+fn read_bytes(path: &str) -> Result<Vec<u8>, String> {
+    let path = std::path::Path::new(path);
+    let full_path = if path.is_relative() {
+        std::env::current_exe()
+            .map_err(|e| e.to_string())?
+            .parent()
+            .map_or_else(|| path.to_path_buf(), |dir| dir.join(path))
+    } else {
+        path.to_path_buf()
+    };
+
+    std::fs::read(full_path).map_err(|e| e.to_string())
+}
+
+#[cfg(all(feature = "http", not(target_arch = "wasm32")))]
+fn load_url(
+    url: &str,
+    progress: &ProgressCounter,
+    cache: Option<&HttpCache>,
+) -> Result<Vec<u8>, String> {
+    if let Some(cache) = cache {
+        return crate::http_cache::load_cached_url(cache, url, progress, |etag| {
+            request_url(url, etag, progress)
+        });
+    }
+
+    request_url(url, None, progress)?
+        .map(|(data, _)| data)
+        .ok_or_else(|| format!("Invalid 'Not Modified' response for '{url}'"))
+}
+
+/// Returns the data and the etag, or `None` if the server returns 'Not Modified' for the etag
+#[cfg(all(feature = "http", not(target_arch = "wasm32")))]
+fn request_url(
+    url: &str,
+    etag: Option<&str>,
+    progress: &ProgressCounter,
+) -> Result<UrlResponse, String> {
+    use std::io::Read;
+
+    // avoid huge allocations if the server sends a wrong length
+    const MAX_PREALLOC: u64 = 64 * 1024 * 1024;
+
+    let mut req = ureq::get(url);
+    if let Some(etag) = etag {
+        req = req.set("If-None-Match", etag);
+    }
+
+    let res = req.call().map_err(|e| e.to_string())?;
+    if res.status() == 304 {
+        return Ok(None);
+    }
+
+    let etag = res.header("ETag").map(|etag| etag.to_string());
+    let total = res
+        .header("Content-Length")
+        .and_then(|len| len.parse::<u64>().ok());
+    if let Some(total) = total {
+        progress.set_total(total);
+    }
+
+    let capacity = total.unwrap_or(0).min(MAX_PREALLOC) as usize;
+    let mut data = Vec::with_capacity(capacity);
+    let mut reader = res.into_reader();
+    let mut buf = [0; 16 * 1024];
+    loop {
+        let read = reader.read(&mut buf).map_err(|e| e.to_string())?;
+        if read == 0 {
+            break;
+        }
+
+        data.extend_from_slice(&buf[..read]);
+        progress.set_loaded(data.len() as u64);
+    }
+
+    if total.is_none() {
+        progress.set_total(data.len() as u64);
+    }
+
+    Ok(Some((data, etag)))
+}
+
+#[cfg(all(not(feature = "http"), not(target_arch = "wasm32")))]
+fn load_url(url: &str, _progress: &ProgressCounter) -> Result<Vec<u8>, String> {
+    Err(format!(
+        "Cannot load '{url}', loading urls on native needs the 'http' feature."
+    ))
 }
 
 // The web logic to make the request is based on the crate 'platter' from Ryan Goldstein
 
+// Decoding jobs run on the main thread spread across frames, so big files
+// decoded at the same time don't block one frame. Moving them to web workers needs
+// a build with wasm threads (atomics and shared memory)
+#[cfg(target_arch = "wasm32")]
+const DECODE_JOBS_PER_FRAME: usize = 1;
+
+#[cfg(target_arch = "wasm32")]
+type DecodeJob = Box<dyn FnOnce() + Send>;
+
 #[cfg(target_arch = "wasm32")]
-pub(crate) struct FileLoader {}
+pub(crate) struct FileLoader {
+    jobs: Mutex<VecDeque<DecodeJob>>,
+}
 
 #[cfg(target_arch = "wasm32")]
 impl FileLoader {
     pub fn new() -> Result<Self, String> {
-        Ok(Self {})
+        Ok(Self {
+            jobs: Mutex::new(VecDeque::new()),
+        })
+    }
+
+    /// Queues the job to run during the next frames
+    pub fn spawn_decode<F>(&self, job: F)
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        self.jobs.lock().push_back(Box::new(job));
     }
 
-    pub fn load_file(&self, path: &str) -> impl Future<Output = Result<Vec<u8>, String>> {
-        ready(create_request(path)).and_then(|xhr| {
+    pub fn update(&mut self) {
+        let jobs = self.jobs.get_mut();
+        let len = DECODE_JOBS_PER_FRAME.min(jobs.len());
+        jobs.drain(..len).for_each(|job| job());
+    }
+
+    pub fn load_file(
+        &self,
+        path: &str,
+        progress: Arc<ProgressCounter>,
+    ) -> impl Future<Output = Result<Vec<u8>, String>> {
+        ready(create_request(path, progress)).and_then(|xhr| {
             let mut have_set_handlers = false;
             poll_fn(move |ctx| poll_request(&xhr, ctx, &mut have_set_handlers))
         })
@@ -80,8 +290,17 @@ unsafe impl Send for Xhr {}
 unsafe impl Sync for Xhr {}
 
 #[cfg(target_arch = "wasm32")]
-fn create_request(path: &str) -> Result<Xhr, String> {
+fn create_request(path: &str, progress: Arc<ProgressCounter>) -> Result<Xhr, String> {
     let xhr = XmlHttpRequest::new().map_err(err_format)?;
+    let on_progress = Closure::wrap(Box::new(move |evt: ProgressEvent| {
+        if evt.length_computable() {
+            progress.set_total(evt.total() as u64);
+        }
+        progress.set_loaded(evt.loaded() as u64);
+    }) as Box<dyn FnMut(ProgressEvent)>);
+    xhr.set_onprogress(Some(on_progress.as_ref().unchecked_ref()));
+    on_progress.forget();
+
     xhr.open("GET", path).map_err(err_format)?;
     xhr.set_response_type(XmlHttpRequestResponseType::Arraybuffer);
     xhr.send().map_err(err_format)?;
@@ -1,12 +1,23 @@
 use super::waker::*;
-use crate::events::{AssetLoad, AssetState};
+use crate::decode::DecodeTask;
+use crate::events::{AssetEvent, AssetLoad, AssetState, LoadProgress, LoadState, ProgressCounter};
+use crate::glob;
+use crate::integrity::AssetManifest;
 use crate::load_file::FileLoader;
+use crate::pack::AssetPack;
+use crate::policy::{loads_to_start, LoadPolicy, LoadPriority};
+use crate::sources::{AssetSource, AssetSources};
 use crate::update_assets;
+#[cfg(all(feature = "watch", not(target_arch = "wasm32")))]
+use crate::watch::AssetsWatch;
 use atomic_refcell::AtomicRefCell;
+use corelib::time::{Duration, Instant};
+use futures::channel::oneshot;
 use futures::task::{Context, Poll};
 use futures_util::future::BoxFuture;
 use once_cell::sync::Lazy;
 use parking_lot::Mutex;
+use rustc_hash::FxHashMap;
 use std::sync::Arc;
 use thunderdome::{Arena, Index};
 
@@ -23,6 +34,22 @@ pub(crate) struct AssetLoader {
     loading: Vec<LoadWrapper>,
     file_loader: FileLoader,
     states: Arena<AssetLoad>,
+    by_path: FxHashMap<String, AssetId>,
+    sources: AssetSources,
+    frame: u64,
+    budget: Option<usize>,
+    events: Vec<AssetEvent>,
+    pending_events: Vec<AssetEvent>,
+    manifest: Option<Vec<String>>,
+    integrity: Option<AssetManifest>,
+    policy: LoadPolicy,
+    // files waiting to be requested again after an error
+    retries: Vec<(AssetId, Instant)>,
+    // files waiting for a free slot to be requested
+    queue: Vec<AssetId>,
+    max_concurrent: Option<usize>,
+    #[cfg(all(feature = "watch", not(target_arch = "wasm32")))]
+    pub(crate) watch: AssetsWatch,
 }
 
 impl AssetLoader {
@@ -31,6 +58,20 @@ impl AssetLoader {
             loading: vec![],
             file_loader: FileLoader::new().unwrap(),
             states: Arena::default(),
+            by_path: FxHashMap::default(),
+            sources: AssetSources::default(),
+            frame: 0,
+            budget: None,
+            events: vec![],
+            pending_events: vec![],
+            manifest: None,
+            integrity: None,
+            policy: LoadPolicy::default(),
+            retries: vec![],
+            queue: vec![],
+            max_concurrent: None,
+            #[cfg(all(feature = "watch", not(target_arch = "wasm32")))]
+            watch: AssetsWatch::new(),
         }
     }
 
@@ -46,6 +87,14 @@ impl AssetLoader {
             .map_or(false, |s| matches!(s.state, AssetState::Loading))
     }
 
+    pub fn progress(&self, id: AssetId) -> Option<LoadProgress> {
+        self.states.get(id.0).map(|s| s.progress.progress())
+    }
+
+    pub fn state(&self, id: AssetId) -> Option<LoadState> {
+        self.states.get(id.0).map(|s| s.state.load_state())
+    }
+
     pub(crate) fn parse<T, F>(
         &mut self,
         id: AssetId,
@@ -60,61 +109,561 @@ impl AssetLoader {
             .get(id.0)
             .ok_or_else(|| "Invalid AssetID".to_string())?;
 
-        let (parsed, remove, res) = match &loaded.state {
+        let (parsed, release, res) = match &loaded.state {
             AssetState::Loading => (false, false, Ok(None)),
             AssetState::Loaded(d) => (true, !keep, Ok(Some(parser(&loaded.id, d.as_slice())?))),
-            AssetState::Err(err) => (false, !keep, Err(err.to_string())),
+            // the reference is kept so the file can be reloaded
+            AssetState::Err(err) => (false, false, Err(err.to_string())),
         };
 
         if parsed {
             log::info!("File '{}' parsed.", &loaded.id);
         }
 
-        if remove {
-            let _ = self.states.remove(id.0);
+        if release {
+            let _ = self.release(id);
         }
 
         res
     }
 
+    pub(crate) fn decode<T, F>(
+        &mut self,
+        id: AssetId,
+        decoder: F,
+        keep: bool,
+    ) -> Result<Option<DecodeTask<T>>, String>
+    where
+        T: Send + 'static,
+        F: FnOnce(&str, &[u8]) -> Result<T, String> + Send + 'static,
+    {
+        let load = self
+            .states
+            .get(id.0)
+            .ok_or_else(|| "Invalid AssetID".to_string())?;
+
+        match &load.state {
+            AssetState::Loading => return Ok(None),
+            AssetState::Err(err) => return Err(err.clone()),
+            AssetState::Loaded(_) => {}
+        }
+
+        // take the data if this was the last reference, otherwise clone it
+        let removed = if keep { None } else { self.release(id) };
+        let load = match removed {
+            Some(load) => load,
+            None => self
+                .states
+                .get(id.0)
+                .cloned()
+                .ok_or_else(|| "Invalid AssetID".to_string())?,
+        };
+
+        let AssetState::Loaded(data) = load.state else {
+            unreachable!()
+        };
+
+        log::info!("Decoding file '{}'", &load.id);
+        let (tx, rx) = oneshot::channel();
+        let path = load.id;
+        self.file_loader.spawn_decode(move || {
+            let _ = tx.send(decoder(&path, &data));
+        });
+
+        Ok(Some(DecodeTask::new(rx)))
+    }
+
     pub(crate) fn update(&mut self) {
+        self.frame += 1;
+        self.file_loader.update();
+        self.evict_unused();
+
+        // events sent since the last update are available during this frame
+        self.events.clear();
+        self.events.append(&mut self.pending_events);
+
+        #[cfg(all(feature = "watch", not(target_arch = "wasm32")))]
+        self.reload_changed();
+
+        self.start_retries();
+
+        let now = Instant::now();
         let mut needs_clean = true;
         self.loading.iter_mut().for_each(|loader| {
-            let asset_state = self.states.get_mut(loader.id.0).unwrap();
-            if let Some(state) = loader.try_load(&asset_state.id) {
-                asset_state.state = state;
+            let Some(asset_state) = self.states.get_mut(loader.id.0) else {
+                return;
+            };
+
+            let timeout = asset_state.policy.timeout();
+            let state = loader
+                .try_load(&asset_state.id)
+                .or_else(|| loader.check_timeout(&asset_state.id, timeout, now))
+                .map(|state| verify(self.integrity.as_ref(), &asset_state.id, state));
+
+            if let Some(state) = state {
                 needs_clean = true;
+
+                if let AssetState::Err(err) = &state {
+                    if asset_state.attempts < asset_state.policy.retries() {
+                        asset_state.attempts += 1;
+                        let delay = asset_state.policy.delay(asset_state.attempts);
+                        log::warn!(
+                            "Retrying '{}' in {:.2}s ({}/{}): {}",
+                            asset_state.id,
+                            delay.as_secs_f32(),
+                            asset_state.attempts,
+                            asset_state.policy.retries(),
+                            err
+                        );
+                        self.retries.push((loader.id, now + delay));
+                        return;
+                    }
+                }
+
+                asset_state.attempts = 0;
+                self.events.push(match &state {
+                    AssetState::Err(err) => AssetEvent::Failed(loader.id, err.clone()),
+                    _ => AssetEvent::Loaded(loader.id),
+                });
+                asset_state.state = state;
+
+                #[cfg(all(feature = "watch", not(target_arch = "wasm32")))]
+                if let Some(pos) = self.watch.reloading.iter().position(|id| *id == loader.id) {
+                    self.watch.reloading.swap_remove(pos);
+                    self.watch.reloaded.push(crate::events::AssetReloadedEvent {
+                        id: loader.id,
+                        path: asset_state.id.clone(),
+                    });
+                }
             }
         });
 
         if needs_clean {
             self.loading.retain(|loader| !loader.is_loaded());
         }
+
+        self.start_queued();
     }
 
     pub(crate) fn load(&mut self, file_path: &str) -> AssetId {
+        self.load_with_policy(file_path, self.policy)
+    }
+
+    pub(crate) fn load_with_policy(&mut self, file_path: &str, policy: LoadPolicy) -> AssetId {
+        // share the file if it's already loaded, loading or cached
+        if let Some(id) = self.by_path.get(file_path).copied() {
+            if let Some(load) = self.states.get_mut(id.0) {
+                if !matches!(load.state, AssetState::Err(_)) {
+                    load.refs += 1;
+                    load.last_used = self.frame;
+                    return id;
+                }
+            }
+        }
+
         log::info!("Loading file '{}'", file_path);
-        let fut = Box::pin(self.file_loader.load_file(file_path));
         let idx = self.states.insert(AssetLoad {
             id: file_path.to_string(),
             state: AssetState::Loading,
+            progress: Arc::new(ProgressCounter::default()),
+            refs: 1,
+            last_used: self.frame,
+            policy,
+            attempts: 0,
         });
         let id = AssetId(idx);
-        let wrapper = LoadWrapper::new(id, fut);
-        self.loading.push(wrapper);
+        self.by_path.insert(file_path.to_string(), id);
+        self.request(id);
+
+        #[cfg(all(feature = "watch", not(target_arch = "wasm32")))]
+        if self.sources.find(file_path).is_none() {
+            self.watch.released.remove(file_path);
+            self.watch.watch(file_path);
+        }
+
         id
     }
 
+    pub(crate) fn load_with_priority(
+        &mut self,
+        file_path: &str,
+        priority: LoadPriority,
+    ) -> AssetId {
+        self.load_with_policy(file_path, self.policy.with_priority(priority))
+    }
+
+    /// Policy used by the files loaded from now on
+    pub(crate) fn set_policy(&mut self, policy: LoadPolicy) {
+        self.policy = policy;
+    }
+
+    /// Max number of files requested at the same time, the rest wait in a queue sorted by priority
+    pub(crate) fn set_max_concurrent(&mut self, max: Option<usize>) {
+        self.max_concurrent = max.map(|max| max.max(1));
+        self.start_queued();
+    }
+
+    /// Queues the file to be requested, it starts now if there is a free slot
+    fn request(&mut self, id: AssetId) {
+        self.queue.push(id);
+        self.start_queued();
+    }
+
+    fn start_queued(&mut self) {
+        // drop the files removed while they were waiting
+        self.queue.retain(|id| self.states.contains(id.0));
+        if self.queue.is_empty() {
+            return;
+        }
+
+        let priorities = self
+            .queue
+            .iter()
+            .map(|id| {
+                self.states
+                    .get(id.0)
+                    .map_or(LoadPriority::Low, |load| load.policy.priority())
+            })
+            .collect::<Vec<_>>();
+        let active = self.loading.iter().filter(|l| !l.is_loaded()).count();
+        let start = loads_to_start(&priorities, active, self.max_concurrent);
+        if start.is_empty() {
+            return;
+        }
+
+        let ids = start.iter().map(|idx| self.queue[*idx]).collect::<Vec<_>>();
+        self.queue.retain(|id| !ids.contains(id));
+        for id in ids {
+            let Some(load) = self.states.get_mut(id.0) else {
+                continue;
+            };
+
+            load.progress = Arc::new(ProgressCounter::default());
+            let fut = load_source(
+                &self.file_loader,
+                &self.sources,
+                &load.id,
+                load.progress.clone(),
+            );
+            self.loading.push(LoadWrapper::new(id, fut));
+        }
+    }
+
+    /// Requests the file again, the id keeps being valid. Files loading are ignored
+    pub(crate) fn reload(&mut self, id: AssetId) -> Result<(), String> {
+        let load = self
+            .states
+            .get_mut(id.0)
+            .ok_or_else(|| "Invalid AssetID".to_string())?;
+
+        let retrying = self.retries.iter().any(|(rid, _)| *rid == id);
+        if matches!(load.state, AssetState::Loading) && !retrying {
+            return Ok(());
+        }
+
+        log::info!("Reloading file '{}'", load.id);
+        self.retries.retain(|(rid, _)| *rid != id);
+        load.state = AssetState::Loading;
+        load.attempts = 0;
+        self.request(id);
+        Ok(())
+    }
+
+    /// Requests again the files whose backoff time is over
+    fn start_retries(&mut self) {
+        if self.retries.is_empty() {
+            return;
+        }
+
+        let now = Instant::now();
+        let (ready, waiting) = self.retries.drain(..).partition(|(_, at)| *at <= now);
+        self.retries = waiting;
+
+        ready
+            .into_iter()
+            .for_each(|(id, _): (AssetId, Instant)| self.queue.push(id));
+        self.start_queued();
+    }
+
+    /// Loads again the files that changed on disk
+    #[cfg(all(feature = "watch", not(target_arch = "wasm32")))]
+    fn reload_changed(&mut self) {
+        // the reloaded events were available during the last frame, so whoever wanted
+        // the files took a reference already and the ones held for them can be released
+        let sent = self
+            .watch
+            .reloaded
+            .drain(..)
+            .map(|evt| evt.id)
+            .collect::<Vec<_>>();
+        let (done, holding) = std::mem::take(&mut self.watch.holding)
+            .into_iter()
+            .partition::<Vec<_>, _>(|id| sent.contains(id));
+        self.watch.holding = holding;
+        done.into_iter().for_each(|id| {
+            let _ = self.release(id);
+        });
+
+        let changed = self.watch.changed();
+        if changed.is_empty() {
+            return;
+        }
+
+        // files removed after being parsed are loaded again, holding a reference until
+        // the reloaded event is sent so they can be parsed again (see `AssetList`)
+        let released = changed
+            .iter()
+            .filter(|path| self.watch.released.contains(*path) && !self.by_path.contains_key(*path))
+            .cloned()
+            .collect::<Vec<_>>();
+        for path in released {
+            log::info!("Reloading file '{path}'");
+            let id = self.load(&path);
+            self.watch.reloading.push(id);
+            self.watch.holding.push(id);
+        }
+
+        for (idx, load) in self.states.iter_mut() {
+            let id = AssetId(idx);
+            let is_changed = changed.contains(&load.id);
+            if !is_changed || self.watch.reloading.contains(&id) {
+                continue;
+            }
+
+            log::info!("Reloading file '{}'", load.id);
+            load.state = AssetState::Loading;
+            self.queue.push(id);
+            self.watch.reloading.push(id);
+        }
+
+        self.start_queued();
+    }
+
+    pub(crate) fn mount_pack(&mut self, pack: AssetPack) {
+        log::info!("Mounting asset pack with {} files", pack.len());
+        self.sources.mount_pack(pack);
+    }
+
+    pub(crate) fn unmount_packs(&mut self) {
+        self.sources.unmount_packs();
+    }
+
+    #[cfg(all(feature = "http", not(target_arch = "wasm32")))]
+    pub(crate) fn set_http_cache(&mut self, cache: Option<crate::http_cache::HttpCache>) {
+        if let Some(cache) = &cache {
+            log::info!("Caching url assets in '{}'", cache.dir().display());
+        }
+
+        self.file_loader.set_cache(cache);
+    }
+
+    pub(crate) fn embed(&mut self, path: &str, data: &'static [u8]) {
+        self.sources.embed(path, data);
+    }
+
+    pub(crate) fn register_source(&mut self, scheme: &str, source: Arc<dyn AssetSource>) {
+        log::info!("Registering asset source '{scheme}://'");
+        self.sources.register(scheme, source);
+    }
+
+    pub(crate) fn unregister_source(&mut self, scheme: &str) -> bool {
+        self.sources.unregister(scheme)
+    }
+
+    /// Removes a reference to the file, once there are no references the data is
+    /// removed or kept in the cache if there is a budget. Returns the removed data
+    pub(crate) fn release(&mut self, id: AssetId) -> Option<AssetLoad> {
+        let load = self.states.get_mut(id.0)?;
+        load.refs = load.refs.saturating_sub(1);
+        load.last_used = self.frame;
+
+        if load.refs > 0 {
+            return None;
+        }
+
+        let cache = self.budget.is_some() && matches!(load.state, AssetState::Loaded(_));
+        if cache {
+            return None;
+        }
+
+        self.remove(id)
+    }
+
+    /// Max bytes used by loaded files, the files without references
+    /// are kept until the budget is exceeded, then the least recently used is removed.
+    pub(crate) fn set_budget(&mut self, budget: Option<usize>) {
+        self.budget = budget;
+        if budget.is_none() {
+            let unused = self
+                .states
+                .iter()
+                .filter(|(_, load)| load.refs == 0)
+                .map(|(idx, _)| AssetId(idx))
+                .collect::<Vec<_>>();
+            unused.into_iter().for_each(|id| {
+                let _ = self.remove(id);
+            });
+        }
+    }
+
+    /// Bytes used by the data of the loaded files
+    pub(crate) fn loaded_bytes(&self) -> usize {
+        self.states
+            .iter()
+            .map(|(_, load)| match &load.state {
+                AssetState::Loaded(data) => data.len(),
+                _ => 0,
+            })
+            .sum()
+    }
+
+    fn evict_unused(&mut self) {
+        let Some(budget) = self.budget else {
+            return;
+        };
+
+        let mut bytes = self.loaded_bytes();
+        if bytes <= budget {
+            return;
+        }
+
+        let mut unused = self
+            .states
+            .iter()
+            .filter_map(|(idx, load)| match &load.state {
+                AssetState::Loaded(data) if load.refs == 0 => {
+                    Some((AssetId(idx), load.last_used, data.len()))
+                }
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        unused.sort_by_key(|(_, last_used, _)| *last_used);
+
+        for (id, _, len) in unused {
+            if bytes <= budget {
+                break;
+            }
+
+            if let Some(load) = self.remove(id) {
+                log::info!("File '{}' evicted from the assets cache", load.id);
+            }
+            bytes -= len;
+        }
+    }
+
+    fn remove(&mut self, id: AssetId) -> Option<AssetLoad> {
+        let load = self.states.remove(id.0)?;
+        if self.by_path.get(&load.id) == Some(&id) {
+            self.by_path.remove(&load.id);
+        }
+        self.loading.retain(|loader| loader.id != id);
+        self.retries.retain(|(rid, _)| *rid != id);
+        self.pending_events.push(AssetEvent::Removed(id));
+
+        // the file is loaded again if it changes, see `reload_changed`
+        #[cfg(all(feature = "watch", not(target_arch = "wasm32")))]
+        self.watch.released.insert(load.id.clone());
+
+        Some(load)
+    }
+
+    pub(crate) fn set_manifest(&mut self, manifest: Option<Vec<String>>) {
+        self.manifest = manifest;
+    }
+
+    pub(crate) fn set_integrity(&mut self, manifest: Option<AssetManifest>) {
+        self.integrity = manifest;
+    }
+
+    /// Returns the files that match the pattern, from the manifest if there is one or from
+    /// the disk if not, plus the files inside of the mounted packs and the embedded ones
+    pub(crate) fn expand_glob(&self, pattern: &str) -> Result<Vec<String>, String> {
+        let mut paths = match &self.manifest {
+            Some(manifest) => glob::filter(pattern, manifest.iter().map(|p| p.as_str())),
+
+            #[cfg(not(target_arch = "wasm32"))]
+            None => glob::walk(pattern)?,
+
+            #[cfg(target_arch = "wasm32")]
+            None if self.sources.paths().next().is_none() => {
+                return Err(format!(
+                    "Cannot list the files for '{pattern}' without an assets manifest"
+                ))
+            }
+
+            #[cfg(target_arch = "wasm32")]
+            None => vec![],
+        };
+
+        paths.extend(glob::filter(pattern, self.sources.paths()));
+        paths.sort();
+        paths.dedup();
+        Ok(paths)
+    }
+
+    pub(crate) fn events(&self) -> &[AssetEvent] {
+        &self.events
+    }
+
     pub(crate) fn clear(&mut self) {
+        let removed = self
+            .states
+            .iter()
+            .map(|(idx, _)| AssetEvent::Removed(AssetId(idx)));
+        self.pending_events.extend(removed);
         self.states.clear();
+        self.by_path.clear();
+        self.loading.clear();
+        self.retries.clear();
+        self.queue.clear();
     }
 }
 
 type InnerBoxFuture = BoxFuture<'static, Result<Vec<u8>, String>>;
 
+/// Loaded files that don't match the integrity manifest fail so they can be retried
+fn verify(integrity: Option<&AssetManifest>, path: &str, state: AssetState) -> AssetState {
+    let (Some(integrity), AssetState::Loaded(data)) = (integrity, &state) else {
+        return state;
+    };
+
+    match integrity.verify(path, data) {
+        Ok(()) => state,
+        Err(err) => AssetState::Err(err),
+    }
+}
+
+// custom sources and packs are loaded on the worker, otherwise it's read from disk or network
+fn load_source(
+    file_loader: &FileLoader,
+    sources: &AssetSources,
+    path: &str,
+    progress: Arc<ProgressCounter>,
+) -> InnerBoxFuture {
+    let Some((source, path)) = sources.find(path) else {
+        return Box::pin(file_loader.load_file(path, progress));
+    };
+
+    let (tx, rx) = oneshot::channel();
+    file_loader.spawn_decode(move || {
+        let res = source.load(&path);
+        if let Ok(data) = &res {
+            progress.set_total(data.len() as u64);
+            progress.set_loaded(data.len() as u64);
+        }
+        let _ = tx.send(res);
+    });
+
+    Box::pin(async move {
+        rx.await
+            .unwrap_or_else(|_| Err("The channel was dropped.".to_string()))
+    })
+}
+
 struct LoadWrapper {
     id: AssetId,
     fut: Arc<Mutex<InnerBoxFuture>>,
+    started: Instant,
     loaded: bool,
 }
 
@@ -123,6 +672,7 @@ impl LoadWrapper {
         Self {
             id,
             fut: Arc::new(Mutex::new(fut)),
+            started: Instant::now(),
             loaded: false,
         }
     }
@@ -153,6 +703,28 @@ impl LoadWrapper {
         }
     }
 
+    /// Fails the load if it's taking longer than the timeout, the task is ignored from now on
+    pub fn check_timeout(
+        &mut self,
+        id: &str,
+        timeout: Option<Duration>,
+        now: Instant,
+    ) -> Option<AssetState> {
+        let timeout = timeout?;
+        if self.loaded || now.duration_since(self.started) < timeout {
+            return None;
+        }
+
+        self.loaded = true;
+        let err = format!(
+            "Cannot load file: {}: timeout after {:.2}s",
+            id,
+            timeout.as_secs_f32()
+        );
+        log::warn!("{}", err);
+        Some(AssetState::Err(err))
+    }
+
     pub fn is_loaded(&self) -> bool {
         self.loaded
     }
@@ -0,0 +1,304 @@
+use rustc_hash::FxHashMap;
+use std::sync::Arc;
+
+const MAGIC: &[u8; 4] = b"RKPK";
+const VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Copy)]
+struct PackEntry {
+    offset: usize,
+    len: usize,
+    compressed: bool,
+}
+
+#[derive(Debug)]
+struct PackInner {
+    data: Vec<u8>,
+    entries: FxHashMap<String, PackEntry>,
+}
+
+/// A bundle of files in a single archive, created with `AssetPackWriter`.
+/// Once mounted with `mount_pack` the files inside are loaded by their original path.
+#[derive(Debug, Clone)]
+pub struct AssetPack {
+    inner: Arc<PackInner>,
+}
+
+impl AssetPack {
+    /// Reads the pack index, the files are decompressed when they are read
+    pub fn from_bytes(data: Vec<u8>) -> Result<Self, String> {
+        let mut reader = Reader::new(&data);
+        if reader.bytes(MAGIC.len())? != MAGIC {
+            return Err("Invalid asset pack: wrong magic number".to_string());
+        }
+
+        let version = reader.u32()?;
+        if version != VERSION {
+            return Err(format!("Unsupported asset pack version {version}"));
+        }
+
+        let count = reader.u32()? as usize;
+        let mut entries = FxHashMap::default();
+        for _ in 0..count {
+            let path_len = reader.u32()? as usize;
+            let path = std::str::from_utf8(reader.bytes(path_len)?)
+                .map_err(|e| format!("Invalid asset pack path: {e}"))?
+                .to_string();
+            let offset = reader.u64()? as usize;
+            let len = reader.u64()? as usize;
+            let compressed = reader.u8()? != 0;
+
+            let in_bounds = offset.checked_add(len).is_some_and(|end| end <= data.len());
+            if !in_bounds {
+                return Err(format!("Invalid asset pack entry '{path}': out of bounds"));
+            }
+
+            entries.insert(
+                path,
+                PackEntry {
+                    offset,
+                    len,
+                    compressed,
+                },
+            );
+        }
+
+        Ok(Self {
+            inner: Arc::new(PackInner { data, entries }),
+        })
+    }
+
+    pub fn contains(&self, path: &str) -> bool {
+        self.inner.entries.contains_key(normalize_path(path))
+    }
+
+    /// Returns the paths of the files inside the pack
+    pub fn paths(&self) -> impl Iterator<Item = &str> {
+        self.inner.entries.keys().map(|p| p.as_str())
+    }
+
+    pub fn len(&self) -> usize {
+        self.inner.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.inner.entries.is_empty()
+    }
+
+    /// Returns the content of the file, decompressing it if needed
+    pub fn read(&self, path: &str) -> Result<Vec<u8>, String> {
+        let entry = self
+            .inner
+            .entries
+            .get(normalize_path(path))
+            .ok_or_else(|| format!("File '{path}' not found in the asset pack"))?;
+
+        let data = &self.inner.data[entry.offset..entry.offset + entry.len];
+        if entry.compressed {
+            miniz_oxide::inflate::decompress_to_vec(data)
+                .map_err(|e| format!("Cannot decompress '{path}': {e:?}"))
+        } else {
+            Ok(data.to_vec())
+        }
+    }
+}
+
+/// Creates an `AssetPack` archive from files in memory or from a directory
+pub struct AssetPackWriter {
+    files: Vec<(String, Vec<u8>)>,
+    level: u8,
+}
+
+impl Default for AssetPackWriter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AssetPackWriter {
+    pub fn new() -> Self {
+        Self {
+            files: vec![],
+            level: 6,
+        }
+    }
+
+    /// Compression level from 0 (none) to 10, files that don't get smaller are stored as is
+    pub fn with_compression_level(mut self, level: u8) -> Self {
+        self.level = level.min(10);
+        self
+    }
+
+    /// Adds a file that will be loaded using `path`
+    pub fn add(&mut self, path: &str, data: Vec<u8>) {
+        let path = normalize_path(path).to_string();
+        self.files.retain(|(p, _)| *p != path);
+        self.files.push((path, data));
+    }
+
+    /// Adds all the files inside of the directory (recursively), they
+    /// will be loaded using the same path used to read them
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn add_dir(&mut self, dir: impl AsRef<std::path::Path>) -> Result<(), String> {
+        let dir = dir.as_ref();
+        let entries = std::fs::read_dir(dir)
+            .map_err(|e| format!("Cannot read directory '{}': {e}", dir.display()))?;
+
+        for entry in entries {
+            let path = entry.map_err(|e| e.to_string())?.path();
+            if path.is_dir() {
+                self.add_dir(&path)?;
+                continue;
+            }
+
+            let data = std::fs::read(&path)
+                .map_err(|e| format!("Cannot read file '{}': {e}", path.display()))?;
+            let name = path.to_string_lossy().replace('\\', "/");
+            self.add(&name, data);
+        }
+
+        Ok(())
+    }
+
+    pub fn len(&self) -> usize {
+        self.files.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+
+    /// Returns the bytes of the pack
+    pub fn write(&self) -> Result<Vec<u8>, String> {
+        let count = u32::try_from(self.files.len())
+            .map_err(|_| "Too many files for an asset pack".to_string())?;
+
+        let blobs = self
+            .files
+            .iter()
+            .map(|(_, data)| {
+                let compressed = miniz_oxide::deflate::compress_to_vec(data, self.level);
+                if self.level > 0 && compressed.len() < data.len() {
+                    (compressed, true)
+                } else {
+                    (data.clone(), false)
+                }
+            })
+            .collect::<Vec<_>>();
+
+        // magic + version + count, then path len + path + offset + len + compressed
+        let index_len = self.files.iter().fold(MAGIC.len() + 8, |len, (path, _)| {
+            len + 4 + path.len() + 8 + 8 + 1
+        });
+
+        let data_len: usize = blobs.iter().map(|(blob, _)| blob.len()).sum();
+        let mut out = Vec::with_capacity(index_len + data_len);
+        out.extend_from_slice(MAGIC);
+        out.extend_from_slice(&VERSION.to_le_bytes());
+        out.extend_from_slice(&count.to_le_bytes());
+
+        let mut offset = index_len;
+        for ((path, _), (blob, compressed)) in self.files.iter().zip(&blobs) {
+            out.extend_from_slice(&(path.len() as u32).to_le_bytes());
+            out.extend_from_slice(path.as_bytes());
+            out.extend_from_slice(&(offset as u64).to_le_bytes());
+            out.extend_from_slice(&(blob.len() as u64).to_le_bytes());
+            out.push(*compressed as u8);
+            offset += blob.len();
+        }
+
+        debug_assert_eq!(out.len(), index_len);
+        blobs
+            .iter()
+            .for_each(|(blob, _)| out.extend_from_slice(blob));
+
+        Ok(out)
+    }
+}
+
+// packs store paths without the leading './' to match both forms
+fn normalize_path(path: &str) -> &str {
+    path.trim_start_matches("./")
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self { data, pos: 0 }
+    }
+
+    fn bytes(&mut self, len: usize) -> Result<&'a [u8], String> {
+        let end = self
+            .pos
+            .checked_add(len)
+            .filter(|end| *end <= self.data.len())
+            .ok_or_else(|| "Invalid asset pack: unexpected end of data".to_string())?;
+        let bytes = &self.data[self.pos..end];
+        self.pos = end;
+        Ok(bytes)
+    }
+
+    fn u8(&mut self) -> Result<u8, String> {
+        Ok(self.bytes(1)?[0])
+    }
+
+    fn u32(&mut self) -> Result<u32, String> {
+        Ok(u32::from_le_bytes(self.bytes(4)?.try_into().unwrap()))
+    }
+
+    fn u64(&mut self) -> Result<u64, String> {
+        Ok(u64::from_le_bytes(self.bytes(8)?.try_into().unwrap()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_roundtrip() {
+        let mut writer = AssetPackWriter::new();
+        writer.add("./assets/a.txt", b"hello".to_vec());
+        writer.add("assets/b.txt", vec![7; 1024]);
+
+        let pack = AssetPack::from_bytes(writer.write().unwrap()).unwrap();
+        assert_eq!(pack.len(), 2);
+        assert!(pack.contains("assets/a.txt"));
+        assert!(pack.contains("./assets/b.txt"));
+        assert_eq!(pack.read("assets/a.txt").unwrap(), b"hello");
+        assert_eq!(pack.read("assets/b.txt").unwrap(), vec![7; 1024]);
+    }
+
+    #[test]
+    fn test_compression() {
+        let mut writer = AssetPackWriter::new();
+        writer.add("zeros", vec![0; 4096]);
+        let bytes = writer.write().unwrap();
+        assert!(bytes.len() < 4096);
+
+        let pack = AssetPack::from_bytes(bytes).unwrap();
+        assert_eq!(pack.read("zeros").unwrap(), vec![0; 4096]);
+    }
+
+    #[test]
+    fn test_missing_file() {
+        let pack = AssetPack::from_bytes(AssetPackWriter::new().write().unwrap()).unwrap();
+        assert!(pack.is_empty());
+        assert!(pack.read("nope").is_err());
+    }
+
+    #[test]
+    fn test_invalid_data() {
+        assert!(AssetPack::from_bytes(b"nope".to_vec()).is_err());
+
+        let mut writer = AssetPackWriter::new();
+        writer.add("a", b"hello".to_vec());
+        let mut bytes = writer.write().unwrap();
+        bytes.truncate(bytes.len() - 2);
+        assert!(AssetPack::from_bytes(bytes).is_err());
+    }
+}
@@ -0,0 +1,135 @@
+use corelib::time::Duration;
+
+/// Order used to request the files when there is a limit of concurrent loads,
+/// see `set_max_concurrent_loads`. Files with the same priority keep the request order
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum LoadPriority {
+    Low,
+    #[default]
+    Normal,
+    High,
+    /// Requested right away even if the limit of concurrent loads is reached
+    Critical,
+}
+
+/// How a file is loaded, by default it fails at the first error, it never times
+/// out and it has `Normal` priority. See `set_load_policy` and `load_asset_with_policy`
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct LoadPolicy {
+    retries: u32,
+    backoff: Duration,
+    timeout: Option<Duration>,
+    priority: LoadPriority,
+}
+
+impl Default for LoadPolicy {
+    fn default() -> Self {
+        Self {
+            retries: 0,
+            backoff: Duration::from_millis(500),
+            timeout: None,
+            priority: LoadPriority::Normal,
+        }
+    }
+}
+
+impl LoadPolicy {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Times the file is requested again after an error or a timeout
+    pub fn with_retries(mut self, retries: u32) -> Self {
+        self.retries = retries;
+        self
+    }
+
+    /// Time to wait before the first retry, it doubles with each retry
+    pub fn with_backoff(mut self, backoff: Duration) -> Self {
+        self.backoff = backoff;
+        self
+    }
+
+    /// Fails the attempt if the file takes longer than this to load
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    pub fn with_priority(mut self, priority: LoadPriority) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    pub fn retries(&self) -> u32 {
+        self.retries
+    }
+
+    pub fn backoff(&self) -> Duration {
+        self.backoff
+    }
+
+    pub fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
+    pub fn priority(&self) -> LoadPriority {
+        self.priority
+    }
+
+    /// Time to wait before the retry number `retry` (starting at 1)
+    pub(crate) fn delay(&self, retry: u32) -> Duration {
+        let factor = 2u32.saturating_pow(retry.saturating_sub(1).min(16));
+        self.backoff.saturating_mul(factor)
+    }
+}
+
+/// Indices of the queued loads that can start now, sorted by priority.
+/// `active` is the number of loads in progress and `max` the limit.
+pub(crate) fn loads_to_start(
+    queue: &[LoadPriority],
+    active: usize,
+    max: Option<usize>,
+) -> Vec<usize> {
+    let mut order = (0..queue.len()).collect::<Vec<_>>();
+    order.sort_by_key(|idx| std::cmp::Reverse(queue[*idx]));
+
+    let mut active = active;
+    order
+        .into_iter()
+        .filter(|idx| {
+            let free = max.is_none_or(|max| active < max);
+            let start = free || queue[*idx] == LoadPriority::Critical;
+            if start {
+                active += 1;
+            }
+            start
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_loads_to_start() {
+        use LoadPriority::*;
+        let queue = [Low, High, Normal, Critical, High];
+        assert_eq!(loads_to_start(&queue, 0, None), vec![3, 1, 4, 2, 0]);
+        assert_eq!(loads_to_start(&queue, 0, Some(2)), vec![3, 1]);
+        assert_eq!(loads_to_start(&queue, 1, Some(3)), vec![3, 1]);
+        assert_eq!(loads_to_start(&queue, 5, Some(2)), vec![3]);
+    }
+
+    #[test]
+    fn test_delay() {
+        let policy = LoadPolicy::new()
+            .with_retries(3)
+            .with_backoff(Duration::from_millis(100));
+        assert_eq!(policy.delay(1), Duration::from_millis(100));
+        assert_eq!(policy.delay(2), Duration::from_millis(200));
+        assert_eq!(policy.delay(3), Duration::from_millis(400));
+        assert_eq!(policy.delay(100), Duration::from_millis(100 * 65536));
+    }
+}
@@ -0,0 +1,148 @@
+use crate::pack::AssetPack;
+use rustc_hash::FxHashMap;
+use std::sync::Arc;
+
+/// Custom backend to load files (memory, archives, platform storage, etc...),
+/// registered with `register_asset_source` to load paths like `scheme://path/file.png`.
+/// `load` runs on the loader's thread pool.
+/// Web: it runs on the main thread spread across frames.
+pub trait AssetSource: Send + Sync + 'static {
+    /// Returns the content of the file, `path` doesn't include the scheme
+    fn load(&self, path: &str) -> Result<Vec<u8>, String>;
+}
+
+impl AssetSource for AssetPack {
+    fn load(&self, path: &str) -> Result<Vec<u8>, String> {
+        self.read(path)
+    }
+}
+
+/// Source that serves files stored in memory
+#[derive(Default)]
+pub struct MemorySource {
+    files: FxHashMap<String, Vec<u8>>,
+}
+
+impl MemorySource {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_file(mut self, path: &str, data: Vec<u8>) -> Self {
+        self.files.insert(path.to_string(), data);
+        self
+    }
+}
+
+impl AssetSource for MemorySource {
+    fn load(&self, path: &str) -> Result<Vec<u8>, String> {
+        self.files
+            .get(path)
+            .cloned()
+            .ok_or_else(|| format!("File '{path}' not found in memory source"))
+    }
+}
+
+/// File embedded in the binary with `embed_asset`
+struct EmbeddedFile(&'static [u8]);
+
+impl AssetSource for EmbeddedFile {
+    fn load(&self, _path: &str) -> Result<Vec<u8>, String> {
+        Ok(self.0.to_vec())
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct AssetSources {
+    custom: FxHashMap<String, Arc<dyn AssetSource>>,
+    packs: Vec<AssetPack>,
+    embedded: FxHashMap<String, &'static [u8]>,
+}
+
+impl AssetSources {
+    pub fn register(&mut self, scheme: &str, source: Arc<dyn AssetSource>) {
+        self.custom.insert(scheme.to_string(), source);
+    }
+
+    pub fn unregister(&mut self, scheme: &str) -> bool {
+        self.custom.remove(scheme).is_some()
+    }
+
+    pub fn mount_pack(&mut self, pack: AssetPack) {
+        self.packs.push(pack);
+    }
+
+    pub fn unmount_packs(&mut self) {
+        self.packs.clear();
+    }
+
+    pub fn embed(&mut self, path: &str, data: &'static [u8]) {
+        self.embedded.insert(path.to_string(), data);
+    }
+
+    /// Paths of the files inside of the mounted packs and the embedded files
+    pub fn paths(&self) -> impl Iterator<Item = &str> {
+        self.packs
+            .iter()
+            .flat_map(|pack| pack.paths())
+            .chain(self.embedded.keys().map(|path| path.as_str()))
+    }
+
+    /// Returns the source and the path inside of it, `None` means that
+    /// the file must be read from disk or network
+    pub fn find(&self, path: &str) -> Option<(Arc<dyn AssetSource>, String)> {
+        let custom = path.split_once("://").and_then(|(scheme, inner)| {
+            let source = self.custom.get(scheme)?;
+            Some((source.clone(), inner.to_string()))
+        });
+
+        if custom.is_some() {
+            return custom;
+        }
+
+        // the last mounted pack with the file has priority
+        let pack = self.packs.iter().rev().find(|pack| pack.contains(path));
+        if let Some(pack) = pack {
+            let source: Arc<dyn AssetSource> = Arc::new(pack.clone());
+            return Some((source, path.to_string()));
+        }
+
+        self.embedded.get(path).map(|data| {
+            let source: Arc<dyn AssetSource> = Arc::new(EmbeddedFile(data));
+            (source, path.to_string())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_by_scheme() {
+        let mut sources = AssetSources::default();
+        let memory = MemorySource::new().with_file("hero.png", vec![1, 2, 3]);
+        sources.register("mem", Arc::new(memory));
+
+        let (source, path) = sources.find("mem://hero.png").unwrap();
+        assert_eq!(path, "hero.png");
+        assert_eq!(source.load(&path).unwrap(), vec![1, 2, 3]);
+
+        assert!(sources.find("hero.png").is_none());
+        assert!(sources.find("https://hero.png").is_none());
+
+        assert!(sources.unregister("mem"));
+        assert!(sources.find("mem://hero.png").is_none());
+    }
+
+    #[test]
+    fn test_find_embedded() {
+        let mut sources = AssetSources::default();
+        sources.embed("ui/button.png", &[4, 5]);
+
+        let (source, path) = sources.find("ui/button.png").unwrap();
+        assert_eq!(source.load(&path).unwrap(), vec![4, 5]);
+        assert_eq!(sources.paths().collect::<Vec<_>>(), vec!["ui/button.png"]);
+        assert!(sources.find("ui/other.png").is_none());
+    }
+}
@@ -0,0 +1,156 @@
+use crate::list::{AssetList, AssetMap};
+
+type LoadedFn<T> = dyn FnOnce(&AssetMap) -> Result<T, String>;
+
+struct TrackedList<T> {
+    name: String,
+    list: AssetList,
+    on_loaded: Option<Box<LoadedFn<T>>>,
+    done: bool,
+}
+
+/// Tracks named `AssetList`s and runs a callback once when each of them is loaded,
+/// the values returned by the callbacks (e.g. the next screen) are given by `update`
+///
+/// ```rust,ignore
+/// let mut tracker = LoadingTracker::new()
+///     .with_list("level", AssetList::from_glob("assets/level1")?)
+///     .with_on_loaded("level", |map| Ok(State::Playing(Level::new(map)?)));
+///
+/// // each frame
+/// if let Some(state) = tracker.update()?.pop() {
+///     *s = state;
+/// }
+/// ```
+pub struct LoadingTracker<T> {
+    lists: Vec<TrackedList<T>>,
+}
+
+impl<T> Default for LoadingTracker<T> {
+    fn default() -> Self {
+        Self { lists: vec![] }
+    }
+}
+
+impl<T> LoadingTracker<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_list(mut self, name: &str, list: AssetList) -> Self {
+        self.add_list(name, list);
+        self
+    }
+
+    pub fn with_on_loaded<F>(mut self, name: &str, cb: F) -> Self
+    where
+        F: FnOnce(&AssetMap) -> Result<T, String> + 'static,
+    {
+        self.on_loaded(name, cb);
+        self
+    }
+
+    /// Tracks the list, replacing any other list with the same name
+    pub fn add_list(&mut self, name: &str, list: AssetList) {
+        self.lists.retain(|tracked| tracked.name != name);
+        self.lists.push(TrackedList {
+            name: name.to_string(),
+            list,
+            on_loaded: None,
+            done: false,
+        });
+    }
+
+    /// Sets the callback called once the list is loaded and parsed. If the list
+    /// is already loaded it will be called on the next `update`
+    pub fn on_loaded<F>(&mut self, name: &str, cb: F)
+    where
+        F: FnOnce(&AssetMap) -> Result<T, String> + 'static,
+    {
+        match self.lists.iter_mut().find(|tracked| tracked.name == name) {
+            Some(tracked) => {
+                tracked.on_loaded = Some(Box::new(cb));
+                tracked.done = false;
+            }
+            None => log::warn!("Cannot set 'on_loaded' for the unknown asset list '{name}'"),
+        }
+    }
+
+    /// Parses the lists, returning the values of the callbacks of the lists that
+    /// finished loading on this call
+    pub fn update(&mut self) -> Result<Vec<T>, String> {
+        let mut values = vec![];
+        for tracked in self.lists.iter_mut().filter(|tracked| !tracked.done) {
+            let mut cb = tracked.on_loaded.take();
+            let value = tracked
+                .list
+                .parse(|map| cb.take().map(|cb| cb(map)).transpose())?;
+            match value {
+                Some(value) => {
+                    tracked.done = true;
+                    values.extend(value);
+                }
+                // not loaded yet, keep the callback for the next update
+                None => tracked.on_loaded = cb,
+            }
+        }
+
+        Ok(values)
+    }
+
+    /// Progress of the list from 0.0 to 1.0
+    pub fn progress(&self, name: &str) -> Option<f32> {
+        self.list(name).map(|list| list.progress())
+    }
+
+    /// Average progress of all the lists, 1.0 if there are no lists
+    pub fn total_progress(&self) -> f32 {
+        if self.lists.is_empty() {
+            return 1.0;
+        }
+
+        let sum: f32 = self
+            .lists
+            .iter()
+            .map(|tracked| tracked.list.progress())
+            .sum();
+        sum / self.lists.len() as f32
+    }
+
+    /// True if the list is loaded and its callback was called
+    pub fn is_done(&self, name: &str) -> bool {
+        self.lists
+            .iter()
+            .any(|tracked| tracked.name == name && tracked.done)
+    }
+
+    /// True if all the lists are loaded and their callbacks were called
+    pub fn all_done(&self) -> bool {
+        self.lists.iter().all(|tracked| tracked.done)
+    }
+
+    pub fn list(&self, name: &str) -> Option<&AssetList> {
+        self.lists
+            .iter()
+            .find(|tracked| tracked.name == name)
+            .map(|tracked| &tracked.list)
+    }
+
+    pub fn list_mut(&mut self, name: &str) -> Option<&mut AssetList> {
+        self.lists
+            .iter_mut()
+            .find(|tracked| tracked.name == name)
+            .map(|tracked| &mut tracked.list)
+    }
+
+    /// Stops tracking the list and returns it
+    pub fn remove(&mut self, name: &str) -> Option<AssetList> {
+        let idx = self.lists.iter().position(|tracked| tracked.name == name)?;
+        Some(self.lists.remove(idx).list)
+    }
+
+    /// Names of the tracked lists
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.lists.iter().map(|tracked| tracked.name.as_str())
+    }
+}
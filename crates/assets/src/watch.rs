@@ -0,0 +1,135 @@
+use crate::events::AssetReloadedEvent;
+use crate::load_file::is_url;
+use crate::AssetId;
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use parking_lot::Mutex;
+use rustc_hash::{FxHashMap, FxHashSet};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{channel, Receiver};
+use std::time::{Duration, Instant};
+
+// editors tend to write files in several steps, so wait a bit after the last change
+const DEBOUNCE: Duration = Duration::from_millis(100);
+
+/// Watches the directories of the loaded files and reports the files that changed
+pub(crate) struct FileWatcher {
+    watcher: RecommendedWatcher,
+    // the assets state lives in a static, so the receiver needs to be Sync
+    rx: Mutex<Receiver<PathBuf>>,
+    dirs: FxHashSet<PathBuf>,
+    files: FxHashMap<PathBuf, String>,
+    pending: FxHashMap<PathBuf, Instant>,
+}
+
+impl FileWatcher {
+    pub fn new() -> Result<Self, String> {
+        let (tx, rx) = channel();
+        let watcher =
+            notify::recommended_watcher(move |res: notify::Result<notify::Event>| match res {
+                Ok(evt) => {
+                    if matches!(evt.kind, EventKind::Create(_) | EventKind::Modify(_)) {
+                        evt.paths.into_iter().for_each(|p| {
+                            let _ = tx.send(p);
+                        });
+                    }
+                }
+                Err(e) => log::warn!("Assets watcher error: {e}"),
+            })
+            .map_err(|e| format!("Cannot create the assets watcher: {e}"))?;
+
+        Ok(Self {
+            watcher,
+            rx: Mutex::new(rx),
+            dirs: FxHashSet::default(),
+            files: FxHashMap::default(),
+            pending: FxHashMap::default(),
+        })
+    }
+
+    /// Starts watching the file, `path` is the one used to load it
+    pub fn watch(&mut self, path: &str) -> Result<(), String> {
+        let full_path = Path::new(path)
+            .canonicalize()
+            .map_err(|e| format!("Cannot watch file '{path}': {e}"))?;
+
+        // watch the parent because some editors replace the file on save
+        if let Some(dir) = full_path.parent() {
+            if !self.dirs.contains(dir) {
+                self.watcher
+                    .watch(dir, RecursiveMode::NonRecursive)
+                    .map_err(|e| format!("Cannot watch file '{path}': {e}"))?;
+                self.dirs.insert(dir.to_path_buf());
+            }
+        }
+
+        self.files.insert(full_path, path.to_string());
+        Ok(())
+    }
+
+    /// Returns the paths (as they were loaded) of the files that changed
+    pub fn changed(&mut self) -> Vec<String> {
+        let now = Instant::now();
+        while let Ok(path) = self.rx.get_mut().try_recv() {
+            if self.files.contains_key(&path) {
+                self.pending.insert(path, now);
+            }
+        }
+
+        let mut changed = vec![];
+        let files = &self.files;
+        self.pending.retain(|path, time| {
+            let ready = now.duration_since(*time) >= DEBOUNCE;
+            if ready {
+                if let Some(id) = files.get(path) {
+                    changed.push(id.clone());
+                }
+            }
+            !ready
+        });
+        changed
+    }
+}
+
+/// Keeps track of the files being reloaded by the asset loader
+pub(crate) struct AssetsWatch {
+    watcher: Option<FileWatcher>,
+    pub reloading: Vec<AssetId>,
+    pub reloaded: Vec<AssetReloadedEvent>,
+    /// Watched files removed from the loader
+    pub released: FxHashSet<String>,
+    /// Removed files loaded again because they changed, released after the event is sent
+    pub holding: Vec<AssetId>,
+}
+
+impl AssetsWatch {
+    pub fn new() -> Self {
+        let watcher = FileWatcher::new().inspect_err(|e| log::warn!("{e}")).ok();
+
+        Self {
+            watcher,
+            reloading: vec![],
+            reloaded: vec![],
+            released: FxHashSet::default(),
+            holding: vec![],
+        }
+    }
+
+    pub fn watch(&mut self, path: &str) {
+        if is_url(path) {
+            return;
+        }
+
+        if let Some(watcher) = &mut self.watcher {
+            if let Err(e) = watcher.watch(path) {
+                log::warn!("{e}");
+            }
+        }
+    }
+
+    pub fn changed(&mut self) -> Vec<String> {
+        self.watcher
+            .as_mut()
+            .map(|watcher| watcher.changed())
+            .unwrap_or_default()
+    }
+}
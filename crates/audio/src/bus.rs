@@ -0,0 +1,9 @@
+#[derive(Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Debug)]
+pub(crate) struct BusId(pub(crate) u64);
+
+/// A named group of sounds sharing volume, pause and mute, like "music", "sfx" or "ui".
+/// The global volume acts as the master bus
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct AudioBus {
+    pub(crate) id: BusId,
+}
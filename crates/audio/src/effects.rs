@@ -0,0 +1,121 @@
+use kira::effect::delay::{DelayBuilder, DelayHandle};
+use kira::effect::filter::{FilterBuilder, FilterHandle, FilterMode};
+use kira::effect::reverb::{ReverbBuilder, ReverbHandle};
+use kira::track::TrackBuilder;
+use kira::tween::Tween;
+use kira::Volume;
+use std::time::Duration;
+
+/// Effect applied to every sound routed through a bus
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum AudioEffect {
+    /// Removes the frequencies above `cutoff` (Hz), useful for underwater or occluded sounds
+    LowPass { cutoff: f32 },
+    /// Removes the frequencies below `cutoff` (Hz), useful for radio or phone voices
+    HighPass { cutoff: f32 },
+    /// Simulates a room, `feedback` is the size of the room and `damping` how much the
+    /// high frequencies are absorbed. All the values go from 0.0 to 1.0
+    Reverb {
+        feedback: f32,
+        damping: f32,
+        mix: f32,
+    },
+    /// Repeats the sound after `delay`, `feedback` is the volume of each repetition
+    /// and `mix` the amount of the repetitions heard. The delay cannot be changed later
+    Delay {
+        delay: Duration,
+        feedback: f32,
+        mix: f32,
+    },
+}
+
+pub(crate) enum EffectHandle {
+    Filter(FilterHandle),
+    Reverb(ReverbHandle),
+    Delay(DelayHandle),
+}
+
+impl AudioEffect {
+    pub(crate) fn add_to(&self, builder: &mut TrackBuilder) -> EffectHandle {
+        match *self {
+            AudioEffect::LowPass { cutoff } => EffectHandle::Filter(
+                builder.add_effect(
+                    FilterBuilder::new()
+                        .mode(FilterMode::LowPass)
+                        .cutoff(cutoff as f64),
+                ),
+            ),
+            AudioEffect::HighPass { cutoff } => EffectHandle::Filter(
+                builder.add_effect(
+                    FilterBuilder::new()
+                        .mode(FilterMode::HighPass)
+                        .cutoff(cutoff as f64),
+                ),
+            ),
+            AudioEffect::Reverb {
+                feedback,
+                damping,
+                mix,
+            } => EffectHandle::Reverb(
+                builder.add_effect(
+                    ReverbBuilder::new()
+                        .feedback(feedback.clamp(0.0, 1.0) as f64)
+                        .damping(damping.clamp(0.0, 1.0) as f64)
+                        .mix(mix.clamp(0.0, 1.0) as f64),
+                ),
+            ),
+            AudioEffect::Delay {
+                delay,
+                feedback,
+                mix,
+            } => EffectHandle::Delay(
+                builder.add_effect(
+                    DelayBuilder::new()
+                        .delay_time(delay.as_secs_f64())
+                        .feedback(Volume::Amplitude(feedback.clamp(0.0, 1.0) as f64))
+                        .mix(mix.clamp(0.0, 1.0) as f64),
+                ),
+            ),
+        }
+    }
+}
+
+impl EffectHandle {
+    /// Updates the effect parameters, the effect must be of the same type
+    pub(crate) fn update(&mut self, effect: AudioEffect) -> Result<(), String> {
+        let tween = Tween::default();
+        match (self, effect) {
+            (EffectHandle::Filter(h), AudioEffect::LowPass { cutoff }) => {
+                h.set_mode(FilterMode::LowPass);
+                h.set_cutoff(cutoff as f64, tween);
+            }
+            (EffectHandle::Filter(h), AudioEffect::HighPass { cutoff }) => {
+                h.set_mode(FilterMode::HighPass);
+                h.set_cutoff(cutoff as f64, tween);
+            }
+            (
+                EffectHandle::Reverb(h),
+                AudioEffect::Reverb {
+                    feedback,
+                    damping,
+                    mix,
+                },
+            ) => {
+                h.set_feedback(feedback.clamp(0.0, 1.0) as f64, tween);
+                h.set_damping(damping.clamp(0.0, 1.0) as f64, tween);
+                h.set_mix(mix.clamp(0.0, 1.0) as f64, tween);
+            }
+            (EffectHandle::Delay(h), AudioEffect::Delay { feedback, mix, .. }) => {
+                h.set_feedback(Volume::Amplitude(feedback.clamp(0.0, 1.0) as f64), tween);
+                h.set_mix(mix.clamp(0.0, 1.0) as f64, tween);
+            }
+            (_, effect) => {
+                return Err(format!(
+                    "The effect {effect:?} is not the same type as the effect in the bus."
+                ));
+            }
+        }
+
+        Ok(())
+    }
+}
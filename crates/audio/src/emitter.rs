@@ -0,0 +1,85 @@
+use crate::{
+    create_sound_instance, play_sound, set_sound_position, stop_sound, AudioBus, Sound,
+    SoundInstance,
+};
+use corelib::math::Vec2;
+
+/// Positional sound owned by a game object, meant to be stored as a component.
+/// The sound starts on the first `update` and stops when the emitter is dropped
+pub struct AudioEmitter {
+    instance: SoundInstance,
+    looping: bool,
+    volume: f32,
+    bus: Option<AudioBus>,
+    started: bool,
+}
+
+impl AudioEmitter {
+    pub fn new(sound: &Sound) -> Self {
+        Self {
+            instance: create_sound_instance(sound),
+            looping: false,
+            volume: 1.0,
+            bus: None,
+            started: false,
+        }
+    }
+
+    pub fn looping(mut self) -> Self {
+        self.looping = true;
+        self
+    }
+
+    pub fn with_volume(mut self, volume: f32) -> Self {
+        self.volume = volume;
+        self
+    }
+
+    pub fn with_bus(mut self, bus: &AudioBus) -> Self {
+        self.bus = Some(*bus);
+        self
+    }
+
+    /// Plays the sound the first time and keeps the position in sync with the owner
+    pub fn update(&mut self, position: Vec2) {
+        if self.started {
+            set_sound_position(&self.instance, position);
+            return;
+        }
+
+        self.started = true;
+        let mut builder = play_sound(&self.instance)
+            .repeat(self.looping)
+            .volume(self.volume)
+            .position(position);
+
+        if let Some(bus) = &self.bus {
+            builder = builder.bus(bus);
+        }
+
+        // the sound is played when the builder is dropped
+        drop(builder);
+    }
+
+    /// Stops the sound, the next `update` plays it again
+    pub fn stop(&mut self) {
+        if self.started {
+            stop_sound(&self.instance);
+            self.started = false;
+        }
+    }
+
+    pub fn is_started(&self) -> bool {
+        self.started
+    }
+
+    pub fn instance(&self) -> &SoundInstance {
+        &self.instance
+    }
+}
+
+impl Drop for AudioEmitter {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
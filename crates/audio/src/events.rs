@@ -0,0 +1,25 @@
+use crate::sound::{InstanceId, SoundId};
+use crate::AsSoundInstance;
+
+/// Emitted when a sound instance ends by itself or when a stopped one finishes its fade out
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct SoundFinishedEvent {
+    /// The sound that finished
+    pub sound: SoundId,
+    pub(crate) instance: u64,
+}
+
+impl SoundFinishedEvent {
+    /// Returns true if the event belongs to the instance, for sounds it checks only the id
+    pub fn is<S: AsSoundInstance>(&self, sound: &S) -> bool {
+        let instance = sound.as_instance();
+        if instance.snd.id != self.sound {
+            return false;
+        }
+
+        match instance.id {
+            InstanceId::Global => true,
+            InstanceId::Local(id) => id == self.instance,
+        }
+    }
+}
@@ -0,0 +1,108 @@
+//! Audio capture from the default input device (microphone)
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+#[cfg(not(target_arch = "wasm32"))]
+mod native;
+#[cfg(target_arch = "wasm32")]
+mod web;
+
+#[cfg(not(target_arch = "wasm32"))]
+use native::InputStream;
+#[cfg(target_arch = "wasm32")]
+use web::InputStream;
+
+// seconds of audio kept if the samples are not read
+const MAX_BUFFERED_SECS: usize = 2;
+
+#[derive(Clone)]
+pub(crate) struct SampleBuffer {
+    inner: Arc<Mutex<VecDeque<f32>>>,
+    max_len: usize,
+}
+
+impl SampleBuffer {
+    fn new(sample_rate: u32, channels: u16) -> Self {
+        Self {
+            inner: Default::default(),
+            max_len: sample_rate as usize * channels as usize * MAX_BUFFERED_SECS,
+        }
+    }
+
+    pub(crate) fn push<I: IntoIterator<Item = f32>>(&self, samples: I) {
+        let Ok(mut buffer) = self.inner.lock() else {
+            return;
+        };
+
+        buffer.extend(samples);
+        let overflow = buffer.len().saturating_sub(self.max_len);
+        buffer.drain(..overflow);
+    }
+}
+
+/// Captures PCM samples from the default input device until it's dropped
+pub struct AudioInput {
+    buffer: SampleBuffer,
+    sample_rate: u32,
+    channels: u16,
+    _stream: InputStream,
+}
+
+impl AudioInput {
+    /// Sample rate of the captured audio
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    /// Number of channels, the samples are interleaved
+    pub fn channels(&self) -> u16 {
+        self.channels
+    }
+
+    /// Moves the samples captured since the last read to `out`, returns how many were added.
+    /// Samples are kept up to 2 seconds if they are not read
+    pub fn read(&mut self, out: &mut Vec<f32>) -> usize {
+        let Ok(mut buffer) = self.buffer.inner.lock() else {
+            return 0;
+        };
+
+        let len = buffer.len();
+        out.extend(buffer.drain(..));
+        len
+    }
+
+    /// Discards the samples captured
+    pub fn clear(&mut self) {
+        if let Ok(mut buffer) = self.buffer.inner.lock() {
+            buffer.clear();
+        }
+    }
+
+    /// Root mean square of the samples waiting to be read, from 0.0 to 1.0.
+    /// Useful for voice activation or to display the volume
+    pub fn level(&self) -> f32 {
+        let Ok(buffer) = self.buffer.inner.lock() else {
+            return 0.0;
+        };
+
+        if buffer.is_empty() {
+            return 0.0;
+        }
+
+        let sum = buffer.iter().map(|s| s * s).sum::<f32>();
+        (sum / buffer.len() as f32).sqrt().min(1.0)
+    }
+}
+
+/// Starts capturing audio from the default input device
+/// `Web`: The browser asks the user for permission, the samples arrive once it's granted
+pub fn start_audio_input() -> Result<AudioInput, String> {
+    let (stream, sample_rate, channels, buffer) = InputStream::start(SampleBuffer::new)?;
+    Ok(AudioInput {
+        buffer,
+        sample_rate,
+        channels,
+        _stream: stream,
+    })
+}
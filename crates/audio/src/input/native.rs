@@ -0,0 +1,64 @@
+use super::SampleBuffer;
+use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+use cpal::{FromSample, SampleFormat, SizedSample, Stream, StreamConfig};
+
+pub(crate) struct InputStream {
+    _stream: Stream,
+}
+
+impl InputStream {
+    pub fn start<F>(create_buffer: F) -> Result<(Self, u32, u16, SampleBuffer), String>
+    where
+        F: FnOnce(u32, u16) -> SampleBuffer,
+    {
+        let device = cpal::default_host()
+            .default_input_device()
+            .ok_or_else(|| "No audio input device available".to_string())?;
+
+        let config = device
+            .default_input_config()
+            .map_err(|e| format!("Cannot get the audio input config: {}", e))?;
+
+        let format = config.sample_format();
+        let config: StreamConfig = config.into();
+        let buffer = create_buffer(config.sample_rate.0, config.channels);
+
+        let stream = match format {
+            SampleFormat::F32 => build_stream::<f32>(&device, &config, buffer.clone()),
+            SampleFormat::I16 => build_stream::<i16>(&device, &config, buffer.clone()),
+            SampleFormat::U16 => build_stream::<u16>(&device, &config, buffer.clone()),
+            SampleFormat::I32 => build_stream::<i32>(&device, &config, buffer.clone()),
+            format => Err(format!("Unsupported audio input format: {}", format)),
+        }?;
+
+        stream
+            .play()
+            .map_err(|e| format!("Cannot start the audio input: {}", e))?;
+
+        Ok((
+            Self { _stream: stream },
+            config.sample_rate.0,
+            config.channels,
+            buffer,
+        ))
+    }
+}
+
+fn build_stream<T>(
+    device: &cpal::Device,
+    config: &StreamConfig,
+    buffer: SampleBuffer,
+) -> Result<Stream, String>
+where
+    T: SizedSample,
+    f32: FromSample<T>,
+{
+    device
+        .build_input_stream(
+            config,
+            move |data: &[T], _| buffer.push(data.iter().map(|s| s.to_sample::<f32>())),
+            |err| log::error!("Audio input error: {}", err),
+            None,
+        )
+        .map_err(|e| format!("Cannot create the audio input stream: {}", e))
+}
@@ -0,0 +1,133 @@
+use super::SampleBuffer;
+use std::cell::RefCell;
+use std::rc::Rc;
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{
+    AudioContext, AudioProcessingEvent, MediaStream, MediaStreamConstraints, MediaStreamTrack,
+};
+
+// samples per callback, bigger values add latency but reduce the overhead
+const BUFFER_SIZE: u32 = 4096;
+
+// the stream arrives once the permission is granted, which can happen after the drop
+enum StreamState {
+    Waiting,
+    Active(MediaStream),
+    Closed,
+}
+
+pub(crate) struct InputStream {
+    ctx: AudioContext,
+    state: Rc<RefCell<StreamState>>,
+}
+
+impl InputStream {
+    pub fn start<F>(create_buffer: F) -> Result<(Self, u32, u16, SampleBuffer), String>
+    where
+        F: FnOnce(u32, u16) -> SampleBuffer,
+    {
+        let ctx = AudioContext::new().map_err(js_err)?;
+        let sample_rate = ctx.sample_rate() as u32;
+        let buffer = create_buffer(sample_rate, 1);
+
+        let devices = web_sys::window()
+            .ok_or_else(|| "Cannot access the browser window".to_string())?
+            .navigator()
+            .media_devices()
+            .map_err(js_err)?;
+
+        let constraints = MediaStreamConstraints::new();
+        constraints.set_audio(&JsValue::TRUE);
+        let request = devices
+            .get_user_media_with_constraints(&constraints)
+            .map_err(js_err)?;
+
+        // the stream is available once the user grants the permission
+        let state = Rc::new(RefCell::new(StreamState::Waiting));
+        let async_ctx = ctx.clone();
+        let async_state = state.clone();
+        let async_buffer = buffer.clone();
+        wasm_bindgen_futures::spawn_local(async move {
+            if let Err(e) = connect(async_ctx, request, async_state, async_buffer).await {
+                log::error!("Cannot start the audio input: {}", e);
+            }
+        });
+
+        Ok((Self { ctx, state }, sample_rate, 1, buffer))
+    }
+}
+
+impl Drop for InputStream {
+    fn drop(&mut self) {
+        let _ = self.ctx.close();
+
+        // stopping the tracks releases the microphone and hides the browser's recording indicator
+        let state = std::mem::replace(&mut *self.state.borrow_mut(), StreamState::Closed);
+        if let StreamState::Active(stream) = state {
+            stop_tracks(&stream);
+        }
+    }
+}
+
+async fn connect(
+    ctx: AudioContext,
+    request: js_sys::Promise,
+    state: Rc<RefCell<StreamState>>,
+    buffer: SampleBuffer,
+) -> Result<(), String> {
+    let stream = JsFuture::from(request)
+        .await
+        .map_err(js_err)?
+        .dyn_into::<MediaStream>()
+        .map_err(js_err)?;
+
+    {
+        let mut state = state.borrow_mut();
+        if matches!(*state, StreamState::Closed) {
+            stop_tracks(&stream);
+            return Ok(());
+        }
+
+        *state = StreamState::Active(stream.clone());
+    }
+
+    let source = ctx.create_media_stream_source(&stream).map_err(js_err)?;
+    let processor = ctx
+        .create_script_processor_with_buffer_size_and_number_of_input_channels_and_number_of_output_channels(
+            BUFFER_SIZE,
+            1,
+            1,
+        )
+        .map_err(js_err)?;
+
+    let callback =
+        Closure::<dyn FnMut(AudioProcessingEvent)>::new(move |evt: AudioProcessingEvent| {
+            if let Ok(data) = evt.input_buffer().and_then(|b| b.get_channel_data(0)) {
+                buffer.push(data);
+            }
+        });
+    processor.set_onaudioprocess(Some(callback.as_ref().unchecked_ref()));
+    // the processor lives as long as the context, closing it stops the callbacks
+    callback.forget();
+
+    source.connect_with_audio_node(&processor).map_err(js_err)?;
+    processor
+        .connect_with_audio_node(&ctx.destination())
+        .map_err(js_err)?;
+
+    Ok(())
+}
+
+fn stop_tracks(stream: &MediaStream) {
+    stream
+        .get_tracks()
+        .iter()
+        .filter_map(|track| track.dyn_into::<MediaStreamTrack>().ok())
+        .for_each(|track| track.stop());
+}
+
+fn js_err(err: JsValue) -> String {
+    format!("{:?}", err)
+}
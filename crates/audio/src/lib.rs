@@ -1,24 +1,79 @@
+pub use crate::bus::AudioBus;
+pub use crate::effects::AudioEffect;
+pub use crate::emitter::AudioEmitter;
+pub use crate::events::SoundFinishedEvent;
 use crate::manager::{PlayOptions, MANAGER};
-pub use crate::sound::{AsSoundInstance, Sound, SoundInstance};
+pub use crate::sound::{AsSoundInstance, Sound, SoundInstance, VoiceStealing};
+use corelib::math::Vec2;
+pub use kira::tween::Easing;
+use kira::tween::Tween;
+use std::time::Duration;
 
+mod bus;
+mod effects;
+mod emitter;
+mod events;
+#[cfg(feature = "input")]
+pub mod input;
 mod manager;
 mod sound;
-
-// TODO return PlayBuilder and StopBuilder to have options like delay, volume, etc...
+mod source;
+mod spatial;
 
 #[inline]
 pub fn create_sound(bytes: &[u8]) -> Result<Sound, String> {
     MANAGER.borrow_mut().create_sound(bytes)
 }
 
+/// Creates a sound from raw PCM samples, useful for synthesized or runtime generated audio.
+/// The samples go from -1.0 to 1.0 and are interleaved if `channels` is 2
+#[inline]
+pub fn create_sound_from_samples(
+    samples: &[f32],
+    sample_rate: u32,
+    channels: u16,
+) -> Result<Sound, String> {
+    MANAGER
+        .borrow_mut()
+        .create_sound_from_samples(samples, sample_rate, channels)
+}
+
+/// Creates a sound that is decoded while it plays instead of decoding it whole in memory.
+/// Useful for long music tracks
+/// `Web`: The sound is decoded in memory
+#[inline]
+pub fn create_streaming_sound(bytes: &[u8]) -> Result<Sound, String> {
+    MANAGER.borrow_mut().create_streaming_sound(bytes)
+}
+
+/// Parser that can be used with the assets crate to load `.ogg`, `.wav` or `.mp3` (with the `mp3` feature) files
+#[inline]
+pub fn parse_sound(_id: &str, data: &[u8]) -> Result<Sound, String> {
+    create_sound(data)
+}
+
+/// Limits how many instances of the sound can play at once, useful for rapid-fire effects
+#[inline]
+pub fn set_sound_max_instances(sound: &Sound, max: usize, policy: VoiceStealing) {
+    MANAGER
+        .borrow_mut()
+        .set_max_instances(sound.id, Some((max, policy)));
+}
+
+#[inline]
+pub fn remove_sound_max_instances(sound: &Sound) {
+    MANAGER.borrow_mut().set_max_instances(sound.id, None);
+}
+
 #[inline]
 pub fn create_sound_instance(sound: &Sound) -> SoundInstance {
     MANAGER.borrow_mut().create_sound_instance(sound)
 }
 
+/// Plays the sound when the returned builder is dropped
 #[inline]
-pub fn play_sound<S: AsSoundInstance>(sound: &S) -> AudioPlay {
-    AudioPlay::new(sound.as_instance())
+pub fn play_sound<S: AsSoundInstance>(sound: &S) -> PlayBuilder {
+    PlayBuilder::new(sound.as_instance())
 }
 
 #[inline]
@@ -33,14 +88,54 @@ pub fn resume_sound<S: AsSoundInstance>(sound: &S) {
 
 #[inline]
 pub fn stop_sound<S: AsSoundInstance>(sound: &S) {
-    MANAGER.borrow_mut().stop_sound(sound.as_instance());
+    MANAGER
+        .borrow_mut()
+        .stop_sound(sound.as_instance(), Tween::default());
+}
+
+/// Fades out and stops `from` while `to` fades in, the returned builder plays `to`
+/// `from` is reported by `finished_sounds` once the fade out ends
+#[inline]
+pub fn crossfade<F: AsSoundInstance, T: AsSoundInstance>(
+    from: &F,
+    to: &T,
+    duration: Duration,
+) -> PlayBuilder {
+    let fade_out = Tween {
+        duration,
+        ..Default::default()
+    };
+    MANAGER
+        .borrow_mut()
+        .stop_sound(from.as_instance(), fade_out);
+    play_sound(to).fade_in(duration)
 }
 
 #[inline]
 pub fn set_sound_volume<S: AsSoundInstance>(sound: &S, vol: f32) {
     MANAGER
         .borrow_mut()
-        .set_sound_volume(sound.as_instance(), vol);
+        .set_sound_volume(sound.as_instance(), vol, Tween::default());
+}
+
+/// Changes the volume progressively, the fade runs in the audio thread so it keeps going
+/// even if the caller stops updating
+#[inline]
+pub fn tween_sound_volume<S: AsSoundInstance>(
+    sound: &S,
+    vol: f32,
+    duration: Duration,
+    easing: Easing,
+) {
+    MANAGER.borrow_mut().set_sound_volume(
+        sound.as_instance(),
+        vol,
+        Tween {
+            duration,
+            easing,
+            ..Default::default()
+        },
+    );
 }
 
 #[inline]
@@ -55,7 +150,27 @@ pub fn sound_volume<S: AsSoundInstance>(sound: &S) -> f32 {
 pub fn set_sound_pitch<S: AsSoundInstance>(sound: &S, pitch: f32) {
     MANAGER
         .borrow_mut()
-        .set_sound_pitch(sound.as_instance(), pitch);
+        .set_sound_pitch(sound.as_instance(), pitch, Tween::default());
+}
+
+/// Changes the pitch progressively, the change runs in the audio thread so it keeps going
+/// even if the caller stops updating
+#[inline]
+pub fn tween_sound_pitch<S: AsSoundInstance>(
+    sound: &S,
+    pitch: f32,
+    duration: Duration,
+    easing: Easing,
+) {
+    MANAGER.borrow_mut().set_sound_pitch(
+        sound.as_instance(),
+        pitch,
+        Tween {
+            duration,
+            easing,
+            ..Default::default()
+        },
+    );
 }
 
 #[inline]
@@ -81,6 +196,49 @@ pub fn sound_panning<S: AsSoundInstance>(sound: &S) -> f32 {
         .unwrap_or_default()
 }
 
+/// Makes the sound positional, the volume and panning are calculated from the listener position
+#[inline]
+pub fn set_sound_position<S: AsSoundInstance>(sound: &S, pos: Vec2) {
+    MANAGER
+        .borrow_mut()
+        .set_sound_position(sound.as_instance(), Some(pos));
+}
+
+/// The sound is not positional anymore, the volume and panning set by the user are restored
+#[inline]
+pub fn remove_sound_position<S: AsSoundInstance>(sound: &S) {
+    MANAGER
+        .borrow_mut()
+        .set_sound_position(sound.as_instance(), None);
+}
+
+#[inline]
+pub fn sound_position<S: AsSoundInstance>(sound: &S) -> Option<Vec2> {
+    MANAGER.borrow().sound_position(sound.as_instance())
+}
+
+/// Set the position from where the positional sounds are heard, usually the camera or the player
+#[inline]
+pub fn set_listener_position(pos: Vec2) {
+    MANAGER.borrow_mut().set_listener_position(pos);
+}
+
+#[inline]
+pub fn listener_position() -> Vec2 {
+    MANAGER.borrow().listener_position()
+}
+
+/// Distance from the listener where the positional sounds are not audible anymore
+#[inline]
+pub fn set_listener_range(range: f32) {
+    MANAGER.borrow_mut().set_listener_range(range);
+}
+
+#[inline]
+pub fn listener_range() -> f32 {
+    MANAGER.borrow().listener_range()
+}
+
 #[inline]
 pub fn is_sound_playing<S: AsSoundInstance>(sound: &S) -> bool {
     MANAGER
@@ -97,6 +255,28 @@ pub fn is_sound_paused<S: AsSoundInstance>(sound: &S) -> bool {
         .unwrap_or_default()
 }
 
+/// Moves the playback position to `secs`
+#[inline]
+pub fn seek_sound<S: AsSoundInstance>(sound: &S, secs: f32) {
+    MANAGER.borrow_mut().seek_sound(sound.as_instance(), secs);
+}
+
+/// Loops between `start` and `end` (in seconds) once the playback reaches `end`
+#[inline]
+pub fn set_sound_loop_region<S: AsSoundInstance>(sound: &S, start: f32, end: f32) {
+    MANAGER
+        .borrow_mut()
+        .set_sound_loop_region(sound.as_instance(), Some((start, end)));
+}
+
+/// Removes the loop region, the sound stops at the end
+#[inline]
+pub fn remove_sound_loop_region<S: AsSoundInstance>(sound: &S) {
+    MANAGER
+        .borrow_mut()
+        .set_sound_loop_region(sound.as_instance(), None);
+}
+
 #[inline]
 pub fn sound_progress<S: AsSoundInstance>(sound: &S) -> f32 {
     MANAGER
@@ -105,8 +285,24 @@ pub fn sound_progress<S: AsSoundInstance>(sound: &S) -> f32 {
         .unwrap_or_default()
 }
 
+/// Current playback position in seconds
+#[inline]
+pub fn sound_position_secs<S: AsSoundInstance>(sound: &S) -> f32 {
+    MANAGER
+        .borrow()
+        .sound_position_secs(sound.as_instance())
+        .unwrap_or_default()
+}
+
+/// Duration of the sound in seconds
+#[inline]
+pub fn sound_duration_secs<S: AsSoundInstance>(sound: &S) -> f32 {
+    sound.as_instance().snd.duration().as_secs_f32()
+}
+
 // TODO set_sound_pitch and set_sound_pan?
 
+/// Set the volume of the master bus, every sound and bus goes through it
 #[inline]
 pub fn set_global_volume(v: f32) {
     MANAGER.borrow_mut().set_volume(v);
@@ -117,18 +313,127 @@ pub fn global_volume() -> f32 {
     MANAGER.borrow().volume
 }
 
+/// Returns if the audio can be played
+/// `Web`: Browsers block the audio until the user interacts with the page, the sounds played
+/// before that are queued and played once a key or mouse button is used
+#[inline]
+pub fn is_unlocked() -> bool {
+    MANAGER.borrow().is_unlocked()
+}
+
+/// Pauses every sound, the sounds played while it's paused wait for the resume
+#[inline]
+pub fn pause_all() {
+    MANAGER.borrow_mut().set_paused(true);
+}
+
+#[inline]
+pub fn resume_all() {
+    MANAGER.borrow_mut().set_paused(false);
+}
+
+#[inline]
+pub fn is_all_paused() -> bool {
+    MANAGER.borrow().paused
+}
+
+/// Pauses the audio while the window is not focused and resumes it when the focus is back.
+/// Disabled by default
+#[inline]
+pub fn set_pause_on_focus_lost(enabled: bool) {
+    MANAGER.borrow_mut().set_pause_on_focus_lost(enabled);
+}
+
+#[inline]
+pub fn is_pause_on_focus_lost() -> bool {
+    MANAGER.borrow().pause_on_focus_lost
+}
+
+/// Returns the bus with this name, it's created the first time
+#[inline]
+pub fn audio_bus(name: &str) -> Result<AudioBus, String> {
+    MANAGER.borrow_mut().audio_bus(name)
+}
+
+/// Creates a bus that applies the effects, in order, to every sound routed through it.
+/// Returns an error if the bus already exists
+#[inline]
+pub fn audio_bus_with_effects(name: &str, effects: &[AudioEffect]) -> Result<AudioBus, String> {
+    MANAGER.borrow_mut().audio_bus_with_effects(name, effects)
+}
+
+/// Updates the parameters of the effect at `index`, the effect must be of the same type
+#[inline]
+pub fn set_bus_effect(bus: &AudioBus, index: usize, effect: AudioEffect) -> Result<(), String> {
+    MANAGER.borrow_mut().set_bus_effect(*bus, index, effect)
+}
+
+/// Set the volume of the sounds routed through the bus
+#[inline]
+pub fn set_bus_volume(bus: &AudioBus, vol: f32) {
+    MANAGER.borrow_mut().set_bus_volume(*bus, vol);
+}
+
+#[inline]
+pub fn bus_volume(bus: &AudioBus) -> f32 {
+    MANAGER.borrow().bus_volume(*bus).unwrap_or_default()
+}
+
+/// Silences the bus keeping its volume
+#[inline]
+pub fn set_bus_muted(bus: &AudioBus, muted: bool) {
+    MANAGER.borrow_mut().set_bus_muted(*bus, muted);
+}
+
+#[inline]
+pub fn is_bus_muted(bus: &AudioBus) -> bool {
+    MANAGER.borrow().is_bus_muted(*bus).unwrap_or_default()
+}
+
+/// Pauses every sound routed through the bus, sounds played while it's paused wait for the resume
+#[inline]
+pub fn pause_bus(bus: &AudioBus) {
+    MANAGER.borrow_mut().set_bus_paused(*bus, true);
+}
+
+/// Resumes every sound routed through the bus
+#[inline]
+pub fn resume_bus(bus: &AudioBus) {
+    MANAGER.borrow_mut().set_bus_paused(*bus, false);
+}
+
+#[inline]
+pub fn is_bus_paused(bus: &AudioBus) -> bool {
+    MANAGER.borrow().is_bus_paused(*bus).unwrap_or_default()
+}
+
+/// Returns the sounds that finished during the last frame
+#[inline]
+pub fn finished_sounds() -> Vec<SoundFinishedEvent> {
+    MANAGER.borrow().finished.clone()
+}
+
 /// Used by the system to clean after the frame ends
 #[inline]
 pub(crate) fn clean_audio_manager() {
     MANAGER.borrow_mut().clean();
 }
 
-pub struct AudioPlay {
+/// Used by the system to pause the sounds while the app is in background
+#[inline]
+pub(crate) fn suspend_audio_manager(suspended: bool) {
+    MANAGER.borrow_mut().set_suspended(suspended);
+}
+
+#[deprecated(note = "Use PlayBuilder instead")]
+pub type AudioPlay = PlayBuilder;
+
+pub struct PlayBuilder {
     instance: Option<SoundInstance>,
     opts: PlayOptions,
 }
 
-impl AudioPlay {
+impl PlayBuilder {
     pub fn new(instance: SoundInstance) -> Self {
         Self {
             instance: Some(instance),
@@ -146,6 +451,13 @@ impl AudioPlay {
         self
     }
 
+    /// Loops between `start` and `end` (in seconds) once the playback reaches `end`,
+    /// the part before `start` plays only once. Useful for music with intros
+    pub fn loop_region(mut self, start: f32, end: f32) -> Self {
+        self.opts.loop_region = Some((start, end));
+        self
+    }
+
     pub fn pitch(mut self, speed: f32) -> Self {
         self.opts.pitch = speed;
         self
@@ -155,9 +467,39 @@ impl AudioPlay {
         self.opts.panning = panning.clamp(0.0, 1.0);
         self
     }
+
+    /// Plays the sound as positional, see `set_sound_position`
+    pub fn position(mut self, pos: Vec2) -> Self {
+        self.opts.position = Some(pos);
+        self
+    }
+
+    /// Routes the sound through the bus instead of the master bus
+    pub fn bus(mut self, bus: &AudioBus) -> Self {
+        self.opts.bus = Some(bus.id);
+        self
+    }
+
+    /// Waits before start playing the sound
+    pub fn delay(mut self, delay: Duration) -> Self {
+        self.opts.delay = Some(delay);
+        self
+    }
+
+    /// Fades the volume from silence to the sound's volume
+    pub fn fade_in(mut self, duration: Duration) -> Self {
+        self.opts.fade_in = Some(duration);
+        self
+    }
+
+    /// Starts playing from the position in seconds
+    pub fn start_at(mut self, secs: f32) -> Self {
+        self.opts.start_at = secs.max(0.0) as _;
+        self
+    }
 }
 
-impl Drop for AudioPlay {
+impl Drop for PlayBuilder {
     fn drop(&mut self) {
         debug_assert!(
             self.instance.is_some(),
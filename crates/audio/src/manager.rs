@@ -1,61 +1,197 @@
-use crate::sound::{InstanceId, SoundId};
-use crate::{clean_audio_manager, Sound, SoundInstance};
+use crate::bus::BusId;
+use crate::effects::{AudioEffect, EffectHandle};
+use crate::events::SoundFinishedEvent;
+use crate::sound::{InstanceId, SoundId, VoiceStealing};
+use crate::source::{SoundHandle, SoundSource};
+use crate::spatial::Listener;
+use crate::{clean_audio_manager, suspend_audio_manager, AudioBus, Sound, SoundInstance};
 use atomic_refcell::AtomicRefCell;
+use corelib::math::Vec2;
+use corelib::time::{self, Instant};
 use kira::manager::{AudioManager, AudioManagerSettings, DefaultBackend};
-use kira::sound::static_sound::{StaticSoundData, StaticSoundHandle, StaticSoundSettings};
-use kira::sound::{PlaybackRate, PlaybackState};
+use kira::sound::static_sound::StaticSoundSettings;
+use kira::sound::{PlaybackRate, PlaybackState, Region};
+use kira::track::{TrackBuilder, TrackHandle};
 use kira::tween::Tween;
-use kira::Volume;
+use kira::{StartTime, Volume};
 use num::Zero;
 use once_cell::sync::Lazy;
 use rustc_hash::{FxBuildHasher, FxHashMap};
 use smallvec::SmallVec;
+use std::time::Duration;
 
 pub(crate) static MANAGER: Lazy<AtomicRefCell<Manager>> = Lazy::new(|| {
     corelib::app::on_sys_post_update(clean_audio_manager);
+    corelib::app::on_sys_suspend(|| suspend_audio_manager(true));
+    corelib::app::on_sys_resume(|| suspend_audio_manager(false));
     AtomicRefCell::new(Manager::default())
 });
 
 struct InstanceData {
     id: u64,
-    raw: StaticSoundData,
-    handle: StaticSoundHandle,
+    source: SoundSource,
+    handle: SoundHandle,
     volume: f32,
     pitch: f32,
     panning: f32,
+    bus: Option<BusId>,
+    position: Option<Vec2>,
+    paused: bool,
+    // end time of the volume tween, kept to resume it after the attenuation changes
+    volume_fade: Option<(Instant, Tween)>,
 }
 
 impl InstanceData {
     fn is_stopped(&self) -> bool {
         matches!(self.handle.state(), PlaybackState::Stopped)
     }
+
+    // held sounds are paused by the user, their bus or the whole manager
+    fn apply_pause(&mut self, held: bool) {
+        match (held, self.handle.state()) {
+            (true, PlaybackState::Playing) => self.handle.pause(Tween::default()),
+            (false, PlaybackState::Paused | PlaybackState::Pausing) => {
+                self.handle.resume(Tween::default())
+            }
+            _ => {}
+        }
+    }
+
+    // the instances fading out to stop do not count as voices
+    fn is_voice(&self) -> bool {
+        !matches!(
+            self.handle.state(),
+            PlaybackState::Stopping | PlaybackState::Stopped
+        )
+    }
+
+    fn loudness(&self, listener: &Listener) -> f32 {
+        let attenuation = self.position.map_or(1.0, |pos| listener.spatial(pos).0);
+        self.volume * attenuation
+    }
+
+    fn set_volume(&mut self, vol: f32, listener: &Listener, tween: Tween) {
+        self.volume = vol;
+        self.volume_fade = Some((time::now() + tween.duration, tween));
+        let attenuation = self.position.map_or(1.0, |pos| listener.spatial(pos).0);
+        self.handle
+            .set_volume(Volume::Amplitude((vol * attenuation) as _), tween);
+    }
+
+    // a tween in progress keeps going to the new target for the time left
+    fn volume_tween(&mut self) -> Tween {
+        let now = time::now();
+        match self.volume_fade {
+            Some((end, tween)) if end > now => Tween {
+                duration: end - now,
+                ..tween
+            },
+            _ => {
+                self.volume_fade = None;
+                Tween::default()
+            }
+        }
+    }
+
+    // positional sounds override the panning and scale the volume by the distance
+    fn apply_spatial(&mut self, listener: &Listener) {
+        let Some(pos) = self.position else {
+            return;
+        };
+
+        let (attenuation, panning) = listener.spatial(pos);
+        let tween = self.volume_tween();
+        self.handle
+            .set_volume(Volume::Amplitude((self.volume * attenuation) as _), tween);
+        self.handle.set_panning(panning as _, Tween::default());
+    }
+}
+
+struct BusData {
+    track: TrackHandle,
+    effects: Vec<(AudioEffect, EffectHandle)>,
+    volume: f32,
+    muted: bool,
+    paused: bool,
+}
+
+impl BusData {
+    fn apply_volume(&mut self) {
+        let vol = if self.muted { 0.0 } else { self.volume };
+        self.track
+            .set_volume(Volume::Amplitude(vol as _), Tween::default());
+    }
 }
 
 pub(crate) struct Manager {
     count_ids: u64,
     manager: AudioManager,
     instances: FxHashMap<SoundId, SmallVec<InstanceData, 10>>,
+    buses: FxHashMap<BusId, BusData>,
+    voice_limits: FxHashMap<SoundId, (usize, VoiceStealing)>,
+    bus_names: FxHashMap<String, BusId>,
+    pub(crate) finished: Vec<SoundFinishedEvent>,
+    listener: Listener,
     pub(crate) volume: f32,
+    pub(crate) paused: bool,
+    pub(crate) pause_on_focus_lost: bool,
+    focus_lost: bool,
+    // the app is in background (mobile), the sounds are always paused
+    suspended: bool,
+    // web browsers keep the audio suspended until the user interacts with the page
+    unlocked: bool,
+    pending: Vec<(SoundInstance, PlayOptions)>,
 }
 
 impl Default for Manager {
     fn default() -> Self {
-        let manager = AudioManager::<DefaultBackend>::new(AudioManagerSettings::default())
-            .map_err(|e| format!("Cannot initialize audio backend: {:?}", e.to_string()))
-            .unwrap();
+        let manager = create_audio_manager().unwrap();
 
         Self {
             count_ids: 0,
             manager,
             instances: FxHashMap::with_capacity_and_hasher(10, FxBuildHasher),
+            buses: FxHashMap::default(),
+            voice_limits: FxHashMap::default(),
+            bus_names: FxHashMap::default(),
+            finished: vec![],
+            listener: Listener::default(),
             volume: 1.0,
+            paused: false,
+            pause_on_focus_lost: false,
+            focus_lost: false,
+            suspended: false,
+            unlocked: !cfg!(target_arch = "wasm32"),
+            pending: vec![],
         }
     }
 }
 
 impl Manager {
     pub fn create_sound(&mut self, bytes: &[u8]) -> Result<Sound, String> {
-        create_sound_from_bytes(self.next_id(), bytes)
+        Ok(Sound {
+            id: SoundId(self.next_id()),
+            source: SoundSource::from_static_bytes(bytes)?,
+        })
+    }
+
+    pub fn create_sound_from_samples(
+        &mut self,
+        samples: &[f32],
+        sample_rate: u32,
+        channels: u16,
+    ) -> Result<Sound, String> {
+        Ok(Sound {
+            id: SoundId(self.next_id()),
+            source: SoundSource::from_samples(samples, sample_rate, channels)?,
+        })
+    }
+
+    pub fn create_streaming_sound(&mut self, bytes: &[u8]) -> Result<Sound, String> {
+        Ok(Sound {
+            id: SoundId(self.next_id()),
+            source: SoundSource::from_streaming_bytes(bytes)?,
+        })
     }
 
     pub fn create_sound_instance(&mut self, snd: &Sound) -> SoundInstance {
@@ -67,15 +203,28 @@ impl Manager {
     }
 
     pub fn play_sound(&mut self, instance: SoundInstance, opts: PlayOptions) {
+        if !self.unlocked {
+            self.pending.push((instance, opts));
+            return;
+        }
+
         // if the instance is global then we assign a new id for the current instance
         let id = match instance.id {
             InstanceId::Global => self.next_id(),
             InstanceId::Local(id) => id,
         };
 
+        let manager_paused = self.is_manager_paused();
+
         // If the sound is in progress get the list if not create the list
         let list = self.instances.entry(instance.snd.id).or_default();
 
+        if let Some(&(max, policy)) = self.voice_limits.get(&instance.snd.id) {
+            if !make_room(list, id, max, policy, &self.listener) {
+                return;
+            }
+        }
+
         // Check if an instance with the same id already exists in the list
         let data_opt = list.iter_mut().find(|data| data.id == id);
         if let Some(data) = data_opt {
@@ -83,34 +232,43 @@ impl Manager {
                 return;
             }
 
-            match self.manager.play(data.raw.with_settings(opts.into())) {
+            let settings = sound_settings(&self.buses, &self.listener, opts);
+            match data.source.play(&mut self.manager, settings) {
                 Ok(handle) => {
                     data.handle = handle;
                     data.volume = opts.volume;
-                    data.pitch = opts.volume;
-                    data.panning = opts.volume;
+                    data.pitch = opts.pitch;
+                    data.panning = opts.panning;
+                    data.bus = opts.bus;
+                    data.position = opts.position;
+                    data.volume_fade = None;
+                    data.paused = false;
+                    data.apply_pause(is_held(&self.buses, manager_paused, data));
                 }
                 Err(e) => {
-                    log::error!("Error playing sound: {}", e.to_string());
+                    log::error!("Error playing sound: {}", e);
                 }
             }
             return;
         }
 
         // No instance with this id found, so create and insert a new one
-        match self
-            .manager
-            .play(instance.snd.raw.with_settings(opts.into()))
-        {
+        let settings = sound_settings(&self.buses, &self.listener, opts);
+        match instance.snd.source.play(&mut self.manager, settings) {
             Ok(handle) => {
-                let data = InstanceData {
+                let mut data = InstanceData {
                     id,
-                    raw: instance.snd.raw,
+                    source: instance.snd.source,
                     handle,
                     volume: opts.volume,
                     pitch: opts.pitch,
                     panning: opts.panning,
+                    bus: opts.bus,
+                    position: opts.position,
+                    paused: false,
+                    volume_fade: None,
                 };
+                data.apply_pause(is_held(&self.buses, manager_paused, &data));
                 list.push(data);
             }
             Err(e) => {
@@ -119,25 +277,22 @@ impl Manager {
         }
     }
 
-    pub fn stop_sound(&mut self, instance: SoundInstance) {
+    pub fn stop_sound(&mut self, instance: SoundInstance, tween: Tween) {
         let Some(list) = self.instances.get_mut(&instance.snd.id) else {
             return;
         };
 
+        // the instances are removed once stopped (after the fade out), sending the finished event
         match instance.id {
             InstanceId::Global => {
                 list.iter_mut().for_each(|d| {
-                    d.handle.stop(Tween::default());
+                    d.handle.stop(tween);
                 });
-                list.clear();
             }
             InstanceId::Local(id) => {
-                let Some(idx) = list.iter().position(|d| d.id == id) else {
-                    return;
-                };
-
-                let mut data = list.remove(idx);
-                data.handle.stop(Tween::default());
+                if let Some(data) = list.iter_mut().find(|d| d.id == id) {
+                    data.handle.stop(tween);
+                }
             }
         }
     }
@@ -150,31 +305,37 @@ impl Manager {
         match instance.id {
             InstanceId::Global => {
                 list.iter_mut().for_each(|d| {
-                    d.handle.pause(Tween::default());
+                    d.paused = true;
+                    d.apply_pause(true);
                 });
             }
             InstanceId::Local(id) => {
                 if let Some(data) = list.iter_mut().find(|d| d.id == id) {
-                    data.handle.pause(Tween::default());
+                    data.paused = true;
+                    data.apply_pause(true);
                 }
             }
         }
     }
 
     pub fn resume_sound(&mut self, instance: SoundInstance) {
+        let manager_paused = self.is_manager_paused();
         let Some(list) = self.instances.get_mut(&instance.snd.id) else {
             return;
         };
 
+        // the sound keeps waiting if its bus or the manager are paused
         match instance.id {
             InstanceId::Global => {
                 list.iter_mut().for_each(|d| {
-                    d.handle.resume(Tween::default());
+                    d.paused = false;
+                    d.apply_pause(is_held(&self.buses, manager_paused, d));
                 });
             }
             InstanceId::Local(id) => {
                 if let Some(data) = list.iter_mut().find(|d| d.id == id) {
-                    data.handle.resume(Tween::default());
+                    data.paused = false;
+                    data.apply_pause(is_held(&self.buses, manager_paused, data));
                 }
             }
         }
@@ -187,10 +348,49 @@ impl Manager {
             .set_volume(Volume::Amplitude(self.volume as _), Tween::default());
     }
 
+    pub fn set_paused(&mut self, paused: bool) {
+        let was_paused = self.is_manager_paused();
+        self.paused = paused;
+        self.apply_pause(was_paused);
+    }
+
+    pub fn set_pause_on_focus_lost(&mut self, enabled: bool) {
+        let was_paused = self.is_manager_paused();
+        self.pause_on_focus_lost = enabled;
+        self.apply_pause(was_paused);
+    }
+
+    pub fn set_suspended(&mut self, suspended: bool) {
+        let was_paused = self.is_manager_paused();
+        self.suspended = suspended;
+        self.apply_pause(was_paused);
+    }
+
+    fn is_manager_paused(&self) -> bool {
+        self.paused || self.suspended || (self.pause_on_focus_lost && self.focus_lost)
+    }
+
+    fn apply_pause(&mut self, was_paused: bool) {
+        let paused = self.is_manager_paused();
+        if paused == was_paused {
+            return;
+        }
+
+        let buses = &self.buses;
+        self.instances
+            .values_mut()
+            .flat_map(|list| list.iter_mut())
+            .for_each(|d| {
+                let held = is_held(buses, paused, d);
+                d.apply_pause(held);
+            });
+    }
+
     pub fn is_playing(&self, instance: SoundInstance) -> Option<bool> {
         self.instances.get(&instance.snd.id)?.iter().find_map(|d| {
+            // the instances being stopped are ignored for global sounds
             let check = match instance.id {
-                InstanceId::Global => true,
+                InstanceId::Global => d.is_voice(),
                 InstanceId::Local(id) => id == d.id,
             };
 
@@ -201,7 +401,7 @@ impl Manager {
     pub fn is_paused(&self, instance: SoundInstance) -> Option<bool> {
         self.instances.get(&instance.snd.id)?.iter().find_map(|d| {
             let check = match instance.id {
-                InstanceId::Global => true,
+                InstanceId::Global => d.is_voice(),
                 InstanceId::Local(id) => id == d.id,
             };
 
@@ -209,28 +409,10 @@ impl Manager {
         })
     }
 
-    pub fn set_sound_volume(&mut self, instance: SoundInstance, vol: f32) {
-        let Some(list) = self.instances.get_mut(&instance.snd.id) else {
-            return;
-        };
-
+    pub fn set_sound_volume(&mut self, instance: SoundInstance, vol: f32, tween: Tween) {
         let vol = vol.clamp(0.0, 1.0);
-        match instance.id {
-            InstanceId::Global => {
-                list.iter_mut().for_each(|d| {
-                    d.handle
-                        .set_volume(Volume::Amplitude(vol as _), Tween::default());
-                    d.volume = vol;
-                });
-            }
-            InstanceId::Local(id) => {
-                if let Some(data) = list.iter_mut().find(|d| d.id == id) {
-                    data.handle
-                        .set_volume(Volume::Amplitude(vol as _), Tween::default());
-                    data.volume = vol;
-                }
-            }
-        }
+        let listener = self.listener;
+        self.for_each_instance(instance, |d| d.set_volume(vol, &listener, tween));
     }
 
     pub fn sound_volume(&self, instance: SoundInstance) -> Option<f32> {
@@ -244,27 +426,12 @@ impl Manager {
         })
     }
 
-    pub fn set_sound_pitch(&mut self, instance: SoundInstance, pitch: f32) {
-        let Some(list) = self.instances.get_mut(&instance.snd.id) else {
-            return;
-        };
-
-        match instance.id {
-            InstanceId::Global => {
-                list.iter_mut().for_each(|d| {
-                    d.handle
-                        .set_playback_rate(PlaybackRate::Factor(pitch as _), Tween::default());
-                    d.pitch = pitch;
-                });
-            }
-            InstanceId::Local(id) => {
-                if let Some(data) = list.iter_mut().find(|d| d.id == id) {
-                    data.handle
-                        .set_playback_rate(PlaybackRate::Factor(pitch as _), Tween::default());
-                    data.pitch = pitch;
-                }
-            }
-        }
+    pub fn set_sound_pitch(&mut self, instance: SoundInstance, pitch: f32, tween: Tween) {
+        self.for_each_instance(instance, |d| {
+            d.handle
+                .set_playback_rate(PlaybackRate::Factor(pitch as _), tween);
+            d.pitch = pitch;
+        });
     }
 
     pub fn sound_pitch(&self, instance: SoundInstance) -> Option<f32> {
@@ -287,13 +454,18 @@ impl Manager {
         match instance.id {
             InstanceId::Global => {
                 list.iter_mut().for_each(|d| {
-                    d.handle.set_panning(panning as f64, Tween::default());
+                    // positional sounds calculate their own panning
+                    if d.position.is_none() {
+                        d.handle.set_panning(panning as f64, Tween::default());
+                    }
                     d.panning = panning;
                 });
             }
             InstanceId::Local(id) => {
                 if let Some(data) = list.iter_mut().find(|d| d.id == id) {
-                    data.handle.set_panning(panning as f64, Tween::default());
+                    if data.position.is_none() {
+                        data.handle.set_panning(panning as f64, Tween::default());
+                    }
                     data.panning = panning;
                 }
             }
@@ -311,6 +483,69 @@ impl Manager {
         })
     }
 
+    pub fn seek_sound(&mut self, instance: SoundInstance, secs: f32) {
+        let position = secs.max(0.0) as f64;
+        self.for_each_instance(instance, |d| d.handle.seek_to(position));
+    }
+
+    pub fn set_sound_loop_region(&mut self, instance: SoundInstance, region: Option<(f32, f32)>) {
+        let region = region.map(loop_region);
+        self.for_each_instance(instance, |d| d.handle.set_loop_region(region));
+    }
+
+    pub fn set_sound_position(&mut self, instance: SoundInstance, pos: Option<Vec2>) {
+        let listener = self.listener;
+        self.for_each_instance(instance, |d| {
+            d.position = pos;
+            match pos {
+                Some(_) => d.apply_spatial(&listener),
+                None => {
+                    // back to the values set by the user
+                    let tween = d.volume_tween();
+                    d.handle.set_volume(Volume::Amplitude(d.volume as _), tween);
+                    d.handle.set_panning(d.panning as _, Tween::default());
+                }
+            }
+        });
+    }
+
+    pub fn sound_position(&self, instance: SoundInstance) -> Option<Vec2> {
+        self.instances.get(&instance.snd.id)?.iter().find_map(|d| {
+            let check = match instance.id {
+                InstanceId::Global => true,
+                InstanceId::Local(id) => id == d.id,
+            };
+
+            check.then_some(d.position).flatten()
+        })
+    }
+
+    pub fn set_listener_position(&mut self, pos: Vec2) {
+        self.listener.position = pos;
+        self.update_spatial_sounds();
+    }
+
+    pub fn listener_position(&self) -> Vec2 {
+        self.listener.position
+    }
+
+    pub fn set_listener_range(&mut self, range: f32) {
+        self.listener.range = range.max(0.0);
+        self.update_spatial_sounds();
+    }
+
+    pub fn listener_range(&self) -> f32 {
+        self.listener.range
+    }
+
+    fn update_spatial_sounds(&mut self) {
+        let listener = self.listener;
+        self.instances
+            .values_mut()
+            .flat_map(|list| list.iter_mut())
+            .for_each(|d| d.apply_spatial(&listener));
+    }
+
     pub fn sound_progress(&self, instance: SoundInstance) -> Option<f32> {
         self.instances.get(&instance.snd.id)?.iter().find_map(|d| {
             let check = match instance.id {
@@ -319,7 +554,7 @@ impl Manager {
             };
 
             check.then(|| {
-                let duration = d.raw.duration().as_secs_f64();
+                let duration = d.source.duration().as_secs_f64();
                 let position = d.handle.position();
                 if duration.is_zero() {
                     0.0
@@ -330,36 +565,346 @@ impl Manager {
         })
     }
 
+    pub fn sound_position_secs(&self, instance: SoundInstance) -> Option<f32> {
+        self.instances.get(&instance.snd.id)?.iter().find_map(|d| {
+            let check = match instance.id {
+                InstanceId::Global => true,
+                InstanceId::Local(id) => id == d.id,
+            };
+
+            check.then(|| d.handle.position() as f32)
+        })
+    }
+
+    pub fn audio_bus(&mut self, name: &str) -> Result<AudioBus, String> {
+        if let Some(id) = self.bus_names.get(name) {
+            return Ok(AudioBus { id: *id });
+        }
+
+        self.create_bus(name, &[])
+    }
+
+    pub fn audio_bus_with_effects(
+        &mut self,
+        name: &str,
+        effects: &[AudioEffect],
+    ) -> Result<AudioBus, String> {
+        if self.bus_names.contains_key(name) {
+            return Err(format!(
+                "Audio bus '{name}' already exists, the effects must be set when the bus is created."
+            ));
+        }
+
+        self.create_bus(name, effects)
+    }
+
+    fn create_bus(&mut self, name: &str, effects: &[AudioEffect]) -> Result<AudioBus, String> {
+        let (track, effects) = create_track(&mut self.manager, effects)
+            .map_err(|e| format!("Cannot create audio bus '{name}': {}", e))?;
+
+        let id = BusId(self.next_id());
+        self.buses.insert(
+            id,
+            BusData {
+                track,
+                effects,
+                volume: 1.0,
+                muted: false,
+                paused: false,
+            },
+        );
+        self.bus_names.insert(name.to_string(), id);
+        Ok(AudioBus { id })
+    }
+
+    pub fn set_bus_volume(&mut self, bus: AudioBus, volume: f32) {
+        let Some(data) = self.buses.get_mut(&bus.id) else {
+            return;
+        };
+
+        data.volume = volume.clamp(0.0, 1.0);
+        data.apply_volume();
+    }
+
+    pub fn bus_volume(&self, bus: AudioBus) -> Option<f32> {
+        self.buses.get(&bus.id).map(|data| data.volume)
+    }
+
+    pub fn set_bus_effect(
+        &mut self,
+        bus: AudioBus,
+        index: usize,
+        effect: AudioEffect,
+    ) -> Result<(), String> {
+        let (current, handle) = self
+            .buses
+            .get_mut(&bus.id)
+            .and_then(|data| data.effects.get_mut(index))
+            .ok_or_else(|| format!("Invalid audio bus effect index {index}"))?;
+
+        handle.update(effect)?;
+        *current = effect;
+        Ok(())
+    }
+
+    pub fn set_bus_muted(&mut self, bus: AudioBus, muted: bool) {
+        let Some(data) = self.buses.get_mut(&bus.id) else {
+            return;
+        };
+
+        data.muted = muted;
+        data.apply_volume();
+    }
+
+    pub fn is_bus_muted(&self, bus: AudioBus) -> Option<bool> {
+        self.buses.get(&bus.id).map(|data| data.muted)
+    }
+
+    pub fn set_bus_paused(&mut self, bus: AudioBus, paused: bool) {
+        let Some(data) = self.buses.get_mut(&bus.id) else {
+            return;
+        };
+
+        if data.paused == paused {
+            return;
+        }
+
+        data.paused = paused;
+        let manager_paused = self.is_manager_paused();
+        let buses = &self.buses;
+        self.instances
+            .values_mut()
+            .flat_map(|list| list.iter_mut())
+            .filter(|d| d.bus == Some(bus.id))
+            .for_each(|d| {
+                let held = is_held(buses, manager_paused, d);
+                d.apply_pause(held);
+            });
+    }
+
+    pub fn is_bus_paused(&self, bus: AudioBus) -> Option<bool> {
+        self.buses.get(&bus.id).map(|data| data.paused)
+    }
+
+    fn for_each_instance<F>(&mut self, instance: SoundInstance, mut cb: F)
+    where
+        F: FnMut(&mut InstanceData),
+    {
+        let Some(list) = self.instances.get_mut(&instance.snd.id) else {
+            return;
+        };
+
+        match instance.id {
+            InstanceId::Global => list.iter_mut().for_each(cb),
+            InstanceId::Local(id) => {
+                if let Some(data) = list.iter_mut().find(|d| d.id == id) {
+                    cb(data);
+                }
+            }
+        }
+    }
+
     fn next_id(&mut self) -> u64 {
         let id = self.count_ids;
         self.count_ids += 1;
         id
     }
 
+    pub fn set_max_instances(&mut self, sound: SoundId, limit: Option<(usize, VoiceStealing)>) {
+        match limit {
+            Some(limit) => self.voice_limits.insert(sound, limit),
+            None => self.voice_limits.remove(&sound),
+        };
+    }
+
+    pub fn is_unlocked(&self) -> bool {
+        self.unlocked
+    }
+
+    // the audio context created before the user interaction stays suspended, so a new
+    // manager is created and the buses and sounds requested until now are moved to it
+    #[cfg(target_arch = "wasm32")]
+    fn unlock(&mut self) {
+        self.manager = match create_audio_manager() {
+            Ok(manager) => manager,
+            Err(e) => {
+                log::error!("{}", e);
+                return;
+            }
+        };
+        self.unlocked = true;
+
+        for data in self.buses.values_mut() {
+            let effects = data
+                .effects
+                .iter()
+                .map(|(effect, _)| *effect)
+                .collect::<Vec<_>>();
+            match create_track(&mut self.manager, &effects) {
+                Ok((track, effects)) => {
+                    data.track = track;
+                    data.effects = effects;
+                    data.apply_volume();
+                }
+                Err(e) => log::error!("Cannot restore audio bus: {}", e),
+            }
+        }
+
+        self.set_volume(self.volume);
+
+        // play_sound holds the pending sounds if the manager is paused
+        std::mem::take(&mut self.pending)
+            .into_iter()
+            .for_each(|(instance, opts)| self.play_sound(instance, opts));
+    }
+
     pub fn clean(&mut self) {
-        self.instances.retain(|_, v| {
-            v.retain(|d| !d.is_stopped());
+        #[cfg(target_arch = "wasm32")]
+        if !self.unlocked && has_user_interaction() {
+            self.unlock();
+        }
+
+        let was_paused = self.is_manager_paused();
+        // without a window (headless, external surfaces) there is no focus to lose
+        self.focus_lost = self.pause_on_focus_lost
+            && corelib::app::has_window()
+            && !corelib::app::is_window_focused();
+        self.apply_pause(was_paused);
+
+        // the events are available during the next frame
+        self.finished.clear();
+        let finished = &mut self.finished;
+        self.instances.retain(|sound, v| {
+            v.retain(|d| {
+                let stopped = d.is_stopped();
+                if stopped {
+                    finished.push(SoundFinishedEvent {
+                        sound: *sound,
+                        instance: d.id,
+                    });
+                }
+                !stopped
+            });
             !v.is_empty()
         });
     }
 }
 
-fn create_sound_from_bytes(id: u64, bytes: &[u8]) -> Result<Sound, String> {
-    let raw = StaticSoundData::from_cursor(std::io::Cursor::new(bytes.to_vec()))
-        .map_err(|e| e.to_string())?;
+// stops an instance if the limit is reached, returns false if the new one must be ignored
+fn make_room(
+    list: &mut [InstanceData],
+    id: u64,
+    max: usize,
+    policy: VoiceStealing,
+    listener: &Listener,
+) -> bool {
+    // restarting an instance that is still playing doesn't add a voice
+    if list.iter().any(|d| d.id == id && d.is_voice()) {
+        return true;
+    }
+
+    let voices = || list.iter().filter(|d| d.is_voice());
+    if voices().count() < max.max(1) {
+        return true;
+    }
+
+    let stolen = match policy {
+        VoiceStealing::Oldest => voices().map(|d| d.id).next(),
+        VoiceStealing::Quietest => voices()
+            .min_by(|a, b| a.loudness(listener).total_cmp(&b.loudness(listener)))
+            .map(|d| d.id),
+        VoiceStealing::Reject => return false,
+    };
+
+    if let Some(data) = stolen.and_then(|stolen| list.iter_mut().find(|d| d.id == stolen)) {
+        data.handle.stop(Tween {
+            duration: Duration::from_millis(10),
+            ..Default::default()
+        });
+    }
 
-    Ok(Sound {
-        id: SoundId(id),
-        raw,
+    true
+}
+
+fn create_audio_manager() -> Result<AudioManager, String> {
+    AudioManager::<DefaultBackend>::new(AudioManagerSettings::default())
+        .map_err(|e| format!("Cannot initialize audio backend: {:?}", e.to_string()))
+}
+
+fn create_track(
+    manager: &mut AudioManager,
+    effects: &[AudioEffect],
+) -> Result<(TrackHandle, Vec<(AudioEffect, EffectHandle)>), String> {
+    let mut builder = TrackBuilder::new();
+    let effects = effects
+        .iter()
+        .map(|effect| (*effect, effect.add_to(&mut builder)))
+        .collect();
+
+    let track = manager.add_sub_track(builder).map_err(|e| e.to_string())?;
+    Ok((track, effects))
+}
+
+// the browsers allow to start the audio after a key or mouse button interaction
+#[cfg(target_arch = "wasm32")]
+fn has_user_interaction() -> bool {
+    use corelib::input::{input_events, InputEventKind};
+
+    input_events().iter().any(|evt| {
+        matches!(
+            evt.kind,
+            InputEventKind::KeyPressed(_)
+                | InputEventKind::KeyReleased(_)
+                | InputEventKind::MouseBtnPressed(_)
+                | InputEventKind::MouseBtnReleased(_)
+        )
     })
 }
 
+fn loop_region((start, end): (f32, f32)) -> Region {
+    (start.max(0.0) as f64..end.max(start) as f64).into()
+}
+
+fn sound_settings(
+    buses: &FxHashMap<BusId, BusData>,
+    listener: &Listener,
+    opts: PlayOptions,
+) -> StaticSoundSettings {
+    let mut settings: StaticSoundSettings = opts.into();
+    if let Some(pos) = opts.position {
+        let (attenuation, panning) = listener.spatial(pos);
+        settings.volume = Volume::Amplitude((opts.volume * attenuation) as _).into();
+        settings.panning = (panning as f64).into();
+    }
+
+    if let Some(data) = opts.bus.and_then(|id| buses.get(&id)) {
+        settings.output_destination = (&data.track).into();
+    }
+    settings
+}
+
+// sounds played on a paused bus or while the manager is paused wait until they are resumed
+fn is_held(buses: &FxHashMap<BusId, BusData>, manager_paused: bool, data: &InstanceData) -> bool {
+    let bus_paused = data
+        .bus
+        .and_then(|id| buses.get(&id))
+        .is_some_and(|bus| bus.paused);
+
+    data.paused || bus_paused || manager_paused
+}
+
 #[derive(Copy, Clone)]
 pub(crate) struct PlayOptions {
     pub volume: f32,
     pub repeat: bool,
     pub pitch: f32,
     pub panning: f32,
+    pub delay: Option<Duration>,
+    pub fade_in: Option<Duration>,
+    pub start_at: f64,
+    pub loop_region: Option<(f32, f32)>,
+    pub position: Option<Vec2>,
+    pub bus: Option<BusId>,
 }
 
 impl Default for PlayOptions {
@@ -369,6 +914,12 @@ impl Default for PlayOptions {
             repeat: false,
             pitch: 1.0,
             panning: 0.5,
+            delay: None,
+            fade_in: None,
+            start_at: 0.0,
+            loop_region: None,
+            position: None,
+            bus: None,
         }
     }
 }
@@ -376,15 +927,21 @@ impl Default for PlayOptions {
 impl From<PlayOptions> for StaticSoundSettings {
     fn from(value: PlayOptions) -> Self {
         Self {
-            start_time: Default::default(),
-            start_position: Default::default(),
-            loop_region: value.repeat.then_some((..).into()),
+            start_time: value.delay.map_or(StartTime::Immediate, StartTime::Delayed),
+            start_position: value.start_at.into(),
+            loop_region: value
+                .loop_region
+                .map(loop_region)
+                .or_else(|| value.repeat.then_some((..).into())),
             reverse: false,
             volume: Volume::Amplitude(value.volume as _).into(),
             playback_rate: PlaybackRate::Factor(value.pitch as _).into(),
             panning: (value.panning as f64).into(),
             output_destination: Default::default(),
-            fade_in_tween: None,
+            fade_in_tween: value.fade_in.map(|duration| Tween {
+                duration,
+                ..Default::default()
+            }),
         }
     }
 }
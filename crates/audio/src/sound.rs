@@ -1,4 +1,5 @@
-use kira::sound::static_sound::StaticSoundData;
+use crate::source::SoundSource;
+use std::time::Duration;
 
 #[derive(Clone)]
 pub struct SoundInstance {
@@ -26,16 +27,47 @@ impl AsSoundInstance for SoundInstance {
 #[derive(Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Debug)]
 pub struct SoundId(pub(crate) u64);
 
+/// What to do when a sound reaches its max number of instances playing
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub enum VoiceStealing {
+    /// Stops the instance that started first
+    #[default]
+    Oldest,
+    /// Stops the instance with the lowest volume
+    Quietest,
+    /// Ignores the new instance
+    Reject,
+}
+
 #[derive(Clone)]
 pub struct Sound {
     pub(crate) id: SoundId,
-    pub(crate) raw: StaticSoundData,
+    pub(crate) source: SoundSource,
 }
 
 impl Sound {
     pub fn id(&self) -> SoundId {
         self.id
     }
+
+    /// Returns if the sound is decoded while it plays instead of being decoded in memory
+    /// `Web`: Always false
+    pub fn is_streaming(&self) -> bool {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            matches!(self.source, SoundSource::Streaming { .. })
+        }
+
+        #[cfg(target_arch = "wasm32")]
+        {
+            false
+        }
+    }
+
+    /// Duration of the sound
+    pub fn duration(&self) -> Duration {
+        self.source.duration()
+    }
 }
 
 impl PartialEq<Self> for Sound {
@@ -0,0 +1,207 @@
+use kira::manager::AudioManager;
+use kira::sound::static_sound::{StaticSoundData, StaticSoundHandle, StaticSoundSettings};
+#[cfg(not(target_arch = "wasm32"))]
+use kira::sound::streaming::{StreamingSoundData, StreamingSoundHandle, StreamingSoundSettings};
+#[cfg(not(target_arch = "wasm32"))]
+use kira::sound::FromFileError;
+use kira::sound::{PlaybackRate, PlaybackState, Region};
+use kira::tween::Tween;
+use kira::{Frame, Volume};
+use std::io::Cursor;
+use std::sync::Arc;
+use std::time::Duration;
+
+// most sounds are static, boxing them would add an allocation to each one
+#[allow(clippy::large_enum_variant)]
+#[derive(Clone)]
+pub(crate) enum SoundSource {
+    /// Decoded in memory
+    Static(StaticSoundData),
+    /// Encoded in memory, a decoder is created each time the sound is played
+    #[cfg(not(target_arch = "wasm32"))]
+    Streaming {
+        bytes: Arc<[u8]>,
+        duration: Duration,
+    },
+}
+
+impl SoundSource {
+    pub fn from_static_bytes(bytes: &[u8]) -> Result<Self, String> {
+        let raw =
+            StaticSoundData::from_cursor(Cursor::new(bytes.to_vec())).map_err(|e| e.to_string())?;
+        Ok(Self::Static(raw))
+    }
+
+    /// Creates the sound from interleaved samples (mono or stereo)
+    pub fn from_samples(samples: &[f32], sample_rate: u32, channels: u16) -> Result<Self, String> {
+        if sample_rate == 0 {
+            return Err("The sample rate must be greater than 0".to_string());
+        }
+
+        let frames: Arc<[Frame]> = match channels {
+            1 => samples.iter().map(|s| Frame::from_mono(*s)).collect(),
+            2 => samples
+                .chunks_exact(2)
+                .map(|s| Frame::new(s[0], s[1]))
+                .collect(),
+            _ => {
+                return Err(format!(
+                    "Only mono and stereo samples are supported, found {channels} channels"
+                ))
+            }
+        };
+
+        Ok(Self::Static(StaticSoundData {
+            sample_rate,
+            frames,
+            settings: StaticSoundSettings::default(),
+            slice: None,
+        }))
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn from_streaming_bytes(bytes: &[u8]) -> Result<Self, String> {
+        let bytes: Arc<[u8]> = Arc::from(bytes);
+        // check that the data can be decoded before storing it
+        let duration = streaming_data(&bytes)?.duration();
+        Ok(Self::Streaming { bytes, duration })
+    }
+
+    // streaming sounds need a decoding thread, not available on wasm32
+    #[cfg(target_arch = "wasm32")]
+    pub fn from_streaming_bytes(bytes: &[u8]) -> Result<Self, String> {
+        Self::from_static_bytes(bytes)
+    }
+
+    pub fn duration(&self) -> Duration {
+        match self {
+            Self::Static(raw) => raw.duration(),
+            #[cfg(not(target_arch = "wasm32"))]
+            Self::Streaming { duration, .. } => *duration,
+        }
+    }
+
+    pub fn play(
+        &self,
+        manager: &mut AudioManager,
+        settings: StaticSoundSettings,
+    ) -> Result<SoundHandle, String> {
+        match self {
+            Self::Static(raw) => manager
+                .play(raw.with_settings(settings))
+                .map(SoundHandle::Static)
+                .map_err(|e| e.to_string()),
+            #[cfg(not(target_arch = "wasm32"))]
+            Self::Streaming { bytes, .. } => {
+                let settings = StreamingSoundSettings {
+                    start_time: settings.start_time,
+                    start_position: settings.start_position,
+                    loop_region: settings.loop_region,
+                    volume: settings.volume,
+                    playback_rate: settings.playback_rate,
+                    panning: settings.panning,
+                    output_destination: settings.output_destination,
+                    fade_in_tween: settings.fade_in_tween,
+                };
+
+                manager
+                    .play(streaming_data(bytes)?.with_settings(settings))
+                    .map(SoundHandle::Streaming)
+                    .map_err(|e| e.to_string())
+            }
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn streaming_data(bytes: &Arc<[u8]>) -> Result<StreamingSoundData<FromFileError>, String> {
+    StreamingSoundData::from_cursor(Cursor::new(bytes.clone())).map_err(|e| e.to_string())
+}
+
+pub(crate) enum SoundHandle {
+    Static(StaticSoundHandle),
+    #[cfg(not(target_arch = "wasm32"))]
+    Streaming(StreamingSoundHandle<FromFileError>),
+}
+
+impl SoundHandle {
+    pub fn state(&self) -> PlaybackState {
+        match self {
+            Self::Static(h) => h.state(),
+            #[cfg(not(target_arch = "wasm32"))]
+            Self::Streaming(h) => h.state(),
+        }
+    }
+
+    pub fn position(&self) -> f64 {
+        match self {
+            Self::Static(h) => h.position(),
+            #[cfg(not(target_arch = "wasm32"))]
+            Self::Streaming(h) => h.position(),
+        }
+    }
+
+    pub fn pause(&mut self, tween: Tween) {
+        match self {
+            Self::Static(h) => h.pause(tween),
+            #[cfg(not(target_arch = "wasm32"))]
+            Self::Streaming(h) => h.pause(tween),
+        }
+    }
+
+    pub fn resume(&mut self, tween: Tween) {
+        match self {
+            Self::Static(h) => h.resume(tween),
+            #[cfg(not(target_arch = "wasm32"))]
+            Self::Streaming(h) => h.resume(tween),
+        }
+    }
+
+    pub fn stop(&mut self, tween: Tween) {
+        match self {
+            Self::Static(h) => h.stop(tween),
+            #[cfg(not(target_arch = "wasm32"))]
+            Self::Streaming(h) => h.stop(tween),
+        }
+    }
+
+    pub fn set_volume(&mut self, volume: Volume, tween: Tween) {
+        match self {
+            Self::Static(h) => h.set_volume(volume, tween),
+            #[cfg(not(target_arch = "wasm32"))]
+            Self::Streaming(h) => h.set_volume(volume, tween),
+        }
+    }
+
+    pub fn set_playback_rate(&mut self, rate: PlaybackRate, tween: Tween) {
+        match self {
+            Self::Static(h) => h.set_playback_rate(rate, tween),
+            #[cfg(not(target_arch = "wasm32"))]
+            Self::Streaming(h) => h.set_playback_rate(rate, tween),
+        }
+    }
+
+    pub fn seek_to(&mut self, position: f64) {
+        match self {
+            Self::Static(h) => h.seek_to(position),
+            #[cfg(not(target_arch = "wasm32"))]
+            Self::Streaming(h) => h.seek_to(position),
+        }
+    }
+
+    pub fn set_loop_region(&mut self, region: Option<Region>) {
+        match self {
+            Self::Static(h) => h.set_loop_region(region),
+            #[cfg(not(target_arch = "wasm32"))]
+            Self::Streaming(h) => h.set_loop_region(region),
+        }
+    }
+
+    pub fn set_panning(&mut self, panning: f64, tween: Tween) {
+        match self {
+            Self::Static(h) => h.set_panning(panning, tween),
+            #[cfg(not(target_arch = "wasm32"))]
+            Self::Streaming(h) => h.set_panning(panning, tween),
+        }
+    }
+}
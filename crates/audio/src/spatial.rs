@@ -0,0 +1,51 @@
+use corelib::math::Vec2;
+
+/// Position from where the positional sounds are heard
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct Listener {
+    pub position: Vec2,
+    pub range: f32,
+}
+
+impl Default for Listener {
+    fn default() -> Self {
+        Self {
+            position: Vec2::ZERO,
+            range: 1000.0,
+        }
+    }
+}
+
+impl Listener {
+    /// Returns the volume attenuation and the panning for a sound at `pos`
+    pub fn spatial(&self, pos: Vec2) -> (f32, f32) {
+        if self.range <= 0.0 {
+            return (0.0, 0.5);
+        }
+
+        let offset = pos - self.position;
+        let attenuation = (1.0 - offset.length() / self.range).clamp(0.0, 1.0);
+        let panning = 0.5 + (offset.x / self.range).clamp(-1.0, 1.0) * 0.5;
+        (attenuation, panning)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use corelib::math::vec2;
+
+    #[test]
+    fn test_spatial() {
+        let listener = Listener {
+            position: vec2(100.0, 100.0),
+            range: 100.0,
+        };
+
+        assert_eq!(listener.spatial(vec2(100.0, 100.0)), (1.0, 0.5));
+        assert_eq!(listener.spatial(vec2(150.0, 100.0)), (0.5, 0.75));
+        assert_eq!(listener.spatial(vec2(50.0, 100.0)), (0.5, 0.25));
+        assert_eq!(listener.spatial(vec2(100.0, 300.0)), (0.0, 0.5));
+        assert_eq!(listener.spatial(vec2(400.0, 100.0)), (0.0, 1.0));
+    }
+}
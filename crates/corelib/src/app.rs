@@ -11,6 +11,12 @@ pub use logger::LogConfig;
 
 use crate::events::{CoreEvent, CORE_EVENTS_MAP};
 
+#[cfg(target_os = "android")]
+pub use winit::platform::android::activity::AndroidApp;
+
+#[cfg(target_os = "android")]
+static ANDROID_APP: once_cell::sync::OnceCell<AndroidApp> = once_cell::sync::OnceCell::new();
+
 // -- Window section
 
 /// Return the window's title
@@ -70,11 +76,26 @@ pub fn is_window_fullscreen() -> bool {
 }
 
 /// Swap between fullscreen/windowed mode
+/// The fullscreen mode used is `FullscreenMode::Borderless`
 #[inline]
 pub fn toggle_fullscreen() {
     get_mut_backend().toggle_fullscreen()
 }
 
+/// Returns the fullscreen mode in use, `None` if the window is not in fullscreen
+/// `Web`: Always returns `FullscreenMode::Borderless` in fullscreen
+#[inline]
+pub fn window_fullscreen_mode() -> Option<FullscreenMode> {
+    get_backend().fullscreen_mode()
+}
+
+/// Set the fullscreen mode, `None` goes back to windowed mode
+/// `Web`: Both modes use the browser's fullscreen API
+#[inline]
+pub fn set_window_fullscreen(mode: Option<FullscreenMode>) {
+    get_mut_backend().set_fullscreen(mode)
+}
+
 /// Return window's resolution (based on the screen dpi)
 #[inline]
 pub fn window_dpi_scale() -> f32 {
@@ -93,6 +114,12 @@ pub fn set_window_position(x: f32, y: f32) {
     get_mut_backend().set_position(x, y);
 }
 
+/// Returns if the app has a window, headless apps and external surfaces have none
+#[inline]
+pub fn has_window() -> bool {
+    get_backend().has_window()
+}
+
 /// Returns if the window is right now focused
 #[inline]
 pub fn is_window_focused() -> bool {
@@ -117,6 +144,44 @@ pub fn is_window_pixelated() -> bool {
     get_backend().is_pixelated()
 }
 
+/// Makes the window's background transparent, the alpha of the clear color is kept
+#[inline]
+pub fn set_window_transparent(transparent: bool) {
+    get_mut_backend().set_transparent(transparent);
+}
+
+/// Return if the window's background is transparent
+#[inline]
+pub fn is_window_transparent() -> bool {
+    get_backend().is_transparent()
+}
+
+/// Keeps the window above the rest of windows
+/// `Web`: Does nothing
+#[inline]
+pub fn set_window_always_on_top(always_on_top: bool) {
+    get_mut_backend().set_always_on_top(always_on_top);
+}
+
+/// Return if the window is kept above the rest of windows
+#[inline]
+pub fn is_window_always_on_top() -> bool {
+    get_backend().is_always_on_top()
+}
+
+/// Lets the mouse events pass through the window to whatever is below
+/// `Web`: Does nothing
+#[inline]
+pub fn set_window_click_through(click_through: bool) {
+    get_mut_backend().set_click_through(click_through);
+}
+
+/// Return if the mouse events pass through the window
+#[inline]
+pub fn is_window_click_through() -> bool {
+    get_backend().is_click_through()
+}
+
 /// Close the window
 /// `Web`: Stops the `requestAnimationFrame`
 #[inline]
@@ -131,6 +196,31 @@ pub fn screen_size() -> Vec2 {
     get_backend().screen_size()
 }
 
+// - Android
+
+/// Set the `AndroidApp` received by `android_main`, it must be set before running the app.
+/// The game must be built as a `cdylib` with an entry point like:
+/// ```ignore
+/// #[no_mangle]
+/// fn android_main(app: AndroidApp) {
+///     set_android_app(app);
+///     main();
+/// }
+/// ```
+#[cfg(target_os = "android")]
+pub fn set_android_app(app: AndroidApp) {
+    if ANDROID_APP.set(app).is_err() {
+        log::warn!("The AndroidApp is already set");
+    }
+}
+
+/// Returns the `AndroidApp` set with `set_android_app`
+#[cfg(target_os = "android")]
+#[inline]
+pub fn android_app() -> Option<AndroidApp> {
+    ANDROID_APP.get().cloned()
+}
+
 // - Core events
 /// Callback executed after the init.
 /// This is primarily intended for use by plugins, not by the end user.
@@ -157,6 +247,30 @@ pub fn on_sys_post_update<F: Fn() + Send + Sync + 'static>(cb: F) {
         .insert(CoreEvent::PostUpdate, cb);
 }
 
+/// Callback executed when the app goes to background and the surface is lost (mobile).
+/// This is primarily intended for use by plugins, not by the end user.
+#[inline]
+pub fn on_sys_suspend<F: Fn() + Send + Sync + 'static>(cb: F) {
+    CORE_EVENTS_MAP.borrow_mut().insert(CoreEvent::Suspend, cb);
+}
+
+/// Callback executed when the app comes back from background and the surface is recreated (mobile).
+/// This is primarily intended for use by plugins, not by the end user.
+#[inline]
+pub fn on_sys_resume<F: Fn() + Send + Sync + 'static>(cb: F) {
+    CORE_EVENTS_MAP.borrow_mut().insert(CoreEvent::Resume, cb);
+}
+
+/// Callback executed when the device orientation changes.
+/// This is primarily intended for use by plugins, not by the end user.
+/// `Native`: Does nothing
+#[inline]
+pub fn on_sys_orientation_change<F: Fn() + Send + Sync + 'static>(cb: F) {
+    CORE_EVENTS_MAP
+        .borrow_mut()
+        .insert(CoreEvent::OrientationChange, cb);
+}
+
 /// Callback executed after the cleanup callback.
 /// This is primarily intended for use by plugins, not by the end user.
 #[inline]
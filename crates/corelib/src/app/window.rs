@@ -1,5 +1,49 @@
 use crate::math::{uvec2, UVec2};
 
+/// How the canvas is sized inside its parent element
+/// `Native`: Does nothing
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub enum CanvasFit {
+    /// Uses the window size
+    #[default]
+    Fixed,
+    /// Uses the parent's size
+    FillParent,
+    /// Uses the biggest size that fits the parent keeping the aspect ratio of the window size,
+    /// the canvas is centered leaving empty bars on the sides
+    Letterbox,
+}
+
+impl CanvasFit {
+    /// Returns the canvas size and the offset inside the parent
+    pub fn fit(&self, size: UVec2, parent: UVec2) -> (UVec2, UVec2) {
+        match self {
+            CanvasFit::Fixed => (size, UVec2::ZERO),
+            CanvasFit::FillParent => (parent, UVec2::ZERO),
+            CanvasFit::Letterbox => {
+                if size.x == 0 || size.y == 0 {
+                    return (parent, UVec2::ZERO);
+                }
+
+                let scale = (parent.x as f32 / size.x as f32).min(parent.y as f32 / size.y as f32);
+                let fitted = (size.as_vec2() * scale).floor().as_uvec2();
+                (fitted, (parent - fitted) / 2)
+            }
+        }
+    }
+}
+
+/// How the window fills the screen when it's in fullscreen mode
+/// `Web`: Both use the browser's fullscreen API
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub enum FullscreenMode {
+    /// A borderless window with the size of the monitor, switching between apps is fast
+    #[default]
+    Borderless,
+    /// Takes exclusive control of the monitor using its best video mode
+    Exclusive,
+}
+
 #[derive(Debug)]
 pub struct WindowConfig {
     pub title: String,
@@ -11,6 +55,11 @@ pub struct WindowConfig {
     pub vsync: bool,
     pub max_fps: Option<u8>,
     pub pixelated: bool,
+    pub canvas_fit: CanvasFit,
+    pub max_dpi: Option<f32>,
+    pub transparent: bool,
+    pub always_on_top: bool,
+    pub click_through: bool,
 }
 
 impl Default for WindowConfig {
@@ -25,6 +74,11 @@ impl Default for WindowConfig {
             vsync: true,
             max_fps: None,
             pixelated: false,
+            canvas_fit: CanvasFit::Fixed,
+            max_dpi: None,
+            transparent: false,
+            always_on_top: false,
+            click_through: false,
         }
     }
 }
@@ -84,4 +138,62 @@ impl WindowConfig {
         self.pixelated = pixelated;
         self
     }
+
+    /// How the canvas fits its parent, the canvas is fitted again on resize and orientation changes
+    /// `Native`: Does nothing
+    pub fn canvas_fit(mut self, fit: CanvasFit) -> Self {
+        self.canvas_fit = fit;
+        self
+    }
+
+    /// Renders the canvas at the device resolution using the devicePixelRatio, capped to `dpi`
+    /// `Native`: Does nothing
+    pub fn max_dpi(mut self, dpi: f32) -> Self {
+        self.max_dpi = Some(dpi.max(1.0));
+        self
+    }
+
+    /// Allows the window's background to be transparent, the alpha of the clear color is kept
+    pub fn transparent(mut self, transparent: bool) -> Self {
+        self.transparent = transparent;
+        self
+    }
+
+    /// Keeps the window above the rest of windows
+    /// `Web`: Does nothing
+    pub fn always_on_top(mut self, always_on_top: bool) -> Self {
+        self.always_on_top = always_on_top;
+        self
+    }
+
+    /// Lets the mouse events pass through the window to whatever is below
+    /// `Web`: Does nothing
+    pub fn click_through(mut self, click_through: bool) -> Self {
+        self.click_through = click_through;
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_canvas_fit() {
+        let size = uvec2(800, 600);
+        let parent = uvec2(1000, 600);
+        assert_eq!(CanvasFit::Fixed.fit(size, parent), (size, UVec2::ZERO));
+        assert_eq!(
+            CanvasFit::FillParent.fit(size, parent),
+            (parent, UVec2::ZERO)
+        );
+        assert_eq!(
+            CanvasFit::Letterbox.fit(size, parent),
+            (uvec2(800, 600), uvec2(100, 0))
+        );
+        assert_eq!(
+            CanvasFit::Letterbox.fit(size, uvec2(400, 1000)),
+            (uvec2(400, 300), uvec2(0, 350))
+        );
+    }
 }
@@ -1,4 +1,8 @@
-use crate::input::{GamepadAxis, GamepadButton, GamepadState};
+use crate::input::{
+    GamepadAxis, GamepadButton, GamepadId as GkGamepadId, GamepadState, InputEventKind,
+    InputEventList,
+};
+use crate::time::{self, Instant};
 use crossbeam_channel::{unbounded, Receiver, Sender};
 use gilrs::{Axis, Button, Event, EventType, GamepadId, Gilrs};
 use heapless::FnvIndexMap;
@@ -49,10 +53,14 @@ impl Default for GilrsBackend {
 }
 
 impl GilrsBackend {
-    pub fn tick(&mut self) {
+    pub fn tick(&mut self, events: &mut InputEventList) {
         self.state.tick();
 
-        while let Ok(Event { id, event, .. }) = self.rx.try_recv() {
+        while let Ok(Event {
+            id, event, time, ..
+        }) = self.rx.try_recv()
+        {
+            let time = event_instant(time);
             match event {
                 EventType::ButtonPressed(btn, _) => {
                     debug_assert!(
@@ -60,10 +68,18 @@ impl GilrsBackend {
                         "Gamepad '{}' not registered?",
                         id
                     );
-                    let gp = self.ids.get(&id).and_then(|&id| self.state.get_mut(id));
+                    let gp = self
+                        .ids
+                        .get(&id)
+                        .and_then(|&id| self.state.get_mut(id).map(|gp| (id, gp)));
 
-                    if let Some(gp) = gp {
-                        gp.press(button_cast(btn));
+                    if let Some((gp_id, gp)) = gp {
+                        let btn = button_cast(btn);
+                        gp.press(btn);
+                        events.push(
+                            time,
+                            InputEventKind::GamepadBtnPressed(GkGamepadId(gp_id), btn),
+                        );
                     }
                 }
                 EventType::ButtonRepeated(_, _) => {}
@@ -73,10 +89,18 @@ impl GilrsBackend {
                         "Gamepad '{}' not registered?",
                         id
                     );
-                    let gp = self.ids.get(&id).and_then(|&id| self.state.get_mut(id));
+                    let gp = self
+                        .ids
+                        .get(&id)
+                        .and_then(|&id| self.state.get_mut(id).map(|gp| (id, gp)));
 
-                    if let Some(gp) = gp {
-                        gp.release(button_cast(btn));
+                    if let Some((gp_id, gp)) = gp {
+                        let btn = button_cast(btn);
+                        gp.release(btn);
+                        events.push(
+                            time,
+                            InputEventKind::GamepadBtnReleased(GkGamepadId(gp_id), btn),
+                        );
                     }
                 }
                 EventType::ButtonChanged(_, _, _) => {}
@@ -86,10 +110,18 @@ impl GilrsBackend {
                         "Gamepad '{}' not registered?",
                         id
                     );
-                    let gp = self.ids.get(&id).and_then(|&id| self.state.get_mut(id));
+                    let gp = self
+                        .ids
+                        .get(&id)
+                        .and_then(|&id| self.state.get_mut(id).map(|gp| (id, gp)));
 
-                    if let Some(gp) = gp {
-                        gp.set_axis_strength(axis_cast(axis), strength);
+                    if let Some((gp_id, gp)) = gp {
+                        let axis = axis_cast(axis);
+                        gp.set_axis_strength(axis, strength);
+                        events.push(
+                            time,
+                            InputEventKind::GamepadAxisChanged(GkGamepadId(gp_id), axis, strength),
+                        );
                     }
                 }
                 EventType::Connected => {
@@ -99,6 +131,9 @@ impl GilrsBackend {
                         self.id_count += 1;
                         next_id
                     });
+                    if let Err(err) = self.ids.insert(id, current_id) {
+                        log::error!("Cannot register gamepad '{}': {:?}", id, err);
+                    }
                     self.state.add(current_id);
                 }
                 EventType::Disconnected => {
@@ -119,6 +154,14 @@ impl GilrsBackend {
     }
 }
 
+// gilrs uses SystemTime, the events are converted to Instant to compare them with the rest
+fn event_instant(event_time: std::time::SystemTime) -> Instant {
+    let elapsed = std::time::SystemTime::now()
+        .duration_since(event_time)
+        .unwrap_or_default();
+    time::now().checked_sub(elapsed).unwrap_or_else(time::now)
+}
+
 fn button_cast(btn: Button) -> GamepadButton {
     match btn {
         Button::South => GamepadButton::South,
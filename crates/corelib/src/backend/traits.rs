@@ -1,5 +1,6 @@
+use crate::app::FullscreenMode;
 use crate::gfx::{BindGroup, BindGroupDescriptor, Buffer, BufferDescriptor, Limits, RenderPipeline, RenderPipelineDescriptor, RenderTexture, RenderTextureDescriptor, Renderer, Sampler, SamplerDescriptor, Texture, TextureData, TextureDescriptor, GpuStats};
-use crate::input::{KeyboardState, MouseState};
+use crate::input::{InputEventList, KeyboardState, MouseState};
 use crate::math::UVec2;
 use crate::math::Vec2;
 use winit::raw_window_handle::{HasDisplayHandle, HasWindowHandle};
@@ -18,13 +19,22 @@ pub(crate) trait BackendImpl<G: GfxBackendImpl> {
     fn screen_size(&self) -> Vec2;
     fn is_fullscreen(&self) -> bool;
     fn toggle_fullscreen(&mut self);
+    fn fullscreen_mode(&self) -> Option<FullscreenMode>;
+    fn set_fullscreen(&mut self, mode: Option<FullscreenMode>);
     fn dpi(&self) -> f32;
     fn position(&self) -> Vec2;
     fn set_position(&mut self, x: f32, y: f32);
+    fn has_window(&self) -> bool;
     fn is_focused(&self) -> bool;
     fn is_maximized(&self) -> bool;
     fn is_minimized(&self) -> bool;
     fn is_pixelated(&self) -> bool;
+    fn set_transparent(&mut self, transparent: bool);
+    fn is_transparent(&self) -> bool;
+    fn set_always_on_top(&mut self, always_on_top: bool);
+    fn is_always_on_top(&self) -> bool;
+    fn set_click_through(&mut self, click_through: bool);
+    fn is_click_through(&self) -> bool;
     fn close(&mut self);
 
     // input
@@ -35,6 +45,7 @@ pub(crate) trait BackendImpl<G: GfxBackendImpl> {
     fn is_cursor_visible(&self) -> bool;
 
     fn keyboard_state(&self) -> &KeyboardState;
+    fn input_events(&self) -> &InputEventList;
 
     #[cfg(feature = "gamepad")]
     fn gamepad_state(&self) -> &GamepadState;
@@ -2,6 +2,7 @@
 
 use crate::input::{KeyCode, MouseButton};
 use crate::math::{UVec2, Vec2};
+use crate::time::{self, Instant};
 use smol_str::SmolStr;
 use std::collections::VecDeque;
 
@@ -17,11 +18,12 @@ pub enum Event {
     KeyDown { key: KeyCode },
     CharReceived { text: SmolStr },
     WindowResize { size: UVec2 },
+    OrientationChange,
 }
 
-/// Event iterator queue
+/// Event iterator queue, the events keep the time they were received
 #[derive(Debug, Clone, Default)]
-pub struct EventIterator(VecDeque<Event>);
+pub struct EventIterator(VecDeque<(Instant, Event)>);
 
 impl EventIterator {
     pub fn new() -> Self {
@@ -29,18 +31,18 @@ impl EventIterator {
     }
 
     /// Remove and return the first element on the queue
-    pub fn pop_front(&mut self) -> Option<Event> {
+    pub fn pop_front(&mut self) -> Option<(Instant, Event)> {
         self.0.pop_front()
     }
 
     /// Add an event at the end of the list
     pub fn push(&mut self, evt: Event) {
-        self.0.push_back(evt);
+        self.0.push_back((time::now(), evt));
     }
 
     /// Add an event at the beginning of the list
     pub fn push_front(&mut self, evt: Event) {
-        self.0.push_front(evt);
+        self.0.push_front((time::now(), evt));
     }
 
     /// Return the events and clear the list
@@ -50,7 +52,7 @@ impl EventIterator {
 }
 
 impl Iterator for EventIterator {
-    type Item = Event;
+    type Item = (Instant, Event);
 
     fn next(&mut self) -> Option<Self::Item> {
         self.pop_front()
@@ -1,11 +1,12 @@
 use super::events::{Event, EventIterator};
 use super::utils::{
     canvas_add_event_listener, canvas_position_from_global, document_add_event_listener,
-    set_size_dpi, window_add_event_listener,
+    parent_size, set_canvas_offset, set_size_dpi, window_add_event_listener,
 };
 use super::window::WebWindow;
+use crate::app::CanvasFit;
 use crate::input::{KeyCode, MouseButton};
-use crate::math::{IVec2, Vec2};
+use crate::math::{IVec2, UVec2, Vec2};
 use glam::{uvec2, vec2};
 use smol_str::SmolStr;
 use std::cell::RefCell;
@@ -168,37 +169,53 @@ fn listen_fullscreen_change(win: &mut WebWindow) {
 }
 
 fn listen_resize_win(win: &mut WebWindow) {
-    if !win.config.resizable {
+    let fit = win.config.canvas_fit;
+    if !win.config.resizable && fit == CanvasFit::Fixed {
         return;
     }
 
+    let base_size = win.config.size;
     let min = win.min_size.clone();
     let max = win.max_size.clone();
     let canvas = win.canvas.clone();
     let parent = win.parent.clone();
     let events = win.events.clone();
 
-    let evt = window_add_event_listener("resize", move |_: WEvent| {
-        let mut parent_size = uvec2(parent.client_width() as _, parent.client_height() as _);
-        if parent_size.x == 0 {
-            parent_size.x = canvas.client_width() as _;
-        }
+    let on_resize = move |_: WEvent| {
+        let parent_size = parent_size(&parent, &canvas);
+        let (mut size, offset) = match fit {
+            CanvasFit::Fixed => (parent_size, UVec2::ZERO),
+            _ => fit.fit(base_size, parent_size),
+        };
 
-        if parent_size.y == 0 {
-            parent_size.y = canvas.client_height() as _;
-        }
         if let Some(min) = *min.borrow() {
-            parent_size = parent_size.max(min);
+            size = size.max(min);
         }
 
         if let Some(max) = *max.borrow() {
-            parent_size = parent_size.min(max);
+            size = size.min(max);
         }
 
-        set_size_dpi(&canvas, parent_size.x, parent_size.y);
-        events
-            .borrow_mut()
-            .push(Event::WindowResize { size: parent_size });
+        set_size_dpi(&canvas, size.x, size.y);
+        set_canvas_offset(&canvas, offset.x, offset.y);
+        events.borrow_mut().push(Event::WindowResize { size });
+    };
+
+    let on_resize = Rc::new(on_resize);
+    let on_orientation = on_resize.clone();
+
+    let evt = window_add_event_listener("resize", move |e: WEvent| on_resize(e));
+    std::mem::forget(evt);
+
+    // mobile browsers don't always dispatch 'resize' after rotating the device
+    let evt = window_add_event_listener("orientationchange", move |e: WEvent| on_orientation(e));
+    std::mem::forget(evt);
+}
+
+fn listen_orientation_change(win: &mut WebWindow) {
+    let events = win.events.clone();
+    let evt = window_add_event_listener("orientationchange", move |_: WEvent| {
+        events.borrow_mut().push(Event::OrientationChange);
     });
     std::mem::forget(evt);
 }
@@ -222,6 +239,7 @@ pub(crate) fn enable_input_events(win: &mut WebWindow) {
     // window events
     listen_resize_win(win);
     listen_fullscreen_change(win);
+    listen_orientation_change(win);
 }
 
 fn get_mouse_xy(
@@ -3,11 +3,12 @@ mod input;
 mod utils;
 mod window;
 
+use crate::app::FullscreenMode;
 use crate::backend::{BackendImpl, GfxBackendImpl};
 use crate::builder::AppBuilder;
 use crate::events::{CoreEvent, CORE_EVENTS_MAP};
 use crate::gfx::GfxBackend;
-use crate::input::{KeyboardState, MouseState};
+use crate::input::{InputEventKind, InputEventList, KeyboardState, MouseState};
 use crate::math::Vec2;
 use atomic_refcell::{AtomicRef, AtomicRefCell, AtomicRefMut};
 use once_cell::sync::Lazy;
@@ -47,14 +48,20 @@ where
 
     let vsync = config.vsync;
     let pixelated = config.pixelated;
+    let transparent = config.transparent;
 
     let callback = Rc::new(RefCell::new(None));
     let win = WebWindow::new(config).unwrap();
     let size = win.size().as_uvec2();
     let close_requested = win.close_requested.clone();
-    let gfx = GfxBackend::init(&win, vsync, size, pixelated)
+    let mut gfx = GfxBackend::init(&win, vsync, size, pixelated)
         .await
         .unwrap();
+    if transparent {
+        if let Err(e) = gfx.set_surface_transparent(true) {
+            log::warn!("{e}");
+        }
+    }
     {
         let mut bck = get_mut_backend();
         bck.win = Some(win);
@@ -117,6 +124,7 @@ impl<S> Runner<S> {
 
             bck.mouse_state.tick();
             bck.keyboard_state.tick();
+            bck.input_events.tick();
         }
     }
 
@@ -125,10 +133,12 @@ impl<S> Runner<S> {
 
         let mut events = get_mut_backend().take_events();
         #[allow(clippy::while_let_on_iterator)]
-        while let Some(evt) = events.next() {
+        while let Some((timestamp, evt)) = events.next() {
             match evt {
                 MouseMove { pos, delta } => {
                     let mut bck = get_mut_backend();
+                    bck.input_events
+                        .push(timestamp, InputEventKind::MouseMoved(pos));
                     bck.mouse_state.position = pos;
                     bck.mouse_state.moving = true;
                     bck.mouse_state.motion_delta = delta;
@@ -136,10 +146,18 @@ impl<S> Runner<S> {
                 }
                 MouseDown { btn } => {
                     let mut bck = get_mut_backend();
+                    if !bck.mouse_state.is_down(btn) {
+                        bck.input_events
+                            .push(timestamp, InputEventKind::MouseBtnPressed(btn));
+                    }
                     bck.mouse_state.press(btn);
                 }
                 MouseUp { btn } => {
                     let mut bck = get_mut_backend();
+                    if bck.mouse_state.is_down(btn) {
+                        bck.input_events
+                            .push(timestamp, InputEventKind::MouseBtnReleased(btn));
+                    }
                     bck.mouse_state.release(btn);
                 }
                 MouseEnter => {
@@ -154,13 +172,23 @@ impl<S> Runner<S> {
                     let mut bck = get_mut_backend();
                     bck.mouse_state.wheel_delta = delta;
                     bck.mouse_state.scrolling = true;
+                    bck.input_events
+                        .push(timestamp, InputEventKind::MouseWheel(delta));
                 }
                 KeyUp { key } => {
                     let mut bck = get_mut_backend();
+                    if bck.keyboard_state.is_down(key) {
+                        bck.input_events
+                            .push(timestamp, InputEventKind::KeyReleased(key));
+                    }
                     bck.keyboard_state.release(key);
                 }
                 KeyDown { key } => {
                     let mut bck = get_mut_backend();
+                    if !bck.keyboard_state.is_down(key) {
+                        bck.input_events
+                            .push(timestamp, InputEventKind::KeyPressed(key));
+                    }
                     bck.keyboard_state.press(key);
                 }
                 CharReceived { text } => {
@@ -174,6 +202,11 @@ impl<S> Runner<S> {
                     }
                     (*self.resize)(&mut self.state);
                 }
+                OrientationChange => {
+                    CORE_EVENTS_MAP
+                        .borrow()
+                        .trigger(CoreEvent::OrientationChange);
+                }
             }
         }
     }
@@ -187,6 +220,7 @@ pub(crate) struct WebBackend {
 
     pub(crate) mouse_state: MouseState,
     pub(crate) keyboard_state: KeyboardState,
+    pub(crate) input_events: InputEventList,
 }
 
 // hackish to allow the Lazy<T>, this is fine because wasm32 is not multithread
@@ -236,6 +270,12 @@ impl BackendImpl<GfxBackend> for WebBackend {
     fn toggle_fullscreen(&mut self) {
         self.win.as_mut().unwrap().toggle_fullscreen();
     }
+    fn fullscreen_mode(&self) -> Option<FullscreenMode> {
+        self.is_fullscreen().then_some(FullscreenMode::Borderless)
+    }
+    fn set_fullscreen(&mut self, mode: Option<FullscreenMode>) {
+        self.win.as_mut().unwrap().set_fullscreen(mode.is_some());
+    }
     fn dpi(&self) -> f32 {
         self.win.as_ref().unwrap().dpi
     }
@@ -245,8 +285,13 @@ impl BackendImpl<GfxBackend> for WebBackend {
     fn set_position(&mut self, _x: f32, _y: f32) {
         todo!()
     }
+    fn has_window(&self) -> bool {
+        self.win.is_some()
+    }
     fn is_focused(&self) -> bool {
-        todo!()
+        self.win
+            .as_ref()
+            .is_some_and(|w| w.document.has_focus().unwrap_or(true))
     }
     fn is_maximized(&self) -> bool {
         todo!()
@@ -257,6 +302,24 @@ impl BackendImpl<GfxBackend> for WebBackend {
     fn is_pixelated(&self) -> bool {
         self.win.as_ref().unwrap().pixelated
     }
+    fn set_transparent(&mut self, transparent: bool) {
+        if let Err(e) = self.gfx().set_surface_transparent(transparent) {
+            log::warn!("{e}");
+        }
+    }
+    fn is_transparent(&self) -> bool {
+        self.gfx
+            .as_ref()
+            .is_some_and(|gfx| gfx.is_surface_transparent())
+    }
+    fn set_always_on_top(&mut self, _always_on_top: bool) {}
+    fn is_always_on_top(&self) -> bool {
+        false
+    }
+    fn set_click_through(&mut self, _click_through: bool) {}
+    fn is_click_through(&self) -> bool {
+        false
+    }
     fn close(&mut self) {
         self.win.as_ref().unwrap().exit();
     }
@@ -309,6 +372,10 @@ impl BackendImpl<GfxBackend> for WebBackend {
         &self.keyboard_state
     }
 
+    fn input_events(&self) -> &InputEventList {
+        &self.input_events
+    }
+
     #[cfg(feature = "gamepad")]
     fn gamepad_state(&self) -> &GamepadState {
         todo!()
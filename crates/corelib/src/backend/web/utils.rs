@@ -1,6 +1,7 @@
+use crate::math::{uvec2, UVec2};
 use wasm_bindgen::prelude::*;
 use wasm_bindgen::JsCast;
-use web_sys::{HtmlCanvasElement, Window};
+use web_sys::{Element, HtmlCanvasElement, Window};
 
 pub(crate) fn set_size_dpi(canvas: &HtmlCanvasElement, width: u32, height: u32) {
     let auto_res = canvas
@@ -8,10 +9,13 @@ pub(crate) fn set_size_dpi(canvas: &HtmlCanvasElement, width: u32, height: u32)
         .unwrap_or_else(|| "false".to_string())
         .parse::<bool>()
         .unwrap_or(false);
-    let dpi = if auto_res {
-        web_sys::window().unwrap().device_pixel_ratio() as f32
-    } else {
-        1.0
+    let max_dpi = canvas
+        .get_attribute("gk-max-dpi")
+        .and_then(|dpi| dpi.parse::<f32>().ok());
+    let dpi = match (auto_res, max_dpi) {
+        (_, Some(max)) => (web_sys::window().unwrap().device_pixel_ratio() as f32).min(max),
+        (true, _) => web_sys::window().unwrap().device_pixel_ratio() as f32,
+        _ => 1.0,
     };
 
     let ww = width as f32 * dpi;
@@ -40,6 +44,31 @@ pub(crate) fn set_size_dpi(canvas: &HtmlCanvasElement, width: u32, height: u32)
     }
 }
 
+/// Returns the parent's size, using the canvas size for any axis where the parent has no size
+pub(crate) fn parent_size(parent: &Element, canvas: &HtmlCanvasElement) -> UVec2 {
+    let mut size = uvec2(parent.client_width() as _, parent.client_height() as _);
+    if size.x == 0 {
+        size.x = canvas.client_width() as _;
+    }
+
+    if size.y == 0 {
+        size.y = canvas.client_height() as _;
+    }
+
+    size
+}
+
+pub(crate) fn set_canvas_offset(canvas: &HtmlCanvasElement, x: u32, y: u32) {
+    let style = canvas.style();
+    let res = style
+        .set_property("margin-left", &format!("{x}px"))
+        .and_then(|_| style.set_property("margin-top", &format!("{y}px")));
+
+    if let Err(e) = res {
+        log::error!("{e:?}");
+    }
+}
+
 pub(crate) fn request_animation_frame(win: &Window, f: &Closure<dyn FnMut()>) -> i32 {
     win.request_animation_frame(f.as_ref().unchecked_ref())
         .expect("should register `requestAnimationFrame` OK")
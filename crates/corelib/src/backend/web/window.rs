@@ -1,6 +1,8 @@
 use super::events::EventIterator;
 use super::input::*;
-use super::utils::{canvas_add_event_listener, get_gk_size, set_size_dpi};
+use super::utils::{
+    canvas_add_event_listener, get_gk_size, parent_size, set_canvas_offset, set_size_dpi,
+};
 use crate::app::WindowConfig;
 use crate::math::{uvec2, UVec2};
 use glam::{vec2, Vec2};
@@ -76,20 +78,23 @@ impl WebWindow {
         let _ = canvas.focus();
         let dpi = window.device_pixel_ratio();
 
+        if let Some(max_dpi) = config.max_dpi {
+            if let Err(e) = canvas.set_attribute("gk-max-dpi", &max_dpi.to_string()) {
+                log::error!("{e:?}");
+            }
+        }
+
+        let parent_size = parent_size(&canvas_parent, &canvas);
+
         config.size = if config.maximized {
-            uvec2(
-                canvas_parent.client_width() as _,
-                canvas_parent.client_height() as _,
-            )
+            parent_size
         } else {
             config.size
         };
 
-        let UVec2 {
-            x: width,
-            y: height,
-        } = config.size;
-        set_size_dpi(&canvas, width, height);
+        let (size, offset) = config.canvas_fit.fit(config.size, parent_size);
+        set_size_dpi(&canvas, size.x, size.y);
+        set_canvas_offset(&canvas, offset.x, offset.y);
 
         let events = Rc::new(RefCell::new(EventIterator::default()));
         let cursor_locked = Rc::new(RefCell::new(false));
@@ -132,6 +137,10 @@ impl WebWindow {
 
     pub fn toggle_fullscreen(&mut self) {
         let full = !self.is_fullscreen();
+        self.set_fullscreen(full);
+    }
+
+    pub fn set_fullscreen(&mut self, full: bool) {
         self.fullscreen_request.replace(Some(full));
     }
 
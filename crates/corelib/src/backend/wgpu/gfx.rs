@@ -8,7 +8,7 @@ use crate::backend::wgpu::surface::Surface;
 use crate::backend::wgpu::utils::{wgpu_depth_stencil, wgpu_shader_visibility};
 use crate::gfx::consts::{MAX_PIPELINE_COMPATIBLE_TEXTURES, SURFACE_DEFAULT_DEPTH_FORMAT};
 use crate::gfx::{BindGroup, BindGroupDescriptor, BindGroupEntry, BindGroupLayoutRef, BindType, Buffer, BufferDescriptor, BufferUsage, Color, InnerBuffer, Limits, RenderPipeline, RenderPipelineDescriptor, RenderTexture, RenderTextureDescriptor, Renderer, Texture, TextureData, TextureDescriptor, TextureFormat, TextureId, GpuStats};
-use crate::gfx::{Sampler, SamplerDescriptor, MAX_BINDING_ENTRIES};
+use crate::gfx::{PresentMode, Sampler, SamplerDescriptor, MAX_BINDING_ENTRIES};
 use crate::math::{vec2, UVec2};
 use arrayvec::ArrayVec;
 use atomic_refcell::AtomicRefCell;
@@ -25,7 +25,7 @@ use wgpu::{
 use winit::raw_window_handle::HasDisplayHandle;
 
 pub(crate) struct GfxBackend {
-    pub(crate) surface: Surface, // Eventually we could have a HashMap<WindowId, Surface> if we want multiple window
+    pub(crate) surface: Option<Surface>, // Eventually we could have a HashMap<WindowId, Surface> if we want multiple window
 
     next_resource_id: u64,
     ctx: Context,
@@ -82,7 +82,7 @@ impl GfxBackendImpl for GfxBackend {
             vsync,
         )
         .await?;
-        self.surface = surface;
+        self.surface = Some(surface);
         Ok(())
     }
 
@@ -104,8 +104,7 @@ impl GfxBackendImpl for GfxBackend {
         let offscreen = self
             .offscreen
             .take()
-            .ok_or_else(|| "Invalid Offscreen surface".to_string())
-            .unwrap();
+            .ok_or_else(|| "Invalid Offscreen surface".to_string())?;
         self.render_to(&offscreen.texture, renderer)?;
         self.offscreen = Some(offscreen);
 
@@ -181,17 +180,20 @@ impl GfxBackendImpl for GfxBackend {
                     None
                 };
 
-                let depth_stencil_attachment = texture.depth_texture.as_ref().and_then(|dt| {
-                    if depth.is_some() || stencil.is_some() {
-                        Some(wgpu::RenderPassDepthStencilAttachment {
-                            view: &dt.view,
-                            depth_ops: depth,
-                            stencil_ops: stencil,
-                        })
-                    } else {
-                        None
-                    }
-                });
+                let depth_stencil_attachment = if depth.is_some() || stencil.is_some() {
+                    let dt = texture.depth_texture.as_ref().ok_or_else(|| {
+                        "Pipelines with depth or stencil need a RenderTexture with depth"
+                            .to_string()
+                    })?;
+
+                    Some(wgpu::RenderPassDepthStencilAttachment {
+                        view: &dt.view,
+                        depth_ops: depth,
+                        stencil_ops: stencil,
+                    })
+                } else {
+                    None
+                };
 
                 let mut rpass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
                     label: None,
@@ -241,7 +243,7 @@ impl GfxBackendImpl for GfxBackend {
                         rpass.set_bind_group(i as _, &*bg.raw, &[]);
                     });
 
-                    if let Some(sr) = rp.stencil_ref {
+                    if let Some(sr) = rp.stencil_ref.or(pip.stencil_reference) {
                         rpass.set_stencil_reference(sr as _);
                     }
 
@@ -453,6 +455,7 @@ impl GfxBackendImpl for GfxBackend {
             index_format,
             uses_depth: desc.depth_stencil.is_some(),
             uses_stencil: desc.stencil.is_some(),
+            stencil_reference: desc.stencil.map(|s| s.reference),
             bind_group_layout,
         })
     }
@@ -707,8 +710,9 @@ impl GfxBackendImpl for GfxBackend {
             let tex = desc.depth.then(|| {
                 self.create_texture(
                     TextureDescriptor {
-                        label: Some("Create RenderTexture inner color texture"),
-                        format: TextureFormat::Depth32Float,
+                        label: Some("Create RenderTexture inner depth texture"),
+                        // same format used by the pipelines with depth or stencil
+                        format: SURFACE_DEFAULT_DEPTH_FORMAT,
                         write: true,
                     },
                     Some(TextureData {
@@ -796,7 +800,7 @@ impl GfxBackend {
             next_resource_id,
             ctx,
             depth_format,
-            surface,
+            surface: Some(surface),
             frame: None,
             offscreen: None,
             last_frame_stats: GpuStats::default(),
@@ -809,8 +813,106 @@ impl GfxBackend {
         Ok(bck)
     }
 
+    /// Creates a backend without surface, it can only render to textures
+    #[cfg(not(target_arch = "wasm32"))]
+    pub(crate) async fn new_headless() -> Result<Self, String> {
+        let ctx = Context::new(Instance::default(), None).await?;
+        Ok(Self {
+            next_resource_id: 0,
+            ctx,
+            depth_format: SURFACE_DEFAULT_DEPTH_FORMAT,
+            surface: None,
+            frame: None,
+            offscreen: None,
+            last_frame_stats: GpuStats::default(),
+            current_stats: GpuStats::default(),
+        })
+    }
+
+    pub(crate) fn surface(&self) -> Result<&Surface, String> {
+        self.surface
+            .as_ref()
+            .ok_or_else(|| "The gfx backend has no surface.".to_string())
+    }
+
+    /// Copies the texture content to the CPU, it blocks until the GPU is done
+    #[cfg(not(target_arch = "wasm32"))]
+    pub(crate) fn read_pixels(&mut self, texture: &Texture) -> Result<Vec<u8>, String> {
+        let bpp = texture
+            .format
+            .as_wgpu()
+            .block_copy_size(None)
+            .ok_or_else(|| format!("Cannot read pixels from format '{:?}'", texture.format))?;
+
+        let width = texture.size.x as u32;
+        let height = texture.size.y as u32;
+        let row_size = width * bpp;
+        // the buffer rows must be aligned
+        let align = wgpu::COPY_BYTES_PER_ROW_ALIGNMENT;
+        let padded_row_size = row_size.div_ceil(align) * align;
+
+        let buffer = self.ctx.device.create_buffer(&WBufferDescriptor {
+            label: Some("Read Pixels Buffer"),
+            size: (padded_row_size * height) as u64,
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+
+        let mut encoder = self
+            .ctx
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: Some("Read Pixels Encoder"),
+            });
+
+        encoder.copy_texture_to_buffer(
+            ImageCopyTexture {
+                texture: &texture.raw,
+                mip_level: 0,
+                origin: Origin3d::ZERO,
+                aspect: TextureAspect::All,
+            },
+            wgpu::ImageCopyBuffer {
+                buffer: &buffer,
+                layout: ImageDataLayout {
+                    offset: 0,
+                    bytes_per_row: Some(padded_row_size),
+                    rows_per_image: Some(height),
+                },
+            },
+            Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            },
+        );
+
+        self.ctx.queue.submit(Some(encoder.finish()));
+
+        let slice = buffer.slice(..);
+        let (tx, rx) = std::sync::mpsc::channel();
+        slice.map_async(wgpu::MapMode::Read, move |res| {
+            let _ = tx.send(res);
+        });
+        self.ctx.device.poll(wgpu::Maintain::Wait);
+        rx.recv()
+            .map_err(|e| e.to_string())?
+            .map_err(|e| e.to_string())?;
+
+        let mut pixels = Vec::with_capacity((row_size * height) as usize);
+        {
+            let data = slice.get_mapped_range();
+            data.chunks(padded_row_size as usize)
+                .for_each(|row| pixels.extend_from_slice(&row[..row_size as usize]));
+        }
+        buffer.unmap();
+
+        self.current_stats.read_pixels += 1;
+        Ok(pixels)
+    }
+
     fn push_frame(&mut self) -> Result<(), String> {
-        let frame = self.surface.frame()?;
+        let frame = self.surface()?.frame()?;
         let view = frame
             .texture
             .create_view(&wgpu::TextureViewDescriptor::default());
@@ -894,7 +996,7 @@ impl GfxBackend {
                     color_attachments: &[color],
                     depth_stencil_attachment: if depth.is_some() || stencil.is_some() {
                         Some(wgpu::RenderPassDepthStencilAttachment {
-                            view: &self.surface.depth_texture.view,
+                            view: &self.surface()?.depth_texture.view,
                             depth_ops: depth,
                             stencil_ops: stencil,
                         })
@@ -933,7 +1035,7 @@ impl GfxBackend {
                         rpass.set_bind_group(i as _, &*bg.raw, &[]);
                     });
 
-                    if let Some(sr) = rp.stencil_ref {
+                    if let Some(sr) = rp.stencil_ref.or(pip.stencil_reference) {
                         rpass.set_stencil_reference(sr as _);
                     }
 
@@ -977,11 +1079,51 @@ impl GfxBackend {
         }
     }
 
+    pub(crate) fn present_mode(&self) -> PresentMode {
+        self.surface
+            .as_ref()
+            .map_or(PresentMode::default(), |surface| {
+                PresentMode::from_wgpu(surface.config.present_mode)
+            })
+    }
+
+    pub(crate) fn supported_present_modes(&self) -> Vec<PresentMode> {
+        self.surface
+            .iter()
+            .flat_map(|surface| surface.present_modes.iter())
+            .map(|mode| PresentMode::from_wgpu(*mode))
+            .collect()
+    }
+
+    pub(crate) fn set_present_mode(&mut self, mode: PresentMode) -> Result<(), String> {
+        match &mut self.surface {
+            Some(surface) => surface.set_present_mode(&self.ctx.device, mode.as_wgpu()),
+            None => Err("The gfx backend has no surface.".to_string()),
+        }
+    }
+
+    pub(crate) fn is_surface_transparent(&self) -> bool {
+        self.surface
+            .as_ref()
+            .is_some_and(|surface| surface.is_transparent())
+    }
+
+    pub(crate) fn set_surface_transparent(&mut self, transparent: bool) -> Result<(), String> {
+        match &mut self.surface {
+            Some(surface) => surface.set_transparent(&self.ctx.device, transparent),
+            None => Err("The gfx backend has no surface.".to_string()),
+        }
+    }
+
     pub(crate) fn resize(&mut self, width: u32, height: u32) {
-        self.surface.resize(&self.ctx.device, width, height);
-        let mut offscreen = self.offscreen.take().unwrap();
-        offscreen.update(self).unwrap();
-        self.offscreen = Some(offscreen);
+        if let Some(surface) = &mut self.surface {
+            surface.resize(&self.ctx.device, width, height);
+        }
+
+        if let Some(mut offscreen) = self.offscreen.take() {
+            offscreen.update(self).unwrap();
+            self.offscreen = Some(offscreen);
+        }
     }
 }
 
@@ -1025,12 +1167,24 @@ fn create_texture(
         depth_or_array_layers: 1,
     });
 
-    let is_depth_texture = matches!(desc.format, TextureFormat::Depth32Float);
+    let is_depth_texture = matches!(
+        desc.format,
+        TextureFormat::Depth16
+            | TextureFormat::Depth24
+            | TextureFormat::Depth32Float
+            | TextureFormat::Depth24Stencil8
+            | TextureFormat::Depth32FloatStencil8
+    );
     let mut usage = wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST;
     if is_depth_texture || desc.write {
         usage |= wgpu::TextureUsages::RENDER_ATTACHMENT;
     }
 
+    // allows to read the pixels of render textures
+    if desc.write && !is_depth_texture {
+        usage |= wgpu::TextureUsages::COPY_SRC;
+    }
+
     let raw = device.create_texture(&wgpu::TextureDescriptor {
         label: desc.label,
         size,
@@ -2,7 +2,7 @@ use crate::backend::wgpu::frame::DrawFrame;
 use crate::backend::GfxBackendImpl;
 use crate::gfx::{
     BindGroup, BindGroupDescriptor, BindGroupEntry, BindGroupLayout, BindingType, BlendMode,
-    Buffer, BufferDescriptor, BufferUsage, GfxBackend, IndexFormat, RenderPipeline,
+    Buffer, BufferDescriptor, BufferUsage, Color, GfxBackend, IndexFormat, RenderPipeline,
     RenderPipelineDescriptor, RenderTexture, RenderTextureDescriptor, Renderer, Sampler,
     SamplerDescriptor, TextureFilter, TextureFormat, VertexFormat, VertexLayout,
 };
@@ -87,11 +87,20 @@ pub(crate) struct OffscreenSurfaceData {
 
 impl OffscreenSurfaceData {
     pub fn new(gfx: &mut GfxBackend, pixelated: bool) -> Result<Self, String> {
+        let (width, height, raw_format) = {
+            let surface = gfx.surface()?;
+            (
+                surface.config.width,
+                surface.config.height,
+                surface.raw_format,
+            )
+        };
+
         let texture = gfx.create_render_texture(RenderTextureDescriptor {
             label: Some("Offscreen Surface"),
             depth: false,
-            width: gfx.surface.config.width,
-            height: gfx.surface.config.height,
+            width,
+            height,
             format: TextureFormat::Rgba8UNormSrgb, // compatible with all platforms even web
         })?;
 
@@ -110,7 +119,7 @@ impl OffscreenSurfaceData {
 
         // output always srgb values even if the device does not support a srgb surface
         // is this the right thing to do?
-        let (vert, frag) = if gfx.surface.raw_format.is_srgb() {
+        let (vert, frag) = if raw_format.is_srgb() {
             (VERT, FRAG)
         } else {
             (VERT, FRAG_TO_SRGB)
@@ -126,8 +135,7 @@ impl OffscreenSurfaceData {
                 .unwrap(),
             blend_mode: Some(BlendMode::NORMAL),
             index_format: IndexFormat::UInt16,
-            compatible_textures: (&[TextureFormat::from_wgpu(gfx.surface.raw_format).unwrap()]
-                as &[_])
+            compatible_textures: (&[TextureFormat::from_wgpu(raw_format).unwrap()] as &[_])
                 .try_into()
                 .unwrap(),
             bind_group_layout: (&[BindGroupLayout::new()
@@ -195,8 +203,13 @@ impl OffscreenSurfaceData {
     }
 
     pub fn update(&mut self, gfx: &mut GfxBackend) -> Result<(), String> {
-        let same_width = gfx.surface.config.width == self.texture.width() as u32;
-        let same_height = gfx.surface.config.height == self.texture.height() as u32;
+        let (width, height) = {
+            let surface = gfx.surface()?;
+            (surface.config.width, surface.config.height)
+        };
+
+        let same_width = width == self.texture.width() as u32;
+        let same_height = height == self.texture.height() as u32;
         let needs_update = !(same_width && same_height);
         if !needs_update {
             // do nothing
@@ -206,8 +219,8 @@ impl OffscreenSurfaceData {
         let texture = gfx.create_render_texture(RenderTextureDescriptor {
             label: Some("Offscreen Surface"),
             depth: false,
-            width: gfx.surface.config.width,
-            height: gfx.surface.config.height,
+            width,
+            height,
             format: TextureFormat::Rgba8UNormSrgb, // compatible with all platforms even web
         })?;
         self.texture = texture;
@@ -236,6 +249,8 @@ impl OffscreenSurfaceData {
         let mut renderer = Renderer::new();
         renderer
             .begin_pass()
+            // keeps the alpha of the offscreen texture for transparent windows
+            .clear_color(Color::TRANSPARENT)
             .pipeline(&self.pip)
             .buffers(&[&self.vbo, &self.ebo])
             .bindings(&[&self.bind_group])
@@ -11,6 +11,7 @@ pub struct RenderPipeline {
     pub(crate) index_format: wgpu::IndexFormat,
     pub(crate) uses_depth: bool,
     pub(crate) uses_stencil: bool,
+    pub(crate) stencil_reference: Option<u8>,
     pub(crate) bind_group_layout: ArrayVec<BindGroupLayoutRef, MAX_BIND_GROUPS_PER_PIPELINE>,
 }
 
@@ -25,6 +26,11 @@ impl RenderPipeline {
         self.id
     }
 
+    /// Stencil reference used when the render pass does not set one
+    pub fn stencil_reference(&self) -> Option<u8> {
+        self.stencil_reference
+    }
+
     pub fn bind_group_layout_ref(&self, index: u32) -> Result<&BindGroupLayoutRef, String> {
         self.bind_group_layout
             .get(index as usize)
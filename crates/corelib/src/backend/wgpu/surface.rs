@@ -17,6 +17,8 @@ pub(crate) struct Surface {
     pub config: SurfaceConfiguration,
     pub depth_texture: Texture,
     pub raw_format: RawTextureFormat,
+    pub present_modes: Vec<wgpu::PresentMode>,
+    pub alpha_modes: Vec<wgpu::CompositeAlphaMode>,
     // pub capabilities: Arc<SurfaceCapabilities>,
 }
 
@@ -94,6 +96,8 @@ impl Surface {
             config,
             depth_texture,
             raw_format,
+            present_modes: capabilities.present_modes.clone(),
+            alpha_modes: capabilities.alpha_modes.clone(),
             // capabilities: Arc::new(capabilities),
         })
     }
@@ -104,6 +108,58 @@ impl Surface {
         self.surface.configure(device, &self.config);
     }
 
+    pub fn set_present_mode(
+        &mut self,
+        device: &Device,
+        mode: wgpu::PresentMode,
+    ) -> Result<(), String> {
+        let is_auto = matches!(
+            mode,
+            wgpu::PresentMode::AutoVsync | wgpu::PresentMode::AutoNoVsync
+        );
+        if !is_auto && !self.present_modes.contains(&mode) {
+            return Err(format!(
+                "Present mode {mode:?} is not supported by the surface. Supported: {:?}",
+                self.present_modes
+            ));
+        }
+
+        self.config.present_mode = mode;
+        self.surface.configure(device, &self.config);
+        Ok(())
+    }
+
+    pub fn set_transparent(&mut self, device: &Device, transparent: bool) -> Result<(), String> {
+        use wgpu::CompositeAlphaMode as Alpha;
+
+        let mode = if transparent {
+            [Alpha::PreMultiplied, Alpha::PostMultiplied, Alpha::Inherit]
+                .into_iter()
+                .find(|mode| self.alpha_modes.contains(mode))
+                .ok_or_else(|| {
+                    format!(
+                        "The surface doesn't support transparency. Supported alpha modes: {:?}",
+                        self.alpha_modes
+                    )
+                })?
+        } else if self.alpha_modes.contains(&Alpha::Opaque) {
+            Alpha::Opaque
+        } else {
+            self.alpha_modes[0]
+        };
+
+        self.config.alpha_mode = mode;
+        self.surface.configure(device, &self.config);
+        Ok(())
+    }
+
+    pub fn is_transparent(&self) -> bool {
+        !matches!(
+            self.config.alpha_mode,
+            wgpu::CompositeAlphaMode::Opaque | wgpu::CompositeAlphaMode::Auto
+        )
+    }
+
     pub fn frame(&self) -> Result<SurfaceTexture, String> {
         self.surface
             .get_current_texture()
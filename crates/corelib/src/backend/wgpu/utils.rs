@@ -1,5 +1,7 @@
 use crate::gfx::consts::SURFACE_DEFAULT_DEPTH_FORMAT;
-use crate::gfx::{BlendComponent, BlendFactor, BlendMode, BlendOperation, DepthStencil, Stencil};
+use crate::gfx::{
+    BlendComponent, BlendFactor, BlendMode, BlendOperation, DepthStencil, PresentMode, Stencil,
+};
 use wgpu::CompareFunction;
 
 pub fn wgpu_shader_visibility(vertex: bool, fragment: bool, compute: bool) -> wgpu::ShaderStages {
@@ -19,6 +21,30 @@ pub fn wgpu_shader_visibility(vertex: bool, fragment: bool, compute: bool) -> wg
     v
 }
 
+impl PresentMode {
+    pub(crate) fn as_wgpu(&self) -> wgpu::PresentMode {
+        match self {
+            PresentMode::AutoVsync => wgpu::PresentMode::AutoVsync,
+            PresentMode::AutoNoVsync => wgpu::PresentMode::AutoNoVsync,
+            PresentMode::Fifo => wgpu::PresentMode::Fifo,
+            PresentMode::FifoRelaxed => wgpu::PresentMode::FifoRelaxed,
+            PresentMode::Mailbox => wgpu::PresentMode::Mailbox,
+            PresentMode::Immediate => wgpu::PresentMode::Immediate,
+        }
+    }
+
+    pub(crate) fn from_wgpu(mode: wgpu::PresentMode) -> Self {
+        match mode {
+            wgpu::PresentMode::AutoVsync => PresentMode::AutoVsync,
+            wgpu::PresentMode::AutoNoVsync => PresentMode::AutoNoVsync,
+            wgpu::PresentMode::Fifo => PresentMode::Fifo,
+            wgpu::PresentMode::FifoRelaxed => PresentMode::FifoRelaxed,
+            wgpu::PresentMode::Mailbox => PresentMode::Mailbox,
+            wgpu::PresentMode::Immediate => PresentMode::Immediate,
+        }
+    }
+}
+
 impl BlendMode {
     pub(crate) fn as_wgpu(&self) -> wgpu::BlendState {
         wgpu::BlendState {
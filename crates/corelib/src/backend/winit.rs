@@ -1,25 +1,33 @@
 #![cfg(not(target_arch = "wasm32"))]
 
-use crate::math::uvec2;
+use crate::math::{uvec2, UVec2};
 use atomic_refcell::{AtomicRef, AtomicRefCell, AtomicRefMut};
 use once_cell::sync::Lazy;
 use spin_sleep_util::Interval;
 use std::time::Duration;
 use winit::application::ApplicationHandler;
 use winit::dpi::{LogicalSize, PhysicalPosition};
-use winit::event::{ElementState, Ime, MouseButton as WMouseButton, MouseScrollDelta, WindowEvent};
+use winit::event::{
+    ElementState, Ime, MouseButton as WMouseButton, MouseScrollDelta, Touch, TouchPhase,
+    WindowEvent,
+};
 use winit::event_loop::{ActiveEventLoop, ControlFlow, EventLoop};
 use winit::keyboard::{KeyCode as WKeyCode, PhysicalKey};
-use winit::window::{CursorGrabMode, Fullscreen, Window, WindowAttributes, WindowId};
+use winit::raw_window_handle::{HasDisplayHandle, HasWindowHandle};
+use winit::window::{CursorGrabMode, Fullscreen, Window, WindowAttributes, WindowId, WindowLevel};
 
+#[cfg(target_os = "android")]
+use winit::platform::android::EventLoopBuilderExtAndroid;
 #[cfg(target_arch = "wasm32")]
 use winit::platform::web::WindowAttributesExtWebSys;
 
 use super::traits::{BackendImpl, GfxBackendImpl};
-use crate::app::WindowConfig;
+use crate::app::{FullscreenMode, WindowConfig};
 use crate::backend::wgpu::GfxBackend;
 use crate::builder::AppBuilder;
-use crate::input::{KeyCode, KeyboardState, MouseButton, MouseState};
+use crate::input::{
+    InputEventKind, InputEventList, KeyCode, KeyboardState, MouseButton, MouseState,
+};
 use crate::math::{vec2, Vec2};
 // TODO, screen_size, positions etc... must be logical or physical pixels?
 
@@ -39,12 +47,22 @@ pub(crate) struct WinitBackend {
     request_close: bool,
     mouse_state: MouseState,
     keyboard_state: KeyboardState,
+    input_events: InputEventList,
 
     pixelated: bool,
 
     cursor_locked: bool,
     cursor_visible: bool,
 
+    always_on_top: bool,
+    click_through: bool,
+
+    suspended: bool,
+    // logical size and scale factor used when rendering to a surface not owned by rkit
+    external_size: Option<(Vec2, f32)>,
+    // touch used to emulate the mouse
+    touch_id: Option<u64>,
+
     #[cfg(feature = "gamepad")]
     gilrs: GilrsBackend,
     gfx: Option<GfxBackend>,
@@ -65,6 +83,10 @@ impl BackendImpl<GfxBackend> for WinitBackend {
 
     #[inline]
     fn size(&self) -> Vec2 {
+        if let Some((size, _)) = self.external_size {
+            return size;
+        }
+
         debug_assert!(self.window.is_some(), "Window must be present");
         let w = self.window.as_ref().unwrap();
         let size = w.inner_size().to_logical::<f32>(w.scale_factor());
@@ -124,8 +146,55 @@ impl BackendImpl<GfxBackend> for WinitBackend {
         self.window.as_mut().unwrap().set_fullscreen(mode);
     }
 
+    #[inline]
+    fn fullscreen_mode(&self) -> Option<FullscreenMode> {
+        debug_assert!(self.window.is_some(), "Window must be present");
+        self.window
+            .as_ref()
+            .unwrap()
+            .fullscreen()
+            .map(|mode| match mode {
+                Fullscreen::Exclusive(_) => FullscreenMode::Exclusive,
+                Fullscreen::Borderless(_) => FullscreenMode::Borderless,
+            })
+    }
+
+    fn set_fullscreen(&mut self, mode: Option<FullscreenMode>) {
+        debug_assert!(self.window.is_some(), "Window must be present");
+        let win = self.window.as_mut().unwrap();
+        let mode = match mode {
+            Some(FullscreenMode::Exclusive) => {
+                // the biggest resolution with the highest refresh rate
+                let video_mode = win.current_monitor().and_then(|m| {
+                    m.video_modes().max_by_key(|vm| {
+                        let size = vm.size();
+                        (size.width * size.height, vm.refresh_rate_millihertz())
+                    })
+                });
+
+                match video_mode {
+                    Some(vm) => Some(Fullscreen::Exclusive(vm)),
+                    None => {
+                        log::warn!(
+                            "No video mode available for exclusive fullscreen, using borderless."
+                        );
+                        Some(Fullscreen::Borderless(None))
+                    }
+                }
+            }
+            Some(FullscreenMode::Borderless) => Some(Fullscreen::Borderless(None)),
+            None => None,
+        };
+
+        win.set_fullscreen(mode);
+    }
+
     #[inline]
     fn dpi(&self) -> f32 {
+        if let Some((_, scale_factor)) = self.external_size {
+            return scale_factor;
+        }
+
         debug_assert!(self.window.is_some(), "Window must be present");
         self.window.as_ref().unwrap().scale_factor() as _
     }
@@ -149,6 +218,11 @@ impl BackendImpl<GfxBackend> for WinitBackend {
             .set_outer_position(PhysicalPosition::new(x, y))
     }
 
+    #[inline]
+    fn has_window(&self) -> bool {
+        self.window.is_some()
+    }
+
     #[inline]
     fn is_focused(&self) -> bool {
         debug_assert!(self.window.is_some(), "Window must be present");
@@ -177,6 +251,62 @@ impl BackendImpl<GfxBackend> for WinitBackend {
         self.pixelated
     }
 
+    fn set_transparent(&mut self, transparent: bool) {
+        if self.is_transparent() == transparent {
+            return;
+        }
+
+        if let Err(e) = self.gfx().set_surface_transparent(transparent) {
+            log::warn!("Error setting window transparency: {e}");
+            return;
+        }
+
+        self.window.as_mut().unwrap().set_transparent(transparent);
+    }
+
+    fn is_transparent(&self) -> bool {
+        self.gfx
+            .as_ref()
+            .is_some_and(|gfx| gfx.is_surface_transparent())
+    }
+
+    fn set_always_on_top(&mut self, always_on_top: bool) {
+        debug_assert!(self.window.is_some(), "Window must be present");
+        let level = if always_on_top {
+            WindowLevel::AlwaysOnTop
+        } else {
+            WindowLevel::Normal
+        };
+        self.window.as_mut().unwrap().set_window_level(level);
+        self.always_on_top = always_on_top;
+    }
+
+    fn is_always_on_top(&self) -> bool {
+        self.always_on_top
+    }
+
+    fn set_click_through(&mut self, click_through: bool) {
+        if self.click_through == click_through {
+            return;
+        }
+
+        let res = self
+            .window
+            .as_mut()
+            .unwrap()
+            .set_cursor_hittest(!click_through);
+        if let Err(err) = res {
+            log::warn!("Error setting click through: {}", err.to_string());
+            return;
+        }
+
+        self.click_through = click_through;
+    }
+
+    fn is_click_through(&self) -> bool {
+        self.click_through
+    }
+
     #[inline]
     fn close(&mut self) {
         debug_assert!(self.window.is_some(), "Window must be present");
@@ -238,6 +368,11 @@ impl BackendImpl<GfxBackend> for WinitBackend {
         &self.keyboard_state
     }
 
+    #[inline]
+    fn input_events(&self) -> &InputEventList {
+        &self.input_events
+    }
+
     #[cfg(feature = "gamepad")]
     #[inline]
     fn gamepad_state(&self) -> &GamepadState {
@@ -258,24 +393,36 @@ struct Runner<S> {
     resize: Box<dyn FnMut(&mut S)>,
     vsync: bool,
     pixelated_offscreen: bool,
+    click_through: bool,
     interval: Option<Interval>,
 }
 
 impl<S> ApplicationHandler for Runner<S> {
     fn resumed(&mut self, event_loop: &ActiveEventLoop) {
-        #[allow(unused_mut)]
-        let mut attrs = self.window_attrs.clone();
-
-        #[cfg(target_arch = "wasm32")]
-        {
-            attrs = attrs.with_append(true);
-        }
+        // on mobile the window is kept but the surface must be recreated after a suspend
+        let win = get_mut_backend().window.take().unwrap_or_else(|| {
+            #[allow(unused_mut)]
+            let mut attrs = self.window_attrs.clone();
+
+            #[cfg(target_arch = "wasm32")]
+            {
+                attrs = attrs.with_append(true);
+            }
 
-        let win = event_loop.create_window(attrs).unwrap();
-        win.set_ime_allowed(true); // allow for chars
+            let win = event_loop.create_window(attrs).unwrap();
+            win.set_ime_allowed(true); // allow for chars
+            win
+        });
 
         let win_size = win.inner_size();
         let gfx_initiated = get_backend().gfx.is_some();
+
+        // the surface state changed at runtime must survive the surface recreation
+        let (transparent, present_mode) = match get_backend().gfx.as_ref() {
+            Some(gfx) => (gfx.is_surface_transparent(), Some(gfx.present_mode())),
+            None => (self.window_attrs.transparent, None),
+        };
+
         if gfx_initiated {
             let res = pollster::block_on(get_mut_backend().gfx.as_mut().unwrap().update_surface(
                 &win,
@@ -313,11 +460,42 @@ impl<S> ApplicationHandler for Runner<S> {
             let mut bck = get_mut_backend();
             bck.window = Some(win);
             bck.pixelated = self.pixelated_offscreen;
+            bck.suspended = false;
+
+            // the surface is created again after a suspend so the alpha mode must be set again
+            if transparent && bck.gfx.is_some() {
+                if let Err(e) = bck.gfx().set_surface_transparent(true) {
+                    log::warn!("Error setting window transparency: {e}");
+                }
+            }
+
+            if let Some(mode) = present_mode {
+                if let Err(e) = bck.gfx().set_present_mode(mode) {
+                    log::warn!("Error setting present mode: {e}");
+                }
+            }
+
+            // the window is kept after a suspend, only a new one takes the config values
+            if !gfx_initiated {
+                bck.always_on_top = self.window_attrs.window_level == WindowLevel::AlwaysOnTop;
+                bck.set_click_through(self.click_through);
+            }
         }
-        if let Some(init_cb) = self.init.take() {
-            self.state = Some(init_cb());
+
+        match self.init.take() {
+            Some(init_cb) => {
+                self.state = Some(init_cb());
+                CORE_EVENTS_MAP.borrow().trigger(CoreEvent::Init);
+            }
+            None => {
+                CORE_EVENTS_MAP.borrow().trigger(CoreEvent::Resume);
+            }
         }
-        CORE_EVENTS_MAP.borrow().trigger(CoreEvent::Init);
+    }
+
+    fn suspended(&mut self, _event_loop: &ActiveEventLoop) {
+        get_mut_backend().suspended = true;
+        CORE_EVENTS_MAP.borrow().trigger(CoreEvent::Suspend);
     }
 
     fn window_event(
@@ -339,6 +517,8 @@ impl<S> ApplicationHandler for Runner<S> {
                 bck.mouse_state.motion_delta = motion_delta;
                 if motion_delta.x != 0.0 || motion_delta.y != 0.0 {
                     bck.mouse_state.moving = true;
+                    bck.input_events
+                        .push(time::now(), InputEventKind::MouseMoved(pos));
                 }
                 bck.mouse_state.cursor_on_screen = true;
             }
@@ -360,21 +540,35 @@ impl<S> ApplicationHandler for Runner<S> {
                 };
                 bck.mouse_state.wheel_delta = value;
                 bck.mouse_state.scrolling = true;
+                bck.input_events
+                    .push(time::now(), InputEventKind::MouseWheel(value));
             }
             WindowEvent::MouseInput { state, button, .. } => {
                 let btn = mouse_btn_cast(button);
                 let mut bck = get_mut_backend();
                 match state {
-                    ElementState::Pressed => bck.mouse_state.press(btn),
-                    ElementState::Released => bck.mouse_state.release(btn),
+                    ElementState::Pressed => press_mouse_btn(&mut bck, btn),
+                    ElementState::Released => release_mouse_btn(&mut bck, btn),
                 }
             }
             WindowEvent::KeyboardInput { event, .. } => {
                 let key = physical_key_cast(event.physical_key);
                 let mut bck = get_mut_backend();
                 match event.state {
-                    ElementState::Pressed => bck.keyboard_state.press(key),
-                    ElementState::Released => bck.keyboard_state.release(key),
+                    ElementState::Pressed => {
+                        if !bck.keyboard_state.is_down(key) {
+                            bck.input_events
+                                .push(time::now(), InputEventKind::KeyPressed(key));
+                        }
+                        bck.keyboard_state.press(key);
+                    }
+                    ElementState::Released => {
+                        if bck.keyboard_state.is_down(key) {
+                            bck.input_events
+                                .push(time::now(), InputEventKind::KeyReleased(key));
+                        }
+                        bck.keyboard_state.release(key);
+                    }
                 }
 
                 // chars
@@ -389,13 +583,24 @@ impl<S> ApplicationHandler for Runner<S> {
             WindowEvent::CloseRequested => {
                 event_loop.exit();
             }
+            WindowEvent::Touch(touch) => {
+                let mut bck = get_mut_backend();
+                emulate_mouse_with_touch(&mut bck, touch);
+            }
             WindowEvent::RedrawRequested => {
+                // the surface is not available until the app resumes
+                if get_backend().suspended {
+                    return;
+                }
+
                 time::tick();
 
                 #[cfg(feature = "gamepad")]
                 {
                     // gamepad must be updated before the update cb
-                    get_mut_backend().gilrs.tick();
+                    let mut bck = get_mut_backend();
+                    let bck = &mut *bck;
+                    bck.gilrs.tick(&mut bck.input_events);
                 }
 
                 // app's update cb
@@ -412,6 +617,7 @@ impl<S> ApplicationHandler for Runner<S> {
                 bck.window.as_ref().unwrap().request_redraw();
                 bck.mouse_state.tick();
                 bck.keyboard_state.tick();
+                bck.input_events.tick();
 
                 if let Some(interval) = &mut self.interval {
                     interval.tick();
@@ -450,7 +656,19 @@ where
         ..
     } = builder;
 
+    #[cfg(not(target_os = "android"))]
     let event_loop = EventLoop::new().unwrap();
+
+    #[cfg(target_os = "android")]
+    let event_loop = {
+        let app = crate::app::android_app()
+            .ok_or_else(|| "The AndroidApp must be set with 'set_android_app'".to_string())?;
+        EventLoop::builder()
+            .with_android_app(app)
+            .build()
+            .map_err(|e| e.to_string())?
+    };
+
     event_loop.set_control_flow(ControlFlow::Poll);
 
     let vsync = window.vsync;
@@ -459,6 +677,7 @@ where
         .map(|max| spin_sleep_util::interval(Duration::from_secs(1) / max as u32));
 
     let pixelated_offscreen = window.pixelated;
+    let click_through = window.click_through;
 
     let mut runner = Runner {
         window_attrs: window_attrs(window),
@@ -469,6 +688,7 @@ where
         vsync,
         interval,
         pixelated_offscreen,
+        click_through,
     };
 
     event_loop.run_app(&mut runner).map_err(|e| e.to_string())?;
@@ -481,6 +701,44 @@ where
     Ok(())
 }
 
+pub(crate) fn init_external_gfx<W>(
+    window: &W,
+    size: UVec2,
+    scale_factor: f32,
+    vsync: bool,
+) -> Result<(), String>
+where
+    W: HasDisplayHandle + HasWindowHandle,
+{
+    let mut bck = get_mut_backend();
+    match bck.gfx.as_mut() {
+        Some(gfx) => pollster::block_on(gfx.update_surface(window, vsync, size))?,
+        None => {
+            let gfx = pollster::block_on(GfxBackend::init(window, vsync, size, false))?;
+            bck.gfx = Some(gfx);
+        }
+    }
+
+    bck.external_size = Some((size.as_vec2() / scale_factor, scale_factor));
+    Ok(())
+}
+
+pub(crate) fn init_headless_gfx() -> Result<(), String> {
+    let mut bck = get_mut_backend();
+    if bck.gfx.is_none() {
+        let gfx = pollster::block_on(GfxBackend::new_headless())?;
+        bck.gfx = Some(gfx);
+    }
+
+    Ok(())
+}
+
+pub(crate) fn resize_external_gfx(width: u32, height: u32, scale_factor: f32) {
+    let mut bck = get_mut_backend();
+    bck.gfx().resize(width, height);
+    bck.external_size = Some((vec2(width as _, height as _) / scale_factor, scale_factor));
+}
+
 #[inline]
 pub(crate) fn get_backend() -> AtomicRef<'static, WinitBackend> {
     BACKEND.borrow()
@@ -502,13 +760,23 @@ fn window_attrs(config: WindowConfig) -> WindowAttributes {
         vsync: _,
         max_fps: _,
         pixelated: _,
+        canvas_fit: _,
+        max_dpi: _,
+        transparent,
+        always_on_top,
+        click_through: _,
     } = config;
 
     let mut attrs = WindowAttributes::default()
         .with_title(title)
         .with_inner_size(LogicalSize::new(size.x, size.y))
         .with_resizable(resizable)
-        .with_maximized(maximized);
+        .with_maximized(maximized)
+        .with_transparent(transparent);
+
+    if always_on_top {
+        attrs = attrs.with_window_level(WindowLevel::AlwaysOnTop);
+    }
 
     if let Some(ms) = min_size {
         attrs = attrs.with_min_inner_size(LogicalSize::new(ms.x, ms.y));
@@ -521,6 +789,66 @@ fn window_attrs(config: WindowConfig) -> WindowAttributes {
     attrs
 }
 
+// the first finger acts as the left mouse button
+fn emulate_mouse_with_touch(bck: &mut WinitBackend, touch: Touch) {
+    let Touch {
+        id,
+        phase,
+        location,
+        ..
+    } = touch;
+
+    match bck.touch_id {
+        Some(current) if current != id => return,
+        None if phase != TouchPhase::Started => return,
+        _ => {}
+    }
+
+    let scale = bck.window.as_ref().unwrap().scale_factor();
+    let w_pos = location.to_logical::<f32>(scale);
+    let pos = vec2(w_pos.x, w_pos.y);
+    let motion_delta = pos - bck.mouse_state.position();
+
+    bck.mouse_state.position = pos;
+    bck.mouse_state.motion_delta = motion_delta;
+    bck.mouse_state.cursor_on_screen = true;
+
+    match phase {
+        TouchPhase::Started => {
+            bck.touch_id = Some(id);
+            press_mouse_btn(bck, MouseButton::Left);
+        }
+        TouchPhase::Moved => {
+            bck.mouse_state.moving = motion_delta != Vec2::ZERO;
+            if bck.mouse_state.moving {
+                bck.input_events
+                    .push(time::now(), InputEventKind::MouseMoved(pos));
+            }
+        }
+        TouchPhase::Ended | TouchPhase::Cancelled => {
+            bck.touch_id = None;
+            release_mouse_btn(bck, MouseButton::Left);
+            bck.mouse_state.cursor_on_screen = false;
+        }
+    }
+}
+
+fn press_mouse_btn(bck: &mut WinitBackend, btn: MouseButton) {
+    if !bck.mouse_state.is_down(btn) {
+        bck.input_events
+            .push(time::now(), InputEventKind::MouseBtnPressed(btn));
+    }
+    bck.mouse_state.press(btn);
+}
+
+fn release_mouse_btn(bck: &mut WinitBackend, btn: MouseButton) {
+    if bck.mouse_state.is_down(btn) {
+        bck.input_events
+            .push(time::now(), InputEventKind::MouseBtnReleased(btn));
+    }
+    bck.mouse_state.release(btn);
+}
+
 fn mouse_btn_cast(wbtn: WMouseButton) -> MouseButton {
     match wbtn {
         WMouseButton::Left => MouseButton::Left,
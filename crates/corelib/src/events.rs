@@ -12,6 +12,9 @@ pub(crate) enum CoreEvent {
     Init,
     PreUpdate,
     PostUpdate,
+    Suspend,
+    Resume,
+    OrientationChange,
     CleanUp,
 }
 
@@ -1,27 +1,41 @@
+#[cfg(not(target_arch = "wasm32"))]
+mod bake;
 mod bind_group;
 mod blend_mode;
 mod buffer;
 mod builders;
 mod color;
 pub mod consts;
+mod image_data;
 mod limits;
 mod pipeline;
+mod present_mode;
 mod renderer;
-mod texture;
+mod shader_module;
 mod stats;
+mod texture;
 
 pub use crate::backend::gfx::*;
 use crate::backend::{get_mut_backend, BackendImpl, GfxBackendImpl};
+#[cfg(not(target_arch = "wasm32"))]
+use crate::math::UVec2;
+#[cfg(not(target_arch = "wasm32"))]
+pub use bake::*;
 pub use bind_group::*;
 pub use blend_mode::*;
 pub use buffer::*;
 pub use builders::*;
 pub use color::*;
+pub use image_data::*;
 pub use limits::*;
 pub use pipeline::*;
+pub use present_mode::*;
 pub use renderer::*;
-pub use texture::*;
+pub use shader_module::*;
 pub use stats::*;
+pub use texture::*;
+#[cfg(not(target_arch = "wasm32"))]
+use winit::raw_window_handle::{HasDisplayHandle, HasWindowHandle};
 
 // - Gfx
 #[inline]
@@ -40,6 +54,13 @@ where
     renderer.render(Some(texture))
 }
 
+/// Returns the raw pixels of the render texture, it waits until the GPU is done
+#[cfg(not(target_arch = "wasm32"))]
+#[inline]
+pub fn read_pixels(texture: &RenderTexture) -> Result<Vec<u8>, String> {
+    get_mut_backend().gfx().read_pixels(&texture.texture)
+}
+
 #[inline]
 pub fn create_render_pipeline(shader: &str) -> RenderPipelineBuilder {
     RenderPipelineBuilder::new(shader)
@@ -98,4 +119,67 @@ pub fn limits() -> Limits {
 #[inline]
 pub fn last_frame_stats() -> GpuStats {
     get_mut_backend().gfx().stats()
-}
\ No newline at end of file
+}
+
+/// Returns the present mode used by the surface
+#[inline]
+pub fn present_mode() -> PresentMode {
+    get_mut_backend().gfx().present_mode()
+}
+
+/// Returns the present modes supported by the surface
+#[inline]
+pub fn supported_present_modes() -> Vec<PresentMode> {
+    get_mut_backend().gfx().supported_present_modes()
+}
+
+/// Changes the present mode, it fails if the surface doesn't support it
+/// `Web`: Only `Fifo` and the auto modes are supported
+#[inline]
+pub fn set_present_mode(mode: PresentMode) -> Result<(), String> {
+    get_mut_backend().gfx().set_present_mode(mode)
+}
+
+// - External surface
+/// Initializes the gfx backend on a window created outside the app loop (e.g. a widget
+/// from an editor or another framework). The rendering must be wrapped with
+/// `begin_external_frame` and `end_external_frame`. The size is in physical pixels,
+/// `window_size` returns it divided by `scale_factor`.
+/// `Web`: Not available
+///
+/// # Safety
+/// The window must outlive the gfx backend, or another call to this function
+/// must replace the surface before the window is destroyed.
+#[cfg(not(target_arch = "wasm32"))]
+pub unsafe fn init_external_surface<W>(
+    window: &W,
+    size: UVec2,
+    scale_factor: f32,
+    vsync: bool,
+) -> Result<(), String>
+where
+    W: HasDisplayHandle + HasWindowHandle,
+{
+    crate::backend::init_external_gfx(window, size, scale_factor.max(f32::EPSILON), vsync)
+}
+
+/// Resizes the surface initialized with `init_external_surface`, the size is in physical pixels
+#[cfg(not(target_arch = "wasm32"))]
+#[inline]
+pub fn resize_external_surface(width: u32, height: u32, scale_factor: f32) {
+    crate::backend::resize_external_gfx(width, height, scale_factor.max(f32::EPSILON));
+}
+
+/// Prepares the frame when the app loop is not used
+#[cfg(not(target_arch = "wasm32"))]
+#[inline]
+pub fn begin_external_frame() {
+    get_mut_backend().gfx().prepare_frame();
+}
+
+/// Presents the frame when the app loop is not used
+#[cfg(not(target_arch = "wasm32"))]
+#[inline]
+pub fn end_external_frame() {
+    get_mut_backend().gfx().present_frame();
+}
@@ -0,0 +1,26 @@
+use crate::backend::{get_mut_backend, BackendImpl};
+use crate::gfx::RenderTexture;
+
+/// Gives access to the results of the work done inside `bake`
+pub struct BakeContext {
+    _private: (),
+}
+
+impl BakeContext {
+    /// Returns the raw pixels of the texture, it waits until the GPU is done
+    pub fn read_pixels(&mut self, texture: &RenderTexture) -> Result<Vec<u8>, String> {
+        get_mut_backend().gfx().read_pixels(&texture.texture)
+    }
+}
+
+/// Runs render work without a window or the app loop, useful for tools and build scripts
+/// that pre-render atlases, SDFs or lightmaps with the same pipelines as the game.
+/// The gfx backend is created headless the first time, if the app is already running it
+/// uses the current one. Only render textures can be used as target.
+pub fn bake<F, R>(cb: F) -> Result<R, String>
+where
+    F: FnOnce(&mut BakeContext) -> Result<R, String>,
+{
+    crate::backend::init_headless_gfx()?;
+    cb(&mut BakeContext { _private: () })
+}
@@ -1,10 +1,10 @@
 use crate::backend::{get_mut_backend, BackendImpl, GfxBackendImpl};
 use crate::gfx::{
-    BindGroup, BindGroupDescriptor, BindGroupEntry, BindGroupLayout, BindGroupLayoutRef, BlendMode,
-    Buffer, BufferDescriptor, BufferUsage, ColorMask, CompareMode, CullMode, DepthStencil,
-    IndexFormat, Primitive, RenderPipeline, RenderPipelineDescriptor, RenderTexture,
-    RenderTextureDescriptor, Sampler, SamplerDescriptor, Stencil, Texture, TextureData,
-    TextureDescriptor, TextureFilter, TextureFormat, TextureWrap, VertexLayout,
+    preprocess_shader, BindGroup, BindGroupDescriptor, BindGroupEntry, BindGroupLayout,
+    BindGroupLayoutRef, BlendMode, Buffer, BufferDescriptor, BufferUsage, ColorMask, CompareMode,
+    CullMode, DepthStencil, IndexFormat, Primitive, RenderPipeline, RenderPipelineDescriptor,
+    RenderTexture, RenderTextureDescriptor, Sampler, SamplerDescriptor, Stencil, Texture,
+    TextureData, TextureDescriptor, TextureFilter, TextureFormat, TextureWrap, VertexLayout,
 };
 use glam::{uvec2, UVec2};
 use image::EncodableLayout;
@@ -92,6 +92,11 @@ impl<'a> RenderPipelineBuilder<'a> {
 
     pub fn build(self) -> Result<RenderPipeline, String> {
         let Self { desc } = self;
+        let shader = preprocess_shader(desc.shader)?;
+        let desc = RenderPipelineDescriptor {
+            shader: &shader,
+            ..desc
+        };
         get_mut_backend().gfx().create_render_pipeline(desc)
     }
 }
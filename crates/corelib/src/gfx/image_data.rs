@@ -0,0 +1,273 @@
+use crate::gfx::{Color, Texture};
+use crate::math::{uvec2, Rect, UVec2};
+
+const CHANNELS: usize = 4;
+
+/// CPU side RGBA8 image that keeps track of the modified area,
+/// it can be uploaded to a texture sending only the pixels that changed
+#[derive(Clone, Debug)]
+pub struct ImageData {
+    width: u32,
+    height: u32,
+    pixels: Vec<u8>,
+    dirty: Option<(UVec2, UVec2)>,
+}
+
+impl ImageData {
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            pixels: vec![0; width as usize * height as usize * CHANNELS],
+            dirty: None,
+        }
+    }
+
+    pub fn from_bytes(bytes: &[u8], width: u32, height: u32) -> Result<Self, String> {
+        let len = width as usize * height as usize * CHANNELS;
+        if bytes.len() != len {
+            return Err(format!(
+                "Invalid image data length {} for a {}x{} RGBA image, expected {}",
+                bytes.len(),
+                width,
+                height,
+                len
+            ));
+        }
+
+        Ok(Self {
+            width,
+            height,
+            pixels: bytes.to_vec(),
+            dirty: None,
+        })
+    }
+
+    /// Decodes an encoded image (png, jpeg or webp) as RGBA8
+    pub fn from_image(image: &[u8]) -> Result<Self, String> {
+        let img = image::load_from_memory(image).map_err(|e| e.to_string())?;
+        let rgba = img.to_rgba8();
+        let (width, height) = rgba.dimensions();
+        Ok(Self {
+            width,
+            height,
+            pixels: rgba.into_raw(),
+            dirty: None,
+        })
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn bytes(&self) -> &[u8] {
+        &self.pixels
+    }
+
+    pub fn pixel(&self, x: u32, y: u32) -> Option<Color> {
+        let idx = self.index(x, y)?;
+        let [r, g, b, a] = self.pixels[idx..idx + CHANNELS] else {
+            return None;
+        };
+        Some(Color::rgba_u8(r, g, b, a))
+    }
+
+    pub fn set_pixel(&mut self, x: u32, y: u32, color: Color) {
+        let Some(idx) = self.index(x, y) else {
+            return;
+        };
+
+        self.pixels[idx..idx + CHANNELS].copy_from_slice(&color.to_rgba_u8());
+        self.mark_dirty(uvec2(x, y), uvec2(x + 1, y + 1));
+    }
+
+    /// Fills the region with the color, the region is clipped to the image bounds
+    pub fn fill_rect(&mut self, x: u32, y: u32, width: u32, height: u32, color: Color) {
+        let x2 = x.saturating_add(width).min(self.width);
+        let y2 = y.saturating_add(height).min(self.height);
+        if x >= x2 || y >= y2 {
+            return;
+        }
+
+        let rgba = color.to_rgba_u8();
+        for yy in y..y2 {
+            let start = self.index(x, yy).unwrap();
+            let end = start + (x2 - x) as usize * CHANNELS;
+            self.pixels[start..end]
+                .chunks_exact_mut(CHANNELS)
+                .for_each(|px| px.copy_from_slice(&rgba));
+        }
+
+        self.mark_dirty(uvec2(x, y), uvec2(x2, y2));
+    }
+
+    pub fn clear(&mut self, color: Color) {
+        self.fill_rect(0, 0, self.width, self.height, color);
+    }
+
+    /// Copies the pixels of other image into this one at the position given
+    pub fn copy_from(&mut self, other: &ImageData, x: u32, y: u32) {
+        let x2 = x.saturating_add(other.width).min(self.width);
+        let y2 = y.saturating_add(other.height).min(self.height);
+        if x >= x2 || y >= y2 {
+            return;
+        }
+
+        let row_len = (x2 - x) as usize * CHANNELS;
+        for yy in y..y2 {
+            let start = self.index(x, yy).unwrap();
+            let src = other.index(0, yy - y).unwrap();
+            self.pixels[start..start + row_len].copy_from_slice(&other.pixels[src..src + row_len]);
+        }
+
+        self.mark_dirty(uvec2(x, y), uvec2(x2, y2));
+    }
+
+    /// Area modified since the last upload
+    pub fn dirty_rect(&self) -> Option<Rect> {
+        self.dirty
+            .map(|(min, max)| Rect::new(min.as_vec2(), (max - min).as_vec2()))
+    }
+
+    pub fn is_dirty(&self) -> bool {
+        self.dirty.is_some()
+    }
+
+    /// Uploads only the modified area to the texture
+    pub fn upload_to(&mut self, texture: &Texture) -> Result<(), String> {
+        let Some((min, max)) = self.dirty.take() else {
+            return Ok(());
+        };
+
+        let region = self.region_bytes(min, max);
+        texture.update_region(Rect::new(min.as_vec2(), (max - min).as_vec2()), &region)
+    }
+
+    fn region_bytes(&self, min: UVec2, max: UVec2) -> Vec<u8> {
+        let row_len = (max.x - min.x) as usize * CHANNELS;
+        let mut bytes = Vec::with_capacity(row_len * (max.y - min.y) as usize);
+        for y in min.y..max.y {
+            let start = self.index(min.x, y).unwrap();
+            bytes.extend_from_slice(&self.pixels[start..start + row_len]);
+        }
+        bytes
+    }
+
+    fn index(&self, x: u32, y: u32) -> Option<usize> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+
+        Some((y as usize * self.width as usize + x as usize) * CHANNELS)
+    }
+
+    fn mark_dirty(&mut self, min: UVec2, max: UVec2) {
+        let (min, max) = match self.dirty {
+            Some((dmin, dmax)) => (dmin.min(min), dmax.max(max)),
+            None => (min, max),
+        };
+        self.dirty = Some((min, max));
+    }
+}
+
+impl Texture {
+    /// Updates only the region of the texture given, the bytes must be tightly packed
+    pub fn update_region(&self, rect: Rect, bytes: &[u8]) -> Result<(), String> {
+        let offset = uvec2(rect.x() as _, rect.y() as _);
+        let size = uvec2(rect.width() as _, rect.height() as _);
+        let expected = size.element_product() as usize * self.format().channels() as usize;
+        if bytes.len() < expected {
+            return Err(format!(
+                "Invalid data length {} to update a region of {}x{}",
+                bytes.len(),
+                size.x,
+                size.y
+            ));
+        }
+
+        crate::gfx::write_texture(self)
+            .from_data(bytes)
+            .with_offset(offset)
+            .with_size(size)
+            .build()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::{vec2, Vec2};
+
+    #[test]
+    fn test_set_pixel_marks_dirty() {
+        let mut img = ImageData::new(4, 4);
+        assert!(!img.is_dirty());
+
+        img.set_pixel(1, 2, Color::RED);
+        assert_eq!(img.pixel(1, 2), Some(Color::RED));
+        assert_eq!(img.dirty_rect(), Some(Rect::new(vec2(1.0, 2.0), Vec2::ONE)));
+
+        img.set_pixel(3, 0, Color::RED);
+        assert_eq!(
+            img.dirty_rect(),
+            Some(Rect::new(vec2(1.0, 0.0), vec2(3.0, 3.0)))
+        );
+    }
+
+    #[test]
+    fn test_out_of_bounds() {
+        let mut img = ImageData::new(2, 2);
+        img.set_pixel(2, 0, Color::RED);
+        assert!(!img.is_dirty());
+        assert_eq!(img.pixel(0, 2), None);
+    }
+
+    #[test]
+    fn test_fill_rect_clipped() {
+        let mut img = ImageData::new(4, 4);
+        img.fill_rect(2, 2, 10, 10, Color::WHITE);
+        assert_eq!(img.pixel(3, 3), Some(Color::WHITE));
+        assert_eq!(img.pixel(1, 1), Some(Color::TRANSPARENT));
+        assert_eq!(
+            img.dirty_rect(),
+            Some(Rect::new(vec2(2.0, 2.0), vec2(2.0, 2.0)))
+        );
+    }
+
+    #[test]
+    fn test_fill_rect_large_size() {
+        let mut img = ImageData::new(4, 4);
+        img.fill_rect(1, 1, u32::MAX, u32::MAX, Color::WHITE);
+        assert_eq!(img.pixel(3, 3), Some(Color::WHITE));
+        assert_eq!(img.pixel(0, 0), Some(Color::TRANSPARENT));
+    }
+
+    #[test]
+    fn test_region_bytes() {
+        let mut img = ImageData::new(3, 3);
+        img.set_pixel(1, 1, Color::WHITE);
+        let bytes = img.region_bytes(uvec2(1, 1), uvec2(3, 2));
+        assert_eq!(bytes, vec![255, 255, 255, 255, 0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn test_copy_from() {
+        let mut src = ImageData::new(2, 2);
+        src.clear(Color::WHITE);
+
+        let mut img = ImageData::new(3, 3);
+        img.copy_from(&src, 2, 2);
+        assert_eq!(img.pixel(2, 2), Some(Color::WHITE));
+        assert_eq!(img.pixel(1, 1), Some(Color::TRANSPARENT));
+    }
+
+    #[test]
+    fn test_from_bytes_invalid_len() {
+        assert!(ImageData::from_bytes(&[0; 3], 1, 1).is_err());
+        assert!(ImageData::from_bytes(&[0; 4], 1, 1).is_ok());
+    }
+}
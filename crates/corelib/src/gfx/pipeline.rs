@@ -61,7 +61,7 @@ pub struct ClearOptions {
     pub stencil: Option<u32>,
 }
 
-#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
 pub enum CompareMode {
     Never,
     Less,
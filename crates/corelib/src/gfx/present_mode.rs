@@ -0,0 +1,17 @@
+/// How the frames are presented to the screen
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum PresentMode {
+    /// Fifo if available, falls back to FifoRelaxed
+    #[default]
+    AutoVsync,
+    /// Immediate or Mailbox if available, falls back to Fifo
+    AutoNoVsync,
+    /// Waits for the vertical blank, no tearing. Supported everywhere
+    Fifo,
+    /// Like Fifo, but late frames are presented right away (may tear)
+    FifoRelaxed,
+    /// Low latency without tearing, the newest frame replaces the queued one
+    Mailbox,
+    /// Presents right away, lowest latency but it may tear
+    Immediate,
+}
@@ -0,0 +1,148 @@
+use once_cell::sync::Lazy;
+use rustc_hash::{FxHashMap, FxHashSet};
+use std::sync::RwLock;
+
+const BUILT_IN: [(&str, &str); 1] = [("srgb", include_str!("./shaders/srgb.wgsl"))];
+
+// RwLock instead of AtomicRefCell because the shaders can be processed from any thread
+static MODULES: Lazy<RwLock<FxHashMap<String, String>>> = Lazy::new(|| {
+    let modules = BUILT_IN
+        .iter()
+        .map(|(name, source)| (name.to_string(), source.to_string()))
+        .collect();
+    RwLock::new(modules)
+});
+
+fn is_built_in(name: &str) -> bool {
+    BUILT_IN.iter().any(|(n, _)| *n == name)
+}
+
+/// Registers a WGSL module that can be used in any shader with `#include "name"`.
+/// Built-in modules (like `"srgb"`) cannot be replaced
+pub fn register_shader_module(name: &str, source: &str) -> Result<(), String> {
+    if is_built_in(name) {
+        return Err(format!(
+            "Shader module '{name}' is built-in and cannot be replaced."
+        ));
+    }
+
+    MODULES
+        .write()
+        .unwrap()
+        .insert(name.to_string(), source.to_string());
+    Ok(())
+}
+
+/// Removes a registered module, returns `true` if it was registered
+pub fn unregister_shader_module(name: &str) -> bool {
+    !is_built_in(name) && MODULES.write().unwrap().remove(name).is_some()
+}
+
+/// Resolves the `#include "name"` lines of the shader with the registered modules.
+/// Each module is included only once, nested includes are allowed.
+/// This is done automatically when a render pipeline is created
+pub fn preprocess_shader(source: &str) -> Result<String, String> {
+    if !source.contains("#include") {
+        return Ok(source.to_string());
+    }
+
+    let modules = MODULES.read().unwrap();
+    preprocess_with(source, |name| modules.get(name).map(String::as_str))
+}
+
+fn preprocess_with<'a, F>(source: &str, resolve: F) -> Result<String, String>
+where
+    F: Fn(&str) -> Option<&'a str>,
+{
+    let mut out = String::with_capacity(source.len());
+    let mut included = FxHashSet::default();
+    let mut stack = vec![];
+    expand(source, &resolve, &mut included, &mut stack, &mut out)?;
+    Ok(out)
+}
+
+fn expand<'a, F>(
+    source: &str,
+    resolve: &F,
+    included: &mut FxHashSet<String>,
+    stack: &mut Vec<String>,
+    out: &mut String,
+) -> Result<(), String>
+where
+    F: Fn(&str) -> Option<&'a str>,
+{
+    for line in source.lines() {
+        let Some(name) = parse_include(line)? else {
+            out.push_str(line);
+            out.push('\n');
+            continue;
+        };
+
+        if stack.iter().any(|n| n == name) {
+            return Err(format!(
+                "Circular shader include: {} -> {name}",
+                stack.join(" -> ")
+            ));
+        }
+
+        if !included.insert(name.to_string()) {
+            continue;
+        }
+
+        let module = resolve(name).ok_or_else(|| format!("Unknown shader module '{name}'"))?;
+        stack.push(name.to_string());
+        expand(module, resolve, included, stack, out)?;
+        stack.pop();
+    }
+
+    Ok(())
+}
+
+fn parse_include(line: &str) -> Result<Option<&str>, String> {
+    let Some(rest) = line.trim().strip_prefix("#include") else {
+        return Ok(None);
+    };
+
+    rest.trim()
+        .strip_prefix('"')
+        .and_then(|r| r.strip_suffix('"'))
+        .filter(|name| !name.is_empty())
+        .map(Some)
+        .ok_or_else(|| format!("Invalid shader include '{}'", line.trim()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn resolve(name: &str) -> Option<&'static str> {
+        match name {
+            "a" => Some("fn a() {}"),
+            "b" => Some("#include \"a\"\nfn b() {}"),
+            "loop" => Some("#include \"loop\""),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_include() {
+        let src = "#include \"b\"\n#include \"a\"\nfn main() {}";
+        let out = preprocess_with(src, resolve).unwrap();
+        assert_eq!(out, "fn a() {}\nfn b() {}\nfn main() {}\n");
+    }
+
+    #[test]
+    fn test_include_errors() {
+        assert!(preprocess_with("#include \"missing\"", resolve).is_err());
+        assert!(preprocess_with("#include missing", resolve).is_err());
+        assert!(preprocess_with("#include \"loop\"", resolve).is_err());
+    }
+
+    #[test]
+    fn test_built_in() {
+        let out = preprocess_shader("#include \"srgb\"").unwrap();
+        assert!(out.contains("fn srgb_to_linear("));
+        assert!(register_shader_module("srgb", "").is_err());
+        assert!(!unregister_shader_module("srgb"));
+    }
+}
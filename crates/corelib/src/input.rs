@@ -1,17 +1,34 @@
 use crate::backend::{get_backend, get_mut_backend, BackendImpl};
 use crate::math::Vec2;
 
+mod buffer;
+mod events;
 #[cfg(feature = "gamepad")]
 mod gamepad;
 mod keyboard;
 mod mouse;
+mod players;
+#[cfg(feature = "gamepad")]
+mod stick;
 mod touch;
 
+pub use buffer::*;
+pub use events::*;
 pub use keyboard::*;
 pub use mouse::*;
+pub use players::*;
 
 #[cfg(feature = "gamepad")]
 pub use gamepad::*;
+#[cfg(feature = "gamepad")]
+pub use stick::{DeadZoneShape, GamepadStick, ResponseCurve, StickConfig};
+
+// -- Events
+/// Returns the input events received since the last frame sorted by the time they happened
+#[inline]
+pub fn input_events() -> InputEventList {
+    get_backend().input_events().clone()
+}
 
 // -- Mouse
 #[inline]
@@ -287,6 +304,40 @@ pub fn gamepad_axis_movement(id: GamepadId, axis: GamepadAxis) -> f32 {
         .unwrap_or_default()
 }
 
+#[cfg(feature = "gamepad")]
+#[inline]
+pub fn gamepad_raw_axis_movement(id: GamepadId, axis: GamepadAxis) -> f32 {
+    get_backend()
+        .gamepad_state()
+        .get(id.raw())
+        .map(|info| info.raw_axis_strength(axis))
+        .unwrap_or_default()
+}
+
+/// Stick position with the dead zone and response curve applied
+#[cfg(feature = "gamepad")]
+#[inline]
+pub fn gamepad_stick(id: GamepadId, stick: GamepadStick) -> Vec2 {
+    get_backend()
+        .gamepad_state()
+        .get(id.raw())
+        .map(|info| info.stick(stick))
+        .unwrap_or_default()
+}
+
+/// Sets the dead zone and response curve used by the stick for all the gamepads
+#[cfg(feature = "gamepad")]
+#[inline]
+pub fn set_gamepad_stick_config(stick: GamepadStick, config: StickConfig) {
+    stick::set_stick_config(stick, config);
+}
+
+#[cfg(feature = "gamepad")]
+#[inline]
+pub fn gamepad_stick_config(stick: GamepadStick) -> StickConfig {
+    stick::stick_config(stick)
+}
+
 // TODO gamepad name?
 // #[inline]
 // pub fn gamepad_name<'a>(id: GamepadId) -> &'a str {
@@ -0,0 +1,197 @@
+use crate::input::{InputEvent, InputEventKind, InputEventList};
+use crate::time::{self, Duration, Instant};
+use std::collections::VecDeque;
+
+type StepFn = Box<dyn Fn(&InputEventKind) -> bool + Send + Sync>;
+
+/// Ordered list of inputs that must happen within a time window, like a quarter-circle + punch
+pub struct InputSequence {
+    steps: Vec<StepFn>,
+    step_window: Duration,
+}
+
+impl Default for InputSequence {
+    fn default() -> Self {
+        Self {
+            steps: vec![],
+            step_window: Duration::from_millis(250),
+        }
+    }
+}
+
+impl InputSequence {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a step that matches the event kind
+    pub fn then(self, kind: InputEventKind) -> Self {
+        self.then_with(move |k| *k == kind)
+    }
+
+    /// Adds a step that matches any event accepted by `cb`, useful for stick directions
+    pub fn then_with<F>(mut self, cb: F) -> Self
+    where
+        F: Fn(&InputEventKind) -> bool + Send + Sync + 'static,
+    {
+        self.steps.push(Box::new(cb));
+        self
+    }
+
+    /// Maximum time between two steps of the sequence
+    pub fn with_step_window(mut self, window: Duration) -> Self {
+        self.step_window = window;
+        self
+    }
+
+    /// Returns the index of the events used by the sequence if it's found ending
+    /// with the last event that matches the last step
+    fn find(&self, events: &VecDeque<InputEvent>) -> Option<usize> {
+        let (last_step, steps) = self.steps.split_last()?;
+        let last_idx = events.iter().rposition(|evt| last_step(&evt.kind))?;
+
+        let mut idx = last_idx;
+        let mut time = events[last_idx].time;
+        for step in steps.iter().rev() {
+            // the latest occurrence keeps the gap between steps as short as possible
+            let found = events.range(..idx).rposition(|evt| step(&evt.kind))?;
+            let evt_time = events[found].time;
+            if time.duration_since(evt_time) > self.step_window {
+                return None;
+            }
+
+            idx = found;
+            time = evt_time;
+        }
+
+        Some(last_idx)
+    }
+}
+
+/// Keeps the recent input events to detect sequences like combos
+pub struct InputBuffer {
+    events: VecDeque<InputEvent>,
+    max_age: Duration,
+}
+
+impl Default for InputBuffer {
+    fn default() -> Self {
+        Self::new(Duration::from_secs(1))
+    }
+}
+
+impl InputBuffer {
+    /// Events older than `max_age` are discarded
+    pub fn new(max_age: Duration) -> Self {
+        Self {
+            events: VecDeque::new(),
+            max_age,
+        }
+    }
+
+    /// Adds the events of the frame, should be called once per frame with `input_events()`
+    pub fn update(&mut self, events: &InputEventList) {
+        self.extend(events, time::now());
+    }
+
+    fn extend(&mut self, events: &InputEventList, now: Instant) {
+        self.events.extend(events.iter().copied());
+        let max_age = self.max_age;
+        self.events
+            .retain(|evt| now.saturating_duration_since(evt.time) <= max_age);
+    }
+
+    /// Returns true if the sequence is in the buffer
+    pub fn matches(&self, sequence: &InputSequence) -> bool {
+        sequence.find(&self.events).is_some()
+    }
+
+    /// Returns true if the sequence is in the buffer, the events used are removed so the
+    /// same inputs don't trigger it again
+    pub fn consume(&mut self, sequence: &InputSequence) -> bool {
+        match sequence.find(&self.events) {
+            Some(last_idx) => {
+                self.events.drain(..=last_idx);
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.events.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::{KeyCode, MouseButton};
+
+    fn list(now: Instant, events: &[(u64, InputEventKind)]) -> InputEventList {
+        let mut list = InputEventList::default();
+        events.iter().for_each(|(ms, kind)| {
+            list.push(now + Duration::from_millis(*ms), *kind);
+        });
+        list
+    }
+
+    #[test]
+    fn test_sequence() {
+        use InputEventKind::*;
+
+        let seq = InputSequence::new()
+            .then(KeyPressed(KeyCode::ArrowDown))
+            .then(KeyPressed(KeyCode::ArrowRight))
+            .then(KeyPressed(KeyCode::KeyP))
+            .with_step_window(Duration::from_millis(100));
+
+        let now = Instant::now();
+        let mut buffer = InputBuffer::new(Duration::from_secs(1));
+        buffer.extend(
+            &list(
+                now,
+                &[
+                    (0, KeyPressed(KeyCode::ArrowDown)),
+                    (50, KeyPressed(KeyCode::ArrowRight)),
+                    (60, KeyReleased(KeyCode::ArrowDown)),
+                    (70, MouseBtnPressed(MouseButton::Left)),
+                    (120, KeyPressed(KeyCode::KeyP)),
+                ],
+            ),
+            now + Duration::from_millis(120),
+        );
+
+        assert!(buffer.matches(&seq));
+        assert!(buffer.consume(&seq));
+        assert!(!buffer.matches(&seq));
+    }
+
+    #[test]
+    fn test_sequence_too_slow() {
+        use InputEventKind::*;
+
+        let seq = InputSequence::new()
+            .then(KeyPressed(KeyCode::ArrowDown))
+            .then(KeyPressed(KeyCode::KeyP))
+            .with_step_window(Duration::from_millis(100));
+
+        let now = Instant::now();
+        let mut buffer = InputBuffer::new(Duration::from_secs(1));
+        buffer.extend(
+            &list(
+                now,
+                &[
+                    (0, KeyPressed(KeyCode::ArrowDown)),
+                    (200, KeyPressed(KeyCode::KeyP)),
+                ],
+            ),
+            now + Duration::from_millis(200),
+        );
+        assert!(!buffer.matches(&seq));
+
+        // old events are discarded
+        buffer.extend(&InputEventList::default(), now + Duration::from_secs(2));
+        assert!(buffer.events.is_empty());
+    }
+}
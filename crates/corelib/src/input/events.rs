@@ -0,0 +1,103 @@
+use crate::input::{KeyCode, MouseButton};
+use crate::math::Vec2;
+use crate::time::Instant;
+use smallvec::SmallVec;
+
+#[cfg(feature = "gamepad")]
+use crate::input::{GamepadAxis, GamepadButton, GamepadId};
+
+// events that can be stored without allocating per frame
+const INPUT_EVENTS_HINT: usize = 32;
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum InputEventKind {
+    KeyPressed(KeyCode),
+    KeyReleased(KeyCode),
+    MouseBtnPressed(MouseButton),
+    MouseBtnReleased(MouseButton),
+    MouseMoved(Vec2),
+    MouseWheel(Vec2),
+    #[cfg(feature = "gamepad")]
+    GamepadBtnPressed(GamepadId, GamepadButton),
+    #[cfg(feature = "gamepad")]
+    GamepadBtnReleased(GamepadId, GamepadButton),
+    #[cfg(feature = "gamepad")]
+    GamepadAxisChanged(GamepadId, GamepadAxis, f32),
+}
+
+/// Input event with the time it happened
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct InputEvent {
+    pub time: Instant,
+    pub kind: InputEventKind,
+}
+
+/// Input events received since the last frame sorted by time
+#[derive(Default, Clone, Debug)]
+pub struct InputEventList {
+    list: SmallVec<InputEvent, INPUT_EVENTS_HINT>,
+}
+
+impl InputEventList {
+    pub(crate) fn push(&mut self, time: Instant, kind: InputEventKind) {
+        // events from different devices can arrive out of order
+        let idx = self
+            .list
+            .iter()
+            .rposition(|evt| evt.time <= time)
+            .map_or(0, |idx| idx + 1);
+        self.list.insert(idx, InputEvent { time, kind });
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &InputEvent> + '_ {
+        self.list.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.list.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.list.is_empty()
+    }
+
+    pub(crate) fn tick(&mut self) {
+        self.list.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::time::Duration;
+
+    #[test]
+    fn test_events_order() {
+        let now = Instant::now();
+        let mut list = InputEventList::default();
+        list.push(now, InputEventKind::KeyPressed(KeyCode::KeyA));
+        list.push(
+            now + Duration::from_millis(10),
+            InputEventKind::KeyReleased(KeyCode::KeyA),
+        );
+        list.push(
+            now + Duration::from_millis(5),
+            InputEventKind::MouseBtnPressed(MouseButton::Left),
+        );
+        list.push(now, InputEventKind::KeyPressed(KeyCode::KeyB));
+
+        let kinds: Vec<_> = list.iter().map(|evt| evt.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                InputEventKind::KeyPressed(KeyCode::KeyA),
+                InputEventKind::KeyPressed(KeyCode::KeyB),
+                InputEventKind::MouseBtnPressed(MouseButton::Left),
+                InputEventKind::KeyReleased(KeyCode::KeyA),
+            ]
+        );
+
+        list.tick();
+        assert!(list.is_empty());
+    }
+}
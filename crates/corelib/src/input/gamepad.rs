@@ -7,6 +7,8 @@
 //     Unknown,
 // }
 
+use super::stick::{apply_stick_config, GamepadStick};
+use crate::math::Vec2;
 use crate::option_usize_env;
 use crate::utils::EnumSet;
 use arrayvec::ArrayVec;
@@ -197,10 +199,36 @@ impl GamepadInfo {
         self.axis.set_strength(axis, strength);
     }
 
+    /// Axis value, the sticks have the dead zone and response curve applied
     pub fn axis_strength(&self, axis: GamepadAxis) -> f32 {
+        match axis {
+            GamepadAxis::LeftX => self.stick(GamepadStick::Left).x,
+            GamepadAxis::LeftY => self.stick(GamepadStick::Left).y,
+            GamepadAxis::RightX => self.stick(GamepadStick::Right).x,
+            GamepadAxis::RightY => self.stick(GamepadStick::Right).y,
+            _ => self.axis.strength(axis),
+        }
+    }
+
+    /// Axis value as reported by the device
+    pub fn raw_axis_strength(&self, axis: GamepadAxis) -> f32 {
         self.axis.strength(axis)
     }
 
+    /// Stick position with the dead zone and response curve applied
+    pub fn stick(&self, stick: GamepadStick) -> Vec2 {
+        apply_stick_config(stick, self.raw_stick(stick))
+    }
+
+    /// Stick position as reported by the device
+    pub fn raw_stick(&self, stick: GamepadStick) -> Vec2 {
+        let (x, y) = match stick {
+            GamepadStick::Left => (GamepadAxis::LeftX, GamepadAxis::LeftY),
+            GamepadStick::Right => (GamepadAxis::RightX, GamepadAxis::RightY),
+        };
+        Vec2::new(self.axis.strength(x), self.axis.strength(y))
+    }
+
     pub fn are_pressed<const N: usize>(&self, btns: &[GamepadButton; N]) -> [bool; N] {
         let mut res = [false; N];
         btns.iter().enumerate().for_each(|(i, &btn)| {
@@ -0,0 +1,226 @@
+use crate::input::{is_key_pressed, KeyCode};
+
+#[cfg(feature = "gamepad")]
+use crate::input::{gamepads_available, is_gamepad_btn_pressed, GamepadButton, GamepadId};
+
+/// Device that can be assigned to a player
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum InputDevice {
+    /// Section of the keyboard defined by the game, e.g. 0 for WASD and 1 for the arrows
+    Keyboard(u8),
+    #[cfg(feature = "gamepad")]
+    Gamepad(GamepadId),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlayerInputEvent {
+    Joined { player: usize, device: InputDevice },
+    Left { player: usize, device: InputDevice },
+}
+
+/// Assigns input devices to local player slots. Devices join pressing their join key or button,
+/// and the gamepads leave when they are disconnected
+pub struct PlayerInputMap {
+    slots: Vec<Option<InputDevice>>,
+    keyboard_join: Vec<(u8, KeyCode)>,
+    #[cfg(feature = "gamepad")]
+    gamepad_join: Option<GamepadButton>,
+    events: Vec<PlayerInputEvent>,
+}
+
+impl PlayerInputMap {
+    pub fn new(max_players: usize) -> Self {
+        Self {
+            slots: vec![None; max_players],
+            keyboard_join: vec![],
+            #[cfg(feature = "gamepad")]
+            gamepad_join: Some(GamepadButton::Start),
+            events: vec![],
+        }
+    }
+
+    /// The keyboard section joins when the key is pressed
+    pub fn with_keyboard_join(mut self, section: u8, key: KeyCode) -> Self {
+        self.keyboard_join.retain(|(s, _)| *s != section);
+        self.keyboard_join.push((section, key));
+        self
+    }
+
+    /// Button used by the gamepads to join, `None` disables it. Default is `Start`
+    #[cfg(feature = "gamepad")]
+    pub fn with_gamepad_join(mut self, btn: Option<GamepadButton>) -> Self {
+        self.gamepad_join = btn;
+        self
+    }
+
+    /// Checks the join inputs and the disconnected gamepads, must be called once per frame
+    pub fn update(&mut self) {
+        self.events.clear();
+
+        #[allow(unused_mut)]
+        let mut joins = self
+            .keyboard_join
+            .iter()
+            .filter(|(_, key)| is_key_pressed(*key))
+            .map(|(section, _)| InputDevice::Keyboard(*section))
+            .collect::<Vec<_>>();
+
+        #[cfg(feature = "gamepad")]
+        {
+            let gamepads = gamepads_available();
+            if let Some(btn) = self.gamepad_join {
+                joins.extend(
+                    gamepads
+                        .iter()
+                        .filter(|id| is_gamepad_btn_pressed(**id, btn))
+                        .map(|id| InputDevice::Gamepad(*id)),
+                );
+            }
+
+            self.sync(
+                |device| match device {
+                    InputDevice::Keyboard(_) => true,
+                    InputDevice::Gamepad(id) => gamepads.iter().any(|gp| gp == id),
+                },
+                &joins,
+            );
+        }
+
+        #[cfg(not(feature = "gamepad"))]
+        self.sync(|_| true, &joins);
+    }
+
+    fn sync<F>(&mut self, is_connected: F, joins: &[InputDevice])
+    where
+        F: Fn(&InputDevice) -> bool,
+    {
+        let disconnected = self
+            .slots
+            .iter()
+            .flatten()
+            .filter(|device| !is_connected(device))
+            .copied()
+            .collect::<Vec<_>>();
+        disconnected.into_iter().for_each(|device| {
+            self.remove_device(device);
+        });
+
+        joins.iter().for_each(|device| {
+            self.assign(*device);
+        });
+    }
+
+    /// Assigns the device to the first free slot, returns the player assigned
+    pub fn assign(&mut self, device: InputDevice) -> Option<usize> {
+        if let Some(player) = self.device_player(device) {
+            return Some(player);
+        }
+
+        let player = self.slots.iter().position(|slot| slot.is_none())?;
+        self.slots[player] = Some(device);
+        self.events
+            .push(PlayerInputEvent::Joined { player, device });
+        Some(player)
+    }
+
+    /// Frees the slot used by the device, returns the player that left
+    pub fn remove_device(&mut self, device: InputDevice) -> Option<usize> {
+        let player = self.device_player(device)?;
+        self.remove_player(player);
+        Some(player)
+    }
+
+    /// Frees the player slot, returns the device that was assigned
+    pub fn remove_player(&mut self, player: usize) -> Option<InputDevice> {
+        let device = self.slots.get_mut(player)?.take()?;
+        self.events.push(PlayerInputEvent::Left { player, device });
+        Some(device)
+    }
+
+    pub fn player_device(&self, player: usize) -> Option<InputDevice> {
+        self.slots.get(player).copied().flatten()
+    }
+
+    pub fn device_player(&self, device: InputDevice) -> Option<usize> {
+        self.slots.iter().position(|slot| *slot == Some(device))
+    }
+
+    /// Iterates the assigned players and their devices
+    pub fn players(&self) -> impl Iterator<Item = (usize, InputDevice)> + '_ {
+        self.slots
+            .iter()
+            .enumerate()
+            .filter_map(|(player, slot)| slot.map(|device| (player, device)))
+    }
+
+    pub fn len(&self) -> usize {
+        self.slots.iter().flatten().count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.slots.iter().all(|slot| slot.is_some())
+    }
+
+    /// Players that joined or left since the last update
+    pub fn events(&self) -> &[PlayerInputEvent] {
+        &self.events
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_assign() {
+        let mut map = PlayerInputMap::new(2);
+        assert_eq!(map.assign(InputDevice::Keyboard(0)), Some(0));
+        assert_eq!(map.assign(InputDevice::Keyboard(0)), Some(0));
+        assert_eq!(map.assign(InputDevice::Keyboard(1)), Some(1));
+        assert_eq!(map.assign(InputDevice::Keyboard(2)), None);
+        assert!(map.is_full());
+
+        assert_eq!(map.remove_player(0), Some(InputDevice::Keyboard(0)));
+        assert_eq!(map.assign(InputDevice::Keyboard(2)), Some(0));
+        assert_eq!(map.device_player(InputDevice::Keyboard(1)), Some(1));
+        assert_eq!(map.len(), 2);
+    }
+
+    #[test]
+    fn test_sync_events() {
+        let mut map = PlayerInputMap::new(4);
+        map.sync(
+            |_| true,
+            &[InputDevice::Keyboard(0), InputDevice::Keyboard(1)],
+        );
+        assert_eq!(
+            map.events(),
+            &[
+                PlayerInputEvent::Joined {
+                    player: 0,
+                    device: InputDevice::Keyboard(0)
+                },
+                PlayerInputEvent::Joined {
+                    player: 1,
+                    device: InputDevice::Keyboard(1)
+                },
+            ]
+        );
+
+        map.events.clear();
+        map.sync(|device| *device != InputDevice::Keyboard(0), &[]);
+        assert_eq!(
+            map.events(),
+            &[PlayerInputEvent::Left {
+                player: 0,
+                device: InputDevice::Keyboard(0)
+            }]
+        );
+        assert_eq!(map.player_device(0), None);
+        assert_eq!(map.player_device(1), Some(InputDevice::Keyboard(1)));
+    }
+}
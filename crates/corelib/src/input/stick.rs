@@ -0,0 +1,204 @@
+use crate::math::Vec2;
+use atomic_refcell::AtomicRefCell;
+use once_cell::sync::Lazy;
+use std::sync::Arc;
+
+static STICK_CONFIG: Lazy<AtomicRefCell<[StickConfig; 2]>> =
+    Lazy::new(|| AtomicRefCell::new(Default::default()));
+
+pub(crate) fn set_stick_config(stick: GamepadStick, config: StickConfig) {
+    STICK_CONFIG.borrow_mut()[stick as usize] = config;
+}
+
+pub(crate) fn stick_config(stick: GamepadStick) -> StickConfig {
+    STICK_CONFIG.borrow()[stick as usize].clone()
+}
+
+pub(crate) fn apply_stick_config(stick: GamepadStick, raw: Vec2) -> Vec2 {
+    STICK_CONFIG.borrow()[stick as usize].apply(raw)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GamepadStick {
+    Left,
+    Right,
+}
+
+/// How the dead zone is checked against the stick position
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum DeadZoneShape {
+    /// Each axis is checked on its own, it makes easier to move in straight lines
+    Axial,
+    /// The stick length is checked, values outside the dead zone are not rescaled
+    Radial,
+    /// The stick length is checked and rescaled to go from 0.0 after the dead zone
+    #[default]
+    ScaledRadial,
+}
+
+/// Curve applied to the stick values once the dead zone has been removed
+#[derive(Clone, Default)]
+pub enum ResponseCurve {
+    #[default]
+    Linear,
+    /// Squares the value, gives more precision to small movements
+    Squared,
+    /// Custom fn that maps a value from 0.0..=1.0 to 0.0..=1.0,
+    /// e.g. `ResponseCurve::custom(|v| v.powf(3.0))`
+    Custom(Arc<dyn Fn(f32) -> f32 + Send + Sync>),
+}
+
+impl ResponseCurve {
+    pub fn custom<F: Fn(f32) -> f32 + Send + Sync + 'static>(cb: F) -> Self {
+        Self::Custom(Arc::new(cb))
+    }
+
+    fn apply(&self, value: f32) -> f32 {
+        let value = value.clamp(0.0, 1.0);
+        match self {
+            ResponseCurve::Linear => value,
+            ResponseCurve::Squared => value * value,
+            ResponseCurve::Custom(cb) => cb(value).clamp(0.0, 1.0),
+        }
+    }
+}
+
+impl std::fmt::Debug for ResponseCurve {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ResponseCurve::Linear => write!(f, "Linear"),
+            ResponseCurve::Squared => write!(f, "Squared"),
+            ResponseCurve::Custom(_) => write!(f, "Custom"),
+        }
+    }
+}
+
+/// Dead zone and response curve applied to the values of a stick.
+/// The default config has no dead zone and a linear curve, so the values are not changed
+#[derive(Debug, Clone)]
+pub struct StickConfig {
+    pub dead_zone: f32,
+    pub shape: DeadZoneShape,
+    pub curve: ResponseCurve,
+}
+
+impl Default for StickConfig {
+    fn default() -> Self {
+        Self {
+            dead_zone: 0.0,
+            shape: DeadZoneShape::default(),
+            curve: ResponseCurve::default(),
+        }
+    }
+}
+
+impl StickConfig {
+    pub fn with_dead_zone(mut self, dead_zone: f32) -> Self {
+        self.dead_zone = dead_zone;
+        self
+    }
+
+    pub fn with_shape(mut self, shape: DeadZoneShape) -> Self {
+        self.shape = shape;
+        self
+    }
+
+    pub fn with_curve(mut self, curve: ResponseCurve) -> Self {
+        self.curve = curve;
+        self
+    }
+
+    /// Returns the stick position after applying the dead zone and the response curve
+    pub fn apply(&self, raw: Vec2) -> Vec2 {
+        if self.dead_zone <= 0.0 && matches!(self.curve, ResponseCurve::Linear) {
+            return raw;
+        }
+
+        let dead_zone = self.dead_zone.clamp(0.0, 0.99);
+        match self.shape {
+            DeadZoneShape::Axial => Vec2::new(
+                self.apply_axis(raw.x, dead_zone),
+                self.apply_axis(raw.y, dead_zone),
+            ),
+            DeadZoneShape::Radial | DeadZoneShape::ScaledRadial => {
+                let len = raw.length();
+                if len <= dead_zone {
+                    return Vec2::ZERO;
+                }
+
+                let len_in = len.min(1.0);
+                let len_out = match self.shape {
+                    DeadZoneShape::ScaledRadial => (len_in - dead_zone) / (1.0 - dead_zone),
+                    _ => len_in,
+                };
+                raw / len * self.curve.apply(len_out)
+            }
+        }
+    }
+
+    fn apply_axis(&self, value: f32, dead_zone: f32) -> f32 {
+        let abs = value.abs();
+        if abs <= dead_zone {
+            return 0.0;
+        }
+
+        let scaled = (abs.min(1.0) - dead_zone) / (1.0 - dead_zone);
+        self.curve.apply(scaled).copysign(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn approx(a: Vec2, b: Vec2) -> bool {
+        (a - b).length() < 0.0001
+    }
+
+    #[test]
+    fn test_default_passthrough() {
+        let cfg = StickConfig::default();
+        let pos = Vec2::new(0.05, -1.0);
+        assert_eq!(cfg.apply(pos), pos);
+        assert_eq!(cfg.apply(Vec2::new(1.0, 1.0)), Vec2::new(1.0, 1.0));
+    }
+
+    #[test]
+    fn test_dead_zone() {
+        let cfg = StickConfig::default().with_dead_zone(0.2);
+        assert_eq!(cfg.apply(Vec2::new(0.1, 0.1)), Vec2::ZERO);
+        assert!(approx(cfg.apply(Vec2::new(1.0, 0.0)), Vec2::new(1.0, 0.0)));
+        assert!(approx(cfg.apply(Vec2::new(0.6, 0.0)), Vec2::new(0.5, 0.0)));
+    }
+
+    #[test]
+    fn test_shapes() {
+        let pos = Vec2::new(0.6, 0.1);
+
+        let axial = StickConfig::default()
+            .with_dead_zone(0.2)
+            .with_shape(DeadZoneShape::Axial);
+        assert!(approx(axial.apply(pos), Vec2::new(0.5, 0.0)));
+
+        let radial = axial.with_shape(DeadZoneShape::Radial);
+        assert!(approx(radial.apply(pos), pos));
+    }
+
+    #[test]
+    fn test_curves() {
+        let squared = StickConfig::default()
+            .with_dead_zone(0.0)
+            .with_curve(ResponseCurve::Squared);
+        assert!(approx(
+            squared.apply(Vec2::new(0.0, -0.5)),
+            Vec2::new(0.0, -0.25)
+        ));
+
+        let custom = squared.with_curve(ResponseCurve::custom(|_| 1.0));
+        assert!(approx(
+            custom.apply(Vec2::new(0.5, 0.0)),
+            Vec2::new(1.0, 0.0)
+        ));
+        assert_eq!(custom.apply(Vec2::ZERO), Vec2::ZERO);
+    }
+}
@@ -1,5 +1,10 @@
 pub use glam::*;
 
+#[cfg(feature = "fixed")]
+pub mod fixed;
+pub mod grid;
+pub mod hex;
+
 #[derive(Default, Clone, Copy, Debug, PartialEq)]
 pub struct Rect {
     pub origin: Vec2,
@@ -62,6 +67,80 @@ impl Rect {
     }
 }
 
+/// Reduces the number of points of a polyline using the Ramer-Douglas-Peucker algorithm,
+/// points closer than `tolerance` to the simplified line are removed
+pub fn simplify_polyline(points: &[Vec2], tolerance: f32) -> Vec<Vec2> {
+    if points.len() < 3 {
+        return points.to_vec();
+    }
+
+    let mut keep = vec![false; points.len()];
+    keep[0] = true;
+    keep[points.len() - 1] = true;
+
+    let mut stack = vec![(0, points.len() - 1)];
+    while let Some((start, end)) = stack.pop() {
+        let a = points[start];
+        let b = points[end];
+
+        let mut max_dist = 0.0;
+        let mut index = start;
+        for (i, p) in points.iter().enumerate().take(end).skip(start + 1) {
+            let dist = distance_to_segment(*p, a, b);
+            if dist > max_dist {
+                max_dist = dist;
+                index = i;
+            }
+        }
+
+        if max_dist > tolerance {
+            keep[index] = true;
+            stack.push((start, index));
+            stack.push((index, end));
+        }
+    }
+
+    points
+        .iter()
+        .zip(keep)
+        .filter_map(|(p, keep)| keep.then_some(*p))
+        .collect()
+}
+
+/// Smooths a polyline using Chaikin's corner cutting algorithm,
+/// the first and last points are preserved
+pub fn smooth_polyline(points: &[Vec2], iterations: usize) -> Vec<Vec2> {
+    let mut current = points.to_vec();
+    for _ in 0..iterations {
+        if current.len() < 3 {
+            break;
+        }
+
+        let mut next = Vec::with_capacity(current.len() * 2);
+        next.push(current[0]);
+        current.windows(2).for_each(|w| {
+            let (p1, p2) = (w[0], w[1]);
+            next.push(p1.lerp(p2, 0.25));
+            next.push(p1.lerp(p2, 0.75));
+        });
+        next.push(current[current.len() - 1]);
+        current = next;
+    }
+
+    current
+}
+
+fn distance_to_segment(p: Vec2, a: Vec2, b: Vec2) -> f32 {
+    let ab = b - a;
+    let len_sq = ab.length_squared();
+    if len_sq == 0.0 {
+        return p.distance(a);
+    }
+
+    let t = ((p - a).dot(ab) / len_sq).clamp(0.0, 1.0);
+    p.distance(a + ab * t)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -95,4 +174,53 @@ mod tests {
         assert!(!rect1.intersects(&rect3));
         assert!(rect1.intersects(&rect4));
     }
+
+    #[test]
+    fn test_simplify_polyline() {
+        let points = [
+            vec2(0.0, 0.0),
+            vec2(1.0, 0.1),
+            vec2(2.0, -0.1),
+            vec2(3.0, 5.0),
+            vec2(4.0, 6.0),
+            vec2(5.0, 7.0),
+        ];
+
+        let simplified = simplify_polyline(&points, 0.5);
+        assert_eq!(
+            simplified,
+            vec![
+                vec2(0.0, 0.0),
+                vec2(2.0, -0.1),
+                vec2(3.0, 5.0),
+                vec2(5.0, 7.0)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_simplify_polyline_keeps_short_lines() {
+        let points = [vec2(0.0, 0.0), vec2(1.0, 1.0)];
+        assert_eq!(simplify_polyline(&points, 10.0), points.to_vec());
+    }
+
+    #[test]
+    fn test_smooth_polyline() {
+        let points = [vec2(0.0, 0.0), vec2(4.0, 0.0), vec2(4.0, 4.0)];
+
+        let smoothed = smooth_polyline(&points, 1);
+        assert_eq!(
+            smoothed,
+            vec![
+                vec2(0.0, 0.0),
+                vec2(1.0, 0.0),
+                vec2(3.0, 0.0),
+                vec2(4.0, 1.0),
+                vec2(4.0, 3.0),
+                vec2(4.0, 4.0),
+            ]
+        );
+
+        assert_eq!(smooth_polyline(&points, 0), points.to_vec());
+    }
 }
@@ -0,0 +1,526 @@
+//! Q16.16 fixed-point numbers for deterministic gameplay (lockstep, replays...).
+//! Every operation uses integer math so the results are the same on every platform,
+//! overflows wrap instead of panicking to keep debug and release builds in sync.
+
+use crate::math::Vec2;
+use std::fmt;
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+const FRAC_BITS: u32 = 16;
+
+// trigonometry is calculated with CORDIC using 30 fractional bits
+const CORDIC_BITS: u32 = 30;
+const CORDIC_SHIFT: u32 = CORDIC_BITS - FRAC_BITS;
+const CORDIC_K: i64 = 652032874;
+const CORDIC_PI: i64 = 3373259426;
+const CORDIC_TAU: i64 = 6746518852;
+const CORDIC_FRAC_PI_2: i64 = 1686629713;
+const CORDIC_ATAN: [i64; 30] = [
+    843314857, 497837829, 263043837, 133525159, 67021687, 33543516, 16775851, 8388437, 4194283,
+    2097149, 1048576, 524288, 262144, 131072, 65536, 32768, 16384, 8192, 4096, 2048, 1024, 512,
+    256, 128, 64, 32, 16, 8, 4, 2,
+];
+
+/// Q16.16 fixed-point number
+#[derive(Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Fixed(i32);
+
+impl Fixed {
+    pub const ZERO: Self = Self(0);
+    pub const ONE: Self = Self(1 << FRAC_BITS);
+    pub const HALF: Self = Self(1 << (FRAC_BITS - 1));
+    pub const MIN: Self = Self(i32::MIN);
+    pub const MAX: Self = Self(i32::MAX);
+    /// Smallest positive value
+    pub const EPSILON: Self = Self(1);
+    pub const PI: Self = Self(205887);
+    pub const TAU: Self = Self(411775);
+    pub const FRAC_PI_2: Self = Self(102944);
+
+    pub const fn from_raw(raw: i32) -> Self {
+        Self(raw)
+    }
+
+    pub const fn raw(self) -> i32 {
+        self.0
+    }
+
+    pub const fn from_int(value: i32) -> Self {
+        Self(value.wrapping_shl(FRAC_BITS))
+    }
+
+    /// Converts from f32 rounding to the nearest value, do not use it on gameplay
+    /// code that needs determinism from values calculated at runtime
+    pub fn from_f32(value: f32) -> Self {
+        Self((value * Self::ONE.0 as f32).round() as i32)
+    }
+
+    pub fn to_f32(self) -> f32 {
+        self.0 as f32 / Self::ONE.0 as f32
+    }
+
+    /// Integer part rounded towards negative infinity
+    pub const fn to_int(self) -> i32 {
+        self.0 >> FRAC_BITS
+    }
+
+    pub const fn floor(self) -> Self {
+        Self(self.0 & !(Self::ONE.0 - 1))
+    }
+
+    pub const fn ceil(self) -> Self {
+        Self(self.0.wrapping_add(Self::ONE.0 - 1)).floor()
+    }
+
+    pub const fn round(self) -> Self {
+        Self(self.0.wrapping_add(Self::HALF.0)).floor()
+    }
+
+    pub const fn fract(self) -> Self {
+        Self(self.0 & (Self::ONE.0 - 1))
+    }
+
+    pub const fn abs(self) -> Self {
+        Self(self.0.wrapping_abs())
+    }
+
+    pub const fn signum(self) -> Self {
+        Self::from_int(self.0.signum())
+    }
+
+    pub fn min(self, other: Self) -> Self {
+        Ord::min(self, other)
+    }
+
+    pub fn max(self, other: Self) -> Self {
+        Ord::max(self, other)
+    }
+
+    pub fn clamp(self, min: Self, max: Self) -> Self {
+        Ord::clamp(self, min, max)
+    }
+
+    pub fn lerp(self, to: Self, t: Self) -> Self {
+        self + (to - self) * t
+    }
+
+    /// Square root, negative values return zero
+    pub fn sqrt(self) -> Self {
+        if self.0 <= 0 {
+            return Self::ZERO;
+        }
+
+        Self(isqrt((self.0 as u64) << FRAC_BITS) as i32)
+    }
+
+    pub fn sin(self) -> Self {
+        self.sin_cos().0
+    }
+
+    pub fn cos(self) -> Self {
+        self.sin_cos().1
+    }
+
+    pub fn sin_cos(self) -> (Self, Self) {
+        // reduce the angle to -PI/2..=PI/2 flipping the cosine when needed
+        let mut z = ((self.0 as i64) << CORDIC_SHIFT).rem_euclid(CORDIC_TAU);
+        if z > CORDIC_PI {
+            z -= CORDIC_TAU;
+        }
+
+        let mut cos_sign = 1;
+        if z > CORDIC_FRAC_PI_2 {
+            z = CORDIC_PI - z;
+            cos_sign = -1;
+        } else if z < -CORDIC_FRAC_PI_2 {
+            z = -CORDIC_PI - z;
+            cos_sign = -1;
+        }
+
+        let (mut x, mut y) = (CORDIC_K, 0i64);
+        for (i, atan) in CORDIC_ATAN.iter().enumerate() {
+            let (dx, dy) = (y >> i, x >> i);
+            if z >= 0 {
+                x -= dx;
+                y += dy;
+                z -= atan;
+            } else {
+                x += dx;
+                y -= dy;
+                z += atan;
+            }
+        }
+
+        (from_cordic(y), from_cordic(x * cos_sign))
+    }
+
+    pub fn tan(self) -> Self {
+        let (sin, cos) = self.sin_cos();
+        sin / cos
+    }
+
+    /// Angle of the point `(x, y)` from -PI to PI
+    pub fn atan2(self, x: Self) -> Self {
+        let (mut x, mut y) = (x.0 as i64, self.0 as i64);
+        if x == 0 && y == 0 {
+            return Self::ZERO;
+        }
+
+        // rotate the vector to the right half plane
+        let mut z = 0;
+        if x < 0 {
+            (x, y, z) = if y >= 0 {
+                (y, -x, CORDIC_FRAC_PI_2)
+            } else {
+                (-y, x, -CORDIC_FRAC_PI_2)
+            };
+        }
+
+        // extra precision for the small vectors
+        let (mut x, mut y) = (x << CORDIC_SHIFT, y << CORDIC_SHIFT);
+        for (i, atan) in CORDIC_ATAN.iter().enumerate() {
+            let (dx, dy) = (y >> i, x >> i);
+            if y > 0 {
+                x += dx;
+                y -= dy;
+                z += atan;
+            } else {
+                x -= dx;
+                y += dy;
+                z -= atan;
+            }
+        }
+
+        from_cordic(z)
+    }
+
+    pub fn to_radians(self) -> Self {
+        self * Self::PI / Self::from_int(180)
+    }
+
+    pub fn to_degrees(self) -> Self {
+        self * Self::from_int(180) / Self::PI
+    }
+}
+
+fn from_cordic(value: i64) -> Fixed {
+    let half = 1 << (CORDIC_SHIFT - 1);
+    Fixed(((value + half) >> CORDIC_SHIFT) as i32)
+}
+
+fn isqrt(value: u64) -> u64 {
+    let mut res = 0u64;
+    let mut bit = 1u64 << 62;
+    let mut value = value;
+    while bit > value {
+        bit >>= 2;
+    }
+
+    while bit != 0 {
+        if value >= res + bit {
+            value -= res + bit;
+            res = (res >> 1) + bit;
+        } else {
+            res >>= 1;
+        }
+        bit >>= 2;
+    }
+
+    res
+}
+
+impl From<i32> for Fixed {
+    fn from(value: i32) -> Self {
+        Self::from_int(value)
+    }
+}
+
+impl From<Fixed> for f32 {
+    fn from(value: Fixed) -> Self {
+        value.to_f32()
+    }
+}
+
+impl fmt::Debug for Fixed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Fixed({})", self.to_f32())
+    }
+}
+
+impl fmt::Display for Fixed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.to_f32(), f)
+    }
+}
+
+impl Add for Fixed {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self(self.0.wrapping_add(rhs.0))
+    }
+}
+
+impl Sub for Fixed {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        Self(self.0.wrapping_sub(rhs.0))
+    }
+}
+
+impl Mul for Fixed {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        Self(((self.0 as i64 * rhs.0 as i64) >> FRAC_BITS) as i32)
+    }
+}
+
+impl Div for Fixed {
+    type Output = Self;
+
+    /// Dividing by zero returns `MAX` or `MIN` depending on the sign
+    fn div(self, rhs: Self) -> Self {
+        if rhs.0 == 0 {
+            return if self.0 >= 0 { Self::MAX } else { Self::MIN };
+        }
+
+        Self((((self.0 as i64) << FRAC_BITS) / rhs.0 as i64) as i32)
+    }
+}
+
+impl Neg for Fixed {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self(self.0.wrapping_neg())
+    }
+}
+
+impl AddAssign for Fixed {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl SubAssign for Fixed {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl MulAssign for Fixed {
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = *self * rhs;
+    }
+}
+
+impl DivAssign for Fixed {
+    fn div_assign(&mut self, rhs: Self) {
+        *self = *self / rhs;
+    }
+}
+
+/// 2D vector of Q16.16 fixed-point numbers
+#[derive(Default, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct FixedVec2 {
+    pub x: Fixed,
+    pub y: Fixed,
+}
+
+impl FixedVec2 {
+    pub const ZERO: Self = Self::new(Fixed::ZERO, Fixed::ZERO);
+    pub const ONE: Self = Self::new(Fixed::ONE, Fixed::ONE);
+    pub const X: Self = Self::new(Fixed::ONE, Fixed::ZERO);
+    pub const Y: Self = Self::new(Fixed::ZERO, Fixed::ONE);
+
+    pub const fn new(x: Fixed, y: Fixed) -> Self {
+        Self { x, y }
+    }
+
+    pub const fn splat(value: Fixed) -> Self {
+        Self::new(value, value)
+    }
+
+    pub fn from_angle(angle: Fixed) -> Self {
+        let (sin, cos) = angle.sin_cos();
+        Self::new(cos, sin)
+    }
+
+    /// Converts from a `Vec2`, see `Fixed::from_f32`
+    pub fn from_vec2(value: Vec2) -> Self {
+        Self::new(Fixed::from_f32(value.x), Fixed::from_f32(value.y))
+    }
+
+    pub fn to_vec2(self) -> Vec2 {
+        Vec2::new(self.x.to_f32(), self.y.to_f32())
+    }
+
+    pub fn dot(self, rhs: Self) -> Fixed {
+        self.x * rhs.x + self.y * rhs.y
+    }
+
+    pub fn perp_dot(self, rhs: Self) -> Fixed {
+        self.x * rhs.y - self.y * rhs.x
+    }
+
+    pub fn length_squared(self) -> Fixed {
+        self.dot(self)
+    }
+
+    pub fn length(self) -> Fixed {
+        self.length_squared().sqrt()
+    }
+
+    pub fn distance(self, rhs: Self) -> Fixed {
+        (self - rhs).length()
+    }
+
+    pub fn normalize_or_zero(self) -> Self {
+        let len = self.length();
+        if len == Fixed::ZERO {
+            return Self::ZERO;
+        }
+
+        Self::new(self.x / len, self.y / len)
+    }
+
+    /// Angle of the vector from -PI to PI
+    pub fn to_angle(self) -> Fixed {
+        self.y.atan2(self.x)
+    }
+
+    pub fn rotate(self, angle: Fixed) -> Self {
+        let (sin, cos) = angle.sin_cos();
+        Self::new(self.x * cos - self.y * sin, self.x * sin + self.y * cos)
+    }
+
+    pub fn lerp(self, to: Self, t: Fixed) -> Self {
+        Self::new(self.x.lerp(to.x, t), self.y.lerp(to.y, t))
+    }
+}
+
+impl From<FixedVec2> for Vec2 {
+    fn from(value: FixedVec2) -> Self {
+        value.to_vec2()
+    }
+}
+
+impl Add for FixedVec2 {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self::new(self.x + rhs.x, self.y + rhs.y)
+    }
+}
+
+impl Sub for FixedVec2 {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        Self::new(self.x - rhs.x, self.y - rhs.y)
+    }
+}
+
+impl Mul<Fixed> for FixedVec2 {
+    type Output = Self;
+
+    fn mul(self, rhs: Fixed) -> Self {
+        Self::new(self.x * rhs, self.y * rhs)
+    }
+}
+
+impl Div<Fixed> for FixedVec2 {
+    type Output = Self;
+
+    fn div(self, rhs: Fixed) -> Self {
+        Self::new(self.x / rhs, self.y / rhs)
+    }
+}
+
+impl Neg for FixedVec2 {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self::new(-self.x, -self.y)
+    }
+}
+
+impl AddAssign for FixedVec2 {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl SubAssign for FixedVec2 {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl MulAssign<Fixed> for FixedVec2 {
+    fn mul_assign(&mut self, rhs: Fixed) {
+        *self = *self * rhs;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn approx(a: Fixed, b: f32) -> bool {
+        (a.to_f32() - b).abs() < 0.0005
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let a = Fixed::from_f32(2.5);
+        let b = Fixed::from_int(-2);
+        assert_eq!((a + b).to_f32(), 0.5);
+        assert_eq!((a * b).to_f32(), -5.0);
+        assert_eq!((a / b).to_f32(), -1.25);
+        assert_eq!(Fixed::from_int(1) / Fixed::ZERO, Fixed::MAX);
+        assert_eq!(Fixed::from_f32(-1.5).floor().to_int(), -2);
+        assert_eq!(Fixed::from_f32(-1.5).ceil().to_int(), -1);
+        assert_eq!(Fixed::from_f32(1.5).round().to_int(), 2);
+        assert_eq!(Fixed::from_int(16).sqrt(), Fixed::from_int(4));
+        assert!(approx(Fixed::from_int(2).sqrt(), std::f32::consts::SQRT_2));
+    }
+
+    #[test]
+    fn test_trig() {
+        for deg in (-720..=720).step_by(15) {
+            let rad = (deg as f32).to_radians();
+            let angle = Fixed::from_int(deg).to_radians();
+            let (sin, cos) = angle.sin_cos();
+            assert!(approx(sin, rad.sin()), "sin({deg}) = {sin:?}");
+            assert!(approx(cos, rad.cos()), "cos({deg}) = {cos:?}");
+        }
+    }
+
+    #[test]
+    fn test_atan2() {
+        for deg in (-165..=180).step_by(15) {
+            let rad = (deg as f32).to_radians();
+            let v = FixedVec2::from_vec2(Vec2::new(rad.cos(), rad.sin()) * 3.0);
+            assert!(
+                approx(v.to_angle(), rad),
+                "atan2({deg}) = {:?}",
+                v.to_angle()
+            );
+        }
+        assert_eq!(Fixed::ZERO.atan2(Fixed::ZERO), Fixed::ZERO);
+    }
+
+    #[test]
+    fn test_vec2() {
+        let v = FixedVec2::new(Fixed::from_int(3), Fixed::from_int(4));
+        assert_eq!(v.length(), Fixed::from_int(5));
+        assert!(approx(v.normalize_or_zero().length(), 1.0));
+
+        let r = FixedVec2::X.rotate(Fixed::FRAC_PI_2);
+        assert!(approx(r.x, 0.0) && approx(r.y, 1.0));
+        assert_eq!(v.to_vec2(), Vec2::new(3.0, 4.0));
+    }
+}
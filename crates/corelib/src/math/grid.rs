@@ -0,0 +1,164 @@
+//! Square grid helpers, cells are `IVec2` where `x` is the column and `y` the row
+
+use crate::math::{ivec2, IVec2, Vec2};
+
+/// Maps cells to world positions
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Grid {
+    pub cell_size: Vec2,
+    /// World position of the top-left corner of the cell `(0, 0)`
+    pub origin: Vec2,
+}
+
+impl Grid {
+    pub fn new(cell_size: Vec2) -> Self {
+        Self {
+            cell_size,
+            origin: Vec2::ZERO,
+        }
+    }
+
+    pub fn with_origin(mut self, origin: Vec2) -> Self {
+        self.origin = origin;
+        self
+    }
+
+    /// Cell that contains the world position
+    pub fn world_to_cell(&self, pos: Vec2) -> IVec2 {
+        ((pos - self.origin) / self.cell_size).floor().as_ivec2()
+    }
+
+    /// Top-left corner of the cell
+    pub fn cell_to_world(&self, cell: IVec2) -> Vec2 {
+        self.origin + cell.as_vec2() * self.cell_size
+    }
+
+    pub fn cell_center(&self, cell: IVec2) -> Vec2 {
+        self.cell_to_world(cell) + self.cell_size * 0.5
+    }
+}
+
+/// Index of the cell in a row-major array, `None` if the cell is outside the grid
+pub fn cell_to_index(cell: IVec2, width: u32, height: u32) -> Option<usize> {
+    let inside = cell.x >= 0 && cell.y >= 0 && (cell.x as u32) < width && (cell.y as u32) < height;
+    inside.then(|| cell.y as usize * width as usize + cell.x as usize)
+}
+
+/// Cell of the index in a row-major array
+pub fn index_to_cell(index: usize, width: u32) -> IVec2 {
+    let width = width.max(1) as usize;
+    ivec2((index % width) as _, (index / width) as _)
+}
+
+/// Up, right, down and left neighbours
+pub fn neighbors4(cell: IVec2) -> [IVec2; 4] {
+    [
+        cell + IVec2::NEG_Y,
+        cell + IVec2::X,
+        cell + IVec2::Y,
+        cell + IVec2::NEG_X,
+    ]
+}
+
+/// Neighbours including the diagonals, clockwise starting from up
+pub fn neighbors8(cell: IVec2) -> [IVec2; 8] {
+    [
+        cell + ivec2(0, -1),
+        cell + ivec2(1, -1),
+        cell + ivec2(1, 0),
+        cell + ivec2(1, 1),
+        cell + ivec2(0, 1),
+        cell + ivec2(-1, 1),
+        cell + ivec2(-1, 0),
+        cell + ivec2(-1, -1),
+    ]
+}
+
+pub fn manhattan_distance(a: IVec2, b: IVec2) -> u32 {
+    a.x.abs_diff(b.x) + a.y.abs_diff(b.y)
+}
+
+/// Distance allowing diagonal moves
+pub fn chebyshev_distance(a: IVec2, b: IVec2) -> u32 {
+    a.x.abs_diff(b.x).max(a.y.abs_diff(b.y))
+}
+
+/// Cells crossed by a line from `from` to `to` (both included) using Bresenham
+pub fn line(from: IVec2, to: IVec2) -> Vec<IVec2> {
+    let delta = (to - from).abs();
+    let step = (to - from).signum();
+    let mut err = delta.x - delta.y;
+    let mut current = from;
+    let mut cells = Vec::with_capacity(delta.x.max(delta.y) as usize + 1);
+    loop {
+        cells.push(current);
+        if current == to {
+            return cells;
+        }
+
+        let err2 = err * 2;
+        if err2 > -delta.y {
+            err -= delta.y;
+            current.x += step.x;
+        }
+        if err2 < delta.x {
+            err += delta.x;
+            current.y += step.y;
+        }
+    }
+}
+
+/// Cells at a manhattan distance of `radius` or less
+pub fn range(center: IVec2, radius: u32) -> impl Iterator<Item = IVec2> {
+    let r = radius as i32;
+    (-r..=r).flat_map(move |y| {
+        let w = r - y.abs();
+        (-w..=w).map(move |x| center + ivec2(x, y))
+    })
+}
+
+/// Cells at a manhattan distance of exactly `radius`
+pub fn ring(center: IVec2, radius: u32) -> impl Iterator<Item = IVec2> {
+    range(center, radius).filter(move |cell| manhattan_distance(center, *cell) == radius)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::vec2;
+
+    #[test]
+    fn test_world_conversion() {
+        let grid = Grid::new(vec2(16.0, 8.0)).with_origin(vec2(10.0, 10.0));
+        assert_eq!(grid.world_to_cell(vec2(10.0, 10.0)), ivec2(0, 0));
+        assert_eq!(grid.world_to_cell(vec2(9.0, 30.0)), ivec2(-1, 2));
+        assert_eq!(grid.cell_to_world(ivec2(2, 1)), vec2(42.0, 18.0));
+        assert_eq!(grid.cell_center(ivec2(0, 0)), vec2(18.0, 14.0));
+    }
+
+    #[test]
+    fn test_index() {
+        assert_eq!(cell_to_index(ivec2(2, 1), 4, 4), Some(6));
+        assert_eq!(cell_to_index(ivec2(4, 1), 4, 4), None);
+        assert_eq!(cell_to_index(ivec2(-1, 0), 4, 4), None);
+        assert_eq!(index_to_cell(6, 4), ivec2(2, 1));
+    }
+
+    #[test]
+    fn test_line() {
+        assert_eq!(
+            line(ivec2(0, 0), ivec2(3, 1)),
+            vec![ivec2(0, 0), ivec2(1, 0), ivec2(2, 1), ivec2(3, 1)]
+        );
+        assert_eq!(line(ivec2(2, 2), ivec2(2, 2)), vec![ivec2(2, 2)]);
+        assert_eq!(line(ivec2(0, 0), ivec2(-2, -2)).len(), 3);
+    }
+
+    #[test]
+    fn test_range_ring() {
+        assert_eq!(range(IVec2::ZERO, 0).count(), 1);
+        assert_eq!(range(IVec2::ZERO, 2).count(), 13);
+        assert_eq!(ring(IVec2::ZERO, 2).count(), 8);
+        assert!(ring(ivec2(5, 5), 1).all(|c| neighbors4(ivec2(5, 5)).contains(&c)));
+    }
+}
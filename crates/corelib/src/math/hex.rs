@@ -0,0 +1,297 @@
+//! Hexagonal grid helpers using axial coordinates
+
+use crate::math::{vec2, Vec2};
+use std::ops::{Add, Mul, Neg, Sub};
+
+const SQRT_3: f32 = 1.732_050_8;
+
+/// Hexagon cell in axial coordinates, the third cube coordinate is `s = -q - r`
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Hex {
+    pub q: i32,
+    pub r: i32,
+}
+
+impl Hex {
+    pub const ZERO: Self = Self::new(0, 0);
+
+    /// Neighbour directions, counter-clockwise starting from the right (pointy) or
+    /// bottom-right (flat)
+    pub const DIRECTIONS: [Self; 6] = [
+        Self::new(1, 0),
+        Self::new(1, -1),
+        Self::new(0, -1),
+        Self::new(-1, 0),
+        Self::new(-1, 1),
+        Self::new(0, 1),
+    ];
+
+    pub const fn new(q: i32, r: i32) -> Self {
+        Self { q, r }
+    }
+
+    pub const fn s(&self) -> i32 {
+        -self.q - self.r
+    }
+
+    pub fn neighbor(&self, direction: usize) -> Self {
+        *self + Self::DIRECTIONS[direction % 6]
+    }
+
+    pub fn neighbors(&self) -> [Self; 6] {
+        Self::DIRECTIONS.map(|dir| *self + dir)
+    }
+
+    pub fn length(&self) -> u32 {
+        (self.q.unsigned_abs() + self.r.unsigned_abs() + self.s().unsigned_abs()) / 2
+    }
+
+    pub fn distance(&self, other: Self) -> u32 {
+        (*self - other).length()
+    }
+
+    /// Rounds fractional axial coordinates to the nearest hex
+    pub fn round(q: f32, r: f32) -> Self {
+        let s = -q - r;
+        let (mut rq, mut rr, rs) = (q.round(), r.round(), s.round());
+        let (dq, dr, ds) = ((rq - q).abs(), (rr - r).abs(), (rs - s).abs());
+        if dq > dr && dq > ds {
+            rq = -rr - rs;
+        } else if dr > ds {
+            rr = -rq - rs;
+        }
+
+        Self::new(rq as _, rr as _)
+    }
+
+    /// Hexes crossed by a line from `self` to `to` (both included)
+    pub fn line(&self, to: Self) -> Vec<Self> {
+        let len = self.distance(to);
+        if len == 0 {
+            return vec![*self];
+        }
+
+        // nudge to avoid landing on the edges between hexes
+        let (aq, ar) = (self.q as f32 + 1e-6, self.r as f32 + 1e-6);
+        let (bq, br) = (to.q as f32 + 1e-6, to.r as f32 + 1e-6);
+        (0..=len)
+            .map(|i| {
+                let t = i as f32 / len as f32;
+                Self::round(aq + (bq - aq) * t, ar + (br - ar) * t)
+            })
+            .collect()
+    }
+
+    /// Hexes at a distance of `radius` or less
+    pub fn range(&self, radius: u32) -> impl Iterator<Item = Self> {
+        let center = *self;
+        let n = radius as i32;
+        (-n..=n).flat_map(move |q| {
+            let start = (-n).max(-q - n);
+            let end = n.min(-q + n);
+            (start..=end).map(move |r| center + Self::new(q, r))
+        })
+    }
+
+    /// Hexes at a distance of exactly `radius`
+    pub fn ring(&self, radius: u32) -> Vec<Self> {
+        if radius == 0 {
+            return vec![*self];
+        }
+
+        let mut hex = *self + Self::DIRECTIONS[4] * radius as i32;
+        let mut hexes = Vec::with_capacity(6 * radius as usize);
+        for dir in 0..6 {
+            for _ in 0..radius {
+                hexes.push(hex);
+                hex = hex.neighbor(dir);
+            }
+        }
+        hexes
+    }
+
+    /// Converts to offset coordinates `(col, row)`
+    pub fn to_offset(&self, kind: HexOffset) -> (i32, i32) {
+        match kind {
+            HexOffset::OddRow => (self.q + (self.r - (self.r & 1)) / 2, self.r),
+            HexOffset::EvenRow => (self.q + (self.r + (self.r & 1)) / 2, self.r),
+            HexOffset::OddCol => (self.q, self.r + (self.q - (self.q & 1)) / 2),
+            HexOffset::EvenCol => (self.q, self.r + (self.q + (self.q & 1)) / 2),
+        }
+    }
+
+    /// Converts from offset coordinates `(col, row)`
+    pub fn from_offset(col: i32, row: i32, kind: HexOffset) -> Self {
+        match kind {
+            HexOffset::OddRow => Self::new(col - (row - (row & 1)) / 2, row),
+            HexOffset::EvenRow => Self::new(col - (row + (row & 1)) / 2, row),
+            HexOffset::OddCol => Self::new(col, row - (col - (col & 1)) / 2),
+            HexOffset::EvenCol => Self::new(col, row - (col + (col & 1)) / 2),
+        }
+    }
+}
+
+impl Add for Hex {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self::new(self.q + rhs.q, self.r + rhs.r)
+    }
+}
+
+impl Sub for Hex {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        Self::new(self.q - rhs.q, self.r - rhs.r)
+    }
+}
+
+impl Mul<i32> for Hex {
+    type Output = Self;
+
+    fn mul(self, rhs: i32) -> Self {
+        Self::new(self.q * rhs, self.r * rhs)
+    }
+}
+
+impl Neg for Hex {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self::new(-self.q, -self.r)
+    }
+}
+
+/// Offset layouts used to store hex grids in 2D arrays, odd/even is the row
+/// or column that is pushed by half a hex
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum HexOffset {
+    OddRow,
+    EvenRow,
+    OddCol,
+    EvenCol,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum HexOrientation {
+    #[default]
+    Pointy,
+    Flat,
+}
+
+/// Maps hexes to world positions
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct HexLayout {
+    pub orientation: HexOrientation,
+    /// Distance from the center to a corner, can be different on each axis to squash the grid
+    pub size: Vec2,
+    /// World position of the center of the hex `(0, 0)`
+    pub origin: Vec2,
+}
+
+impl HexLayout {
+    pub fn new(orientation: HexOrientation, size: Vec2) -> Self {
+        Self {
+            orientation,
+            size,
+            origin: Vec2::ZERO,
+        }
+    }
+
+    pub fn with_origin(mut self, origin: Vec2) -> Self {
+        self.origin = origin;
+        self
+    }
+
+    /// Center of the hex
+    pub fn hex_to_world(&self, hex: Hex) -> Vec2 {
+        let (q, r) = (hex.q as f32, hex.r as f32);
+        let pos = match self.orientation {
+            HexOrientation::Pointy => vec2(SQRT_3 * q + SQRT_3 * 0.5 * r, 1.5 * r),
+            HexOrientation::Flat => vec2(1.5 * q, SQRT_3 * 0.5 * q + SQRT_3 * r),
+        };
+        self.origin + pos * self.size
+    }
+
+    /// Hex that contains the world position
+    pub fn world_to_hex(&self, pos: Vec2) -> Hex {
+        let p = (pos - self.origin) / self.size;
+        let (q, r) = match self.orientation {
+            HexOrientation::Pointy => (SQRT_3 / 3.0 * p.x - p.y / 3.0, 2.0 / 3.0 * p.y),
+            HexOrientation::Flat => (2.0 / 3.0 * p.x, -p.x / 3.0 + SQRT_3 / 3.0 * p.y),
+        };
+        Hex::round(q, r)
+    }
+
+    /// Corners of the hex, clockwise in screen space
+    pub fn corners(&self, hex: Hex) -> [Vec2; 6] {
+        let center = self.hex_to_world(hex);
+        let start = match self.orientation {
+            HexOrientation::Pointy => 30.0f32,
+            HexOrientation::Flat => 0.0,
+        };
+        std::array::from_fn(|i| {
+            let angle = (start + 60.0 * i as f32).to_radians();
+            center + vec2(angle.cos(), angle.sin()) * self.size
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_distance() {
+        assert_eq!(Hex::new(0, 0).distance(Hex::new(3, -1)), 3);
+        assert_eq!(Hex::new(-2, 2).distance(Hex::new(2, -2)), 4);
+        assert!(Hex::ZERO.neighbors().iter().all(|n| n.length() == 1));
+    }
+
+    #[test]
+    fn test_range_ring() {
+        assert_eq!(Hex::ZERO.range(2).count(), 19);
+        let ring = Hex::new(1, 1).ring(2);
+        assert_eq!(ring.len(), 12);
+        assert!(ring.iter().all(|h| h.distance(Hex::new(1, 1)) == 2));
+    }
+
+    #[test]
+    fn test_line() {
+        let line = Hex::ZERO.line(Hex::new(3, -3));
+        assert_eq!(line.len(), 4);
+        assert!(line.windows(2).all(|w| w[0].distance(w[1]) == 1));
+        assert_eq!(line[3], Hex::new(3, -3));
+    }
+
+    #[test]
+    fn test_offset() {
+        let kinds = [
+            HexOffset::OddRow,
+            HexOffset::EvenRow,
+            HexOffset::OddCol,
+            HexOffset::EvenCol,
+        ];
+        for kind in kinds {
+            for hex in Hex::new(1, -1).range(3) {
+                let (col, row) = hex.to_offset(kind);
+                assert_eq!(Hex::from_offset(col, row, kind), hex);
+            }
+        }
+        assert_eq!(Hex::new(0, 1).to_offset(HexOffset::OddRow), (0, 1));
+        assert_eq!(Hex::new(-1, 2).to_offset(HexOffset::OddRow), (0, 2));
+    }
+
+    #[test]
+    fn test_world() {
+        for orientation in [HexOrientation::Pointy, HexOrientation::Flat] {
+            let layout = HexLayout::new(orientation, vec2(10.0, 12.0)).with_origin(vec2(5.0, 5.0));
+            for hex in Hex::ZERO.range(3) {
+                let pos = layout.hex_to_world(hex);
+                assert_eq!(layout.world_to_hex(pos), hex);
+                assert_eq!(layout.world_to_hex(pos + vec2(3.0, -2.0)), hex);
+            }
+        }
+    }
+}
@@ -61,6 +61,12 @@ pub fn last_time() -> Option<Instant> {
     TIME_STATE.borrow().last_time()
 }
 
+/// Number of frames since application's init
+#[inline]
+pub fn frame_count() -> u64 {
+    TIME_STATE.borrow().frame_count()
+}
+
 /// Measure Application times
 #[derive(Debug, Clone)]
 pub(crate) struct Time {
@@ -73,6 +79,7 @@ pub(crate) struct Time {
     fps_cache: RingBuffer<f32, 30>,
     last_cached_fps_time: Instant,
     fps: f32,
+    frame_count: u64,
 }
 
 impl Default for Time {
@@ -87,6 +94,7 @@ impl Default for Time {
             fps_cache: Default::default(),
             last_cached_fps_time: Instant::now(),
             fps: 0.0,
+            frame_count: 0,
         }
     }
 }
@@ -102,6 +110,7 @@ impl Time {
         }
 
         self.last_time = Some(now);
+        self.frame_count += 1;
 
         self.elapsed = now - self.init_time;
         self.elapsed_time = self.elapsed.as_secs_f32();
@@ -155,6 +164,12 @@ impl Time {
     pub fn last_time(&self) -> Option<Instant> {
         self.last_time
     }
+
+    /// Number of frames since application's init
+    #[inline]
+    pub fn frame_count(&self) -> u64 {
+        self.frame_count
+    }
 }
 #[cfg(test)]
 mod tests {
@@ -178,6 +193,7 @@ mod tests {
         assert_eq!(time.elapsed, Duration::from_secs(0));
         assert_eq!(time.elapsed_time, 0.0);
         assert_eq!(time.fps, 0.0);
+        assert_eq!(time.frame_count, 0);
     }
 
     #[test]
@@ -193,6 +209,7 @@ mod tests {
         assert!(time.elapsed().as_secs_f32() > 0.0);
         assert!(time.elapsed_f32() > 0.0);
         assert!(time.last_time().is_some());
+        assert_eq!(time.frame_count(), 2);
     }
 
     #[test]
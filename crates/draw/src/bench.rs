@@ -0,0 +1,115 @@
+//! Entry points to measure the CPU cost of batching, they fill a `Draw2D` without
+//! rendering it so the GPU is not part of the measure. The gfx context must be
+//! initialized because the painter creates the pipelines on the first use.
+
+use crate::{Draw2D, Sprite};
+use corelib::gfx::Color;
+use corelib::math::{vec2, Vec2};
+use corelib::time::{Duration, Instant};
+
+/// Result of a batching benchmark
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct BenchResult {
+    /// Time spent filling the draw
+    pub elapsed: Duration,
+    pub elements: usize,
+    pub batches: usize,
+    pub vertices: usize,
+    pub indices: usize,
+}
+
+impl BenchResult {
+    /// Average time per element in microseconds
+    pub fn micros_per_element(&self) -> f64 {
+        if self.elements == 0 {
+            return 0.0;
+        }
+
+        self.elapsed.as_secs_f64() * 1_000_000.0 / self.elements as f64
+    }
+}
+
+/// Measures the time used by `cb` to fill the draw
+pub fn bench_draw<F>(draw: &mut Draw2D, cb: F) -> BenchResult
+where
+    F: FnOnce(&mut Draw2D),
+{
+    let (start_vertices, start_indices) = draw.buffers_len();
+    let start_stats = draw.stats();
+
+    let now = Instant::now();
+    cb(draw);
+    let elapsed = now.elapsed();
+
+    let (vertices, indices) = draw.buffers_len();
+    let stats = draw.stats();
+    BenchResult {
+        elapsed,
+        elements: stats.elements - start_stats.elements,
+        batches: stats.batches - start_stats.batches,
+        vertices: vertices - start_vertices,
+        indices: indices - start_indices,
+    }
+}
+
+/// Draws `count` images of the sprite spread over the draw size
+pub fn bench_sprites(draw: &mut Draw2D, sprite: &Sprite, count: usize) -> BenchResult {
+    bench_draw(draw, |draw| {
+        for i in 0..count {
+            let pos = grid_pos(draw.size(), i);
+            draw.image(sprite).translate(pos).color(grid_color(i));
+        }
+    })
+}
+
+/// Draws `count` short texts with the default font spread over the draw size
+pub fn bench_texts(draw: &mut Draw2D, count: usize) -> BenchResult {
+    bench_draw(draw, |draw| {
+        for i in 0..count {
+            let pos = grid_pos(draw.size(), i);
+            draw.text("Bench 1234")
+                .position(pos)
+                .color(grid_color(i))
+                .size(12.0);
+        }
+    })
+}
+
+/// Draws `count` shapes alternating rects, circles, triangles and lines
+pub fn bench_shapes(draw: &mut Draw2D, count: usize) -> BenchResult {
+    bench_draw(draw, |draw| {
+        for i in 0..count {
+            let pos = grid_pos(draw.size(), i);
+            let color = grid_color(i);
+            match i % 4 {
+                0 => {
+                    draw.rect(pos, vec2(16.0, 16.0)).color(color);
+                }
+                1 => {
+                    draw.circle(8.0).position(pos).color(color);
+                }
+                2 => {
+                    draw.triangle(pos, pos + vec2(16.0, 0.0), pos + vec2(8.0, 16.0))
+                        .color(color);
+                }
+                _ => {
+                    draw.line(pos, pos + vec2(16.0, 16.0)).color(color);
+                }
+            }
+        }
+    })
+}
+
+// positions are deterministic to compare the results between runs
+fn grid_pos(size: Vec2, i: usize) -> Vec2 {
+    const STEP: f32 = 20.0;
+    let cols = ((size.x / STEP) as usize).max(1);
+    let rows = ((size.y / STEP) as usize).max(1);
+    let cell = i % (cols * rows);
+    vec2((cell % cols) as f32, (cell / cols) as f32) * STEP
+}
+
+fn grid_color(i: usize) -> Color {
+    const COLORS: [Color; 4] = [Color::RED, Color::GREEN, Color::BLUE, Color::WHITE];
+    COLORS[i % COLORS.len()]
+}
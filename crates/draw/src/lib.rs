@@ -1,3 +1,5 @@
+#[cfg(feature = "bench")]
+pub mod bench;
 mod m2d;
 mod shapes;
 mod sprite;
@@ -9,6 +11,8 @@ pub use sprite::*;
 pub use text::*;
 
 use corelib::app::window_size;
+use corelib::gfx::{self, RenderTexture};
+use corelib::math::Mat3;
 
 // -- Draw API
 #[inline]
@@ -40,6 +44,18 @@ pub fn remove_pipeline_2d(id: &DrawPipelineId) -> Option<PipelineContext> {
     get_mut_2d_painter().remove_pipeline(id)
 }
 
+/// Shapes, images and text will share the same pipeline and atlas when enabled,
+/// interleaving them will not break the batch unless the image's texture changes
+#[inline]
+pub fn set_unified_batching_2d(enabled: bool) {
+    get_mut_2d_painter().set_unified_batching(enabled);
+}
+
+#[inline]
+pub fn is_unified_batching_2d() -> bool {
+    get_2d_painter().is_unified_batching()
+}
+
 #[inline]
 pub fn create_draw_2d() -> Draw2D {
     Draw2D::new(window_size())
@@ -50,6 +66,32 @@ pub(crate) fn clean_2d() {
     get_mut_2d_painter().clean();
 }
 
+// -- Stamping
+/// Draws the sprite into the render texture keeping what was already drawn on it,
+/// this allows to accumulate decals, paint or terrain damage without redrawing the history
+#[inline]
+pub fn blit_sprite_into(
+    target: &RenderTexture,
+    sprite: &Sprite,
+    transform: Mat3,
+) -> Result<(), String> {
+    blit_sprites_into(target, &[(sprite, transform)])
+}
+
+/// Same as `blit_sprite_into` but draws all the sprites using the same render pass
+pub fn blit_sprites_into(
+    target: &RenderTexture,
+    sprites: &[(&Sprite, Mat3)],
+) -> Result<(), String> {
+    let mut draw = Draw2D::new(target.size());
+    sprites.iter().for_each(|(sprite, transform)| {
+        draw.push_matrix(*transform);
+        draw.image(sprite);
+        draw.pop_matrix();
+    });
+    gfx::render_to_texture(target, &draw)
+}
+
 // -- text
 pub struct FontBuilder<'a> {
     source: &'a [u8],
@@ -1,3 +1,4 @@
+use corelib::input::{is_cursor_on_screen, mouse_position};
 use corelib::math::{vec2, vec3, vec4, Mat3, Mat4, Rect, Vec2};
 
 pub trait BaseCam2D {
@@ -17,6 +18,11 @@ pub trait BaseCam2D {
     fn is_rect_visible(&self, rect: Rect) -> bool {
         self.bounds().intersects(&rect)
     }
+
+    /// Cursor position in local coordinates, `None` if the cursor is outside the window
+    fn cursor_position(&self) -> Option<Vec2> {
+        is_cursor_on_screen().then(|| self.screen_to_local(mouse_position()))
+    }
 }
 
 #[derive(Default, Clone, Copy, PartialEq, Debug)]
@@ -258,6 +264,13 @@ impl Camera2D {
         BaseCam2D::screen_to_local(self, point)
     }
 
+    /// Cursor position in local coordinates, `None` if the cursor is outside the window
+    pub fn cursor_position(&self) -> Option<Vec2> {
+        debug_assert!(!self.dirty_projection);
+        debug_assert!(!self.dirty_transform);
+        BaseCam2D::cursor_position(self)
+    }
+
     fn calculate_projection(&mut self) {
         let (projection, ratio) = match self.mode {
             ScreenMode::Normal => calculate_ortho_projection(self.size, self.pixel_perfect),
@@ -3,15 +3,22 @@ use crate::m2d::images::Image2D;
 use crate::m2d::mat3_stack::Mat3Stack;
 use crate::m2d::painter::DrawPipelineId;
 use crate::m2d::shapes::{Line2D, Path2D, Rectangle2D, Triangle2D};
-use crate::m2d::text::Text2D;
+use crate::m2d::text::{RecordedText2D, Text2D};
+use crate::m2d::unified::{is_unified_compatible, to_unified_vertices};
+use crate::m2d::variant::{Mask2D, PipelineVariant2D};
 use crate::sprite::Sprite;
-use crate::text::get_mut_text_system;
-use crate::{BaseCam2D, Circle2D, Ellipse2D, Pattern2D, Polygon2D, Star2D};
+use crate::text::{get_mut_text_system, get_text_system};
+use crate::{
+    Arc2D, BaseCam2D, Capsule2D, Circle2D, Ellipse2D, Pattern2D, Pie2D, Polygon2D, Star2D,
+};
 use arrayvec::ArrayVec;
 use corelib::gfx::consts::MAX_BIND_GROUPS_PER_PIPELINE;
-use corelib::gfx::{self, AsRenderer, BindGroup, Color, RenderPipeline, RenderTexture, Renderer};
+use corelib::gfx::{
+    self, AsRenderer, BindGroup, Color, CompareMode, RenderPipeline, RenderTexture, Renderer,
+};
 use corelib::math::{vec2, vec3, vec4, Mat3, Mat4, Rect, Vec2};
 use smallvec::SmallVec;
+use std::cell::RefCell;
 use std::ops::{Deref, DerefMut, Range};
 
 // TODO Cached elements is a must
@@ -19,6 +26,10 @@ use std::ops::{Deref, DerefMut, Range};
 // This is used to avoid heap allocations when doing small number of drawcalls
 const STACK_ALLOCATED_QUADS: usize = 200;
 
+thread_local! {
+    static UNIFIED_VERTICES: RefCell<Vec<f32>> = const { RefCell::new(vec![]) };
+}
+
 #[derive(Clone)]
 pub struct PipelineContext {
     pub pipeline: RenderPipeline,
@@ -44,13 +55,50 @@ impl AsBindGroups for &[BindGroup] {
     }
 }
 
+// Commands stored by a recording Draw2D, they are processed when the draw is submitted
+#[derive(Clone)]
+enum DrawCommand {
+    Geometry {
+        pipeline: DrawPipelineId,
+        vertices: Vec<f32>,
+        indices: Vec<u32>,
+        transform: Mat3,
+        sprite: Option<Sprite>,
+        palette: Option<Sprite>,
+        matrix: Mat3,
+        alpha: f32,
+        depth: f32,
+        mask: Option<Mask2D>,
+        mask_layer: u32,
+    },
+    Text {
+        text: RecordedText2D,
+        matrix: Mat3,
+        alpha: f32,
+        depth: f32,
+        mask: Option<Mask2D>,
+        mask_layer: u32,
+    },
+}
+
+struct VertexInfo {
+    vertex_offset: usize,
+    x_pos: usize,
+    y_pos: usize,
+    alpha_pos: Option<usize>,
+    depth: bool,
+}
+
 struct BatchInfo {
     vbo_range: Range<u64>,
     ebo_range: Range<u64>,
     start_idx: usize,
     end_idx: usize,
+    depth_range: Option<Range<u64>>,
     pipeline: RenderPipeline,
     bind_groups: ArrayVec<BindGroup, MAX_BIND_GROUPS_PER_PIPELINE>,
+    // each `masked` call uses its own layer, the stencil is cleared when it changes
+    mask_layer: Option<u32>,
 }
 
 impl Clone for BatchInfo {
@@ -60,8 +108,10 @@ impl Clone for BatchInfo {
             ebo_range: self.ebo_range.clone(),
             start_idx: self.start_idx,
             end_idx: self.end_idx,
+            depth_range: self.depth_range.clone(),
             pipeline: self.pipeline.clone(),
             bind_groups: self.bind_groups.clone(),
+            mask_layer: self.mask_layer,
         }
     }
 }
@@ -76,6 +126,10 @@ impl BatchInfo {
             return false;
         }
 
+        if self.mask_layer != other.mask_layer {
+            return false;
+        }
+
         true
     }
 
@@ -89,6 +143,7 @@ where
     T: Element2D,
 {
     inner: Option<T>,
+    depth: Option<f32>,
     draw: &'a mut Draw2D,
 }
 
@@ -99,10 +154,18 @@ where
     pub fn new(draw: &'a mut Draw2D, inner: T) -> Self {
         Self {
             inner: Some(inner),
+            depth: None,
             draw,
         }
     }
 
+    /// Depth used by this element when the depth test is enabled, see `Draw2D::set_depth`.
+    /// Call it before the element's methods, e.g. `draw.rect(pos, size).depth(0.5).color(c)`
+    pub fn depth(&mut self, depth: f32) -> &mut Self {
+        self.depth = Some(depth);
+        self
+    }
+
     pub fn into_inner(mut self) -> T {
         self.inner.take().unwrap()
     }
@@ -114,7 +177,15 @@ where
 {
     fn drop(&mut self) {
         if let Some(inner) = self.inner.take() {
+            let last_depth = self
+                .depth
+                .map(|depth| std::mem::replace(&mut self.draw.depth, depth));
+
             self.draw.add_element(&inner);
+
+            if let Some(depth) = last_depth {
+                self.draw.depth = depth;
+            }
         }
     }
 }
@@ -158,15 +229,23 @@ pub struct Draw2D {
     clear_color: Option<Color>,
     alpha: f32,
 
+    depth_test: Option<CompareMode>,
+    depth: f32,
+    mask: Option<Mask2D>,
+    mask_layer: u32,
+
     matrix_stack: Mat3Stack,
 
     indices_offset: usize,
     batches: SmallVec<BatchInfo, STACK_ALLOCATED_QUADS>,
     vertices: SmallVec<f32, { STACK_ALLOCATED_QUADS * 12 }>,
     indices: SmallVec<u32, { STACK_ALLOCATED_QUADS * 6 }>,
+    depths: SmallVec<f32, { STACK_ALLOCATED_QUADS * 4 }>,
 
     pub(crate) last_text_bounds: Rect,
     stats: DrawStats,
+
+    recorded: Option<Vec<DrawCommand>>,
 }
 
 impl Draw2D {
@@ -182,6 +261,129 @@ impl Draw2D {
         }
     }
 
+    /// Creates a Draw2D that only records the draw commands without touching the painter,
+    /// it can be built on a worker thread and must be submitted on the main thread before render
+    pub fn new_recording(size: Vec2) -> Self {
+        Self {
+            recorded: Some(vec![]),
+            ..Self::new(size)
+        }
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.recorded.is_some()
+    }
+
+    /// Process the recorded commands, this must be called from the main thread
+    pub fn submit(&mut self) {
+        let Some(commands) = self.recorded.take() else {
+            return;
+        };
+
+        self.process_commands(commands);
+    }
+
+    /// Adds the commands of a recording Draw2D without consuming them, so the same
+    /// recording can be replayed each frame while nothing changes
+    pub fn replay(&mut self, recording: &Draw2D) {
+        let Some(commands) = &recording.recorded else {
+            return;
+        };
+
+        match &mut self.recorded {
+            Some(recorded) => recorded.extend(commands.iter().cloned()),
+            None => self.process_commands(commands.iter().cloned()),
+        }
+    }
+
+    fn process_commands<I>(&mut self, commands: I)
+    where
+        I: IntoIterator<Item = DrawCommand>,
+    {
+        let matrix_stack = self.matrix_stack.clone();
+        let current_alpha = self.alpha;
+        let current_depth = self.depth;
+        let current_mask = self.mask;
+
+        // the recorded layers go after the ones used by this draw
+        let base_layer = self.mask_layer;
+        let mut last_layer = base_layer;
+
+        commands.into_iter().for_each(|cmd| match cmd {
+            DrawCommand::Geometry {
+                pipeline,
+                mut vertices,
+                indices,
+                transform,
+                sprite,
+                palette,
+                matrix,
+                alpha,
+                depth,
+                mask,
+                mask_layer,
+            } => {
+                self.matrix_stack.set_matrix(matrix);
+                self.alpha = alpha;
+                self.depth = depth;
+                self.mask = mask;
+                self.mask_layer = base_layer + mask_layer;
+                last_layer = last_layer.max(self.mask_layer);
+                self.add_geometry(
+                    DrawingInfo {
+                        pipeline,
+                        vertices: &mut vertices,
+                        indices: &indices,
+                        transform,
+                        sprite: sprite.as_ref(),
+                    },
+                    palette.as_ref(),
+                );
+            }
+            DrawCommand::Text {
+                text,
+                matrix,
+                alpha,
+                depth,
+                mask,
+                mask_layer,
+            } => {
+                self.matrix_stack.set_matrix(matrix);
+                self.alpha = alpha;
+                self.depth = depth;
+                self.mask = mask;
+                self.mask_layer = base_layer + mask_layer;
+                last_layer = last_layer.max(self.mask_layer);
+                text.as_text_2d().process(self);
+            }
+        });
+
+        self.matrix_stack = matrix_stack;
+        self.alpha = current_alpha;
+        self.depth = current_depth;
+        self.mask = current_mask;
+        self.mask_layer = last_layer;
+        self.inverse_transform = None;
+    }
+
+    pub(crate) fn record_text(&mut self, text: RecordedText2D) {
+        let matrix = self.matrix();
+        let alpha = self.alpha;
+        let depth = self.depth;
+        let mask = self.mask;
+        let mask_layer = self.mask_layer;
+        if let Some(commands) = &mut self.recorded {
+            commands.push(DrawCommand::Text {
+                text,
+                matrix,
+                alpha,
+                depth,
+                mask,
+                mask_layer,
+            });
+        }
+    }
+
     pub fn clear(&mut self, color: Color) {
         self.clear_color = Some(color);
     }
@@ -198,6 +400,70 @@ impl Draw2D {
         self.alpha
     }
 
+    /// Enables the depth test for the built-in pipelines, the elements are sorted by
+    /// the GPU using their depth instead of the drawing order. Useful for big static scenes
+    /// with opaque elements, translucent pixels still write to the depth buffer.
+    /// Custom pipelines are drawn without depth test.
+    /// Render textures used as target must be created with depth.
+    pub fn set_depth_test(&mut self, compare: Option<CompareMode>) {
+        debug_assert!(
+            self.batches.is_empty(),
+            "The Draw2D depth test must be set before any drawing."
+        );
+        self.depth_test = compare;
+    }
+
+    pub fn depth_test(&self) -> Option<CompareMode> {
+        self.depth_test
+    }
+
+    /// Depth for the next elements, from 0.0 (near) to 1.0 (far)
+    pub fn set_depth(&mut self, depth: f32) {
+        self.depth = depth;
+    }
+
+    pub fn depth(&self) -> f32 {
+        self.depth
+    }
+
+    /// Stencil mask mode for the next elements using the built-in pipelines,
+    /// custom pipelines ignore it. The mask is cleared when `masked` starts a new one.
+    pub fn set_mask(&mut self, mask: Option<Mask2D>) {
+        self.mask = mask;
+    }
+
+    pub fn mask(&self) -> Option<Mask2D> {
+        self.mask
+    }
+
+    /// Draws the elements added by `content` only where the elements added by `mask` were drawn.
+    /// Useful for portals, lenses or minimaps. The mask elements are not visible.
+    /// Each call starts with an empty mask, so masks drawn before don't affect it.
+    /// Render textures used as target must be created with depth.
+    pub fn masked<M, C>(&mut self, mask: M, content: C)
+    where
+        M: FnOnce(&mut Draw2D),
+        C: FnOnce(&mut Draw2D),
+    {
+        let last_mask = self.mask;
+        self.mask_layer += 1;
+
+        self.mask = Some(Mask2D::Write);
+        mask(self);
+
+        self.mask = Some(Mask2D::Inside);
+        content(self);
+
+        self.mask = last_mask;
+    }
+
+    fn pipeline_variant(&self) -> PipelineVariant2D {
+        PipelineVariant2D {
+            depth: self.depth_test,
+            mask: self.mask,
+        }
+    }
+
     pub fn add_element<T>(&mut self, element: &T)
     where
         T: Element2D,
@@ -207,9 +473,58 @@ impl Draw2D {
     }
 
     pub fn add_to_batch<'a>(&'a mut self, info: DrawingInfo<'a>) {
-        let start_idx = self.indices.len();
-        let end_idx = start_idx + info.indices.len();
+        self.add_geometry(info, None);
+    }
+
+    /// Same as `add_to_batch` but binding the palette LUT in the bind group 2
+    pub(crate) fn add_paletted_to_batch<'a>(
+        &'a mut self,
+        info: DrawingInfo<'a>,
+        palette: &'a Sprite,
+    ) {
+        self.add_geometry(info, Some(palette));
+    }
+
+    fn add_geometry(&mut self, info: DrawingInfo, palette: Option<&Sprite>) {
+        if self.recorded.is_some() {
+            let matrix = self.matrix();
+            let alpha = self.alpha;
+            let depth = self.depth;
+            let mask = self.mask;
+            let mask_layer = self.mask_layer;
+            if let Some(commands) = &mut self.recorded {
+                commands.push(DrawCommand::Geometry {
+                    pipeline: info.pipeline,
+                    vertices: info.vertices.to_vec(),
+                    indices: info.indices.to_vec(),
+                    transform: info.transform,
+                    sprite: info.sprite.cloned(),
+                    palette: palette.cloned(),
+                    matrix,
+                    alpha,
+                    depth,
+                    mask,
+                    mask_layer,
+                });
+            }
+            return;
+        }
+
+        let unified =
+            get_2d_painter().is_unified_batching() && is_unified_compatible(info.pipeline);
+        if unified {
+            self.add_to_unified_batch(info);
+            return;
+        }
+
         let mut painter = get_mut_2d_painter();
+        let variant = self.pipeline_variant();
+        let variant_ctx = if variant.is_default() {
+            None
+        } else {
+            painter.variant_pipeline(&info.pipeline, variant)
+        };
+        let depth = variant_ctx.is_some() && variant.depth.is_some();
         let PipelineContext {
             pipeline,
             mut groups,
@@ -217,12 +532,15 @@ impl Draw2D {
             x_pos,
             y_pos,
             alpha_pos,
-        } = painter
-            .pipelines
-            .get(&info.pipeline)
-            .ok_or_else(|| format!("Missing pipeline '{:?}'", info.pipeline))
-            .unwrap()
-            .clone();
+        } = match variant_ctx {
+            Some(ctx) => ctx,
+            None => painter
+                .pipelines
+                .get(&info.pipeline)
+                .ok_or_else(|| format!("Missing pipeline '{:?}'", info.pipeline))
+                .unwrap()
+                .clone(),
+        };
 
         // assign in spot 1 the texture/sampler binding group
         if let Some(sp) = info.sprite {
@@ -234,20 +552,145 @@ impl Draw2D {
             }
         }
 
+        if let Some(lut) = palette {
+            groups.push(painter.cached_bind_group_for(&pipeline, lut));
+        }
+
         if matches!(info.pipeline, DrawPipelineId::Text) {
             groups.push(get_mut_text_system().bind_group(&pipeline).clone());
         }
 
+        drop(painter);
+
+        self.push_to_batch(
+            pipeline,
+            groups,
+            VertexInfo {
+                vertex_offset,
+                x_pos,
+                y_pos,
+                alpha_pos,
+                depth,
+            },
+            info.vertices,
+            info.indices,
+            info.transform,
+        );
+    }
+
+    fn add_to_unified_batch(&mut self, info: DrawingInfo) {
+        UNIFIED_VERTICES.with_borrow_mut(|vertices| {
+            vertices.clear();
+
+            let white_pixel = get_text_system().white_pixel_uvs();
+            to_unified_vertices(info.pipeline, info.vertices, white_pixel, vertices);
+
+            let mut painter = get_mut_2d_painter();
+            let variant = self.pipeline_variant();
+            let variant_ctx = if variant.is_default() {
+                None
+            } else {
+                painter.variant_pipeline(&DrawPipelineId::Unified, variant)
+            };
+            let depth = variant_ctx.is_some() && variant.depth.is_some();
+            let PipelineContext {
+                pipeline,
+                mut groups,
+                vertex_offset,
+                x_pos,
+                y_pos,
+                alpha_pos,
+            } = match variant_ctx {
+                Some(ctx) => ctx,
+                None => painter
+                    .pipelines
+                    .get(&DrawPipelineId::Unified)
+                    .ok_or_else(|| "Missing unified pipeline".to_string())
+                    .unwrap()
+                    .clone(),
+            };
+
+            let dummy = painter.dummy_sprite_bg.clone().unwrap();
+
+            // shapes and text do not care about the sprite binding group,
+            // so they can keep the one used by the current batch
+            let last_sprite_bg = self
+                .batches
+                .last_mut()
+                .filter(|b| b.pipeline == pipeline)
+                .and_then(|b| b.bind_groups.get_mut(1));
+
+            let sprite_bg = match (info.sprite, last_sprite_bg) {
+                (Some(sp), Some(last)) => {
+                    let bind_group = painter.cached_bind_group_for(&pipeline, sp);
+
+                    // the current batch does not use any sprite yet, so we can take it
+                    if *last == dummy {
+                        *last = bind_group.clone();
+                    }
+
+                    bind_group
+                }
+                (Some(sp), None) => painter.cached_bind_group_for(&pipeline, sp),
+                (None, Some(last)) => last.clone(),
+                (None, None) => dummy,
+            };
+
+            groups.push(sprite_bg);
+            groups.push(get_mut_text_system().unified_bind_group(&pipeline).clone());
+
+            drop(painter);
+
+            self.push_to_batch(
+                pipeline,
+                groups,
+                VertexInfo {
+                    vertex_offset,
+                    x_pos,
+                    y_pos,
+                    alpha_pos,
+                    depth,
+                },
+                vertices,
+                info.indices,
+                info.transform,
+            );
+        });
+    }
+
+    fn push_to_batch(
+        &mut self,
+        pipeline: RenderPipeline,
+        groups: ArrayVec<BindGroup, MAX_BIND_GROUPS_PER_PIPELINE>,
+        vertex_info: VertexInfo,
+        vertices: &mut [f32],
+        indices: &[u32],
+        transform: Mat3,
+    ) {
+        let VertexInfo {
+            vertex_offset,
+            x_pos,
+            y_pos,
+            alpha_pos,
+            depth,
+        } = vertex_info;
+
+        let start_idx = self.indices.len();
+        let end_idx = start_idx + indices.len();
+
         let vbo_start = self.vertices.len() as u64 * 4;
         let ebo_start = self.indices.len() as u64 * 4;
+        let depth_start = self.depths.len() as u64 * 4;
 
         let batch = BatchInfo {
             vbo_range: vbo_start..vbo_start,
             ebo_range: ebo_start..ebo_start,
             start_idx,
             end_idx,
+            depth_range: depth.then_some(depth_start..depth_start),
             pipeline,
             bind_groups: groups,
+            mask_layer: self.mask.map(|_| self.mask_layer),
         };
 
         let new_batch = match self.batches.last() {
@@ -264,45 +707,47 @@ impl Draw2D {
         let current = self.batches.last_mut().unwrap();
         current.end_idx = end_idx;
 
-        let vbo_count = info.vertices.len() as u64 * 4; // f32=4bytes
-        let ebo_count = info.indices.len() as u64 * 4; // u32=4bytes
+        let vbo_count = vertices.len() as u64 * 4; // f32=4bytes
+        let ebo_count = indices.len() as u64 * 4; // u32=4bytes
         current.vbo_range.end += vbo_count;
         current.ebo_range.end += ebo_count;
 
+        // one depth value per vertex in its own buffer
+        if let Some(depth_range) = &mut current.depth_range {
+            let count = vertices.len() / vertex_offset;
+            depth_range.end += count as u64 * 4;
+            self.depths.resize(self.depths.len() + count, self.depth);
+        }
+
         // the indices must use an offset
-        self.indices.extend(
-            info.indices
-                .iter()
-                .map(|idx| idx + self.indices_offset as u32),
-        );
+        self.indices
+            .extend(indices.iter().map(|idx| idx + self.indices_offset as u32));
 
-        self.indices_offset += info.vertices.len() / vertex_offset;
+        self.indices_offset += vertices.len() / vertex_offset;
 
-        let matrix = self.matrix() * info.transform;
-        info.vertices
-            .chunks_exact_mut(vertex_offset)
-            .for_each(|chunk| {
-                debug_assert!(chunk.len() >= 2);
+        let matrix = self.matrix() * transform;
+        vertices.chunks_exact_mut(vertex_offset).for_each(|chunk| {
+            debug_assert!(chunk.len() >= 2);
 
-                let x = chunk[x_pos];
-                let y = chunk[y_pos];
+            let x = chunk[x_pos];
+            let y = chunk[y_pos];
 
-                let prev_xyz = if self.round_pixels {
-                    vec3(x, y, 1.0).round()
-                } else {
-                    vec3(x, y, 1.0)
-                };
+            let prev_xyz = if self.round_pixels {
+                vec3(x, y, 1.0).round()
+            } else {
+                vec3(x, y, 1.0)
+            };
 
-                let xyz = matrix * prev_xyz;
-                chunk[x_pos] = xyz.x;
-                chunk[y_pos] = xyz.y;
+            let xyz = matrix * prev_xyz;
+            chunk[x_pos] = xyz.x;
+            chunk[y_pos] = xyz.y;
 
-                if let Some(a_pos) = alpha_pos {
-                    let alpha = chunk[a_pos] * self.alpha;
-                    chunk[a_pos] = alpha;
-                }
-            });
-        self.vertices.extend_from_slice(info.vertices);
+            if let Some(a_pos) = alpha_pos {
+                let alpha = chunk[a_pos] * self.alpha;
+                chunk[a_pos] = alpha;
+            }
+        });
+        self.vertices.extend_from_slice(vertices);
     }
 
     pub fn last_text_bounds(&self) -> Rect {
@@ -444,6 +889,18 @@ impl Draw2D {
         Drawing::new(self, Circle2D::new(radius))
     }
 
+    pub fn arc(&mut self, radius: f32, start_angle: f32, end_angle: f32) -> Drawing<'_, Arc2D> {
+        Drawing::new(self, Arc2D::new(radius, start_angle, end_angle))
+    }
+
+    pub fn pie(&mut self, radius: f32, start_angle: f32, end_angle: f32) -> Drawing<'_, Pie2D> {
+        Drawing::new(self, Pie2D::new(radius, start_angle, end_angle))
+    }
+
+    pub fn capsule(&mut self, p1: Vec2, p2: Vec2, radius: f32) -> Drawing<'_, Capsule2D> {
+        Drawing::new(self, Capsule2D::new(p1, p2, radius))
+    }
+
     pub fn ellipse(&mut self, pos: Vec2, size: Vec2) -> Drawing<'_, Ellipse2D> {
         Drawing::new(self, Ellipse2D::new(pos, size))
     }
@@ -476,6 +933,12 @@ impl Draw2D {
         Drawing::new(self, Text2D::new(text))
     }
 
+    /// Length of the vertex and index buffers
+    #[cfg(feature = "bench")]
+    pub(crate) fn buffers_len(&self) -> (usize, usize) {
+        (self.vertices.len(), self.indices.len())
+    }
+
     pub fn stats(&self) -> DrawStats {
         self.stats
     }
@@ -502,11 +965,16 @@ pub trait Element2D {
 
 impl AsRenderer for Draw2D {
     fn render(&self, target: Option<&RenderTexture>) -> Result<(), String> {
+        if self.is_recording() {
+            return Err("A recording Draw2D must be submitted before rendering it".to_string());
+        }
+
         let painter = get_2d_painter();
 
         let ubo_transform = &painter.ubo;
         let vbo = &painter.vbo;
         let ebo = &painter.ebo;
+        let depth_vbo = &painter.depth_vbo;
 
         // TODO check dirty transform flag to avoid update all the time this
         gfx::write_buffer(ubo_transform)
@@ -524,7 +992,16 @@ impl AsRenderer for Draw2D {
             .build()
             .unwrap();
 
+        if !self.depths.is_empty() {
+            gfx::write_buffer(depth_vbo)
+                .with_data(&self.depths)
+                .build()
+                .unwrap();
+        }
+
         let mut cleared = false;
+        let mut depth_cleared = false;
+        let mut stencil_layer = None;
         let mut renderer = Renderer::new();
 
         if self.batches.is_empty() {
@@ -549,7 +1026,27 @@ impl AsRenderer for Draw2D {
                 b.bind_groups.iter().collect();
 
             pass.pipeline(&b.pipeline)
-                .buffers_with_offset(&[(vbo, b.vbo_range.clone()), (ebo, b.ebo_range.clone())])
+                .buffers_with_offset(&[(vbo, b.vbo_range.clone())]);
+
+            // the depth buffer must be bound after the vertices to use the slot 1
+            if let Some(depth_range) = &b.depth_range {
+                pass.buffers_with_offset(&[(depth_vbo, depth_range.clone())]);
+
+                // the depth attachment only exists on passes using a depth pipeline
+                if !depth_cleared {
+                    pass.clear_depth(1.0);
+                    depth_cleared = true;
+                }
+            }
+
+            // the stencil attachment only exists on passes using a stencil pipeline,
+            // it's cleared again when a new mask starts
+            if b.pipeline.stencil_reference().is_some() && stencil_layer != Some(b.mask_layer) {
+                pass.clear_stencil(0);
+                stencil_layer = Some(b.mask_layer);
+            }
+
+            pass.buffers_with_offset(&[(ebo, b.ebo_range.clone())])
                 .bindings(&binds);
 
             let count = b.count() as u32;
@@ -559,3 +1056,150 @@ impl AsRenderer for Draw2D {
         self.flush(&renderer, target)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recording_stores_commands() {
+        let mut draw = Draw2D::new_recording(vec2(800.0, 600.0));
+        assert!(draw.is_recording());
+
+        draw.push_matrix(Mat3::from_translation(vec2(10.0, 20.0)));
+        draw.set_alpha(0.5);
+
+        #[rustfmt::skip]
+        let mut vertices = [
+            0.0, 0.0, 1.0, 1.0, 1.0, 1.0,
+            1.0, 0.0, 1.0, 1.0, 1.0, 1.0,
+            0.0, 1.0, 1.0, 1.0, 1.0, 1.0,
+        ];
+
+        draw.add_to_batch(DrawingInfo {
+            pipeline: DrawPipelineId::Shapes,
+            vertices: &mut vertices,
+            indices: &[0, 1, 2],
+            transform: Mat3::IDENTITY,
+            sprite: None,
+        });
+
+        assert!(draw.batches.is_empty());
+        assert!(draw.vertices.is_empty());
+
+        let commands = draw.recorded.as_ref().unwrap();
+        assert_eq!(commands.len(), 1);
+        match &commands[0] {
+            DrawCommand::Geometry { matrix, alpha, .. } => {
+                assert_eq!(*matrix, Mat3::from_translation(vec2(10.0, 20.0)));
+                assert_eq!(*alpha, 0.5);
+            }
+            _ => panic!("Expected a geometry command"),
+        }
+    }
+
+    #[test]
+    fn test_element_depth() {
+        let mut draw = Draw2D::new_recording(vec2(800.0, 600.0));
+        draw.set_depth(0.3);
+        draw.rect(Vec2::ZERO, vec2(10.0, 10.0)).depth(0.7);
+        assert_eq!(draw.depth(), 0.3);
+
+        let commands = draw.recorded.as_ref().unwrap();
+        match &commands[0] {
+            DrawCommand::Geometry { depth, .. } => assert_eq!(*depth, 0.7),
+            _ => panic!("Expected a geometry command"),
+        }
+    }
+
+    #[test]
+    fn test_masked() {
+        let mut draw = Draw2D::new_recording(vec2(800.0, 600.0));
+        draw.masked(
+            |d| {
+                d.circle(10.0);
+            },
+            |d| {
+                d.rect(Vec2::ZERO, vec2(10.0, 10.0));
+            },
+        );
+        assert_eq!(draw.mask(), None);
+
+        let masks = draw
+            .recorded
+            .as_ref()
+            .unwrap()
+            .iter()
+            .map(|cmd| match cmd {
+                DrawCommand::Geometry { mask, .. } => *mask,
+                _ => panic!("Expected a geometry command"),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(masks, [Some(Mask2D::Write), Some(Mask2D::Inside)]);
+    }
+
+    #[test]
+    fn test_masked_twice_uses_new_layers() {
+        let mut draw = Draw2D::new_recording(vec2(800.0, 600.0));
+        for _ in 0..2 {
+            draw.masked(
+                |d| {
+                    d.circle(10.0);
+                },
+                |d| {
+                    d.rect(Vec2::ZERO, vec2(10.0, 10.0));
+                },
+            );
+        }
+
+        let masks = draw
+            .recorded
+            .as_ref()
+            .unwrap()
+            .iter()
+            .map(|cmd| match cmd {
+                DrawCommand::Geometry {
+                    mask, mask_layer, ..
+                } => (*mask, *mask_layer),
+                _ => panic!("Expected a geometry command"),
+            })
+            .collect::<Vec<_>>();
+
+        // the second mask doesn't share the stencil of the first one
+        assert_eq!(
+            masks,
+            [
+                (Some(Mask2D::Write), 1),
+                (Some(Mask2D::Inside), 1),
+                (Some(Mask2D::Write), 2),
+                (Some(Mask2D::Inside), 2),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_recording_on_worker_thread() {
+        let draw = std::thread::spawn(|| {
+            let mut draw = Draw2D::new_recording(vec2(800.0, 600.0));
+            draw.clear(Color::RED);
+            draw
+        })
+        .join()
+        .unwrap();
+
+        assert!(draw.is_recording());
+    }
+
+    #[test]
+    fn test_replay_into_recording() {
+        let mut cache = Draw2D::new_recording(vec2(800.0, 600.0));
+        cache.rect(Vec2::ZERO, vec2(10.0, 10.0));
+
+        let mut draw = Draw2D::new_recording(vec2(800.0, 600.0));
+        draw.replay(&cache);
+        draw.replay(&cache);
+
+        assert_eq!(cache.recorded.as_ref().unwrap().len(), 1);
+        assert_eq!(draw.recorded.as_ref().unwrap().len(), 2);
+    }
+}
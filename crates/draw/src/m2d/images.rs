@@ -1,3 +1,4 @@
+use crate::m2d::variant::{variant_shader, with_variant_2d, PipelineVariant2D};
 use crate::{
     AsBindGroups, Draw2D, DrawPipelineId, DrawingInfo, Element2D, PipelineContext, Sprite,
     Transform2D,
@@ -46,7 +47,7 @@ var t_texture: texture_2d<f32>;
 var s_texture: sampler;
 
 // srg to linear
-{{SRGB_TO_LINEAR}}
+#include "srgb"
 
 @fragment
 fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
@@ -55,12 +56,37 @@ fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
 }
 "#;
 
+// language=wgsl
+const PALETTE_FRAG: &str = r#"
+@group(2) @binding(0)
+var t_palette: texture_2d<f32>;
+
+// srg to linear
+#include "srgb"
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    let in_color = srgb_to_linear(in.color);
+    let index_color = textureSample(t_texture, s_texture, in.uvs);
+
+    // the red channel stores the index of the color in the palette
+    let size = textureDimensions(t_palette);
+    let index = min(u32(index_color.r * 255.0 + 0.5), size.x - 1u);
+    let color = textureLoad(t_palette, vec2<u32>(index, 0u), 0);
+    return vec4<f32>(color.rgb, color.a * index_color.a) * in_color;
+}
+"#;
+
 pub fn create_images_2d_pipeline_ctx(ubo_transform: &Buffer) -> Result<PipelineContext, String> {
-    let shader = SHADER.replace(
-        "{{SRGB_TO_LINEAR}}",
-        include_str!("../resources/to_linear.wgsl"),
-    );
-    let pip = gfx::create_render_pipeline(&shader)
+    images_2d_pipeline_ctx(ubo_transform, Default::default())
+}
+
+pub(crate) fn images_2d_pipeline_ctx(
+    ubo_transform: &Buffer,
+    variant: PipelineVariant2D,
+) -> Result<PipelineContext, String> {
+    let shader = variant_shader(SHADER, variant);
+    let builder = gfx::create_render_pipeline(&shader)
         .with_label("Draw2D images default pipeline")
         .with_vertex_layout(
             VertexLayout::new()
@@ -76,9 +102,62 @@ pub fn create_images_2d_pipeline_ctx(ubo_transform: &Buffer) -> Result<PipelineC
                 .with_entry(BindingType::texture(0).with_fragment_visibility(true))
                 .with_entry(BindingType::sampler(1).with_fragment_visibility(true)),
         )
-        .with_blend_mode(BlendMode::NORMAL)
+        .with_blend_mode(BlendMode::NORMAL);
+
+    let pip = with_variant_2d(builder, variant).build()?;
+
+    let bind_group = gfx::create_bind_group()
+        .with_layout(pip.bind_group_layout_ref(0)?)
+        .with_uniform(0, ubo_transform)
         .build()?;
 
+    Ok(PipelineContext {
+        pipeline: pip,
+        groups: (&[bind_group]).to_bind_groups(),
+        vertex_offset: 8,
+        x_pos: 0,
+        y_pos: 1,
+        alpha_pos: Some(7),
+    })
+}
+
+/// Pipeline used by the images drawn with a palette, the sprite stores the indices
+/// in the red channel and the palette is a LUT texture with one color per pixel
+pub fn create_palette_2d_pipeline_ctx(ubo_transform: &Buffer) -> Result<PipelineContext, String> {
+    palette_2d_pipeline_ctx(ubo_transform, Default::default())
+}
+
+pub(crate) fn palette_2d_pipeline_ctx(
+    ubo_transform: &Buffer,
+    variant: PipelineVariant2D,
+) -> Result<PipelineContext, String> {
+    let vert = SHADER.split("// srg to linear").next().unwrap_or_default();
+    let shader = variant_shader(&format!("{vert}{PALETTE_FRAG}"), variant);
+    let builder = gfx::create_render_pipeline(&shader)
+        .with_label("Draw2D images palette pipeline")
+        .with_vertex_layout(
+            VertexLayout::new()
+                .with_attr(0, VertexFormat::Float32x2)
+                .with_attr(1, VertexFormat::Float32x2)
+                .with_attr(2, VertexFormat::Float32x4),
+        )
+        .with_bind_group_layout(
+            BindGroupLayout::new().with_entry(BindingType::uniform(0).with_vertex_visibility(true)),
+        )
+        .with_bind_group_layout(
+            BindGroupLayout::new()
+                .with_entry(BindingType::texture(0).with_fragment_visibility(true))
+                .with_entry(BindingType::sampler(1).with_fragment_visibility(true)),
+        )
+        .with_bind_group_layout(
+            BindGroupLayout::new()
+                .with_entry(BindingType::texture(0).with_fragment_visibility(true))
+                .with_entry(BindingType::sampler(1).with_fragment_visibility(true)),
+        )
+        .with_blend_mode(BlendMode::NORMAL);
+
+    let pip = with_variant_2d(builder, variant).build()?;
+
     let bind_group = gfx::create_bind_group()
         .with_layout(pip.bind_group_layout_ref(0)?)
         .with_uniform(0, ubo_transform)
@@ -102,6 +181,7 @@ pub struct Image2D {
     alpha: f32,
     size: Option<Vec2>,
     crop: Option<Rect>,
+    palette: Option<Sprite>,
 
     #[pipeline_id]
     pip: DrawPipelineId,
@@ -119,6 +199,7 @@ impl Image2D {
             alpha: 1.0,
             crop: None,
             size: None,
+            palette: None,
             pip: DrawPipelineId::Images,
             transform: None,
         }
@@ -148,6 +229,15 @@ impl Image2D {
         self.crop = Some(Rect::new(origin, size));
         self.size(size)
     }
+
+    /// Colors the image using the LUT given, the red channel of the image is used as index
+    pub fn palette(&mut self, lut: &Sprite) -> &mut Self {
+        self.palette = Some(lut.clone());
+        if self.pip == DrawPipelineId::Images {
+            self.pip = DrawPipelineId::Palette;
+        }
+        self
+    }
 }
 
 impl Element2D for Image2D {
@@ -190,12 +280,17 @@ impl Element2D for Image2D {
             .transform
             .map_or(Mat3::IDENTITY, |mut t| t.set_size(size).updated_mat3());
 
-        draw.add_to_batch(DrawingInfo {
+        let info = DrawingInfo {
             pipeline: self.pip,
             vertices: &mut vertices,
             indices: &indices,
             transform: matrix,
             sprite: Some(&self.sprite),
-        })
+        };
+
+        match &self.palette {
+            Some(lut) => draw.add_paletted_to_batch(info, lut),
+            None => draw.add_to_batch(info),
+        }
     }
 }
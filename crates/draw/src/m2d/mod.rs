@@ -3,15 +3,25 @@ mod draw_2d;
 mod images;
 mod mat3_stack;
 mod painter;
+mod parallax;
 pub mod pattern;
 mod shapes;
+mod sprite_renderer;
 mod text;
+mod unified;
+mod variant;
+mod visibility_lod;
 
 pub use camera::*;
 pub use draw_2d::*;
 pub use images::*;
 pub use mat3_stack::*;
 pub use painter::*;
+pub use parallax::*;
 pub use pattern::*;
 pub use shapes::*;
+pub use sprite_renderer::*;
 pub use text::*;
+pub use unified::*;
+pub use variant::*;
+pub use visibility_lod::*;
@@ -1,14 +1,21 @@
+use super::images::{images_2d_pipeline_ctx, palette_2d_pipeline_ctx};
+use super::pattern::pattern_2d_pipeline_ctx;
+use super::shapes::shapes_2d_pipeline_ctx;
+use super::text::text_2d_pipeline_ctx;
+use super::unified::unified_2d_pipeline_ctx;
+use super::variant::PipelineVariant2D;
 use super::{create_shapes_2d_pipeline_ctx, PipelineContext};
 use crate::sprite::SpriteId;
 use crate::{
-    clean_2d, create_images_2d_pipeline_ctx, create_pattern_2d_pipeline_ctx,
-    create_text_2d_pipeline_ctx, Sprite,
+    clean_2d, create_images_2d_pipeline_ctx, create_palette_2d_pipeline_ctx,
+    create_pattern_2d_pipeline_ctx, create_text_2d_pipeline_ctx, create_unified_2d_pipeline_ctx,
+    Sprite,
 };
 use atomic_refcell::{AtomicRef, AtomicRefCell, AtomicRefMut};
 use corelib::gfx::{self, BindGroup, Buffer, RenderPipeline};
 use corelib::math::Mat4;
 use once_cell::sync::Lazy;
-use rustc_hash::FxHashMap;
+use rustc_hash::{FxHashMap, FxHashSet};
 use utils::drop_signal::DropSignal;
 
 pub(crate) static PAINTER_2D: Lazy<AtomicRefCell<Painter2D>> = Lazy::new(|| {
@@ -34,6 +41,8 @@ pub enum DrawPipelineId {
     Images,
     Text,
     Pattern,
+    Palette,
+    Unified,
     Custom(u64),
 }
 
@@ -50,13 +59,18 @@ impl CachedBindGroup {
 
 pub(crate) struct Painter2D {
     pub pipelines: FxHashMap<DrawPipelineId, PipelineContext>,
+    variants: FxHashMap<(DrawPipelineId, PipelineVariant2D), PipelineContext>,
+    // built-in pipelines replaced by the user, they don't have variants
+    replaced: FxHashSet<DrawPipelineId>,
     pip_ctx_id: u64,
 
     pub ubo: Buffer,
     pub vbo: Buffer,
     pub ebo: Buffer,
+    pub depth_vbo: Buffer,
     pub dummy_sprite_bg: Option<BindGroup>,
     sprites_cache: FxHashMap<SpriteId, CachedBindGroup>,
+    unified_batching: bool,
 }
 
 impl Default for Painter2D {
@@ -79,14 +93,24 @@ impl Default for Painter2D {
             .build()
             .unwrap();
 
+        let depth_vbo = gfx::create_vertex_buffer(&[] as &[f32])
+            .with_label("Painter2D Depth VBO")
+            .with_write_flag(true)
+            .build()
+            .unwrap();
+
         let mut painter = Self {
             pipelines: Default::default(),
+            variants: Default::default(),
+            replaced: Default::default(),
             pip_ctx_id: 0,
             ubo,
             vbo,
             ebo,
+            depth_vbo,
             dummy_sprite_bg: None,
             sprites_cache: Default::default(),
+            unified_batching: false,
         };
 
         painter.set_pipeline(
@@ -109,6 +133,16 @@ impl Default for Painter2D {
             create_pattern_2d_pipeline_ctx(&painter.ubo).unwrap(),
         );
 
+        painter.set_pipeline(
+            &DrawPipelineId::Palette,
+            create_palette_2d_pipeline_ctx(&painter.ubo).unwrap(),
+        );
+
+        painter.set_pipeline(
+            &DrawPipelineId::Unified,
+            create_unified_2d_pipeline_ctx(&painter.ubo).unwrap(),
+        );
+
         // we need this to create custom shaders
         let dummy_sprite_bg = {
             let layout = painter
@@ -131,6 +165,7 @@ impl Default for Painter2D {
         };
 
         painter.dummy_sprite_bg = Some(dummy_sprite_bg);
+        painter.replaced.clear();
 
         painter
     }
@@ -159,13 +194,56 @@ impl Painter2D {
         id: &DrawPipelineId,
         pip: PipelineContext,
     ) -> Option<PipelineContext> {
+        self.replaced.insert(*id);
+        self.variants.retain(|(pip_id, _), _| pip_id != id);
         self.pipelines.insert(*id, pip)
     }
 
     pub fn remove_pipeline(&mut self, id: &DrawPipelineId) -> Option<PipelineContext> {
+        self.replaced.insert(*id);
+        self.variants.retain(|(pip_id, _), _| pip_id != id);
         self.pipelines.remove(id)
     }
 
+    /// Returns a variant (depth test, stencil mask) of a built-in pipeline, it's created
+    /// the first time. Custom and replaced pipelines don't have variants
+    pub(crate) fn variant_pipeline(
+        &mut self,
+        id: &DrawPipelineId,
+        variant: PipelineVariant2D,
+    ) -> Option<PipelineContext> {
+        if self.replaced.contains(id) {
+            return None;
+        }
+
+        if let Some(ctx) = self.variants.get(&(*id, variant)) {
+            return Some(ctx.clone());
+        }
+
+        let ctx = match id {
+            DrawPipelineId::Shapes => shapes_2d_pipeline_ctx(&self.ubo, variant),
+            DrawPipelineId::Images => images_2d_pipeline_ctx(&self.ubo, variant),
+            DrawPipelineId::Text => text_2d_pipeline_ctx(&self.ubo, variant),
+            DrawPipelineId::Pattern => pattern_2d_pipeline_ctx(&self.ubo, variant),
+            DrawPipelineId::Palette => palette_2d_pipeline_ctx(&self.ubo, variant),
+            DrawPipelineId::Unified => unified_2d_pipeline_ctx(&self.ubo, variant),
+            DrawPipelineId::Custom(_) => return None,
+        }
+        .map_err(|e| log::error!("Cannot create the pipeline variant for '{id:?}': {e}"))
+        .ok()?;
+
+        self.variants.insert((*id, variant), ctx.clone());
+        Some(ctx)
+    }
+
+    pub fn set_unified_batching(&mut self, enabled: bool) {
+        self.unified_batching = enabled;
+    }
+
+    pub fn is_unified_batching(&self) -> bool {
+        self.unified_batching
+    }
+
     pub fn cached_bind_group_for(&mut self, pip: &RenderPipeline, sprite: &Sprite) -> BindGroup {
         self.sprites_cache
             .entry(sprite.id())
@@ -0,0 +1,174 @@
+use crate::{BaseCam2D, Draw2D, Sprite};
+use corelib::gfx::Color;
+use corelib::math::{Rect, Vec2};
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum ParallaxRepeat {
+    #[default]
+    None,
+    X,
+    Y,
+    Both,
+}
+
+impl ParallaxRepeat {
+    fn axes(&self) -> (bool, bool) {
+        match self {
+            ParallaxRepeat::None => (false, false),
+            ParallaxRepeat::X => (true, false),
+            ParallaxRepeat::Y => (false, true),
+            ParallaxRepeat::Both => (true, true),
+        }
+    }
+}
+
+/// Background layer that moves relative to the camera, a factor of 1.0 moves
+/// with the world and 0.0 keeps the layer fixed on the screen
+#[derive(Clone)]
+pub struct ParallaxLayer {
+    pub sprite: Sprite,
+    pub position: Vec2,
+    pub factor: Vec2,
+    /// Auto-scroll speed in pixels per second
+    pub scroll_speed: Vec2,
+    pub repeat: ParallaxRepeat,
+    pub color: Color,
+    scroll: Vec2,
+}
+
+impl ParallaxLayer {
+    pub fn new(sprite: &Sprite) -> Self {
+        Self {
+            sprite: sprite.clone(),
+            position: Vec2::ZERO,
+            factor: Vec2::ONE,
+            scroll_speed: Vec2::ZERO,
+            repeat: ParallaxRepeat::X,
+            color: Color::WHITE,
+            scroll: Vec2::ZERO,
+        }
+    }
+
+    pub fn with_position(mut self, pos: Vec2) -> Self {
+        self.position = pos;
+        self
+    }
+
+    pub fn with_factor(mut self, factor: Vec2) -> Self {
+        self.factor = factor;
+        self
+    }
+
+    pub fn with_scroll_speed(mut self, speed: Vec2) -> Self {
+        self.scroll_speed = speed;
+        self
+    }
+
+    pub fn with_repeat(mut self, repeat: ParallaxRepeat) -> Self {
+        self.repeat = repeat;
+        self
+    }
+
+    pub fn with_color(mut self, color: Color) -> Self {
+        self.color = color;
+        self
+    }
+
+    /// Advances the auto-scroll
+    pub fn update(&mut self, delta: f32) {
+        self.scroll += self.scroll_speed * delta;
+
+        // keep the value small on the repeated axes to avoid precision issues
+        let size = self.sprite.size();
+        let (rx, ry) = self.repeat.axes();
+        if rx && size.x > 0.0 {
+            self.scroll.x %= size.x;
+        }
+        if ry && size.y > 0.0 {
+            self.scroll.y %= size.y;
+        }
+    }
+
+    /// World position of the layer for the camera position given
+    pub fn origin(&self, cam_pos: Vec2) -> Vec2 {
+        self.position + cam_pos * (1.0 - self.factor) + self.scroll
+    }
+}
+
+// Returns the position, size and image offset of the pattern used to draw the layer
+fn layer_area(
+    origin: Vec2,
+    sprite_size: Vec2,
+    repeat: ParallaxRepeat,
+    bounds: Rect,
+) -> (Vec2, Vec2, Vec2) {
+    let (rx, ry) = repeat.axes();
+    let axis = |repeat: bool, origin: f32, size: f32, min: f32, len: f32| {
+        if repeat {
+            (min, len, min - origin)
+        } else {
+            (origin, size, 0.0)
+        }
+    };
+
+    let (x, w, ox) = axis(rx, origin.x, sprite_size.x, bounds.x(), bounds.width());
+    let (y, h, oy) = axis(ry, origin.y, sprite_size.y, bounds.y(), bounds.height());
+    (Vec2::new(x, y), Vec2::new(w, h), Vec2::new(ox, oy))
+}
+
+impl Draw2D {
+    /// Draws the layer relative to the camera, the camera must be already set on the draw
+    pub fn parallax(&mut self, layer: &ParallaxLayer, cam: &dyn BaseCam2D) {
+        let bounds = cam.bounds();
+        let origin = layer.origin(bounds.center());
+        let (pos, size, offset) = layer_area(origin, layer.sprite.size(), layer.repeat, bounds);
+        if !bounds.intersects(&Rect::new(pos, size)) {
+            return;
+        }
+
+        self.pattern(&layer.sprite)
+            .position(pos)
+            .size(size)
+            .image_offset(offset)
+            .color(layer.color);
+    }
+
+    /// Draws the layers in order, the first one is the farthest
+    pub fn parallax_layers(&mut self, layers: &[ParallaxLayer], cam: &dyn BaseCam2D) {
+        layers.iter().for_each(|layer| self.parallax(layer, cam));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use corelib::math::vec2;
+
+    #[test]
+    fn test_layer_area_repeat_x() {
+        let bounds = Rect::new(vec2(100.0, 0.0), vec2(800.0, 600.0));
+        let (pos, size, offset) = layer_area(
+            vec2(20.0, 300.0),
+            vec2(64.0, 128.0),
+            ParallaxRepeat::X,
+            bounds,
+        );
+        assert_eq!(pos, vec2(100.0, 300.0));
+        assert_eq!(size, vec2(800.0, 128.0));
+        assert_eq!(offset, vec2(80.0, 0.0));
+    }
+
+    #[test]
+    fn test_layer_area_no_repeat() {
+        let bounds = Rect::new(Vec2::ZERO, vec2(800.0, 600.0));
+        let (pos, size, offset) = layer_area(
+            vec2(20.0, 30.0),
+            vec2(64.0, 128.0),
+            ParallaxRepeat::None,
+            bounds,
+        );
+        assert_eq!(pos, vec2(20.0, 30.0));
+        assert_eq!(size, vec2(64.0, 128.0));
+        assert_eq!(offset, Vec2::ZERO);
+    }
+}
@@ -1,3 +1,4 @@
+use crate::m2d::variant::{variant_shader, with_variant_2d, PipelineVariant2D};
 use crate::{Draw2D, DrawPipelineId, DrawingInfo, Element2D, PipelineContext, Sprite, Transform2D};
 use corelib::gfx::{
     self, BindGroupLayout, BindingType, BlendMode, Buffer, Color, VertexFormat, VertexLayout,
@@ -47,7 +48,7 @@ var t_texture: texture_2d<f32>;
 var s_texture: sampler;
 
 // srg to linear
-{{SRGB_TO_LINEAR}}
+#include "srgb"
 
 @fragment
 fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
@@ -58,11 +59,15 @@ fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
 "#;
 
 pub fn create_pattern_2d_pipeline_ctx(ubo_transform: &Buffer) -> Result<PipelineContext, String> {
-    let shader = SHADER.replace(
-        "{{SRGB_TO_LINEAR}}",
-        include_str!("../resources/to_linear.wgsl"),
-    );
-    let pip = gfx::create_render_pipeline(&shader)
+    pattern_2d_pipeline_ctx(ubo_transform, Default::default())
+}
+
+pub(crate) fn pattern_2d_pipeline_ctx(
+    ubo_transform: &Buffer,
+    variant: PipelineVariant2D,
+) -> Result<PipelineContext, String> {
+    let shader = variant_shader(SHADER, variant);
+    let builder = gfx::create_render_pipeline(&shader)
         .with_label("Draw2D pattern default pipeline")
         .with_vertex_layout(
             VertexLayout::new()
@@ -79,8 +84,9 @@ pub fn create_pattern_2d_pipeline_ctx(ubo_transform: &Buffer) -> Result<Pipeline
                 .with_entry(BindingType::texture(0).with_fragment_visibility(true))
                 .with_entry(BindingType::sampler(1).with_fragment_visibility(true)),
         )
-        .with_blend_mode(BlendMode::NORMAL)
-        .build()?;
+        .with_blend_mode(BlendMode::NORMAL);
+
+    let pip = with_variant_2d(builder, variant).build()?;
 
     let bind_group = gfx::create_bind_group()
         .with_layout(pip.bind_group_layout_ref(0)?)
@@ -0,0 +1,220 @@
+use crate::shapes::{TessMode, SHAPE_TESSELLATOR};
+use crate::{Draw2D, DrawPipelineId, DrawingInfo, Element2D, Transform2D};
+use corelib::gfx::Color;
+use corelib::math::{bvec2, Mat3, Vec2};
+use lyon::geom::Arc;
+use lyon::math::{point, vector, Angle};
+use lyon::path::Path;
+use lyon::tessellation::*;
+use macros::Drawable2D;
+use num::Zero;
+
+#[derive(Drawable2D)]
+pub struct Arc2D {
+    color: Color,
+    pos: Vec2,
+    radius: f32,
+    start_angle: f32,
+    end_angle: f32,
+    stroke_width: f32,
+    alpha: f32,
+    tolerance: f32,
+    modes: [Option<TessMode>; 2],
+    mode_index: usize,
+    fill_color: Option<Color>,
+    stroke_color: Option<Color>,
+
+    #[pipeline_id]
+    pip: DrawPipelineId,
+
+    #[transform_2d]
+    transform: Option<Transform2D>,
+}
+
+impl Arc2D {
+    pub fn new(radius: f32, start_angle: f32, end_angle: f32) -> Self {
+        debug_assert!(!radius.is_zero(), "Arc radius cannot be 0");
+        Self {
+            color: Color::WHITE,
+            pos: Vec2::splat(0.0),
+            radius,
+            start_angle,
+            end_angle,
+            stroke_width: 1.0,
+            alpha: 1.0,
+            tolerance: StrokeOptions::DEFAULT_TOLERANCE,
+            modes: [None; 2],
+            mode_index: 0,
+            fill_color: None,
+            stroke_color: None,
+
+            pip: DrawPipelineId::Shapes,
+            transform: None,
+        }
+    }
+
+    pub fn position(&mut self, pos: Vec2) -> &mut Self {
+        self.pos = pos;
+        self
+    }
+
+    pub fn tolerance(&mut self, value: f32) -> &mut Self {
+        self.tolerance = value;
+        self
+    }
+
+    pub fn fill_color(&mut self, color: Color) -> &mut Self {
+        self.fill_color = Some(color);
+        self
+    }
+
+    pub fn stroke_color(&mut self, color: Color) -> &mut Self {
+        self.stroke_color = Some(color);
+        self
+    }
+
+    pub fn color(&mut self, color: Color) -> &mut Self {
+        self.color = color;
+        self
+    }
+
+    pub fn alpha(&mut self, alpha: f32) -> &mut Self {
+        self.alpha = alpha;
+        self
+    }
+
+    pub fn fill(&mut self) -> &mut Self {
+        self.modes[self.mode_index] = Some(TessMode::Fill);
+        self.mode_index = (self.mode_index + 1) % 2;
+        self
+    }
+
+    pub fn stroke(&mut self, width: f32) -> &mut Self {
+        self.modes[self.mode_index] = Some(TessMode::Stroke);
+        self.stroke_width = width;
+        self.mode_index = (self.mode_index + 1) % 2;
+        self
+    }
+}
+
+impl Element2D for Arc2D {
+    fn process(&self, draw: &mut Draw2D) {
+        // default to stroke mode
+        let first_mode = self.modes[0].unwrap_or(TessMode::Stroke);
+        match first_mode {
+            TessMode::Fill => fill(self, draw),
+            TessMode::Stroke => stroke(self, draw),
+        }
+
+        if let Some(mode) = self.modes[1] {
+            match mode {
+                TessMode::Fill => fill(self, draw),
+                TessMode::Stroke => stroke(self, draw),
+            }
+        }
+    }
+}
+
+fn stroke(arc: &Arc2D, draw: &mut Draw2D) {
+    let stroke_options = StrokeOptions::default()
+        .with_line_width(arc.stroke_width)
+        .with_tolerance(arc.tolerance);
+
+    let color = arc.stroke_color.unwrap_or(arc.color);
+    let color = color.with_alpha(color.a * arc.alpha);
+
+    let size = Vec2::splat(arc.radius * 2.0);
+    let center = arc.pos + size * 0.5;
+
+    let raw = tess_arc(
+        center,
+        arc.radius,
+        arc.start_angle,
+        arc.end_angle,
+        false,
+        arc.tolerance,
+    );
+    let (mut vertices, indices) = SHAPE_TESSELLATOR.with(|st| {
+        st.borrow_mut()
+            .stroke_lyon_path(&raw, color, &stroke_options)
+    });
+
+    let matrix = arc
+        .transform
+        .map_or(Mat3::IDENTITY, |mut t| t.set_size(size).updated_mat3());
+
+    draw.add_to_batch(DrawingInfo {
+        pipeline: arc.pip,
+        vertices: &mut vertices,
+        indices: &indices,
+        transform: matrix,
+        sprite: None,
+    });
+}
+
+fn fill(arc: &Arc2D, draw: &mut Draw2D) {
+    let fill_options = FillOptions::default().with_tolerance(arc.tolerance);
+
+    let color = arc.fill_color.unwrap_or(arc.color);
+    let color = color.with_alpha(color.a * arc.alpha);
+
+    let size = Vec2::splat(arc.radius * 2.0);
+    let center = arc.pos + size * 0.5;
+
+    // filling an arc closes it with a chord between both ends
+    let raw = tess_arc(
+        center,
+        arc.radius,
+        arc.start_angle,
+        arc.end_angle,
+        false,
+        arc.tolerance,
+    );
+    let (mut vertices, indices) =
+        SHAPE_TESSELLATOR.with(|st| st.borrow_mut().fill_lyon_path(&raw, color, &fill_options));
+
+    let matrix = arc
+        .transform
+        .map_or(Mat3::IDENTITY, |mut t| t.set_size(size).updated_mat3());
+
+    draw.add_to_batch(DrawingInfo {
+        pipeline: arc.pip,
+        vertices: &mut vertices,
+        indices: &indices,
+        transform: matrix,
+        sprite: None,
+    });
+}
+
+/// Builds an arc path, if `from_center` is true the path will start and end at the center
+pub(super) fn tess_arc(
+    center: Vec2,
+    radius: f32,
+    start_angle: f32,
+    end_angle: f32,
+    from_center: bool,
+    tolerance: f32,
+) -> Path {
+    let arc = Arc {
+        center: point(center.x, center.y),
+        radii: vector(radius, radius),
+        start_angle: Angle::radians(start_angle),
+        sweep_angle: Angle::radians(end_angle - start_angle),
+        x_rotation: Angle::radians(0.0),
+    };
+
+    let mut builder = Path::builder();
+    if from_center {
+        builder.begin(point(center.x, center.y));
+        builder.line_to(arc.from());
+    } else {
+        builder.begin(arc.from());
+    }
+
+    arc.for_each_flattened(tolerance, &mut |segment| {
+        builder.line_to(segment.to);
+    });
+
+    builder.end(from_center);
+    builder.build()
+}
@@ -0,0 +1,217 @@
+use crate::shapes::{TessMode, SHAPE_TESSELLATOR};
+use crate::{Draw2D, DrawPipelineId, DrawingInfo, Element2D, Transform2D};
+use corelib::gfx::Color;
+use corelib::math::{bvec2, Mat3, Vec2};
+use lyon::geom::Arc;
+use lyon::math::{point, vector, Angle};
+use lyon::path::Path;
+use lyon::tessellation::*;
+use macros::Drawable2D;
+use num::Zero;
+
+#[derive(Drawable2D)]
+pub struct Capsule2D {
+    color: Color,
+    p1: Vec2,
+    p2: Vec2,
+    radius: f32,
+    stroke_width: f32,
+    alpha: f32,
+    tolerance: f32,
+    modes: [Option<TessMode>; 2],
+    mode_index: usize,
+    fill_color: Option<Color>,
+    stroke_color: Option<Color>,
+
+    #[pipeline_id]
+    pip: DrawPipelineId,
+
+    #[transform_2d]
+    transform: Option<Transform2D>,
+}
+
+impl Capsule2D {
+    pub fn new(p1: Vec2, p2: Vec2, radius: f32) -> Self {
+        debug_assert!(!radius.is_zero(), "Capsule radius cannot be 0");
+        Self {
+            color: Color::WHITE,
+            p1,
+            p2,
+            radius,
+            stroke_width: 1.0,
+            alpha: 1.0,
+            tolerance: StrokeOptions::DEFAULT_TOLERANCE,
+            modes: [None; 2],
+            mode_index: 0,
+            fill_color: None,
+            stroke_color: None,
+
+            pip: DrawPipelineId::Shapes,
+            transform: None,
+        }
+    }
+
+    pub fn tolerance(&mut self, value: f32) -> &mut Self {
+        self.tolerance = value;
+        self
+    }
+
+    pub fn fill_color(&mut self, color: Color) -> &mut Self {
+        self.fill_color = Some(color);
+        self
+    }
+
+    pub fn stroke_color(&mut self, color: Color) -> &mut Self {
+        self.stroke_color = Some(color);
+        self
+    }
+
+    pub fn color(&mut self, color: Color) -> &mut Self {
+        self.color = color;
+        self
+    }
+
+    pub fn alpha(&mut self, alpha: f32) -> &mut Self {
+        self.alpha = alpha;
+        self
+    }
+
+    pub fn fill(&mut self) -> &mut Self {
+        self.modes[self.mode_index] = Some(TessMode::Fill);
+        self.mode_index = (self.mode_index + 1) % 2;
+        self
+    }
+
+    pub fn stroke(&mut self, width: f32) -> &mut Self {
+        self.modes[self.mode_index] = Some(TessMode::Stroke);
+        self.stroke_width = width;
+        self.mode_index = (self.mode_index + 1) % 2;
+        self
+    }
+}
+
+impl Element2D for Capsule2D {
+    fn process(&self, draw: &mut Draw2D) {
+        // default to fill mode
+        let first_mode = self.modes[0].unwrap_or(TessMode::Fill);
+        match first_mode {
+            TessMode::Fill => fill(self, draw),
+            TessMode::Stroke => stroke(self, draw),
+        }
+
+        if let Some(mode) = self.modes[1] {
+            match mode {
+                TessMode::Fill => fill(self, draw),
+                TessMode::Stroke => stroke(self, draw),
+            }
+        }
+    }
+}
+
+fn stroke(capsule: &Capsule2D, draw: &mut Draw2D) {
+    let stroke_options = StrokeOptions::default()
+        .with_line_width(capsule.stroke_width)
+        .with_tolerance(capsule.tolerance);
+
+    let color = capsule.stroke_color.unwrap_or(capsule.color);
+    let color = color.with_alpha(color.a * capsule.alpha);
+
+    let size = capsule_size(capsule.p1, capsule.p2, capsule.radius);
+
+    let raw = tess_capsule(capsule.p1, capsule.p2, capsule.radius, capsule.tolerance);
+    let (mut vertices, indices) = SHAPE_TESSELLATOR.with(|st| {
+        st.borrow_mut()
+            .stroke_lyon_path(&raw, color, &stroke_options)
+    });
+
+    let matrix = capsule
+        .transform
+        .map_or(Mat3::IDENTITY, |mut t| t.set_size(size).updated_mat3());
+
+    draw.add_to_batch(DrawingInfo {
+        pipeline: capsule.pip,
+        vertices: &mut vertices,
+        indices: &indices,
+        transform: matrix,
+        sprite: None,
+    });
+}
+
+fn fill(capsule: &Capsule2D, draw: &mut Draw2D) {
+    let fill_options = FillOptions::default().with_tolerance(capsule.tolerance);
+
+    let color = capsule.fill_color.unwrap_or(capsule.color);
+    let color = color.with_alpha(color.a * capsule.alpha);
+
+    let size = capsule_size(capsule.p1, capsule.p2, capsule.radius);
+
+    let raw = tess_capsule(capsule.p1, capsule.p2, capsule.radius, capsule.tolerance);
+    let (mut vertices, indices) =
+        SHAPE_TESSELLATOR.with(|st| st.borrow_mut().fill_lyon_path(&raw, color, &fill_options));
+
+    let matrix = capsule
+        .transform
+        .map_or(Mat3::IDENTITY, |mut t| t.set_size(size).updated_mat3());
+
+    draw.add_to_batch(DrawingInfo {
+        pipeline: capsule.pip,
+        vertices: &mut vertices,
+        indices: &indices,
+        transform: matrix,
+        sprite: None,
+    });
+}
+
+fn capsule_size(p1: Vec2, p2: Vec2, radius: f32) -> Vec2 {
+    (p1 - p2).abs() + Vec2::splat(radius * 2.0)
+}
+
+fn tess_capsule(p1: Vec2, p2: Vec2, radius: f32, tolerance: f32) -> Path {
+    let dir = p2 - p1;
+    let angle = dir.y.atan2(dir.x);
+    let half_turn = Angle::radians(std::f32::consts::PI);
+
+    let end_cap = Arc {
+        center: point(p2.x, p2.y),
+        radii: vector(radius, radius),
+        start_angle: Angle::radians(angle - std::f32::consts::FRAC_PI_2),
+        sweep_angle: half_turn,
+        x_rotation: Angle::radians(0.0),
+    };
+
+    let start_cap = Arc {
+        center: point(p1.x, p1.y),
+        radii: vector(radius, radius),
+        start_angle: Angle::radians(angle + std::f32::consts::FRAC_PI_2),
+        sweep_angle: half_turn,
+        x_rotation: Angle::radians(0.0),
+    };
+
+    let mut builder = Path::builder();
+    builder.begin(start_cap.to());
+    builder.line_to(end_cap.from());
+    end_cap.for_each_flattened(tolerance, &mut |segment| {
+        builder.line_to(segment.to);
+    });
+    builder.line_to(start_cap.from());
+    start_cap.for_each_flattened(tolerance, &mut |segment| {
+        builder.line_to(segment.to);
+    });
+    builder.end(true);
+    builder.build()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use corelib::math::vec2;
+
+    #[test]
+    fn test_capsule_size() {
+        let size = capsule_size(vec2(10.0, 10.0), vec2(50.0, 20.0), 5.0);
+        assert_eq!(size, vec2(50.0, 20.0));
+
+        let size = capsule_size(vec2(50.0, 20.0), vec2(10.0, 10.0), 5.0);
+        assert_eq!(size, vec2(50.0, 20.0));
+    }
+}
@@ -1,22 +1,29 @@
+mod arc;
+mod capsule;
 mod cirlce;
 mod ellipse;
 mod line;
 mod path;
+mod pie;
 mod polygon;
 mod rectangle;
 mod star;
 mod triangle;
 
+pub use arc::*;
+pub use capsule::*;
 pub use cirlce::*;
 pub use ellipse::*;
 pub use line::*;
 pub use path::*;
+pub use pie::*;
 pub use polygon::*;
 pub use rectangle::*;
 pub use star::*;
 pub use triangle::*;
 
 use super::PipelineContext;
+use crate::m2d::variant::{variant_shader, with_variant_2d, PipelineVariant2D};
 use corelib::gfx::{
     self, BindGroupLayout, BindingType, BlendMode, Buffer, VertexFormat, VertexLayout,
 };
@@ -52,7 +59,7 @@ fn vs_main(
 }
 
 // srgb_to_linear
-{{SRGB_TO_LINEAR}}
+#include "srgb"
 
 @fragment
 fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
@@ -61,11 +68,15 @@ fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
 "#;
 
 pub fn create_shapes_2d_pipeline_ctx(ubo_transform: &Buffer) -> Result<PipelineContext, String> {
-    let shader = SHADER.replace(
-        "{{SRGB_TO_LINEAR}}",
-        include_str!("../../resources/to_linear.wgsl"),
-    );
-    let pip = gfx::create_render_pipeline(&shader)
+    shapes_2d_pipeline_ctx(ubo_transform, Default::default())
+}
+
+pub(crate) fn shapes_2d_pipeline_ctx(
+    ubo_transform: &Buffer,
+    variant: PipelineVariant2D,
+) -> Result<PipelineContext, String> {
+    let shader = variant_shader(SHADER, variant);
+    let builder = gfx::create_render_pipeline(&shader)
         .with_vertex_layout(
             VertexLayout::new()
                 .with_attr(0, VertexFormat::Float32x2)
@@ -74,8 +85,9 @@ pub fn create_shapes_2d_pipeline_ctx(ubo_transform: &Buffer) -> Result<PipelineC
         .with_bind_group_layout(
             BindGroupLayout::new().with_entry(BindingType::uniform(0).with_vertex_visibility(true)),
         )
-        .with_blend_mode(BlendMode::NORMAL)
-        .build()?;
+        .with_blend_mode(BlendMode::NORMAL);
+
+    let pip = with_variant_2d(builder, variant).build()?;
 
     let bind_group = gfx::create_bind_group()
         .with_layout(pip.bind_group_layout_ref(0)?)
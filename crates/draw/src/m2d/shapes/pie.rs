@@ -0,0 +1,184 @@
+use super::arc::tess_arc;
+use crate::shapes::{TessMode, SHAPE_TESSELLATOR};
+use crate::{Draw2D, DrawPipelineId, DrawingInfo, Element2D, Transform2D};
+use corelib::gfx::Color;
+use corelib::math::{bvec2, Mat3, Vec2};
+use lyon::tessellation::*;
+use macros::Drawable2D;
+use num::Zero;
+
+#[derive(Drawable2D)]
+pub struct Pie2D {
+    color: Color,
+    pos: Vec2,
+    radius: f32,
+    start_angle: f32,
+    end_angle: f32,
+    stroke_width: f32,
+    alpha: f32,
+    tolerance: f32,
+    modes: [Option<TessMode>; 2],
+    mode_index: usize,
+    fill_color: Option<Color>,
+    stroke_color: Option<Color>,
+
+    #[pipeline_id]
+    pip: DrawPipelineId,
+
+    #[transform_2d]
+    transform: Option<Transform2D>,
+}
+
+impl Pie2D {
+    pub fn new(radius: f32, start_angle: f32, end_angle: f32) -> Self {
+        debug_assert!(!radius.is_zero(), "Pie radius cannot be 0");
+        Self {
+            color: Color::WHITE,
+            pos: Vec2::splat(0.0),
+            radius,
+            start_angle,
+            end_angle,
+            stroke_width: 1.0,
+            alpha: 1.0,
+            tolerance: StrokeOptions::DEFAULT_TOLERANCE,
+            modes: [None; 2],
+            mode_index: 0,
+            fill_color: None,
+            stroke_color: None,
+
+            pip: DrawPipelineId::Shapes,
+            transform: None,
+        }
+    }
+
+    pub fn position(&mut self, pos: Vec2) -> &mut Self {
+        self.pos = pos;
+        self
+    }
+
+    pub fn tolerance(&mut self, value: f32) -> &mut Self {
+        self.tolerance = value;
+        self
+    }
+
+    pub fn fill_color(&mut self, color: Color) -> &mut Self {
+        self.fill_color = Some(color);
+        self
+    }
+
+    pub fn stroke_color(&mut self, color: Color) -> &mut Self {
+        self.stroke_color = Some(color);
+        self
+    }
+
+    pub fn color(&mut self, color: Color) -> &mut Self {
+        self.color = color;
+        self
+    }
+
+    pub fn alpha(&mut self, alpha: f32) -> &mut Self {
+        self.alpha = alpha;
+        self
+    }
+
+    pub fn fill(&mut self) -> &mut Self {
+        self.modes[self.mode_index] = Some(TessMode::Fill);
+        self.mode_index = (self.mode_index + 1) % 2;
+        self
+    }
+
+    pub fn stroke(&mut self, width: f32) -> &mut Self {
+        self.modes[self.mode_index] = Some(TessMode::Stroke);
+        self.stroke_width = width;
+        self.mode_index = (self.mode_index + 1) % 2;
+        self
+    }
+}
+
+impl Element2D for Pie2D {
+    fn process(&self, draw: &mut Draw2D) {
+        // default to fill mode
+        let first_mode = self.modes[0].unwrap_or(TessMode::Fill);
+        match first_mode {
+            TessMode::Fill => fill(self, draw),
+            TessMode::Stroke => stroke(self, draw),
+        }
+
+        if let Some(mode) = self.modes[1] {
+            match mode {
+                TessMode::Fill => fill(self, draw),
+                TessMode::Stroke => stroke(self, draw),
+            }
+        }
+    }
+}
+
+fn stroke(pie: &Pie2D, draw: &mut Draw2D) {
+    let stroke_options = StrokeOptions::default()
+        .with_line_width(pie.stroke_width)
+        .with_tolerance(pie.tolerance);
+
+    let color = pie.stroke_color.unwrap_or(pie.color);
+    let color = color.with_alpha(color.a * pie.alpha);
+
+    let size = Vec2::splat(pie.radius * 2.0);
+    let center = pie.pos + size * 0.5;
+
+    let raw = tess_arc(
+        center,
+        pie.radius,
+        pie.start_angle,
+        pie.end_angle,
+        true,
+        pie.tolerance,
+    );
+    let (mut vertices, indices) = SHAPE_TESSELLATOR.with(|st| {
+        st.borrow_mut()
+            .stroke_lyon_path(&raw, color, &stroke_options)
+    });
+
+    let matrix = pie
+        .transform
+        .map_or(Mat3::IDENTITY, |mut t| t.set_size(size).updated_mat3());
+
+    draw.add_to_batch(DrawingInfo {
+        pipeline: pie.pip,
+        vertices: &mut vertices,
+        indices: &indices,
+        transform: matrix,
+        sprite: None,
+    });
+}
+
+fn fill(pie: &Pie2D, draw: &mut Draw2D) {
+    let fill_options = FillOptions::default().with_tolerance(pie.tolerance);
+
+    let color = pie.fill_color.unwrap_or(pie.color);
+    let color = color.with_alpha(color.a * pie.alpha);
+
+    let size = Vec2::splat(pie.radius * 2.0);
+    let center = pie.pos + size * 0.5;
+
+    let raw = tess_arc(
+        center,
+        pie.radius,
+        pie.start_angle,
+        pie.end_angle,
+        true,
+        pie.tolerance,
+    );
+    let (mut vertices, indices) =
+        SHAPE_TESSELLATOR.with(|st| st.borrow_mut().fill_lyon_path(&raw, color, &fill_options));
+
+    let matrix = pie
+        .transform
+        .map_or(Mat3::IDENTITY, |mut t| t.set_size(size).updated_mat3());
+
+    draw.add_to_batch(DrawingInfo {
+        pipeline: pie.pip,
+        vertices: &mut vertices,
+        indices: &indices,
+        transform: matrix,
+        sprite: None,
+    });
+}
@@ -8,6 +8,25 @@ use lyon::path::{Path, Winding};
 use lyon::tessellation::*;
 use macros::Drawable2D;
 
+// width in local units of the band used to smooth the edges
+const AA_FRINGE_WIDTH: f32 = 1.0;
+
+/// Radius for each corner in the order `[top_left, top_right, bottom_right, bottom_left]`
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct CornerRadius(pub [f32; 4]);
+
+impl From<f32> for CornerRadius {
+    fn from(radius: f32) -> Self {
+        Self([radius; 4])
+    }
+}
+
+impl From<[f32; 4]> for CornerRadius {
+    fn from(radii: [f32; 4]) -> Self {
+        Self(radii)
+    }
+}
+
 #[derive(Drawable2D)]
 pub struct Rectangle2D {
     color: Color,
@@ -17,6 +36,7 @@ pub struct Rectangle2D {
     alpha: f32,
     rounded_corners: Option<[f32; 4]>,
     corner_tolerance: f32,
+    antialias: bool,
     modes: [Option<TessMode>; 2],
     mode_index: usize,
     fill_color: Option<Color>,
@@ -39,6 +59,7 @@ impl Rectangle2D {
             alpha: 1.0,
             rounded_corners: None,
             corner_tolerance: FillOptions::DEFAULT_TOLERANCE,
+            antialias: false,
             modes: [None; 2],
             mode_index: 0,
             fill_color: None,
@@ -49,8 +70,10 @@ impl Rectangle2D {
         }
     }
 
-    pub fn corner_radius(&mut self, radius: f32) -> &mut Self {
-        self.rounded_corners = Some([radius; 4]);
+    /// Accepts a radius for all the corners or `[top_left, top_right, bottom_right, bottom_left]`
+    pub fn corner_radius<R: Into<CornerRadius>>(&mut self, radius: R) -> &mut Self {
+        let CornerRadius(radii) = radius.into();
+        self.rounded_corners = Some(radii);
         self
     }
 
@@ -60,26 +83,30 @@ impl Rectangle2D {
     }
 
     pub fn top_left_radius(&mut self, radius: f32) -> &mut Self {
-        let mut corners = self.rounded_corners.unwrap_or([0.0, 0.0, 0.0, 0.0]);
-        corners[0] = radius;
-        self
+        self.set_corner(0, radius)
     }
 
     pub fn top_right_radius(&mut self, radius: f32) -> &mut Self {
-        let mut corners = self.rounded_corners.unwrap_or([0.0, 0.0, 0.0, 0.0]);
-        corners[1] = radius;
-        self
+        self.set_corner(1, radius)
+    }
+
+    pub fn bottom_right_radius(&mut self, radius: f32) -> &mut Self {
+        self.set_corner(2, radius)
     }
 
     pub fn bottom_left_radius(&mut self, radius: f32) -> &mut Self {
-        let mut corners = self.rounded_corners.unwrap_or([0.0, 0.0, 0.0, 0.0]);
-        corners[2] = radius;
+        self.set_corner(3, radius)
+    }
+
+    fn set_corner(&mut self, index: usize, radius: f32) -> &mut Self {
+        let corners = self.rounded_corners.get_or_insert([0.0; 4]);
+        corners[index] = radius;
         self
     }
 
-    pub fn bottom_right_radius(&mut self, radius: f32) -> &mut Self {
-        let mut corners = self.rounded_corners.unwrap_or([0.0, 0.0, 0.0, 0.0]);
-        corners[3] = radius;
+    /// Smooth the edges of the filled rectangle
+    pub fn antialias(&mut self, enabled: bool) -> &mut Self {
+        self.antialias = enabled;
         self
     }
 
@@ -151,7 +178,7 @@ fn stroke(quad: &Rectangle2D, draw: &mut Draw2D) {
     } = quad.size;
 
     let raw = match quad.rounded_corners {
-        Some([tl, tr, bl, br]) => rounded_rect(x, y, width, height, (tl, tr, bl, br)),
+        Some(corners) => rounded_rect(x, y, width, height, corners),
         _ => rectangle(x, y, width, height),
     };
 
@@ -198,8 +225,8 @@ fn fill(quad: &Rectangle2D, draw: &mut Draw2D) {
     } = quad.size;
 
     match quad.rounded_corners {
-        Some([tl, tr, bl, br]) => {
-            let raw = rounded_rect(x1, y1, width, height, (tl, tr, bl, br));
+        Some(corners) => {
+            let raw = rounded_rect(x1, y1, width, height, corners);
             let options = FillOptions::default().with_tolerance(quad.corner_tolerance);
             let (mut vertices, indices) =
                 SHAPE_TESSELLATOR.with(|st| st.borrow_mut().fill_lyon_path(&raw, c, &options));
@@ -223,6 +250,24 @@ fn fill(quad: &Rectangle2D, draw: &mut Draw2D) {
             draw_shape(&mut vertices, &indices);
         }
     };
+
+    if quad.antialias {
+        let raw = match quad.rounded_corners {
+            Some(corners) => rounded_rect(x1, y1, width, height, corners),
+            _ => rectangle(x1, y1, width, height),
+        };
+
+        let options = StrokeOptions::default()
+            .with_line_width(AA_FRINGE_WIDTH)
+            .with_tolerance(quad.corner_tolerance);
+        let center = quad.pos + quad.size * 0.5;
+        let (mut vertices, indices) = SHAPE_TESSELLATOR.with(|st| {
+            st.borrow_mut()
+                .aa_fringe_lyon_path(&raw, c, AA_FRINGE_WIDTH, center, &options)
+        });
+
+        draw_shape(&mut vertices, &indices);
+    }
 }
 
 fn rectangle(x: f32, y: f32, width: f32, height: f32) -> Path {
@@ -237,8 +282,8 @@ fn rectangle(x: f32, y: f32, width: f32, height: f32) -> Path {
     builder.build()
 }
 
-fn rounded_rect(x: f32, y: f32, width: f32, height: f32, corner: (f32, f32, f32, f32)) -> Path {
-    let (tl, tr, bl, br) = corner;
+fn rounded_rect(x: f32, y: f32, width: f32, height: f32, corners: [f32; 4]) -> Path {
+    let [tl, tr, br, bl] = corners;
 
     let mut builder = Path::builder();
     builder.add_rounded_rectangle(
@@ -256,3 +301,28 @@ fn rounded_rect(x: f32, y: f32, width: f32, height: f32, corner: (f32, f32, f32,
     );
     builder.build()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_corner_radius() {
+        let mut rect = Rectangle2D::new(Vec2::ZERO, Vec2::splat(100.0));
+        rect.corner_radius(10.0);
+        assert_eq!(rect.rounded_corners, Some([10.0; 4]));
+
+        rect.corner_radius([1.0, 2.0, 3.0, 4.0]);
+        assert_eq!(rect.rounded_corners, Some([1.0, 2.0, 3.0, 4.0]));
+    }
+
+    #[test]
+    fn test_single_corner_radius() {
+        let mut rect = Rectangle2D::new(Vec2::ZERO, Vec2::splat(100.0));
+        rect.top_left_radius(1.0)
+            .top_right_radius(2.0)
+            .bottom_right_radius(3.0)
+            .bottom_left_radius(4.0);
+        assert_eq!(rect.rounded_corners, Some([1.0, 2.0, 3.0, 4.0]));
+    }
+}
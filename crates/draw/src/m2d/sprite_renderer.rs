@@ -0,0 +1,150 @@
+use crate::{Draw2D, Sprite};
+use corelib::gfx::Color;
+use corelib::math::{bvec2, BVec2, Vec2};
+
+/// Describes how a sprite is rendered, the sprites are sorted by `layer` and then by `order`
+#[derive(Clone)]
+pub struct SpriteRenderer {
+    pub sprite: Sprite,
+    pub layer: i32,
+    pub order: i32,
+    pub flip: BVec2,
+    pub color: Color,
+}
+
+impl SpriteRenderer {
+    pub fn new(sprite: &Sprite) -> Self {
+        Self {
+            sprite: sprite.clone(),
+            layer: 0,
+            order: 0,
+            flip: bvec2(false, false),
+            color: Color::WHITE,
+        }
+    }
+
+    pub fn with_layer(mut self, layer: i32) -> Self {
+        self.layer = layer;
+        self
+    }
+
+    pub fn with_order(mut self, order: i32) -> Self {
+        self.order = order;
+        self
+    }
+
+    pub fn with_flip(mut self, flip: BVec2) -> Self {
+        self.flip = flip;
+        self
+    }
+
+    pub fn with_color(mut self, color: Color) -> Self {
+        self.color = color;
+        self
+    }
+}
+
+struct QueuedSprite {
+    renderer: SpriteRenderer,
+    position: Vec2,
+    rotation: f32,
+    scale: Vec2,
+}
+
+/// Collects the sprites to render during the frame and submits them sorted to a Draw2D,
+/// the sprites with the same layer and order keep the order in which they were pushed
+#[derive(Default)]
+pub struct SpriteRenderQueue {
+    items: Vec<QueuedSprite>,
+}
+
+impl SpriteRenderQueue {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a sprite centered on the position given
+    pub fn push(&mut self, renderer: &SpriteRenderer, position: Vec2) {
+        self.push_with_transform(renderer, position, 0.0, Vec2::ONE);
+    }
+
+    pub fn push_with_transform(
+        &mut self,
+        renderer: &SpriteRenderer,
+        position: Vec2,
+        rotation: f32,
+        scale: Vec2,
+    ) {
+        self.items.push(QueuedSprite {
+            renderer: renderer.clone(),
+            position,
+            rotation,
+            scale,
+        });
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.items.clear();
+    }
+
+    /// Sorts and draws the sprites, the queue is emptied after this
+    pub fn submit(&mut self, draw: &mut Draw2D) {
+        sort_by_layer(&mut self.items, |item| {
+            (item.renderer.layer, item.renderer.order)
+        });
+
+        self.items.drain(..).for_each(|item| {
+            let SpriteRenderer {
+                sprite,
+                flip,
+                color,
+                ..
+            } = &item.renderer;
+
+            draw.image(sprite)
+                .translate(item.position)
+                .anchor(Vec2::splat(0.5))
+                .rotation(item.rotation)
+                .scale(item.scale)
+                .flip_x(flip.x)
+                .flip_y(flip.y)
+                .color(*color);
+        });
+    }
+}
+
+// stable sort, so elements with the same key keep the insertion order
+fn sort_by_layer<T, F>(items: &mut [T], key: F)
+where
+    F: Fn(&T) -> (i32, i32),
+{
+    items.sort_by_key(key);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sort_by_layer() {
+        let mut items = vec![
+            ("a", 1, 0),
+            ("b", 0, 5),
+            ("c", 0, 1),
+            ("d", 1, 0),
+            ("e", -1, 10),
+        ];
+        sort_by_layer(&mut items, |(_, layer, order)| (*layer, *order));
+
+        let names = items.iter().map(|(n, _, _)| *n).collect::<Vec<_>>();
+        assert_eq!(names, ["e", "c", "b", "a", "d"]);
+    }
+}
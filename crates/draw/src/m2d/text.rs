@@ -1,3 +1,4 @@
+use crate::m2d::variant::{variant_shader, with_variant_2d, PipelineVariant2D};
 use crate::text::{get_mut_text_system, AtlasType, Font, HAlign, TextInfo};
 use crate::{Draw2D, DrawPipelineId, DrawingInfo, Element2D, PipelineContext, Transform2D};
 use corelib::gfx::{
@@ -60,7 +61,7 @@ var t_mask: texture_2d<f32>;
 var t_color: texture_2d<f32>;
 
 // srg to linear
-{{SRGB_TO_LINEAR}}
+#include "srgb"
 
 @fragment
 fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
@@ -112,7 +113,7 @@ const SELECT_TEXTURE_SAMPLER_NEAREST: &str = r#"
 // to make this work, webgpu targets will select the sampler based on the font but
 // webgl will select only one sampler based on the window "pixelated" flag. This means that
 // if the window is defined as pixelated all font will use a NEAREST sampler while targeting WebGL
-fn select_texture_sampler() -> &'static str {
+pub(super) fn select_texture_sampler() -> &'static str {
     #[cfg(any(not(target_arch = "wasm32"), not(feature = "webgl")))]
     {
         #[allow(clippy::needless_return)]
@@ -135,14 +136,16 @@ fn select_texture_sampler() -> &'static str {
 // we'll need to swap the batches if we're swapping between pixelated or linear fonts incurring in more
 // draw calls. Which is probably worth it if we want better webgl suppor.
 pub fn create_text_2d_pipeline_ctx(ubo_transform: &Buffer) -> Result<PipelineContext, String> {
-    let shader = SHADER
-        .replace(
-            "{{SRGB_TO_LINEAR}}",
-            include_str!("../resources/to_linear.wgsl"),
-        )
-        .replace("{{SELECT_TEXTURE_AND_SAMPLER}}", select_texture_sampler());
+    text_2d_pipeline_ctx(ubo_transform, Default::default())
+}
 
-    let pip = gfx::create_render_pipeline(&shader)
+pub(crate) fn text_2d_pipeline_ctx(
+    ubo_transform: &Buffer,
+    variant: PipelineVariant2D,
+) -> Result<PipelineContext, String> {
+    let shader = SHADER.replace("{{SELECT_TEXTURE_AND_SAMPLER}}", select_texture_sampler());
+    let shader = variant_shader(&shader, variant);
+    let builder = gfx::create_render_pipeline(&shader)
         .with_label("Draw2D text default pipeline")
         .with_vertex_layout(
             VertexLayout::new()
@@ -161,8 +164,9 @@ pub fn create_text_2d_pipeline_ctx(ubo_transform: &Buffer) -> Result<PipelineCon
                 .with_entry(BindingType::texture(2).with_fragment_visibility(true))
                 .with_entry(BindingType::texture(3).with_fragment_visibility(true)),
         )
-        .with_blend_mode(BlendMode::NORMAL)
-        .build()?;
+        .with_blend_mode(BlendMode::NORMAL);
+
+    let pip = with_variant_2d(builder, variant).build()?;
 
     let bind_group = gfx::create_bind_group()
         .with_layout(pip.bind_group_layout_ref(0)?)
@@ -274,8 +278,69 @@ impl<'a> Text2D<'a> {
     }
 }
 
+// Owned version of Text2D used to defer the processing of the text until the
+// recorded Draw2D is submitted, the text system is not available on worker threads
+#[derive(Clone)]
+pub(crate) struct RecordedText2D {
+    text: String,
+    font: Option<Font>,
+    position: Vec2,
+    color: Color,
+    alpha: f32,
+    size: f32,
+    line_height: Option<f32>,
+    max_width: Option<f32>,
+    h_align: HAlign,
+    res: f32,
+    pip: DrawPipelineId,
+    transform: Option<Transform2D>,
+}
+
+impl RecordedText2D {
+    pub(crate) fn as_text_2d(&self) -> Text2D<'_> {
+        Text2D {
+            text: &self.text,
+            font: self.font.as_ref(),
+            position: self.position,
+            color: self.color,
+            alpha: self.alpha,
+            size: self.size,
+            line_height: self.line_height,
+            max_width: self.max_width,
+            h_align: self.h_align,
+            res: self.res,
+            pip: self.pip,
+            transform: self.transform,
+        }
+    }
+}
+
+impl Text2D<'_> {
+    pub(crate) fn to_recorded(&self) -> RecordedText2D {
+        RecordedText2D {
+            text: self.text.to_string(),
+            font: self.font.cloned(),
+            position: self.position,
+            color: self.color,
+            alpha: self.alpha,
+            size: self.size,
+            line_height: self.line_height,
+            max_width: self.max_width,
+            h_align: self.h_align,
+            res: self.res,
+            pip: self.pip,
+            transform: self.transform,
+        }
+    }
+}
+
 impl Element2D for Text2D<'_> {
     fn process(&self, draw: &mut Draw2D) {
+        if draw.is_recording() {
+            draw.record_text(self.to_recorded());
+            return;
+        }
+
         let info = TextInfo {
             pos: self.position,
             font: self.font,
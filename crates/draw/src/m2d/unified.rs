@@ -0,0 +1,234 @@
+use super::text::select_texture_sampler;
+use crate::m2d::variant::{variant_shader, with_variant_2d, PipelineVariant2D};
+use crate::{DrawPipelineId, PipelineContext};
+use corelib::gfx::{
+    self, BindGroupLayout, BindingType, BlendMode, Buffer, VertexFormat, VertexLayout,
+};
+use corelib::math::Vec2;
+
+// Texture modes stored per vertex, text glyphs already use 0, 1 and 2
+const TEX_MASK_LINEAR: f32 = 0.0;
+const TEX_SPRITE: f32 = 3.0;
+
+const UNIFIED_VERTEX_OFFSET: usize = 9;
+
+// language=wgsl
+const SHADER: &str = r#"
+struct Transform {
+    mvp: mat4x4<f32>,
+};
+
+@group(0) @binding(0)
+var<uniform> transform: Transform;
+
+struct VertexInput {
+    @location(0) position: vec2<f32>,
+    @location(1) uvs: vec2<f32>,
+    @location(2) tex: f32,
+    @location(3) color: vec4<f32>,
+};
+
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) uvs: vec2<f32>,
+    @location(1) color: vec4<f32>,
+    @location(2) @interpolate(flat) tex: f32,
+};
+
+@vertex
+fn vs_main(
+    model: VertexInput,
+) -> VertexOutput {
+    var out: VertexOutput;
+    out.tex = model.tex;
+    out.color = model.color;
+    out.uvs = model.uvs;
+    out.position = transform.mvp * vec4(model.position, 0.0, 1.0);
+    return out;
+}
+
+@group(1) @binding(0)
+var t_texture: texture_2d<f32>;
+@group(1) @binding(1)
+var s_texture: sampler;
+
+@group(2) @binding(0)
+var s_linear: sampler;
+@group(2) @binding(1)
+var s_nearest: sampler;
+@group(2) @binding(2)
+var t_mask: texture_2d<f32>;
+@group(2) @binding(3)
+var t_color: texture_2d<f32>;
+
+// srg to linear
+#include "srgb"
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    let in_color = srgb_to_linear(in.color);
+
+    // sprites
+    if (in.tex == 3.0) {
+        return textureSampleLevel(t_texture, s_texture, in.uvs, 0.0) * in_color;
+    }
+
+    // glyphs masks and solid shapes (white pixel)
+    {{SELECT_TEXTURE_AND_SAMPLER}}
+
+    // emojis
+    let color_sample = textureSampleLevel(t_color, s_linear, in.uvs, 0.0);
+    return color_sample * in_color;
+}
+"#;
+
+/// Pipeline used when the painter's unified batching is enabled, shapes, images and text
+/// share the same vertex layout so they can be drawn in the same batch
+pub fn create_unified_2d_pipeline_ctx(ubo_transform: &Buffer) -> Result<PipelineContext, String> {
+    unified_2d_pipeline_ctx(ubo_transform, Default::default())
+}
+
+pub(crate) fn unified_2d_pipeline_ctx(
+    ubo_transform: &Buffer,
+    variant: PipelineVariant2D,
+) -> Result<PipelineContext, String> {
+    let shader = SHADER.replace("{{SELECT_TEXTURE_AND_SAMPLER}}", select_texture_sampler());
+    let shader = variant_shader(&shader, variant);
+    let builder = gfx::create_render_pipeline(&shader)
+        .with_label("Draw2D unified default pipeline")
+        .with_vertex_layout(
+            VertexLayout::new()
+                .with_attr(0, VertexFormat::Float32x2)
+                .with_attr(1, VertexFormat::Float32x2)
+                .with_attr(2, VertexFormat::Float32)
+                .with_attr(3, VertexFormat::Float32x4),
+        )
+        .with_bind_group_layout(
+            BindGroupLayout::new().with_entry(BindingType::uniform(0).with_vertex_visibility(true)),
+        )
+        .with_bind_group_layout(
+            BindGroupLayout::new()
+                .with_entry(BindingType::texture(0).with_fragment_visibility(true))
+                .with_entry(BindingType::sampler(1).with_fragment_visibility(true)),
+        )
+        .with_bind_group_layout(
+            BindGroupLayout::new()
+                .with_entry(BindingType::sampler(0).with_fragment_visibility(true))
+                .with_entry(BindingType::sampler(1).with_fragment_visibility(true))
+                .with_entry(BindingType::texture(2).with_fragment_visibility(true))
+                .with_entry(BindingType::texture(3).with_fragment_visibility(true)),
+        )
+        .with_blend_mode(BlendMode::NORMAL);
+
+    let pip = with_variant_2d(builder, variant).build()?;
+
+    let bind_group = gfx::create_bind_group()
+        .with_layout(pip.bind_group_layout_ref(0)?)
+        .with_uniform(0, ubo_transform)
+        .build()?;
+
+    Ok(PipelineContext {
+        pipeline: pip,
+        groups: (&[bind_group] as &[_]).try_into().unwrap(),
+        vertex_offset: UNIFIED_VERTEX_OFFSET,
+        x_pos: 0,
+        y_pos: 1,
+        alpha_pos: Some(8),
+    })
+}
+
+/// Returns true if the elements drawn with this pipeline can be converted to the unified layout
+pub(crate) fn is_unified_compatible(id: DrawPipelineId) -> bool {
+    matches!(
+        id,
+        DrawPipelineId::Shapes | DrawPipelineId::Images | DrawPipelineId::Text
+    )
+}
+
+/// Converts the vertices of the default pipelines to the unified layout
+/// `[x, y, u, v, tex, r, g, b, a]`, shapes will sample the white pixel from the glyph atlas
+pub(crate) fn to_unified_vertices(
+    id: DrawPipelineId,
+    vertices: &[f32],
+    white_pixel: Vec2,
+    out: &mut Vec<f32>,
+) {
+    match id {
+        DrawPipelineId::Shapes => {
+            out.reserve(vertices.len() / 6 * UNIFIED_VERTEX_OFFSET);
+            vertices.chunks_exact(6).for_each(|v| {
+                out.extend_from_slice(&[
+                    v[0],
+                    v[1],
+                    white_pixel.x,
+                    white_pixel.y,
+                    TEX_MASK_LINEAR,
+                    v[2],
+                    v[3],
+                    v[4],
+                    v[5],
+                ]);
+            });
+        }
+        DrawPipelineId::Images => {
+            out.reserve(vertices.len() / 8 * UNIFIED_VERTEX_OFFSET);
+            vertices.chunks_exact(8).for_each(|v| {
+                out.extend_from_slice(&[v[0], v[1], v[2], v[3], TEX_SPRITE]);
+                out.extend_from_slice(&v[4..8]);
+            });
+        }
+        DrawPipelineId::Text => out.extend_from_slice(vertices),
+        _ => unreachable!("Pipeline '{:?}' cannot be unified", id),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use corelib::math::vec2;
+
+    #[test]
+    fn test_unified_shapes_vertices() {
+        #[rustfmt::skip]
+        let vertices = [
+            1.0, 2.0, 0.1, 0.2, 0.3, 0.4,
+            3.0, 4.0, 0.5, 0.6, 0.7, 0.8,
+        ];
+
+        let mut out = vec![];
+        to_unified_vertices(DrawPipelineId::Shapes, &vertices, vec2(0.5, 0.25), &mut out);
+
+        #[rustfmt::skip]
+        let expected = [
+            1.0, 2.0, 0.5, 0.25, 0.0, 0.1, 0.2, 0.3, 0.4,
+            3.0, 4.0, 0.5, 0.25, 0.0, 0.5, 0.6, 0.7, 0.8,
+        ];
+        assert_eq!(out, expected);
+    }
+
+    #[test]
+    fn test_unified_images_vertices() {
+        #[rustfmt::skip]
+        let vertices = [
+            1.0, 2.0, 0.0, 1.0, 0.1, 0.2, 0.3, 0.4,
+        ];
+
+        let mut out = vec![];
+        to_unified_vertices(DrawPipelineId::Images, &vertices, Vec2::ZERO, &mut out);
+
+        #[rustfmt::skip]
+        let expected = [
+            1.0, 2.0, 0.0, 1.0, 3.0, 0.1, 0.2, 0.3, 0.4,
+        ];
+        assert_eq!(out, expected);
+    }
+
+    #[test]
+    fn test_unified_compatible() {
+        assert!(is_unified_compatible(DrawPipelineId::Shapes));
+        assert!(is_unified_compatible(DrawPipelineId::Images));
+        assert!(is_unified_compatible(DrawPipelineId::Text));
+        assert!(!is_unified_compatible(DrawPipelineId::Pattern));
+        assert!(!is_unified_compatible(DrawPipelineId::Custom(0)));
+    }
+}
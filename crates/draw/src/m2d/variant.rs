@@ -0,0 +1,125 @@
+use corelib::gfx::{
+    ColorMask, CompareMode, RenderPipelineBuilder, Stencil, StencilAction, VertexFormat,
+    VertexLayout,
+};
+
+// last location available, so it doesn't collide with the shader attributes
+const DEPTH_LOCATION: u64 = 15;
+
+// value written in the stencil buffer by the mask
+const MASK_REFERENCE: u8 = 1;
+
+/// Stencil mask mode used by Draw2D
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum Mask2D {
+    /// The elements write the mask without being visible
+    Write,
+    /// The elements are visible only where the mask was written
+    Inside,
+    /// The elements are visible only where the mask was not written
+    Outside,
+}
+
+/// Depth test and stencil mask applied to the built-in pipelines
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub(crate) struct PipelineVariant2D {
+    pub depth: Option<CompareMode>,
+    pub mask: Option<Mask2D>,
+}
+
+impl PipelineVariant2D {
+    pub fn is_default(&self) -> bool {
+        self.depth.is_none() && self.mask.is_none()
+    }
+}
+
+/// Adds the depth input to a Draw2D vertex shader, the depth comes from a second vertex buffer
+pub(crate) fn variant_shader(shader: &str, variant: PipelineVariant2D) -> String {
+    if variant.depth.is_none() {
+        return shader.to_string();
+    }
+
+    // the default projection maps z=0 to the near plane and z=-1 to the far plane
+    shader
+        .replace(
+            "model: VertexInput,",
+            &format!("model: VertexInput,\n    @location({DEPTH_LOCATION}) depth: f32,"),
+        )
+        .replace(
+            "vec4(model.position, 0.0, 1.0)",
+            "vec4(model.position, -depth, 1.0)",
+        )
+}
+
+/// Sets the depth vertex layout, the depth test and the stencil mask if needed
+pub(crate) fn with_variant_2d(
+    builder: RenderPipelineBuilder<'_>,
+    variant: PipelineVariant2D,
+) -> RenderPipelineBuilder<'_> {
+    let writing_mask = matches!(variant.mask, Some(Mask2D::Write));
+
+    let builder = match variant.depth {
+        Some(compare) => builder
+            .with_vertex_layout(
+                VertexLayout::new().with_attr(DEPTH_LOCATION, VertexFormat::Float32),
+            )
+            .with_depth_stencil(compare, !writing_mask),
+        None => builder,
+    };
+
+    match variant.mask {
+        Some(Mask2D::Write) => builder
+            .with_stencil(mask_stencil(
+                CompareMode::Always,
+                StencilAction::Replace,
+                0xff,
+            ))
+            .with_color_mask(ColorMask::NONE),
+        Some(Mask2D::Inside) => {
+            builder.with_stencil(mask_stencil(CompareMode::Equal, StencilAction::Keep, 0))
+        }
+        Some(Mask2D::Outside) => {
+            builder.with_stencil(mask_stencil(CompareMode::NotEqual, StencilAction::Keep, 0))
+        }
+        None => builder,
+    }
+}
+
+fn mask_stencil(compare: CompareMode, pass: StencilAction, write_mask: u32) -> Stencil {
+    Stencil {
+        stencil_fail: StencilAction::Keep,
+        depth_fail: StencilAction::Keep,
+        pass,
+        compare,
+        read_mask: 0xff,
+        write_mask,
+        reference: MASK_REFERENCE,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_variant_shader() {
+        let shader =
+            "fn vs_main(\n    model: VertexInput,\n) {\n    vec4(model.position, 0.0, 1.0)\n}";
+        assert_eq!(variant_shader(shader, Default::default()), shader);
+
+        // the mask doesn't need any change in the shader
+        let masked = PipelineVariant2D {
+            depth: None,
+            mask: Some(Mask2D::Inside),
+        };
+        assert_eq!(variant_shader(shader, masked), shader);
+
+        let with_depth = PipelineVariant2D {
+            depth: Some(CompareMode::LEqual),
+            mask: None,
+        };
+        let with_depth = variant_shader(shader, with_depth);
+        assert!(with_depth.contains("@location(15) depth: f32,"));
+        assert!(with_depth.contains("vec4(model.position, -depth, 1.0)"));
+    }
+}
@@ -0,0 +1,154 @@
+use crate::BaseCam2D;
+use corelib::math::{Rect, Vec2};
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum SimulationLod {
+    /// Visible or close to the camera
+    #[default]
+    Full,
+    /// Off-screen but near, spawn less and simulate cheaper
+    Reduced,
+    /// Far away from the camera, the simulation can be paused
+    Paused,
+}
+
+/// Off-screen throttling for effects like particle emitters, the level is chosen by the
+/// distance between the camera bounds and the effect bounds. The hysteresis avoids
+/// switching levels every frame when the effect is close to a threshold
+#[derive(Copy, Clone, Debug)]
+pub struct VisibilityLod {
+    pub reduce_distance: f32,
+    pub pause_distance: f32,
+    pub hysteresis: f32,
+    /// Spawn rate multiplier used for `SimulationLod::Reduced`
+    pub reduced_rate: f32,
+    lod: SimulationLod,
+}
+
+impl Default for VisibilityLod {
+    fn default() -> Self {
+        Self {
+            reduce_distance: 0.0,
+            pause_distance: 500.0,
+            hysteresis: 50.0,
+            reduced_rate: 0.25,
+            lod: SimulationLod::Full,
+        }
+    }
+}
+
+impl VisibilityLod {
+    pub fn new(reduce_distance: f32, pause_distance: f32) -> Self {
+        Self {
+            reduce_distance,
+            pause_distance: pause_distance.max(reduce_distance),
+            ..Default::default()
+        }
+    }
+
+    pub fn lod(&self) -> SimulationLod {
+        self.lod
+    }
+
+    /// Spawn rate multiplier for the current level
+    pub fn rate(&self) -> f32 {
+        match self.lod {
+            SimulationLod::Full => 1.0,
+            SimulationLod::Reduced => self.reduced_rate,
+            SimulationLod::Paused => 0.0,
+        }
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.lod == SimulationLod::Paused
+    }
+
+    /// Updates the level using the camera bounds and the bounds of the effect
+    pub fn update(&mut self, cam: &dyn BaseCam2D, bounds: Rect) -> SimulationLod {
+        self.update_with_distance(rect_distance(cam.bounds(), bounds))
+    }
+
+    pub fn update_with_distance(&mut self, distance: f32) -> SimulationLod {
+        // going farther needs to cross the threshold plus the hysteresis
+        let threshold = |limit: f32, is_farther: bool| {
+            if is_farther {
+                limit
+            } else {
+                limit + self.hysteresis
+            }
+        };
+
+        let reduce = threshold(self.reduce_distance, self.lod != SimulationLod::Full);
+        let pause = threshold(self.pause_distance, self.lod == SimulationLod::Paused);
+
+        self.lod = if distance <= reduce {
+            SimulationLod::Full
+        } else if distance <= pause {
+            SimulationLod::Reduced
+        } else {
+            SimulationLod::Paused
+        };
+
+        self.lod
+    }
+}
+
+/// Distance between the edges of two rects, 0.0 if they intersect
+pub fn rect_distance(a: Rect, b: Rect) -> f32 {
+    let gap = (a.min() - b.max()).max(b.min() - a.max()).max(Vec2::ZERO);
+    gap.length()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use corelib::math::vec2;
+
+    #[test]
+    fn test_rect_distance() {
+        let a = Rect::new(Vec2::ZERO, vec2(10.0, 10.0));
+        assert_eq!(
+            rect_distance(a, Rect::new(vec2(5.0, 5.0), vec2(10.0, 10.0))),
+            0.0
+        );
+        assert_eq!(
+            rect_distance(a, Rect::new(vec2(20.0, 0.0), vec2(5.0, 5.0))),
+            10.0
+        );
+        assert_eq!(
+            rect_distance(a, Rect::new(vec2(13.0, 14.0), vec2(5.0, 5.0))),
+            5.0
+        );
+    }
+
+    #[test]
+    fn test_lod_levels() {
+        let mut lod = VisibilityLod::new(0.0, 100.0);
+        lod.hysteresis = 10.0;
+
+        assert_eq!(lod.update_with_distance(0.0), SimulationLod::Full);
+        assert_eq!(lod.update_with_distance(50.0), SimulationLod::Reduced);
+        assert_eq!(lod.update_with_distance(200.0), SimulationLod::Paused);
+        assert_eq!(lod.rate(), 0.0);
+    }
+
+    #[test]
+    fn test_lod_hysteresis() {
+        let mut lod = VisibilityLod::new(0.0, 100.0);
+        lod.hysteresis = 10.0;
+
+        // stays reduced until crossing the pause distance plus the hysteresis
+        lod.update_with_distance(50.0);
+        assert_eq!(lod.update_with_distance(105.0), SimulationLod::Reduced);
+        assert_eq!(lod.update_with_distance(111.0), SimulationLod::Paused);
+
+        // comes back only when is below the pause distance
+        assert_eq!(lod.update_with_distance(105.0), SimulationLod::Paused);
+        assert_eq!(lod.update_with_distance(99.0), SimulationLod::Reduced);
+
+        // the first threshold works the same way
+        assert_eq!(lod.update_with_distance(0.0), SimulationLod::Full);
+        assert_eq!(lod.update_with_distance(5.0), SimulationLod::Full);
+        assert_eq!(lod.update_with_distance(11.0), SimulationLod::Reduced);
+    }
+}
@@ -1,4 +1,5 @@
 use corelib::gfx::Color;
+use corelib::math::Vec2;
 use lyon::path::Path;
 use lyon::tessellation::*;
 use std::cell::RefCell;
@@ -61,6 +62,40 @@ impl ShapeTessellator {
 
         (geometry.vertices.concat(), geometry.indices)
     }
+
+    /// Generates a thin band outside the edges of a convex path going from the color to
+    /// a transparent color, drawn along with the fill it smooths the edges of the shape
+    pub(crate) fn aa_fringe_lyon_path(
+        &mut self,
+        path: &Path,
+        color: Color,
+        width: f32,
+        center: Vec2,
+        options: &StrokeOptions,
+    ) -> (Vec<f32>, Vec<u32>) {
+        let mut geometry: VertexBuffers<[f32; 6], u32> = VertexBuffers::new();
+        self.stroke
+            .tessellate_path(
+                path,
+                options,
+                &mut BuffersBuilder::new(&mut geometry, |vertex: StrokeVertex| {
+                    let on_path = vertex.position_on_path();
+                    let normal = vertex.normal();
+                    let to_edge = (on_path.x - center.x, on_path.y - center.y);
+                    let is_outer = normal.x * to_edge.0 + normal.y * to_edge.1 > 0.0;
+                    if is_outer {
+                        let x = on_path.x + normal.x * width;
+                        let y = on_path.y + normal.y * width;
+                        [x, y, color.r, color.g, color.b, 0.0]
+                    } else {
+                        [on_path.x, on_path.y, color.r, color.g, color.b, color.a]
+                    }
+                }),
+            )
+            .unwrap();
+
+        (geometry.vertices.concat(), geometry.indices)
+    }
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
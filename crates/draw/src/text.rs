@@ -171,6 +171,10 @@ pub struct TextSystem {
     default_font: Option<Font>,
 
     bind_group: Option<BindGroup>,
+    unified_bind_group: Option<BindGroup>,
+
+    // reserved white pixel in the mask atlas used to draw solid shapes with unified batching
+    white_pixel: Vec2,
 
     // used to calculate rendering data
     process_data: Vec<ProcessData>,
@@ -247,11 +251,16 @@ impl TextSystem {
             default_font: None,
 
             bind_group: None,
+            unified_bind_group: None,
+
+            white_pixel: Vec2::ZERO,
 
             process_data: vec![],
             temp_data: vec![],
         };
 
+        sys.reserve_white_pixel()?;
+
         #[cfg(feature = "default-font")]
         {
             let font = sys.create_font(
@@ -282,6 +291,37 @@ impl TextSystem {
         self.bind_group.as_ref().unwrap()
     }
 
+    pub(crate) fn unified_bind_group(&mut self, pip: &RenderPipeline) -> &BindGroup {
+        if self.unified_bind_group.is_none() {
+            log::debug!("New text atlas unified bind_group created");
+            let bg = gfx::create_bind_group()
+                .with_sampler(0, &self.linear_sampler)
+                .with_sampler(1, &self.nearest_sampler)
+                .with_texture(2, &self.mask.texture)
+                .with_texture(3, &self.color.texture)
+                .with_layout(pip.bind_group_layout_ref(2).unwrap())
+                .build()
+                .unwrap();
+
+            self.unified_bind_group = Some(bg);
+        }
+
+        self.unified_bind_group.as_ref().unwrap()
+    }
+
+    /// UVs pointing to the center of the white pixel reserved in the mask atlas
+    pub(crate) fn white_pixel_uvs(&self) -> Vec2 {
+        (self.white_pixel + 0.5) / self.mask.texture.size()
+    }
+
+    fn reserve_white_pixel(&mut self) -> Result<(), String> {
+        self.white_pixel = self
+            .mask
+            .store(uvec2(1, 1), &[255])?
+            .ok_or_else(|| "Cannot reserve the white pixel on the text atlas".to_string())?;
+        Ok(())
+    }
+
     pub fn set_default_font(&mut self, font: &Font) {
         self.default_font = Some(font.clone());
     }
@@ -429,7 +469,11 @@ impl TextSystem {
             atlas.upload(size, offset, &image.data).unwrap();
         }
 
+        let white_pixel = uvec2(self.white_pixel.x as _, self.white_pixel.y as _);
+        self.mask.upload(uvec2(1, 1), white_pixel, &[255]).unwrap();
+
         self.bind_group = None;
+        self.unified_bind_group = None;
     }
 
     fn measure(&mut self, resolution: f32) -> Result<(Vec2, usize), String> {
@@ -551,7 +595,7 @@ impl TextSystem {
         self.cache.clear();
         self.temp_data.clear();
 
-        Ok(())
+        self.reserve_white_pixel()
     }
 }
 
@@ -0,0 +1,142 @@
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use std::path::Path;
+use syn::parse::{Parse, ParseStream};
+use syn::punctuated::Punctuated;
+use syn::{LitStr, Token};
+
+pub(crate) struct EmbedAssetsInput {
+    patterns: Punctuated<LitStr, Token![,]>,
+}
+
+impl Parse for EmbedAssetsInput {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        Ok(Self {
+            patterns: Punctuated::parse_terminated(input)?,
+        })
+    }
+}
+
+/// Registers every file matching the patterns (relative to the crate's manifest dir)
+/// with `include_bytes!`, the same path is used to load them at runtime
+pub(crate) fn expand(input: EmbedAssetsInput) -> syn::Result<TokenStream> {
+    let root = std::env::var("CARGO_MANIFEST_DIR")
+        .map_err(|_| syn::Error::new(Span::call_site(), "CARGO_MANIFEST_DIR is not set"))?;
+
+    let mut files = vec![];
+    for pattern in &input.patterns {
+        let matches = walk(Path::new(&root), &pattern.value())
+            .map_err(|e| syn::Error::new(pattern.span(), e))?;
+        if matches.is_empty() {
+            return Err(syn::Error::new(
+                pattern.span(),
+                format!("No files match '{}'", pattern.value()),
+            ));
+        }
+
+        files.extend(matches);
+    }
+
+    files.sort();
+    files.dedup();
+
+    let embeds = files.iter().map(|file| {
+        let full_path = Path::new(&root).join(file).to_string_lossy().to_string();
+        quote! {
+            rkit::assets::embed_asset(#file, include_bytes!(#full_path));
+        }
+    });
+
+    Ok(quote! {
+        {
+            #(#embeds)*
+        }
+    })
+}
+
+/// Files inside of the root matching the pattern, relative to the root
+fn walk(root: &Path, pattern: &str) -> Result<Vec<String>, String> {
+    let pattern = pattern.trim_start_matches("./");
+
+    // start from the directories before the first wildcard
+    let base = pattern
+        .split('/')
+        .take_while(|segment| !segment.contains(['*', '?']))
+        .collect::<Vec<_>>()
+        .join("/");
+
+    let mut files = vec![];
+    if root.join(&base).is_file() {
+        files.push(base);
+    } else if root.join(&base).is_dir() {
+        collect_files(root, &base, &mut files)?;
+    }
+
+    let pattern = segments(pattern);
+    files.retain(|file| match_segments(&pattern, &segments(file)));
+    Ok(files)
+}
+
+fn collect_files(root: &Path, dir: &str, files: &mut Vec<String>) -> Result<(), String> {
+    let path = root.join(dir);
+    let entries = std::fs::read_dir(&path)
+        .map_err(|e| format!("Cannot read directory '{}': {e}", path.display()))?;
+
+    for entry in entries {
+        let entry = entry.map_err(|e| e.to_string())?;
+        let name = entry.file_name().to_string_lossy().to_string();
+        let file = if dir.is_empty() {
+            name
+        } else {
+            format!("{}/{name}", dir.trim_end_matches('/'))
+        };
+
+        if entry.path().is_dir() {
+            collect_files(root, &file, files)?;
+        } else {
+            files.push(file);
+        }
+    }
+
+    Ok(())
+}
+
+// same rules than the glob patterns of the assets crate
+fn segments(path: &str) -> Vec<&str> {
+    path.split('/')
+        .filter(|s| !s.is_empty() && *s != ".")
+        .collect()
+}
+
+fn match_segments(pattern: &[&str], path: &[&str]) -> bool {
+    match pattern.split_first() {
+        None => path.is_empty(),
+        Some((&"**", rest)) => (0..=path.len()).any(|i| match_segments(rest, &path[i..])),
+        Some((segment, rest)) => path.split_first().is_some_and(|(name, path_rest)| {
+            match_name(segment, name) && match_segments(rest, path_rest)
+        }),
+    }
+}
+
+fn match_name(pattern: &str, name: &str) -> bool {
+    let (p, n) = (pattern.as_bytes(), name.as_bytes());
+    let (mut pi, mut ni) = (0, 0);
+    let mut star = None;
+    while ni < n.len() {
+        if pi < p.len() && (p[pi] == b'?' || p[pi] == n[ni]) {
+            pi += 1;
+            ni += 1;
+        } else if pi < p.len() && p[pi] == b'*' {
+            star = Some((pi, ni));
+            pi += 1;
+        } else if let Some((star_pi, star_ni)) = star {
+            pi = star_pi + 1;
+            ni = star_ni + 1;
+            star = Some((star_pi, star_ni + 1));
+        } else {
+            return false;
+        }
+    }
+
+    p[pi..].iter().all(|c| *c == b'*')
+}
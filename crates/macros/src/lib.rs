@@ -1,3 +1,5 @@
+mod embed;
+
 use proc_macro::TokenStream;
 use proc_macro2::Ident;
 use quote::{format_ident, quote};
@@ -357,3 +359,22 @@ pub fn derive_interpolable(input: TokenStream) -> TokenStream {
 
     TokenStream::from(interpolate_impl)
 }
+
+/// Embeds the files matching the glob patterns in the binary and registers them
+/// with `assets::embed_asset`, so they load without disk or network access (single
+/// binary builds, wasm without fetch). Call it once at startup:
+///
+/// ```rust,ignore
+/// embed_assets!("assets/ui/**/*.png", "assets/fonts/*.ttf");
+/// let id = load_asset("assets/ui/button.png");
+/// ```
+///
+/// Paths are relative to the crate's `Cargo.toml`. Changes to the embedded files trigger a
+/// rebuild, but new files matching the patterns need a rebuild of the crate to be added.
+#[proc_macro]
+pub fn embed_assets(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as embed::EmbedAssetsInput);
+    embed::expand(input)
+        .unwrap_or_else(|e| e.to_compile_error())
+        .into()
+}
@@ -0,0 +1,258 @@
+use std::any::Any;
+use std::collections::VecDeque;
+
+/// A reversible change over a value of type `T`.
+pub trait Edit<T> {
+    /// Applies the change to the target.
+    fn apply(&mut self, target: &mut T);
+
+    /// Restores the target to the state before `apply`.
+    fn revert(&mut self, target: &mut T);
+
+    /// Tries to combine the next edit into this one, used to coalesce consecutive
+    /// changes like slider drags into one undo step. Returns `true` if merged.
+    fn merge(&mut self, _next: &dyn Any) -> bool {
+        false
+    }
+}
+
+/// Stores the change of one field as a diff of old and new values.
+pub struct FieldEdit<T, V> {
+    key: &'static str,
+    accessor: fn(&mut T) -> &mut V,
+    old: Option<V>,
+    new: V,
+}
+
+impl<T, V: Clone> FieldEdit<T, V> {
+    /// Creates an edit that sets `value` into the field returned by `accessor`,
+    /// consecutive edits with the same `key` are merged.
+    pub fn new(key: &'static str, accessor: fn(&mut T) -> &mut V, value: V) -> Self {
+        Self {
+            key,
+            accessor,
+            old: None,
+            new: value,
+        }
+    }
+}
+
+impl<T: 'static, V: Clone + 'static> Edit<T> for FieldEdit<T, V> {
+    fn apply(&mut self, target: &mut T) {
+        let field = (self.accessor)(target);
+        if self.old.is_none() {
+            self.old = Some(field.clone());
+        }
+        *field = self.new.clone();
+    }
+
+    fn revert(&mut self, target: &mut T) {
+        if let Some(old) = &self.old {
+            *(self.accessor)(target) = old.clone();
+        }
+    }
+
+    fn merge(&mut self, next: &dyn Any) -> bool {
+        match next.downcast_ref::<Self>() {
+            Some(next) if next.key == self.key => {
+                self.new = next.new.clone();
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Undo/redo stack of edits applied to a value.
+pub struct EditHistory<T> {
+    undo: VecDeque<Box<dyn Edit<T>>>,
+    redo: Vec<Box<dyn Edit<T>>>,
+    limit: usize,
+    merge_next: bool,
+}
+
+impl<T> Default for EditHistory<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> EditHistory<T> {
+    /// Creates a history without limit of steps.
+    pub fn new() -> Self {
+        Self::with_limit(usize::MAX)
+    }
+
+    /// Creates a history that keeps only the last `limit` edits.
+    pub fn with_limit(limit: usize) -> Self {
+        Self {
+            undo: VecDeque::new(),
+            redo: vec![],
+            limit: limit.max(1),
+            merge_next: true,
+        }
+    }
+
+    /// Applies the edit to the target and records it, the redo stack is discarded.
+    pub fn apply<E: Edit<T> + 'static>(&mut self, target: &mut T, mut edit: E) {
+        edit.apply(target);
+        self.redo.clear();
+
+        let merged = self.merge_next
+            && self
+                .undo
+                .back_mut()
+                .is_some_and(|last| last.merge(&edit as &dyn Any));
+        self.merge_next = true;
+
+        if !merged {
+            self.undo.push_back(Box::new(edit));
+            if self.undo.len() > self.limit {
+                self.undo.pop_front();
+            }
+        }
+    }
+
+    /// The next edit will not be merged with the last one, call it when the user
+    /// finishes an interaction (e.g. releasing a slider).
+    pub fn commit(&mut self) {
+        self.merge_next = false;
+    }
+
+    /// Reverts the last edit, returns `false` if there is nothing to undo.
+    pub fn undo(&mut self, target: &mut T) -> bool {
+        let Some(mut edit) = self.undo.pop_back() else {
+            return false;
+        };
+
+        edit.revert(target);
+        self.redo.push(edit);
+        self.merge_next = false;
+        true
+    }
+
+    /// Applies again the last reverted edit, returns `false` if there is nothing to redo.
+    pub fn redo(&mut self, target: &mut T) -> bool {
+        let Some(mut edit) = self.redo.pop() else {
+            return false;
+        };
+
+        edit.apply(target);
+        self.undo.push_back(edit);
+        self.merge_next = false;
+        true
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Default)]
+    struct Config {
+        rate: f32,
+        name: String,
+    }
+
+    fn rate(c: &mut Config) -> &mut f32 {
+        &mut c.rate
+    }
+
+    fn name(c: &mut Config) -> &mut String {
+        &mut c.name
+    }
+
+    #[test]
+    fn test_undo_redo() {
+        let mut cfg = Config::default();
+        let mut history = EditHistory::new();
+
+        history.apply(&mut cfg, FieldEdit::new("name", name, "fire".to_string()));
+        history.commit();
+        history.apply(&mut cfg, FieldEdit::new("rate", rate, 10.0));
+        assert_eq!(cfg.rate, 10.0);
+
+        assert!(history.undo(&mut cfg));
+        assert_eq!(cfg.rate, 0.0);
+        assert_eq!(cfg.name, "fire");
+
+        assert!(history.undo(&mut cfg));
+        assert_eq!(cfg.name, "");
+        assert!(!history.undo(&mut cfg));
+
+        assert!(history.redo(&mut cfg));
+        assert!(history.redo(&mut cfg));
+        assert_eq!(cfg.rate, 10.0);
+        assert_eq!(cfg.name, "fire");
+        assert!(!history.can_redo());
+    }
+
+    #[test]
+    fn test_merge_consecutive_edits() {
+        let mut cfg = Config::default();
+        let mut history = EditHistory::new();
+
+        (1..=5).for_each(|i| history.apply(&mut cfg, FieldEdit::new("rate", rate, i as f32)));
+        assert_eq!(cfg.rate, 5.0);
+
+        // all the changes are undone in one step
+        assert!(history.undo(&mut cfg));
+        assert_eq!(cfg.rate, 0.0);
+        assert!(!history.can_undo());
+    }
+
+    #[test]
+    fn test_commit_stops_merge() {
+        let mut cfg = Config::default();
+        let mut history = EditHistory::new();
+
+        history.apply(&mut cfg, FieldEdit::new("rate", rate, 1.0));
+        history.commit();
+        history.apply(&mut cfg, FieldEdit::new("rate", rate, 2.0));
+
+        assert!(history.undo(&mut cfg));
+        assert_eq!(cfg.rate, 1.0);
+    }
+
+    #[test]
+    fn test_new_edit_clears_redo() {
+        let mut cfg = Config::default();
+        let mut history = EditHistory::new();
+
+        history.apply(&mut cfg, FieldEdit::new("rate", rate, 1.0));
+        history.undo(&mut cfg);
+        assert!(history.can_redo());
+
+        history.apply(&mut cfg, FieldEdit::new("rate", rate, 2.0));
+        assert!(!history.can_redo());
+    }
+
+    #[test]
+    fn test_limit() {
+        let mut cfg = Config::default();
+        let mut history = EditHistory::with_limit(2);
+
+        (1..=3).for_each(|i| {
+            history.apply(&mut cfg, FieldEdit::new("rate", rate, i as f32));
+            history.commit();
+        });
+
+        assert!(history.undo(&mut cfg));
+        assert!(history.undo(&mut cfg));
+        assert!(!history.undo(&mut cfg));
+        assert_eq!(cfg.rate, 1.0);
+    }
+}
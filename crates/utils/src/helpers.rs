@@ -20,6 +20,30 @@ pub const fn next_pot2(x: usize) -> usize {
     n + 1
 }
 
+/// Directory to store the user data of the app (saves, caches, settings...), based on
+/// `APPDATA` on windows, `~/Library/Application Support` on macos and `XDG_DATA_HOME`
+/// or `~/.local/share` on linux. Returns `None` if the base directory is unknown.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn user_data_path(app_name: &str) -> Option<std::path::PathBuf> {
+    use std::path::PathBuf;
+
+    let env_dir = |name: &str| {
+        std::env::var_os(name)
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+    };
+
+    let base = if cfg!(target_os = "windows") {
+        env_dir("APPDATA")
+    } else if cfg!(target_os = "macos") {
+        env_dir("HOME").map(|home| home.join("Library/Application Support"))
+    } else {
+        env_dir("XDG_DATA_HOME").or_else(|| env_dir("HOME").map(|home| home.join(".local/share")))
+    };
+
+    base.map(|dir| dir.join(app_name))
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -1,5 +1,10 @@
 pub mod drop_signal;
+pub mod edit_history;
 pub mod fast_cache;
 pub mod helpers;
 pub mod local_pool;
+pub mod pool;
+pub mod rect_pack;
 pub mod ring_buffer;
+pub mod save_blob;
+pub mod save_scheduler;
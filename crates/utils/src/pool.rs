@@ -0,0 +1,278 @@
+/// Identifies an active item inside a [`Pool`].
+/// Ids are invalidated when the item is released, reusing the slot
+/// bumps its generation so stale ids will not alias the new item.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PoolId {
+    index: u32,
+    generation: u32,
+}
+
+type ResetFn<T> = Box<dyn Fn(&mut T)>;
+
+struct Slot<T> {
+    value: T,
+    generation: u32,
+    active: bool,
+}
+
+/// A recycling pool for short-lived objects like bullets or particles.
+///
+/// Items are created upfront (or on demand when the pool runs out) and
+/// kept around once released, so `spawn` just re-activates an existing
+/// item instead of allocating a new one. The `reset` callback is called
+/// on every spawn to put the item back into a clean state.
+pub struct Pool<T> {
+    slots: Vec<Slot<T>>,
+    free: Vec<u32>,
+    create: Box<dyn Fn() -> T>,
+    reset: Option<ResetFn<T>>,
+    high_water_mark: usize,
+}
+
+impl<T> Pool<T> {
+    /// Creates a new pool pre-filled with `capacity` items made by `create`.
+    pub fn new<F>(capacity: usize, create: F) -> Self
+    where
+        F: Fn() -> T + 'static,
+    {
+        let mut pool = Self {
+            slots: Vec::with_capacity(capacity),
+            free: Vec::with_capacity(capacity),
+            create: Box::new(create),
+            reset: None,
+            high_water_mark: 0,
+        };
+        pool.grow(capacity);
+        pool
+    }
+
+    /// Sets a callback to reset the item each time it is spawned.
+    pub fn with_reset<F>(mut self, reset: F) -> Self
+    where
+        F: Fn(&mut T) + 'static,
+    {
+        self.reset = Some(Box::new(reset));
+        self
+    }
+
+    /// Adds `count` inactive items to the pool.
+    pub fn grow(&mut self, count: usize) {
+        self.slots.reserve(count);
+        self.free.reserve(count);
+        for _ in 0..count {
+            let index = self.slots.len() as u32;
+            self.slots.push(Slot {
+                value: (self.create)(),
+                generation: 0,
+                active: false,
+            });
+            self.free.push(index);
+        }
+    }
+
+    /// Activates an item and returns its id and a mutable reference to it.
+    /// If there are no free items a new one is created.
+    pub fn spawn(&mut self) -> (PoolId, &mut T) {
+        let index = match self.free.pop() {
+            Some(index) => index,
+            None => {
+                self.grow(1);
+                self.free.pop().unwrap()
+            }
+        };
+
+        let slot = &mut self.slots[index as usize];
+        slot.active = true;
+        if let Some(reset) = &self.reset {
+            reset(&mut slot.value);
+        }
+
+        let active = self.slots.len() - self.free.len();
+        self.high_water_mark = self.high_water_mark.max(active);
+
+        let slot = &mut self.slots[index as usize];
+        let id = PoolId {
+            index,
+            generation: slot.generation,
+        };
+        (id, &mut slot.value)
+    }
+
+    /// Returns the item to the pool. Returns `false` if the id is stale.
+    pub fn release(&mut self, id: PoolId) -> bool {
+        match self.slots.get_mut(id.index as usize) {
+            Some(slot) if slot.active && slot.generation == id.generation => {
+                slot.active = false;
+                slot.generation = slot.generation.wrapping_add(1);
+                self.free.push(id.index);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Releases every active item for which `keep` returns `false`.
+    pub fn retain<F>(&mut self, mut keep: F)
+    where
+        F: FnMut(&mut T) -> bool,
+    {
+        for (index, slot) in self.slots.iter_mut().enumerate() {
+            if slot.active && !keep(&mut slot.value) {
+                slot.active = false;
+                slot.generation = slot.generation.wrapping_add(1);
+                self.free.push(index as u32);
+            }
+        }
+    }
+
+    /// Returns a reference to the item if the id is still active.
+    pub fn get(&self, id: PoolId) -> Option<&T> {
+        self.slots
+            .get(id.index as usize)
+            .filter(|slot| slot.active && slot.generation == id.generation)
+            .map(|slot| &slot.value)
+    }
+
+    /// Returns a mutable reference to the item if the id is still active.
+    pub fn get_mut(&mut self, id: PoolId) -> Option<&mut T> {
+        self.slots
+            .get_mut(id.index as usize)
+            .filter(|slot| slot.active && slot.generation == id.generation)
+            .map(|slot| &mut slot.value)
+    }
+
+    /// Returns an iterator over the active items.
+    pub fn iter(&self) -> impl Iterator<Item = (PoolId, &T)> {
+        self.slots
+            .iter()
+            .enumerate()
+            .filter(|(_, slot)| slot.active)
+            .map(|(index, slot)| {
+                let id = PoolId {
+                    index: index as u32,
+                    generation: slot.generation,
+                };
+                (id, &slot.value)
+            })
+    }
+
+    /// Returns a mutable iterator over the active items.
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (PoolId, &mut T)> {
+        self.slots
+            .iter_mut()
+            .enumerate()
+            .filter(|(_, slot)| slot.active)
+            .map(|(index, slot)| {
+                let id = PoolId {
+                    index: index as u32,
+                    generation: slot.generation,
+                };
+                (id, &mut slot.value)
+            })
+    }
+
+    /// Number of active items.
+    pub fn active_len(&self) -> usize {
+        self.slots.len() - self.free.len()
+    }
+
+    /// Number of items ready to be spawned without allocating.
+    pub fn free_len(&self) -> usize {
+        self.free.len()
+    }
+
+    /// Total number of items owned by the pool.
+    pub fn capacity(&self) -> usize {
+        self.slots.len()
+    }
+
+    /// Highest number of items active at the same time.
+    /// Useful to tune the initial capacity of the pool.
+    pub fn high_water_mark(&self) -> usize {
+        self.high_water_mark
+    }
+
+    /// Resets the high water mark to the current number of active items.
+    pub fn reset_high_water_mark(&mut self) {
+        self.high_water_mark = self.active_len();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prefill() {
+        let pool = Pool::new(8, || 0u32);
+        assert_eq!(pool.capacity(), 8);
+        assert_eq!(pool.free_len(), 8);
+        assert_eq!(pool.active_len(), 0);
+    }
+
+    #[test]
+    fn test_spawn_reuses_released() {
+        let mut pool = Pool::new(1, || 0u32);
+        let (id, v) = pool.spawn();
+        *v = 10;
+        assert!(pool.release(id));
+        let (_, v) = pool.spawn();
+        assert_eq!(*v, 10);
+        assert_eq!(pool.capacity(), 1);
+    }
+
+    #[test]
+    fn test_reset_on_spawn() {
+        let mut pool = Pool::new(1, || 0u32).with_reset(|v| *v = 0);
+        let (id, v) = pool.spawn();
+        *v = 10;
+        pool.release(id);
+        let (_, v) = pool.spawn();
+        assert_eq!(*v, 0);
+    }
+
+    #[test]
+    fn test_grows_when_empty() {
+        let mut pool = Pool::new(1, || 0u32);
+        pool.spawn();
+        pool.spawn();
+        assert_eq!(pool.capacity(), 2);
+        assert_eq!(pool.active_len(), 2);
+    }
+
+    #[test]
+    fn test_stale_id() {
+        let mut pool = Pool::new(1, || 0u32);
+        let (id, _) = pool.spawn();
+        pool.release(id);
+        let (new_id, _) = pool.spawn();
+        assert_ne!(id, new_id);
+        assert!(pool.get(id).is_none());
+        assert!(!pool.release(id));
+        assert!(pool.get(new_id).is_some());
+    }
+
+    #[test]
+    fn test_high_water_mark() {
+        let mut pool = Pool::new(4, || 0u32);
+        let ids: Vec<_> = (0..3).map(|_| pool.spawn().0).collect();
+        ids.iter().for_each(|id| {
+            pool.release(*id);
+        });
+        pool.spawn();
+        assert_eq!(pool.high_water_mark(), 3);
+        pool.reset_high_water_mark();
+        assert_eq!(pool.high_water_mark(), 1);
+    }
+
+    #[test]
+    fn test_retain() {
+        let mut pool = Pool::new(4, || 0u32);
+        for i in 0..4 {
+            *pool.spawn().1 = i;
+        }
+        pool.retain(|v| *v % 2 == 0);
+        assert_eq!(pool.active_len(), 2);
+        assert!(pool.iter().all(|(_, v)| *v % 2 == 0));
+    }
+}
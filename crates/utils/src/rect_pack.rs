@@ -0,0 +1,227 @@
+/// Position and size of a rectangle placed by the packer
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PackedRect {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Segment {
+    x: u32,
+    y: u32,
+    width: u32,
+}
+
+/// Skyline bottom-left rectangle packer, useful to build atlases at runtime
+#[derive(Debug, Clone)]
+pub struct SkylinePacker {
+    width: u32,
+    height: u32,
+    padding: u32,
+    skyline: Vec<Segment>,
+    used_area: u64,
+}
+
+impl SkylinePacker {
+    /// Creates a packer for an area of `width` x `height`
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            padding: 0,
+            skyline: vec![Segment { x: 0, y: 0, width }],
+            used_area: 0,
+        }
+    }
+
+    /// Space left on the right and bottom of each rectangle, to avoid bleeding when sampling
+    pub fn with_padding(mut self, padding: u32) -> Self {
+        self.padding = padding;
+        self
+    }
+
+    /// Size of the packing area
+    pub fn size(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    /// Percentage of the area used, from 0.0 to 1.0
+    pub fn occupancy(&self) -> f32 {
+        let total = self.width as u64 * self.height as u64;
+        if total == 0 {
+            return 0.0;
+        }
+
+        self.used_area as f32 / total as f32
+    }
+
+    /// Places a rectangle, returns `None` if there is no space left for it
+    pub fn pack(&mut self, width: u32, height: u32) -> Option<PackedRect> {
+        let w = width + self.padding;
+        let h = height + self.padding;
+
+        // lowest top edge wins, on ties the leftmost one
+        let (idx, y) = (0..self.skyline.len())
+            .filter_map(|i| self.fit(i, w, h).map(|y| (i, y)))
+            .min_by_key(|(i, y)| (y + h, self.skyline[*i].x))?;
+
+        let x = self.skyline[idx].x;
+        self.add_segment(idx, x, y + h, w);
+        self.used_area += w as u64 * h as u64;
+
+        Some(PackedRect {
+            x,
+            y,
+            width,
+            height,
+        })
+    }
+
+    /// Packs all the sizes (tallest first for a better fit), returns the rectangles in the
+    /// same order as the sizes. Returns `None` and keeps the packer untouched if any fails
+    pub fn pack_all(&mut self, sizes: &[(u32, u32)]) -> Option<Vec<PackedRect>> {
+        let mut order = (0..sizes.len()).collect::<Vec<_>>();
+        order.sort_by_key(|&i| std::cmp::Reverse((sizes[i].1, sizes[i].0)));
+
+        let mut packer = self.clone();
+        let mut rects = vec![
+            PackedRect {
+                x: 0,
+                y: 0,
+                width: 0,
+                height: 0
+            };
+            sizes.len()
+        ];
+        for i in order {
+            let (w, h) = sizes[i];
+            rects[i] = packer.pack(w, h)?;
+        }
+
+        *self = packer;
+        Some(rects)
+    }
+
+    /// Removes all the rectangles
+    pub fn clear(&mut self) {
+        self.skyline.clear();
+        self.skyline.push(Segment {
+            x: 0,
+            y: 0,
+            width: self.width,
+        });
+        self.used_area = 0;
+    }
+
+    // returns the y position if the rect fits starting at the segment
+    fn fit(&self, idx: usize, width: u32, height: u32) -> Option<u32> {
+        let x = self.skyline[idx].x;
+        if x + width > self.width {
+            return None;
+        }
+
+        let mut y = 0;
+        let mut width_left = width;
+        for seg in &self.skyline[idx..] {
+            if width_left == 0 {
+                break;
+            }
+
+            y = y.max(seg.y);
+            if y + height > self.height {
+                return None;
+            }
+            width_left = width_left.saturating_sub(seg.width);
+        }
+
+        (width_left == 0).then_some(y)
+    }
+
+    fn add_segment(&mut self, idx: usize, x: u32, y: u32, width: u32) {
+        self.skyline.insert(idx, Segment { x, y, width });
+
+        // shrink or remove the segments below the new one
+        let end = x + width;
+        let next = idx + 1;
+        while next < self.skyline.len() {
+            let seg = &mut self.skyline[next];
+            if seg.x >= end {
+                break;
+            }
+
+            let overlap = end - seg.x;
+            if seg.width <= overlap {
+                self.skyline.remove(next);
+            } else {
+                seg.x += overlap;
+                seg.width -= overlap;
+                break;
+            }
+        }
+
+        // merge the segments with the same height
+        let mut i = 0;
+        while i + 1 < self.skyline.len() {
+            if self.skyline[i].y == self.skyline[i + 1].y {
+                self.skyline[i].width += self.skyline[i + 1].width;
+                self.skyline.remove(i + 1);
+            } else {
+                i += 1;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn overlaps(a: &PackedRect, b: &PackedRect) -> bool {
+        a.x < b.x + b.width && b.x < a.x + a.width && a.y < b.y + b.height && b.y < a.y + a.height
+    }
+
+    #[test]
+    fn test_pack() {
+        let mut packer = SkylinePacker::new(64, 64);
+        let a = packer.pack(32, 32).unwrap();
+        let b = packer.pack(32, 16).unwrap();
+        let c = packer.pack(32, 16).unwrap();
+        assert_eq!((a.x, a.y), (0, 0));
+        assert_eq!((b.x, b.y), (32, 0));
+        assert_eq!((c.x, c.y), (32, 16));
+        assert_eq!(packer.occupancy(), 0.5);
+
+        assert!(packer.pack(65, 1).is_none());
+        packer.clear();
+        assert_eq!(packer.occupancy(), 0.0);
+    }
+
+    #[test]
+    fn test_full() {
+        let mut packer = SkylinePacker::new(32, 32);
+        (0..16).for_each(|_| assert!(packer.pack(8, 8).is_some()));
+        assert!(packer.pack(8, 8).is_none());
+        assert_eq!(packer.occupancy(), 1.0);
+    }
+
+    #[test]
+    fn test_pack_all() {
+        let sizes = [(10, 5), (20, 30), (15, 15), (30, 8), (7, 22)];
+        let mut packer = SkylinePacker::new(64, 64).with_padding(1);
+        let rects = packer.pack_all(&sizes).unwrap();
+
+        for (i, rect) in rects.iter().enumerate() {
+            assert_eq!((rect.width, rect.height), sizes[i]);
+            assert!(rect.x + rect.width <= 64 && rect.y + rect.height <= 64);
+            rects[i + 1..]
+                .iter()
+                .for_each(|other| assert!(!overlaps(rect, other)));
+        }
+
+        let mut small = SkylinePacker::new(16, 16);
+        assert!(small.pack_all(&[(8, 8), (16, 16)]).is_none());
+        assert_eq!(small.occupancy(), 0.0);
+    }
+}
@@ -0,0 +1,132 @@
+const MAGIC: &[u8; 4] = b"RKSV";
+const VERSION: u16 = 1;
+
+// magic + version + header len + body len
+const PREFIX_SIZE: usize = 4 + 2 + 4 + 4;
+const CHECKSUM_SIZE: usize = 4;
+
+/// Save file split in a small header (slot name, date, playtime...) and the game data.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SaveData {
+    pub header: Vec<u8>,
+    pub body: Vec<u8>,
+}
+
+impl SaveData {
+    pub fn new(header: Vec<u8>, body: Vec<u8>) -> Self {
+        Self { header, body }
+    }
+}
+
+/// Bundles the header and the body into one portable blob. The format is the same
+/// on every platform (little endian) so the saves can be moved between devices
+/// and between web and native builds.
+pub fn export_save(save: &SaveData) -> Result<Vec<u8>, String> {
+    let header_len = u32::try_from(save.header.len())
+        .map_err(|_| "Save header is too big to be exported.".to_string())?;
+    let body_len = u32::try_from(save.body.len())
+        .map_err(|_| "Save body is too big to be exported.".to_string())?;
+
+    let mut bytes =
+        Vec::with_capacity(PREFIX_SIZE + save.header.len() + save.body.len() + CHECKSUM_SIZE);
+    bytes.extend_from_slice(MAGIC);
+    bytes.extend_from_slice(&VERSION.to_le_bytes());
+    bytes.extend_from_slice(&header_len.to_le_bytes());
+    bytes.extend_from_slice(&body_len.to_le_bytes());
+    bytes.extend_from_slice(&save.header);
+    bytes.extend_from_slice(&save.body);
+
+    let checksum = fnv1a(&bytes);
+    bytes.extend_from_slice(&checksum.to_le_bytes());
+    Ok(bytes)
+}
+
+/// Validates and unpacks a blob created with `export_save`.
+pub fn import_save(bytes: &[u8]) -> Result<SaveData, String> {
+    if bytes.len() < PREFIX_SIZE + CHECKSUM_SIZE {
+        return Err("Invalid save: the data is too short.".to_string());
+    }
+
+    if &bytes[..4] != MAGIC {
+        return Err("Invalid save: unknown format.".to_string());
+    }
+
+    let version = u16::from_le_bytes([bytes[4], bytes[5]]);
+    if version > VERSION {
+        return Err(format!(
+            "Invalid save: version '{version}' is newer than the supported '{VERSION}'."
+        ));
+    }
+
+    let (data, checksum) = bytes.split_at(bytes.len() - CHECKSUM_SIZE);
+    if fnv1a(data).to_le_bytes() != checksum {
+        return Err("Invalid save: checksum mismatch, the data is corrupted.".to_string());
+    }
+
+    let header_len = read_u32(&data[6..10]) as usize;
+    let body_len = read_u32(&data[10..14]) as usize;
+    let content = &data[PREFIX_SIZE..];
+    // the lengths come from the blob, so they can overflow on 32 bits targets
+    if header_len.checked_add(body_len) != Some(content.len()) {
+        return Err("Invalid save: size mismatch.".to_string());
+    }
+
+    let (header, body) = content.split_at(header_len);
+    Ok(SaveData::new(header.to_vec(), body.to_vec()))
+}
+
+fn read_u32(bytes: &[u8]) -> u32 {
+    u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
+}
+
+fn fnv1a(bytes: &[u8]) -> u32 {
+    bytes.iter().fold(0x811c9dc5, |hash, b| {
+        (hash ^ *b as u32).wrapping_mul(0x01000193)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn save() -> SaveData {
+        SaveData::new(b"slot 1".to_vec(), vec![1, 2, 3, 4, 5])
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let bytes = export_save(&save()).unwrap();
+        assert_eq!(import_save(&bytes).unwrap(), save());
+
+        let empty = export_save(&SaveData::default()).unwrap();
+        assert_eq!(import_save(&empty).unwrap(), SaveData::default());
+    }
+
+    #[test]
+    fn test_corrupted() {
+        let mut bytes = export_save(&save()).unwrap();
+        bytes[PREFIX_SIZE + 2] ^= 0xff;
+        assert!(import_save(&bytes).is_err());
+    }
+
+    #[test]
+    fn test_invalid() {
+        assert!(import_save(&[]).is_err());
+        assert!(import_save(b"not a save file at all").is_err());
+
+        let bytes = export_save(&save()).unwrap();
+        assert!(import_save(&bytes[..bytes.len() - 1]).is_err());
+    }
+
+    #[test]
+    fn test_crafted_lengths() {
+        // valid checksum but lengths that wrap around on 32 bits targets
+        let mut bytes = export_save(&save()).unwrap();
+        bytes.truncate(bytes.len() - CHECKSUM_SIZE);
+        bytes[6..10].copy_from_slice(&u32::MAX.to_le_bytes());
+        bytes[10..14].copy_from_slice(&12u32.to_le_bytes());
+        let checksum = fnv1a(&bytes);
+        bytes.extend_from_slice(&checksum.to_le_bytes());
+        assert!(import_save(&bytes).is_err());
+    }
+}
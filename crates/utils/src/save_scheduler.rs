@@ -0,0 +1,166 @@
+type WriterFn<T> = dyn FnMut(&T) -> Result<(), String>;
+
+/// Coalesces consecutive save requests into one write. The write happens once no
+/// new requests arrive during the debounce time, or after `max_delay` if the requests
+/// never stop. Any pending save is written when the scheduler is dropped.
+pub struct SaveScheduler<T> {
+    writer: Box<WriterFn<T>>,
+    pending: Option<T>,
+    debounce: f32,
+    max_delay: f32,
+    since_request: f32,
+    since_first: f32,
+}
+
+impl<T> SaveScheduler<T> {
+    /// Creates a scheduler that calls `writer` to persist the data.
+    pub fn new<F>(writer: F) -> Self
+    where
+        F: FnMut(&T) -> Result<(), String> + 'static,
+    {
+        Self {
+            writer: Box::new(writer),
+            pending: None,
+            debounce: 0.5,
+            max_delay: 5.0,
+            since_request: 0.0,
+            since_first: 0.0,
+        }
+    }
+
+    /// Seconds without new requests to wait before writing.
+    pub fn with_debounce(mut self, seconds: f32) -> Self {
+        self.debounce = seconds.max(0.0);
+        self
+    }
+
+    /// Maximum seconds a request can wait when new requests keep arriving.
+    pub fn with_max_delay(mut self, seconds: f32) -> Self {
+        self.max_delay = seconds.max(0.0);
+        self
+    }
+
+    /// Schedules `data` to be saved, replacing any pending data.
+    pub fn request(&mut self, data: T) {
+        if self.pending.is_none() {
+            self.since_first = 0.0;
+        }
+
+        self.pending = Some(data);
+        self.since_request = 0.0;
+    }
+
+    pub fn is_pending(&self) -> bool {
+        self.pending.is_some()
+    }
+
+    /// Advances the timers, returns `Ok(true)` if a write happened.
+    pub fn update(&mut self, dt: f32) -> Result<bool, String> {
+        if self.pending.is_none() {
+            return Ok(false);
+        }
+
+        self.since_request += dt;
+        self.since_first += dt;
+
+        let ready = self.since_request >= self.debounce || self.since_first >= self.max_delay;
+        if !ready {
+            return Ok(false);
+        }
+
+        self.flush()
+    }
+
+    /// Writes the pending data right away, returns `Ok(true)` if there was something to write.
+    /// If the write fails the data is kept to try again later.
+    pub fn flush(&mut self) -> Result<bool, String> {
+        let Some(data) = self.pending.take() else {
+            return Ok(false);
+        };
+
+        match (self.writer)(&data) {
+            Ok(()) => Ok(true),
+            Err(err) => {
+                self.pending = Some(data);
+                self.since_request = 0.0;
+                Err(err)
+            }
+        }
+    }
+}
+
+impl<T> Drop for SaveScheduler<T> {
+    fn drop(&mut self) {
+        if let Err(err) = self.flush() {
+            log::error!("Unable to flush pending save: {err}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    fn scheduler() -> (SaveScheduler<u32>, Rc<RefCell<Vec<u32>>>) {
+        let writes = Rc::new(RefCell::new(vec![]));
+        let w = writes.clone();
+        let scheduler = SaveScheduler::new(move |v: &u32| {
+            w.borrow_mut().push(*v);
+            Ok(())
+        })
+        .with_debounce(1.0)
+        .with_max_delay(3.0);
+        (scheduler, writes)
+    }
+
+    #[test]
+    fn test_debounce() {
+        let (mut scheduler, writes) = scheduler();
+        (0..10).for_each(|i| {
+            scheduler.request(i);
+            scheduler.update(0.1).unwrap();
+        });
+        assert!(writes.borrow().is_empty());
+
+        assert!(scheduler.update(1.0).unwrap());
+        assert_eq!(*writes.borrow(), [9]);
+        assert!(!scheduler.update(1.0).unwrap());
+    }
+
+    #[test]
+    fn test_max_delay() {
+        let (mut scheduler, writes) = scheduler();
+        (0..40).for_each(|i| {
+            scheduler.request(i);
+            scheduler.update(0.1).unwrap();
+        });
+        assert_eq!(writes.borrow().len(), 1);
+    }
+
+    #[test]
+    fn test_flush_on_drop() {
+        let (mut scheduler, writes) = scheduler();
+        scheduler.request(7);
+        drop(scheduler);
+        assert_eq!(*writes.borrow(), [7]);
+    }
+
+    #[test]
+    fn test_failed_write_is_kept() {
+        let mut fail = true;
+        let mut scheduler = SaveScheduler::new(move |_: &u32| {
+            if fail {
+                fail = false;
+                return Err("disk full".to_string());
+            }
+            Ok(())
+        });
+
+        scheduler.request(1);
+        assert!(scheduler.flush().is_err());
+        assert!(scheduler.is_pending());
+        assert!(scheduler.flush().unwrap());
+    }
+}
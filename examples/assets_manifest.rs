@@ -0,0 +1,42 @@
+use rkit::assets::AssetManifest;
+
+// Lists the files of a directory in a manifest used to expand glob patterns on web,
+// with their size and checksum to verify them at runtime with `set_assets_integrity`
+// usage: cargo run --example assets_manifest -- ./examples/assets assets.manifest
+fn main() -> Result<(), String> {
+    let mut args = std::env::args().skip(1);
+    let (Some(dir), Some(output)) = (args.next(), args.next()) else {
+        return Err("Usage: assets_manifest <directory> <output>".to_string());
+    };
+
+    let mut files = vec![];
+    collect_files(std::path::Path::new(&dir), &mut files)?;
+
+    let mut manifest = AssetManifest::new();
+    for file in &files {
+        let data = std::fs::read(file).map_err(|e| format!("Cannot read '{file}': {e}"))?;
+        manifest.add(file, &data);
+    }
+
+    std::fs::write(&output, manifest.to_text())
+        .map_err(|e| format!("Cannot write '{output}': {e}"))?;
+
+    println!("Listed {} files from '{dir}' into '{output}'", files.len());
+    Ok(())
+}
+
+fn collect_files(dir: &std::path::Path, files: &mut Vec<String>) -> Result<(), String> {
+    let entries = std::fs::read_dir(dir)
+        .map_err(|e| format!("Cannot read directory '{}': {e}", dir.display()))?;
+
+    for entry in entries {
+        let path = entry.map_err(|e| e.to_string())?.path();
+        if path.is_dir() {
+            collect_files(&path, files)?;
+        } else {
+            files.push(path.to_string_lossy().replace('\\', "/"));
+        }
+    }
+
+    Ok(())
+}
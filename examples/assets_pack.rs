@@ -0,0 +1,23 @@
+use assets::AssetPackWriter;
+
+// Bundles a directory into a single asset pack that can be mounted with `assets::mount_pack`
+// usage: cargo run --example assets_pack -- ./examples/assets assets.pak
+fn main() -> Result<(), String> {
+    let mut args = std::env::args().skip(1);
+    let (Some(dir), Some(output)) = (args.next(), args.next()) else {
+        return Err("Usage: assets_pack <directory> <output>".to_string());
+    };
+
+    let mut writer = AssetPackWriter::new();
+    writer.add_dir(&dir)?;
+    let bytes = writer.write()?;
+    std::fs::write(&output, &bytes).map_err(|e| format!("Cannot write '{output}': {e}"))?;
+
+    println!(
+        "Packed {} files from '{dir}' into '{output}' ({} bytes)",
+        writer.len(),
+        bytes.len()
+    );
+
+    Ok(())
+}
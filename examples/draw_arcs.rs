@@ -37,5 +37,21 @@ fn update() {
         .color(Color::BLUE)
         .fill();
 
+    // cooldown indicator
+    draw.pie(40.0, -90f32.to_radians(), angle - 90f32.to_radians())
+        .position(vec2(160.0, 480.0))
+        .color(Color::GREEN);
+
+    draw.arc(40.0, 0.0, angle)
+        .position(vec2(360.0, 480.0))
+        .color(Color::YELLOW)
+        .stroke(4.0);
+
+    draw.capsule(vec2(500.0, 520.0), vec2(700.0, 520.0), 20.0)
+        .fill_color(Color::PINK)
+        .fill()
+        .stroke_color(Color::WHITE)
+        .stroke(2.0);
+
     gfx::render_to_frame(&draw).unwrap();
 }
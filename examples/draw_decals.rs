@@ -0,0 +1,56 @@
+use rkit::app::window_size;
+use rkit::draw::{self, create_draw_2d, Sprite};
+use rkit::gfx::{self, Color, RenderTexture};
+use rkit::input::{is_mouse_btn_down, mouse_position, MouseButton};
+use rkit::math::Mat3;
+
+struct State {
+    sprite: Sprite,
+    canvas: RenderTexture,
+    canvas_sprite: Sprite,
+}
+
+impl State {
+    fn new() -> Result<Self, String> {
+        let sprite = draw::create_sprite()
+            .from_image(include_bytes!("assets/bunny.png"))
+            .build()?;
+
+        let size = window_size();
+        let canvas = gfx::create_render_texture()
+            .with_size(size.x as _, size.y as _)
+            .build()?;
+
+        let canvas_sprite = draw::create_sprite()
+            .from_texture(canvas.texture())
+            .build()?;
+
+        Ok(Self {
+            sprite,
+            canvas,
+            canvas_sprite,
+        })
+    }
+}
+
+fn main() -> Result<(), String> {
+    rkit::init_with(|| State::new().unwrap())
+        .update(update)
+        .run()
+}
+
+fn update(s: &mut State) {
+    // stamps are accumulated on the canvas, no need to keep them around
+    if is_mouse_btn_down(MouseButton::Left) {
+        let pos = mouse_position() - s.sprite.size() * 0.5;
+        draw::blit_sprite_into(&s.canvas, &s.sprite, Mat3::from_translation(pos)).unwrap();
+    }
+
+    let mut draw = create_draw_2d();
+    draw.clear(Color::rgb(0.1, 0.2, 0.3));
+    draw.image(&s.canvas_sprite);
+    draw.text("Click and drag to stamp bunnies")
+        .position(rkit::math::vec2(10.0, 10.0));
+
+    gfx::render_to_frame(&draw).unwrap();
+}
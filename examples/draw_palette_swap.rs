@@ -0,0 +1,80 @@
+use rkit::draw::{create_draw_2d, Sprite};
+use rkit::gfx::{self, Color, TextureFilter};
+use rkit::math::{vec2, Vec2};
+
+// each pixel stores the palette index in the red channel
+#[rustfmt::skip]
+const INDICES: [u8; 64] = [
+    0, 0, 1, 1, 1, 1, 0, 0,
+    0, 1, 2, 2, 2, 2, 1, 0,
+    1, 2, 3, 2, 2, 3, 2, 1,
+    1, 2, 2, 2, 2, 2, 2, 1,
+    1, 2, 3, 2, 2, 3, 2, 1,
+    1, 2, 2, 3, 3, 2, 2, 1,
+    0, 1, 2, 2, 2, 2, 1, 0,
+    0, 0, 1, 1, 1, 1, 0, 0,
+];
+
+struct State {
+    sprite: Sprite,
+    palettes: Vec<Sprite>,
+}
+
+impl State {
+    fn new() -> Result<Self, String> {
+        let bytes = INDICES
+            .iter()
+            .flat_map(|idx| [*idx, 0, 0, if *idx == 0 { 0 } else { 255 }])
+            .collect::<Vec<_>>();
+
+        let sprite = draw::create_sprite()
+            .from_bytes(&bytes, 8, 8)
+            .with_min_filter(TextureFilter::Nearest)
+            .with_mag_filter(TextureFilter::Nearest)
+            .build()?;
+
+        let palettes = [
+            [Color::TRANSPARENT, Color::BLACK, Color::YELLOW, Color::RED],
+            [Color::TRANSPARENT, Color::BLACK, Color::GREEN, Color::BLUE],
+            [
+                Color::TRANSPARENT,
+                Color::WHITE,
+                Color::ORANGE,
+                Color::BLACK,
+            ],
+        ]
+        .iter()
+        .map(|colors| {
+            let bytes = colors
+                .iter()
+                .flat_map(|c| c.to_rgba_u8())
+                .collect::<Vec<_>>();
+
+            draw::create_sprite().from_bytes(&bytes, 4, 1).build()
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self { sprite, palettes })
+    }
+}
+
+fn main() -> Result<(), String> {
+    rkit::init_with(|| State::new().unwrap())
+        .update(update)
+        .run()
+}
+
+fn update(s: &mut State) {
+    let mut draw = create_draw_2d();
+    draw.clear(Color::rgb(0.1, 0.2, 0.3));
+
+    // same sprite, different colors
+    s.palettes.iter().enumerate().for_each(|(i, lut)| {
+        draw.image(&s.sprite)
+            .position(vec2(100.0 + i as f32 * 200.0, 200.0))
+            .size(Vec2::splat(160.0))
+            .palette(lut);
+    });
+
+    gfx::render_to_frame(&draw).unwrap();
+}
@@ -0,0 +1,81 @@
+use draw::{ParallaxLayer, ParallaxRepeat, ScreenMode};
+use rkit::app::window_size;
+use rkit::draw::{create_draw_2d, Camera2D};
+use rkit::gfx::{self, Color};
+use rkit::input::{is_key_down, KeyCode};
+use rkit::math::{vec2, Vec2};
+use rkit::time;
+
+struct State {
+    cam: Camera2D,
+    player_pos: Vec2,
+    layers: Vec<ParallaxLayer>,
+}
+
+impl State {
+    fn new() -> Result<Self, String> {
+        let pattern = draw::create_sprite()
+            .from_image(include_bytes!("assets/pattern.png"))
+            .build()?;
+
+        let ferris = draw::create_sprite()
+            .from_image(include_bytes!("assets/ferris.png"))
+            .build()?;
+
+        // from far to near
+        let layers = vec![
+            ParallaxLayer::new(&pattern)
+                .with_factor(Vec2::splat(0.1))
+                .with_repeat(ParallaxRepeat::Both)
+                .with_color(Color::rgb(0.3, 0.3, 0.4)),
+            ParallaxLayer::new(&ferris)
+                .with_position(vec2(0.0, 150.0))
+                .with_factor(vec2(0.5, 1.0))
+                .with_scroll_speed(vec2(-20.0, 0.0))
+                .with_color(Color::rgb(0.6, 0.6, 0.6)),
+            ParallaxLayer::new(&ferris)
+                .with_position(vec2(0.0, 300.0))
+                .with_factor(Vec2::ONE),
+        ];
+
+        Ok(Self {
+            cam: Camera2D::new(window_size(), ScreenMode::Normal),
+            player_pos: Vec2::ZERO,
+            layers,
+        })
+    }
+}
+
+fn main() -> Result<(), String> {
+    rkit::init_with(|| State::new().unwrap())
+        .update(update)
+        .run()
+}
+
+fn update(s: &mut State) {
+    let speed = 200.0;
+    let dt = time::delta_f32();
+    if is_key_down(KeyCode::KeyA) {
+        s.player_pos.x -= speed * dt;
+    } else if is_key_down(KeyCode::KeyD) {
+        s.player_pos.x += speed * dt;
+    }
+
+    s.layers.iter_mut().for_each(|layer| layer.update(dt));
+
+    s.cam.set_size(window_size());
+    s.cam.set_position(s.player_pos);
+    s.cam.update();
+
+    let mut draw = create_draw_2d();
+    draw.clear(Color::rgb(0.1, 0.2, 0.3));
+    draw.set_camera(&s.cam);
+
+    draw.parallax_layers(&s.layers, &s.cam);
+
+    draw.circle(10.0)
+        .position(s.player_pos - 10.0)
+        .color(Color::ORANGE);
+
+    gfx::render_to_frame(&draw).unwrap();
+}
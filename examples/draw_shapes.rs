@@ -40,6 +40,11 @@ fn update() {
         .color(Color::ORANGE)
         .stroke(15.0);
 
+    draw.rect(vec2(620.0, 480.0), vec2(150.0, 80.0))
+        .corner_radius([30.0, 4.0, 30.0, 4.0])
+        .antialias(true)
+        .color(Color::SILVER);
+
     draw.star(10, 80.0, 40.0)
         .position(vec2(150.0, 480.0))
         .fill_color(Color::PINK)
@@ -0,0 +1,54 @@
+use rkit::draw::{create_draw_2d, Sprite};
+use rkit::gfx::{self, Color};
+use rkit::math::vec2;
+
+struct State {
+    sprite: Sprite,
+}
+
+impl State {
+    fn new() -> Result<Self, String> {
+        // shapes, text and images will share the same batch
+        draw::set_unified_batching_2d(true);
+
+        let sprite = draw::create_sprite()
+            .from_image(include_bytes!("assets/ferris.png"))
+            .build()?;
+
+        Ok(Self { sprite })
+    }
+}
+
+fn main() -> Result<(), String> {
+    rkit::init_with(|| State::new().unwrap())
+        .update(update)
+        .run()
+}
+
+fn update(s: &mut State) {
+    let mut draw = create_draw_2d();
+    draw.clear(Color::rgb(0.1, 0.2, 0.3));
+
+    for i in 0..5 {
+        let pos = vec2(50.0 + i as f32 * 140.0, 200.0);
+
+        draw.rect(pos, vec2(120.0, 120.0))
+            .corner_radius(10.0)
+            .color(Color::ORANGE);
+
+        draw.image(&s.sprite)
+            .position(pos + 10.0)
+            .size(vec2(100.0, 66.0));
+
+        draw.text(&format!("Item {i}"))
+            .position(pos + vec2(10.0, 90.0))
+            .color(Color::BLACK);
+    }
+
+    let batches = draw.stats().batches;
+    draw.text(&format!("Batches: {batches}"))
+        .position(vec2(10.0, 10.0))
+        .size(20.0);
+
+    gfx::render_to_frame(&draw).unwrap();
+}
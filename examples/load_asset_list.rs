@@ -2,6 +2,7 @@ use assets::{AssetList, AssetMap};
 use draw::{Font, Sprite};
 use rkit::app::window_size;
 use rkit::draw::create_draw_2d;
+use rkit::draw_assets::DrawAssetList;
 use rkit::gfx::{self, Color};
 use rkit::math::{vec2, Vec2};
 
@@ -28,19 +29,10 @@ fn setup() -> State {
         "./examples/assets/Ubuntu-B.ttf",
         "./examples/assets/data.txt",
     ])
-    .with_extension_parser("png", parse_sprite)
-    .with_extension_parser("ttf", parse_font);
+    .with_draw_parsers();
     State::Loading { list }
 }
 
-fn parse_sprite(id: &str, data: &[u8]) -> Result<Sprite, String> {
-    draw::create_sprite().from_image(data).build()
-}
-
-fn parse_font(id: &str, data: &[u8]) -> Result<Font, String> {
-    draw::create_font(data).build()
-}
-
 fn parse_assets(map: &AssetMap) -> Result<Assets, String> {
     Ok(Assets {
         tex1: map.get("./examples/assets/bunny.png")?,
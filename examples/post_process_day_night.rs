@@ -0,0 +1,66 @@
+use rkit::draw::{create_draw_2d, Sprite};
+use rkit::gfx::Color;
+use rkit::math::{vec2, Vec2};
+use rkit::postfx::{ColorGradeFx, ColorGradeParams, DayNightCycle, PostFx, PostProcess};
+use rkit::{draw, gfx, time};
+use shipyard::{Unique, UniqueView, UniqueViewMut, World};
+
+#[derive(Unique)]
+struct DayNight(DayNightCycle);
+
+#[derive(Unique)]
+struct Resources {
+    sprite: Sprite,
+    grade: ColorGradeFx,
+}
+
+fn main() -> Result<(), String> {
+    rkit::init_with(setup).update(update).run()
+}
+
+fn setup() -> World {
+    let world = World::new();
+
+    let sprite = draw::create_sprite()
+        .from_image(include_bytes!("./assets/ferris.png"))
+        .build()
+        .unwrap();
+
+    let grade = ColorGradeFx::new(ColorGradeParams::default()).unwrap();
+
+    world.add_unique(Resources { sprite, grade });
+
+    // a full day every 20 seconds
+    world.add_unique(DayNight(DayNightCycle::new(20.0)));
+
+    world
+}
+
+fn update_day_night(mut cycle: UniqueViewMut<DayNight>, mut res: UniqueViewMut<Resources>) {
+    cycle.0.tick(time::delta_f32());
+    cycle.0.apply(&mut res.grade);
+    res.grade.update().unwrap();
+}
+
+fn render(cycle: UniqueView<DayNight>, res: UniqueView<Resources>) {
+    let mut draw = create_draw_2d();
+    draw.clear(Color::rgb(0.4, 0.7, 0.4));
+
+    (0..5).for_each(|i| {
+        let pos = vec2(50.0 + i as f32 * 150.0, 250.0);
+        draw.image(&res.sprite)
+            .position(pos)
+            .size(Vec2::splat(100.0));
+    });
+
+    draw.text(&format!("Time of day: {:.2}", cycle.0.time()))
+        .size(12.0)
+        .position(vec2(10.0, 10.0));
+
+    gfx::render_to_frame(&PostProcess::new(&draw, &[&res.grade])).unwrap();
+}
+
+fn update(world: &mut World) {
+    world.run(update_day_night);
+    world.run(render);
+}
@@ -5,7 +5,7 @@ use rkit::draw::{create_draw_2d, Sprite};
 use rkit::gfx::{self, Color};
 use rkit::math::{vec2, Vec2};
 use rkit::postfx::{
-    BlurFx, ColorReplaceFx, GrayScaleFx, PixelateFx, PostFx, PostProcess, RgbSplitFx,
+    BlurFx, ColorReplaceFx, DitherFx, GrayScaleFx, PixelateFx, PostFx, PostProcess, RgbSplitFx,
 };
 use rkit::time;
 
@@ -15,6 +15,7 @@ struct MyFilters {
     gray_scale: GrayScaleFx,
     blur: BlurFx,
     rgb_split: RgbSplitFx,
+    dither: DitherFx,
 }
 
 impl MyFilters {
@@ -29,6 +30,8 @@ impl MyFilters {
         blur.enabled = false;
         let mut rgb_split = RgbSplitFx::new(Default::default())?;
         rgb_split.enabled = false;
+        let mut dither = DitherFx::new(Default::default())?;
+        dither.enabled = false;
 
         Ok(Self {
             pixelate,
@@ -36,6 +39,7 @@ impl MyFilters {
             gray_scale,
             blur,
             rgb_split,
+            dither,
         })
     }
 
@@ -70,17 +74,19 @@ impl MyFilters {
         self.gray_scale.update()?;
         self.blur.update()?;
         self.rgb_split.update()?;
+        self.dither.update()?;
 
         Ok(())
     }
 
-    fn filters(&self) -> [&dyn PostFx; 5] {
+    fn filters(&self) -> [&dyn PostFx; 6] {
         [
             &self.gray_scale,
             &self.color_replace,
             &self.pixelate,
             &self.blur,
             &self.rgb_split,
+            &self.dither,
         ]
     }
 }
@@ -151,6 +157,10 @@ fn draw_ui(s: &mut State) {
         .position(vec2(10.0, 90.0))
         .size(12.0);
 
+    draw.text(&format!("6: Dither: {:?}", s.filters.dither.enabled))
+        .position(vec2(10.0, 110.0))
+        .size(12.0);
+
     draw.text(&format!("MS: {:.4}", time::delta_f32()))
         .anchor(vec2(0.0, 1.0))
         .translate(vec2(10.0, window_height() - 10.0))
@@ -177,4 +187,8 @@ fn draw_ui(s: &mut State) {
     if is_key_pressed(KeyCode::Digit5) {
         s.filters.rgb_split.enabled = !s.filters.rgb_split.enabled;
     }
+
+    if is_key_pressed(KeyCode::Digit6) {
+        s.filters.dither.enabled = !s.filters.dither.enabled;
+    }
 }
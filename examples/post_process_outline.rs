@@ -0,0 +1,60 @@
+use rkit::draw::{create_draw_2d, Sprite};
+use rkit::gfx::{self, Color};
+use rkit::input::mouse_position;
+use rkit::math::{vec2, Rect, Vec2};
+use rkit::postfx::{OutlineFx, OutlineParams, PostFx, PostProcess};
+
+struct State {
+    sprite: Sprite,
+    positions: Vec<Vec2>,
+    outline: OutlineFx,
+}
+
+impl State {
+    fn new() -> Result<Self, String> {
+        let sprite = draw::create_sprite()
+            .from_image(include_bytes!("assets/ferris.png"))
+            .build()?;
+
+        let positions = vec![vec2(100.0, 150.0), vec2(300.0, 250.0), vec2(500.0, 150.0)];
+
+        let outline = OutlineFx::new(OutlineParams {
+            color: Color::ORANGE,
+            thickness: 4.0,
+        })?;
+
+        Ok(Self {
+            sprite,
+            positions,
+            outline,
+        })
+    }
+}
+
+fn main() -> Result<(), String> {
+    rkit::init_with(|| State::new().unwrap())
+        .update(update)
+        .run()
+}
+
+fn update(s: &mut State) {
+    let mut draw = create_draw_2d();
+    draw.clear(Color::rgb(0.1, 0.2, 0.3));
+
+    // the mask only contains the hovered sprites
+    let mut mask = create_draw_2d();
+    let mouse = mouse_position();
+    s.positions.iter().for_each(|pos| {
+        draw.image(&s.sprite).position(*pos);
+
+        let hovered = Rect::new(*pos, s.sprite.size()).contains(mouse);
+        if hovered {
+            mask.image(&s.sprite).position(*pos);
+        }
+    });
+
+    s.outline.update().unwrap();
+    s.outline.render_mask(&mask).unwrap();
+
+    gfx::render_to_frame(&PostProcess::new(&draw, &[&s.outline])).unwrap();
+}
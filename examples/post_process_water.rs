@@ -0,0 +1,45 @@
+use rkit::app::window_size;
+use rkit::draw::{create_draw_2d, Sprite};
+use rkit::gfx::{self, Color};
+use rkit::math::vec2;
+use rkit::postfx::{PostFx, PostProcess, WaterFx, WaterParams};
+use rkit::time;
+
+struct State {
+    sprite: Sprite,
+    water: WaterFx,
+}
+
+impl State {
+    fn new() -> Result<Self, String> {
+        let sprite = draw::create_sprite()
+            .from_image(include_bytes!("assets/ferris.png"))
+            .build()?;
+
+        let water = WaterFx::new(WaterParams::default())?;
+
+        Ok(Self { sprite, water })
+    }
+}
+
+fn main() -> Result<(), String> {
+    rkit::init_with(|| State::new().unwrap())
+        .update(update)
+        .run()
+}
+
+fn update(s: &mut State) {
+    let mut draw = create_draw_2d();
+    draw.clear(Color::rgb(0.6, 0.8, 0.9));
+
+    let water_line = window_size().y * 0.6;
+    let x = window_size().x * 0.5 + time::elapsed_f32().sin() * 200.0;
+    draw.image(&s.sprite)
+        .position(vec2(x, water_line) - s.sprite.size());
+
+    // keep the water line at the same position when the window is resized
+    s.water.params.water_line = water_line;
+    s.water.update().unwrap();
+
+    gfx::render_to_frame(&PostProcess::new(&draw, &[&s.water])).unwrap();
+}
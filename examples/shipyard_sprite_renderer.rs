@@ -0,0 +1,79 @@
+use rkit::draw::{create_draw_2d, SpriteRenderQueue, SpriteRenderer};
+use rkit::gfx::Color;
+use rkit::math::{bvec2, vec2, Vec2};
+use rkit::{draw, gfx, time};
+use shipyard::{Component, IntoIter, Unique, UniqueViewMut, View, ViewMut, World};
+
+#[derive(Component)]
+struct Pos(Vec2);
+
+#[derive(Component)]
+struct Renderer(SpriteRenderer);
+
+#[derive(Unique, Default)]
+struct RenderQueue(SpriteRenderQueue);
+
+fn main() -> Result<(), String> {
+    rkit::init_with(setup).update(update).run()
+}
+
+fn setup() -> World {
+    let mut world = World::new();
+
+    let ferris = draw::create_sprite()
+        .from_image(include_bytes!("./assets/ferris.png"))
+        .build()
+        .unwrap();
+
+    let bunny = draw::create_sprite()
+        .from_image(include_bytes!("./assets/bunny.png"))
+        .build()
+        .unwrap();
+
+    // the background is added last but drawn first because of its layer
+    (0..10).for_each(|i| {
+        let renderer = SpriteRenderer::new(&bunny)
+            .with_layer(1)
+            .with_order(i)
+            .with_flip(bvec2(i % 2 == 0, false));
+        world.add_entity((
+            Pos(vec2(100.0 + i as f32 * 60.0, 300.0)),
+            Renderer(renderer),
+        ));
+    });
+
+    let background = SpriteRenderer::new(&ferris)
+        .with_layer(0)
+        .with_color(Color::rgb(0.5, 0.5, 0.5));
+    world.add_entity((Pos(vec2(400.0, 300.0)), Renderer(background)));
+
+    world.add_unique(RenderQueue::default());
+
+    world
+}
+
+fn move_sprites(mut pos: ViewMut<Pos>, renderer: View<Renderer>) {
+    let elapsed = time::elapsed_f32();
+    (&mut pos, &renderer)
+        .iter()
+        .filter(|(_, Renderer(r))| r.layer == 1)
+        .for_each(|(Pos(pos), Renderer(r))| {
+            pos.y = 300.0 + (elapsed + r.order as f32).sin() * 50.0;
+        });
+}
+
+fn render_sprites(pos: View<Pos>, renderer: View<Renderer>, mut queue: UniqueViewMut<RenderQueue>) {
+    (&pos, &renderer)
+        .iter()
+        .for_each(|(Pos(pos), Renderer(r))| queue.0.push(r, *pos));
+
+    let mut draw = create_draw_2d();
+    draw.clear(Color::rgb(0.1, 0.2, 0.3));
+    queue.0.submit(&mut draw);
+    gfx::render_to_frame(&draw).unwrap();
+}
+
+fn update(world: &mut World) {
+    world.run(move_sprites);
+    world.run(render_sprites);
+}
@@ -0,0 +1,324 @@
+use rustc_hash::FxHashMap;
+
+/// Platform layer (Steam, consoles...) that receives the unlocks and progress
+pub trait AchievementBackend {
+    fn unlock(&mut self, id: &str) -> Result<(), String>;
+    fn set_progress(&mut self, id: &str, current: u32, goal: u32) -> Result<(), String>;
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Achievement {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    /// Progress needed to unlock it, 1 for simple achievements
+    pub goal: u32,
+}
+
+impl Achievement {
+    pub fn new(id: &str, name: &str) -> Self {
+        Self {
+            id: id.to_string(),
+            name: name.to_string(),
+            description: String::new(),
+            goal: 1,
+        }
+    }
+
+    pub fn with_description(mut self, description: &str) -> Self {
+        self.description = description.to_string();
+        self
+    }
+
+    pub fn with_goal(mut self, goal: u32) -> Self {
+        self.goal = goal.max(1);
+        self
+    }
+}
+
+struct Entry {
+    info: Achievement,
+    progress: u32,
+}
+
+impl Entry {
+    fn is_unlocked(&self) -> bool {
+        self.progress >= self.info.goal
+    }
+}
+
+/// Keeps track of the achievements locally and forwards the changes to the platform
+/// backends. The state can be persisted with `save`/`load`
+#[derive(Default)]
+pub struct Achievements {
+    entries: FxHashMap<String, Entry>,
+    backends: Vec<Box<dyn AchievementBackend>>,
+    unlocked: Vec<String>,
+    backend_errors: Vec<String>,
+    dirty: bool,
+}
+
+impl Achievements {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add_backend<B: AchievementBackend + 'static>(&mut self, backend: B) {
+        self.backends.push(Box::new(backend));
+    }
+
+    pub fn define(&mut self, info: Achievement) {
+        let progress = self.entries.get(&info.id).map_or(0, |e| e.progress);
+        self.entries
+            .insert(info.id.clone(), Entry { info, progress });
+    }
+
+    pub fn get(&self, id: &str) -> Option<&Achievement> {
+        self.entries.get(id).map(|e| &e.info)
+    }
+
+    pub fn is_unlocked(&self, id: &str) -> bool {
+        self.entries.get(id).is_some_and(Entry::is_unlocked)
+    }
+
+    /// Returns the current progress and the goal
+    pub fn progress_of(&self, id: &str) -> Option<(u32, u32)> {
+        self.entries.get(id).map(|e| (e.progress, e.info.goal))
+    }
+
+    /// Unlocks the achievement, returns `true` if it was locked before
+    pub fn unlock(&mut self, id: &str) -> Result<bool, String> {
+        let goal = self.entry(id)?.info.goal;
+        self.set_progress(id, goal)
+    }
+
+    /// Adds progress to the achievement, returns `true` if it gets unlocked
+    pub fn progress(&mut self, id: &str, amount: u32) -> Result<bool, String> {
+        let current = self.entry(id)?.progress;
+        self.set_progress(id, current.saturating_add(amount))
+    }
+
+    /// Sets the progress of the achievement, returns `true` if it gets unlocked.
+    /// The local state is always updated, backend failures are kept apart and
+    /// can be read with `take_backend_errors`
+    pub fn set_progress(&mut self, id: &str, value: u32) -> Result<bool, String> {
+        let entry = self.entry_mut(id)?;
+        if entry.is_unlocked() {
+            return Ok(false);
+        }
+
+        let value = value.min(entry.info.goal);
+        if value <= entry.progress {
+            return Ok(false);
+        }
+
+        entry.progress = value;
+        let goal = entry.info.goal;
+        let unlocked = entry.is_unlocked();
+        self.dirty = true;
+
+        if unlocked {
+            self.unlocked.push(id.to_string());
+        }
+
+        self.sync_backends(id, value, goal);
+        Ok(unlocked)
+    }
+
+    /// Returns the achievements unlocked since the last call, useful to show notifications
+    pub fn take_unlocked(&mut self) -> Vec<String> {
+        std::mem::take(&mut self.unlocked)
+    }
+
+    /// Returns the errors reported by the backends since the last call
+    pub fn take_backend_errors(&mut self) -> Vec<String> {
+        std::mem::take(&mut self.backend_errors)
+    }
+
+    /// Returns `true` if the state changed since the last `save`
+    pub fn is_dirty(&self) -> bool {
+        self.dirty
+    }
+
+    /// Serializes the progress to store it locally
+    pub fn save(&mut self) -> Vec<u8> {
+        self.dirty = false;
+
+        let mut entries = self
+            .entries
+            .iter()
+            .filter(|(_, e)| e.progress > 0)
+            .map(|(id, e)| format!("{id}={}", e.progress))
+            .collect::<Vec<_>>();
+        entries.sort();
+        entries.join("\n").into_bytes()
+    }
+
+    /// Restores the progress saved with `save`, unknown ids are ignored.
+    /// The progress is forwarded to the backends, failures go to `take_backend_errors`
+    pub fn load(&mut self, bytes: &[u8]) -> Result<(), String> {
+        let text =
+            std::str::from_utf8(bytes).map_err(|e| format!("Invalid achievements data: {e}"))?;
+
+        for line in text.lines().filter(|l| !l.trim().is_empty()) {
+            let (id, progress) = line
+                .split_once('=')
+                .ok_or_else(|| format!("Invalid achievements line '{line}'"))?;
+            let progress = progress
+                .trim()
+                .parse::<u32>()
+                .map_err(|e| format!("Invalid progress for '{id}': {e}"))?;
+
+            let id = id.trim();
+            if let Some(entry) = self.entries.get_mut(id) {
+                entry.progress = progress.min(entry.info.goal);
+                let (progress, goal) = (entry.progress, entry.info.goal);
+
+                // keep the platforms in sync in case the unlock happened offline
+                self.sync_backends(id, progress, goal);
+            }
+        }
+
+        self.dirty = false;
+        Ok(())
+    }
+
+    fn sync_backends(&mut self, id: &str, progress: u32, goal: u32) {
+        let unlocked = progress >= goal;
+        for backend in &mut self.backends {
+            let res = if unlocked {
+                backend.unlock(id)
+            } else {
+                backend.set_progress(id, progress, goal)
+            };
+
+            if let Err(e) = res {
+                self.backend_errors.push(e);
+            }
+        }
+    }
+
+    fn entry(&self, id: &str) -> Result<&Entry, String> {
+        self.entries
+            .get(id)
+            .ok_or_else(|| format!("Undefined achievement '{id}'"))
+    }
+
+    fn entry_mut(&mut self, id: &str) -> Result<&mut Entry, String> {
+        self.entries
+            .get_mut(id)
+            .ok_or_else(|| format!("Undefined achievement '{id}'"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    #[derive(Clone, Default)]
+    struct TestBackend {
+        unlocked: Rc<RefCell<Vec<String>>>,
+    }
+
+    impl AchievementBackend for TestBackend {
+        fn unlock(&mut self, id: &str) -> Result<(), String> {
+            self.unlocked.borrow_mut().push(id.to_string());
+            Ok(())
+        }
+
+        fn set_progress(&mut self, _id: &str, _current: u32, _goal: u32) -> Result<(), String> {
+            Ok(())
+        }
+    }
+
+    struct FailingBackend;
+
+    impl AchievementBackend for FailingBackend {
+        fn unlock(&mut self, id: &str) -> Result<(), String> {
+            Err(format!("Cannot unlock '{id}'"))
+        }
+
+        fn set_progress(&mut self, id: &str, _current: u32, _goal: u32) -> Result<(), String> {
+            Err(format!("Cannot set progress of '{id}'"))
+        }
+    }
+
+    fn achievements() -> Achievements {
+        let mut achievements = Achievements::new();
+        achievements.define(Achievement::new("first_blood", "First Blood"));
+        achievements.define(Achievement::new("collector", "Collector").with_goal(10));
+        achievements
+    }
+
+    #[test]
+    fn test_unlock() {
+        let backend = TestBackend::default();
+        let mut achievements = achievements();
+        achievements.add_backend(backend.clone());
+
+        assert!(achievements.unlock("first_blood").unwrap());
+        assert!(!achievements.unlock("first_blood").unwrap());
+        assert!(achievements.is_unlocked("first_blood"));
+        assert!(achievements.unlock("unknown").is_err());
+
+        assert_eq!(achievements.take_unlocked(), ["first_blood"]);
+        assert!(achievements.take_unlocked().is_empty());
+        assert_eq!(*backend.unlocked.borrow(), ["first_blood"]);
+    }
+
+    #[test]
+    fn test_progress() {
+        let mut achievements = achievements();
+        assert!(!achievements.progress("collector", 4).unwrap());
+        assert!(!achievements.progress("collector", 5).unwrap());
+        assert_eq!(achievements.progress_of("collector"), Some((9, 10)));
+
+        assert!(achievements.progress("collector", 5).unwrap());
+        assert_eq!(achievements.progress_of("collector"), Some((10, 10)));
+        assert!(achievements.is_unlocked("collector"));
+    }
+
+    #[test]
+    fn test_save_load() {
+        let mut achievements = achievements();
+        achievements.unlock("first_blood").unwrap();
+        achievements.progress("collector", 3).unwrap();
+        assert!(achievements.is_dirty());
+
+        let bytes = achievements.save();
+        assert!(!achievements.is_dirty());
+
+        let mut loaded = self::achievements();
+        loaded.load(&bytes).unwrap();
+        assert!(loaded.is_unlocked("first_blood"));
+        assert_eq!(loaded.progress_of("collector"), Some((3, 10)));
+
+        assert!(loaded.load(b"broken").is_err());
+    }
+
+    #[test]
+    fn test_backend_errors() {
+        let backend = TestBackend::default();
+        let mut achievements = achievements();
+        achievements.add_backend(FailingBackend);
+        achievements.add_backend(backend.clone());
+
+        assert!(achievements.unlock("first_blood").unwrap());
+        assert!(!achievements.unlock("first_blood").unwrap());
+        assert!(achievements.is_unlocked("first_blood"));
+        assert_eq!(achievements.take_unlocked(), ["first_blood"]);
+        assert_eq!(*backend.unlocked.borrow(), ["first_blood"]);
+
+        assert!(!achievements.progress("collector", 2).unwrap());
+        assert_eq!(
+            achievements.take_backend_errors(),
+            [
+                "Cannot unlock 'first_blood'",
+                "Cannot set progress of 'collector'"
+            ]
+        );
+        assert!(achievements.take_backend_errors().is_empty());
+    }
+}
@@ -0,0 +1,501 @@
+use super::UtilityScorer;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum BtStatus {
+    Success,
+    Failure,
+    Running,
+}
+
+type ActionFn<C> = dyn FnMut(&mut C, f32) -> BtStatus;
+type ConditionFn<C> = dyn Fn(&C) -> bool;
+
+enum BtKind<C> {
+    Action(Box<ActionFn<C>>),
+    Condition(Box<ConditionFn<C>>),
+    Sequence {
+        children: Vec<BtNode<C>>,
+        current: usize,
+    },
+    Selector {
+        children: Vec<BtNode<C>>,
+        current: usize,
+    },
+    Parallel {
+        children: Vec<BtNode<C>>,
+        done: Vec<bool>,
+    },
+    Utility {
+        scorer: UtilityScorer<C, BtNode<C>>,
+        current: Option<usize>,
+    },
+    Invert(Box<BtNode<C>>),
+    Repeat {
+        child: Box<BtNode<C>>,
+        times: Option<u32>,
+        count: u32,
+    },
+    Wait {
+        duration: f32,
+        elapsed: f32,
+    },
+}
+
+impl<C> BtKind<C> {
+    fn name(&self) -> &'static str {
+        match self {
+            BtKind::Action(_) => "Action",
+            BtKind::Condition(_) => "Condition",
+            BtKind::Sequence { .. } => "Sequence",
+            BtKind::Selector { .. } => "Selector",
+            BtKind::Parallel { .. } => "Parallel",
+            BtKind::Utility { .. } => "Utility",
+            BtKind::Invert(_) => "Invert",
+            BtKind::Repeat { .. } => "Repeat",
+            BtKind::Wait { .. } => "Wait",
+        }
+    }
+}
+
+/// Node of a `BehaviorTree`, `C` is the context passed to the actions
+/// (an entity, a world, a blackboard, etc...)
+pub struct BtNode<C> {
+    name: String,
+    kind: BtKind<C>,
+    status: Option<BtStatus>,
+    last_tick: u64,
+}
+
+impl<C> BtNode<C> {
+    fn new(name: &str, kind: BtKind<C>) -> Self {
+        Self {
+            name: name.to_string(),
+            kind,
+            status: None,
+            last_tick: 0,
+        }
+    }
+
+    /// Runs the closure each tick while it returns `Running`
+    pub fn action<F>(name: &str, action: F) -> Self
+    where
+        F: FnMut(&mut C, f32) -> BtStatus + 'static,
+    {
+        Self::new(name, BtKind::Action(Box::new(action)))
+    }
+
+    /// Succeeds if the closure returns true, fails otherwise
+    pub fn condition<F>(name: &str, condition: F) -> Self
+    where
+        F: Fn(&C) -> bool + 'static,
+    {
+        Self::new(name, BtKind::Condition(Box::new(condition)))
+    }
+
+    /// Runs the children in order until one fails
+    pub fn sequence(children: Vec<BtNode<C>>) -> Self {
+        Self::new(
+            "",
+            BtKind::Sequence {
+                children,
+                current: 0,
+            },
+        )
+    }
+
+    /// Runs the children in order until one succeeds
+    pub fn selector(children: Vec<BtNode<C>>) -> Self {
+        Self::new(
+            "",
+            BtKind::Selector {
+                children,
+                current: 0,
+            },
+        )
+    }
+
+    /// Runs all the children each tick, fails as soon as one fails
+    /// and succeeds once all of them succeed
+    pub fn parallel(children: Vec<BtNode<C>>) -> Self {
+        let done = vec![false; children.len()];
+        Self::new("", BtKind::Parallel { children, done })
+    }
+
+    /// Runs the child with the highest score until it ends, the scores are
+    /// evaluated again after that. Fails if no child reaches the threshold
+    pub fn utility(scorer: UtilityScorer<C, BtNode<C>>) -> Self {
+        Self::new(
+            "",
+            BtKind::Utility {
+                scorer,
+                current: None,
+            },
+        )
+    }
+
+    /// Swaps success and failure
+    pub fn invert(child: BtNode<C>) -> Self {
+        Self::new("", BtKind::Invert(Box::new(child)))
+    }
+
+    /// Runs the child again each time it succeeds, until it fails or it has
+    /// succeeded `times` (forever if `None`). One run per tick at most
+    pub fn repeat(times: Option<u32>, child: BtNode<C>) -> Self {
+        Self::new(
+            "",
+            BtKind::Repeat {
+                child: Box::new(child),
+                times,
+                count: 0,
+            },
+        )
+    }
+
+    /// Running until `seconds` have passed
+    pub fn wait(seconds: f32) -> Self {
+        Self::new(
+            "",
+            BtKind::Wait {
+                duration: seconds,
+                elapsed: 0.0,
+            },
+        )
+    }
+
+    /// Name displayed by the debug info
+    pub fn with_name(mut self, name: &str) -> Self {
+        self.name = name.to_string();
+        self
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn tick(&mut self, ctx: &mut C, delta: f32, tick: u64) -> BtStatus {
+        let status = match &mut self.kind {
+            BtKind::Action(action) => action(ctx, delta),
+            BtKind::Condition(condition) => {
+                if condition(ctx) {
+                    BtStatus::Success
+                } else {
+                    BtStatus::Failure
+                }
+            }
+            BtKind::Sequence { children, current } => {
+                tick_in_order(children, current, BtStatus::Success, ctx, delta, tick)
+            }
+            BtKind::Selector { children, current } => {
+                tick_in_order(children, current, BtStatus::Failure, ctx, delta, tick)
+            }
+            BtKind::Parallel { children, done } => {
+                let mut status = BtStatus::Success;
+                for (child, done) in children.iter_mut().zip(done.iter_mut()) {
+                    if *done {
+                        continue;
+                    }
+
+                    match child.tick(ctx, delta, tick) {
+                        BtStatus::Success => *done = true,
+                        BtStatus::Running => status = BtStatus::Running,
+                        BtStatus::Failure => {
+                            status = BtStatus::Failure;
+                            break;
+                        }
+                    }
+                }
+                status
+            }
+            BtKind::Utility { scorer, current } => {
+                let idx = current.or_else(|| scorer.best_index(ctx));
+                match idx.and_then(|idx| scorer.get_mut(idx).map(|child| (idx, child))) {
+                    Some((idx, child)) => {
+                        let status = child.tick(ctx, delta, tick);
+                        *current = (status == BtStatus::Running).then_some(idx);
+                        status
+                    }
+                    None => BtStatus::Failure,
+                }
+            }
+            BtKind::Invert(child) => match child.tick(ctx, delta, tick) {
+                BtStatus::Success => BtStatus::Failure,
+                BtStatus::Failure => BtStatus::Success,
+                BtStatus::Running => BtStatus::Running,
+            },
+            BtKind::Repeat {
+                child,
+                times,
+                count,
+            } => match child.tick(ctx, delta, tick) {
+                BtStatus::Success => {
+                    *count += 1;
+                    if times.is_some_and(|times| *count >= times) {
+                        BtStatus::Success
+                    } else {
+                        BtStatus::Running
+                    }
+                }
+                status => status,
+            },
+            BtKind::Wait { duration, elapsed } => {
+                *elapsed += delta;
+                if *elapsed >= *duration {
+                    BtStatus::Success
+                } else {
+                    BtStatus::Running
+                }
+            }
+        };
+
+        self.status = Some(status);
+        self.last_tick = tick;
+
+        // the next tick starts again, aborting the children still running
+        if status != BtStatus::Running {
+            self.reset_state();
+        }
+
+        status
+    }
+
+    fn reset_state(&mut self) {
+        match &mut self.kind {
+            BtKind::Action(_) | BtKind::Condition(_) => {}
+            BtKind::Sequence { children, current } | BtKind::Selector { children, current } => {
+                *current = 0;
+                children.iter_mut().for_each(|child| child.reset_state());
+            }
+            BtKind::Parallel { children, done } => {
+                done.iter_mut().for_each(|done| *done = false);
+                children.iter_mut().for_each(|child| child.reset_state());
+            }
+            BtKind::Utility { scorer, current } => {
+                if let Some(child) = current.take().and_then(|idx| scorer.get_mut(idx)) {
+                    child.reset_state();
+                }
+            }
+            BtKind::Invert(child) => child.reset_state(),
+            BtKind::Repeat { child, count, .. } => {
+                *count = 0;
+                child.reset_state();
+            }
+            BtKind::Wait { elapsed, .. } => *elapsed = 0.0,
+        }
+    }
+
+    fn children(&self) -> Box<dyn Iterator<Item = &BtNode<C>> + '_> {
+        match &self.kind {
+            BtKind::Action(_) | BtKind::Condition(_) | BtKind::Wait { .. } => {
+                Box::new(std::iter::empty())
+            }
+            BtKind::Sequence { children, .. }
+            | BtKind::Selector { children, .. }
+            | BtKind::Parallel { children, .. } => Box::new(children.iter()),
+            BtKind::Utility { scorer, .. } => Box::new(scorer.values()),
+            BtKind::Invert(child) | BtKind::Repeat { child, .. } => {
+                Box::new(std::iter::once(child.as_ref()))
+            }
+        }
+    }
+
+    fn collect_debug(&self, depth: usize, tick: u64, out: &mut Vec<BtDebugNode>) {
+        out.push(BtDebugNode {
+            depth,
+            kind: self.kind.name(),
+            name: self.name.clone(),
+            status: self.status.filter(|_| self.last_tick == tick),
+        });
+
+        self.children()
+            .for_each(|child| child.collect_debug(depth + 1, tick, out));
+    }
+
+    fn clear_status(&mut self) {
+        self.status = None;
+        self.last_tick = 0;
+        match &mut self.kind {
+            BtKind::Action(_) | BtKind::Condition(_) | BtKind::Wait { .. } => {}
+            BtKind::Sequence { children, .. }
+            | BtKind::Selector { children, .. }
+            | BtKind::Parallel { children, .. } => {
+                children.iter_mut().for_each(|child| child.clear_status())
+            }
+            BtKind::Utility { scorer, .. } => {
+                (0..scorer.len()).for_each(|idx| {
+                    if let Some(child) = scorer.get_mut(idx) {
+                        child.clear_status();
+                    }
+                });
+            }
+            BtKind::Invert(child) | BtKind::Repeat { child, .. } => child.clear_status(),
+        }
+    }
+}
+
+fn tick_in_order<C>(
+    children: &mut [BtNode<C>],
+    current: &mut usize,
+    continue_on: BtStatus,
+    ctx: &mut C,
+    delta: f32,
+    tick: u64,
+) -> BtStatus {
+    while let Some(child) = children.get_mut(*current) {
+        match child.tick(ctx, delta, tick) {
+            BtStatus::Running => return BtStatus::Running,
+            status if status == continue_on => *current += 1,
+            status => return status,
+        }
+    }
+
+    continue_on
+}
+
+/// Info of a node used to display the tree
+#[derive(Clone, Debug, PartialEq)]
+pub struct BtDebugNode {
+    pub depth: usize,
+    pub kind: &'static str,
+    pub name: String,
+    /// Status returned on the last tick, `None` if the node was not ticked
+    pub status: Option<BtStatus>,
+}
+
+/// Behavior tree, usually one per entity with the entity (or its data) as context.
+/// Running nodes are resumed on the next tick.
+pub struct BehaviorTree<C> {
+    root: BtNode<C>,
+    tick: u64,
+}
+
+impl<C> BehaviorTree<C> {
+    pub fn new(root: BtNode<C>) -> Self {
+        Self { root, tick: 0 }
+    }
+
+    pub fn tick(&mut self, ctx: &mut C, delta: f32) -> BtStatus {
+        self.tick += 1;
+        self.root.tick(ctx, delta, self.tick)
+    }
+
+    /// Status returned by the last tick
+    pub fn status(&self) -> Option<BtStatus> {
+        self.root.status
+    }
+
+    /// Aborts the running nodes, the next tick starts from the root
+    pub fn reset(&mut self) {
+        self.root.reset_state();
+        self.root.clear_status();
+    }
+
+    /// Nodes in depth-first order with the status of the last tick
+    pub fn debug_nodes(&self) -> Vec<BtDebugNode> {
+        let mut nodes = vec![];
+        self.root.collect_debug(0, self.tick, &mut nodes);
+        nodes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Default)]
+    struct Guard {
+        enemy_near: bool,
+        ammo: u32,
+        log: Vec<&'static str>,
+    }
+
+    fn tree() -> BehaviorTree<Guard> {
+        BehaviorTree::new(BtNode::selector(vec![
+            BtNode::sequence(vec![
+                BtNode::condition("enemy near", |g: &Guard| g.enemy_near),
+                BtNode::condition("has ammo", |g: &Guard| g.ammo > 0),
+                BtNode::action("shoot", |g: &mut Guard, _| {
+                    g.ammo -= 1;
+                    g.log.push("shoot");
+                    BtStatus::Success
+                }),
+            ])
+            .with_name("attack"),
+            BtNode::sequence(vec![
+                BtNode::action("walk", |g: &mut Guard, _| {
+                    g.log.push("walk");
+                    BtStatus::Success
+                }),
+                BtNode::wait(1.0),
+            ])
+            .with_name("patrol"),
+        ]))
+    }
+
+    #[test]
+    fn test_selector_and_sequence() {
+        let mut tree = tree();
+        let mut guard = Guard::default();
+        assert_eq!(tree.tick(&mut guard, 0.5), BtStatus::Running);
+        assert_eq!(tree.tick(&mut guard, 0.5), BtStatus::Success);
+        assert_eq!(guard.log, vec!["walk"]);
+
+        guard.enemy_near = true;
+        guard.ammo = 1;
+        assert_eq!(tree.tick(&mut guard, 0.5), BtStatus::Success);
+        assert_eq!(tree.tick(&mut guard, 0.5), BtStatus::Running);
+        assert_eq!(guard.log, vec!["walk", "shoot", "walk"]);
+        assert_eq!(guard.ammo, 0);
+    }
+
+    #[test]
+    fn test_repeat_and_invert() {
+        let mut tree = BehaviorTree::new(BtNode::repeat(
+            Some(3),
+            BtNode::invert(BtNode::condition("empty", |g: &Guard| g.ammo > 0)),
+        ));
+        let mut guard = Guard::default();
+        assert_eq!(tree.tick(&mut guard, 0.0), BtStatus::Running);
+        assert_eq!(tree.tick(&mut guard, 0.0), BtStatus::Running);
+        assert_eq!(tree.tick(&mut guard, 0.0), BtStatus::Success);
+
+        guard.ammo = 1;
+        assert_eq!(tree.tick(&mut guard, 0.0), BtStatus::Failure);
+    }
+
+    #[test]
+    fn test_utility_and_parallel() {
+        let scorer = UtilityScorer::new()
+            .with_option(
+                BtNode::action("reload", |g: &mut Guard, _| {
+                    g.ammo = 2;
+                    BtStatus::Success
+                }),
+                |g: &Guard| if g.ammo == 0 { 1.0 } else { 0.0 },
+            )
+            .with_option(
+                BtNode::parallel(vec![
+                    BtNode::action("aim", |g: &mut Guard, _| {
+                        g.log.push("aim");
+                        BtStatus::Success
+                    }),
+                    BtNode::wait(1.0),
+                ]),
+                |g: &Guard| g.ammo as f32 * 0.5,
+            )
+            .with_threshold(0.1);
+        let mut tree = BehaviorTree::new(BtNode::utility(scorer));
+        let mut guard = Guard::default();
+
+        assert_eq!(tree.tick(&mut guard, 0.5), BtStatus::Success);
+        assert_eq!(guard.ammo, 2);
+        assert_eq!(tree.tick(&mut guard, 0.5), BtStatus::Running);
+        assert_eq!(tree.tick(&mut guard, 0.5), BtStatus::Success);
+        assert_eq!(guard.log, vec!["aim"]);
+
+        let nodes = tree.debug_nodes();
+        assert_eq!(nodes.len(), 5);
+        assert_eq!(nodes[0].kind, "Utility");
+        assert_eq!(nodes[1].status, None);
+        assert_eq!(nodes[4].status, Some(BtStatus::Success));
+    }
+}
@@ -0,0 +1,31 @@
+use super::{BehaviorTree, BtStatus};
+use crate::gfx::Color;
+use crate::math::{vec2, Vec2};
+use draw::Draw2D;
+
+const LINE_HEIGHT: f32 = 14.0;
+const INDENT: f32 = 12.0;
+
+/// Draws the nodes of the tree as an indented list, colored by the status of the last tick
+pub fn draw_behavior_tree<C>(draw: &mut Draw2D, tree: &BehaviorTree<C>, pos: Vec2) {
+    tree.debug_nodes().iter().enumerate().for_each(|(i, node)| {
+        let color = match node.status {
+            Some(BtStatus::Success) => Color::GREEN,
+            Some(BtStatus::Failure) => Color::RED,
+            Some(BtStatus::Running) => Color::YELLOW,
+            None => Color::GRAY,
+        };
+
+        let text = if node.name.is_empty() {
+            node.kind.to_string()
+        } else {
+            format!("{}: {}", node.kind, node.name)
+        };
+
+        let offset = vec2(node.depth as f32 * INDENT, i as f32 * LINE_HEIGHT);
+        draw.text(&text)
+            .translate(pos + offset)
+            .size(10.0)
+            .color(color);
+    });
+}
@@ -0,0 +1,9 @@
+mod behavior;
+#[cfg(feature = "draw")]
+mod debug;
+mod utility;
+
+pub use behavior::*;
+#[cfg(feature = "draw")]
+pub use debug::*;
+pub use utility::*;
@@ -0,0 +1,133 @@
+type ScoreFn<C> = dyn Fn(&C) -> f32;
+
+struct UtilityOption<C, T> {
+    value: T,
+    score: Box<ScoreFn<C>>,
+}
+
+/// Picks the option with the highest score for the context, the options can be
+/// anything (names, enums, actions or `BtNode` with `BtNode::utility`)
+pub struct UtilityScorer<C, T> {
+    options: Vec<UtilityOption<C, T>>,
+    threshold: f32,
+}
+
+impl<C, T> Default for UtilityScorer<C, T> {
+    fn default() -> Self {
+        Self {
+            options: vec![],
+            threshold: 0.0,
+        }
+    }
+}
+
+impl<C, T> UtilityScorer<C, T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_option<F>(mut self, value: T, score: F) -> Self
+    where
+        F: Fn(&C) -> f32 + 'static,
+    {
+        self.options.push(UtilityOption {
+            value,
+            score: Box::new(score),
+        });
+        self
+    }
+
+    /// Options with a score lower than this are never picked (defaults to 0.0)
+    pub fn with_threshold(mut self, threshold: f32) -> Self {
+        self.threshold = threshold;
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.options.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.options.is_empty()
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &T> {
+        self.options.iter().map(|opt| &opt.value)
+    }
+
+    pub fn get(&self, idx: usize) -> Option<&T> {
+        self.options.get(idx).map(|opt| &opt.value)
+    }
+
+    pub fn get_mut(&mut self, idx: usize) -> Option<&mut T> {
+        self.options.get_mut(idx).map(|opt| &mut opt.value)
+    }
+
+    /// Scores of every option in order, useful to debug the decisions
+    pub fn scores<'a>(&'a self, ctx: &'a C) -> impl Iterator<Item = (&'a T, f32)> + 'a {
+        self.options
+            .iter()
+            .map(|opt| (&opt.value, (opt.score)(ctx)))
+    }
+
+    /// Index of the option with the highest score, the first one wins on ties
+    pub fn best_index(&self, ctx: &C) -> Option<usize> {
+        self.options
+            .iter()
+            .enumerate()
+            .map(|(idx, opt)| (idx, (opt.score)(ctx)))
+            .filter(|(_, score)| *score >= self.threshold)
+            .fold(
+                None,
+                |best: Option<(usize, f32)>, (idx, score)| match best {
+                    Some((_, best_score)) if best_score >= score => best,
+                    _ => Some((idx, score)),
+                },
+            )
+            .map(|(idx, _)| idx)
+    }
+
+    pub fn best(&self, ctx: &C) -> Option<&T> {
+        self.best_index(ctx).and_then(|idx| self.get(idx))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Npc {
+        health: f32,
+        enemies: u32,
+    }
+
+    #[test]
+    fn test_best() {
+        let scorer = UtilityScorer::new()
+            .with_option("heal", |npc: &Npc| 1.0 - npc.health)
+            .with_option("attack", |npc: &Npc| npc.enemies.min(1) as f32 * npc.health)
+            .with_option("idle", |_: &Npc| 0.1)
+            .with_threshold(0.1);
+
+        let npc = Npc {
+            health: 0.2,
+            enemies: 1,
+        };
+        assert_eq!(scorer.best(&npc), Some(&"heal"));
+
+        let npc = Npc {
+            health: 0.9,
+            enemies: 1,
+        };
+        assert_eq!(scorer.best(&npc), Some(&"attack"));
+
+        let npc = Npc {
+            health: 0.95,
+            enemies: 0,
+        };
+        assert_eq!(scorer.best(&npc), Some(&"idle"));
+
+        let scorer = scorer.with_threshold(0.5);
+        assert_eq!(scorer.best(&npc), None);
+    }
+}
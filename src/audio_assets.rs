@@ -0,0 +1,19 @@
+use assets::AssetList;
+use audio::parse_sound;
+
+/// Extensions parsed as `audio::Sound` by `with_audio_parsers`
+pub const AUDIO_EXTENSIONS: [&str; 3] = ["ogg", "wav", "mp3"];
+
+pub trait AudioAssetList {
+    /// Parses the audio files in the list as `audio::Sound`.
+    /// The `mp3` files need the `audio-mp3` feature
+    fn with_audio_parsers(self) -> Self;
+}
+
+impl AudioAssetList for AssetList {
+    fn with_audio_parsers(self) -> Self {
+        AUDIO_EXTENSIONS.iter().fold(self, |list, ext| {
+            list.with_extension_parser(ext, parse_sound)
+        })
+    }
+}
@@ -0,0 +1,147 @@
+use super::{CaptureFormat, CaptureFrame};
+
+pub(super) fn gif<'a>(
+    mut frames: impl Iterator<Item = &'a CaptureFrame>,
+    fps: f32,
+) -> Result<Vec<u8>, String> {
+    let first = frames
+        .next()
+        .ok_or_else(|| "No frames to encode".to_string())?;
+    let (width, height) = frame_size(first)?;
+    let width = u16::try_from(width).map_err(|_| "Frame too big for a GIF".to_string())?;
+    let height = u16::try_from(height).map_err(|_| "Frame too big for a GIF".to_string())?;
+
+    // gif delays are in hundredths of a second
+    let delay = (100.0 / fps).round().max(1.0) as u16;
+
+    let mut out = vec![];
+    {
+        let mut encoder = gif::Encoder::new(&mut out, width, height, &[])
+            .map_err(|e| format!("Cannot create GIF encoder: {e}"))?;
+        encoder
+            .set_repeat(gif::Repeat::Infinite)
+            .map_err(|e| format!("Cannot create GIF encoder: {e}"))?;
+
+        for frame in std::iter::once(first).chain(frames) {
+            check_size(frame, width as u32, height as u32)?;
+            let mut pixels = frame.pixels.clone();
+            let mut gif_frame = gif::Frame::from_rgba_speed(width, height, &mut pixels, 10);
+            gif_frame.delay = delay;
+            encoder
+                .write_frame(&gif_frame)
+                .map_err(|e| format!("Cannot encode GIF frame: {e}"))?;
+        }
+    }
+
+    Ok(out)
+}
+
+/// Pipes the raw frames to an `ffmpeg` process
+#[cfg(all(feature = "capture-ffmpeg", not(target_arch = "wasm32")))]
+pub(super) fn ffmpeg<'a>(
+    mut frames: impl Iterator<Item = &'a CaptureFrame>,
+    fps: f32,
+    format: CaptureFormat,
+) -> Result<Vec<u8>, String> {
+    use std::io::Write;
+    use std::process::{Command, Stdio};
+
+    let first = frames
+        .next()
+        .ok_or_else(|| "No frames to encode".to_string())?;
+    let (width, height) = frame_size(first)?;
+
+    let codec: &[&str] = match format {
+        // h264 needs even sizes
+        CaptureFormat::Mp4 => &[
+            "-c:v",
+            "libx264",
+            "-pix_fmt",
+            "yuv420p",
+            "-vf",
+            "pad=ceil(iw/2)*2:ceil(ih/2)*2",
+        ],
+        CaptureFormat::WebP => &["-c:v", "libwebp", "-loop", "0", "-quality", "80"],
+        CaptureFormat::Gif => &[],
+    };
+
+    let output = std::env::temp_dir().join(format!(
+        "rkit_capture_{}.{}",
+        std::process::id(),
+        format.extension()
+    ));
+
+    let mut child = Command::new("ffmpeg")
+        .args([
+            "-y",
+            "-loglevel",
+            "error",
+            "-f",
+            "rawvideo",
+            "-pix_fmt",
+            "rgba",
+        ])
+        .args(["-s", &format!("{width}x{height}")])
+        .args(["-r", &fps.to_string()])
+        .args(["-i", "-"])
+        .args(codec)
+        .arg(&output)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Cannot run ffmpeg: {e}"))?;
+
+    {
+        let mut stdin = child
+            .stdin
+            .take()
+            .ok_or_else(|| "Cannot write to ffmpeg".to_string())?;
+        for frame in std::iter::once(first).chain(frames) {
+            check_size(frame, width, height)?;
+            stdin
+                .write_all(&frame.pixels)
+                .map_err(|e| format!("Cannot write to ffmpeg: {e}"))?;
+        }
+    }
+
+    let res = child
+        .wait_with_output()
+        .map_err(|e| format!("Cannot run ffmpeg: {e}"))?;
+    if !res.status.success() {
+        return Err(format!(
+            "ffmpeg failed: {}",
+            String::from_utf8_lossy(&res.stderr)
+        ));
+    }
+
+    let data = std::fs::read(&output).map_err(|e| format!("Cannot read ffmpeg output: {e}"))?;
+    let _ = std::fs::remove_file(&output);
+    Ok(data)
+}
+
+#[cfg(not(all(feature = "capture-ffmpeg", not(target_arch = "wasm32"))))]
+pub(super) fn ffmpeg<'a>(
+    _frames: impl Iterator<Item = &'a CaptureFrame>,
+    _fps: f32,
+    format: CaptureFormat,
+) -> Result<Vec<u8>, String> {
+    Err(format!(
+        "Encoding captures as '{}' needs the 'capture-ffmpeg' feature on native",
+        format.extension()
+    ))
+}
+
+fn frame_size(frame: &CaptureFrame) -> Result<(u32, u32), String> {
+    check_size(frame, frame.width, frame.height)?;
+    Ok((frame.width, frame.height))
+}
+
+fn check_size(frame: &CaptureFrame, width: u32, height: u32) -> Result<(), String> {
+    let len = width as usize * height as usize * 4;
+    if frame.width != width || frame.height != height || frame.pixels.len() != len {
+        return Err("All the captured frames must have the same size".to_string());
+    }
+
+    Ok(())
+}
@@ -0,0 +1,219 @@
+mod encode;
+#[cfg(target_arch = "wasm32")]
+mod web;
+
+#[cfg(target_arch = "wasm32")]
+pub use web::CanvasRecorder;
+
+use std::collections::VecDeque;
+
+/// Format used to encode the captured frames
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum CaptureFormat {
+    #[default]
+    Gif,
+    /// Animated WebP, needs the `capture-ffmpeg` feature and `ffmpeg` installed
+    WebP,
+    /// H264 video, needs the `capture-ffmpeg` feature and `ffmpeg` installed
+    Mp4,
+}
+
+impl CaptureFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            CaptureFormat::Gif => "gif",
+            CaptureFormat::WebP => "webp",
+            CaptureFormat::Mp4 => "mp4",
+        }
+    }
+}
+
+/// RGBA pixels of a captured frame
+#[derive(Clone, Debug)]
+pub struct CaptureFrame {
+    pub width: u32,
+    pub height: u32,
+    pub pixels: Vec<u8>,
+}
+
+/// Grabs frames from a render texture at a fixed rate to encode them as GIF, WebP or MP4.
+/// Render the game to a `RenderTexture` and call `capture` each frame after drawing it.
+/// With a ring buffer it keeps only the last seconds, useful for "save the last 30 seconds".
+/// Web: use `CanvasRecorder` instead, it records the canvas with a MediaRecorder.
+pub struct VideoCapture {
+    fps: f32,
+    max_frames: Option<usize>,
+    frames: VecDeque<CaptureFrame>,
+    recording: bool,
+    elapsed: f32,
+}
+
+impl VideoCapture {
+    /// Captures `fps` frames per second, it doesn't need to match the game's framerate
+    pub fn new(fps: f32) -> Self {
+        debug_assert!(fps > 0.0, "Capture fps must be greater than 0");
+        Self {
+            fps,
+            max_frames: None,
+            frames: VecDeque::new(),
+            recording: false,
+            elapsed: 0.0,
+        }
+    }
+
+    /// Keeps only the frames of the last `seconds` while recording
+    pub fn with_ring_buffer(mut self, seconds: f32) -> Self {
+        self.max_frames = Some(((seconds * self.fps).ceil() as usize).max(1));
+        self
+    }
+
+    pub fn fps(&self) -> f32 {
+        self.fps
+    }
+
+    /// Starts recording, removing the frames captured before
+    pub fn start(&mut self) {
+        self.frames.clear();
+        self.recording = true;
+        // the first frame is captured right away
+        self.elapsed = self.frame_time();
+    }
+
+    /// Stops recording and returns the frames captured
+    pub fn stop(&mut self) -> Vec<CaptureFrame> {
+        self.recording = false;
+        self.frames.drain(..).collect()
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.recording
+    }
+
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    /// Seconds of video captured
+    pub fn duration(&self) -> f32 {
+        self.frames.len() as f32 / self.fps
+    }
+
+    /// Reads the texture if it's time to capture a new frame, it waits for the GPU,
+    /// so keep the fps low or the texture small to avoid stalls
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn capture(&mut self, texture: &crate::gfx::RenderTexture) -> Result<(), String> {
+        if !self.tick(crate::time::delta_f32()) {
+            return Ok(());
+        }
+
+        let pixels = crate::gfx::read_pixels(texture)?;
+        self.push_frame(CaptureFrame {
+            width: texture.width() as u32,
+            height: texture.height() as u32,
+            pixels,
+        });
+        Ok(())
+    }
+
+    /// Adds a frame captured by other means, the ring buffer limit applies
+    pub fn push_frame(&mut self, frame: CaptureFrame) {
+        self.frames.push_back(frame);
+        if let Some(max) = self.max_frames {
+            while self.frames.len() > max {
+                self.frames.pop_front();
+            }
+        }
+    }
+
+    /// Encodes the frames captured so far without stopping the recording
+    pub fn encode(&self, format: CaptureFormat) -> Result<Vec<u8>, String> {
+        encode_frames(self.frames.iter(), self.fps, format)
+    }
+
+    /// Encodes the frames captured so far and writes them to `path`
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn save(&self, path: &str, format: CaptureFormat) -> Result<(), String> {
+        let data = self.encode(format)?;
+        std::fs::write(path, data).map_err(|e| format!("Cannot write capture '{path}': {e}"))
+    }
+
+    // returns true if a new frame must be captured
+    fn tick(&mut self, dt: f32) -> bool {
+        if !self.recording {
+            return false;
+        }
+
+        let frame_time = self.frame_time();
+        self.elapsed += dt;
+        if self.elapsed < frame_time {
+            return false;
+        }
+
+        // slow frames don't capture a burst of frames to catch up
+        self.elapsed = (self.elapsed - frame_time).min(frame_time);
+        true
+    }
+
+    fn frame_time(&self) -> f32 {
+        1.0 / self.fps
+    }
+}
+
+/// Encodes the frames, all of them must have the same size
+pub fn encode_frames<'a>(
+    frames: impl Iterator<Item = &'a CaptureFrame>,
+    fps: f32,
+    format: CaptureFormat,
+) -> Result<Vec<u8>, String> {
+    match format {
+        CaptureFormat::Gif => encode::gif(frames, fps),
+        CaptureFormat::WebP | CaptureFormat::Mp4 => encode::ffmpeg(frames, fps, format),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(n: u8) -> CaptureFrame {
+        CaptureFrame {
+            width: 1,
+            height: 1,
+            pixels: vec![n; 4],
+        }
+    }
+
+    #[test]
+    fn test_tick_fixed_rate() {
+        let mut capture = VideoCapture::new(10.0);
+        assert!(!capture.tick(1.0));
+
+        capture.start();
+        assert!(capture.tick(0.0));
+        assert!(!capture.tick(0.05));
+        assert!(capture.tick(0.05));
+
+        // a long frame only captures one frame
+        assert!(capture.tick(1.0));
+        assert!(capture.tick(0.0));
+        assert!(!capture.tick(0.0));
+    }
+
+    #[test]
+    fn test_ring_buffer() {
+        let mut capture = VideoCapture::new(2.0).with_ring_buffer(1.5);
+        capture.start();
+        (0..5).for_each(|n| capture.push_frame(frame(n)));
+        assert_eq!(capture.len(), 3);
+        assert_eq!(capture.duration(), 1.5);
+
+        let frames = capture.stop();
+        assert_eq!(frames[0].pixels, vec![2; 4]);
+        assert!(capture.is_empty());
+        assert!(!capture.is_recording());
+    }
+}
@@ -0,0 +1,97 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use wasm_bindgen::{closure::Closure, JsCast, JsValue};
+use web_sys::{Blob, BlobEvent, HtmlAnchorElement, HtmlCanvasElement, MediaRecorder, Url};
+
+/// Records the game's canvas using the browser's MediaRecorder (usually as WebM)
+pub struct CanvasRecorder {
+    recorder: MediaRecorder,
+    download: Rc<RefCell<Option<String>>>,
+    _on_data: Closure<dyn FnMut(BlobEvent)>,
+    _on_stop: Closure<dyn FnMut()>,
+}
+
+impl CanvasRecorder {
+    /// Starts recording the first canvas of the document at `fps`
+    pub fn start(fps: f32) -> Result<Self, String> {
+        let document = web_sys::window()
+            .and_then(|w| w.document())
+            .ok_or_else(|| "Cannot access the document".to_string())?;
+        let canvas = document
+            .query_selector("canvas")
+            .map_err(js_err)?
+            .ok_or_else(|| "Cannot find the canvas".to_string())?
+            .dyn_into::<HtmlCanvasElement>()
+            .map_err(|_| "Invalid canvas element".to_string())?;
+
+        let stream = canvas
+            .capture_stream_with_frame_request_rate(fps as f64)
+            .map_err(js_err)?;
+        let recorder = MediaRecorder::new_with_media_stream(&stream).map_err(js_err)?;
+
+        let chunks = Rc::new(RefCell::new(js_sys::Array::new()));
+        let on_data = {
+            let chunks = chunks.clone();
+            Closure::wrap(Box::new(move |evt: BlobEvent| {
+                if let Some(data) = evt.data() {
+                    chunks.borrow().push(&data);
+                }
+            }) as Box<dyn FnMut(BlobEvent)>)
+        };
+
+        // the last chunk arrives after stop, so the file is saved on the stop event
+        let download = Rc::new(RefCell::new(None::<String>));
+        let on_stop = {
+            let download = download.clone();
+            Closure::wrap(Box::new(move || {
+                let chunks = chunks.replace(js_sys::Array::new());
+                if let Some(name) = download.borrow_mut().take() {
+                    if let Err(e) = save_blob(&chunks, &name) {
+                        log::error!("Cannot save the canvas recording: {e}");
+                    }
+                }
+            }) as Box<dyn FnMut()>)
+        };
+
+        recorder.set_ondataavailable(Some(on_data.as_ref().unchecked_ref()));
+        recorder.set_onstop(Some(on_stop.as_ref().unchecked_ref()));
+        recorder.start().map_err(js_err)?;
+
+        Ok(Self {
+            recorder,
+            download,
+            _on_data: on_data,
+            _on_stop: on_stop,
+        })
+    }
+
+    /// Stops the recording and downloads it as `filename`
+    pub fn stop_and_download(self, filename: &str) -> Result<(), String> {
+        *self.download.borrow_mut() = Some(filename.to_string());
+        self.recorder.stop().map_err(js_err)?;
+
+        // the callbacks must live until the stop event is dispatched
+        std::mem::forget(self);
+        Ok(())
+    }
+}
+
+fn save_blob(chunks: &js_sys::Array, name: &str) -> Result<(), String> {
+    let blob = Blob::new_with_blob_sequence(chunks).map_err(js_err)?;
+    let url = Url::create_object_url_with_blob(&blob).map_err(js_err)?;
+    let anchor = web_sys::window()
+        .and_then(|w| w.document())
+        .ok_or_else(|| "Cannot access the document".to_string())?
+        .create_element("a")
+        .map_err(js_err)?
+        .dyn_into::<HtmlAnchorElement>()
+        .map_err(|_| "Cannot create the download link".to_string())?;
+    anchor.set_href(&url);
+    anchor.set_download(name);
+    anchor.click();
+    Url::revoke_object_url(&url).map_err(js_err)
+}
+
+fn js_err(e: JsValue) -> String {
+    format!("{e:?}")
+}
@@ -0,0 +1,287 @@
+use super::DialogueValue;
+use rustc_hash::FxHashMap;
+
+#[derive(Clone, Debug, PartialEq)]
+pub(super) enum Expr {
+    Value(DialogueValue),
+    Var(String),
+    Not(Box<Expr>),
+    Neg(Box<Expr>),
+    Binary(Op, Box<Expr>, Box<Expr>),
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub(super) enum Op {
+    Or,
+    And,
+    Eq,
+    NotEq,
+    Lt,
+    LtEq,
+    Gt,
+    GtEq,
+    Add,
+    Sub,
+    Mul,
+    Div,
+}
+
+impl Op {
+    fn precedence(&self) -> u8 {
+        match self {
+            Op::Or => 1,
+            Op::And => 2,
+            Op::Eq | Op::NotEq => 3,
+            Op::Lt | Op::LtEq | Op::Gt | Op::GtEq => 4,
+            Op::Add | Op::Sub => 5,
+            Op::Mul | Op::Div => 6,
+        }
+    }
+}
+
+impl Expr {
+    pub fn eval(&self, vars: &FxHashMap<String, DialogueValue>) -> Result<DialogueValue, String> {
+        Ok(match self {
+            Expr::Value(value) => value.clone(),
+            // unset variables are false, 0 or empty depending on how they are used
+            Expr::Var(name) => vars
+                .get(name)
+                .cloned()
+                .unwrap_or(DialogueValue::Number(0.0)),
+            Expr::Not(expr) => DialogueValue::Bool(!expr.eval(vars)?.is_truthy()),
+            Expr::Neg(expr) => DialogueValue::Number(-expr.eval(vars)?.as_number()?),
+            Expr::Binary(op, lhs, rhs) => {
+                let lhs = lhs.eval(vars)?;
+                // short-circuit
+                match op {
+                    Op::Or if lhs.is_truthy() => return Ok(DialogueValue::Bool(true)),
+                    Op::And if !lhs.is_truthy() => return Ok(DialogueValue::Bool(false)),
+                    _ => {}
+                }
+
+                let rhs = rhs.eval(vars)?;
+                binary(*op, lhs, rhs)?
+            }
+        })
+    }
+}
+
+fn binary(op: Op, lhs: DialogueValue, rhs: DialogueValue) -> Result<DialogueValue, String> {
+    use DialogueValue::*;
+    Ok(match op {
+        Op::Or | Op::And => Bool(rhs.is_truthy()),
+        Op::Eq => Bool(lhs == rhs),
+        Op::NotEq => Bool(lhs != rhs),
+        Op::Lt => Bool(lhs.as_number()? < rhs.as_number()?),
+        Op::LtEq => Bool(lhs.as_number()? <= rhs.as_number()?),
+        Op::Gt => Bool(lhs.as_number()? > rhs.as_number()?),
+        Op::GtEq => Bool(lhs.as_number()? >= rhs.as_number()?),
+        Op::Add => match (lhs, rhs) {
+            (Text(a), b) => Text(format!("{a}{b}")),
+            (a, Text(b)) => Text(format!("{a}{b}")),
+            (a, b) => Number(a.as_number()? + b.as_number()?),
+        },
+        Op::Sub => Number(lhs.as_number()? - rhs.as_number()?),
+        Op::Mul => Number(lhs.as_number()? * rhs.as_number()?),
+        Op::Div => Number(lhs.as_number()? / rhs.as_number()?),
+    })
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Value(DialogueValue),
+    Var(String),
+    Op(Op),
+    Not,
+    Minus,
+    Open,
+    Close,
+}
+
+fn tokenize(src: &str) -> Result<Vec<Token>, String> {
+    let chars = src.chars().collect::<Vec<_>>();
+    let mut tokens = vec![];
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+        i += 1;
+        let token = match (c, next) {
+            (c, _) if c.is_whitespace() => continue,
+            ('(', _) => Token::Open,
+            (')', _) => Token::Close,
+            ('+', _) => Token::Op(Op::Add),
+            ('-', _) => Token::Minus,
+            ('*', _) => Token::Op(Op::Mul),
+            ('/', _) => Token::Op(Op::Div),
+            ('=', _) => {
+                // both '=' and '==' compare
+                i += (next == Some('=')) as usize;
+                Token::Op(Op::Eq)
+            }
+            ('!', Some('=')) => {
+                i += 1;
+                Token::Op(Op::NotEq)
+            }
+            ('!', _) => Token::Not,
+            ('<', Some('=')) => {
+                i += 1;
+                Token::Op(Op::LtEq)
+            }
+            ('<', _) => Token::Op(Op::Lt),
+            ('>', Some('=')) => {
+                i += 1;
+                Token::Op(Op::GtEq)
+            }
+            ('>', _) => Token::Op(Op::Gt),
+            ('&', Some('&')) => {
+                i += 1;
+                Token::Op(Op::And)
+            }
+            ('|', Some('|')) => {
+                i += 1;
+                Token::Op(Op::Or)
+            }
+            ('"', _) => {
+                let start = i;
+                while i < chars.len() && chars[i] != '"' {
+                    i += 1;
+                }
+                if i >= chars.len() {
+                    return Err(format!("Unclosed string in '{src}'"));
+                }
+                let text = chars[start..i].iter().collect::<String>();
+                i += 1;
+                Token::Value(DialogueValue::Text(text))
+            }
+            (c, _) if c == '$' || c.is_alphanumeric() || c == '_' || c == '.' => {
+                let start = i - 1;
+                while i < chars.len()
+                    && (chars[i].is_alphanumeric() || chars[i] == '_' || chars[i] == '.')
+                {
+                    i += 1;
+                }
+                let word = chars[start..i].iter().collect::<String>();
+                word_token(&word).ok_or_else(|| format!("Invalid token '{word}' in '{src}'"))?
+            }
+            (c, _) => return Err(format!("Invalid character '{c}' in '{src}'")),
+        };
+        tokens.push(token);
+    }
+
+    Ok(tokens)
+}
+
+fn word_token(word: &str) -> Option<Token> {
+    if let Some(name) = word.strip_prefix('$') {
+        return (!name.is_empty()).then(|| Token::Var(name.to_string()));
+    }
+
+    Some(match word {
+        "true" => Token::Value(DialogueValue::Bool(true)),
+        "false" => Token::Value(DialogueValue::Bool(false)),
+        "and" => Token::Op(Op::And),
+        "or" => Token::Op(Op::Or),
+        "not" => Token::Not,
+        "is" | "eq" => Token::Op(Op::Eq),
+        "neq" => Token::Op(Op::NotEq),
+        "lt" => Token::Op(Op::Lt),
+        "lte" => Token::Op(Op::LtEq),
+        "gt" => Token::Op(Op::Gt),
+        "gte" => Token::Op(Op::GtEq),
+        _ => Token::Value(DialogueValue::Number(word.parse().ok()?)),
+    })
+}
+
+/// Parses expressions like `$gold >= 10 and not $angry`
+pub(super) fn parse_expr(src: &str) -> Result<Expr, String> {
+    let tokens = tokenize(src)?;
+    let mut pos = 0;
+    let expr = parse_binary(&tokens, &mut pos, 0, src)?;
+    if pos != tokens.len() {
+        return Err(format!("Unexpected tokens at the end of '{src}'"));
+    }
+    Ok(expr)
+}
+
+fn parse_binary(tokens: &[Token], pos: &mut usize, min: u8, src: &str) -> Result<Expr, String> {
+    let mut lhs = parse_unary(tokens, pos, src)?;
+    loop {
+        let op = match tokens.get(*pos) {
+            Some(Token::Op(op)) => *op,
+            Some(Token::Minus) => Op::Sub,
+            _ => break,
+        };
+
+        if op.precedence() <= min {
+            break;
+        }
+
+        *pos += 1;
+        let rhs = parse_binary(tokens, pos, op.precedence(), src)?;
+        lhs = Expr::Binary(op, Box::new(lhs), Box::new(rhs));
+    }
+
+    Ok(lhs)
+}
+
+fn parse_unary(tokens: &[Token], pos: &mut usize, src: &str) -> Result<Expr, String> {
+    let token = tokens
+        .get(*pos)
+        .ok_or_else(|| format!("Unexpected end of expression '{src}'"))?;
+    *pos += 1;
+    Ok(match token {
+        Token::Value(value) => Expr::Value(value.clone()),
+        Token::Var(name) => Expr::Var(name.clone()),
+        Token::Not => Expr::Not(Box::new(parse_unary(tokens, pos, src)?)),
+        Token::Minus => Expr::Neg(Box::new(parse_unary(tokens, pos, src)?)),
+        Token::Open => {
+            let expr = parse_binary(tokens, pos, 0, src)?;
+            if tokens.get(*pos) != Some(&Token::Close) {
+                return Err(format!("Missing ')' in '{src}'"));
+            }
+            *pos += 1;
+            expr
+        }
+        _ => return Err(format!("Unexpected token in '{src}'")),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn eval(src: &str, vars: &FxHashMap<String, DialogueValue>) -> DialogueValue {
+        parse_expr(src).unwrap().eval(vars).unwrap()
+    }
+
+    #[test]
+    fn test_eval() {
+        let mut vars = FxHashMap::default();
+        vars.insert("gold".to_string(), DialogueValue::Number(12.0));
+        vars.insert("name".to_string(), DialogueValue::Text("Ana".to_string()));
+
+        assert_eq!(eval("1 + 2 * 3", &vars), DialogueValue::Number(7.0));
+        assert_eq!(eval("(1 + 2) * 3", &vars), DialogueValue::Number(9.0));
+        assert_eq!(eval("10 - 2 - 3", &vars), DialogueValue::Number(5.0));
+        assert_eq!(eval("$gold >= 10", &vars), DialogueValue::Bool(true));
+        assert_eq!(
+            eval("$gold > 20 or not $angry", &vars),
+            DialogueValue::Bool(true)
+        );
+        assert_eq!(eval("$name == \"Ana\"", &vars), DialogueValue::Bool(true));
+        assert_eq!(
+            eval("\"Hi \" + $name", &vars),
+            DialogueValue::Text("Hi Ana".to_string())
+        );
+        assert_eq!(eval("-$gold", &vars), DialogueValue::Number(-12.0));
+    }
+
+    #[test]
+    fn test_invalid() {
+        assert!(parse_expr("1 +").is_err());
+        assert!(parse_expr("(1").is_err());
+        assert!(parse_expr("\"open").is_err());
+        assert!(parse_expr("1 2").is_err());
+    }
+}
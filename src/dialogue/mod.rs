@@ -0,0 +1,115 @@
+mod expr;
+mod parser;
+mod runner;
+
+pub use runner::*;
+
+use parser::Instr;
+use std::fmt;
+use std::sync::Arc;
+
+/// Value stored in the dialogue variables
+#[derive(Clone, Debug, PartialEq)]
+pub enum DialogueValue {
+    Number(f32),
+    Bool(bool),
+    Text(String),
+}
+
+impl DialogueValue {
+    pub fn is_truthy(&self) -> bool {
+        match self {
+            DialogueValue::Number(n) => *n != 0.0,
+            DialogueValue::Bool(b) => *b,
+            DialogueValue::Text(t) => !t.is_empty(),
+        }
+    }
+
+    pub fn as_number(&self) -> Result<f32, String> {
+        match self {
+            DialogueValue::Number(n) => Ok(*n),
+            DialogueValue::Bool(b) => Ok(*b as u8 as f32),
+            DialogueValue::Text(t) => Err(format!("'{t}' is not a number")),
+        }
+    }
+}
+
+impl fmt::Display for DialogueValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DialogueValue::Number(n) => write!(f, "{n}"),
+            DialogueValue::Bool(b) => write!(f, "{b}"),
+            DialogueValue::Text(t) => write!(f, "{t}"),
+        }
+    }
+}
+
+impl From<f32> for DialogueValue {
+    fn from(value: f32) -> Self {
+        Self::Number(value)
+    }
+}
+
+impl From<bool> for DialogueValue {
+    fn from(value: bool) -> Self {
+        Self::Bool(value)
+    }
+}
+
+impl From<&str> for DialogueValue {
+    fn from(value: &str) -> Self {
+        Self::Text(value.to_string())
+    }
+}
+
+/// Line of text said by a character (or narration if there is no speaker)
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DialogueLine {
+    /// Id set with `#line:id`, used as key for localization
+    pub id: Option<String>,
+    pub speaker: Option<String>,
+    pub text: String,
+    /// Hashtags of the line without the `#`, except the line id
+    pub tags: Vec<String>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct DialogueNode {
+    title: String,
+    instrs: Vec<Instr>,
+}
+
+/// Branching dialogue parsed from a Yarn like script, cheap to clone.
+/// Can be loaded with the asset loader using `parse_dialogue` as parser.
+#[derive(Clone, Debug)]
+pub struct Dialogue {
+    nodes: Arc<Vec<DialogueNode>>,
+}
+
+impl Dialogue {
+    pub fn parse(src: &str) -> Result<Self, String> {
+        let nodes = parser::parse_nodes(src)?;
+        Ok(Self {
+            nodes: Arc::new(nodes),
+        })
+    }
+
+    /// Titles of the nodes
+    pub fn nodes(&self) -> impl Iterator<Item = &str> {
+        self.nodes.iter().map(|n| n.title.as_str())
+    }
+
+    pub fn contains(&self, node: &str) -> bool {
+        self.node_index(node).is_some()
+    }
+
+    fn node_index(&self, node: &str) -> Option<usize> {
+        self.nodes.iter().position(|n| n.title == node)
+    }
+}
+
+/// Parser for the asset loader, e.g. `list.with_extension_parser("yarn", parse_dialogue)`
+pub fn parse_dialogue(id: &str, data: &[u8]) -> Result<Dialogue, String> {
+    let src = std::str::from_utf8(data).map_err(|e| format!("Invalid dialogue '{id}': {e}"))?;
+    Dialogue::parse(src).map_err(|e| format!("Invalid dialogue '{id}': {e}"))
+}
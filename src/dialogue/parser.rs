@@ -0,0 +1,338 @@
+use super::expr::{parse_expr, Expr};
+use super::{DialogueLine, DialogueNode};
+
+#[derive(Clone, Debug, PartialEq)]
+pub(super) struct OptionDef {
+    pub line: DialogueLine,
+    pub condition: Option<Expr>,
+    pub target: usize,
+}
+
+/// Flat list of instructions of a node, blocks are compiled to jumps
+#[derive(Clone, Debug, PartialEq)]
+pub(super) enum Instr {
+    Line(DialogueLine),
+    Options(Vec<OptionDef>),
+    /// Goes to the instruction if the expression is false
+    JumpIfFalse(Expr, usize),
+    Goto(usize),
+    Set(String, Expr),
+    JumpNode(String),
+    Command(String),
+    Stop,
+}
+
+struct Src<'a> {
+    // (indent, text, line number)
+    lines: Vec<(usize, &'a str, usize)>,
+    pos: usize,
+}
+
+impl<'a> Src<'a> {
+    fn peek(&self) -> Option<(usize, &'a str, usize)> {
+        self.lines.get(self.pos).copied()
+    }
+}
+
+/// Parses the nodes of a Yarn like script:
+/// ```text
+/// title: Start
+/// ---
+/// Guard: Who goes there? #line:guard_ask
+/// -> A friend.
+///     <<set $friend to true>>
+///     <<jump Gate>>
+/// -> Nobody. <<if $gold > 10>>
+///     Guard: Move along.
+/// ===
+/// ```
+pub(super) fn parse_nodes(src: &str) -> Result<Vec<DialogueNode>, String> {
+    let mut nodes = vec![];
+    let mut lines = src.lines().enumerate().map(|(n, l)| (n + 1, l));
+
+    while let Some((line_no, line)) = lines.next() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with("//") {
+            continue;
+        }
+
+        // header
+        let mut title = None;
+        let mut header = Some((line_no, line));
+        while let Some((line_no, line)) = header {
+            if line == "---" {
+                break;
+            }
+
+            if let Some(name) = line.strip_prefix("title:") {
+                title = Some(name.trim().to_string());
+            } else if !line.is_empty() && !line.contains(':') {
+                return Err(format!(
+                    "Line {line_no}: expected a node header, found '{line}'"
+                ));
+            }
+
+            header = lines.next().map(|(n, l)| (n, l.trim()));
+        }
+
+        let title = title.ok_or_else(|| format!("Line {line_no}: node without title"))?;
+        if header.is_none() {
+            return Err(format!("Node '{title}': missing '---' after the header"));
+        }
+
+        // body
+        let mut body = vec![];
+        let mut closed = false;
+        for (line_no, line) in lines.by_ref() {
+            if line.trim() == "===" {
+                closed = true;
+                break;
+            }
+
+            let trimmed = line.trim_start();
+            if trimmed.trim().is_empty() || trimmed.starts_with("//") {
+                continue;
+            }
+
+            let indent = line.len() - trimmed.len();
+            body.push((indent, trimmed.trim_end(), line_no));
+        }
+
+        if !closed {
+            return Err(format!("Node '{title}': missing '==='"));
+        }
+
+        let mut src = Src {
+            lines: body,
+            pos: 0,
+        };
+        let mut instrs = vec![];
+        parse_block(&mut src, 0, &mut instrs)?;
+        if let Some((_, text, line_no)) = src.peek() {
+            return Err(format!("Line {line_no}: unexpected '{text}'"));
+        }
+
+        if nodes.iter().any(|n: &DialogueNode| n.title == title) {
+            return Err(format!("Duplicated node '{title}'"));
+        }
+
+        nodes.push(DialogueNode { title, instrs });
+    }
+
+    Ok(nodes)
+}
+
+fn parse_block(src: &mut Src, min_indent: usize, out: &mut Vec<Instr>) -> Result<(), String> {
+    while let Some((indent, text, line_no)) = src.peek() {
+        if indent < min_indent || is_block_end(text) {
+            break;
+        }
+
+        if text.starts_with("->") {
+            parse_options(src, indent, out)?;
+            continue;
+        }
+
+        src.pos += 1;
+        let err = |e: String| format!("Line {line_no}: {e}");
+        match command(text) {
+            Some(cmd) if cmd.starts_with("if ") => {
+                parse_if(src, min_indent, &cmd[3..], out).map_err(err)?
+            }
+            Some(cmd) => out.push(parse_command(&cmd).map_err(err)?),
+            None => out.push(Instr::Line(parse_line(text))),
+        }
+    }
+
+    Ok(())
+}
+
+fn parse_options(src: &mut Src, indent: usize, out: &mut Vec<Instr>) -> Result<(), String> {
+    let options_idx = out.len();
+    out.push(Instr::Options(vec![]));
+
+    let mut options = vec![];
+    let mut gotos = vec![];
+    while let Some((line_indent, text, line_no)) = src.peek() {
+        let Some(option) = text.strip_prefix("->") else {
+            break;
+        };
+
+        if line_indent != indent {
+            break;
+        }
+
+        src.pos += 1;
+
+        // optional condition: -> text <<if $cond>>
+        let (option, condition) = match option.find("<<if ") {
+            Some(start) => {
+                let cond = option[start..]
+                    .strip_prefix("<<if ")
+                    .and_then(|c| c.split_once(">>"))
+                    .ok_or_else(|| format!("Line {line_no}: unclosed option condition"))?;
+                let rest = format!("{}{}", &option[..start], cond.1);
+                let expr = parse_expr(cond.0).map_err(|e| format!("Line {line_no}: {e}"))?;
+                (rest, Some(expr))
+            }
+            None => (option.to_string(), None),
+        };
+
+        options.push(OptionDef {
+            line: parse_line(option.trim()),
+            condition,
+            target: out.len(),
+        });
+
+        parse_block(src, indent + 1, out)?;
+        gotos.push(out.len());
+        out.push(Instr::Goto(0));
+    }
+
+    // every option continues after the group
+    let end = out.len();
+    gotos
+        .into_iter()
+        .for_each(|idx| out[idx] = Instr::Goto(end));
+    out[options_idx] = Instr::Options(options);
+    Ok(())
+}
+
+fn parse_if(
+    src: &mut Src,
+    min_indent: usize,
+    cond: &str,
+    out: &mut Vec<Instr>,
+) -> Result<(), String> {
+    let mut gotos = vec![];
+    let mut cond = Some(parse_expr(cond)?);
+    loop {
+        let jump_idx = cond.as_ref().map(|_| {
+            out.push(Instr::Goto(0));
+            out.len() - 1
+        });
+
+        parse_block(src, min_indent, out)?;
+
+        let (_, text, _) = src.peek().ok_or_else(|| "missing <<endif>>".to_string())?;
+        src.pos += 1;
+        let cmd = command(text).unwrap_or_default();
+
+        let ends = cmd == "endif";
+        if !ends {
+            gotos.push(out.len());
+            out.push(Instr::Goto(0));
+        }
+
+        // a false condition goes to the next branch
+        if let (Some(idx), Some(expr)) = (jump_idx, cond.take()) {
+            out[idx] = Instr::JumpIfFalse(expr, out.len());
+        }
+
+        if ends {
+            break;
+        }
+
+        if let Some(next) = cmd.strip_prefix("elseif ") {
+            cond = Some(parse_expr(next)?);
+        } else if cmd != "else" {
+            return Err(format!("unexpected '{text}'"));
+        }
+    }
+
+    let end = out.len();
+    gotos
+        .into_iter()
+        .for_each(|idx| out[idx] = Instr::Goto(end));
+    Ok(())
+}
+
+fn parse_command(cmd: &str) -> Result<Instr, String> {
+    let (name, args) = cmd.split_once(' ').unwrap_or((cmd, ""));
+    let args = args.trim();
+    Ok(match name {
+        "jump" if !args.is_empty() => Instr::JumpNode(args.to_string()),
+        "stop" => Instr::Stop,
+        "set" => {
+            let (var, expr) = args
+                .split_once(" to ")
+                .or_else(|| args.split_once('='))
+                .ok_or_else(|| format!("invalid set '{cmd}'"))?;
+            let var = var
+                .trim()
+                .strip_prefix('$')
+                .ok_or_else(|| format!("invalid variable in '{cmd}'"))?;
+            Instr::Set(var.to_string(), parse_expr(expr)?)
+        }
+        "else" | "elseif" | "endif" => return Err(format!("unexpected '<<{cmd}>>'")),
+        _ => Instr::Command(cmd.to_string()),
+    })
+}
+
+/// Returns the content of `<<command>>` lines
+fn command(text: &str) -> Option<String> {
+    text.strip_prefix("<<")
+        .and_then(|t| t.strip_suffix(">>"))
+        .map(|t| t.trim().to_string())
+}
+
+fn is_block_end(text: &str) -> bool {
+    command(text).is_some_and(|cmd| cmd == "else" || cmd == "endif" || cmd.starts_with("elseif "))
+}
+
+/// `Speaker: text #line:id #tag`
+fn parse_line(text: &str) -> DialogueLine {
+    let (text, tags) = match text.find(" #") {
+        Some(idx) => (&text[..idx], &text[idx..]),
+        None => (text, ""),
+    };
+
+    let mut id = None;
+    let mut line_tags = vec![];
+    tags.split_whitespace()
+        .filter_map(|tag| tag.strip_prefix('#'))
+        .for_each(|tag| match tag.strip_prefix("line:") {
+            Some(line_id) => id = Some(line_id.to_string()),
+            None => line_tags.push(tag.to_string()),
+        });
+
+    let (speaker, text) = match text.split_once(':') {
+        Some((name, rest)) if !name.trim().is_empty() && !name.contains(['{', '<']) => {
+            (Some(name.trim().to_string()), rest.trim())
+        }
+        _ => (None, text.trim()),
+    };
+
+    DialogueLine {
+        id,
+        speaker,
+        text: text.to_string(),
+        tags: line_tags,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_line() {
+        let line = parse_line("Guard: Who goes there? #line:guard_ask #angry");
+        assert_eq!(line.id.as_deref(), Some("guard_ask"));
+        assert_eq!(line.speaker.as_deref(), Some("Guard"));
+        assert_eq!(line.text, "Who goes there?");
+        assert_eq!(line.tags, vec!["angry"]);
+
+        let line = parse_line("Just narration");
+        assert_eq!(line.speaker, None);
+        assert_eq!(line.text, "Just narration");
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(parse_nodes("title: A\n---\nHi").is_err());
+        assert!(parse_nodes("title: A\n---\n<<if true>>\nHi\n===").is_err());
+        assert!(parse_nodes("title: A\n---\n===\ntitle: A\n---\n===").is_err());
+        assert!(parse_nodes("---\nHi\n===").is_err());
+    }
+}
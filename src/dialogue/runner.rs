@@ -0,0 +1,324 @@
+use super::parser::Instr;
+use super::{Dialogue, DialogueLine, DialogueValue};
+use rustc_hash::FxHashMap;
+
+type LocalizeFn = dyn Fn(&str, &str) -> Option<String> + Send + Sync;
+
+/// Option shown to the player
+#[derive(Clone, Debug, PartialEq)]
+pub struct DialogueChoice {
+    pub line: DialogueLine,
+    /// False if the option condition is not met, it can be shown disabled or hidden
+    pub available: bool,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum DialogueEvent {
+    Line(DialogueLine),
+    /// The runner waits until `select` is called
+    Options(Vec<DialogueChoice>),
+    /// Custom `<<command args>>` for the game to handle
+    Command(String),
+    End,
+}
+
+/// Runs a `Dialogue` step by step, keeping the variables between nodes and runs
+pub struct DialogueRunner {
+    dialogue: Dialogue,
+    node: Option<usize>,
+    pc: usize,
+    options: Option<Vec<(usize, bool)>>,
+    vars: FxHashMap<String, DialogueValue>,
+    localize: Option<Box<LocalizeFn>>,
+}
+
+impl DialogueRunner {
+    pub fn new(dialogue: Dialogue) -> Self {
+        Self {
+            dialogue,
+            node: None,
+            pc: 0,
+            options: None,
+            vars: FxHashMap::default(),
+            localize: None,
+        }
+    }
+
+    /// Called with the id and the text of each line (and option) with a `#line:id`
+    /// tag, returning `Some` replaces the text. Variables are interpolated after it
+    pub fn with_localization<F>(mut self, localize: F) -> Self
+    where
+        F: Fn(&str, &str) -> Option<String> + Send + Sync + 'static,
+    {
+        self.localize = Some(Box::new(localize));
+        self
+    }
+
+    pub fn dialogue(&self) -> &Dialogue {
+        &self.dialogue
+    }
+
+    /// Starts running the node, stopping the current one if any
+    pub fn start(&mut self, node: &str) -> Result<(), String> {
+        let idx = self
+            .dialogue
+            .node_index(node)
+            .ok_or_else(|| format!("Dialogue node '{node}' not found"))?;
+        self.node = Some(idx);
+        self.pc = 0;
+        self.options = None;
+        Ok(())
+    }
+
+    pub fn stop(&mut self) {
+        self.node = None;
+        self.options = None;
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.node.is_some()
+    }
+
+    /// Returns true if `advance` can't continue until an option is selected
+    pub fn is_waiting_option(&self) -> bool {
+        self.options.is_some()
+    }
+
+    /// Title of the node running
+    pub fn current_node(&self) -> Option<&str> {
+        self.node.map(|idx| self.dialogue.nodes[idx].title.as_str())
+    }
+
+    pub fn variable(&self, name: &str) -> Option<&DialogueValue> {
+        self.vars.get(name)
+    }
+
+    pub fn set_variable(&mut self, name: &str, value: impl Into<DialogueValue>) {
+        self.vars.insert(name.to_string(), value.into());
+    }
+
+    pub fn variables(&self) -> impl Iterator<Item = (&str, &DialogueValue)> {
+        self.vars.iter().map(|(k, v)| (k.as_str(), v))
+    }
+
+    /// Runs until the next line, options, command or the end of the dialogue
+    pub fn advance(&mut self) -> Result<DialogueEvent, String> {
+        if self.options.is_some() {
+            return Err("The dialogue is waiting for an option to be selected".to_string());
+        }
+
+        let nodes = self.dialogue.nodes.clone();
+        loop {
+            let Some(node) = self.node else {
+                return Ok(DialogueEvent::End);
+            };
+
+            let Some(instr) = nodes[node].instrs.get(self.pc) else {
+                self.stop();
+                return Ok(DialogueEvent::End);
+            };
+
+            self.pc += 1;
+            match instr {
+                Instr::Line(line) => return Ok(DialogueEvent::Line(self.present(line)?)),
+                Instr::Options(options) => {
+                    let mut targets = vec![];
+                    let mut choices = vec![];
+                    for option in options {
+                        let available = match &option.condition {
+                            Some(cond) => cond.eval(&self.vars)?.is_truthy(),
+                            None => true,
+                        };
+
+                        targets.push((option.target, available));
+                        choices.push(DialogueChoice {
+                            line: self.present(&option.line)?,
+                            available,
+                        });
+                    }
+
+                    self.options = Some(targets);
+                    return Ok(DialogueEvent::Options(choices));
+                }
+                Instr::JumpIfFalse(cond, target) => {
+                    if !cond.eval(&self.vars)?.is_truthy() {
+                        self.pc = *target;
+                    }
+                }
+                Instr::Goto(target) => self.pc = *target,
+                Instr::Set(name, expr) => {
+                    let value = expr.eval(&self.vars)?;
+                    self.vars.insert(name.clone(), value);
+                }
+                Instr::JumpNode(name) => self.start(name)?,
+                Instr::Command(cmd) => return Ok(DialogueEvent::Command(self.interpolate(cmd))),
+                Instr::Stop => {
+                    self.stop();
+                    return Ok(DialogueEvent::End);
+                }
+            }
+        }
+    }
+
+    /// Picks one of the options returned by `advance`
+    pub fn select(&mut self, idx: usize) -> Result<(), String> {
+        let options = self
+            .options
+            .as_ref()
+            .ok_or_else(|| "The dialogue is not waiting for an option".to_string())?;
+        let (target, available) = *options
+            .get(idx)
+            .ok_or_else(|| format!("Invalid dialogue option {idx}"))?;
+        if !available {
+            return Err(format!("The dialogue option {idx} is not available"));
+        }
+
+        self.pc = target;
+        self.options = None;
+        Ok(())
+    }
+
+    fn present(&self, line: &DialogueLine) -> Result<DialogueLine, String> {
+        let localized = line
+            .id
+            .as_ref()
+            .zip(self.localize.as_ref())
+            .and_then(|(id, localize)| localize(id, &line.text));
+        let text = localized.as_deref().unwrap_or(&line.text);
+
+        Ok(DialogueLine {
+            text: self.interpolate(text),
+            ..line.clone()
+        })
+    }
+
+    /// Replaces `{$var}` with the value of the variable
+    fn interpolate(&self, text: &str) -> String {
+        let mut out = String::with_capacity(text.len());
+        let mut rest = text;
+        while let Some(start) = rest.find("{$") {
+            let Some(end) = rest[start..].find('}') else {
+                break;
+            };
+
+            out.push_str(&rest[..start]);
+            let name = &rest[start + 2..start + end];
+            if let Some(value) = self.vars.get(name) {
+                out.push_str(&value.to_string());
+            }
+            rest = &rest[start + end + 1..];
+        }
+
+        out.push_str(rest);
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCRIPT: &str = r#"
+title: Start
+---
+Guard: Halt! #line:halt
+<<set $visits to $visits + 1>>
+<<if $visits > 1>>
+    Guard: You again, {$name}?
+<<elseif $name == "Ana">>
+    Guard: Hi Ana.
+<<else>>
+    Guard: Who are you?
+<<endif>>
+-> A friend.
+    <<set $friend to true>>
+    <<jump Gate>>
+-> Give gold. <<if $gold >= 10>>
+    <<pay 10>>
+-> Leave.
+Guard: Bye.
+===
+
+title: Gate
+---
+Guard: Pass.
+<<stop>>
+Never shown.
+===
+"#;
+
+    fn line(evt: DialogueEvent) -> String {
+        match evt {
+            DialogueEvent::Line(line) => line.text,
+            evt => panic!("Expected line, found {evt:?}"),
+        }
+    }
+
+    #[test]
+    fn test_branches() {
+        let dialogue = Dialogue::parse(SCRIPT).unwrap();
+        let mut runner = DialogueRunner::new(dialogue);
+        runner.set_variable("name", "Ana");
+        runner.start("Start").unwrap();
+
+        assert_eq!(line(runner.advance().unwrap()), "Halt!");
+        assert_eq!(line(runner.advance().unwrap()), "Hi Ana.");
+
+        let DialogueEvent::Options(choices) = runner.advance().unwrap() else {
+            panic!("Expected options");
+        };
+        assert_eq!(choices.len(), 3);
+        assert!(!choices[1].available);
+        assert!(runner.advance().is_err());
+        assert!(runner.select(1).is_err());
+
+        runner.select(2).unwrap();
+        assert_eq!(line(runner.advance().unwrap()), "Bye.");
+        assert_eq!(runner.advance().unwrap(), DialogueEvent::End);
+        assert!(!runner.is_running());
+
+        // variables are kept between runs
+        runner.start("Start").unwrap();
+        runner.advance().unwrap();
+        assert_eq!(line(runner.advance().unwrap()), "You again, Ana?");
+    }
+
+    #[test]
+    fn test_jump_and_commands() {
+        let mut runner = DialogueRunner::new(Dialogue::parse(SCRIPT).unwrap());
+        runner.set_variable("gold", 20.0);
+        runner.start("Start").unwrap();
+        runner.advance().unwrap();
+        runner.advance().unwrap();
+        runner.advance().unwrap();
+
+        runner.select(1).unwrap();
+        assert_eq!(
+            runner.advance().unwrap(),
+            DialogueEvent::Command("pay 10".to_string())
+        );
+
+        runner.start("Start").unwrap();
+        runner.advance().unwrap();
+        runner.advance().unwrap();
+        runner.advance().unwrap();
+        runner.select(0).unwrap();
+        assert_eq!(line(runner.advance().unwrap()), "Pass.");
+        assert_eq!(runner.current_node(), Some("Gate"));
+        assert_eq!(runner.variable("friend"), Some(&DialogueValue::Bool(true)));
+        assert_eq!(runner.advance().unwrap(), DialogueEvent::End);
+    }
+
+    #[test]
+    fn test_localization() {
+        let mut runner = DialogueRunner::new(Dialogue::parse(SCRIPT).unwrap())
+            .with_localization(|id, _text| (id == "halt").then(|| "¡Alto!".to_string()));
+        runner.start("Start").unwrap();
+        let DialogueEvent::Line(line) = runner.advance().unwrap() else {
+            panic!("Expected line");
+        };
+        assert_eq!(line.text, "¡Alto!");
+        assert_eq!(line.speaker.as_deref(), Some("Guard"));
+        assert_eq!(line.id.as_deref(), Some("halt"));
+    }
+}
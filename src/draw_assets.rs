@@ -0,0 +1,52 @@
+use crate::gfx::{self, ImageData, Texture};
+use assets::AssetList;
+use draw::{Font, Sprite};
+
+/// Extensions parsed as `draw::Sprite` by `with_draw_parsers`
+pub const IMAGE_EXTENSIONS: [&str; 3] = ["png", "jpg", "jpeg"];
+
+/// Extensions parsed as `draw::Font` by `with_draw_parsers`
+pub const FONT_EXTENSIONS: [&str; 2] = ["ttf", "otf"];
+
+pub fn parse_sprite(_id: &str, data: &[u8]) -> Result<Sprite, String> {
+    draw::create_sprite().from_image(data).build()
+}
+
+/// Decodes the image without the GPU, it can run outside the main thread
+pub fn decode_image(_id: &str, data: &[u8]) -> Result<ImageData, String> {
+    ImageData::from_image(data)
+}
+
+/// Uploads an image decoded with `decode_image` as `draw::Sprite`
+pub fn sprite_from_image(_id: &str, image: ImageData) -> Result<Sprite, String> {
+    draw::create_sprite()
+        .from_bytes(image.bytes(), image.width(), image.height())
+        .build()
+}
+
+pub fn parse_texture(_id: &str, data: &[u8]) -> Result<Texture, String> {
+    gfx::create_texture().from_image(data).build()
+}
+
+pub fn parse_font(_id: &str, data: &[u8]) -> Result<Font, String> {
+    draw::create_font(data).build()
+}
+
+pub trait DrawAssetList {
+    /// Parses the images in the list as `draw::Sprite` and the fonts as `draw::Font`.
+    /// The images are decoded in the background and uploaded on the main thread.
+    /// Use `load_typed` with `parse_texture` to get a `gfx::Texture` instead
+    fn with_draw_parsers(self) -> Self;
+}
+
+impl DrawAssetList for AssetList {
+    fn with_draw_parsers(self) -> Self {
+        let list = IMAGE_EXTENSIONS.iter().fold(self, |list, ext| {
+            list.with_background_decoder(ext, decode_image, sprite_from_image)
+        });
+
+        FONT_EXTENSIONS.iter().fold(list, |list, ext| {
+            list.with_extension_parser(ext, parse_font)
+        })
+    }
+}
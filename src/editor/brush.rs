@@ -0,0 +1,138 @@
+use super::TileLayer;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum TileBrush {
+    /// Paints the cells under the cursor while dragging
+    Paint(u32),
+    /// Clears the cells under the cursor while dragging
+    Erase,
+    /// Fills the area between the press and the release
+    Rect(Option<u32>),
+    /// Flood fills the connected cells with the same tile
+    Fill(Option<u32>),
+}
+
+/// Change of a cell, used to undo it
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct TileChange {
+    pub x: u32,
+    pub y: u32,
+    pub before: Option<u32>,
+    pub after: Option<u32>,
+}
+
+impl TileBrush {
+    /// True if the brush is applied on each cell while dragging instead of on release
+    pub fn is_stroke(&self) -> bool {
+        matches!(self, TileBrush::Paint(_) | TileBrush::Erase)
+    }
+
+    /// Applies the brush from the pressed cell to the current one and returns the changes
+    pub fn apply(
+        &self,
+        layer: &mut TileLayer,
+        from: (u32, u32),
+        to: (u32, u32),
+    ) -> Vec<TileChange> {
+        match *self {
+            TileBrush::Paint(tile) => set_cells(layer, [to].into_iter(), Some(tile)),
+            TileBrush::Erase => set_cells(layer, [to].into_iter(), None),
+            TileBrush::Rect(tile) => {
+                let (x1, x2) = (from.0.min(to.0), from.0.max(to.0));
+                let (y1, y2) = (from.1.min(to.1), from.1.max(to.1));
+                let cells = (y1..=y2).flat_map(|y| (x1..=x2).map(move |x| (x, y)));
+                set_cells(layer, cells, tile)
+            }
+            TileBrush::Fill(tile) => flood_fill(layer, to, tile),
+        }
+    }
+}
+
+fn set_cells(
+    layer: &mut TileLayer,
+    cells: impl Iterator<Item = (u32, u32)>,
+    tile: Option<u32>,
+) -> Vec<TileChange> {
+    cells
+        .filter_map(|(x, y)| {
+            let idx = layer.index(x, y)?;
+            let before = layer.tiles[idx];
+            if before == tile {
+                return None;
+            }
+
+            layer.tiles[idx] = tile;
+            Some(TileChange {
+                x,
+                y,
+                before,
+                after: tile,
+            })
+        })
+        .collect()
+}
+
+fn flood_fill(layer: &mut TileLayer, start: (u32, u32), tile: Option<u32>) -> Vec<TileChange> {
+    let Some(idx) = layer.index(start.0, start.1) else {
+        return vec![];
+    };
+
+    let target = layer.tiles[idx];
+    if target == tile {
+        return vec![];
+    }
+
+    let mut changes = vec![];
+    let mut stack = vec![start];
+    while let Some((x, y)) = stack.pop() {
+        let Some(idx) = layer.index(x, y) else {
+            continue;
+        };
+
+        if layer.tiles[idx] != target {
+            continue;
+        }
+
+        layer.tiles[idx] = tile;
+        changes.push(TileChange {
+            x,
+            y,
+            before: target,
+            after: tile,
+        });
+
+        stack.push((x + 1, y));
+        stack.push((x, y + 1));
+        if x > 0 {
+            stack.push((x - 1, y));
+        }
+        if y > 0 {
+            stack.push((x, y - 1));
+        }
+    }
+
+    changes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rect_and_fill() {
+        let mut layer = TileLayer::new("ground", 4, 4, 16.0);
+        let changes = TileBrush::Rect(Some(1)).apply(&mut layer, (2, 2), (0, 0));
+        assert_eq!(changes.len(), 9);
+        assert_eq!(layer.get(1, 1), Some(1));
+        assert_eq!(layer.get(3, 3), None);
+
+        // fills the empty cells around the rect
+        let changes = TileBrush::Fill(Some(2)).apply(&mut layer, (3, 3), (3, 3));
+        assert_eq!(changes.len(), 7);
+        assert_eq!(layer.get(3, 0), Some(2));
+        assert_eq!(layer.get(0, 0), Some(1));
+
+        let changes = TileBrush::Paint(2).apply(&mut layer, (3, 3), (3, 3));
+        assert!(changes.is_empty());
+    }
+}
@@ -0,0 +1,134 @@
+use super::{LevelData, LevelEntity, TileChange};
+
+/// Change done by the editor, it can be reverted
+#[derive(Clone, Debug, PartialEq)]
+pub enum EditorAction {
+    Tiles {
+        layer: usize,
+        changes: Vec<TileChange>,
+    },
+    AddEntities(Vec<LevelEntity>),
+    RemoveEntities(Vec<LevelEntity>),
+    MoveEntity {
+        id: u64,
+        from: [f32; 2],
+        to: [f32; 2],
+    },
+    SetProperty {
+        id: u64,
+        key: String,
+        before: Option<String>,
+        after: Option<String>,
+    },
+}
+
+impl EditorAction {
+    pub(super) fn apply(&self, level: &mut LevelData) {
+        match self {
+            EditorAction::Tiles { layer, changes } => {
+                if let Some(layer) = level.layers.get_mut(*layer) {
+                    changes.iter().for_each(|c| layer.set(c.x, c.y, c.after));
+                }
+            }
+            EditorAction::AddEntities(entities) => {
+                level.entities.extend(entities.iter().cloned());
+            }
+            EditorAction::RemoveEntities(entities) => {
+                level
+                    .entities
+                    .retain(|e| !entities.iter().any(|removed| removed.id == e.id));
+            }
+            EditorAction::MoveEntity { id, to, .. } => {
+                if let Some(entity) = level.entity_mut(*id) {
+                    entity.pos = *to;
+                }
+            }
+            EditorAction::SetProperty { id, key, after, .. } => {
+                if let Some(entity) = level.entity_mut(*id) {
+                    set_prop(entity, key, after.clone());
+                }
+            }
+        }
+    }
+
+    fn reverse(&self) -> EditorAction {
+        match self {
+            EditorAction::Tiles { layer, changes } => EditorAction::Tiles {
+                layer: *layer,
+                changes: changes
+                    .iter()
+                    .rev()
+                    .map(|c| TileChange {
+                        before: c.after,
+                        after: c.before,
+                        ..*c
+                    })
+                    .collect(),
+            },
+            EditorAction::AddEntities(entities) => EditorAction::RemoveEntities(entities.clone()),
+            EditorAction::RemoveEntities(entities) => EditorAction::AddEntities(entities.clone()),
+            EditorAction::MoveEntity { id, from, to } => EditorAction::MoveEntity {
+                id: *id,
+                from: *to,
+                to: *from,
+            },
+            EditorAction::SetProperty {
+                id,
+                key,
+                before,
+                after,
+            } => EditorAction::SetProperty {
+                id: *id,
+                key: key.clone(),
+                before: after.clone(),
+                after: before.clone(),
+            },
+        }
+    }
+}
+
+fn set_prop(entity: &mut LevelEntity, key: &str, value: Option<String>) {
+    match value {
+        Some(value) => {
+            entity.props.insert(key.to_string(), value);
+        }
+        None => {
+            entity.props.remove(key);
+        }
+    }
+}
+
+#[derive(Default)]
+pub(super) struct EditorHistory {
+    undo: Vec<EditorAction>,
+    redo: Vec<EditorAction>,
+}
+
+impl EditorHistory {
+    /// Stores an action already applied
+    pub fn push(&mut self, action: EditorAction) {
+        self.undo.push(action);
+        self.redo.clear();
+    }
+
+    /// Reverts the last action returning the action applied to revert it
+    pub fn undo(&mut self, level: &mut LevelData) -> Option<EditorAction> {
+        let action = self.undo.pop()?;
+        let reverse = action.reverse();
+        reverse.apply(level);
+        self.redo.push(action);
+        Some(reverse)
+    }
+
+    pub fn redo(&mut self, level: &mut LevelData) -> Option<EditorAction> {
+        let action = self.redo.pop()?;
+        action.apply(level);
+        self.undo.push(action.clone());
+        Some(action)
+    }
+
+    pub fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+    }
+}
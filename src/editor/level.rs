@@ -0,0 +1,181 @@
+use std::collections::BTreeMap;
+
+/// Grid of tile ids, `None` is an empty cell
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TileLayer {
+    pub name: String,
+    pub width: u32,
+    pub height: u32,
+    pub tile_size: f32,
+    pub tiles: Vec<Option<u32>>,
+}
+
+impl TileLayer {
+    pub fn new(name: &str, width: u32, height: u32, tile_size: f32) -> Self {
+        Self {
+            name: name.to_string(),
+            width,
+            height,
+            tile_size,
+            tiles: vec![None; width as usize * height as usize],
+        }
+    }
+
+    pub fn index(&self, x: u32, y: u32) -> Option<usize> {
+        (x < self.width && y < self.height).then(|| (y * self.width + x) as usize)
+    }
+
+    pub fn get(&self, x: u32, y: u32) -> Option<u32> {
+        self.index(x, y).and_then(|idx| self.tiles[idx])
+    }
+
+    pub fn set(&mut self, x: u32, y: u32, tile: Option<u32>) {
+        if let Some(idx) = self.index(x, y) {
+            self.tiles[idx] = tile;
+        }
+    }
+
+    /// Cell under the world position, `None` if it's outside of the layer
+    pub fn cell_at(&self, pos: [f32; 2]) -> Option<(u32, u32)> {
+        let x = (pos[0] / self.tile_size).floor();
+        let y = (pos[1] / self.tile_size).floor();
+        let valid = x >= 0.0 && y >= 0.0 && (x as u32) < self.width && (y as u32) < self.height;
+        valid.then_some((x as u32, y as u32))
+    }
+}
+
+/// Entity placed on the level, the game decides what to spawn for each `kind`
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LevelEntity {
+    pub id: u64,
+    pub kind: String,
+    pub pos: [f32; 2],
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub props: BTreeMap<String, String>,
+}
+
+/// Group of entities that can be saved and placed several times,
+/// the positions are relative to the prefab origin
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Prefab {
+    pub name: String,
+    pub entities: Vec<LevelEntity>,
+}
+
+/// Data edited by `LevelEditor`, it can be saved and loaded with the `serde` feature
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LevelData {
+    pub layers: Vec<TileLayer>,
+    pub entities: Vec<LevelEntity>,
+}
+
+impl LevelData {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_layer(mut self, layer: TileLayer) -> Self {
+        self.layers.push(layer);
+        self
+    }
+
+    pub fn entity(&self, id: u64) -> Option<&LevelEntity> {
+        self.entities.iter().find(|e| e.id == id)
+    }
+
+    pub fn entity_mut(&mut self, id: u64) -> Option<&mut LevelEntity> {
+        self.entities.iter_mut().find(|e| e.id == id)
+    }
+
+    /// Id not used by any entity of the level
+    pub fn next_entity_id(&self) -> u64 {
+        self.entities.iter().map(|e| e.id + 1).max().unwrap_or(1)
+    }
+
+    /// Last entity placed under the position within `radius`
+    pub fn entity_at(&self, pos: [f32; 2], radius: f32) -> Option<&LevelEntity> {
+        self.entities.iter().rev().find(|e| {
+            let dx = e.pos[0] - pos[0];
+            let dy = e.pos[1] - pos[1];
+            dx * dx + dy * dy <= radius * radius
+        })
+    }
+
+    /// Creates a prefab with the entities, using the first one as origin
+    pub fn prefab(&self, name: &str, ids: &[u64]) -> Prefab {
+        let entities = ids
+            .iter()
+            .filter_map(|id| self.entity(*id))
+            .collect::<Vec<_>>();
+        let origin = entities.first().map_or([0.0, 0.0], |e| e.pos);
+        let entities = entities
+            .into_iter()
+            .map(|e| LevelEntity {
+                pos: [e.pos[0] - origin[0], e.pos[1] - origin[1]],
+                ..e.clone()
+            })
+            .collect();
+
+        Prefab {
+            name: name.to_string(),
+            entities,
+        }
+    }
+
+    /// Entities of the prefab placed at the position with new ids
+    pub fn instantiate(&self, prefab: &Prefab, pos: [f32; 2]) -> Vec<LevelEntity> {
+        let first_id = self.next_entity_id();
+        prefab
+            .entities
+            .iter()
+            .enumerate()
+            .map(|(i, e)| LevelEntity {
+                id: first_id + i as u64,
+                pos: [e.pos[0] + pos[0], e.pos[1] + pos[1]],
+                ..e.clone()
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entity(id: u64, pos: [f32; 2]) -> LevelEntity {
+        LevelEntity {
+            id,
+            kind: "coin".to_string(),
+            pos,
+            props: BTreeMap::default(),
+        }
+    }
+
+    #[test]
+    fn test_cell_at() {
+        let layer = TileLayer::new("ground", 4, 2, 16.0);
+        assert_eq!(layer.cell_at([17.0, 31.0]), Some((1, 1)));
+        assert_eq!(layer.cell_at([-1.0, 0.0]), None);
+        assert_eq!(layer.cell_at([64.0, 0.0]), None);
+    }
+
+    #[test]
+    fn test_prefab() {
+        let mut level = LevelData::new();
+        level.entities = vec![entity(1, [10.0, 10.0]), entity(4, [20.0, 5.0])];
+        assert_eq!(level.next_entity_id(), 5);
+        assert_eq!(level.entity_at([11.0, 11.0], 4.0).map(|e| e.id), Some(1));
+
+        let prefab = level.prefab("coins", &[1, 4]);
+        assert_eq!(prefab.entities[1].pos, [10.0, -5.0]);
+
+        let placed = level.instantiate(&prefab, [100.0, 100.0]);
+        assert_eq!(placed[0].id, 5);
+        assert_eq!(placed[1].id, 6);
+        assert_eq!(placed[1].pos, [110.0, 95.0]);
+    }
+}
@@ -0,0 +1,596 @@
+use super::history::EditorHistory;
+use super::{EditorAction, LevelData, LevelEntity, Prefab, TileBrush, TileChange};
+use crate::gfx::Color;
+use crate::math::{vec2, Vec2};
+use corelib::input::{
+    is_key_down, is_key_pressed, is_mouse_btn_down, is_mouse_btn_pressed, is_mouse_btn_released,
+    KeyCode, MouseButton,
+};
+use draw::Draw2D;
+use rustc_hash::FxHashMap;
+use std::collections::BTreeMap;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum EditorMode {
+    Tiles,
+    Entities,
+}
+
+type ListenerFn = dyn FnMut(&EditorAction, &LevelData);
+type EntityDrawFn = dyn Fn(&mut Draw2D, &LevelEntity);
+
+enum Drag {
+    Stroke {
+        layer: usize,
+        last: (u32, u32),
+        changes: Vec<TileChange>,
+    },
+    Area {
+        layer: usize,
+        from: (u32, u32),
+        to: (u32, u32),
+    },
+    Move {
+        id: u64,
+        from: [f32; 2],
+        offset: [f32; 2],
+    },
+}
+
+/// In-game level editor for tile layers and entities. The game keeps the level in sync
+/// using `with_listener`, and it can be extended with custom entity drawers and
+/// by calling the editing methods from its own tools or UI
+pub struct LevelEditor {
+    level: LevelData,
+    enabled: bool,
+    toggle_key: Option<KeyCode>,
+    mode: EditorMode,
+    layer: usize,
+    brush: TileBrush,
+    kinds: Vec<String>,
+    kind: usize,
+    pick_radius: f32,
+    selected: Vec<u64>,
+    history: EditorHistory,
+    drag: Option<Drag>,
+    cursor: Vec2,
+    listeners: Vec<Box<ListenerFn>>,
+    drawers: FxHashMap<String, Box<EntityDrawFn>>,
+}
+
+impl LevelEditor {
+    pub fn new(level: LevelData) -> Self {
+        Self {
+            level,
+            enabled: false,
+            toggle_key: Some(KeyCode::F1),
+            mode: EditorMode::Tiles,
+            layer: 0,
+            brush: TileBrush::Paint(0),
+            kinds: vec![],
+            kind: 0,
+            pick_radius: 8.0,
+            selected: vec![],
+            history: EditorHistory::default(),
+            drag: None,
+            cursor: Vec2::ZERO,
+            listeners: vec![],
+            drawers: FxHashMap::default(),
+        }
+    }
+
+    /// Key to enable or disable the editor, `F1` by default
+    pub fn with_toggle_key(mut self, key: Option<KeyCode>) -> Self {
+        self.toggle_key = key;
+        self
+    }
+
+    /// Kinds of entities that can be placed, the first one is selected
+    pub fn with_entity_kinds(mut self, kinds: &[&str]) -> Self {
+        self.kinds = kinds.iter().map(|k| k.to_string()).collect();
+        self.kind = 0;
+        self
+    }
+
+    /// Distance to the cursor used to pick the entities
+    pub fn with_pick_radius(mut self, radius: f32) -> Self {
+        self.pick_radius = radius;
+        self
+    }
+
+    /// Called after each change (and undo or redo) with the level already updated
+    pub fn with_listener<F>(mut self, listener: F) -> Self
+    where
+        F: FnMut(&EditorAction, &LevelData) + 'static,
+    {
+        self.listeners.push(Box::new(listener));
+        self
+    }
+
+    /// Draws the entities of the kind instead of the default marker
+    pub fn with_entity_drawer<F>(mut self, kind: &str, drawer: F) -> Self
+    where
+        F: Fn(&mut Draw2D, &LevelEntity) + 'static,
+    {
+        self.drawers.insert(kind.to_string(), Box::new(drawer));
+        self
+    }
+
+    pub fn level(&self) -> &LevelData {
+        &self.level
+    }
+
+    /// Replaces the level clearing the undo history
+    pub fn set_level(&mut self, level: LevelData) {
+        self.level = level;
+        self.history.clear();
+        self.selected.clear();
+        self.drag = None;
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+        self.cancel();
+    }
+
+    pub fn mode(&self) -> EditorMode {
+        self.mode
+    }
+
+    pub fn set_mode(&mut self, mode: EditorMode) {
+        self.cancel();
+        self.mode = mode;
+    }
+
+    pub fn layer(&self) -> usize {
+        self.layer
+    }
+
+    pub fn set_layer(&mut self, layer: usize) {
+        self.cancel();
+        self.layer = layer.min(self.level.layers.len().saturating_sub(1));
+    }
+
+    pub fn brush(&self) -> TileBrush {
+        self.brush
+    }
+
+    pub fn set_brush(&mut self, brush: TileBrush) {
+        self.cancel();
+        self.brush = brush;
+    }
+
+    pub fn entity_kind(&self) -> Option<&str> {
+        self.kinds.get(self.kind).map(|k| k.as_str())
+    }
+
+    pub fn set_entity_kind(&mut self, kind: &str) {
+        if let Some(idx) = self.kinds.iter().position(|k| k == kind) {
+            self.kind = idx;
+        }
+    }
+
+    pub fn selected(&self) -> &[u64] {
+        &self.selected
+    }
+
+    pub fn select(&mut self, ids: &[u64]) {
+        self.selected = ids.to_vec();
+    }
+
+    /// Reads the mouse and keyboard, `cursor` is the mouse position in level coordinates
+    pub fn update(&mut self, cursor: Vec2) {
+        if self.toggle_key.is_some_and(is_key_pressed) {
+            self.set_enabled(!self.enabled);
+        }
+
+        if !self.enabled {
+            return;
+        }
+
+        let ctrl = is_key_down(KeyCode::ControlLeft) || is_key_down(KeyCode::ControlRight);
+        if ctrl && is_key_pressed(KeyCode::KeyZ) {
+            self.undo();
+        } else if ctrl && is_key_pressed(KeyCode::KeyY) {
+            self.redo();
+        } else if is_key_pressed(KeyCode::Delete) {
+            self.delete_selected();
+        } else if is_key_pressed(KeyCode::Tab) {
+            let mode = match self.mode {
+                EditorMode::Tiles => EditorMode::Entities,
+                EditorMode::Entities => EditorMode::Tiles,
+            };
+            self.set_mode(mode);
+        } else if is_key_pressed(KeyCode::Escape) {
+            self.cancel();
+            self.selected.clear();
+        }
+
+        let pos = cursor.to_array();
+        if is_mouse_btn_pressed(MouseButton::Left) {
+            self.press(pos);
+        } else if is_mouse_btn_down(MouseButton::Left) && self.cursor != cursor {
+            self.drag_to(pos);
+        } else if is_mouse_btn_released(MouseButton::Left) {
+            self.release(pos);
+        } else if is_mouse_btn_pressed(MouseButton::Right) {
+            self.erase_at(pos);
+        }
+
+        self.cursor = cursor;
+    }
+
+    /// Starts applying the current tool at the position
+    pub fn press(&mut self, pos: [f32; 2]) {
+        self.cancel();
+        match self.mode {
+            EditorMode::Tiles => {
+                let Some(cell) = self.cell_at(pos) else {
+                    return;
+                };
+
+                let layer = self.layer;
+                self.drag = Some(if self.brush.is_stroke() {
+                    let changes = self.brush.apply(&mut self.level.layers[layer], cell, cell);
+                    Drag::Stroke {
+                        layer,
+                        last: cell,
+                        changes,
+                    }
+                } else {
+                    Drag::Area {
+                        layer,
+                        from: cell,
+                        to: cell,
+                    }
+                });
+            }
+            EditorMode::Entities => {
+                let picked = self
+                    .level
+                    .entity_at(pos, self.pick_radius)
+                    .map(|e| (e.id, e.pos));
+
+                let (id, from) = match picked {
+                    Some(picked) => picked,
+                    None => {
+                        let Some(kind) = self.entity_kind() else {
+                            return;
+                        };
+
+                        let entity = LevelEntity {
+                            id: self.level.next_entity_id(),
+                            kind: kind.to_string(),
+                            pos,
+                            props: BTreeMap::default(),
+                        };
+                        let picked = (entity.id, entity.pos);
+                        self.commit(EditorAction::AddEntities(vec![entity]));
+                        picked
+                    }
+                };
+
+                self.selected = vec![id];
+                self.drag = Some(Drag::Move {
+                    id,
+                    from,
+                    offset: [pos[0] - from[0], pos[1] - from[1]],
+                });
+            }
+        }
+    }
+
+    pub fn drag_to(&mut self, pos: [f32; 2]) {
+        let cell = self.cell_at(pos);
+        match &mut self.drag {
+            Some(Drag::Stroke {
+                layer,
+                last,
+                changes,
+            }) => {
+                let Some(cell) = cell.filter(|cell| cell != last) else {
+                    return;
+                };
+
+                *last = cell;
+                let layer = &mut self.level.layers[*layer];
+                changes.extend(self.brush.apply(layer, cell, cell));
+            }
+            Some(Drag::Area { to, .. }) => {
+                if let Some(cell) = cell {
+                    *to = cell;
+                }
+            }
+            Some(Drag::Move { id, offset, .. }) => {
+                if let Some(entity) = self.level.entity_mut(*id) {
+                    entity.pos = [pos[0] - offset[0], pos[1] - offset[1]];
+                }
+            }
+            None => {}
+        }
+    }
+
+    /// Ends the current tool storing the change in the history
+    pub fn release(&mut self, pos: [f32; 2]) {
+        self.drag_to(pos);
+        match self.drag.take() {
+            Some(Drag::Stroke { layer, changes, .. }) if !changes.is_empty() => {
+                self.commit_applied(EditorAction::Tiles { layer, changes });
+            }
+            Some(Drag::Area { layer, from, to }) => {
+                let changes = self.brush.apply(&mut self.level.layers[layer], from, to);
+                if !changes.is_empty() {
+                    self.commit_applied(EditorAction::Tiles { layer, changes });
+                }
+            }
+            Some(Drag::Move { id, from, .. }) => {
+                let to = self.level.entity(id).map(|e| e.pos);
+                if let Some(to) = to.filter(|to| *to != from) {
+                    self.commit_applied(EditorAction::MoveEntity { id, from, to });
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Stops the current tool reverting what it did
+    pub fn cancel(&mut self) {
+        match self.drag.take() {
+            Some(Drag::Stroke { layer, changes, .. }) => {
+                if let Some(layer) = self.level.layers.get_mut(layer) {
+                    changes
+                        .iter()
+                        .rev()
+                        .for_each(|c| layer.set(c.x, c.y, c.before));
+                }
+            }
+            Some(Drag::Move { id, from, .. }) => {
+                if let Some(entity) = self.level.entity_mut(id) {
+                    entity.pos = from;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Clears the tile or removes the entity under the position
+    pub fn erase_at(&mut self, pos: [f32; 2]) {
+        self.cancel();
+        match self.mode {
+            EditorMode::Tiles => {
+                let Some(cell) = self.cell_at(pos) else {
+                    return;
+                };
+
+                let layer = self.layer;
+                let changes = TileBrush::Erase.apply(&mut self.level.layers[layer], cell, cell);
+                if !changes.is_empty() {
+                    self.commit_applied(EditorAction::Tiles { layer, changes });
+                }
+            }
+            EditorMode::Entities => {
+                if let Some(entity) = self.level.entity_at(pos, self.pick_radius).cloned() {
+                    self.selected.retain(|id| *id != entity.id);
+                    self.commit(EditorAction::RemoveEntities(vec![entity]));
+                }
+            }
+        }
+    }
+
+    pub fn delete_selected(&mut self) {
+        let entities = self
+            .selected
+            .drain(..)
+            .filter_map(|id| self.level.entity(id).cloned())
+            .collect::<Vec<_>>();
+
+        if !entities.is_empty() {
+            self.commit(EditorAction::RemoveEntities(entities));
+        }
+    }
+
+    /// Sets (or removes with `None`) a property of the entity
+    pub fn set_property(&mut self, id: u64, key: &str, value: Option<&str>) {
+        let Some(entity) = self.level.entity(id) else {
+            return;
+        };
+
+        let before = entity.props.get(key).cloned();
+        let after = value.map(|v| v.to_string());
+        if before != after {
+            self.commit(EditorAction::SetProperty {
+                id,
+                key: key.to_string(),
+                before,
+                after,
+            });
+        }
+    }
+
+    /// Creates a prefab with the selected entities, the game decides where to save it
+    pub fn selection_prefab(&self, name: &str) -> Prefab {
+        self.level.prefab(name, &self.selected)
+    }
+
+    /// Places the entities of the prefab selecting them
+    pub fn place_prefab(&mut self, prefab: &Prefab, pos: [f32; 2]) {
+        let entities = self.level.instantiate(prefab, pos);
+        if entities.is_empty() {
+            return;
+        }
+
+        self.selected = entities.iter().map(|e| e.id).collect();
+        self.commit(EditorAction::AddEntities(entities));
+    }
+
+    pub fn undo(&mut self) {
+        self.cancel();
+        if let Some(action) = self.history.undo(&mut self.level) {
+            self.notify(&action);
+        }
+    }
+
+    pub fn redo(&mut self) {
+        self.cancel();
+        if let Some(action) = self.history.redo(&mut self.level) {
+            self.notify(&action);
+        }
+    }
+
+    /// Draws the grid of the current layer, the tool preview and the entities
+    pub fn draw(&self, draw: &mut Draw2D) {
+        if !self.enabled {
+            return;
+        }
+
+        if let Some(layer) = self.level.layers.get(self.layer) {
+            let size = vec2(layer.width as f32, layer.height as f32) * layer.tile_size;
+            (0..=layer.width).for_each(|x| {
+                let x = x as f32 * layer.tile_size;
+                draw.line(vec2(x, 0.0), vec2(x, size.y))
+                    .color(Color::WHITE)
+                    .alpha(0.2);
+            });
+            (0..=layer.height).for_each(|y| {
+                let y = y as f32 * layer.tile_size;
+                draw.line(vec2(0.0, y), vec2(size.x, y))
+                    .color(Color::WHITE)
+                    .alpha(0.2);
+            });
+
+            let area = match &self.drag {
+                Some(Drag::Area { from, to, .. }) => Some((*from, *to)),
+                _ if self.mode == EditorMode::Tiles => self
+                    .cell_at(self.cursor.to_array())
+                    .map(|cell| (cell, cell)),
+                _ => None,
+            };
+
+            if let Some((from, to)) = area {
+                let min = vec2(from.0.min(to.0) as f32, from.1.min(to.1) as f32);
+                let max = vec2(from.0.max(to.0) as f32, from.1.max(to.1) as f32) + 1.0;
+                draw.rect(min * layer.tile_size, (max - min) * layer.tile_size)
+                    .stroke_color(Color::YELLOW)
+                    .stroke(1.0);
+            }
+        }
+
+        self.level.entities.iter().for_each(|entity| {
+            match self.drawers.get(&entity.kind) {
+                Some(drawer) => drawer(draw, entity),
+                None => {
+                    let pos = Vec2::from(entity.pos);
+                    draw.circle(self.pick_radius)
+                        .position(pos - self.pick_radius)
+                        .color(Color::ORANGE)
+                        .alpha(0.6)
+                        .fill();
+                    draw.text(&entity.kind)
+                        .translate(pos + vec2(0.0, self.pick_radius + 2.0))
+                        .anchor(vec2(0.5, 0.0))
+                        .size(8.0);
+                }
+            }
+
+            if self.selected.contains(&entity.id) {
+                draw.circle(self.pick_radius + 2.0)
+                    .position(Vec2::from(entity.pos) - (self.pick_radius + 2.0))
+                    .stroke_color(Color::YELLOW)
+                    .stroke(1.0);
+            }
+        });
+    }
+
+    fn cell_at(&self, pos: [f32; 2]) -> Option<(u32, u32)> {
+        self.level.layers.get(self.layer)?.cell_at(pos)
+    }
+
+    fn commit(&mut self, action: EditorAction) {
+        action.apply(&mut self.level);
+        self.commit_applied(action);
+    }
+
+    fn commit_applied(&mut self, action: EditorAction) {
+        self.notify(&action);
+        self.history.push(action);
+    }
+
+    fn notify(&mut self, action: &EditorAction) {
+        let level = &self.level;
+        self.listeners
+            .iter_mut()
+            .for_each(|listener| listener(action, level));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::editor::TileLayer;
+
+    fn editor() -> LevelEditor {
+        let level = LevelData::new().with_layer(TileLayer::new("ground", 8, 8, 10.0));
+        LevelEditor::new(level).with_entity_kinds(&["coin", "enemy"])
+    }
+
+    #[test]
+    fn test_tiles_undo_redo() {
+        let mut editor = editor();
+        editor.set_brush(TileBrush::Paint(3));
+        editor.press([5.0, 5.0]);
+        editor.drag_to([15.0, 5.0]);
+        editor.release([25.0, 5.0]);
+        let layer = &editor.level().layers[0];
+        assert_eq!((layer.get(0, 0), layer.get(2, 0)), (Some(3), Some(3)));
+
+        // the whole stroke is one action
+        editor.undo();
+        assert!(editor.level().layers[0].tiles.iter().all(|t| t.is_none()));
+        editor.redo();
+        assert_eq!(editor.level().layers[0].get(1, 0), Some(3));
+
+        editor.set_brush(TileBrush::Rect(Some(1)));
+        editor.press([0.0, 10.0]);
+        editor.release([19.0, 29.0]);
+        assert_eq!(editor.level().layers[0].get(1, 2), Some(1));
+        editor.undo();
+        assert_eq!(editor.level().layers[0].get(1, 2), None);
+    }
+
+    #[test]
+    fn test_entities() {
+        use std::cell::RefCell;
+        use std::rc::Rc;
+
+        let actions = Rc::new(RefCell::new(0));
+        let counter = actions.clone();
+        let mut editor = editor().with_listener(move |_, _| *counter.borrow_mut() += 1);
+        editor.set_mode(EditorMode::Entities);
+
+        editor.press([20.0, 20.0]);
+        editor.release([20.0, 20.0]);
+        assert_eq!(editor.level().entities.len(), 1);
+        let id = editor.selected()[0];
+
+        // dragging the entity moves it
+        editor.press([22.0, 20.0]);
+        editor.release([42.0, 30.0]);
+        assert_eq!(editor.level().entity(id).unwrap().pos, [40.0, 30.0]);
+
+        editor.set_property(id, "value", Some("10"));
+        let prefab = editor.selection_prefab("coin");
+        editor.place_prefab(&prefab, [0.0, 0.0]);
+        assert_eq!(editor.level().entities.len(), 2);
+        assert_eq!(editor.level().entities[1].props["value"], "10");
+
+        editor.delete_selected();
+        editor.undo();
+        editor.undo();
+        assert_eq!(editor.level().entities.len(), 1);
+        assert_eq!(*actions.borrow(), 7);
+    }
+}
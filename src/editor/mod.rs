@@ -0,0 +1,9 @@
+mod brush;
+mod history;
+mod level;
+mod level_editor;
+
+pub use brush::*;
+pub use history::EditorAction;
+pub use level::*;
+pub use level_editor::*;
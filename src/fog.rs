@@ -0,0 +1,355 @@
+use crate::gfx::{self, Color, ImageData, Texture, TextureFilter};
+use crate::math::{uvec2, vec2, UVec2, Vec2};
+use draw::{create_sprite, Draw2D, Element2D, Sprite};
+
+/// Coverage of the fog per cell, it keeps two tiers, cells currently visible and
+/// cells that were visible at some point (explored)
+#[derive(Clone, Debug)]
+pub struct FogGrid {
+    size: UVec2,
+    cell_size: f32,
+    soft_edge: f32,
+    visible: Vec<f32>,
+    explored: Vec<f32>,
+}
+
+impl FogGrid {
+    pub fn new(world_size: Vec2, cell_size: f32) -> Self {
+        debug_assert!(cell_size > 0.0, "Fog cell size must be greater than 0");
+        let size = (world_size / cell_size).ceil().as_uvec2().max(UVec2::ONE);
+        let len = size.element_product() as usize;
+        Self {
+            size,
+            cell_size,
+            soft_edge: cell_size,
+            visible: vec![0.0; len],
+            explored: vec![0.0; len],
+        }
+    }
+
+    pub fn size(&self) -> UVec2 {
+        self.size
+    }
+
+    pub fn cell_size(&self) -> f32 {
+        self.cell_size
+    }
+
+    /// Distance in world units used to fade the edges of the vision stamps
+    pub fn set_soft_edge(&mut self, distance: f32) {
+        self.soft_edge = distance.max(f32::EPSILON);
+    }
+
+    /// Hides the visible cells keeping the explored ones, it should be called before
+    /// stamping the vision of the current frame
+    pub fn clear_visible(&mut self) {
+        self.visible.iter_mut().for_each(|v| *v = 0.0);
+    }
+
+    /// Hides everything, explored cells included
+    pub fn reset(&mut self) {
+        self.clear_visible();
+        self.explored.iter_mut().for_each(|v| *v = 0.0);
+    }
+
+    pub fn reveal_circle(&mut self, center: Vec2, radius: f32) {
+        let (min, max) = self.cells_in(center - radius, center + radius);
+        let soft = self.soft_edge;
+        self.stamp(min, max, |pos| {
+            ((radius - pos.distance(center)) / soft).clamp(0.0, 1.0)
+        });
+    }
+
+    pub fn reveal_polygon(&mut self, points: &[Vec2]) {
+        if points.len() < 3 {
+            return;
+        }
+
+        let (pmin, pmax) = points.iter().fold((Vec2::MAX, Vec2::MIN), |(min, max), p| {
+            (min.min(*p), max.max(*p))
+        });
+        let (min, max) = self.cells_in(pmin, pmax);
+        let soft = self.soft_edge;
+        self.stamp(min, max, |pos| {
+            if !point_in_polygon(pos, points) {
+                return 0.0;
+            }
+
+            (distance_to_polygon_edges(pos, points) / soft).clamp(0.0, 1.0)
+        });
+    }
+
+    /// Visibility in the range 0.0..=1.0 for a world position
+    pub fn visibility(&self, pos: Vec2) -> f32 {
+        self.index_at(pos).map_or(0.0, |idx| self.visible[idx])
+    }
+
+    pub fn is_visible(&self, pos: Vec2) -> bool {
+        self.visibility(pos) > 0.0
+    }
+
+    pub fn is_explored(&self, pos: Vec2) -> bool {
+        self.index_at(pos)
+            .is_some_and(|idx| self.explored[idx] > 0.0)
+    }
+
+    /// Opacity of the fog per cell, the explored cells not visible use `explored_alpha`
+    pub(crate) fn alpha_at(&self, idx: usize, explored_alpha: f32) -> f32 {
+        let base = 1.0 - self.explored[idx] * (1.0 - explored_alpha);
+        base * (1.0 - self.visible[idx])
+    }
+
+    fn stamp<F: Fn(Vec2) -> f32>(&mut self, min: UVec2, max: UVec2, coverage: F) {
+        for y in min.y..max.y {
+            for x in min.x..max.x {
+                let pos = (vec2(x as _, y as _) + 0.5) * self.cell_size;
+                let value = coverage(pos);
+                if value <= 0.0 {
+                    continue;
+                }
+
+                let idx = (y * self.size.x + x) as usize;
+                self.visible[idx] = self.visible[idx].max(value);
+                self.explored[idx] = self.explored[idx].max(value);
+            }
+        }
+    }
+
+    fn cells_in(&self, min: Vec2, max: Vec2) -> (UVec2, UVec2) {
+        let min = (min / self.cell_size).floor().max(Vec2::ZERO).as_uvec2();
+        let max = (max / self.cell_size)
+            .ceil()
+            .max(Vec2::ZERO)
+            .as_uvec2()
+            .min(self.size);
+        (min, max)
+    }
+
+    fn index_at(&self, pos: Vec2) -> Option<usize> {
+        if pos.x < 0.0 || pos.y < 0.0 {
+            return None;
+        }
+
+        let cell = (pos / self.cell_size).as_uvec2();
+        if cell.x >= self.size.x || cell.y >= self.size.y {
+            return None;
+        }
+
+        Some((cell.y * self.size.x + cell.x) as usize)
+    }
+}
+
+fn point_in_polygon(p: Vec2, points: &[Vec2]) -> bool {
+    let mut inside = false;
+    let mut j = points.len() - 1;
+    for i in 0..points.len() {
+        let (a, b) = (points[i], points[j]);
+        if (a.y > p.y) != (b.y > p.y) && p.x < (b.x - a.x) * (p.y - a.y) / (b.y - a.y) + a.x {
+            inside = !inside;
+        }
+        j = i;
+    }
+    inside
+}
+
+fn distance_to_polygon_edges(p: Vec2, points: &[Vec2]) -> f32 {
+    let mut j = points.len() - 1;
+    let mut dist = f32::MAX;
+    for i in 0..points.len() {
+        let (a, b) = (points[j], points[i]);
+        let ab = b - a;
+        let t = ((p - a).dot(ab) / ab.length_squared().max(f32::EPSILON)).clamp(0.0, 1.0);
+        dist = dist.min(p.distance(a + ab * t));
+        j = i;
+    }
+    dist
+}
+
+/// Fog of war overlay, stamp the vision each frame and then draw it over the world
+pub struct FogOfWar {
+    grid: FogGrid,
+    color: Color,
+    explored_alpha: f32,
+    image: ImageData,
+    alphas: Vec<u8>,
+    texture: Texture,
+    sprite: Sprite,
+    world_size: Vec2,
+}
+
+impl FogOfWar {
+    pub fn new(world_size: Vec2, cell_size: f32) -> Result<Self, String> {
+        let grid = FogGrid::new(world_size, cell_size);
+        let UVec2 { x: w, y: h } = grid.size();
+
+        let color = Color::BLACK;
+        let mut image = ImageData::new(w, h);
+        image.clear(color);
+
+        let texture = gfx::create_texture()
+            .with_label("FogOfWar Texture")
+            .from_bytes(image.bytes(), w, h)
+            .with_write_flag(true)
+            .build()?;
+
+        let sprite = create_sprite()
+            .from_texture(&texture)
+            .with_min_filter(TextureFilter::Linear)
+            .with_mag_filter(TextureFilter::Linear)
+            .build()?;
+
+        Ok(Self {
+            grid,
+            color,
+            explored_alpha: 0.6,
+            alphas: vec![255; image.bytes().len() / 4],
+            image,
+            texture,
+            sprite,
+            world_size,
+        })
+    }
+
+    pub fn grid(&self) -> &FogGrid {
+        &self.grid
+    }
+
+    pub fn grid_mut(&mut self) -> &mut FogGrid {
+        &mut self.grid
+    }
+
+    pub fn set_color(&mut self, color: Color) {
+        self.color = color;
+        // force to write all the cells
+        self.alphas.iter_mut().for_each(|a| *a = 0);
+    }
+
+    /// Opacity used for the explored cells that are not visible
+    pub fn set_explored_alpha(&mut self, alpha: f32) {
+        self.explored_alpha = alpha.clamp(0.0, 1.0);
+    }
+
+    pub fn set_soft_edge(&mut self, distance: f32) {
+        self.grid.set_soft_edge(distance);
+    }
+
+    pub fn clear_visible(&mut self) {
+        self.grid.clear_visible();
+    }
+
+    pub fn reveal_circle(&mut self, center: Vec2, radius: f32) {
+        self.grid.reveal_circle(center, radius);
+    }
+
+    pub fn reveal_polygon(&mut self, points: &[Vec2]) {
+        self.grid.reveal_polygon(points);
+    }
+
+    pub fn is_visible(&self, pos: Vec2) -> bool {
+        self.grid.is_visible(pos)
+    }
+
+    pub fn is_explored(&self, pos: Vec2) -> bool {
+        self.grid.is_explored(pos)
+    }
+
+    /// Updates the coverage texture uploading only the cells that changed
+    pub fn update(&mut self) -> Result<(), String> {
+        let UVec2 { x: w, y: h } = self.grid.size();
+        for y in 0..h {
+            for x in 0..w {
+                let idx = (y * w + x) as usize;
+                let alpha = self.grid.alpha_at(idx, self.explored_alpha) * self.color.a;
+                let alpha_u8 = (alpha * 255.0).round() as u8;
+                if self.alphas[idx] == alpha_u8 {
+                    continue;
+                }
+
+                self.alphas[idx] = alpha_u8;
+                self.image.set_pixel(x, y, self.color.with_alpha(alpha));
+            }
+        }
+
+        self.image.upload_to(&self.texture)
+    }
+
+    pub fn size(&self) -> Vec2 {
+        self.world_size
+    }
+
+    pub fn cells(&self) -> UVec2 {
+        uvec2(self.image.width(), self.image.height())
+    }
+}
+
+impl Element2D for FogOfWar {
+    fn process(&self, draw: &mut Draw2D) {
+        // cells can go beyond the world size, scale the sprite to keep the cells aligned
+        let size = self.grid.size().as_vec2() * self.grid.cell_size();
+        draw.image(&self.sprite).size(size);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_grid_size() {
+        let grid = FogGrid::new(vec2(100.0, 50.0), 10.0);
+        assert_eq!(grid.size(), uvec2(10, 5));
+
+        let grid = FogGrid::new(vec2(101.0, 50.0), 10.0);
+        assert_eq!(grid.size(), uvec2(11, 5));
+    }
+
+    #[test]
+    fn test_reveal_circle() {
+        let mut grid = FogGrid::new(vec2(100.0, 100.0), 10.0);
+        grid.reveal_circle(vec2(50.0, 50.0), 20.0);
+
+        assert!(grid.is_visible(vec2(50.0, 50.0)));
+        assert!(grid.is_explored(vec2(50.0, 50.0)));
+        assert!(!grid.is_visible(vec2(5.0, 5.0)));
+        assert!(!grid.is_explored(vec2(5.0, 5.0)));
+    }
+
+    #[test]
+    fn test_explored_tier() {
+        let mut grid = FogGrid::new(vec2(100.0, 100.0), 10.0);
+        grid.reveal_circle(vec2(50.0, 50.0), 20.0);
+        grid.clear_visible();
+
+        assert!(!grid.is_visible(vec2(50.0, 50.0)));
+        assert!(grid.is_explored(vec2(50.0, 50.0)));
+
+        let idx = grid.index_at(vec2(50.0, 50.0)).unwrap();
+        assert_eq!(grid.alpha_at(idx, 0.5), 0.5);
+
+        grid.reset();
+        assert!(!grid.is_explored(vec2(50.0, 50.0)));
+        assert_eq!(grid.alpha_at(idx, 0.5), 1.0);
+    }
+
+    #[test]
+    fn test_reveal_polygon() {
+        let mut grid = FogGrid::new(vec2(100.0, 100.0), 10.0);
+        grid.set_soft_edge(1.0);
+        grid.reveal_polygon(&[vec2(0.0, 0.0), vec2(60.0, 0.0), vec2(0.0, 60.0)]);
+
+        assert!(grid.is_visible(vec2(15.0, 15.0)));
+        assert!(!grid.is_visible(vec2(55.0, 55.0)));
+    }
+
+    #[test]
+    fn test_soft_edge() {
+        let mut grid = FogGrid::new(vec2(100.0, 100.0), 1.0);
+        grid.set_soft_edge(10.0);
+        grid.reveal_circle(vec2(50.0, 50.0), 20.0);
+
+        let center = grid.visibility(vec2(50.0, 50.0));
+        let edge = grid.visibility(vec2(50.0, 66.0));
+        assert_eq!(center, 1.0);
+        assert!(edge > 0.0 && edge < 1.0);
+    }
+}
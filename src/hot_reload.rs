@@ -0,0 +1,121 @@
+use libloading::{Library, Symbol};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// Loads game systems from a dynamic library and reloads it when the file changes.
+/// The state lives on the host (e.g. the `World`), so it's kept between reloads.
+///
+/// The library is copied before being loaded to let the compiler replace the original
+/// file. Both sides must be built with the same compiler and rkit version because the
+/// functions use the Rust ABI.
+pub struct HotReload {
+    path: PathBuf,
+    lib: Option<Library>,
+    modified: Option<SystemTime>,
+    version: usize,
+}
+
+impl HotReload {
+    /// Loads the library at `path` (e.g. `target/debug/libgame.so`)
+    pub fn new(path: impl AsRef<Path>) -> Result<Self, String> {
+        let mut hot = Self {
+            path: path.as_ref().to_path_buf(),
+            lib: None,
+            modified: None,
+            version: 0,
+        };
+
+        hot.reload()?;
+        Ok(hot)
+    }
+
+    /// Number of times the library was loaded
+    pub fn version(&self) -> usize {
+        self.version
+    }
+
+    /// Reloads the library if the file changed, returns `true` if it was reloaded
+    pub fn update(&mut self) -> Result<bool, String> {
+        let modified = modified_time(&self.path)?;
+        if self.modified.is_some_and(|last| last >= modified) {
+            return Ok(false);
+        }
+
+        self.reload()?;
+        Ok(true)
+    }
+
+    /// Calls a function exported by the library as `#[no_mangle] pub fn name(state: &mut S)`
+    ///
+    /// # Safety
+    /// The exported function must have exactly that signature, with `S` being the same type
+    /// on both sides, otherwise the call is undefined behaviour
+    pub unsafe fn call<S>(&self, name: &str, state: &mut S) -> Result<(), String> {
+        let func = self.get::<fn(&mut S)>(name)?;
+        func(state);
+        Ok(())
+    }
+
+    /// Returns a symbol exported by the library
+    ///
+    /// # Safety
+    /// `T` must match the type of the exported symbol, and it must not be used after a reload
+    pub unsafe fn get<T: Copy>(&self, name: &str) -> Result<T, String> {
+        let lib = self
+            .lib
+            .as_ref()
+            .ok_or_else(|| "Hot reload library is not loaded.".to_string())?;
+
+        let symbol: Symbol<T> = lib
+            .get(name.as_bytes())
+            .map_err(|e| format!("Cannot find symbol '{name}': {e}"))?;
+        Ok(*symbol)
+    }
+
+    fn reload(&mut self) -> Result<(), String> {
+        let modified = modified_time(&self.path)?;
+
+        // copy with a different name each time, some platforms cache the libraries by path
+        let copy = self.copy_path(self.version + 1);
+        std::fs::copy(&self.path, &copy)
+            .map_err(|e| format!("Cannot copy library '{}': {e}", self.path.display()))?;
+
+        // safety: loading a library can run arbitrary code, this is meant for development only
+        let lib = unsafe { Library::new(&copy) }
+            .map_err(|e| format!("Cannot load library '{}': {e}", copy.display()))?;
+
+        if let Some(old) = self.lib.replace(lib) {
+            drop(old);
+            let _ = std::fs::remove_file(self.copy_path(self.version));
+        }
+
+        self.modified = Some(modified);
+        self.version += 1;
+        log::info!("Library '{}' loaded.", self.path.display());
+        Ok(())
+    }
+
+    fn copy_path(&self, version: usize) -> PathBuf {
+        let name = self
+            .path
+            .file_name()
+            .map_or_else(|| "hot".into(), |n| n.to_string_lossy());
+        // the pid avoids sharing the copy with other instances of the game running
+        let pid = std::process::id();
+        std::env::temp_dir().join(format!("rkit_{pid}_{version}_{name}"))
+    }
+}
+
+impl Drop for HotReload {
+    fn drop(&mut self) {
+        if self.lib.take().is_some() {
+            let _ = std::fs::remove_file(self.copy_path(self.version));
+        }
+    }
+}
+
+fn modified_time(path: &Path) -> Result<SystemTime, String> {
+    std::fs::metadata(path)
+        .and_then(|m| m.modified())
+        .map_err(|e| format!("Cannot read library '{}': {e}", path.display()))
+}
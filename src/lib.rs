@@ -1,12 +1,48 @@
+pub mod achievements;
+pub mod dialogue;
+pub mod panic_context;
+pub mod scheduler;
+pub mod sim;
+pub mod telemetry;
 pub mod tween;
 pub mod utils;
 
+#[cfg(feature = "ai")]
+pub mod ai;
+
+#[cfg(all(feature = "audio", feature = "assets"))]
+pub mod audio_assets;
+
+#[cfg(feature = "capture")]
+pub mod capture;
+
+#[cfg(all(feature = "draw", feature = "assets"))]
+pub mod draw_assets;
+
+#[cfg(feature = "editor")]
+pub mod editor;
+
+#[cfg(feature = "fog")]
+pub mod fog;
+
+#[cfg(all(feature = "hot-reload", not(target_arch = "wasm32")))]
+pub mod hot_reload;
+
+#[cfg(feature = "locale")]
+pub mod locale;
+
 #[cfg(feature = "postfx")]
 pub mod postfx;
 
 #[cfg(feature = "random")]
 pub mod random;
 
+#[cfg(any(feature = "assets-json", feature = "assets-ron"))]
+pub mod serde_assets;
+
+#[cfg(feature = "skeleton")]
+pub mod skeleton;
+
 #[cfg(feature = "ui")]
 pub mod ui;
 
@@ -25,4 +61,8 @@ pub use audio;
 #[cfg(feature = "assets")]
 pub use assets;
 
-pub use macros;
\ No newline at end of file
+pub use macros;
+
+/// Embeds asset files in the binary, see `macros::embed_assets`
+#[cfg(feature = "assets")]
+pub use macros::embed_assets;
@@ -0,0 +1,150 @@
+use super::LocaleTable;
+use atomic_refcell::AtomicRefCell;
+use once_cell::sync::Lazy;
+use std::collections::{BTreeMap, BTreeSet};
+use std::panic::Location;
+
+// keys used at runtime with the places of the source code where they were requested
+type KeySources = BTreeMap<String, BTreeSet<(&'static str, u32)>>;
+
+static KEYS: Lazy<AtomicRefCell<KeySources>> = Lazy::new(|| AtomicRefCell::new(BTreeMap::new()));
+
+/// Registers a key used outside `Localization::tr` (e.g. UI labels translated later)
+#[track_caller]
+pub fn register_locale_key(key: &str) {
+    register_key_at(key, Location::caller());
+}
+
+pub(super) fn register_key_at(key: &str, location: &'static Location<'static>) {
+    add_key(&mut KEYS.borrow_mut(), key, location);
+}
+
+fn add_key(keys: &mut KeySources, key: &str, location: &'static Location<'static>) {
+    let entry = (location.file(), location.line());
+    match keys.get_mut(key) {
+        Some(sources) => {
+            sources.insert(entry);
+        }
+        None => {
+            keys.insert(key.to_string(), BTreeSet::from([entry]));
+        }
+    }
+}
+
+/// Keys registered so far, sorted
+pub fn registered_locale_keys() -> Vec<String> {
+    KEYS.borrow().keys().cloned().collect()
+}
+
+pub fn clear_locale_keys() {
+    KEYS.borrow_mut().clear();
+}
+
+/// CSV with the columns `key,text,context` for the registered keys. The text is taken
+/// from `table` if it's set (e.g. the source language), the context lists the source locations
+pub fn export_csv(table: Option<&LocaleTable>) -> String {
+    write_csv(&KEYS.borrow(), table)
+}
+
+/// Fluent (`.ftl`) skeleton for the registered keys, with the source locations as comments.
+/// The characters not valid in Fluent identifiers are replaced by `-`
+pub fn export_fluent(table: Option<&LocaleTable>) -> String {
+    write_fluent(&KEYS.borrow(), table)
+}
+
+fn write_csv(keys: &KeySources, table: Option<&LocaleTable>) -> String {
+    let mut csv = String::from("key,text,context\n");
+    keys.iter().for_each(|(key, sources)| {
+        let text = table.and_then(|t| t.get(key)).unwrap_or_default();
+        csv.push_str(&format!(
+            "{},{},{}\n",
+            csv_field(key),
+            csv_field(text),
+            csv_field(&sources_text(sources, " "))
+        ));
+    });
+    csv
+}
+
+fn write_fluent(keys: &KeySources, table: Option<&LocaleTable>) -> String {
+    let mut ftl = String::new();
+    keys.iter().for_each(|(key, sources)| {
+        let text = table.and_then(|t| t.get(key)).unwrap_or_default();
+        ftl.push_str(&format!(
+            "# {}\n{} = {}\n\n",
+            sources_text(sources, ", "),
+            fluent_id(key),
+            text.replace('\n', "\n    ")
+        ));
+    });
+    ftl
+}
+
+fn sources_text(sources: &BTreeSet<(&'static str, u32)>, separator: &str) -> String {
+    sources
+        .iter()
+        .map(|(file, line)| format!("{file}:{line}"))
+        .collect::<Vec<_>>()
+        .join(separator)
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn fluent_id(key: &str) -> String {
+    key.chars()
+        .enumerate()
+        .map(|(i, c)| match c {
+            'a'..='z' | 'A'..='Z' => c,
+            '0'..='9' | '_' | '-' if i > 0 => c,
+            _ => '-',
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_export() {
+        let mut table = LocaleTable::default();
+        table.insert("menu.play", "Play, now");
+
+        let mut keys = KeySources::new();
+        add_key(&mut keys, "menu.play", Location::caller());
+        add_key(&mut keys, "menu.play", Location::caller());
+        add_key(&mut keys, "menu.quit", Location::caller());
+        assert_eq!(keys["menu.play"].len(), 2);
+
+        let file = file!();
+        let csv = write_csv(&keys, Some(&table));
+        let mut lines = csv.lines();
+        assert_eq!(lines.next(), Some("key,text,context"));
+        assert!(lines
+            .next()
+            .unwrap()
+            .starts_with(&format!("menu.play,\"Play, now\",{file}:")));
+        assert!(lines
+            .next()
+            .unwrap()
+            .starts_with(&format!("menu.quit,,{file}:")));
+
+        let ftl = write_fluent(&keys, None);
+        assert!(ftl.starts_with(&format!("# {file}:")));
+        assert!(ftl.contains("menu-play = \n"));
+        assert!(ftl.contains("menu-quit = \n"));
+    }
+
+    #[test]
+    fn test_fluent_id() {
+        assert_eq!(fluent_id("menu.play"), "menu-play");
+        assert_eq!(fluent_id("1st_level"), "-st_level");
+        assert_eq!(fluent_id("dialog_intro-2"), "dialog_intro-2");
+    }
+}
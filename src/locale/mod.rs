@@ -0,0 +1,228 @@
+#[cfg(feature = "locale-extract")]
+mod extract;
+
+#[cfg(feature = "locale-extract")]
+pub use extract::*;
+
+use assets::{asset_events, load_asset, parse_asset, AssetEvent, AssetId};
+use rustc_hash::FxHashMap;
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::collections::BTreeSet;
+
+/// Translated strings by key, parsed from files with a `key = value` per line.
+/// Empty lines and lines starting with `#` are ignored, `\n` in a value is a line break
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct LocaleTable {
+    entries: FxHashMap<String, String>,
+}
+
+impl LocaleTable {
+    /// Parses a locale file, it can be used as parser for `parse_asset` or `AssetList`
+    pub fn parse(id: &str, data: &[u8]) -> Result<Self, String> {
+        let text = std::str::from_utf8(data)
+            .map_err(|e| format!("Cannot parse locale file '{id}': {e}"))?;
+
+        let mut entries = FxHashMap::default();
+        for (n, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let (key, value) = line.split_once('=').ok_or_else(|| {
+                format!(
+                    "Cannot parse locale file '{id}': missing '=' on line {}",
+                    n + 1
+                )
+            })?;
+
+            let key = key.trim();
+            if key.is_empty() {
+                return Err(format!(
+                    "Cannot parse locale file '{id}': empty key on line {}",
+                    n + 1
+                ));
+            }
+
+            entries.insert(key.to_string(), value.trim().replace("\\n", "\n"));
+        }
+
+        Ok(Self { entries })
+    }
+
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.entries.get(key).map(|s| s.as_str())
+    }
+
+    pub fn insert(&mut self, key: &str, value: &str) {
+        self.entries.insert(key.to_string(), value.to_string());
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+/// Locale loaded with the asset loader, the table is parsed again when the file is reloaded
+/// (`reload_asset` or the `assets-watch` feature), so translators can edit it during playtests.
+/// The keys requested but not found in the table are collected in the missing report.
+pub struct Localization {
+    id: Option<AssetId>,
+    table: Option<LocaleTable>,
+    missing: RefCell<BTreeSet<String>>,
+    highlight_missing: bool,
+}
+
+impl Localization {
+    /// Loads the locale file, call `update` each frame to parse it once loaded
+    pub fn load(path: &str) -> Self {
+        Self {
+            id: Some(load_asset(path)),
+            table: None,
+            missing: RefCell::new(BTreeSet::new()),
+            highlight_missing: false,
+        }
+    }
+
+    /// Uses a table already parsed, it's never reloaded
+    pub fn from_table(table: LocaleTable) -> Self {
+        Self {
+            id: None,
+            table: Some(table),
+            missing: RefCell::new(BTreeSet::new()),
+            highlight_missing: false,
+        }
+    }
+
+    /// Returns missing keys as `[!key]` instead of the key to spot them on screen
+    pub fn with_highlight_missing(mut self, enabled: bool) -> Self {
+        self.highlight_missing = enabled;
+        self
+    }
+
+    pub fn set_highlight_missing(&mut self, enabled: bool) {
+        self.highlight_missing = enabled;
+    }
+
+    /// Parses the file when it's loaded or reloaded, returns `true` if the table changed.
+    /// It must be called once per frame to not miss the reload events
+    pub fn update(&mut self) -> Result<bool, String> {
+        let Some(id) = self.id else {
+            return Ok(false);
+        };
+
+        let reloaded = asset_events()
+            .iter()
+            .any(|evt| matches!(evt, AssetEvent::Loaded(loaded) if *loaded == id));
+        if self.table.is_some() && !reloaded {
+            return Ok(false);
+        }
+
+        // the file is kept to parse it again after a reload
+        let Some(table) = parse_asset(&id, LocaleTable::parse, true)? else {
+            return Ok(false);
+        };
+
+        self.table = Some(table);
+        // the new table may have the keys that were missing
+        self.missing.borrow_mut().clear();
+        Ok(true)
+    }
+
+    pub fn is_loaded(&self) -> bool {
+        self.table.is_some()
+    }
+
+    pub fn table(&self) -> Option<&LocaleTable> {
+        self.table.as_ref()
+    }
+
+    /// Returns the translation of the key, or the key itself if it's missing.
+    /// Nothing is reported while the table is still loading
+    #[track_caller]
+    pub fn tr<'a>(&'a self, key: &'a str) -> Cow<'a, str> {
+        #[cfg(feature = "locale-extract")]
+        extract::register_key_at(key, std::panic::Location::caller());
+
+        let Some(table) = &self.table else {
+            return Cow::Borrowed(key);
+        };
+
+        if let Some(value) = table.get(key) {
+            return Cow::Borrowed(value);
+        }
+
+        self.missing.borrow_mut().insert(key.to_string());
+        if self.highlight_missing {
+            Cow::Owned(format!("[!{key}]"))
+        } else {
+            Cow::Borrowed(key)
+        }
+    }
+
+    /// Keys requested but not found in the table, sorted
+    pub fn missing_keys(&self) -> Vec<String> {
+        self.missing.borrow().iter().cloned().collect()
+    }
+
+    /// Missing keys, one per line, ready to be logged or saved for the translators
+    pub fn missing_report(&self) -> String {
+        let missing = self.missing.borrow();
+        let mut report = format!("{} missing keys", missing.len());
+        missing.iter().for_each(|key| {
+            report.push('\n');
+            report.push_str(key);
+        });
+        report
+    }
+
+    pub fn clear_missing(&self) {
+        self.missing.borrow_mut().clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let data = b"# menu\nmenu.play = Play\n\nmenu.quit=Quit game\nintro = Hello\\nWorld\n";
+        let table = LocaleTable::parse("en.txt", data).unwrap();
+        assert_eq!(table.len(), 3);
+        assert_eq!(table.get("menu.play"), Some("Play"));
+        assert_eq!(table.get("menu.quit"), Some("Quit game"));
+        assert_eq!(table.get("intro"), Some("Hello\nWorld"));
+
+        assert!(LocaleTable::parse("en.txt", b"menu.play Play").is_err());
+        assert!(LocaleTable::parse("en.txt", b" = Play").is_err());
+    }
+
+    #[test]
+    fn test_missing_keys() {
+        let mut table = LocaleTable::default();
+        table.insert("menu.play", "Play");
+
+        let mut locale = Localization::from_table(table);
+        assert_eq!(locale.tr("menu.play"), "Play");
+        assert_eq!(locale.tr("menu.quit"), "menu.quit");
+        assert_eq!(locale.tr("menu.options"), "menu.options");
+        assert_eq!(locale.tr("menu.quit"), "menu.quit");
+        assert_eq!(locale.missing_keys(), vec!["menu.options", "menu.quit"]);
+        assert_eq!(
+            locale.missing_report(),
+            "2 missing keys\nmenu.options\nmenu.quit"
+        );
+
+        locale.set_highlight_missing(true);
+        assert_eq!(locale.tr("menu.quit"), "[!menu.quit]");
+
+        locale.clear_missing();
+        assert!(locale.missing_keys().is_empty());
+    }
+}
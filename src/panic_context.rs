@@ -0,0 +1,131 @@
+use atomic_refcell::AtomicRefCell;
+use once_cell::sync::Lazy;
+use std::fmt;
+
+static CONTEXT: Lazy<AtomicRefCell<Vec<(String, String)>>> =
+    Lazy::new(|| AtomicRefCell::new(vec![]));
+
+/// State of the game when a panic happens, the frame number and the key-value pairs
+/// set with `set_panic_context` (e.g. the current screen or level)
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PanicContext {
+    pub frame: u64,
+    pub values: Vec<(String, String)>,
+}
+
+impl fmt::Display for PanicContext {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "frame: {}", self.frame)?;
+        self.values
+            .iter()
+            .try_for_each(|(k, v)| write!(f, ", {k}: {v}"))
+    }
+}
+
+/// Adds or replaces a value included in the panic messages, e.g. `set_panic_context("screen", "menu")`
+pub fn set_panic_context(key: &str, value: impl ToString) {
+    let value = value.to_string();
+    let mut ctx = CONTEXT.borrow_mut();
+    match ctx.iter_mut().find(|(k, _)| k == key) {
+        Some((_, v)) => *v = value,
+        None => ctx.push((key.to_string(), value)),
+    }
+}
+
+pub fn remove_panic_context(key: &str) {
+    CONTEXT.borrow_mut().retain(|(k, _)| k != key);
+}
+
+pub fn clear_panic_context() {
+    CONTEXT.borrow_mut().clear();
+}
+
+/// Returns the current context, custom panic hooks and crash reporters can attach it to their reports
+pub fn panic_context() -> PanicContext {
+    // the panic can happen while the context is being changed
+    let values = CONTEXT
+        .try_borrow()
+        .map(|ctx| ctx.clone())
+        .unwrap_or_default();
+
+    PanicContext {
+        frame: corelib::time::frame_count(),
+        values,
+    }
+}
+
+/// Sets a panic hook that logs the context before calling the previous hook
+pub fn set_panic_context_hook() {
+    let prev = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        log::error!("Panic context: {}", panic_context());
+        prev(info);
+    }));
+}
+
+/// Unwraps the value or panics with the message and the panic context
+pub trait OrPanic<T> {
+    #[track_caller]
+    fn or_panic(self, msg: &str) -> T;
+}
+
+impl<T, E: fmt::Debug> OrPanic<T> for Result<T, E> {
+    #[track_caller]
+    fn or_panic(self, msg: &str) -> T {
+        match self {
+            Ok(value) => value,
+            Err(e) => panic!("{msg}: {e:?} ({})", panic_context()),
+        }
+    }
+}
+
+impl<T> OrPanic<T> for Option<T> {
+    #[track_caller]
+    fn or_panic(self, msg: &str) -> T {
+        match self {
+            Some(value) => value,
+            None => panic!("{msg} ({})", panic_context()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_context() {
+        set_panic_context("screen", "menu");
+        set_panic_context("level", 3);
+        set_panic_context("screen", "game");
+
+        let ctx = panic_context();
+        assert_eq!(
+            ctx.values,
+            vec![
+                ("screen".to_string(), "game".to_string()),
+                ("level".to_string(), "3".to_string()),
+            ]
+        );
+        assert_eq!(ctx.to_string(), "frame: 0, screen: game, level: 3");
+
+        let msg = std::panic::catch_unwind(|| Err::<(), _>("oops").or_panic("Cannot load"))
+            .unwrap_err()
+            .downcast::<String>()
+            .unwrap();
+        assert_eq!(
+            *msg,
+            "Cannot load: \"oops\" (frame: 0, screen: game, level: 3)"
+        );
+
+        remove_panic_context("screen");
+        assert_eq!(panic_context().to_string(), "frame: 0, level: 3");
+
+        clear_panic_context();
+        let msg = std::panic::catch_unwind(|| None::<u8>.or_panic("Missing"))
+            .unwrap_err()
+            .downcast::<String>()
+            .unwrap();
+        assert_eq!(*msg, "Missing (frame: 0)");
+    }
+}
@@ -0,0 +1,172 @@
+use crate::gfx;
+use crate::gfx::{
+    BindGroup, BindGroupLayout, BindingType, Buffer, Color, RenderPipeline, Renderer,
+};
+use crate::postfx::pfx::{create_pfx_pipeline, PostFx};
+use crate::postfx::sys::IOPostFxData;
+use encase::{ShaderType, UniformBuffer};
+
+// language=wgsl
+const FRAG: &str = r#"
+struct ColorGrade {
+    color: vec4<f32>,
+    mode: u32,
+    strength: f32,
+};
+
+@group(1) @binding(0)
+var<uniform> grade: ColorGrade;
+
+fn overlay(base: vec3<f32>, blend: vec3<f32>) -> vec3<f32> {
+    let low = 2.0 * base * blend;
+    let high = 1.0 - 2.0 * (1.0 - base) * (1.0 - blend);
+    return select(high, low, base < vec3<f32>(0.5));
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    let color = textureSample(t_texture, s_texture, in.uvs);
+
+    var graded = color.rgb * grade.color.rgb;
+    if grade.mode == 1u {
+        graded = overlay(color.rgb, grade.color.rgb);
+    }
+
+    let factor = grade.strength * grade.color.a;
+    return vec4<f32>(mix(color.rgb, graded, factor), color.a);
+}
+"#;
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum ColorGradeMode {
+    #[default]
+    Multiply,
+    Overlay,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ColorGradeParams {
+    pub color: Color,
+    pub mode: ColorGradeMode,
+    pub strength: f32,
+}
+
+impl Default for ColorGradeParams {
+    fn default() -> Self {
+        Self {
+            color: Color::WHITE,
+            mode: ColorGradeMode::Multiply,
+            strength: 1.0,
+        }
+    }
+}
+
+#[derive(ShaderType)]
+struct ColorGradeUniform {
+    color: Color,
+    mode: u32,
+    strength: f32,
+}
+
+impl From<ColorGradeParams> for ColorGradeUniform {
+    fn from(params: ColorGradeParams) -> Self {
+        Self {
+            color: params.color,
+            mode: match params.mode {
+                ColorGradeMode::Multiply => 0,
+                ColorGradeMode::Overlay => 1,
+            },
+            strength: params.strength,
+        }
+    }
+}
+
+/// Global color overlay for the whole frame, see `DayNightCycle` to animate it
+pub struct ColorGradeFx {
+    pip: RenderPipeline,
+    ubo: Buffer,
+    bind_group: BindGroup,
+    ubs: UniformBuffer<[u8; 32]>,
+
+    last_params: ColorGradeParams,
+    pub params: ColorGradeParams,
+
+    pub enabled: bool,
+}
+
+impl ColorGradeFx {
+    pub fn new(params: ColorGradeParams) -> Result<Self, String> {
+        let pip = create_pfx_pipeline(FRAG, |builder| {
+            builder
+                .with_label("ColorGradeFx Pipeline")
+                // this is bind group 1
+                .with_bind_group_layout(
+                    BindGroupLayout::new()
+                        .with_entry(BindingType::uniform(0).with_fragment_visibility(true)),
+                )
+                .build()
+        })?;
+
+        let mut ubs = UniformBuffer::new([0; 32]);
+        ubs.write(&ColorGradeUniform::from(params))
+            .map_err(|e| e.to_string())?;
+
+        let ubo = gfx::create_uniform_buffer(ubs.as_ref())
+            .with_label("ColorGradeFx UBO")
+            .with_write_flag(true)
+            .build()?;
+
+        let bind_group = gfx::create_bind_group()
+            .with_label("ColorGradeFx BindGroup(1)")
+            .with_layout(pip.bind_group_layout_ref(1)?)
+            .with_uniform(0, &ubo)
+            .build()?;
+
+        Ok(Self {
+            pip,
+            ubo,
+            bind_group,
+            ubs,
+            last_params: params,
+            params,
+            enabled: true,
+        })
+    }
+}
+
+impl PostFx for ColorGradeFx {
+    fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    fn name(&self) -> &str {
+        "ColorGradeFx"
+    }
+
+    fn apply(&self, data: IOPostFxData) -> Result<bool, String> {
+        let mut renderer = Renderer::new();
+        renderer
+            .begin_pass()
+            .pipeline(&self.pip)
+            .bindings(&[data.input.bind_group, &self.bind_group])
+            .draw(0..6);
+
+        gfx::render_to_texture(data.output.tex, &renderer)?;
+        Ok(true)
+    }
+
+    fn update(&mut self) -> Result<(), String> {
+        if self.last_params != self.params {
+            self.ubs
+                .write(&ColorGradeUniform::from(self.params))
+                .map_err(|e| e.to_string())?;
+
+            gfx::write_buffer(&self.ubo)
+                .with_data(self.ubs.as_ref())
+                .build()?;
+            self.last_params = self.params;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,387 @@
+use crate::gfx;
+use crate::gfx::{BindGroup, BindGroupLayout, BindingType, Buffer, RenderPipeline, Renderer};
+use crate::math::{vec2, Vec2};
+use crate::postfx::pfx::{create_pfx_pipeline, PostFx};
+use crate::postfx::sys::IOPostFxData;
+use crate::time;
+use encase::{ShaderType, UniformBuffer};
+
+// language=wgsl
+const FRAG: &str = r#"
+struct Crt {
+    curvature: vec2<f32>,
+    mask: u32,
+    mask_intensity: f32,
+    scanline_intensity: f32,
+    scanline_size: f32,
+    scanline_offset: f32,
+    vignette: f32,
+};
+
+@group(1) @binding(0)
+var<uniform> crt: Crt;
+
+fn curve(uvs: vec2<f32>) -> vec2<f32> {
+    let centered = uvs * 2.0 - 1.0;
+    let offset = centered.yx * centered.yx * crt.curvature;
+    return (centered + centered * offset) * 0.5 + 0.5;
+}
+
+fn mask_color(pos: vec2<f32>) -> vec3<f32> {
+    let col = u32(pos.x) % 3u;
+    let stripe = vec3<f32>(f32(col == 0u), f32(col == 1u), f32(col == 2u));
+    var color = vec3<f32>(1.0);
+    switch crt.mask {
+        // aperture grille, continuous vertical stripes
+        case 1u: {
+            color = stripe;
+        }
+        // slot mask, stripes broken every few lines alternating per triad
+        case 2u: {
+            let triad = u32(pos.x / 3.0) % 2u;
+            let row = (u32(pos.y) + triad * 2u) % 4u;
+            color = stripe * f32(row != 0u);
+        }
+        // shadow mask, triads shifted every line
+        case 3u: {
+            let shifted = (u32(pos.x) + (u32(pos.y) % 2u) * 2u) % 3u;
+            color = vec3<f32>(f32(shifted == 0u), f32(shifted == 1u), f32(shifted == 2u));
+        }
+        default: {}
+    }
+    return color;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    let tex_size = vec2<f32>(textureDimensions(t_texture));
+    let uvs = curve(in.uvs);
+    let color = textureSample(t_texture, s_texture, uvs);
+
+    let outside = any(uvs < vec2<f32>(0.0)) || any(uvs > vec2<f32>(1.0));
+    if outside {
+        return vec4<f32>(0.0, 0.0, 0.0, 1.0);
+    }
+
+    let pos = uvs * tex_size;
+    let line = (pos.y + crt.scanline_offset) / max(crt.scanline_size, 1.0);
+    let scan = 1.0 - crt.scanline_intensity * (0.5 + 0.5 * cos(line * 6.28318530718));
+
+    let mask = mix(vec3<f32>(1.0), mask_color(pos), crt.mask_intensity);
+
+    let edges = uvs * (1.0 - uvs.yx);
+    let vignette = pow(max(edges.x * edges.y * 16.0, 0.0001), crt.vignette);
+
+    return vec4<f32>(color.rgb * scan * mask * vignette, color.a);
+}
+"#;
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum CrtMask {
+    #[default]
+    None,
+    /// Vertical RGB stripes (Trinitron like)
+    Aperture,
+    /// RGB stripes with gaps alternated per column (consumer TVs)
+    Slot,
+    /// RGB dots shifted every line (PC monitors)
+    Shadow,
+}
+
+impl CrtMask {
+    fn name(&self) -> &str {
+        match self {
+            CrtMask::None => "none",
+            CrtMask::Aperture => "aperture",
+            CrtMask::Slot => "slot",
+            CrtMask::Shadow => "shadow",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "none" => CrtMask::None,
+            "aperture" => CrtMask::Aperture,
+            "slot" => CrtMask::Slot,
+            "shadow" => CrtMask::Shadow,
+            _ => return None,
+        })
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct CrtParams {
+    /// Barrel distortion per axis
+    pub curvature: Vec2,
+    pub mask: CrtMask,
+    pub mask_intensity: f32,
+    pub scanline_intensity: f32,
+    /// Scanline height in pixels
+    pub scanline_size: f32,
+    /// Scanline scroll speed in pixels per second
+    pub scanline_speed: f32,
+    /// Amount of intensity variation of the scanlines over time
+    pub scanline_flicker: f32,
+    pub vignette: f32,
+}
+
+impl Default for CrtParams {
+    fn default() -> Self {
+        Self {
+            curvature: vec2(0.1, 0.1),
+            mask: CrtMask::Aperture,
+            mask_intensity: 0.3,
+            scanline_intensity: 0.3,
+            scanline_size: 3.0,
+            scanline_speed: 0.0,
+            scanline_flicker: 0.0,
+            vignette: 0.2,
+        }
+    }
+}
+
+impl CrtParams {
+    pub fn consumer_tv() -> Self {
+        Self {
+            curvature: vec2(0.12, 0.16),
+            mask: CrtMask::Slot,
+            mask_intensity: 0.4,
+            scanline_intensity: 0.35,
+            scanline_size: 3.0,
+            scanline_speed: 8.0,
+            scanline_flicker: 0.1,
+            vignette: 0.3,
+        }
+    }
+
+    pub fn arcade() -> Self {
+        Self {
+            curvature: vec2(0.06, 0.08),
+            mask: CrtMask::Aperture,
+            mask_intensity: 0.5,
+            scanline_intensity: 0.5,
+            scanline_size: 4.0,
+            scanline_speed: 0.0,
+            scanline_flicker: 0.0,
+            vignette: 0.15,
+        }
+    }
+
+    pub fn pc_monitor() -> Self {
+        Self {
+            curvature: vec2(0.02, 0.02),
+            mask: CrtMask::Shadow,
+            mask_intensity: 0.25,
+            scanline_intensity: 0.15,
+            scanline_size: 2.0,
+            scanline_speed: 0.0,
+            scanline_flicker: 0.0,
+            vignette: 0.1,
+        }
+    }
+
+    /// Serializes the params as `key=value` lines
+    pub fn to_preset(&self) -> String {
+        format!(
+            "curvature_x={}\ncurvature_y={}\nmask={}\nmask_intensity={}\nscanline_intensity={}\nscanline_size={}\nscanline_speed={}\nscanline_flicker={}\nvignette={}\n",
+            self.curvature.x,
+            self.curvature.y,
+            self.mask.name(),
+            self.mask_intensity,
+            self.scanline_intensity,
+            self.scanline_size,
+            self.scanline_speed,
+            self.scanline_flicker,
+            self.vignette
+        )
+    }
+
+    /// Parses a preset created with `to_preset`, missing keys use the default values
+    pub fn from_preset(preset: &str) -> Result<Self, String> {
+        let mut params = Self::default();
+        for (n, line) in preset.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let (key, value) = line
+                .split_once('=')
+                .ok_or_else(|| format!("Invalid CRT preset line {}: '{}'", n + 1, line))?;
+            let (key, value) = (key.trim(), value.trim());
+
+            if key == "mask" {
+                params.mask = CrtMask::from_name(value)
+                    .ok_or_else(|| format!("Invalid CRT mask '{}'", value))?;
+                continue;
+            }
+
+            let num = value
+                .parse::<f32>()
+                .map_err(|e| format!("Invalid CRT preset value for '{}': {}", key, e))?;
+            match key {
+                "curvature_x" => params.curvature.x = num,
+                "curvature_y" => params.curvature.y = num,
+                "mask_intensity" => params.mask_intensity = num,
+                "scanline_intensity" => params.scanline_intensity = num,
+                "scanline_size" => params.scanline_size = num,
+                "scanline_speed" => params.scanline_speed = num,
+                "scanline_flicker" => params.scanline_flicker = num,
+                "vignette" => params.vignette = num,
+                _ => return Err(format!("Unknown CRT preset key '{}'", key)),
+            }
+        }
+
+        Ok(params)
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, ShaderType)]
+struct CrtUniform {
+    curvature: Vec2,
+    mask: u32,
+    mask_intensity: f32,
+    scanline_intensity: f32,
+    scanline_size: f32,
+    scanline_offset: f32,
+    vignette: f32,
+}
+
+impl CrtUniform {
+    fn new(params: &CrtParams, elapsed: f32) -> Self {
+        let flicker = 1.0 - params.scanline_flicker * (0.5 + 0.5 * (elapsed * 60.0).sin());
+        Self {
+            curvature: params.curvature,
+            mask: match params.mask {
+                CrtMask::None => 0,
+                CrtMask::Aperture => 1,
+                CrtMask::Slot => 2,
+                CrtMask::Shadow => 3,
+            },
+            mask_intensity: params.mask_intensity,
+            scanline_intensity: params.scanline_intensity * flicker,
+            scanline_size: params.scanline_size,
+            scanline_offset: -elapsed * params.scanline_speed,
+            vignette: params.vignette,
+        }
+    }
+}
+
+pub struct CrtFx {
+    pip: RenderPipeline,
+    ubo: Buffer,
+    bind_group: BindGroup,
+    ubs: UniformBuffer<[u8; 32]>,
+
+    last_uniform: CrtUniform,
+    pub params: CrtParams,
+
+    pub enabled: bool,
+}
+
+impl CrtFx {
+    pub fn new(params: CrtParams) -> Result<Self, String> {
+        let pip = create_pfx_pipeline(FRAG, |builder| {
+            builder
+                .with_label("CrtFx Pipeline")
+                // this is bind group 1
+                .with_bind_group_layout(
+                    BindGroupLayout::new()
+                        .with_entry(BindingType::uniform(0).with_fragment_visibility(true)),
+                )
+                .build()
+        })?;
+
+        let uniform = CrtUniform::new(&params, time::elapsed_f32());
+        let mut ubs = UniformBuffer::new([0; 32]);
+        ubs.write(&uniform).map_err(|e| e.to_string())?;
+
+        let ubo = gfx::create_uniform_buffer(ubs.as_ref())
+            .with_label("CrtFx UBO")
+            .with_write_flag(true)
+            .build()?;
+
+        let bind_group = gfx::create_bind_group()
+            .with_label("CrtFx BindGroup(1)")
+            .with_layout(pip.bind_group_layout_ref(1)?)
+            .with_uniform(0, &ubo)
+            .build()?;
+
+        Ok(Self {
+            pip,
+            ubo,
+            bind_group,
+            ubs,
+            last_uniform: uniform,
+            params,
+            enabled: true,
+        })
+    }
+}
+
+impl PostFx for CrtFx {
+    fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    fn name(&self) -> &str {
+        "CrtFx"
+    }
+
+    fn apply(&self, data: IOPostFxData) -> Result<bool, String> {
+        let mut renderer = Renderer::new();
+        renderer
+            .begin_pass()
+            .pipeline(&self.pip)
+            .bindings(&[data.input.bind_group, &self.bind_group])
+            .draw(0..6);
+
+        gfx::render_to_texture(data.output.tex, &renderer)?;
+        Ok(true)
+    }
+
+    fn update(&mut self) -> Result<(), String> {
+        // the scanlines animation changes the uniform each frame
+        let uniform = CrtUniform::new(&self.params, time::elapsed_f32());
+        if self.last_uniform != uniform {
+            self.ubs.write(&uniform).map_err(|e| e.to_string())?;
+
+            gfx::write_buffer(&self.ubo)
+                .with_data(self.ubs.as_ref())
+                .build()?;
+            self.last_uniform = uniform;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_preset_roundtrip() {
+        let params = CrtParams::consumer_tv();
+        let loaded = CrtParams::from_preset(&params.to_preset()).unwrap();
+        assert_eq!(params, loaded);
+    }
+
+    #[test]
+    fn test_preset_partial() {
+        let params = CrtParams::from_preset("# comment\nmask=shadow\ncurvature_y = 0.5").unwrap();
+        assert_eq!(params.mask, CrtMask::Shadow);
+        assert_eq!(
+            params.curvature,
+            vec2(CrtParams::default().curvature.x, 0.5)
+        );
+    }
+
+    #[test]
+    fn test_preset_invalid() {
+        assert!(CrtParams::from_preset("mask=round").is_err());
+        assert!(CrtParams::from_preset("vignette=abc").is_err());
+        assert!(CrtParams::from_preset("unknown=1.0").is_err());
+        assert!(CrtParams::from_preset("vignette").is_err());
+    }
+}
@@ -0,0 +1,166 @@
+use crate::gfx::Color;
+use crate::postfx::ColorGradeFx;
+
+/// Drives a `ColorGradeFx` over the game time, the time of the day goes from 0.0 to 1.0
+/// (0.0 midnight, 0.5 midday). It's a plain struct so it can be stored as an ECS resource
+#[derive(Clone, Debug)]
+pub struct DayNightCycle {
+    day_length: f32,
+    time: f32,
+    paused: bool,
+    keys: Vec<(f32, Color)>,
+}
+
+impl Default for DayNightCycle {
+    fn default() -> Self {
+        Self::new(120.0)
+    }
+}
+
+impl DayNightCycle {
+    /// Creates a cycle that lasts `day_length` seconds with a default set of colors
+    pub fn new(day_length: f32) -> Self {
+        Self {
+            day_length: day_length.max(f32::EPSILON),
+            time: 0.5,
+            paused: false,
+            keys: vec![
+                (0.0, Color::rgb(0.25, 0.3, 0.55)),
+                (0.2, Color::rgb(0.3, 0.35, 0.6)),
+                (0.3, Color::rgb(1.0, 0.75, 0.6)),
+                (0.4, Color::WHITE),
+                (0.65, Color::WHITE),
+                (0.75, Color::rgb(1.0, 0.6, 0.45)),
+                (0.85, Color::rgb(0.3, 0.35, 0.6)),
+            ],
+        }
+    }
+
+    /// Replaces the colors used along the day, each key is the time of the day and its color
+    pub fn with_keys(mut self, keys: &[(f32, Color)]) -> Self {
+        self.set_keys(keys);
+        self
+    }
+
+    pub fn set_keys(&mut self, keys: &[(f32, Color)]) {
+        self.keys = keys.iter().map(|(t, c)| (t.rem_euclid(1.0), *c)).collect();
+        self.keys.sort_by(|a, b| a.0.total_cmp(&b.0));
+    }
+
+    pub fn set_time(&mut self, time: f32) {
+        self.time = time.rem_euclid(1.0);
+    }
+
+    pub fn time(&self) -> f32 {
+        self.time
+    }
+
+    pub fn set_day_length(&mut self, seconds: f32) {
+        self.day_length = seconds.max(f32::EPSILON);
+    }
+
+    pub fn day_length(&self) -> f32 {
+        self.day_length
+    }
+
+    pub fn set_paused(&mut self, paused: bool) {
+        self.paused = paused;
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Advances the time of the day
+    pub fn tick(&mut self, delta: f32) {
+        if self.paused {
+            return;
+        }
+
+        self.set_time(self.time + delta / self.day_length);
+    }
+
+    /// Color for the current time of the day
+    pub fn color(&self) -> Color {
+        self.color_at(self.time)
+    }
+
+    pub fn color_at(&self, time: f32) -> Color {
+        let (first, last) = match (self.keys.first(), self.keys.last()) {
+            (Some(first), Some(last)) => (*first, *last),
+            _ => return Color::WHITE,
+        };
+
+        let time = time.rem_euclid(1.0);
+
+        // find the surrounding keys wrapping around midnight
+        let next_idx = self.keys.iter().position(|(t, _)| *t > time);
+        let (from, to) = match next_idx {
+            Some(0) | None => (last, (first.0 + 1.0, first.1)),
+            Some(idx) => (self.keys[idx - 1], self.keys[idx]),
+        };
+
+        let from_time = if from.0 > time { from.0 - 1.0 } else { from.0 };
+        let to_time = if next_idx == Some(0) {
+            to.0 - 1.0
+        } else {
+            to.0
+        };
+        let len = to_time - from_time;
+        if len <= f32::EPSILON {
+            return from.1;
+        }
+
+        let progress = ((time - from_time) / len).clamp(0.0, 1.0);
+        // color's sub is clamped so we cannot use `from + (to - from) * progress`
+        from.1 * (1.0 - progress) + to.1 * progress
+    }
+
+    /// Sets the current color to the effect, `ColorGradeFx::update` must be called after this
+    pub fn apply(&self, fx: &mut ColorGradeFx) {
+        fx.params.color = self.color();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_color(a: Color, b: Color) {
+        let diff = a
+            .to_rgba()
+            .into_iter()
+            .zip(b.to_rgba())
+            .map(|(a, b)| (a - b).abs());
+        assert!(diff.fold(0.0, f32::max) < 0.0001, "{a:?} != {b:?}");
+    }
+
+    #[test]
+    fn test_color_between_keys() {
+        let cycle = DayNightCycle::new(10.0).with_keys(&[(0.0, Color::BLACK), (0.5, Color::WHITE)]);
+        assert_color(cycle.color_at(0.0), Color::BLACK);
+        assert_color(cycle.color_at(0.25), Color::rgb(0.5, 0.5, 0.5));
+        assert_color(cycle.color_at(0.5), Color::WHITE);
+    }
+
+    #[test]
+    fn test_color_wraps_midnight() {
+        let cycle =
+            DayNightCycle::new(10.0).with_keys(&[(0.25, Color::BLACK), (0.75, Color::WHITE)]);
+        assert_color(cycle.color_at(0.0), Color::rgb(0.5, 0.5, 0.5));
+        assert_color(cycle.color_at(0.95), Color::rgb(0.6, 0.6, 0.6));
+        assert_color(cycle.color_at(0.1), Color::rgb(0.3, 0.3, 0.3));
+    }
+
+    #[test]
+    fn test_tick() {
+        let mut cycle = DayNightCycle::new(10.0);
+        cycle.set_time(0.9);
+        cycle.tick(2.0);
+        assert!((cycle.time() - 0.1).abs() < 0.0001);
+
+        cycle.set_paused(true);
+        cycle.tick(2.0);
+        assert!((cycle.time() - 0.1).abs() < 0.0001);
+    }
+}
@@ -0,0 +1,195 @@
+use crate::gfx;
+use crate::gfx::{BindGroup, BindGroupLayout, BindingType, Buffer, RenderPipeline, Renderer};
+use crate::postfx::pfx::{create_pfx_pipeline, PostFx};
+use crate::postfx::sys::IOPostFxData;
+use corelib::gfx::TextureFilter;
+use encase::{ShaderType, UniformBuffer};
+
+// language=wgsl
+const FRAG: &str = r#"
+struct Dither {
+    levels: f32,
+    downscale: f32,
+    mode: u32,
+    spread: f32,
+};
+
+@group(1) @binding(0)
+var<uniform> dither: Dither;
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    let tex_size = vec2<f32>(textureDimensions(t_texture));
+    let scale = max(dither.downscale, 1.0);
+    let pixel = floor((in.uvs * tex_size) / scale);
+    let uvs = ((pixel + 0.5) * scale) / tex_size;
+    let color = textureSample(t_texture, s_texture, uvs);
+
+    var threshold = 0.0;
+    if dither.mode == 0u {
+        var bayer = array<f32, 16>(
+            0.0, 8.0, 2.0, 10.0,
+            12.0, 4.0, 14.0, 6.0,
+            3.0, 11.0, 1.0, 9.0,
+            15.0, 7.0, 13.0, 5.0
+        );
+        let p = vec2<u32>(pixel) % 4u;
+        threshold = (bayer[p.y * 4u + p.x] + 0.5) / 16.0;
+    } else {
+        // interleaved gradient noise
+        threshold = fract(52.9829189 * fract(dot(pixel, vec2<f32>(0.06711056, 0.00583715))));
+    }
+
+    let steps = max(dither.levels - 1.0, 1.0);
+    let offset = (threshold - 0.5) * dither.spread;
+    let rgb = floor(color.rgb * steps + 0.5 + offset) / steps;
+    return vec4<f32>(clamp(rgb, vec3<f32>(0.0), vec3<f32>(1.0)), color.a);
+}
+"#;
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum DitherMode {
+    /// Ordered dithering using a 4x4 Bayer matrix
+    #[default]
+    Bayer,
+    /// Blue-noise like dithering, less regular than the Bayer pattern
+    BlueNoise,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct DitherParams {
+    pub mode: DitherMode,
+    /// Number of colors per channel
+    pub levels: u32,
+    /// Size of the dithered pixels, use the same size as `PixelateFx` to combine them
+    pub downscale: f32,
+    /// Strength of the dithering pattern, 0.0 means only quantization
+    pub spread: f32,
+}
+
+impl Default for DitherParams {
+    fn default() -> Self {
+        Self {
+            mode: DitherMode::Bayer,
+            levels: 4,
+            downscale: 1.0,
+            spread: 1.0,
+        }
+    }
+}
+
+#[derive(ShaderType)]
+struct DitherUniform {
+    levels: f32,
+    downscale: f32,
+    mode: u32,
+    spread: f32,
+}
+
+impl From<DitherParams> for DitherUniform {
+    fn from(params: DitherParams) -> Self {
+        Self {
+            levels: params.levels.max(2) as _,
+            downscale: params.downscale,
+            mode: match params.mode {
+                DitherMode::Bayer => 0,
+                DitherMode::BlueNoise => 1,
+            },
+            spread: params.spread,
+        }
+    }
+}
+
+pub struct DitherFx {
+    pip: RenderPipeline,
+    ubo: Buffer,
+    bind_group: BindGroup,
+    ubs: UniformBuffer<[u8; 16]>,
+
+    last_params: DitherParams,
+    pub params: DitherParams,
+
+    pub enabled: bool,
+}
+
+impl DitherFx {
+    pub fn new(params: DitherParams) -> Result<Self, String> {
+        let pip = create_pfx_pipeline(FRAG, |builder| {
+            builder
+                .with_label("DitherFx Pipeline")
+                // this is bind group 1
+                .with_bind_group_layout(
+                    BindGroupLayout::new()
+                        .with_entry(BindingType::uniform(0).with_fragment_visibility(true)),
+                )
+                .build()
+        })?;
+
+        // uniform buffer storage
+        let mut ubs = UniformBuffer::new([0; 16]);
+        ubs.write(&DitherUniform::from(params))
+            .map_err(|e| e.to_string())?;
+
+        let ubo = gfx::create_uniform_buffer(ubs.as_ref())
+            .with_label("DitherFx UBO")
+            .with_write_flag(true)
+            .build()?;
+
+        let bind_group = gfx::create_bind_group()
+            .with_label("DitherFx BindGroup(1)")
+            .with_layout(pip.bind_group_layout_ref(1)?)
+            .with_uniform(0, &ubo)
+            .build()?;
+
+        Ok(Self {
+            pip,
+            ubo,
+            bind_group,
+            ubs,
+            last_params: params,
+            params,
+            enabled: true,
+        })
+    }
+}
+
+impl PostFx for DitherFx {
+    fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    fn name(&self) -> &str {
+        "DitherFx"
+    }
+
+    fn apply(&self, data: IOPostFxData) -> Result<bool, String> {
+        let mut renderer = Renderer::new();
+        renderer
+            .begin_pass()
+            .pipeline(&self.pip)
+            .bindings(&[data.input.bind_group, &self.bind_group])
+            .draw(0..6);
+
+        gfx::render_to_texture(data.output.tex, &renderer)?;
+        Ok(true)
+    }
+
+    fn update(&mut self) -> Result<(), String> {
+        if self.last_params != self.params {
+            self.ubs
+                .write(&DitherUniform::from(self.params))
+                .map_err(|e| e.to_string())?;
+
+            gfx::write_buffer(&self.ubo)
+                .with_data(self.ubs.as_ref())
+                .build()?;
+            self.last_params = self.params;
+        }
+
+        Ok(())
+    }
+
+    fn texture_filter(&self) -> Option<TextureFilter> {
+        Some(TextureFilter::Nearest)
+    }
+}
@@ -1,11 +1,17 @@
 mod alpha_fx;
 mod blur_fx;
+mod color_grade_fx;
 mod color_replace_fx;
+mod crt_fx;
+mod day_night;
+mod dither_fx;
 mod gray_scale_fx;
+mod outline_fx;
 mod pfx;
 mod pixelate_fx;
 mod rgb_split_fx;
 mod sys;
+mod water_fx;
 
 use crate::gfx;
 use crate::gfx::AsRenderer;
@@ -13,12 +19,18 @@ use sys::SYS;
 
 pub use alpha_fx::*;
 pub use blur_fx::*;
+pub use color_grade_fx::*;
 pub use color_replace_fx::*;
+pub use crt_fx::*;
+pub use day_night::*;
+pub use dither_fx::*;
 pub use gray_scale_fx::*;
+pub use outline_fx::*;
 pub use pfx::*;
 pub use pixelate_fx::*;
 pub use rgb_split_fx::*;
 pub use sys::{IOPostFxData, InOutTextures};
+pub use water_fx::*;
 
 #[inline]
 pub fn render_to_pfx_frame<R>(renderer: &R) -> Result<(), String>
@@ -0,0 +1,202 @@
+use crate::app::window_size;
+use crate::gfx::{
+    self, AsRenderer, BindGroup, BindGroupLayout, BindingType, Buffer, Color, RenderPipeline,
+    RenderTexture, Renderer, Sampler, TextureFilter,
+};
+use crate::math::UVec2;
+use crate::postfx::pfx::{create_pfx_pipeline, PostFx};
+use crate::postfx::sys::IOPostFxData;
+use encase::{ShaderType, UniformBuffer};
+
+// language=wgsl
+const FRAG: &str = r#"
+struct Outline {
+    color: vec4<f32>,
+    thickness: f32,
+};
+
+@group(1) @binding(0)
+var<uniform> outline: Outline;
+@group(1) @binding(1)
+var t_mask: texture_2d<f32>;
+@group(1) @binding(2)
+var s_mask: sampler;
+
+const DIRECTIONS: i32 = 16;
+const TAU: f32 = 6.28318530718;
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    let color = textureSample(t_texture, s_texture, in.uvs);
+    let center = textureSampleLevel(t_mask, s_mask, in.uvs, 0.0).a;
+
+    let texel = 1.0 / vec2<f32>(textureDimensions(t_mask));
+    var coverage = 0.0;
+    for (var i = 0; i < DIRECTIONS; i++) {
+        let angle = TAU * f32(i) / f32(DIRECTIONS);
+        let dir = vec2<f32>(cos(angle), sin(angle)) * texel * outline.thickness;
+        coverage = max(coverage, textureSampleLevel(t_mask, s_mask, in.uvs + dir, 0.0).a);
+        coverage = max(coverage, textureSampleLevel(t_mask, s_mask, in.uvs + dir * 0.5, 0.0).a);
+    }
+
+    // only the pixels around the flagged drawables, not the ones covered by them
+    let factor = coverage * (1.0 - center) * outline.color.a;
+    return mix(color, vec4<f32>(outline.color.rgb, 1.0), factor);
+}
+"#;
+
+#[derive(Copy, Clone, Debug, PartialEq, ShaderType)]
+pub struct OutlineParams {
+    pub color: Color,
+    /// Thickness in pixels
+    #[align(16)]
+    pub thickness: f32,
+}
+
+impl Default for OutlineParams {
+    fn default() -> Self {
+        Self {
+            color: Color::YELLOW,
+            thickness: 2.0,
+        }
+    }
+}
+
+/// Draws an outline around the drawables rendered into its mask,
+/// useful to highlight selected or hovered elements
+pub struct OutlineFx {
+    pip: RenderPipeline,
+    ubo: Buffer,
+    sampler: Sampler,
+    mask: RenderTexture,
+    bind_group: BindGroup,
+    ubs: UniformBuffer<[u8; 32]>,
+
+    last_params: OutlineParams,
+    pub params: OutlineParams,
+
+    pub enabled: bool,
+}
+
+impl OutlineFx {
+    pub fn new(params: OutlineParams) -> Result<Self, String> {
+        let pip = create_pfx_pipeline(FRAG, |builder| {
+            builder
+                .with_label("OutlineFx Pipeline")
+                // this is bind group 1
+                .with_bind_group_layout(
+                    BindGroupLayout::new()
+                        .with_entry(BindingType::uniform(0).with_fragment_visibility(true))
+                        .with_entry(BindingType::texture(1).with_fragment_visibility(true))
+                        .with_entry(BindingType::sampler(2).with_fragment_visibility(true)),
+                )
+                .build()
+        })?;
+
+        let mut ubs = UniformBuffer::new([0; 32]);
+        ubs.write(&params).map_err(|e| e.to_string())?;
+
+        let ubo = gfx::create_uniform_buffer(ubs.as_ref())
+            .with_label("OutlineFx UBO")
+            .with_write_flag(true)
+            .build()?;
+
+        let sampler = gfx::create_sampler()
+            .with_label("OutlineFx Mask Sampler")
+            .with_min_filter(TextureFilter::Nearest)
+            .with_mag_filter(TextureFilter::Nearest)
+            .build()?;
+
+        let mask = create_mask_rt(window_size().as_uvec2())?;
+        let bind_group = create_bind_group(&pip, &ubo, &mask, &sampler)?;
+
+        Ok(Self {
+            pip,
+            ubo,
+            sampler,
+            mask,
+            bind_group,
+            ubs,
+            last_params: params,
+            params,
+            enabled: true,
+        })
+    }
+
+    /// Texture used as mask, any pixel with alpha will be outlined
+    pub fn mask(&self) -> &RenderTexture {
+        &self.mask
+    }
+
+    /// Clears the mask and renders the flagged drawables on it
+    pub fn render_mask<R: AsRenderer>(&self, renderer: &R) -> Result<(), String> {
+        let mut clear = Renderer::new();
+        clear.begin_pass().clear_color(Color::TRANSPARENT);
+        gfx::render_to_texture(&self.mask, &clear)?;
+        gfx::render_to_texture(&self.mask, renderer)
+    }
+}
+
+impl PostFx for OutlineFx {
+    fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    fn name(&self) -> &str {
+        "OutlineFx"
+    }
+
+    fn apply(&self, data: IOPostFxData) -> Result<bool, String> {
+        let mut renderer = Renderer::new();
+        renderer
+            .begin_pass()
+            .pipeline(&self.pip)
+            .bindings(&[data.input.bind_group, &self.bind_group])
+            .draw(0..6);
+
+        gfx::render_to_texture(data.output.tex, &renderer)?;
+        Ok(true)
+    }
+
+    fn update(&mut self) -> Result<(), String> {
+        // keep the mask the same size as the frame
+        let size = window_size().as_uvec2();
+        if self.mask.size().as_uvec2() != size {
+            self.mask = create_mask_rt(size)?;
+            self.bind_group = create_bind_group(&self.pip, &self.ubo, &self.mask, &self.sampler)?;
+        }
+
+        if self.last_params != self.params {
+            self.ubs.write(&self.params).map_err(|e| e.to_string())?;
+
+            gfx::write_buffer(&self.ubo)
+                .with_data(self.ubs.as_ref())
+                .build()?;
+            self.last_params = self.params;
+        }
+
+        Ok(())
+    }
+}
+
+fn create_mask_rt(size: UVec2) -> Result<RenderTexture, String> {
+    gfx::create_render_texture()
+        .with_label("OutlineFx Mask RenderTexture")
+        .with_size(size.x, size.y)
+        .build()
+}
+
+fn create_bind_group(
+    pip: &RenderPipeline,
+    ubo: &Buffer,
+    mask: &RenderTexture,
+    sampler: &Sampler,
+) -> Result<BindGroup, String> {
+    gfx::create_bind_group()
+        .with_label("OutlineFx BindGroup(1)")
+        .with_layout(pip.bind_group_layout_ref(1)?)
+        .with_uniform(0, ubo)
+        .with_texture(1, mask.texture())
+        .with_sampler(2, sampler)
+        .build()
+}
@@ -0,0 +1,193 @@
+use crate::gfx;
+use crate::gfx::{
+    BindGroup, BindGroupLayout, BindingType, Buffer, Color, RenderPipeline, Renderer,
+};
+use crate::postfx::pfx::{create_pfx_pipeline, PostFx};
+use crate::postfx::sys::IOPostFxData;
+use crate::time;
+use encase::{ShaderType, UniformBuffer};
+
+// language=wgsl
+const FRAG: &str = r#"
+struct Water {
+    tint: vec4<f32>,
+    water_line: f32,
+    amplitude: f32,
+    frequency: f32,
+    phase: f32,
+    reflection_alpha: f32,
+    depth_fade: f32,
+};
+
+@group(1) @binding(0)
+var<uniform> water: Water;
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    let tex_size = vec2<f32>(textureDimensions(t_texture));
+    let pos = in.uvs * tex_size;
+    let depth = pos.y - water.water_line;
+
+    // mirror the region above the water line with some ripples
+    let ripple = vec2<f32>(
+        sin(pos.y * water.frequency + water.phase),
+        cos(pos.x * water.frequency * 0.5 + water.phase) * 0.5
+    ) * water.amplitude * min(depth * 0.1, 1.0);
+    let mirrored = vec2<f32>(pos.x, water.water_line * 2.0 - pos.y) + ripple;
+    let uvs = clamp(mirrored / tex_size, vec2<f32>(0.0), vec2<f32>(1.0));
+
+    let color = textureSample(t_texture, s_texture, in.uvs);
+    let reflected = textureSample(t_texture, s_texture, uvs);
+
+    let fade = exp(-max(depth, 0.0) * water.depth_fade);
+    let water_rgb = mix(water.tint.rgb, reflected.rgb, water.reflection_alpha * fade);
+    let under = vec4<f32>(mix(color.rgb, water_rgb, water.tint.a), max(color.a, water.tint.a));
+
+    return select(color, under, depth > 0.0);
+}
+"#;
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct WaterParams {
+    /// Vertical position in pixels of the water surface
+    pub water_line: f32,
+    /// Ripple distortion in pixels
+    pub ripple_amplitude: f32,
+    pub ripple_frequency: f32,
+    pub ripple_speed: f32,
+    /// Color of the water, the alpha is the water opacity
+    pub tint: Color,
+    pub reflection_alpha: f32,
+    /// How fast the reflection fades with the depth, 0.0 means no fading
+    pub depth_fade: f32,
+}
+
+impl Default for WaterParams {
+    fn default() -> Self {
+        Self {
+            water_line: 300.0,
+            ripple_amplitude: 4.0,
+            ripple_frequency: 0.2,
+            ripple_speed: 3.0,
+            tint: Color::rgba(0.1, 0.3, 0.5, 0.8),
+            reflection_alpha: 0.6,
+            depth_fade: 0.005,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, ShaderType)]
+struct WaterUniform {
+    tint: Color,
+    water_line: f32,
+    amplitude: f32,
+    frequency: f32,
+    phase: f32,
+    reflection_alpha: f32,
+    depth_fade: f32,
+}
+
+impl WaterUniform {
+    fn new(params: &WaterParams, elapsed: f32) -> Self {
+        Self {
+            tint: params.tint,
+            water_line: params.water_line,
+            amplitude: params.ripple_amplitude,
+            frequency: params.ripple_frequency,
+            phase: elapsed * params.ripple_speed,
+            reflection_alpha: params.reflection_alpha,
+            depth_fade: params.depth_fade,
+        }
+    }
+}
+
+/// Reflects the frame above the water line and composites it under the line with ripples,
+/// `Camera2D::local_to_screen` can be used to get the water line from world coordinates
+pub struct WaterFx {
+    pip: RenderPipeline,
+    ubo: Buffer,
+    bind_group: BindGroup,
+    ubs: UniformBuffer<[u8; 48]>,
+
+    last_uniform: WaterUniform,
+    pub params: WaterParams,
+
+    pub enabled: bool,
+}
+
+impl WaterFx {
+    pub fn new(params: WaterParams) -> Result<Self, String> {
+        let pip = create_pfx_pipeline(FRAG, |builder| {
+            builder
+                .with_label("WaterFx Pipeline")
+                // this is bind group 1
+                .with_bind_group_layout(
+                    BindGroupLayout::new()
+                        .with_entry(BindingType::uniform(0).with_fragment_visibility(true)),
+                )
+                .build()
+        })?;
+
+        let uniform = WaterUniform::new(&params, time::elapsed_f32());
+        let mut ubs = UniformBuffer::new([0; 48]);
+        ubs.write(&uniform).map_err(|e| e.to_string())?;
+
+        let ubo = gfx::create_uniform_buffer(ubs.as_ref())
+            .with_label("WaterFx UBO")
+            .with_write_flag(true)
+            .build()?;
+
+        let bind_group = gfx::create_bind_group()
+            .with_label("WaterFx BindGroup(1)")
+            .with_layout(pip.bind_group_layout_ref(1)?)
+            .with_uniform(0, &ubo)
+            .build()?;
+
+        Ok(Self {
+            pip,
+            ubo,
+            bind_group,
+            ubs,
+            last_uniform: uniform,
+            params,
+            enabled: true,
+        })
+    }
+}
+
+impl PostFx for WaterFx {
+    fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    fn name(&self) -> &str {
+        "WaterFx"
+    }
+
+    fn apply(&self, data: IOPostFxData) -> Result<bool, String> {
+        let mut renderer = Renderer::new();
+        renderer
+            .begin_pass()
+            .pipeline(&self.pip)
+            .bindings(&[data.input.bind_group, &self.bind_group])
+            .draw(0..6);
+
+        gfx::render_to_texture(data.output.tex, &renderer)?;
+        Ok(true)
+    }
+
+    fn update(&mut self) -> Result<(), String> {
+        // the ripples phase changes each frame
+        let uniform = WaterUniform::new(&self.params, time::elapsed_f32());
+        if self.last_uniform != uniform {
+            self.ubs.write(&uniform).map_err(|e| e.to_string())?;
+
+            gfx::write_buffer(&self.ubo)
+                .with_data(self.ubs.as_ref())
+                .build()?;
+            self.last_uniform = uniform;
+        }
+
+        Ok(())
+    }
+}
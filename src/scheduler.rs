@@ -0,0 +1,189 @@
+use crate::time::{self, Duration};
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::task::Poll;
+
+type StepFn = Box<dyn FnMut() -> bool>;
+
+struct Task {
+    id: u64,
+    step: StepFn,
+}
+
+/// Result of a task spawned on the `FrameScheduler`
+pub struct TaskHandle<T> {
+    id: u64,
+    result: Rc<RefCell<Option<T>>>,
+}
+
+impl<T> TaskHandle<T> {
+    pub fn is_done(&self) -> bool {
+        self.result.borrow().is_some()
+    }
+
+    /// Takes the result once the task is done
+    pub fn take(&self) -> Option<T> {
+        self.result.borrow_mut().take()
+    }
+}
+
+/// Cooperative scheduler for long jobs (pathfinding, map generation...) split in small steps.
+/// Each update runs the steps of the tasks in turns until the frame budget is spent, so the
+/// frame time stays stable without threads (works on wasm)
+pub struct FrameScheduler {
+    tasks: Vec<Task>,
+    budget: Duration,
+    next: usize,
+    count_ids: u64,
+}
+
+impl FrameScheduler {
+    pub fn new(budget: Duration) -> Self {
+        Self {
+            tasks: vec![],
+            budget,
+            next: 0,
+            count_ids: 0,
+        }
+    }
+
+    pub fn set_budget(&mut self, budget: Duration) {
+        self.budget = budget;
+    }
+
+    pub fn budget(&self) -> Duration {
+        self.budget
+    }
+
+    /// Adds a task, `step` is called until it returns `Poll::Ready`
+    pub fn spawn<T, F>(&mut self, mut step: F) -> TaskHandle<T>
+    where
+        T: 'static,
+        F: FnMut() -> Poll<T> + 'static,
+    {
+        let id = self.count_ids;
+        self.count_ids += 1;
+
+        let result = Rc::new(RefCell::new(None));
+        let task_result = result.clone();
+        self.tasks.push(Task {
+            id,
+            step: Box::new(move || match step() {
+                Poll::Ready(value) => {
+                    *task_result.borrow_mut() = Some(value);
+                    true
+                }
+                Poll::Pending => false,
+            }),
+        });
+
+        TaskHandle { id, result }
+    }
+
+    /// Removes the task if it's still running
+    pub fn cancel<T>(&mut self, handle: &TaskHandle<T>) {
+        if let Some(idx) = self.tasks.iter().position(|task| task.id == handle.id) {
+            self.remove(idx);
+        }
+    }
+
+    pub fn is_running<T>(&self, handle: &TaskHandle<T>) -> bool {
+        self.tasks.iter().any(|task| task.id == handle.id)
+    }
+
+    /// Number of tasks running
+    pub fn len(&self) -> usize {
+        self.tasks.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tasks.is_empty()
+    }
+
+    /// Runs steps until the budget is spent, at least one step is done each update
+    pub fn update(&mut self) {
+        let start = time::now();
+        while !self.tasks.is_empty() {
+            self.next %= self.tasks.len();
+            let done = (self.tasks[self.next].step)();
+            if done {
+                self.remove(self.next);
+            } else {
+                self.next += 1;
+            }
+
+            if start.elapsed() >= self.budget {
+                break;
+            }
+        }
+    }
+
+    fn remove(&mut self, idx: usize) {
+        self.tasks.remove(idx);
+        if idx < self.next {
+            self.next -= 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn counter(steps: u32) -> impl FnMut() -> Poll<u32> {
+        let mut count = 0;
+        move || {
+            count += 1;
+            if count >= steps {
+                Poll::Ready(count)
+            } else {
+                Poll::Pending
+            }
+        }
+    }
+
+    #[test]
+    fn test_complete_in_budget() {
+        let mut scheduler = FrameScheduler::new(Duration::from_secs(10));
+        let a = scheduler.spawn(counter(5));
+        let b = scheduler.spawn(counter(3));
+        scheduler.update();
+
+        assert!(scheduler.is_empty());
+        assert_eq!(a.take(), Some(5));
+        assert_eq!(b.take(), Some(3));
+        assert_eq!(b.take(), None);
+    }
+
+    #[test]
+    fn test_one_step_per_update() {
+        let mut scheduler = FrameScheduler::new(Duration::ZERO);
+        let a = scheduler.spawn(counter(2));
+        let b = scheduler.spawn(counter(1));
+        let c = scheduler.spawn(counter(2));
+
+        // turns: a, b (done), c, a (done), c (done)
+        scheduler.update();
+        scheduler.update();
+        assert!(!a.is_done());
+        assert!(b.is_done());
+        scheduler.update();
+        scheduler.update();
+        assert!(a.is_done());
+        assert!(!c.is_done());
+        scheduler.update();
+        assert!(c.is_done());
+        assert!(scheduler.is_empty());
+    }
+
+    #[test]
+    fn test_cancel() {
+        let mut scheduler = FrameScheduler::new(Duration::ZERO);
+        let a = scheduler.spawn(counter(10));
+        assert!(scheduler.is_running(&a));
+        scheduler.cancel(&a);
+        assert!(!scheduler.is_running(&a));
+        scheduler.update();
+        assert!(!a.is_done());
+    }
+}
@@ -0,0 +1,40 @@
+use assets::AssetList;
+use serde::de::DeserializeOwned;
+
+/// Deserializes a JSON file
+#[cfg(feature = "assets-json")]
+pub fn parse_json<T: DeserializeOwned>(id: &str, data: &[u8]) -> Result<T, String> {
+    serde_json::from_slice(data).map_err(|e| format!("Cannot parse JSON file '{id}': {e}"))
+}
+
+/// Deserializes a RON file
+#[cfg(feature = "assets-ron")]
+pub fn parse_ron<T: DeserializeOwned>(id: &str, data: &[u8]) -> Result<T, String> {
+    ron::de::from_bytes(data).map_err(|e| format!("Cannot parse RON file '{id}': {e}"))
+}
+
+/// Deserializes the file using the format of the extension (`json` or `ron`)
+pub fn parse_serde<T: DeserializeOwned>(id: &str, data: &[u8]) -> Result<T, String> {
+    let ext = id.rsplit('.').next().unwrap_or_default();
+    match ext {
+        #[cfg(feature = "assets-json")]
+        "json" => parse_json(id, data),
+        #[cfg(feature = "assets-ron")]
+        "ron" => parse_ron(id, data),
+        _ => Err(format!(
+            "Cannot parse '{id}': unsupported format '{ext}', enable the 'assets-json' or 'assets-ron' features"
+        )),
+    }
+}
+
+pub trait SerdeAssetList {
+    /// Deserializes the files with the extension as `T`, the format is picked
+    /// by the extension, `json` needs `assets-json` and `ron` needs `assets-ron`
+    fn with_serde_parser<T: DeserializeOwned + 'static>(self, ext: &str) -> Self;
+}
+
+impl SerdeAssetList for AssetList {
+    fn with_serde_parser<T: DeserializeOwned + 'static>(self, ext: &str) -> Self {
+        self.with_extension_parser(ext, parse_serde::<T>)
+    }
+}
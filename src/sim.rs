@@ -0,0 +1,379 @@
+use crate::input::{KeyCode, KeyCodeList, MouseButton, MouseButtonList};
+use crate::math::Vec2;
+use rustc_hash::FxHasher;
+use std::collections::BTreeMap;
+use std::hash::{Hash, Hasher};
+
+type UpdateFn<S> = Box<dyn FnMut(&mut S, &SimFrame)>;
+type HashFn<S> = Box<dyn Fn(&S) -> u64>;
+
+/// Input change scripted for a tick
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum ScriptedInput {
+    KeyPress(KeyCode),
+    KeyRelease(KeyCode),
+    MousePress(MouseButton),
+    MouseRelease(MouseButton),
+    MouseMove(Vec2),
+}
+
+/// Inputs that happen at specific ticks of a `SimHarness`
+#[derive(Clone, Debug, Default)]
+pub struct InputScript {
+    inputs: BTreeMap<u64, Vec<ScriptedInput>>,
+}
+
+impl InputScript {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_key_press(self, tick: u64, key: KeyCode) -> Self {
+        self.with(tick, ScriptedInput::KeyPress(key))
+    }
+
+    pub fn with_key_release(self, tick: u64, key: KeyCode) -> Self {
+        self.with(tick, ScriptedInput::KeyRelease(key))
+    }
+
+    /// Keeps the key down from `tick` during `ticks` ticks
+    pub fn with_key_hold(self, tick: u64, ticks: u64, key: KeyCode) -> Self {
+        self.with_key_press(tick, key)
+            .with_key_release(tick + ticks.max(1), key)
+    }
+
+    pub fn with_mouse_press(self, tick: u64, btn: MouseButton) -> Self {
+        self.with(tick, ScriptedInput::MousePress(btn))
+    }
+
+    pub fn with_mouse_release(self, tick: u64, btn: MouseButton) -> Self {
+        self.with(tick, ScriptedInput::MouseRelease(btn))
+    }
+
+    pub fn with_mouse_position(self, tick: u64, pos: Vec2) -> Self {
+        self.with(tick, ScriptedInput::MouseMove(pos))
+    }
+
+    fn with(mut self, tick: u64, input: ScriptedInput) -> Self {
+        self.inputs.entry(tick).or_default().push(input);
+        self
+    }
+}
+
+/// Input state of the current tick, the simulated version of the `input` functions
+#[derive(Clone, Default)]
+pub struct SimInput {
+    keys_down: KeyCodeList,
+    keys_pressed: KeyCodeList,
+    keys_released: KeyCodeList,
+    mouse_down: MouseButtonList,
+    mouse_pressed: MouseButtonList,
+    mouse_released: MouseButtonList,
+    mouse_position: Vec2,
+}
+
+impl SimInput {
+    pub fn is_key_down(&self, key: KeyCode) -> bool {
+        self.keys_down.contains(key)
+    }
+
+    pub fn is_key_pressed(&self, key: KeyCode) -> bool {
+        self.keys_pressed.contains(key)
+    }
+
+    pub fn is_key_released(&self, key: KeyCode) -> bool {
+        self.keys_released.contains(key)
+    }
+
+    pub fn is_mouse_btn_down(&self, btn: MouseButton) -> bool {
+        self.mouse_down.contains(btn)
+    }
+
+    pub fn is_mouse_btn_pressed(&self, btn: MouseButton) -> bool {
+        self.mouse_pressed.contains(btn)
+    }
+
+    pub fn is_mouse_btn_released(&self, btn: MouseButton) -> bool {
+        self.mouse_released.contains(btn)
+    }
+
+    pub fn mouse_position(&self) -> Vec2 {
+        self.mouse_position
+    }
+
+    fn apply(&mut self, inputs: &[ScriptedInput]) {
+        self.keys_pressed.clear();
+        self.keys_released.clear();
+        self.mouse_pressed.clear();
+        self.mouse_released.clear();
+
+        for input in inputs {
+            match *input {
+                ScriptedInput::KeyPress(key) => {
+                    if self.keys_down.insert(key) {
+                        self.keys_pressed.insert(key);
+                    }
+                }
+                ScriptedInput::KeyRelease(key) => {
+                    if self.keys_down.remove(key) {
+                        self.keys_released.insert(key);
+                    }
+                }
+                ScriptedInput::MousePress(btn) => {
+                    if self.mouse_down.insert(btn) {
+                        self.mouse_pressed.insert(btn);
+                    }
+                }
+                ScriptedInput::MouseRelease(btn) => {
+                    if self.mouse_down.remove(btn) {
+                        self.mouse_released.insert(btn);
+                    }
+                }
+                ScriptedInput::MouseMove(pos) => self.mouse_position = pos,
+            }
+        }
+    }
+}
+
+/// Data of the tick given to the update callback of the `SimHarness`
+pub struct SimFrame {
+    pub tick: u64,
+    /// Fixed delta time in seconds
+    pub delta: f32,
+    /// Elapsed time in seconds, `tick * delta`
+    pub elapsed: f32,
+    pub input: SimInput,
+}
+
+/// Runs the game logic headless with a fixed delta time and scripted inputs,
+/// so the same inputs produce the same state. The logic must read the time
+/// and input from `SimFrame` instead of the global functions.
+///
+/// ```rust,ignore
+/// let mut sim = SimHarness::new(Game::new(42), |game, frame| game.update(frame.delta, &frame.input))
+///     .with_script(InputScript::new().with_key_hold(10, 30, KeyCode::ArrowRight))
+///     .with_frame_hash(|game| hash_value(&game.player_pos()));
+///
+/// sim.run(120);
+/// sim.assert_state("player moved", |game| game.player_pos().0 > 100)?;
+/// sim.compare_hashes(&expected_hashes)?;
+/// ```
+pub struct SimHarness<S> {
+    state: S,
+    update: UpdateFn<S>,
+    frame: SimFrame,
+    script: InputScript,
+    hasher: Option<HashFn<S>>,
+    hashes: Vec<u64>,
+}
+
+impl<S> SimHarness<S> {
+    pub fn new<F>(state: S, update: F) -> Self
+    where
+        F: FnMut(&mut S, &SimFrame) + 'static,
+    {
+        Self {
+            state,
+            update: Box::new(update),
+            frame: SimFrame {
+                tick: 0,
+                delta: 1.0 / 60.0,
+                elapsed: 0.0,
+                input: SimInput::default(),
+            },
+            script: InputScript::default(),
+            hasher: None,
+            hashes: vec![],
+        }
+    }
+
+    /// Delta time of each tick in seconds (defaults to 1/60)
+    pub fn with_delta(mut self, delta: f32) -> Self {
+        self.frame.delta = delta;
+        self
+    }
+
+    pub fn with_script(mut self, script: InputScript) -> Self {
+        self.script = script;
+        self
+    }
+
+    /// Hashes the state after each tick, see `hash_value`
+    pub fn with_frame_hash<F>(mut self, hash: F) -> Self
+    where
+        F: Fn(&S) -> u64 + 'static,
+    {
+        self.hasher = Some(Box::new(hash));
+        self
+    }
+
+    /// Runs a tick, the first one is the tick 0
+    pub fn step(&mut self) {
+        let tick = self.frame.tick;
+        let inputs = self
+            .script
+            .inputs
+            .get(&tick)
+            .map_or(&[][..], |i| i.as_slice());
+        self.frame.input.apply(inputs);
+        self.frame.elapsed = tick as f32 * self.frame.delta;
+
+        (self.update)(&mut self.state, &self.frame);
+
+        if let Some(hash) = &self.hasher {
+            self.hashes.push(hash(&self.state));
+        }
+        self.frame.tick += 1;
+    }
+
+    pub fn run(&mut self, ticks: u64) -> &mut Self {
+        (0..ticks).for_each(|_| self.step());
+        self
+    }
+
+    /// Runs until the condition is true, returns the tick or an error if it takes more than `max_ticks`
+    pub fn run_until<F>(&mut self, max_ticks: u64, cond: F) -> Result<u64, String>
+    where
+        F: Fn(&S) -> bool,
+    {
+        for _ in 0..max_ticks {
+            self.step();
+            if cond(&self.state) {
+                return Ok(self.frame.tick - 1);
+            }
+        }
+
+        Err(format!("Condition not met after {max_ticks} ticks"))
+    }
+
+    /// Ticks already run
+    pub fn ticks(&self) -> u64 {
+        self.frame.tick
+    }
+
+    pub fn state(&self) -> &S {
+        &self.state
+    }
+
+    pub fn state_mut(&mut self) -> &mut S {
+        &mut self.state
+    }
+
+    pub fn into_state(self) -> S {
+        self.state
+    }
+
+    /// Returns an error with the current tick if the check fails
+    pub fn assert_state<F>(&self, msg: &str, check: F) -> Result<(), String>
+    where
+        F: FnOnce(&S) -> bool,
+    {
+        if check(&self.state) {
+            Ok(())
+        } else {
+            Err(format!(
+                "Assertion '{msg}' failed at tick {}",
+                self.frame.tick
+            ))
+        }
+    }
+
+    /// Hashes of the state after each tick, empty without `with_frame_hash`
+    pub fn hashes(&self) -> &[u64] {
+        &self.hashes
+    }
+
+    /// Compares the hashes with the ones of a previous run, the error contains the first
+    /// tick that diverges. Only the ticks run by both are compared.
+    pub fn compare_hashes(&self, expected: &[u64]) -> Result<(), String> {
+        if self.hasher.is_none() {
+            return Err("Frame hashes are disabled, use 'with_frame_hash'".to_string());
+        }
+
+        let diff = self
+            .hashes
+            .iter()
+            .zip(expected)
+            .position(|(hash, expected)| hash != expected);
+
+        match diff {
+            Some(tick) => Err(format!(
+                "Frame hash mismatch at tick {tick}: expected {:x}, got {:x}",
+                expected[tick], self.hashes[tick]
+            )),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Deterministic hash of the value, the same across runs of the same build
+pub fn hash_value<T: Hash + ?Sized>(value: &T) -> u64 {
+    let mut hasher = FxHasher::default();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Default)]
+    struct Game {
+        x: f32,
+        jumps: u32,
+    }
+
+    fn update(game: &mut Game, frame: &SimFrame) {
+        if frame.input.is_key_down(KeyCode::ArrowRight) {
+            game.x += 60.0 * frame.delta;
+        }
+
+        if frame.input.is_key_pressed(KeyCode::Space) {
+            game.jumps += 1;
+        }
+    }
+
+    fn sim() -> SimHarness<Game> {
+        let script = InputScript::new()
+            .with_key_hold(10, 30, KeyCode::ArrowRight)
+            .with_key_hold(5, 20, KeyCode::Space)
+            .with_key_press(50, KeyCode::Space);
+
+        SimHarness::new(Game::default(), update)
+            .with_script(script)
+            .with_frame_hash(|game| hash_value(&(game.x.to_bits(), game.jumps)))
+    }
+
+    #[test]
+    fn test_scripted_run() {
+        let mut sim = sim();
+        sim.run(60);
+        assert_eq!(sim.ticks(), 60);
+        assert!((sim.state().x - 30.0).abs() < 0.001);
+        assert_eq!(sim.state().jumps, 2);
+        assert!(sim.assert_state("moved", |game| game.x > 29.0).is_ok());
+        assert!(sim.assert_state("jumped", |game| game.jumps > 2).is_err());
+    }
+
+    #[test]
+    fn test_frame_hashes() {
+        let mut sim1 = sim();
+        sim1.run(60);
+
+        let mut sim2 = sim();
+        sim2.run(60);
+        assert!(sim2.compare_hashes(sim1.hashes()).is_ok());
+
+        let mut sim3 = sim().with_delta(1.0 / 30.0);
+        sim3.run(60);
+        let err = sim3.compare_hashes(sim1.hashes()).unwrap_err();
+        assert!(err.contains("tick 10"), "{err}");
+    }
+
+    #[test]
+    fn test_run_until() {
+        let mut sim = sim();
+        assert_eq!(sim.run_until(100, |game| game.jumps == 1), Ok(5));
+        assert!(sim.run_until(10, |game| game.jumps == 5).is_err());
+    }
+}
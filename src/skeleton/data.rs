@@ -0,0 +1,580 @@
+use crate::gfx::Color;
+use rustc_hash::FxHashMap;
+use serde::Deserialize;
+
+/// Setup pose of a bone
+#[derive(Debug, Clone)]
+pub struct BoneData {
+    pub name: String,
+    pub parent: Option<usize>,
+    pub length: f32,
+    pub x: f32,
+    pub y: f32,
+    pub rotation: f32,
+    pub scale_x: f32,
+    pub scale_y: f32,
+}
+
+/// Slot definition, slots are drawn in the order they are declared
+#[derive(Debug, Clone)]
+pub struct SlotData {
+    pub name: String,
+    pub bone: usize,
+    pub color: Color,
+    pub attachment: Option<String>,
+}
+
+/// Textured quad attached to a slot
+#[derive(Debug, Clone)]
+pub struct RegionAttachment {
+    /// Name of the region on the atlas
+    pub region: String,
+    pub x: f32,
+    pub y: f32,
+    pub rotation: f32,
+    pub scale_x: f32,
+    pub scale_y: f32,
+    pub width: f32,
+    pub height: f32,
+    pub color: Color,
+}
+
+/// Event fired by an animation
+#[derive(Debug, Clone, PartialEq)]
+pub struct SkeletonEvent {
+    pub name: String,
+    pub time: f32,
+    pub int: i32,
+    pub float: f32,
+    pub string: Option<String>,
+}
+
+/// Animation keyframe
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Keyframe<T> {
+    pub time: f32,
+    pub value: T,
+    pub stepped: bool,
+}
+
+/// Keyframes for a bone, values are relative to the setup pose
+#[derive(Debug, Clone, Default)]
+pub struct BoneTimeline {
+    pub bone: usize,
+    pub rotate: Vec<Keyframe<f32>>,
+    pub translate: Vec<Keyframe<[f32; 2]>>,
+    pub scale: Vec<Keyframe<[f32; 2]>>,
+}
+
+/// Keyframes for a slot
+#[derive(Debug, Clone, Default)]
+pub struct SlotTimeline {
+    pub slot: usize,
+    pub attachment: Vec<(f32, Option<String>)>,
+    pub color: Vec<Keyframe<[f32; 4]>>,
+}
+
+/// Animation definition
+#[derive(Debug, Clone, Default)]
+pub struct AnimationData {
+    pub name: String,
+    pub duration: f32,
+    pub bones: Vec<BoneTimeline>,
+    pub slots: Vec<SlotTimeline>,
+    pub events: Vec<SkeletonEvent>,
+}
+
+/// Skeleton definition parsed from a Spine JSON export
+#[derive(Debug, Clone, Default)]
+pub struct SkeletonData {
+    pub bones: Vec<BoneData>,
+    pub slots: Vec<SlotData>,
+    pub skins: FxHashMap<String, FxHashMap<(usize, String), RegionAttachment>>,
+    pub animations: Vec<AnimationData>,
+}
+
+impl SkeletonData {
+    /// Parse a Spine JSON export (3.x and 4.x)
+    pub fn from_json(data: &[u8]) -> Result<Self, String> {
+        let raw: RawSkeleton =
+            serde_json::from_slice(data).map_err(|e| format!("Cannot parse skeleton: {e}"))?;
+        raw.into_data()
+    }
+
+    pub fn bone_index(&self, name: &str) -> Option<usize> {
+        self.bones.iter().position(|b| b.name == name)
+    }
+
+    pub fn slot_index(&self, name: &str) -> Option<usize> {
+        self.slots.iter().position(|s| s.name == name)
+    }
+
+    pub fn animation_index(&self, name: &str) -> Option<usize> {
+        self.animations.iter().position(|a| a.name == name)
+    }
+
+    pub fn animation(&self, name: &str) -> Option<&AnimationData> {
+        self.animations.iter().find(|a| a.name == name)
+    }
+
+    /// Attachment for a slot, looking on the given skin and then on the default one
+    pub fn attachment(&self, skin: &str, slot: usize, name: &str) -> Option<&RegionAttachment> {
+        let key = (slot, name.to_string());
+        self.skins
+            .get(skin)
+            .and_then(|s| s.get(&key))
+            .or_else(|| self.skins.get("default").and_then(|s| s.get(&key)))
+    }
+}
+
+/// Parse colors like "ff00ffff" (RRGGBBAA) or "ff00ff" (RRGGBB)
+fn parse_color(s: &str) -> Result<Color, String> {
+    let hex = u32::from_str_radix(s, 16).map_err(|e| format!("Invalid color '{s}': {e}"))?;
+    match s.len() {
+        8 => Ok(Color::hex(hex)),
+        6 => Ok(Color::hex((hex << 8) | 0xff)),
+        _ => Err(format!("Invalid color '{s}'")),
+    }
+}
+
+fn is_stepped(curve: &Option<serde_json::Value>) -> bool {
+    matches!(curve, Some(serde_json::Value::String(s)) if s == "stepped")
+}
+
+fn one() -> f32 {
+    1.0
+}
+
+#[derive(Deserialize)]
+struct RawSkeleton {
+    #[serde(default)]
+    bones: Vec<RawBone>,
+    #[serde(default)]
+    slots: Vec<RawSlot>,
+    #[serde(default)]
+    skins: Option<RawSkins>,
+    #[serde(default)]
+    events: FxHashMap<String, RawEventData>,
+    #[serde(default)]
+    animations: FxHashMap<String, RawAnimation>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawBone {
+    name: String,
+    parent: Option<String>,
+    #[serde(default)]
+    length: f32,
+    #[serde(default)]
+    x: f32,
+    #[serde(default)]
+    y: f32,
+    #[serde(default)]
+    rotation: f32,
+    #[serde(default = "one")]
+    scale_x: f32,
+    #[serde(default = "one")]
+    scale_y: f32,
+}
+
+#[derive(Deserialize)]
+struct RawSlot {
+    name: String,
+    bone: String,
+    color: Option<String>,
+    attachment: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RawSkins {
+    // spine 4.x
+    List(Vec<RawSkin>),
+    // spine 3.x
+    Map(FxHashMap<String, FxHashMap<String, FxHashMap<String, RawAttachment>>>),
+}
+
+#[derive(Deserialize)]
+struct RawSkin {
+    name: String,
+    #[serde(default)]
+    attachments: FxHashMap<String, FxHashMap<String, RawAttachment>>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawAttachment {
+    #[serde(rename = "type")]
+    kind: Option<String>,
+    name: Option<String>,
+    path: Option<String>,
+    #[serde(default)]
+    x: f32,
+    #[serde(default)]
+    y: f32,
+    #[serde(default)]
+    rotation: f32,
+    #[serde(default = "one")]
+    scale_x: f32,
+    #[serde(default = "one")]
+    scale_y: f32,
+    #[serde(default)]
+    width: f32,
+    #[serde(default)]
+    height: f32,
+    color: Option<String>,
+}
+
+#[derive(Deserialize, Default)]
+struct RawEventData {
+    #[serde(default)]
+    int: i32,
+    #[serde(default)]
+    float: f32,
+    string: Option<String>,
+}
+
+#[derive(Deserialize, Default)]
+struct RawAnimation {
+    #[serde(default)]
+    bones: FxHashMap<String, RawBoneTimeline>,
+    #[serde(default)]
+    slots: FxHashMap<String, RawSlotTimeline>,
+    #[serde(default)]
+    events: Vec<RawEventKey>,
+}
+
+#[derive(Deserialize, Default)]
+struct RawBoneTimeline {
+    #[serde(default)]
+    rotate: Vec<RawRotateKey>,
+    #[serde(default)]
+    translate: Vec<RawVecKey>,
+    #[serde(default)]
+    scale: Vec<RawVecKey>,
+}
+
+#[derive(Deserialize, Default)]
+struct RawSlotTimeline {
+    #[serde(default)]
+    attachment: Vec<RawAttachmentKey>,
+    // spine 3.x uses "color", 4.x uses "rgba"
+    #[serde(default, alias = "rgba")]
+    color: Vec<RawColorKey>,
+}
+
+#[derive(Deserialize)]
+struct RawRotateKey {
+    #[serde(default)]
+    time: f32,
+    // spine 3.x uses "angle", 4.x uses "value"
+    #[serde(alias = "value", default)]
+    angle: f32,
+    curve: Option<serde_json::Value>,
+}
+
+#[derive(Deserialize)]
+struct RawVecKey {
+    #[serde(default)]
+    time: f32,
+    x: Option<f32>,
+    y: Option<f32>,
+    curve: Option<serde_json::Value>,
+}
+
+#[derive(Deserialize)]
+struct RawAttachmentKey {
+    #[serde(default)]
+    time: f32,
+    name: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct RawColorKey {
+    #[serde(default)]
+    time: f32,
+    color: String,
+    curve: Option<serde_json::Value>,
+}
+
+#[derive(Deserialize)]
+struct RawEventKey {
+    #[serde(default)]
+    time: f32,
+    name: String,
+    int: Option<i32>,
+    float: Option<f32>,
+    string: Option<String>,
+}
+
+impl RawSkeleton {
+    fn into_data(self) -> Result<SkeletonData, String> {
+        let mut data = SkeletonData::default();
+
+        for raw in self.bones {
+            let parent = match raw.parent {
+                Some(name) => Some(data.bone_index(&name).ok_or_else(|| {
+                    format!("Bone '{}' has an unknown parent '{}'", raw.name, name)
+                })?),
+                None => None,
+            };
+            data.bones.push(BoneData {
+                name: raw.name,
+                parent,
+                length: raw.length,
+                x: raw.x,
+                y: raw.y,
+                rotation: raw.rotation,
+                scale_x: raw.scale_x,
+                scale_y: raw.scale_y,
+            });
+        }
+
+        for raw in self.slots {
+            let bone = data
+                .bone_index(&raw.bone)
+                .ok_or_else(|| format!("Slot '{}' has an unknown bone '{}'", raw.name, raw.bone))?;
+            let color = match raw.color {
+                Some(c) => parse_color(&c)?,
+                None => Color::WHITE,
+            };
+            data.slots.push(SlotData {
+                name: raw.name,
+                bone,
+                color,
+                attachment: raw.attachment,
+            });
+        }
+
+        let skins: Vec<(String, _)> = match self.skins {
+            Some(RawSkins::List(list)) => {
+                list.into_iter().map(|s| (s.name, s.attachments)).collect()
+            }
+            Some(RawSkins::Map(map)) => map.into_iter().collect(),
+            None => vec![],
+        };
+        for (skin_name, slots) in skins {
+            let mut skin = FxHashMap::default();
+            for (slot_name, attachments) in slots {
+                let Some(slot) = data.slot_index(&slot_name) else {
+                    log::warn!("Skin '{skin_name}' references unknown slot '{slot_name}'");
+                    continue;
+                };
+                for (name, raw) in attachments {
+                    // only region attachments are supported
+                    if raw.kind.as_deref().is_some_and(|k| k != "region") {
+                        continue;
+                    }
+                    let color = match raw.color {
+                        Some(c) => parse_color(&c)?,
+                        None => Color::WHITE,
+                    };
+                    let region = raw.path.or(raw.name).unwrap_or_else(|| name.clone());
+                    skin.insert(
+                        (slot, name),
+                        RegionAttachment {
+                            region,
+                            x: raw.x,
+                            y: raw.y,
+                            rotation: raw.rotation,
+                            scale_x: raw.scale_x,
+                            scale_y: raw.scale_y,
+                            width: raw.width,
+                            height: raw.height,
+                            color,
+                        },
+                    );
+                }
+            }
+            data.skins.insert(skin_name, skin);
+        }
+
+        for (name, raw) in self.animations {
+            let anim = raw.into_data(name, &data, &self.events)?;
+            data.animations.push(anim);
+        }
+        data.animations.sort_by(|a, b| a.name.cmp(&b.name));
+
+        Ok(data)
+    }
+}
+
+impl RawAnimation {
+    fn into_data(
+        self,
+        name: String,
+        data: &SkeletonData,
+        events: &FxHashMap<String, RawEventData>,
+    ) -> Result<AnimationData, String> {
+        let mut anim = AnimationData {
+            name,
+            ..Default::default()
+        };
+        let mut duration = 0.0f32;
+
+        for (bone_name, raw) in self.bones {
+            let bone = data.bone_index(&bone_name).ok_or_else(|| {
+                format!(
+                    "Animation '{}' has an unknown bone '{bone_name}'",
+                    anim.name
+                )
+            })?;
+            let rotate = raw
+                .rotate
+                .into_iter()
+                .map(|k| Keyframe {
+                    time: k.time,
+                    value: k.angle,
+                    stepped: is_stepped(&k.curve),
+                })
+                .collect::<Vec<_>>();
+            let translate = raw
+                .translate
+                .into_iter()
+                .map(|k| Keyframe {
+                    time: k.time,
+                    value: [k.x.unwrap_or(0.0), k.y.unwrap_or(0.0)],
+                    stepped: is_stepped(&k.curve),
+                })
+                .collect::<Vec<_>>();
+            let scale = raw
+                .scale
+                .into_iter()
+                .map(|k| Keyframe {
+                    time: k.time,
+                    value: [k.x.unwrap_or(1.0), k.y.unwrap_or(1.0)],
+                    stepped: is_stepped(&k.curve),
+                })
+                .collect::<Vec<_>>();
+
+            let times = rotate
+                .iter()
+                .map(|k| k.time)
+                .chain(translate.iter().map(|k| k.time))
+                .chain(scale.iter().map(|k| k.time));
+            duration = times.fold(duration, f32::max);
+
+            anim.bones.push(BoneTimeline {
+                bone,
+                rotate,
+                translate,
+                scale,
+            });
+        }
+
+        for (slot_name, raw) in self.slots {
+            let slot = data.slot_index(&slot_name).ok_or_else(|| {
+                format!(
+                    "Animation '{}' has an unknown slot '{slot_name}'",
+                    anim.name
+                )
+            })?;
+            let attachment = raw
+                .attachment
+                .into_iter()
+                .map(|k| (k.time, k.name))
+                .collect::<Vec<_>>();
+            let color = raw
+                .color
+                .into_iter()
+                .map(|k| {
+                    let c = parse_color(&k.color)?;
+                    Ok(Keyframe {
+                        time: k.time,
+                        value: [c.r, c.g, c.b, c.a],
+                        stepped: is_stepped(&k.curve),
+                    })
+                })
+                .collect::<Result<Vec<_>, String>>()?;
+
+            let times = attachment
+                .iter()
+                .map(|(t, _)| *t)
+                .chain(color.iter().map(|k| k.time));
+            duration = times.fold(duration, f32::max);
+
+            anim.slots.push(SlotTimeline {
+                slot,
+                attachment,
+                color,
+            });
+        }
+
+        for raw in self.events {
+            let def = events.get(&raw.name);
+            anim.events.push(SkeletonEvent {
+                time: raw.time,
+                int: raw.int.or(def.map(|d| d.int)).unwrap_or(0),
+                float: raw.float.or(def.map(|d| d.float)).unwrap_or(0.0),
+                string: raw.string.or_else(|| def.and_then(|d| d.string.clone())),
+                name: raw.name,
+            });
+            duration = duration.max(raw.time);
+        }
+        anim.events.sort_by(|a, b| {
+            a.time
+                .partial_cmp(&b.time)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        anim.duration = duration;
+
+        Ok(anim)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const JSON: &str = r#"{
+        "bones": [
+            { "name": "root" },
+            { "name": "arm", "parent": "root", "x": 10, "rotation": 90 }
+        ],
+        "slots": [
+            { "name": "body", "bone": "root", "attachment": "body" },
+            { "name": "hand", "bone": "arm", "color": "ff000080", "attachment": "hand" }
+        ],
+        "skins": [{
+            "name": "default",
+            "attachments": {
+                "body": { "body": { "width": 20, "height": 40 } },
+                "hand": { "hand": { "path": "hand_open", "width": 8, "height": 8 } }
+            }
+        }],
+        "events": { "step": { "int": 3 } },
+        "animations": {
+            "wave": {
+                "bones": { "arm": { "rotate": [ { "value": 0 }, { "time": 1, "value": 45 } ] } },
+                "slots": { "hand": { "attachment": [ { "time": 0.5, "name": null } ] } },
+                "events": [ { "time": 0.25, "name": "step" } ]
+            }
+        }
+    }"#;
+
+    #[test]
+    fn test_parse_json() {
+        let data = SkeletonData::from_json(JSON.as_bytes()).unwrap();
+        assert_eq!(data.bones.len(), 2);
+        assert_eq!(data.bones[1].parent, Some(0));
+        assert_eq!(data.bones[1].scale_x, 1.0);
+        assert_eq!(data.slots[1].bone, 1);
+        assert!((data.slots[1].color.a - 0.5).abs() < 0.01);
+
+        let hand = data.attachment("default", 1, "hand").unwrap();
+        assert_eq!(hand.region, "hand_open");
+
+        let anim = data.animation("wave").unwrap();
+        assert_eq!(anim.duration, 1.0);
+        assert_eq!(anim.bones[0].rotate[1].value, 45.0);
+        assert_eq!(anim.slots[0].attachment[0], (0.5, None));
+        assert_eq!(anim.events[0].int, 3);
+    }
+
+    #[test]
+    fn test_unknown_parent() {
+        let json = r#"{ "bones": [ { "name": "arm", "parent": "root" } ] }"#;
+        assert!(SkeletonData::from_json(json.as_bytes()).is_err());
+    }
+}
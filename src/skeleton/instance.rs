@@ -0,0 +1,599 @@
+use super::data::{AnimationData, Keyframe, RegionAttachment, SkeletonData, SkeletonEvent};
+use crate::gfx::Color;
+use crate::math::{vec2, Mat3};
+use rustc_hash::FxHashMap;
+use std::sync::Arc;
+
+/// Local transform of a bone
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BonePose {
+    pub x: f32,
+    pub y: f32,
+    pub rotation: f32,
+    pub scale_x: f32,
+    pub scale_y: f32,
+}
+
+impl BonePose {
+    fn lerp(&self, to: &Self, t: f32) -> Self {
+        Self {
+            x: lerp(self.x, to.x, t),
+            y: lerp(self.y, to.y, t),
+            rotation: lerp_angle(self.rotation, to.rotation, t),
+            scale_x: lerp(self.scale_x, to.scale_x, t),
+            scale_y: lerp(self.scale_y, to.scale_y, t),
+        }
+    }
+
+    /// Local matrix (translation * rotation * scale)
+    pub fn matrix(&self) -> Mat3 {
+        Mat3::from_scale_angle_translation(
+            vec2(self.scale_x, self.scale_y),
+            self.rotation.to_radians(),
+            vec2(self.x, self.y),
+        )
+    }
+}
+
+/// Current state of a slot
+#[derive(Debug, Clone, PartialEq)]
+pub struct SlotPose {
+    pub attachment: Option<String>,
+    pub color: Color,
+}
+
+/// Region ready to be drawn, the matrix is in skeleton space (y-up)
+#[derive(Debug, Clone, Copy)]
+pub struct SkeletonRegion<'a> {
+    pub slot: usize,
+    pub region: &'a str,
+    pub matrix: Mat3,
+    pub width: f32,
+    pub height: f32,
+    pub color: Color,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Track {
+    animation: usize,
+    time: f32,
+    looped: bool,
+}
+
+/// Skeleton instance with its own pose and animation state
+pub struct Skeleton {
+    data: Arc<SkeletonData>,
+    skin: String,
+    bones: Vec<BonePose>,
+    world: Vec<Mat3>,
+    slots: Vec<SlotPose>,
+    current: Option<Track>,
+    previous: Option<Track>,
+    mix_time: f32,
+    mix_duration: f32,
+    default_mix: f32,
+    mixes: FxHashMap<(String, String), f32>,
+    speed: f32,
+    events: Vec<SkeletonEvent>,
+}
+
+impl Skeleton {
+    pub fn new(data: Arc<SkeletonData>) -> Self {
+        let mut skeleton = Self {
+            bones: vec![],
+            world: vec![],
+            slots: vec![],
+            data,
+            skin: "default".to_string(),
+            current: None,
+            previous: None,
+            mix_time: 0.0,
+            mix_duration: 0.0,
+            default_mix: 0.0,
+            mixes: FxHashMap::default(),
+            speed: 1.0,
+            events: vec![],
+        };
+        skeleton.set_to_setup_pose();
+        skeleton
+    }
+
+    /// Skin used to look up attachments (falls back to "default")
+    pub fn with_skin(mut self, skin: &str) -> Self {
+        self.skin = skin.to_string();
+        self
+    }
+
+    /// Crossfade duration used when changing between any animations
+    pub fn with_default_mix(mut self, secs: f32) -> Self {
+        self.default_mix = secs;
+        self
+    }
+
+    /// Crossfade duration used when changing from one animation to another
+    pub fn with_mix(mut self, from: &str, to: &str, secs: f32) -> Self {
+        self.mixes.insert((from.to_string(), to.to_string()), secs);
+        self
+    }
+
+    pub fn with_speed(mut self, speed: f32) -> Self {
+        self.speed = speed;
+        self
+    }
+
+    pub fn data(&self) -> &SkeletonData {
+        &self.data
+    }
+
+    pub fn set_skin(&mut self, skin: &str) {
+        self.skin = skin.to_string();
+    }
+
+    pub fn skin(&self) -> &str {
+        &self.skin
+    }
+
+    pub fn set_speed(&mut self, speed: f32) {
+        self.speed = speed;
+    }
+
+    pub fn speed(&self) -> f32 {
+        self.speed
+    }
+
+    /// Plays an animation, crossfading from the current one if a mix is defined
+    pub fn play(&mut self, name: &str, looped: bool) -> Result<(), String> {
+        let animation = self
+            .data
+            .animation_index(name)
+            .ok_or_else(|| format!("Unknown skeleton animation '{name}'"))?;
+
+        if let Some(current) = self.current {
+            if current.animation == animation && current.looped == looped {
+                return Ok(());
+            }
+
+            let from = &self.data.animations[current.animation].name;
+            let mix = self
+                .mixes
+                .get(&(from.clone(), name.to_string()))
+                .copied()
+                .unwrap_or(self.default_mix);
+            self.previous = (mix > 0.0).then_some(current);
+            self.mix_time = 0.0;
+            self.mix_duration = mix;
+        }
+
+        self.current = Some(Track {
+            animation,
+            time: 0.0,
+            looped,
+        });
+        self.apply();
+        Ok(())
+    }
+
+    /// Stops any animation and resets the setup pose
+    pub fn stop(&mut self) {
+        self.current = None;
+        self.previous = None;
+        self.set_to_setup_pose();
+    }
+
+    /// Name of the animation playing
+    pub fn animation(&self) -> Option<&str> {
+        self.current
+            .map(|t| self.data.animations[t.animation].name.as_str())
+    }
+
+    /// Time elapsed on the current animation
+    pub fn time(&self) -> f32 {
+        self.current.map_or(0.0, |t| t.time)
+    }
+
+    /// True if the current animation is not looped and has reached its end
+    pub fn is_finished(&self) -> bool {
+        self.current
+            .is_some_and(|t| !t.looped && t.time >= self.data.animations[t.animation].duration)
+    }
+
+    /// True while crossfading between two animations
+    pub fn is_mixing(&self) -> bool {
+        self.previous.is_some()
+    }
+
+    /// Events fired during the last update
+    pub fn events(&self) -> &[SkeletonEvent] {
+        &self.events
+    }
+
+    /// Advance the animations and compute the new pose
+    pub fn update(&mut self, dt: f32) {
+        self.events.clear();
+        let dt = dt * self.speed;
+
+        if let Some(track) = &mut self.current {
+            let anim = &self.data.animations[track.animation];
+            let from = track.time;
+            track.time += dt;
+            collect_events(anim, from, track.time, track.looped, &mut self.events);
+        }
+
+        if let Some(track) = &mut self.previous {
+            track.time += dt;
+            self.mix_time += dt;
+            if self.mix_time >= self.mix_duration {
+                self.previous = None;
+            }
+        }
+
+        self.apply();
+    }
+
+    /// Reset bones and slots to the setup pose
+    pub fn set_to_setup_pose(&mut self) {
+        self.bones = setup_bones(&self.data);
+        self.slots = setup_slots(&self.data);
+        self.update_world();
+    }
+
+    pub fn bones(&self) -> &[BonePose] {
+        &self.bones
+    }
+
+    pub fn bones_mut(&mut self) -> &mut [BonePose] {
+        &mut self.bones
+    }
+
+    pub fn slots(&self) -> &[SlotPose] {
+        &self.slots
+    }
+
+    /// World matrix of a bone, in skeleton space (y-up)
+    pub fn bone_world(&self, name: &str) -> Option<Mat3> {
+        self.data.bone_index(name).map(|i| self.world[i])
+    }
+
+    /// Recompute the world matrices, use it after changing bones manually
+    pub fn update_world(&mut self) {
+        self.world.clear();
+        for (i, bone) in self.data.bones.iter().enumerate() {
+            let local = self.bones[i].matrix();
+            let world = match bone.parent {
+                Some(parent) => self.world[parent] * local,
+                None => local,
+            };
+            self.world.push(world);
+        }
+    }
+
+    /// Regions to draw in slot order
+    pub fn regions(&self) -> impl Iterator<Item = SkeletonRegion<'_>> + '_ {
+        self.slots.iter().enumerate().filter_map(|(i, slot)| {
+            let name = slot.attachment.as_ref()?;
+            let attachment = self.data.attachment(&self.skin, i, name)?;
+            let bone = self.data.slots[i].bone;
+            Some(SkeletonRegion {
+                slot: i,
+                region: &attachment.region,
+                matrix: self.world[bone] * attachment_matrix(attachment),
+                width: attachment.width,
+                height: attachment.height,
+                color: mul_color(slot.color, attachment.color),
+            })
+        })
+    }
+
+    fn apply(&mut self) {
+        let Some(current) = self.current else {
+            return;
+        };
+
+        let (mut bones, mut slots) = (setup_bones(&self.data), setup_slots(&self.data));
+        let anim = &self.data.animations[current.animation];
+        apply_animation(
+            anim,
+            &self.data,
+            current.time,
+            current.looped,
+            &mut bones,
+            &mut slots,
+        );
+
+        if let Some(previous) = self.previous {
+            let (mut from, mut from_slots) = (setup_bones(&self.data), setup_slots(&self.data));
+            let anim = &self.data.animations[previous.animation];
+            apply_animation(
+                anim,
+                &self.data,
+                previous.time,
+                previous.looped,
+                &mut from,
+                &mut from_slots,
+            );
+
+            let alpha = (self.mix_time / self.mix_duration).clamp(0.0, 1.0);
+            bones
+                .iter_mut()
+                .zip(from.iter())
+                .for_each(|(to, from)| *to = from.lerp(to, alpha));
+            slots
+                .iter_mut()
+                .zip(from_slots.iter())
+                .for_each(|(to, from)| to.color = lerp_color(from.color, to.color, alpha));
+        }
+
+        self.bones = bones;
+        self.slots = slots;
+        self.update_world();
+    }
+}
+
+fn setup_bones(data: &SkeletonData) -> Vec<BonePose> {
+    data.bones
+        .iter()
+        .map(|b| BonePose {
+            x: b.x,
+            y: b.y,
+            rotation: b.rotation,
+            scale_x: b.scale_x,
+            scale_y: b.scale_y,
+        })
+        .collect()
+}
+
+fn setup_slots(data: &SkeletonData) -> Vec<SlotPose> {
+    data.slots
+        .iter()
+        .map(|s| SlotPose {
+            attachment: s.attachment.clone(),
+            color: s.color,
+        })
+        .collect()
+}
+
+/// Attachment transform, maps the image (y-down) to the bone space (y-up) centered
+fn attachment_matrix(attachment: &RegionAttachment) -> Mat3 {
+    let local = Mat3::from_scale_angle_translation(
+        vec2(attachment.scale_x, attachment.scale_y),
+        attachment.rotation.to_radians(),
+        vec2(attachment.x, attachment.y),
+    );
+    let flip = Mat3::from_scale(vec2(1.0, -1.0));
+    let center = Mat3::from_translation(vec2(-attachment.width, -attachment.height) * 0.5);
+    local * flip * center
+}
+
+/// Apply the animation at the given time on top of the setup pose
+fn apply_animation(
+    anim: &AnimationData,
+    data: &SkeletonData,
+    time: f32,
+    looped: bool,
+    bones: &mut [BonePose],
+    slots: &mut [SlotPose],
+) {
+    let time = if looped && anim.duration > 0.0 {
+        time % anim.duration
+    } else {
+        time.min(anim.duration)
+    };
+
+    for timeline in &anim.bones {
+        let setup = &data.bones[timeline.bone];
+        let bone = &mut bones[timeline.bone];
+        if let Some(angle) = sample(&timeline.rotate, time, lerp) {
+            bone.rotation = setup.rotation + angle;
+        }
+        if let Some([x, y]) = sample(&timeline.translate, time, lerp2) {
+            bone.x = setup.x + x;
+            bone.y = setup.y + y;
+        }
+        if let Some([x, y]) = sample(&timeline.scale, time, lerp2) {
+            bone.scale_x = setup.scale_x * x;
+            bone.scale_y = setup.scale_y * y;
+        }
+    }
+
+    for timeline in &anim.slots {
+        let slot = &mut slots[timeline.slot];
+        if let Some((_, name)) = timeline.attachment.iter().rev().find(|(t, _)| *t <= time) {
+            slot.attachment = name.clone();
+        }
+        if let Some([r, g, b, a]) = sample(&timeline.color, time, lerp4) {
+            slot.color = Color::new(r, g, b, a);
+        }
+    }
+}
+
+/// Value of the timeline at the given time, keys must be sorted by time
+fn sample<T: Copy>(keys: &[Keyframe<T>], time: f32, lerp: fn(T, T, f32) -> T) -> Option<T> {
+    let first = keys.first()?;
+    if time <= first.time {
+        return Some(first.value);
+    }
+
+    let idx = keys.iter().rposition(|k| k.time <= time)?;
+    let key = &keys[idx];
+    match keys.get(idx + 1) {
+        Some(next) if !key.stepped => {
+            let t = (time - key.time) / (next.time - key.time);
+            Some(lerp(key.value, next.value, t))
+        }
+        _ => Some(key.value),
+    }
+}
+
+/// Push the events on the interval [from, to), wrapping looped animations
+fn collect_events(
+    anim: &AnimationData,
+    from: f32,
+    to: f32,
+    looped: bool,
+    out: &mut Vec<SkeletonEvent>,
+) {
+    let duration = anim.duration;
+    if !looped || duration <= 0.0 {
+        if from >= duration && from > 0.0 {
+            return;
+        }
+        // the last frame includes the events placed at the end
+        let end = to.min(duration);
+        let inclusive = to >= duration;
+        out.extend(
+            anim.events
+                .iter()
+                .filter(|e| e.time >= from && (e.time < end || (inclusive && e.time <= end)))
+                .cloned(),
+        );
+        return;
+    }
+
+    let first_loop = (from / duration).floor() as u32;
+    let last_loop = (to / duration).floor() as u32;
+    for n in first_loop..=last_loop {
+        let offset = n as f32 * duration;
+        let start = (from - offset).max(0.0);
+        let end = (to - offset).min(duration);
+        out.extend(
+            anim.events
+                .iter()
+                .filter(|e| e.time >= start && e.time < end)
+                .cloned(),
+        );
+    }
+}
+
+fn lerp(a: f32, b: f32, t: f32) -> f32 {
+    a + (b - a) * t
+}
+
+fn lerp2(a: [f32; 2], b: [f32; 2], t: f32) -> [f32; 2] {
+    [lerp(a[0], b[0], t), lerp(a[1], b[1], t)]
+}
+
+fn lerp4(a: [f32; 4], b: [f32; 4], t: f32) -> [f32; 4] {
+    [
+        lerp(a[0], b[0], t),
+        lerp(a[1], b[1], t),
+        lerp(a[2], b[2], t),
+        lerp(a[3], b[3], t),
+    ]
+}
+
+/// Interpolates degrees using the shortest path
+fn lerp_angle(a: f32, b: f32, t: f32) -> f32 {
+    let diff = (b - a + 180.0).rem_euclid(360.0) - 180.0;
+    a + diff * t
+}
+
+fn lerp_color(a: Color, b: Color, t: f32) -> Color {
+    let [r, g, b, a] = lerp4([a.r, a.g, a.b, a.a], [b.r, b.g, b.b, b.a], t);
+    Color::new(r, g, b, a)
+}
+
+fn mul_color(a: Color, b: Color) -> Color {
+    Color::new(a.r * b.r, a.g * b.g, a.b * b.b, a.a * b.a)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::Vec2;
+
+    const JSON: &str = r#"{
+        "bones": [
+            { "name": "root" },
+            { "name": "arm", "parent": "root", "x": 10 }
+        ],
+        "slots": [ { "name": "hand", "bone": "arm", "attachment": "hand" } ],
+        "skins": [{
+            "name": "default",
+            "attachments": { "hand": { "hand": { "width": 4, "height": 2 } } }
+        }],
+        "animations": {
+            "idle": {
+                "bones": { "arm": { "rotate": [ { "value": 0 }, { "time": 1, "value": 90 } ] } },
+                "events": [ { "time": 0.5, "name": "half" } ]
+            },
+            "move": {
+                "bones": { "root": { "translate": [ { "x": 10, "y": 0 } ] } }
+            }
+        }
+    }"#;
+
+    fn skeleton() -> Skeleton {
+        let data = SkeletonData::from_json(JSON.as_bytes()).unwrap();
+        Skeleton::new(Arc::new(data))
+    }
+
+    #[test]
+    fn test_world_transform() {
+        let mut sk = skeleton();
+        sk.play("idle", true).unwrap();
+        sk.update(1.0);
+        // looped, so it's back at the start
+        let pos = sk
+            .bone_world("arm")
+            .unwrap()
+            .transform_point2(Vec2::new(1.0, 0.0));
+        assert!((pos - Vec2::new(11.0, 0.0)).length() < 0.001);
+
+        sk.update(0.5);
+        let pos = sk
+            .bone_world("arm")
+            .unwrap()
+            .transform_point2(Vec2::new(1.0, 0.0));
+        let expected = Vec2::new(10.0 + 45f32.to_radians().cos(), 45f32.to_radians().sin());
+        assert!((pos - expected).length() < 0.001);
+    }
+
+    #[test]
+    fn test_events() {
+        let mut sk = skeleton();
+        sk.play("idle", true).unwrap();
+        sk.update(0.4);
+        assert!(sk.events().is_empty());
+        sk.update(0.2);
+        assert_eq!(sk.events()[0].name, "half");
+        // wraps and fires again
+        sk.update(1.0);
+        assert_eq!(sk.events().len(), 1);
+
+        sk.play("idle", false).unwrap();
+        sk.update(2.0);
+        assert_eq!(sk.events().len(), 1);
+        assert!(sk.is_finished());
+        sk.update(1.0);
+        assert!(sk.events().is_empty());
+    }
+
+    #[test]
+    fn test_mix() {
+        let mut sk = skeleton().with_mix("idle", "move", 1.0);
+        sk.play("idle", true).unwrap();
+        sk.play("move", true).unwrap();
+        assert!(sk.is_mixing());
+        sk.update(0.5);
+        assert!((sk.bones()[0].x - 5.0).abs() < 0.001);
+        sk.update(0.5);
+        assert!(!sk.is_mixing());
+        assert!((sk.bones()[0].x - 10.0).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_regions() {
+        let sk = skeleton();
+        let region = sk.regions().next().unwrap();
+        assert_eq!(region.region, "hand");
+        // top-left of the image goes to the top-left of the centered quad
+        let pos = region.matrix.transform_point2(Vec2::ZERO);
+        assert!((pos - Vec2::new(8.0, 1.0)).length() < 0.001);
+    }
+
+    #[test]
+    fn test_lerp_angle() {
+        assert!((lerp_angle(350.0, 10.0, 0.5) - 360.0).abs() < 0.001);
+        assert!((lerp_angle(10.0, 350.0, 0.5) - 0.0).abs() < 0.001);
+    }
+}
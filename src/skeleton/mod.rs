@@ -0,0 +1,7 @@
+mod data;
+mod instance;
+mod render;
+
+pub use data::*;
+pub use instance::*;
+pub use render::*;
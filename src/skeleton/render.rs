@@ -0,0 +1,60 @@
+use super::instance::Skeleton;
+use crate::math::{vec2, Mat3, Rect, Vec2};
+use draw::{Draw2D, Sprite};
+use rustc_hash::FxHashMap;
+
+/// Sprites used by the skeleton attachments, indexed by region name
+#[derive(Default, Clone)]
+pub struct SkeletonAtlas {
+    regions: FxHashMap<String, (Sprite, Option<Rect>)>,
+}
+
+impl SkeletonAtlas {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Use the whole sprite for the region
+    pub fn with_region(mut self, name: &str, sprite: &Sprite) -> Self {
+        self.add_region(name, sprite, None);
+        self
+    }
+
+    /// Use a part of the sprite (like a packed atlas) for the region
+    pub fn with_region_crop(mut self, name: &str, sprite: &Sprite, crop: Rect) -> Self {
+        self.add_region(name, sprite, Some(crop));
+        self
+    }
+
+    pub fn add_region(&mut self, name: &str, sprite: &Sprite, crop: Option<Rect>) {
+        self.regions
+            .insert(name.to_string(), (sprite.clone(), crop));
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.regions.contains_key(name)
+    }
+}
+
+/// Draw the skeleton with its root at the given position using the sprite batcher
+pub fn draw_skeleton(draw: &mut Draw2D, skeleton: &Skeleton, atlas: &SkeletonAtlas, pos: Vec2) {
+    // skeletons are y-up, the screen is y-down
+    let root = Mat3::from_translation(pos) * Mat3::from_scale(vec2(1.0, -1.0));
+    for region in skeleton.regions() {
+        let Some((sprite, crop)) = atlas.regions.get(region.region) else {
+            log::warn!("Skeleton region '{}' not found on the atlas", region.region);
+            continue;
+        };
+
+        draw.push_matrix(root * region.matrix);
+        {
+            let mut img = draw.image(sprite);
+            if let Some(crop) = crop {
+                img.crop(crop.origin, crop.size);
+            }
+            img.size(vec2(region.width, region.height))
+                .color(region.color);
+        }
+        draw.pop_matrix();
+    }
+}
@@ -0,0 +1,303 @@
+use std::collections::VecDeque;
+
+/// Named event with properties
+#[derive(Clone, Debug, PartialEq)]
+pub struct TelemetryEvent {
+    pub name: String,
+    /// Seconds since the app started
+    pub time: f32,
+    pub props: Vec<(String, String)>,
+}
+
+impl TelemetryEvent {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            time: 0.0,
+            props: vec![],
+        }
+    }
+
+    pub fn with_prop(mut self, key: &str, value: impl ToString) -> Self {
+        self.props.push((key.to_string(), value.to_string()));
+        self
+    }
+
+    /// Serializes the event as a JSON object
+    pub fn to_json(&self) -> String {
+        let props = self
+            .props
+            .iter()
+            .map(|(k, v)| format!("{}:{}", json_str(k), json_str(v)))
+            .collect::<Vec<_>>()
+            .join(",");
+
+        format!(
+            "{{\"name\":{},\"time\":{},\"props\":{{{props}}}}}",
+            json_str(&self.name),
+            self.time
+        )
+    }
+}
+
+/// Destination of the batches, usually an HTTP endpoint
+pub trait TelemetrySink {
+    /// Sends a batch serialized as a JSON array
+    fn send(&mut self, batch: &str) -> Result<(), String>;
+}
+
+/// Opt-in event queue. Nothing is recorded until the user gives consent with
+/// `set_enabled(true)`. The events are sent in batches every `interval` seconds or
+/// when `batch_size` is reached, failed batches are kept to be sent later and the
+/// queue can be spooled to disk while offline
+pub struct Telemetry {
+    sink: Box<dyn TelemetrySink>,
+    queue: VecDeque<TelemetryEvent>,
+    enabled: bool,
+    batch_size: usize,
+    max_queued: usize,
+    interval: f32,
+    elapsed: f32,
+    time: f32,
+}
+
+impl Telemetry {
+    pub fn new<S: TelemetrySink + 'static>(sink: S) -> Self {
+        Self {
+            sink: Box::new(sink),
+            queue: VecDeque::new(),
+            enabled: false,
+            batch_size: 20,
+            max_queued: 1000,
+            interval: 30.0,
+            elapsed: 0.0,
+            time: 0.0,
+        }
+    }
+
+    pub fn with_batch_size(mut self, size: usize) -> Self {
+        self.batch_size = size.max(1);
+        self
+    }
+
+    /// Seconds between batches
+    pub fn with_interval(mut self, seconds: f32) -> Self {
+        self.interval = seconds.max(0.0);
+        self
+    }
+
+    /// Maximum events kept while the sink is unavailable, the oldest ones are dropped
+    pub fn with_max_queued(mut self, max: usize) -> Self {
+        self.max_queued = max.max(1);
+        self
+    }
+
+    /// Privacy flag, disabling it discards any queued event
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+        if !enabled {
+            self.queue.clear();
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub fn queued(&self) -> usize {
+        self.queue.len()
+    }
+
+    pub fn track(&mut self, mut event: TelemetryEvent) {
+        if !self.enabled {
+            return;
+        }
+
+        event.time = self.time;
+        self.queue.push_back(event);
+        if self.queue.len() > self.max_queued {
+            self.queue.pop_front();
+        }
+    }
+
+    /// Advances the timer and sends a batch when it's time
+    pub fn update(&mut self, dt: f32) -> Result<(), String> {
+        self.time += dt;
+        self.elapsed += dt;
+
+        let ready = self.elapsed >= self.interval || self.queue.len() >= self.batch_size;
+        if !ready {
+            return Ok(());
+        }
+
+        self.elapsed = 0.0;
+        self.send_batch()
+    }
+
+    /// Sends all the queued events in batches
+    pub fn flush(&mut self) -> Result<(), String> {
+        while !self.queue.is_empty() {
+            self.send_batch()?;
+        }
+
+        Ok(())
+    }
+
+    /// Serializes the queued events (one JSON object per line) to store them
+    /// on disk while offline, the queue is emptied
+    pub fn spool(&mut self) -> String {
+        self.queue
+            .drain(..)
+            .map(|evt| evt.to_json())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Restores a spool created with `spool`, the events will be sent as raw JSON
+    /// in batches of `batch_size`, stopping at the first batch that fails
+    pub fn restore_spool(&mut self, spool: &str) -> Result<(), String> {
+        if !self.enabled {
+            return Ok(());
+        }
+
+        let events = spool
+            .lines()
+            .filter(|l| !l.trim().is_empty())
+            .collect::<Vec<_>>();
+
+        events
+            .chunks(self.batch_size)
+            .try_for_each(|batch| self.sink.send(&format!("[{}]", batch.join(","))))
+    }
+
+    fn send_batch(&mut self) -> Result<(), String> {
+        if self.queue.is_empty() {
+            return Ok(());
+        }
+
+        let len = self.batch_size.min(self.queue.len());
+        let batch = self
+            .queue
+            .iter()
+            .take(len)
+            .map(|evt| evt.to_json())
+            .collect::<Vec<_>>()
+            .join(",");
+
+        // the events are removed only when the sink accepts them
+        self.sink.send(&format!("[{batch}]"))?;
+        self.queue.drain(..len);
+        Ok(())
+    }
+}
+
+fn json_str(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    #[derive(Clone, Default)]
+    struct TestSink {
+        online: Rc<RefCell<bool>>,
+        sent: Rc<RefCell<Vec<String>>>,
+    }
+
+    impl TelemetrySink for TestSink {
+        fn send(&mut self, batch: &str) -> Result<(), String> {
+            if !*self.online.borrow() {
+                return Err("offline".to_string());
+            }
+            self.sent.borrow_mut().push(batch.to_string());
+            Ok(())
+        }
+    }
+
+    fn telemetry() -> (Telemetry, TestSink) {
+        let sink = TestSink::default();
+        *sink.online.borrow_mut() = true;
+        let mut telemetry = Telemetry::new(sink.clone())
+            .with_batch_size(2)
+            .with_interval(10.0);
+        telemetry.set_enabled(true);
+        (telemetry, sink)
+    }
+
+    #[test]
+    fn test_json() {
+        let evt = TelemetryEvent::new("level_end")
+            .with_prop("level", 3)
+            .with_prop("name", "say \"hi\"");
+        assert_eq!(
+            evt.to_json(),
+            r#"{"name":"level_end","time":0,"props":{"level":"3","name":"say \"hi\""}}"#
+        );
+    }
+
+    #[test]
+    fn test_disabled() {
+        let (mut telemetry, sink) = telemetry();
+        telemetry.set_enabled(false);
+        telemetry.track(TelemetryEvent::new("a"));
+        assert_eq!(telemetry.queued(), 0);
+        telemetry.flush().unwrap();
+        assert!(sink.sent.borrow().is_empty());
+    }
+
+    #[test]
+    fn test_batching() {
+        let (mut telemetry, sink) = telemetry();
+        telemetry.track(TelemetryEvent::new("a"));
+        telemetry.update(1.0).unwrap();
+        assert!(sink.sent.borrow().is_empty());
+
+        telemetry.track(TelemetryEvent::new("b"));
+        telemetry.track(TelemetryEvent::new("c"));
+        telemetry.update(1.0).unwrap();
+        assert_eq!(sink.sent.borrow().len(), 1);
+        assert_eq!(telemetry.queued(), 1);
+
+        telemetry.update(10.0).unwrap();
+        assert_eq!(sink.sent.borrow().len(), 2);
+        assert_eq!(telemetry.queued(), 0);
+    }
+
+    #[test]
+    fn test_offline() {
+        let (mut telemetry, sink) = telemetry();
+        *sink.online.borrow_mut() = false;
+
+        telemetry.track(TelemetryEvent::new("a"));
+        telemetry.track(TelemetryEvent::new("b"));
+        telemetry.track(TelemetryEvent::new("c"));
+        assert!(telemetry.update(1.0).is_err());
+        assert_eq!(telemetry.queued(), 3);
+
+        let spool = telemetry.spool();
+        assert_eq!(telemetry.queued(), 0);
+
+        *sink.online.borrow_mut() = true;
+        telemetry.restore_spool(&spool).unwrap();
+        assert_eq!(sink.sent.borrow().len(), 2);
+        assert!(sink.sent.borrow()[0].starts_with("[{\"name\":\"a\""));
+        assert!(sink.sent.borrow()[1].starts_with("[{\"name\":\"c\""));
+    }
+}
@@ -0,0 +1,266 @@
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TangentMode {
+    /// Keeps the value until the next key
+    Step,
+    #[default]
+    Linear,
+    /// Catmull-Rom like interpolation using the neighbour keys
+    Smooth,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Keyframe {
+    pub time: f32,
+    pub value: f32,
+    /// Interpolation used from this key to the next one
+    pub mode: TangentMode,
+}
+
+impl Keyframe {
+    pub fn new(time: f32, value: f32) -> Self {
+        Self {
+            time,
+            value,
+            mode: TangentMode::Linear,
+        }
+    }
+
+    pub fn with_mode(mut self, mode: TangentMode) -> Self {
+        self.mode = mode;
+        self
+    }
+}
+
+/// Keyframed curve, the keys are kept sorted by time. Values before the first key
+/// or after the last one are clamped
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(from = "UnsortedCurveData"))]
+pub struct CurveData {
+    keys: Vec<Keyframe>,
+}
+
+/// Deserialized curve, the keys of hand-edited files may not be sorted
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct UnsortedCurveData {
+    keys: Vec<Keyframe>,
+}
+
+#[cfg(feature = "serde")]
+impl From<UnsortedCurveData> for CurveData {
+    fn from(data: UnsortedCurveData) -> Self {
+        Self::new(&data.keys)
+    }
+}
+
+impl CurveData {
+    pub fn new(keys: &[Keyframe]) -> Self {
+        let mut curve = Self {
+            keys: keys.to_vec(),
+        };
+        curve.sort();
+        curve
+    }
+
+    /// Creates a linear curve from `(time, value)` pairs
+    pub fn linear(points: &[(f32, f32)]) -> Self {
+        let keys = points
+            .iter()
+            .map(|(t, v)| Keyframe::new(*t, *v))
+            .collect::<Vec<_>>();
+        Self::new(&keys)
+    }
+
+    pub fn keys(&self) -> &[Keyframe] {
+        &self.keys
+    }
+
+    pub fn add_key(&mut self, key: Keyframe) {
+        let idx = self.keys.partition_point(|k| k.time <= key.time);
+        self.keys.insert(idx, key);
+    }
+
+    pub fn remove_key(&mut self, idx: usize) -> Option<Keyframe> {
+        (idx < self.keys.len()).then(|| self.keys.remove(idx))
+    }
+
+    /// Time of the first and the last key
+    pub fn range(&self) -> Option<(f32, f32)> {
+        Some((self.keys.first()?.time, self.keys.last()?.time))
+    }
+
+    pub fn evaluate(&self, time: f32) -> f32 {
+        let Some((first, last)) = self.keys.first().zip(self.keys.last()) else {
+            return 0.0;
+        };
+
+        if time <= first.time {
+            return first.value;
+        }
+
+        if time >= last.time {
+            return last.value;
+        }
+
+        let idx = self.keys.partition_point(|k| k.time <= time) - 1;
+        self.evaluate_segment(idx, time)
+    }
+
+    /// Samples the curve in a lookup table, useful for curves evaluated many times per frame
+    pub fn bake(&self, samples: usize) -> BakedCurve {
+        let samples = samples.max(2);
+        let (start, end) = self.range().unwrap_or((0.0, 0.0));
+        let step = (end - start) / (samples - 1) as f32;
+        let values = (0..samples)
+            .map(|i| self.evaluate(start + step * i as f32))
+            .collect();
+
+        BakedCurve { start, end, values }
+    }
+
+    fn evaluate_segment(&self, idx: usize, time: f32) -> f32 {
+        let k0 = self.keys[idx];
+        let k1 = self.keys[idx + 1];
+        let len = k1.time - k0.time;
+        if len <= f32::EPSILON {
+            return k1.value;
+        }
+
+        let t = (time - k0.time) / len;
+        match k0.mode {
+            TangentMode::Step => k0.value,
+            TangentMode::Linear => k0.value + (k1.value - k0.value) * t,
+            TangentMode::Smooth => {
+                // tangents scaled to the segment length
+                let prev = idx.checked_sub(1).map_or(k0, |i| self.keys[i]);
+                let next = self.keys.get(idx + 2).copied().unwrap_or(k1);
+                let m0 = tangent(prev, k1) * len;
+                let m1 = tangent(k0, next) * len;
+                hermite(k0.value, m0, k1.value, m1, t)
+            }
+        }
+    }
+
+    fn sort(&mut self) {
+        self.keys.sort_by(|a, b| a.time.total_cmp(&b.time));
+    }
+}
+
+fn tangent(a: Keyframe, b: Keyframe) -> f32 {
+    let len = b.time - a.time;
+    if len <= f32::EPSILON {
+        0.0
+    } else {
+        (b.value - a.value) / len
+    }
+}
+
+fn hermite(p0: f32, m0: f32, p1: f32, m1: f32, t: f32) -> f32 {
+    let t2 = t * t;
+    let t3 = t2 * t;
+    (2.0 * t3 - 3.0 * t2 + 1.0) * p0
+        + (t3 - 2.0 * t2 + t) * m0
+        + (-2.0 * t3 + 3.0 * t2) * p1
+        + (t3 - t2) * m1
+}
+
+/// Lookup table created with `CurveData::bake`
+#[derive(Clone, Debug, PartialEq)]
+pub struct BakedCurve {
+    start: f32,
+    end: f32,
+    values: Vec<f32>,
+}
+
+impl BakedCurve {
+    pub fn evaluate(&self, time: f32) -> f32 {
+        let len = self.end - self.start;
+        if len <= f32::EPSILON {
+            return self.values[0];
+        }
+
+        let pos = ((time - self.start) / len).clamp(0.0, 1.0) * (self.values.len() - 1) as f32;
+        let idx = pos.floor() as usize;
+        let next = (idx + 1).min(self.values.len() - 1);
+        let t = pos - idx as f32;
+        self.values[idx] + (self.values[next] - self.values[idx]) * t
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_linear() {
+        let curve = CurveData::linear(&[(0.0, 0.0), (1.0, 10.0), (2.0, 0.0)]);
+        assert_eq!(curve.evaluate(-1.0), 0.0);
+        assert_eq!(curve.evaluate(0.5), 5.0);
+        assert_eq!(curve.evaluate(1.0), 10.0);
+        assert_eq!(curve.evaluate(1.5), 5.0);
+        assert_eq!(curve.evaluate(3.0), 0.0);
+    }
+
+    #[test]
+    fn test_step() {
+        let curve = CurveData::new(&[
+            Keyframe::new(0.0, 1.0).with_mode(TangentMode::Step),
+            Keyframe::new(1.0, 2.0),
+        ]);
+        assert_eq!(curve.evaluate(0.99), 1.0);
+        assert_eq!(curve.evaluate(1.0), 2.0);
+    }
+
+    #[test]
+    fn test_smooth_passes_through_keys() {
+        let curve = CurveData::new(&[
+            Keyframe::new(0.0, 0.0).with_mode(TangentMode::Smooth),
+            Keyframe::new(1.0, 1.0).with_mode(TangentMode::Smooth),
+            Keyframe::new(2.0, 0.0),
+        ]);
+        assert_eq!(curve.evaluate(1.0), 1.0);
+        assert!(curve.evaluate(0.5) > 0.0 && curve.evaluate(0.5) < 1.0);
+
+        // symmetric peak
+        let a = curve.evaluate(0.75);
+        let b = curve.evaluate(1.25);
+        assert!((a - b).abs() < 0.0001);
+    }
+
+    #[test]
+    fn test_unsorted_keys() {
+        let mut curve = CurveData::linear(&[(1.0, 10.0), (0.0, 0.0)]);
+        assert_eq!(curve.evaluate(0.5), 5.0);
+
+        curve.add_key(Keyframe::new(0.5, 0.0));
+        assert_eq!(curve.keys()[1].time, 0.5);
+        assert_eq!(curve.evaluate(0.5), 0.0);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_deserialized_keys_are_sorted() {
+        let keys = vec![Keyframe::new(1.0, 10.0), Keyframe::new(0.0, 0.0)];
+        let curve = CurveData::from(UnsortedCurveData { keys });
+        assert_eq!(curve.keys()[0].time, 0.0);
+        assert_eq!(curve.evaluate(0.5), 5.0);
+    }
+
+    #[test]
+    fn test_baked() {
+        let curve = CurveData::linear(&[(0.0, 0.0), (2.0, 10.0)]);
+        let baked = curve.bake(16);
+        assert!((baked.evaluate(1.0) - 5.0).abs() < 0.0001);
+        assert_eq!(baked.evaluate(5.0), 10.0);
+    }
+
+    #[test]
+    fn test_empty() {
+        let curve = CurveData::default();
+        assert_eq!(curve.evaluate(1.0), 0.0);
+        assert_eq!(curve.bake(4).evaluate(1.0), 0.0);
+    }
+}
@@ -0,0 +1,168 @@
+use super::easing::*;
+use once_cell::sync::Lazy;
+use rustc_hash::FxHashMap;
+use std::sync::RwLock;
+
+// RwLock because configs can be resolved from loading threads
+static REGISTRY: Lazy<RwLock<EasingRegistry>> = Lazy::new(|| RwLock::new(EasingRegistry::new()));
+
+const BUILT_IN: [(&str, EaseFn); 31] = [
+    ("linear", LINEAR),
+    ("in_quad", IN_QUAD),
+    ("out_quad", OUT_QUAD),
+    ("in_out_quad", IN_OUT_QUAD),
+    ("in_cubic", IN_CUBIC),
+    ("out_cubic", OUT_CUBIC),
+    ("in_out_cubic", IN_OUT_CUBIC),
+    ("in_quart", IN_QUART),
+    ("out_quart", OUT_QUART),
+    ("in_out_quart", IN_OUT_QUART),
+    ("in_quint", IN_QUINT),
+    ("out_quint", OUT_QUINT),
+    ("in_out_quint", IN_OUT_QUINT),
+    ("in_sine", IN_SINE),
+    ("out_sine", OUT_SINE),
+    ("in_out_sine", IN_OUT_SINE),
+    ("in_expo", IN_EXPO),
+    ("out_expo", OUT_EXPO),
+    ("in_out_expo", IN_OUT_EXPO),
+    ("in_circ", IN_CIRC),
+    ("out_circ", OUT_CIRC),
+    ("in_out_circ", IN_OUT_CIRC),
+    ("in_elastic", IN_ELASTIC),
+    ("out_elastic", OUT_ELASTIC),
+    ("in_out_elastic", IN_OUT_ELASTIC),
+    ("in_back", IN_BACK),
+    ("out_back", OUT_BACK),
+    ("in_out_back", IN_OUT_BACK),
+    ("in_bounce", IN_BOUNCE),
+    ("out_bounce", OUT_BOUNCE),
+    ("in_out_bounce", IN_OUT_BOUNCE),
+];
+
+struct EasingRegistry {
+    easings: FxHashMap<String, EaseFn>,
+}
+
+impl EasingRegistry {
+    fn new() -> Self {
+        let easings = BUILT_IN
+            .iter()
+            .map(|(name, ease)| (name.to_string(), *ease))
+            .collect();
+        Self { easings }
+    }
+}
+
+fn is_built_in(name: &str) -> bool {
+    BUILT_IN.iter().any(|(n, _)| *n == name)
+}
+
+/// Registers a custom easing, it can be referenced later by name from configs.
+/// Built-in names (like `"out_bounce"`) cannot be replaced
+pub fn register_easing(name: &str, ease: EaseFn) -> Result<(), String> {
+    if is_built_in(name) {
+        return Err(format!(
+            "Easing '{name}' is built-in and cannot be replaced."
+        ));
+    }
+
+    REGISTRY
+        .write()
+        .unwrap()
+        .easings
+        .insert(name.to_string(), ease);
+    Ok(())
+}
+
+/// Removes a custom easing, returns `true` if it was registered
+pub fn unregister_easing(name: &str) -> bool {
+    !is_built_in(name) && REGISTRY.write().unwrap().easings.remove(name).is_some()
+}
+
+pub fn easing_by_name(name: &str) -> Option<EaseFn> {
+    REGISTRY.read().unwrap().easings.get(name).copied()
+}
+
+/// Names of all the easings available, sorted alphabetically
+pub fn easing_names() -> Vec<String> {
+    let mut names = REGISTRY
+        .read()
+        .unwrap()
+        .easings
+        .keys()
+        .cloned()
+        .collect::<Vec<_>>();
+    names.sort();
+    names
+}
+
+/// Samples `n` values of the easing from 0.0 to 1.0, useful to preview it (e.g. plotting it on an editor)
+pub fn easing_preview(ease: EaseFn, samples: usize) -> Vec<f32> {
+    let samples = samples.max(2);
+    let step = 1.0 / (samples - 1) as f32;
+    (0..samples).map(|i| ease(i as f32 * step)).collect()
+}
+
+/// Easing referenced by name, serialized as a plain string on configs and resolved
+/// using the registry at load time
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
+pub struct EasingName(pub String);
+
+impl Default for EasingName {
+    fn default() -> Self {
+        Self("linear".to_string())
+    }
+}
+
+impl EasingName {
+    pub fn new(name: &str) -> Self {
+        Self(name.to_string())
+    }
+
+    pub fn resolve(&self) -> Result<EaseFn, String> {
+        easing_by_name(&self.0).ok_or_else(|| format!("Unknown easing '{}'.", self.0))
+    }
+}
+
+impl From<&str> for EasingName {
+    fn from(value: &str) -> Self {
+        Self::new(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const STEP: EaseFn = |t: f32| -> f32 { (t * 4.0).floor() / 4.0 };
+
+    #[test]
+    fn test_built_in() {
+        let ease = EasingName::new("out_bounce").resolve().unwrap();
+        assert_eq!(ease(0.3), OUT_BOUNCE(0.3));
+        assert!(register_easing("linear", STEP).is_err());
+        assert!(!unregister_easing("linear"));
+    }
+
+    #[test]
+    fn test_custom() {
+        assert!(EasingName::new("test_step").resolve().is_err());
+
+        register_easing("test_step", STEP).unwrap();
+        let ease = EasingName::from("test_step").resolve().unwrap();
+        assert_eq!(ease(0.3), 0.25);
+        assert!(easing_names().contains(&"test_step".to_string()));
+
+        assert!(unregister_easing("test_step"));
+        assert!(easing_by_name("test_step").is_none());
+    }
+
+    #[test]
+    fn test_preview() {
+        let values = easing_preview(LINEAR, 5);
+        assert_eq!(values, [0.0, 0.25, 0.5, 0.75, 1.0]);
+    }
+}
@@ -1,8 +1,14 @@
+mod curve;
 mod easing;
+mod easing_registry;
+mod timeline;
 mod tween_map;
 mod tweens;
 
+pub use curve::*;
 pub use easing::*;
+pub use easing_registry::*;
+pub use timeline::*;
 pub use tween_map::*;
 pub use tweens::*;
 
@@ -0,0 +1,434 @@
+use super::{EaseFn, EasingName};
+
+/// Action played by a timeline, the game decides how to apply it when it gets the events
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TimelineAction {
+    /// Moves the entity referenced by `target` to the position
+    Move {
+        target: String,
+        to: [f32; 2],
+        duration: f32,
+        #[cfg_attr(feature = "serde", serde(default))]
+        easing: EasingName,
+    },
+    PlaySound {
+        sound: String,
+    },
+    ShowText {
+        text: String,
+        duration: f32,
+    },
+    Camera {
+        to: [f32; 2],
+        #[cfg_attr(feature = "serde", serde(default = "default_zoom"))]
+        zoom: f32,
+        duration: f32,
+        #[cfg_attr(feature = "serde", serde(default))]
+        easing: EasingName,
+    },
+    Wait {
+        duration: f32,
+    },
+    /// Calls the game code registered with `name`
+    Call {
+        name: String,
+    },
+}
+
+#[cfg(feature = "serde")]
+fn default_zoom() -> f32 {
+    1.0
+}
+
+impl TimelineAction {
+    pub fn duration(&self) -> f32 {
+        match self {
+            TimelineAction::Move { duration, .. }
+            | TimelineAction::ShowText { duration, .. }
+            | TimelineAction::Camera { duration, .. }
+            | TimelineAction::Wait { duration } => duration.max(0.0),
+            TimelineAction::PlaySound { .. } | TimelineAction::Call { .. } => 0.0,
+        }
+    }
+
+    fn easing(&self) -> Option<&EasingName> {
+        match self {
+            TimelineAction::Move { easing, .. } | TimelineAction::Camera { easing, .. } => {
+                Some(easing)
+            }
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TimelineKey {
+    /// Start time in seconds, if not set it starts when the previous key of the track ends
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub at: Option<f32>,
+    pub action: TimelineAction,
+}
+
+/// Actions of a track are played in order, the tracks are played at the same time
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TimelineTrack {
+    pub name: String,
+    pub keys: Vec<TimelineKey>,
+}
+
+impl TimelineTrack {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            keys: vec![],
+        }
+    }
+
+    /// Adds an action that starts when the previous one ends
+    pub fn then(mut self, action: TimelineAction) -> Self {
+        self.keys.push(TimelineKey { at: None, action });
+        self
+    }
+
+    /// Adds an action that starts at `time` seconds
+    pub fn at(mut self, time: f32, action: TimelineAction) -> Self {
+        self.keys.push(TimelineKey {
+            at: Some(time),
+            action,
+        });
+        self
+    }
+}
+
+/// Definition of a cutscene, it can be loaded from a file with the `serde` feature
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TimelineData {
+    pub tracks: Vec<TimelineTrack>,
+}
+
+impl TimelineData {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_track(mut self, track: TimelineTrack) -> Self {
+        self.tracks.push(track);
+        self
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum TimelineActionState {
+    Start,
+    /// Eased progress from 0.0 to 1.0
+    Update(f32),
+    End,
+}
+
+/// Sent for each action when it starts, on each update while it runs and when it ends.
+/// Every action gets `Start`, at least one `Update` with `1.0` and `End`, even if skipped
+#[derive(Copy, Clone, Debug)]
+pub struct TimelineEvent<'a> {
+    pub track: &'a str,
+    pub action: &'a TimelineAction,
+    pub state: TimelineActionState,
+    /// The timeline was skipped, usually sounds and text must be ignored
+    pub skipped: bool,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum EntryState {
+    Pending,
+    Running,
+    Done,
+}
+
+#[derive(Clone, Debug)]
+struct Entry {
+    track: usize,
+    key: usize,
+    start: f32,
+    end: f32,
+    easing: Option<EaseFn>,
+    state: EntryState,
+}
+
+/// Plays a `TimelineData` calling back the game with the actions to apply.
+/// Cutscenes can be skipped, jumping every action to its end.
+#[derive(Clone, Debug)]
+pub struct Timeline {
+    data: TimelineData,
+    entries: Vec<Entry>,
+    duration: f32,
+    time: f32,
+    playing: bool,
+}
+
+impl Timeline {
+    /// Returns an error if any easing name is not registered
+    pub fn new(data: TimelineData) -> Result<Self, String> {
+        let mut entries = vec![];
+        for (track_idx, track) in data.tracks.iter().enumerate() {
+            let mut last_end = 0.0;
+            for (key_idx, key) in track.keys.iter().enumerate() {
+                let start = key.at.unwrap_or(last_end).max(0.0);
+                let end = start + key.action.duration();
+                let easing = key.action.easing().map(|name| name.resolve()).transpose()?;
+
+                entries.push(Entry {
+                    track: track_idx,
+                    key: key_idx,
+                    start,
+                    end,
+                    easing,
+                    state: EntryState::Pending,
+                });
+                last_end = end;
+            }
+        }
+
+        // stable, so actions at the same time keep the definition order
+        entries.sort_by(|a, b| a.start.total_cmp(&b.start));
+        let duration = entries.iter().fold(0.0, |acc: f32, e| acc.max(e.end));
+
+        Ok(Self {
+            data,
+            entries,
+            duration,
+            time: 0.0,
+            playing: false,
+        })
+    }
+
+    pub fn data(&self) -> &TimelineData {
+        &self.data
+    }
+
+    pub fn play(&mut self) {
+        self.playing = true;
+    }
+
+    pub fn pause(&mut self) {
+        self.playing = false;
+    }
+
+    pub fn is_playing(&self) -> bool {
+        self.playing
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.entries.iter().all(|e| e.state == EntryState::Done)
+    }
+
+    /// Current time in seconds
+    pub fn time(&self) -> f32 {
+        self.time
+    }
+
+    /// Seconds until the last action ends
+    pub fn duration(&self) -> f32 {
+        self.duration
+    }
+
+    /// Goes back to the start, the actions will be played again
+    pub fn reset(&mut self) {
+        self.time = 0.0;
+        self.entries
+            .iter_mut()
+            .for_each(|e| e.state = EntryState::Pending);
+    }
+
+    /// Advances the time sending the events of the actions running
+    pub fn update<F>(&mut self, delta: f32, cb: F)
+    where
+        F: FnMut(TimelineEvent),
+    {
+        if !self.playing {
+            return;
+        }
+
+        self.time += delta;
+        self.process(self.time, false, cb);
+        if self.is_finished() {
+            self.playing = false;
+        }
+    }
+
+    /// Ends every pending or running action sending their events with `skipped: true`
+    pub fn skip<F>(&mut self, cb: F)
+    where
+        F: FnMut(TimelineEvent),
+    {
+        self.time = self.duration;
+        self.process(f32::INFINITY, true, cb);
+        self.playing = false;
+    }
+
+    fn process<F>(&mut self, time: f32, skipped: bool, mut cb: F)
+    where
+        F: FnMut(TimelineEvent),
+    {
+        for entry in &mut self.entries {
+            if time < entry.start {
+                // sorted by start time
+                break;
+            }
+
+            if entry.state == EntryState::Done {
+                continue;
+            }
+
+            let track = &self.data.tracks[entry.track];
+            let mut send = |state| {
+                cb(TimelineEvent {
+                    track: &track.name,
+                    action: &track.keys[entry.key].action,
+                    state,
+                    skipped,
+                })
+            };
+
+            if entry.state == EntryState::Pending {
+                send(TimelineActionState::Start);
+                entry.state = EntryState::Running;
+            }
+
+            let len = entry.end - entry.start;
+            let progress = if len > 0.0 {
+                ((time - entry.start) / len).min(1.0)
+            } else {
+                1.0
+            };
+            let eased = entry.easing.map_or(progress, |ease| ease(progress));
+            send(TimelineActionState::Update(eased));
+
+            if time >= entry.end {
+                send(TimelineActionState::End);
+                entry.state = EntryState::Done;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wait(duration: f32) -> TimelineAction {
+        TimelineAction::Wait { duration }
+    }
+
+    fn call(name: &str) -> TimelineAction {
+        TimelineAction::Call {
+            name: name.to_string(),
+        }
+    }
+
+    fn calls(events: &[(String, TimelineActionState)]) -> Vec<&str> {
+        events
+            .iter()
+            .filter(|(_, state)| *state == TimelineActionState::End)
+            .map(|(name, _)| name.as_str())
+            .collect()
+    }
+
+    fn record(events: &mut Vec<(String, TimelineActionState)>) -> impl FnMut(TimelineEvent) + '_ {
+        |evt| {
+            if let TimelineAction::Call { name } = evt.action {
+                events.push((name.clone(), evt.state));
+            }
+        }
+    }
+
+    #[test]
+    fn test_sequential_track() {
+        let data = TimelineData::new().with_track(
+            TimelineTrack::new("main")
+                .then(call("a"))
+                .then(wait(1.0))
+                .then(call("b")),
+        );
+        let mut timeline = Timeline::new(data).unwrap();
+        assert_eq!(timeline.duration(), 1.0);
+
+        let mut events = vec![];
+        timeline.update(0.5, record(&mut events));
+        assert!(events.is_empty(), "not playing");
+
+        timeline.play();
+        timeline.update(0.5, record(&mut events));
+        assert_eq!(calls(&events), vec!["a"]);
+
+        timeline.update(0.5, record(&mut events));
+        assert_eq!(calls(&events), vec!["a", "b"]);
+        assert!(timeline.is_finished());
+        assert!(!timeline.is_playing());
+    }
+
+    #[test]
+    fn test_parallel_tracks_and_progress() {
+        let data = TimelineData::new()
+            .with_track(TimelineTrack::new("a").at(1.0, call("late")))
+            .with_track(TimelineTrack::new("b").then(TimelineAction::Move {
+                target: "hero".to_string(),
+                to: [10.0, 0.0],
+                duration: 2.0,
+                easing: EasingName::default(),
+            }));
+        let mut timeline = Timeline::new(data).unwrap();
+        timeline.play();
+
+        let mut progress = vec![];
+        timeline.update(1.0, |evt| {
+            if let TimelineActionState::Update(p) = evt.state {
+                progress.push((evt.track.to_string(), p));
+            }
+        });
+        assert_eq!(
+            progress,
+            vec![("b".to_string(), 0.5), ("a".to_string(), 1.0)]
+        );
+    }
+
+    #[test]
+    fn test_skip() {
+        let data = TimelineData::new().with_track(
+            TimelineTrack::new("main")
+                .then(call("a"))
+                .then(wait(10.0))
+                .then(call("b")),
+        );
+        let mut timeline = Timeline::new(data).unwrap();
+        timeline.play();
+
+        let mut events = vec![];
+        timeline.update(1.0, record(&mut events));
+
+        let mut skipped = vec![];
+        timeline.skip(|evt| skipped.push((evt.action.clone(), evt.state, evt.skipped)));
+        assert!(timeline.is_finished());
+        assert_eq!(timeline.time(), 10.0);
+        assert!(skipped.iter().all(|(_, _, skipped)| *skipped));
+
+        // the wait only ends, 'b' starts, updates and ends
+        assert_eq!(skipped.len(), 5);
+        assert_eq!(skipped[4], (call("b"), TimelineActionState::End, true));
+    }
+
+    #[test]
+    fn test_invalid_easing() {
+        let data = TimelineData::new().with_track(TimelineTrack::new("main").then(
+            TimelineAction::Camera {
+                to: [0.0, 0.0],
+                zoom: 1.0,
+                duration: 1.0,
+                easing: EasingName::new("nope"),
+            },
+        ));
+        assert!(Timeline::new(data).is_err());
+    }
+}
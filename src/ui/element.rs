@@ -71,6 +71,15 @@ pub trait UIElement<S>: Downcast + Send + Sync {
     fn relayout(&mut self, _state: &mut S, _events: &mut UIEvents<S>, _parent_bounds: Rect) {}
     fn update(&mut self, _state: &mut S, _events: &mut UIEvents<S>, _metadata: UINodeMetadata) {}
     fn render(&mut self, _draw: &mut Draw2D, _state: &mut S, _metadata: UINodeMetadata) {}
+    /// Used with the manager's render cache, must return `true` when the element
+    /// will render something different (e.g. animations or changes in the state)
+    fn needs_redraw(&self) -> bool {
+        false
+    }
+    /// Name displayed by the manager's debug overlay
+    fn debug_name(&self) -> &str {
+        std::any::type_name::<Self>()
+    }
 }
 
 impl_downcast!(UIElement<S>);
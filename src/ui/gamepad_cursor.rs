@@ -0,0 +1,178 @@
+use corelib::input::{GamepadButton, GamepadId, GamepadStick};
+use corelib::math::Vec2;
+
+type AccelerationFn = dyn Fn(f32) -> f32 + Send + Sync;
+
+/// Virtual cursor moved with a gamepad stick that replaces the mouse for the UI.
+/// Enabled with `UIManager::set_gamepad_cursor`, the mouse still works and moves it too.
+pub struct UIGamepadCursor {
+    pub(super) gamepad: Option<GamepadId>,
+    pub(super) stick: GamepadStick,
+    pub(super) button: GamepadButton,
+    speed: f32,
+    max_speed: f32,
+    acceleration_time: f32,
+    acceleration: Box<AccelerationFn>,
+    pub(super) snap_distance: f32,
+
+    pub(super) pos: Vec2,
+    hold_time: f32,
+    pub(super) moving: bool,
+}
+
+impl Default for UIGamepadCursor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl UIGamepadCursor {
+    /// Cursor moved by the right stick of any gamepad, the south button clicks
+    pub fn new() -> Self {
+        Self {
+            gamepad: None,
+            stick: GamepadStick::Right,
+            button: GamepadButton::South,
+            speed: 400.0,
+            max_speed: 1200.0,
+            acceleration_time: 0.6,
+            acceleration: Box::new(|t| t * t),
+            snap_distance: 0.0,
+
+            pos: Vec2::ZERO,
+            hold_time: 0.0,
+            moving: false,
+        }
+    }
+
+    /// Only this gamepad will move the cursor
+    pub fn with_gamepad(mut self, id: GamepadId) -> Self {
+        self.gamepad = Some(id);
+        self
+    }
+
+    pub fn with_stick(mut self, stick: GamepadStick) -> Self {
+        self.stick = stick;
+        self
+    }
+
+    /// Button that acts as the left mouse button
+    pub fn with_button(mut self, btn: GamepadButton) -> Self {
+        self.button = btn;
+        self
+    }
+
+    /// Pixels per second with the stick fully tilted, it goes from `speed`
+    /// to `max_speed` while the stick is held for `time` seconds
+    pub fn with_speed(mut self, speed: f32, max_speed: f32, time: f32) -> Self {
+        self.speed = speed;
+        self.max_speed = max_speed.max(speed);
+        self.acceleration_time = time;
+        self
+    }
+
+    /// Maps the hold progress (0.0..=1.0) to the speed between `speed` and `max_speed`,
+    /// it is quadratic by default
+    pub fn with_acceleration_curve<F>(mut self, curve: F) -> Self
+    where
+        F: Fn(f32) -> f32 + Send + Sync + 'static,
+    {
+        self.acceleration = Box::new(curve);
+        self
+    }
+
+    /// When the stick is released the cursor jumps to the center of the
+    /// closest element that accepts input if it is closer than `distance`
+    pub fn with_snap(mut self, distance: f32) -> Self {
+        self.snap_distance = distance;
+        self
+    }
+
+    /// Position in screen coordinates
+    pub fn position(&self) -> Vec2 {
+        self.pos
+    }
+
+    pub fn set_position(&mut self, pos: Vec2) {
+        self.pos = pos;
+        self.hold_time = 0.0;
+    }
+
+    /// Moves the cursor using the stick value, keeping it inside of `size`.
+    /// Returns true if the cursor moved
+    pub(super) fn step(&mut self, stick: Vec2, dt: f32, size: Vec2) -> bool {
+        // sticks use y up, the screen y down
+        let dir = Vec2::new(stick.x, -stick.y);
+        self.moving = dir != Vec2::ZERO;
+        if !self.moving {
+            self.hold_time = 0.0;
+            return false;
+        }
+
+        self.hold_time += dt;
+        let t = if self.acceleration_time > 0.0 {
+            (self.hold_time / self.acceleration_time).min(1.0)
+        } else {
+            1.0
+        };
+
+        let accel = (self.acceleration)(t).clamp(0.0, 1.0);
+        let speed = self.speed + (self.max_speed - self.speed) * accel;
+        let last = self.pos;
+        self.pos = (self.pos + dir * speed * dt).clamp(Vec2::ZERO, size.max(Vec2::ZERO));
+        self.pos != last
+    }
+}
+
+/// Returns the closest target to `pos` that is at less than `distance`
+pub(super) fn snap_target(pos: Vec2, targets: &[Vec2], distance: f32) -> Option<Vec2> {
+    targets
+        .iter()
+        .map(|target| (*target, target.distance_squared(pos)))
+        .filter(|(_, dist)| *dist <= distance * distance)
+        .min_by(|(_, a), (_, b)| a.total_cmp(b))
+        .map(|(target, _)| target)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use corelib::math::vec2;
+
+    #[test]
+    fn test_step_acceleration() {
+        let mut cursor = UIGamepadCursor::new().with_speed(100.0, 300.0, 1.0);
+        let size = vec2(10000.0, 10000.0);
+        cursor.set_position(vec2(100.0, 100.0));
+
+        assert!(cursor.step(vec2(1.0, 0.0), 0.5, size));
+        assert_eq!(cursor.position(), vec2(175.0, 100.0));
+
+        assert!(cursor.step(vec2(1.0, 0.0), 0.5, size));
+        assert_eq!(cursor.position(), vec2(325.0, 100.0));
+
+        // releasing the stick resets the acceleration
+        assert!(!cursor.step(Vec2::ZERO, 0.5, size));
+        assert!(cursor.step(vec2(0.0, 1.0), 0.5, size));
+        assert_eq!(cursor.position(), vec2(325.0, 25.0));
+    }
+
+    #[test]
+    fn test_step_clamped() {
+        let mut cursor = UIGamepadCursor::new();
+        cursor.set_position(vec2(5.0, 5.0));
+        cursor.step(vec2(-1.0, 1.0), 1.0, vec2(100.0, 100.0));
+        assert_eq!(cursor.position(), Vec2::ZERO);
+        assert!(!cursor.step(vec2(-1.0, 0.0), 1.0, vec2(100.0, 100.0)));
+    }
+
+    #[test]
+    fn test_snap_target() {
+        let targets = [vec2(0.0, 0.0), vec2(50.0, 50.0), vec2(100.0, 0.0)];
+        assert_eq!(
+            snap_target(vec2(60.0, 40.0), &targets, 20.0),
+            Some(vec2(50.0, 50.0))
+        );
+        assert_eq!(snap_target(vec2(300.0, 300.0), &targets, 20.0), None);
+    }
+}
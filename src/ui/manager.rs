@@ -1,8 +1,18 @@
+use corelib::gfx::Color;
+#[cfg(feature = "gamepad")]
+use corelib::input::{
+    gamepad_stick, gamepads_available, is_gamepad_btn_down, is_gamepad_btn_pressed,
+    is_gamepad_btn_released, MouseButtonList,
+};
 use corelib::input::{
     is_mouse_moving, is_mouse_scrolling, mouse_btns_down, mouse_btns_pressed, mouse_btns_released,
     mouse_position, mouse_wheel_delta, MouseButton,
 };
+#[cfg(feature = "gamepad")]
+use corelib::math::{vec2, vec3};
 use corelib::math::{Mat3, Mat4, Rect, Vec2};
+#[cfg(feature = "gamepad")]
+use corelib::time;
 use draw::{BaseCam2D, Draw2D, Transform2D};
 use heapless::FnvIndexSet;
 use rustc_hash::{FxBuildHasher, FxHashMap, FxHashSet};
@@ -16,8 +26,13 @@ use utils::helpers::next_pot2;
 
 use crate::ui::element::{UIElement, UIRoot};
 use crate::ui::events::{EventListener, ListenerType};
+#[cfg(feature = "gamepad")]
+use crate::ui::gamepad_cursor::snap_target;
 use crate::ui::graph::{UIGraph, UIHandler, UINode};
-use crate::ui::{UIEvents, UIInput, UINodeMetadata};
+use crate::ui::popup::place_popup;
+#[cfg(feature = "gamepad")]
+use crate::ui::UIGamepadCursor;
+use crate::ui::{UIEvents, UIInput, UINodeMetadata, UIPopup, UIPopupDismissed};
 
 /// Defines the actions to take after an input event is triggered.
 ///
@@ -93,6 +108,20 @@ pub struct UIManager<S: 'static> {
     clicked: FxHashSet<(UIRawHandler, MouseButton)>,
     scrolling: FxHashMap<UIRawHandler, Vec2>,
     dragging: FxHashMap<(UIRawHandler, MouseButton), Vec2>,
+    #[cfg(feature = "gamepad")]
+    gamepad_cursor: Option<UIGamepadCursor>,
+
+    // render cache
+    cache_enabled: bool,
+    cache_dirty: bool,
+    cache: Option<(Draw2D, Mat3)>,
+
+    debug: bool,
+
+    // layers, popups are added to the overlay to be always on top of the content
+    content: UIRawHandler,
+    overlay: UIRawHandler,
+    popups: Vec<(UIRawHandler, UIPopup)>,
 }
 
 impl<S> Default for UIManager<S> {
@@ -114,8 +143,16 @@ impl<S> UIManager<S> {
             alpha: 1.0,
         };
 
+        let mut graph = UIGraph::new(node);
+        let content = graph.add(UIRoot {
+            transform: Transform2D::default(),
+        });
+        let overlay = graph.add(UIRoot {
+            transform: Transform2D::default(),
+        });
+
         Self {
-            graph: UIGraph::new(node),
+            graph,
             events: UIEvents::default(),
             listener_id: 0,
 
@@ -140,12 +177,173 @@ impl<S> UIManager<S> {
             clicked: Default::default(),
             scrolling: Default::default(),
             dragging: Default::default(),
+            #[cfg(feature = "gamepad")]
+            gamepad_cursor: None,
+
+            cache_enabled: false,
+            cache_dirty: true,
+            cache: None,
+
+            debug: false,
+
+            content: content.raw(),
+            overlay: overlay.raw(),
+            popups: vec![],
         }
     }
 
+    /// Adds an element on top of the rest of the UI, placed as the `popup` options say.
+    /// Tooltips, dropdown menus or modal dialogs are usually popups
+    pub fn open_popup<T: UIElement<S> + 'static>(
+        &mut self,
+        element: T,
+        popup: UIPopup,
+    ) -> UIHandler<T> {
+        self.cache_dirty = true;
+
+        // the overlay node is never removed
+        let handler = self.graph.add_to(self.overlay, element).unwrap();
+        self.popups.push((handler.raw(), popup));
+        handler
+    }
+
+    pub fn close_popup<T: UIElement<S> + 'static>(
+        &mut self,
+        handler: UIHandler<T>,
+    ) -> Result<(), String> {
+        if !self.is_popup_open(handler) {
+            return Err("The element is not an open popup".to_string());
+        }
+
+        self.remove(handler)
+    }
+
+    pub fn is_popup_open<T>(&self, handler: UIHandler<T>) -> bool {
+        let raw = handler.raw();
+        self.popups.iter().any(|(popup, _)| *popup == raw)
+    }
+
+    /// Updates the options of an open popup, useful to follow the anchor
+    pub fn set_popup<T>(&mut self, handler: UIHandler<T>, popup: UIPopup) -> Result<(), String> {
+        let raw = handler.raw();
+        let (_, opts) = self
+            .popups
+            .iter_mut()
+            .find(|(p, _)| *p == raw)
+            .ok_or_else(|| "The element is not a popup".to_string())?;
+
+        *opts = popup;
+        Ok(())
+    }
+
+    fn place_popups(&mut self) {
+        let bounds = Rect::new(Vec2::ZERO, self.graph.root_transform_mut().size());
+        self.popups.iter().for_each(|(raw, popup)| {
+            let Some(element) = self.graph.element_mut(*raw) else {
+                return;
+            };
+
+            let transform = element.transform_mut();
+            let size = transform.size() * transform.scale();
+            let pos = place_popup(popup, size, bounds);
+            transform.set_translation(pos + transform.anchor() * size);
+        });
+    }
+
+    fn dismiss_popups(&mut self) {
+        if !self.popups.iter().any(|(_, p)| p.dismiss_on_click_away) {
+            return;
+        }
+
+        if mouse_btns_pressed().is_empty() {
+            return;
+        }
+
+        let mut dismissed: SmallVec<UIRawHandler, 4> = SmallVec::new();
+        self.popups.retain(|(raw, popup)| {
+            if !popup.dismiss_on_click_away {
+                return true;
+            }
+
+            let inside = raw
+                .idx
+                .and_then(|idx| self.graph.scene_graph.get(idx))
+                .is_some_and(|node| {
+                    let node = &node.value;
+                    let point = node.screen_to_local(
+                        self.screen_mouse_pos,
+                        self.size,
+                        self.inverse_projection,
+                    );
+                    Rect::new(Vec2::ZERO, node.inner.transform().size()).contains(point)
+                });
+
+            if !inside {
+                dismissed.push(*raw);
+            }
+
+            inside
+        });
+
+        dismissed.into_iter().for_each(|raw| {
+            let _ = self.remove(UIHandler::<UIRoot>::from(raw));
+            self.events.send_global(UIPopupDismissed { popup: raw });
+        });
+    }
+
+    /// Draws the bounds, input boxes and names of the nodes over the UI
+    pub fn set_debug(&mut self, enabled: bool) {
+        self.debug = enabled;
+    }
+
+    pub fn is_debug(&self) -> bool {
+        self.debug
+    }
+
+    /// Enables a virtual cursor moved by a gamepad stick that works as the mouse for the UI,
+    /// it starts at the current mouse position. `None` goes back to the mouse only
+    #[cfg(feature = "gamepad")]
+    pub fn set_gamepad_cursor(&mut self, cursor: Option<UIGamepadCursor>) {
+        self.gamepad_cursor = cursor.map(|mut cursor| {
+            cursor.set_position(self.screen_mouse_pos);
+            cursor
+        });
+    }
+
+    #[cfg(feature = "gamepad")]
+    pub fn gamepad_cursor(&self) -> Option<&UIGamepadCursor> {
+        self.gamepad_cursor.as_ref()
+    }
+
+    #[cfg(feature = "gamepad")]
+    pub fn gamepad_cursor_mut(&mut self) -> Option<&mut UIGamepadCursor> {
+        self.gamepad_cursor.as_mut()
+    }
+
+    /// Records the draw commands and replays them each frame until something changes,
+    /// skipping the elements' render. The cache is invalidated when the graph, layout,
+    /// alpha or enabled state changes, on input or events, when an element is borrowed
+    /// mutably or when any element returns `true` from `needs_redraw`
+    pub fn set_render_cache(&mut self, enabled: bool) {
+        self.cache_enabled = enabled;
+        self.cache = None;
+    }
+
+    pub fn is_render_cache_enabled(&self) -> bool {
+        self.cache_enabled
+    }
+
+    /// Forces the elements to render again next frame when the render cache is enabled
+    pub fn invalidate_render(&mut self) {
+        self.cache_dirty = true;
+    }
+
     #[inline(always)]
     pub fn add<T: UIElement<S> + 'static>(&mut self, element: T) -> UIHandler<T> {
-        self.graph.add(element)
+        self.cache_dirty = true;
+
+        // the content node is never removed
+        self.graph.add_to(self.content, element).unwrap()
     }
 
     #[inline(always)]
@@ -154,6 +352,7 @@ impl<S> UIManager<S> {
         parent: H,
         element: T,
     ) -> Result<UIHandler<T>, String> {
+        self.cache_dirty = true;
         self.graph.add_to(parent, element)
     }
 
@@ -170,6 +369,7 @@ impl<S> UIManager<S> {
     where
         T: UIElement<S> + 'static,
     {
+        self.cache_dirty = true;
         self.graph.element_mut_as(handler)
     }
 
@@ -186,6 +386,7 @@ impl<S> UIManager<S> {
     where
         H: Into<UIRawHandler>,
     {
+        self.cache_dirty = true;
         self.graph.element_mut(handler)
     }
 
@@ -194,6 +395,8 @@ impl<S> UIManager<S> {
         &mut self,
         handler: UIHandler<T>,
     ) -> Result<(), String> {
+        self.cache_dirty = true;
+        self.popups.retain(|(raw, _)| *raw != handler.raw());
         self.graph.remove(handler)
     }
 
@@ -209,11 +412,103 @@ impl<S> UIManager<S> {
         self.root_matrix = cam.transform();
         self.inverse_root_matrix = cam.inverse_transform();
 
+        // the layers fill the root
+        let size = cam.bounds().size;
+        [self.content, self.overlay].into_iter().for_each(|layer| {
+            if let Some(layer) = self.graph.element_mut(layer) {
+                layer.transform_mut().set_size(size);
+            }
+        });
+
         if self.enable_input {
             self.screen_mouse_pos = mouse_position();
+
+            // the mouse takes control of the cursor when it moves
+            #[cfg(feature = "gamepad")]
+            if let Some(cursor) = &mut self.gamepad_cursor {
+                if is_mouse_moving() {
+                    cursor.set_position(self.screen_mouse_pos);
+                } else {
+                    self.screen_mouse_pos = cursor.position();
+                }
+            }
         }
     }
 
+    // modal popups block the input for the content layer
+    fn blocked_nodes(&self) -> Option<FxHashSet<UIRawHandler>> {
+        self.popups.iter().any(|(_, p)| p.modal).then(|| {
+            let mut blocked = FxHashSet::default();
+            self.graph.scene_graph.iter().for_each(|(parent, node)| {
+                let parent = UIRawHandler { idx: parent.idx };
+                if parent == self.content || blocked.contains(&parent) {
+                    blocked.insert(UIRawHandler { idx: node.idx });
+                }
+            });
+            blocked
+        })
+    }
+
+    /// Moves the gamepad cursor and returns if it moved and the state of its button
+    #[cfg(feature = "gamepad")]
+    fn update_gamepad_cursor(&mut self) -> Option<GamepadCursorInput> {
+        let mut cursor = self.gamepad_cursor.take()?;
+        let gamepad = cursor
+            .gamepad
+            .or_else(|| gamepads_available().iter().next().copied());
+
+        let input = gamepad.map(|id| {
+            let was_moving = cursor.moving;
+            let mut moved = cursor.step(
+                gamepad_stick(id, cursor.stick),
+                time::delta_f32(),
+                self.size,
+            );
+
+            let released_stick = was_moving && !cursor.moving;
+            if released_stick && cursor.snap_distance > 0.0 {
+                let targets = self.input_targets();
+                if let Some(pos) = snap_target(cursor.pos, &targets, cursor.snap_distance) {
+                    moved |= pos != cursor.pos;
+                    cursor.set_position(pos);
+                }
+            }
+
+            GamepadCursorInput {
+                moved,
+                down: is_gamepad_btn_down(id, cursor.button),
+                pressed: is_gamepad_btn_pressed(id, cursor.button),
+                released: is_gamepad_btn_released(id, cursor.button),
+            }
+        });
+
+        self.screen_mouse_pos = cursor.position();
+        self.gamepad_cursor = Some(cursor);
+        input
+    }
+
+    /// Screen position of the center of the elements that can receive input
+    #[cfg(feature = "gamepad")]
+    fn input_targets(&self) -> Vec<Vec2> {
+        let blocked = self.blocked_nodes();
+        self.graph
+            .scene_graph
+            .iter()
+            .filter(|(_, node)| {
+                let raw = UIRawHandler { idx: node.idx };
+                let is_blocked = blocked.as_ref().is_some_and(|b| b.contains(&raw));
+                node.is_enabled && node.inner.input_enabled() && !is_blocked
+            })
+            .map(|(_, node)| {
+                let local = node.inner.input_box().center();
+                let pos = node.root_inverse_matrix.inverse().transform_point2(local);
+                let clip = self.projection.project_point3(vec3(pos.x, pos.y, 1.0));
+                let norm = (vec2(clip.x, clip.y) - vec2(-1.0, 1.0)) / vec2(2.0, -2.0);
+                norm * self.size
+            })
+            .collect()
+    }
+
     fn dispatch_events(&mut self, state: &mut S) {
         // clean removed in last frame
         self.graph.removed.iter().for_each(|raw| {
@@ -227,6 +522,9 @@ impl<S> UIManager<S> {
             return;
         }
 
+        // listeners can change any element
+        self.cache_dirty = true;
+
         // iter through events in reverse order
         self.temp_iter_handlers.clear();
         self.temp_iter_handlers
@@ -264,6 +562,10 @@ impl<S> UIManager<S> {
         std::mem::swap(&mut self.last_frame_down, &mut self.down);
         self.down.clear();
 
+        let blocked = self.blocked_nodes();
+        #[cfg(feature = "gamepad")]
+        let gamepad = self.update_gamepad_cursor();
+
         // reverse the graph
         let mut graph: SmallVec<(&mut UINode<S>, &mut UINode<S>), 120> =
             self.graph.scene_graph.iter_mut().collect();
@@ -276,8 +578,21 @@ impl<S> UIManager<S> {
         let released_btns = mouse_btns_released();
         let scroll = is_mouse_scrolling().then_some(mouse_wheel_delta());
         let moving = is_mouse_moving();
+
+        #[cfg(feature = "gamepad")]
+        let (moving, down_btns, pressed_btns, released_btns) = match gamepad {
+            Some(input) => input.merge(moving, down_btns, pressed_btns, released_btns),
+            None => (moving, down_btns, pressed_btns, released_btns),
+        };
+        if moving || scroll.is_some() || !down_btns.is_empty() || !released_btns.is_empty() {
+            self.cache_dirty = true;
+        }
+
         for (parent, node) in graph {
-            let can_process = node.is_enabled && node.inner.input_enabled();
+            let is_blocked = blocked
+                .as_ref()
+                .is_some_and(|b| b.contains(&UIRawHandler { idx: node.idx }));
+            let can_process = node.is_enabled && node.inner.input_enabled() && !is_blocked;
             if !can_process {
                 continue;
             }
@@ -496,17 +811,20 @@ impl<S> UIManager<S> {
     pub fn update(&mut self, cam: &dyn BaseCam2D, state: &mut S) {
         // TODO: this could be done in the update iteration if we move process inputs to the end of the frame?
         // process visible state
+        let mut dirty = false;
         self.graph
             .scene_graph
             .iter_mut()
-            .for_each(|(parent, child)| process_enable_state(parent, child));
+            .for_each(|(parent, child)| dirty |= process_enable_state(parent, child));
 
         // process inputs and dispatch events
         self.process_inputs(state);
+        self.dismiss_popups();
         self.dispatch_events(state);
 
         // update matrices
         self.set_camera(cam);
+        self.place_popups();
 
         // update root element matrix
         self.graph.root_update_matrix();
@@ -523,10 +841,14 @@ impl<S> UIManager<S> {
                 };
 
                 node.inner.update(state, &mut self.events, metadata);
-                node.alpha = parent.alpha * node.inner.alpha();
+
+                let alpha = parent.alpha * node.inner.alpha();
+                dirty |= alpha != node.alpha;
+                node.alpha = alpha;
 
                 let matrix = parent.matrix * node.inner.transform_mut().updated_mat3();
                 if matrix != node.matrix || !node.initialized_layout {
+                    dirty = true;
                     node.initialized_layout = true;
                     node.matrix = matrix;
                     node.root_inverse_matrix = (self.root_matrix * matrix).inverse();
@@ -537,9 +859,51 @@ impl<S> UIManager<S> {
                     );
                 }
             });
+
+        self.cache_dirty |= dirty;
     }
 
     pub fn render(&mut self, draw: &mut Draw2D, state: &mut S) {
+        self.render_cached(draw, state);
+
+        if self.debug {
+            self.render_debug(draw);
+        }
+    }
+
+    fn render_cached(&mut self, draw: &mut Draw2D, state: &mut S) {
+        if !self.cache_enabled {
+            self.render_elements(draw, state);
+            return;
+        }
+
+        let needs_redraw = self
+            .graph
+            .scene_graph
+            .iter()
+            .any(|(_parent, node)| node.is_enabled && node.inner.needs_redraw());
+
+        let matrix = draw.matrix();
+        let valid_cache = !self.cache_dirty
+            && !needs_redraw
+            && self.cache.as_ref().is_some_and(|(_, m)| *m == matrix);
+
+        if !valid_cache {
+            let mut recording = Draw2D::new_recording(draw.size());
+            recording.set_matrix(matrix);
+            recording.set_depth(draw.depth());
+            recording.set_mask(draw.mask());
+            self.render_elements(&mut recording, state);
+            self.cache = Some((recording, matrix));
+            self.cache_dirty = false;
+        }
+
+        if let Some((recording, _)) = &self.cache {
+            draw.replay(recording);
+        }
+    }
+
+    fn render_elements(&mut self, draw: &mut Draw2D, state: &mut S) {
         self.graph
             .scene_graph
             .iter_mut()
@@ -557,6 +921,49 @@ impl<S> UIManager<S> {
             });
     }
 
+    fn render_debug(&self, draw: &mut Draw2D) {
+        const DEBUG_COLORS: [Color; 4] = [Color::AQUA, Color::ORANGE, Color::MAGENTA, Color::GREEN];
+
+        self.graph
+            .scene_graph
+            .iter()
+            .filter(|(_parent, node)| {
+                let raw = UIRawHandler { idx: node.idx };
+                node.is_enabled && raw != self.content && raw != self.overlay
+            })
+            .enumerate()
+            .for_each(|(i, (_parent, node))| {
+                let raw = UIRawHandler { idx: node.idx };
+                let hovered = self.hover.contains(&raw);
+                let color = if hovered {
+                    Color::YELLOW
+                } else {
+                    DEBUG_COLORS[i % DEBUG_COLORS.len()]
+                };
+
+                draw.push_matrix(node.matrix);
+
+                let size = node.inner.transform().size();
+                draw.rect(Vec2::ZERO, size).stroke_color(color).stroke(1.0);
+
+                if node.inner.input_enabled() {
+                    let input_box = node.inner.input_box();
+                    draw.rect(input_box.origin, input_box.size)
+                        .stroke_color(Color::RED)
+                        .alpha(0.5)
+                        .stroke(1.0);
+                }
+
+                // display only the type's name without the path or generics
+                let name = node.inner.debug_name();
+                let name = name.split('<').next().unwrap_or(name);
+                let name = name.rsplit("::").next().unwrap_or(name);
+                draw.text(name).size(10.0).color(color);
+
+                draw.pop_matrix();
+            });
+    }
+
     pub fn listen<E, F, R>(&mut self, mut cb: F) -> Result<UIListenerId<(), E>, String>
     where
         E: 'static,
@@ -911,8 +1318,42 @@ pub(super) struct NodeIterInfo {
 }
 
 #[inline]
-fn process_enable_state<S: 'static>(parent: &mut UINode<S>, child: &mut UINode<S>) {
-    child.is_enabled = parent.is_enabled && child.inner.enabled();
+fn process_enable_state<S: 'static>(parent: &mut UINode<S>, child: &mut UINode<S>) -> bool {
+    let enabled = parent.is_enabled && child.inner.enabled();
+    let changed = enabled != child.is_enabled;
+    child.is_enabled = enabled;
+    changed
+}
+
+#[cfg(feature = "gamepad")]
+struct GamepadCursorInput {
+    moved: bool,
+    down: bool,
+    pressed: bool,
+    released: bool,
+}
+
+#[cfg(feature = "gamepad")]
+impl GamepadCursorInput {
+    // the gamepad cursor button acts as the left mouse button
+    fn merge(
+        &self,
+        moving: bool,
+        mut down: MouseButtonList,
+        mut pressed: MouseButtonList,
+        mut released: MouseButtonList,
+    ) -> (bool, MouseButtonList, MouseButtonList, MouseButtonList) {
+        if self.down {
+            down.insert(MouseButton::Left);
+        }
+        if self.pressed {
+            pressed.insert(MouseButton::Left);
+        }
+        if self.released {
+            released.insert(MouseButton::Left);
+        }
+        (moving || self.moved, down, pressed, released)
+    }
 }
 
 #[derive(Copy, Clone, Hash, PartialEq, PartialOrd, Ord, Eq, Debug, EnumCount)]
@@ -1,9 +1,17 @@
 mod element;
 mod events;
+#[cfg(feature = "gamepad")]
+mod gamepad_cursor;
 mod graph;
 mod manager;
+mod popup;
+mod virtual_list;
 
 pub use element::*;
 pub use events::*;
+#[cfg(feature = "gamepad")]
+pub use gamepad_cursor::UIGamepadCursor;
 pub use graph::*;
 pub use manager::*;
+pub use popup::{UIPopup, UIPopupDismissed, UIPopupPlacement};
+pub use virtual_list::UIVirtualList;
@@ -0,0 +1,176 @@
+use crate::ui::UIRawHandler;
+use corelib::math::{vec2, Rect, Vec2};
+
+/// Side of the anchor where the popup is placed
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum UIPopupPlacement {
+    Above,
+    #[default]
+    Below,
+    Left,
+    Right,
+    /// Centered on the screen, ignores the anchor
+    Center,
+}
+
+/// Options used by `UIManager::open_popup` to place and close a popup
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct UIPopup {
+    pub(super) anchor: Rect,
+    pub(super) placement: UIPopupPlacement,
+    pub(super) margin: f32,
+    pub(super) dismiss_on_click_away: bool,
+    pub(super) modal: bool,
+}
+
+impl UIPopup {
+    /// Popup placed next to `anchor` (in UI coordinates), below it by default
+    pub fn new(anchor: Rect) -> Self {
+        Self {
+            anchor,
+            placement: UIPopupPlacement::Below,
+            margin: 0.0,
+            dismiss_on_click_away: false,
+            modal: false,
+        }
+    }
+
+    /// Popup centered on the screen that blocks the input for the rest of the UI
+    pub fn modal() -> Self {
+        Self {
+            anchor: Rect::default(),
+            placement: UIPopupPlacement::Center,
+            margin: 0.0,
+            dismiss_on_click_away: false,
+            modal: true,
+        }
+    }
+
+    /// Side of the anchor to use, it flips to the opposite side if there is no room
+    pub fn with_placement(mut self, placement: UIPopupPlacement) -> Self {
+        self.placement = placement;
+        self
+    }
+
+    /// Space between the anchor and the popup
+    pub fn with_margin(mut self, margin: f32) -> Self {
+        self.margin = margin;
+        self
+    }
+
+    /// Closes the popup when a mouse button is pressed outside of it
+    pub fn dismiss_on_click_away(mut self) -> Self {
+        self.dismiss_on_click_away = true;
+        self
+    }
+
+    pub fn is_modal(&self) -> bool {
+        self.modal
+    }
+}
+
+/// Global event sent when a popup is closed by clicking outside of it
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct UIPopupDismissed {
+    pub popup: UIRawHandler,
+}
+
+/// Returns the top-left position of a popup of `size` next to `anchor`,
+/// flipping the side if it doesn't fit and keeping it inside of `bounds`
+pub(super) fn place_popup(popup: &UIPopup, size: Vec2, bounds: Rect) -> Vec2 {
+    let UIPopup { anchor, margin, .. } = *popup;
+
+    let above = anchor.min().y - margin - size.y;
+    let below = anchor.max().y + margin;
+    let left = anchor.min().x - margin - size.x;
+    let right = anchor.max().x + margin;
+
+    let fits_above = above >= bounds.min().y;
+    let fits_below = below + size.y <= bounds.max().y;
+    let fits_left = left >= bounds.min().x;
+    let fits_right = right + size.x <= bounds.max().x;
+
+    let pos = match popup.placement {
+        UIPopupPlacement::Above => {
+            let y = if !fits_above && fits_below {
+                below
+            } else {
+                above
+            };
+            vec2(anchor.min().x, y)
+        }
+        UIPopupPlacement::Below => {
+            let y = if !fits_below && fits_above {
+                above
+            } else {
+                below
+            };
+            vec2(anchor.min().x, y)
+        }
+        UIPopupPlacement::Left => {
+            let x = if !fits_left && fits_right {
+                right
+            } else {
+                left
+            };
+            vec2(x, anchor.min().y)
+        }
+        UIPopupPlacement::Right => {
+            let x = if !fits_right && fits_left {
+                left
+            } else {
+                right
+            };
+            vec2(x, anchor.min().y)
+        }
+        UIPopupPlacement::Center => bounds.center() - size * 0.5,
+    };
+
+    // keep it on screen, if the popup is bigger than the bounds show the top-left side
+    let max = (bounds.max() - size).max(bounds.min());
+    pos.clamp(bounds.min(), max)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bounds() -> Rect {
+        Rect::new(Vec2::ZERO, vec2(800.0, 600.0))
+    }
+
+    #[test]
+    fn test_place_below() {
+        let popup = UIPopup::new(Rect::new(vec2(100.0, 100.0), vec2(50.0, 20.0))).with_margin(2.0);
+        let pos = place_popup(&popup, vec2(100.0, 100.0), bounds());
+        assert_eq!(pos, vec2(100.0, 122.0));
+    }
+
+    #[test]
+    fn test_flip_above() {
+        let popup = UIPopup::new(Rect::new(vec2(100.0, 550.0), vec2(50.0, 20.0)));
+        let pos = place_popup(&popup, vec2(100.0, 100.0), bounds());
+        assert_eq!(pos, vec2(100.0, 450.0));
+    }
+
+    #[test]
+    fn test_flip_left() {
+        let popup = UIPopup::new(Rect::new(vec2(750.0, 100.0), vec2(20.0, 20.0)))
+            .with_placement(UIPopupPlacement::Right);
+        let pos = place_popup(&popup, vec2(100.0, 50.0), bounds());
+        assert_eq!(pos, vec2(650.0, 100.0));
+    }
+
+    #[test]
+    fn test_clamp_to_bounds() {
+        let popup = UIPopup::new(Rect::new(vec2(750.0, 100.0), vec2(20.0, 20.0)));
+        let pos = place_popup(&popup, vec2(100.0, 50.0), bounds());
+        assert_eq!(pos, vec2(700.0, 120.0));
+    }
+
+    #[test]
+    fn test_modal_centered() {
+        let pos = place_popup(&UIPopup::modal(), vec2(200.0, 100.0), bounds());
+        assert_eq!(pos, vec2(300.0, 250.0));
+    }
+}
@@ -0,0 +1,266 @@
+use crate::ui::{UIControl, UIElement, UIEvents, UIInput, UINodeMetadata};
+use corelib::math::{vec2, Rect, Vec2};
+use draw::{Draw2D, Transform2D};
+use std::ops::Range;
+
+type RenderItemFn<S> = dyn Fn(&mut Draw2D, &mut S, usize, Rect) + Send + Sync;
+
+/// Scrollable list or grid that only renders the visible items.
+/// The items are not stored, `render_item` is called with the index and
+/// the local bounds of each visible item, so it can read them from the state.
+pub struct UIVirtualList<S> {
+    pub transform: Transform2D,
+    item_size: Vec2,
+    spacing: Vec2,
+    columns: usize,
+    len: usize,
+    scroll: f32,
+    scroll_speed: f32,
+    clip: bool,
+    render_item: Box<RenderItemFn<S>>,
+}
+
+impl<S> UIVirtualList<S> {
+    /// Creates a list with a viewport of `size` and items of `item_size`
+    pub fn new<F>(size: Vec2, item_size: Vec2, render_item: F) -> Self
+    where
+        F: Fn(&mut Draw2D, &mut S, usize, Rect) + Send + Sync + 'static,
+    {
+        let mut transform = Transform2D::default();
+        transform.set_size(size);
+
+        Self {
+            transform,
+            item_size,
+            spacing: Vec2::ZERO,
+            columns: 1,
+            len: 0,
+            scroll: 0.0,
+            scroll_speed: 1.0,
+            clip: false,
+            render_item: Box::new(render_item),
+        }
+    }
+
+    /// Number of items per row, more than 1 makes it a grid
+    pub fn with_columns(mut self, columns: usize) -> Self {
+        self.columns = columns.max(1);
+        self
+    }
+
+    /// Space between items
+    pub fn with_spacing(mut self, spacing: Vec2) -> Self {
+        self.spacing = spacing;
+        self
+    }
+
+    pub fn with_len(mut self, len: usize) -> Self {
+        self.set_len(len);
+        self
+    }
+
+    /// Multiplier applied to the mouse wheel delta
+    pub fn with_scroll_speed(mut self, speed: f32) -> Self {
+        self.scroll_speed = speed;
+        self
+    }
+
+    /// Hides the parts of the items outside of the viewport using a stencil mask.
+    /// Render textures used as target must be created with depth.
+    pub fn with_clip(mut self) -> Self {
+        self.clip = true;
+        self
+    }
+
+    /// Sets the number of items, the scroll is kept inside of the new bounds
+    pub fn set_len(&mut self, len: usize) {
+        self.len = len;
+        self.set_scroll(self.scroll);
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn scroll(&self) -> f32 {
+        self.scroll
+    }
+
+    pub fn set_scroll(&mut self, scroll: f32) {
+        self.scroll = scroll.clamp(0.0, self.max_scroll());
+    }
+
+    /// Scrolls the minimum needed to make the item visible
+    pub fn scroll_to(&mut self, index: usize) {
+        let rect = self.item_rect(index);
+        let top = rect.min().y + self.scroll;
+        let bottom = rect.max().y + self.scroll;
+        let height = self.transform.size().y;
+        if top < self.scroll {
+            self.set_scroll(top);
+        } else if bottom > self.scroll + height {
+            self.set_scroll(bottom - height);
+        }
+    }
+
+    /// Height of all the rows
+    pub fn content_height(&self) -> f32 {
+        let rows = self.len.div_ceil(self.columns);
+        if rows == 0 {
+            return 0.0;
+        }
+
+        rows as f32 * self.row_height() - self.spacing.y
+    }
+
+    pub fn max_scroll(&self) -> f32 {
+        (self.content_height() - self.transform.size().y).max(0.0)
+    }
+
+    /// Range of the indices of the items inside of the viewport
+    pub fn visible_range(&self) -> Range<usize> {
+        let row_height = self.row_height();
+        if self.len == 0 || row_height <= 0.0 {
+            return 0..0;
+        }
+
+        let first_row = (self.scroll / row_height).floor() as usize;
+        let last_row = ((self.scroll + self.transform.size().y) / row_height).ceil() as usize;
+        let start = (first_row * self.columns).min(self.len);
+        let end = (last_row * self.columns).min(self.len);
+        start..end
+    }
+
+    /// Bounds of the item relative to the viewport
+    pub fn item_rect(&self, index: usize) -> Rect {
+        let row = index / self.columns;
+        let col = index % self.columns;
+        let pos = vec2(
+            col as f32 * (self.item_size.x + self.spacing.x),
+            row as f32 * self.row_height() - self.scroll,
+        );
+        Rect::new(pos, self.item_size)
+    }
+
+    /// Index of the item at the local point, if any
+    pub fn index_at(&self, point: Vec2) -> Option<usize> {
+        self.visible_range()
+            .find(|idx| self.item_rect(*idx).contains(point))
+    }
+
+    fn row_height(&self) -> f32 {
+        self.item_size.y + self.spacing.y
+    }
+}
+
+impl<S: 'static> UIElement<S> for UIVirtualList<S> {
+    fn transform(&self) -> &Transform2D {
+        &self.transform
+    }
+
+    fn transform_mut(&mut self) -> &mut Transform2D {
+        &mut self.transform
+    }
+
+    fn input_enabled(&self) -> bool {
+        true
+    }
+
+    fn input(
+        &mut self,
+        input: UIInput,
+        _state: &mut S,
+        _events: &mut UIEvents<S>,
+        _metadata: UINodeMetadata,
+    ) -> UIControl {
+        match input {
+            UIInput::Scroll { delta } => {
+                self.set_scroll(self.scroll - delta.y * self.scroll_speed);
+                UIControl::Consume
+            }
+            UIInput::ButtonReleasedAnywhere(_) => UIControl::Continue,
+            _ => UIControl::Consume,
+        }
+    }
+
+    fn relayout(&mut self, _state: &mut S, _events: &mut UIEvents<S>, _parent_bounds: Rect) {
+        // the viewport could be resized
+        self.set_scroll(self.scroll);
+    }
+
+    fn render(&mut self, draw: &mut Draw2D, state: &mut S, _metadata: UINodeMetadata) {
+        let range = self.visible_range();
+        let render_items = |draw: &mut Draw2D| {
+            range.for_each(|idx| (self.render_item)(draw, state, idx, self.item_rect(idx)));
+        };
+
+        if self.clip {
+            let size = self.transform.size();
+            draw.masked(
+                |d| {
+                    d.rect(Vec2::ZERO, size);
+                },
+                render_items,
+            );
+        } else {
+            render_items(draw);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn list(len: usize) -> UIVirtualList<()> {
+        UIVirtualList::new(vec2(100.0, 100.0), vec2(100.0, 20.0), |_, _, _, _| {}).with_len(len)
+    }
+
+    #[test]
+    fn test_visible_range() {
+        let mut list = list(1000);
+        assert_eq!(list.visible_range(), 0..5);
+
+        list.set_scroll(30.0);
+        assert_eq!(list.visible_range(), 1..7);
+    }
+
+    #[test]
+    fn test_scroll_is_clamped() {
+        let mut list = list(10);
+        list.set_scroll(1000.0);
+        assert_eq!(list.scroll(), 100.0);
+        assert_eq!(list.visible_range(), 5..10);
+
+        list.set_len(2);
+        assert_eq!(list.scroll(), 0.0);
+    }
+
+    #[test]
+    fn test_grid() {
+        let list = UIVirtualList::<()>::new(vec2(100.0, 50.0), vec2(20.0, 20.0), |_, _, _, _| {})
+            .with_columns(3)
+            .with_spacing(vec2(5.0, 5.0))
+            .with_len(10);
+
+        assert_eq!(list.content_height(), 95.0);
+        assert_eq!(list.visible_range(), 0..6);
+        assert_eq!(list.item_rect(4).origin, vec2(25.0, 25.0));
+        assert_eq!(list.index_at(vec2(30.0, 30.0)), Some(4));
+        assert_eq!(list.index_at(vec2(22.0, 30.0)), None);
+    }
+
+    #[test]
+    fn test_scroll_to() {
+        let mut list = list(100);
+        list.scroll_to(10);
+        assert_eq!(list.scroll(), 120.0);
+
+        list.scroll_to(2);
+        assert_eq!(list.scroll(), 40.0);
+    }
+}
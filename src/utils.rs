@@ -7,9 +7,22 @@ pub use ::utils::helpers::*;
 #[doc(inline)]
 pub use ::utils::ring_buffer::*;
 
+mod quadtree;
+pub use quadtree::*;
+
 pub mod local_pool {
     #[doc(inline)]
     pub use ::macros::init_local_pool;
     #[doc(inline)]
     pub use ::utils::local_pool::*;
 }
+
+pub mod rect_pack {
+    #[doc(inline)]
+    pub use ::utils::rect_pack::*;
+}
+
+pub mod pool {
+    #[doc(inline)]
+    pub use ::utils::pool::*;
+}
@@ -0,0 +1,437 @@
+use crate::math::{vec2, Mat3, Rect, Vec2};
+
+/// Handle of an item stored in a `QuadTree`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct QuadTreeId {
+    idx: usize,
+    generation: u32,
+}
+
+struct Entry<T> {
+    rect: Rect,
+    node: usize,
+    data: T,
+}
+
+struct Slot<T> {
+    generation: u32,
+    entry: Option<Entry<T>>,
+}
+
+struct Node {
+    rect: Rect,
+    depth: u32,
+    children: Option<[usize; 4]>,
+    items: Vec<usize>,
+}
+
+/// Quadtree to speed up region, point and ray queries over rects (culling, picking,
+/// collision broad phase...). Items that do not fit in the bounds are kept in the root
+pub struct QuadTree<T> {
+    nodes: Vec<Node>,
+    slots: Vec<Slot<T>>,
+    free: Vec<usize>,
+    len: usize,
+    max_depth: u32,
+    max_items: usize,
+}
+
+impl<T> QuadTree<T> {
+    pub fn new(bounds: Rect) -> Self {
+        Self {
+            nodes: vec![Node {
+                rect: bounds,
+                depth: 0,
+                children: None,
+                items: vec![],
+            }],
+            slots: vec![],
+            free: vec![],
+            len: 0,
+            max_depth: 8,
+            max_items: 8,
+        }
+    }
+
+    /// Max levels of subdivision
+    pub fn with_max_depth(mut self, depth: u32) -> Self {
+        self.max_depth = depth;
+        self
+    }
+
+    /// Items stored in a node before it is subdivided
+    pub fn with_max_items(mut self, items: usize) -> Self {
+        self.max_items = items.max(1);
+        self
+    }
+
+    pub fn bounds(&self) -> Rect {
+        self.nodes[0].rect
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn insert(&mut self, rect: Rect, data: T) -> QuadTreeId {
+        let idx = match self.free.pop() {
+            Some(idx) => idx,
+            None => {
+                self.slots.push(Slot {
+                    generation: 0,
+                    entry: None,
+                });
+                self.slots.len() - 1
+            }
+        };
+
+        let node = self.place(idx, rect);
+        let slot = &mut self.slots[idx];
+        slot.entry = Some(Entry { rect, node, data });
+        self.len += 1;
+
+        QuadTreeId {
+            idx,
+            generation: slot.generation,
+        }
+    }
+
+    /// Inserts the bounding box of the rect after applying the transform
+    pub fn insert_transformed(&mut self, rect: Rect, transform: Mat3, data: T) -> QuadTreeId {
+        self.insert(transformed_bounds(rect, transform), data)
+    }
+
+    pub fn remove(&mut self, id: QuadTreeId) -> Option<T> {
+        self.get(id)?;
+        let slot = &mut self.slots[id.idx];
+        let entry = slot.entry.take()?;
+        slot.generation = slot.generation.wrapping_add(1);
+        self.free.push(id.idx);
+        self.len -= 1;
+
+        self.unlink(id.idx, entry.node);
+        Some(entry.data)
+    }
+
+    /// Moves the item to a new rect, returns false if the item does not exists
+    pub fn update(&mut self, id: QuadTreeId, rect: Rect) -> bool {
+        let Some(node) = self.entry(id).map(|entry| entry.node) else {
+            return false;
+        };
+
+        self.unlink(id.idx, node);
+        let node = self.place(id.idx, rect);
+        if let Some(entry) = self.slots[id.idx].entry.as_mut() {
+            entry.rect = rect;
+            entry.node = node;
+        }
+        true
+    }
+
+    pub fn update_transformed(&mut self, id: QuadTreeId, rect: Rect, transform: Mat3) -> bool {
+        self.update(id, transformed_bounds(rect, transform))
+    }
+
+    pub fn get(&self, id: QuadTreeId) -> Option<&T> {
+        self.entry(id).map(|entry| &entry.data)
+    }
+
+    pub fn get_mut(&mut self, id: QuadTreeId) -> Option<&mut T> {
+        let slot = self.slots.get_mut(id.idx)?;
+        if slot.generation != id.generation {
+            return None;
+        }
+        slot.entry.as_mut().map(|entry| &mut entry.data)
+    }
+
+    pub fn rect(&self, id: QuadTreeId) -> Option<Rect> {
+        self.entry(id).map(|entry| entry.rect)
+    }
+
+    /// Items intersecting the rect
+    pub fn query_rect(&self, rect: Rect) -> Vec<QuadTreeId> {
+        let mut res = vec![];
+        self.visit(
+            |node| node.intersects(&rect),
+            |item| item.intersects(&rect),
+            |id, _| res.push(id),
+        );
+        res
+    }
+
+    /// Items containing the point
+    pub fn query_point(&self, point: Vec2) -> Vec<QuadTreeId> {
+        let mut res = vec![];
+        self.visit(
+            |node| node.contains(point),
+            |item| item.contains(point),
+            |id, _| res.push(id),
+        );
+        res
+    }
+
+    /// Items hit by the ray sorted by distance, `dir` doesn't need to be normalized
+    pub fn raycast(&self, origin: Vec2, dir: Vec2, max_distance: f32) -> Vec<(QuadTreeId, f32)> {
+        let dir = dir.normalize_or_zero();
+        if dir == Vec2::ZERO {
+            return vec![];
+        }
+
+        let mut res = vec![];
+        self.visit(
+            |node| ray_rect(origin, dir, node).is_some_and(|d| d <= max_distance),
+            |_| true,
+            |id, rect| {
+                if let Some(d) = ray_rect(origin, dir, rect).filter(|d| *d <= max_distance) {
+                    res.push((id, d));
+                }
+            },
+        );
+        res.sort_by(|a, b| a.1.total_cmp(&b.1));
+        res
+    }
+
+    /// Iterates all the items
+    pub fn iter(&self) -> impl Iterator<Item = (QuadTreeId, &T)> + '_ {
+        self.slots.iter().enumerate().filter_map(|(idx, slot)| {
+            slot.entry.as_ref().map(|entry| {
+                (
+                    QuadTreeId {
+                        idx,
+                        generation: slot.generation,
+                    },
+                    &entry.data,
+                )
+            })
+        })
+    }
+
+    pub fn clear(&mut self) {
+        let bounds = self.bounds();
+        *self = Self::new(bounds)
+            .with_max_depth(self.max_depth)
+            .with_max_items(self.max_items);
+    }
+
+    fn entry(&self, id: QuadTreeId) -> Option<&Entry<T>> {
+        let slot = self.slots.get(id.idx)?;
+        if slot.generation != id.generation {
+            return None;
+        }
+        slot.entry.as_ref()
+    }
+
+    fn visit<N, I, F>(&self, node_check: N, item_check: I, mut cb: F)
+    where
+        N: Fn(&Rect) -> bool,
+        I: Fn(&Rect) -> bool,
+        F: FnMut(QuadTreeId, &Rect),
+    {
+        // the root is always checked because it keeps the items out of bounds
+        let mut stack = vec![0];
+        while let Some(idx) = stack.pop() {
+            let node = &self.nodes[idx];
+            if idx != 0 && !node_check(&node.rect) {
+                continue;
+            }
+
+            for &item in &node.items {
+                let slot = &self.slots[item];
+                if let Some(entry) = &slot.entry {
+                    if item_check(&entry.rect) {
+                        let id = QuadTreeId {
+                            idx: item,
+                            generation: slot.generation,
+                        };
+                        cb(id, &entry.rect);
+                    }
+                }
+            }
+
+            if let Some(children) = node.children {
+                stack.extend(children);
+            }
+        }
+    }
+
+    // stores the item in the deepest node that contains it, returns the node
+    fn place(&mut self, item: usize, rect: Rect) -> usize {
+        let mut idx = 0;
+        loop {
+            let node = &self.nodes[idx];
+            let can_split = node.depth < self.max_depth;
+            if node.children.is_none() && can_split && node.items.len() >= self.max_items {
+                self.split(idx);
+            }
+
+            let child = self.nodes[idx].children.and_then(|children| {
+                children
+                    .into_iter()
+                    .find(|&child| contains_rect(&self.nodes[child].rect, &rect))
+            });
+
+            match child {
+                Some(child) => idx = child,
+                None => {
+                    self.nodes[idx].items.push(item);
+                    return idx;
+                }
+            }
+        }
+    }
+
+    fn split(&mut self, idx: usize) {
+        let Node { rect, depth, .. } = self.nodes[idx];
+        let half = rect.size * 0.5;
+        let first = self.nodes.len();
+        let origins = [
+            rect.origin,
+            rect.origin + vec2(half.x, 0.0),
+            rect.origin + vec2(0.0, half.y),
+            rect.origin + half,
+        ];
+        self.nodes.extend(origins.map(|origin| Node {
+            rect: Rect::new(origin, half),
+            depth: depth + 1,
+            children: None,
+            items: vec![],
+        }));
+
+        let children = [first, first + 1, first + 2, first + 3];
+        self.nodes[idx].children = Some(children);
+
+        // move down the items that fit in a child
+        let items = std::mem::take(&mut self.nodes[idx].items);
+        for item in items {
+            let Some(entry) = self.slots[item].entry.as_mut() else {
+                continue;
+            };
+
+            let child = children
+                .into_iter()
+                .find(|&child| contains_rect(&self.nodes[child].rect, &entry.rect))
+                .unwrap_or(idx);
+            entry.node = child;
+            self.nodes[child].items.push(item);
+        }
+    }
+
+    fn unlink(&mut self, item: usize, node: usize) {
+        let items = &mut self.nodes[node].items;
+        if let Some(pos) = items.iter().position(|&i| i == item) {
+            items.swap_remove(pos);
+        }
+    }
+}
+
+fn contains_rect(outer: &Rect, inner: &Rect) -> bool {
+    let (omin, omax) = (outer.min(), outer.max());
+    let (imin, imax) = (inner.min(), inner.max());
+    imin.x >= omin.x && imin.y >= omin.y && imax.x <= omax.x && imax.y <= omax.y
+}
+
+/// Axis aligned bounds of the rect after applying the transform
+pub fn transformed_bounds(rect: Rect, transform: Mat3) -> Rect {
+    let (min, max) = (rect.min(), rect.max());
+    let corners = [min, vec2(max.x, min.y), max, vec2(min.x, max.y)]
+        .map(|point| transform.transform_point2(point));
+    let (min, max) = corners.iter().fold(
+        (Vec2::splat(f32::MAX), Vec2::splat(f32::MIN)),
+        |(min, max), p| (min.min(*p), max.max(*p)),
+    );
+    Rect::new(min, max - min)
+}
+
+// distance to the rect using the slab method, zero if the origin is inside
+fn ray_rect(origin: Vec2, dir: Vec2, rect: &Rect) -> Option<f32> {
+    let inv = dir.recip();
+    let t1 = (rect.min() - origin) * inv;
+    let t2 = (rect.max() - origin) * inv;
+    let near = t1.min(t2);
+    let far = t1.max(t2);
+
+    // nan happens when the ray is parallel and starts on the edge
+    let near = near.x.max(near.y);
+    let far = far.x.min(far.y);
+    (!near.is_nan() && !far.is_nan() && far >= near.max(0.0)).then_some(near.max(0.0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tree() -> QuadTree<u32> {
+        QuadTree::new(Rect::new(Vec2::ZERO, vec2(100.0, 100.0))).with_max_items(2)
+    }
+
+    #[test]
+    fn test_insert_query() {
+        let mut tree = tree();
+        let ids = (0..10)
+            .map(|i| {
+                let pos = vec2(i as f32 * 10.0, i as f32 * 10.0);
+                tree.insert(Rect::new(pos, vec2(5.0, 5.0)), i)
+            })
+            .collect::<Vec<_>>();
+        let out = tree.insert(Rect::new(vec2(-50.0, -50.0), vec2(10.0, 10.0)), 99);
+
+        assert_eq!(tree.len(), 11);
+        assert_eq!(tree.query_point(vec2(22.0, 22.0)), vec![ids[2]]);
+        assert_eq!(tree.query_point(vec2(-45.0, -45.0)), vec![out]);
+
+        let mut found = tree.query_rect(Rect::new(vec2(0.0, 0.0), vec2(32.0, 32.0)));
+        found.sort_by_key(|id| *tree.get(*id).unwrap());
+        assert_eq!(found, ids[..4].to_vec());
+    }
+
+    #[test]
+    fn test_remove_update() {
+        let mut tree = tree();
+        let a = tree.insert(Rect::new(vec2(10.0, 10.0), vec2(5.0, 5.0)), 1);
+        let b = tree.insert(Rect::new(vec2(80.0, 80.0), vec2(5.0, 5.0)), 2);
+
+        assert!(tree.update(a, Rect::new(vec2(60.0, 10.0), vec2(5.0, 5.0))));
+        assert!(tree.query_point(vec2(12.0, 12.0)).is_empty());
+        assert_eq!(tree.query_point(vec2(62.0, 12.0)), vec![a]);
+
+        assert_eq!(tree.remove(b), Some(2));
+        assert_eq!(tree.remove(b), None);
+        assert!(tree.query_point(vec2(82.0, 82.0)).is_empty());
+
+        // reused slots must not be reachable with old ids
+        let c = tree.insert(Rect::new(vec2(0.0, 0.0), vec2(1.0, 1.0)), 3);
+        assert_ne!(b, c);
+        assert_eq!(tree.get(b), None);
+        assert_eq!(tree.len(), 2);
+    }
+
+    #[test]
+    fn test_raycast() {
+        let mut tree = tree();
+        let near = tree.insert(Rect::new(vec2(20.0, 0.0), vec2(10.0, 10.0)), 1);
+        let far = tree.insert(Rect::new(vec2(60.0, 0.0), vec2(10.0, 10.0)), 2);
+        tree.insert(Rect::new(vec2(20.0, 50.0), vec2(10.0, 10.0)), 3);
+
+        let hits = tree.raycast(vec2(0.0, 5.0), vec2(1.0, 0.0), 1000.0);
+        assert_eq!(hits, vec![(near, 20.0), (far, 60.0)]);
+        assert_eq!(
+            tree.raycast(vec2(0.0, 5.0), Vec2::X, 30.0),
+            vec![(near, 20.0)]
+        );
+    }
+
+    #[test]
+    fn test_transformed() {
+        let rect = Rect::new(vec2(-5.0, -5.0), vec2(10.0, 10.0));
+        let transform = Mat3::from_translation(vec2(50.0, 50.0))
+            * Mat3::from_angle(std::f32::consts::FRAC_PI_4);
+        let bounds = transformed_bounds(rect, transform);
+        let half = 50.0f32.sqrt();
+        assert!((bounds.min() - vec2(50.0 - half, 50.0 - half)).length() < 0.001);
+        assert!((bounds.width() - half * 2.0).abs() < 0.001);
+    }
+}